@@ -8,6 +8,7 @@
 //! instead of IndexedDB (which is used in the web version).
 
 use super::types::{CacheEntry, CacheStats};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tauri::Manager;
 
@@ -47,6 +48,19 @@ fn get_cache_file_path(cache_dir: &PathBuf, cache_key: &str) -> Result<PathBuf,
     Ok(cache_dir.join(format!("{}.bin", cache_key)))
 }
 
+/// Compute the canonical cache key for file content and processing options
+///
+/// Same derivation as the server and WASM bindings, so the key can also be
+/// used to look up server-side results.
+#[tauri::command]
+pub fn compute_cache_key(data: Vec<u8>, options: HashMap<String, String>) -> String {
+    let pairs: Vec<(&str, &str)> = options
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    ifc_lite_core::cache_key(&data, &pairs)
+}
+
 /// Get cached geometry by key
 #[tauri::command]
 pub async fn get_cached(
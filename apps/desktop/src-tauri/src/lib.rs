@@ -22,6 +22,7 @@ pub fn run() {
             commands::ifc::parse_ifc_buffer,
             commands::ifc::get_geometry,
             commands::ifc::get_geometry_streaming,
+            commands::cache::compute_cache_key,
             commands::cache::get_cached,
             commands::cache::set_cached,
             commands::cache::clear_cache,
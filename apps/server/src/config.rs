@@ -3,6 +3,14 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 //! Server configuration loaded from environment variables.
+//!
+//! [`Config`] is read once at startup. The subset of settings that can be
+//! changed while the server is running lives in [`Tunables`], which handlers
+//! snapshot at the start of every request via [`SharedTunables`].
+
+use ifc_lite_processing::{OpeningFilterMode, TessellationQuality};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
 
 /// Server configuration.
 #[derive(Debug, Clone)]
@@ -25,8 +33,17 @@ pub struct Config {
     pub batch_size: usize,
     /// Maximum cache age in days.
     pub cache_max_age_days: u64,
+    /// Maximum total size of cached entries in MB.
+    pub cache_max_size_mb: u64,
     /// Allowed CORS origins (comma-separated, or "*" for all in development).
     pub cors_origins: Vec<String>,
+    /// Bearer token required by the admin API. Admin endpoints are disabled when unset.
+    pub admin_token: Option<String>,
+    /// Hosts that validation webhooks may be delivered to (comma-separated).
+    /// Webhook delivery is disabled when empty.
+    pub webhook_allowed_hosts: Vec<String>,
+    /// Validation jobs allowed to run at once; further requests are rejected.
+    pub max_validation_jobs: usize,
 }
 
 impl Config {
@@ -79,6 +96,10 @@ impl Config {
                 .unwrap_or_else(|_| "7".into())
                 .parse()
                 .unwrap_or(7),
+            cache_max_size_mb: std::env::var("CACHE_MAX_SIZE_MB")
+                .unwrap_or_else(|_| "10240".into())
+                .parse()
+                .unwrap_or(10240),
             cors_origins: std::env::var("CORS_ORIGINS")
                 .unwrap_or_else(|_| {
                     // Default: allow common development origins
@@ -88,6 +109,20 @@ impl Config {
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
                 .collect(),
+            admin_token: std::env::var("ADMIN_TOKEN")
+                .ok()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty()),
+            webhook_allowed_hosts: std::env::var("WEBHOOK_ALLOWED_HOSTS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_ascii_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            max_validation_jobs: std::env::var("MAX_VALIDATION_JOBS")
+                .unwrap_or_else(|_| "4".into())
+                .parse()
+                .unwrap_or(4),
         }
     }
 }
@@ -97,3 +132,240 @@ impl Default for Config {
         Self::from_env()
     }
 }
+
+/// Settings that can be changed at runtime through the admin API.
+///
+/// Every request reads a snapshot when it starts, so an update only affects
+/// requests that arrive afterwards; in-flight parses keep their settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tunables {
+    /// Initial batch size for fast first frame in streaming endpoints.
+    pub initial_batch_size: usize,
+    /// Maximum batch size for throughput in streaming endpoints.
+    pub max_batch_size: usize,
+    /// Maximum accepted file size in MB.
+    ///
+    /// Can be lowered at runtime but never raised above the startup
+    /// `MAX_FILE_SIZE_MB`, which also sizes the request body limit.
+    pub max_file_size_mb: usize,
+    /// Opening filter applied when a request does not specify `opening_filter`.
+    pub default_opening_filter: OpeningFilterMode,
+    /// Curve and surface tessellation quality for geometry processing.
+    pub tessellation: TessellationQuality,
+    /// Maximum total size of the disk cache in MB; the oldest entries are
+    /// evicted when a write exceeds it.
+    pub cache_max_size_mb: u64,
+}
+
+impl Tunables {
+    /// Derive the initial tunables from the startup configuration.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            initial_batch_size: config.initial_batch_size,
+            max_batch_size: config.max_batch_size,
+            max_file_size_mb: config.max_file_size_mb,
+            default_opening_filter: OpeningFilterMode::Default,
+            tessellation: TessellationQuality::Default,
+            cache_max_size_mb: config.cache_max_size_mb,
+        }
+    }
+
+    /// Maximum accepted file size in bytes.
+    pub fn max_file_size_bytes(&self) -> usize {
+        self.max_file_size_mb * 1024 * 1024
+    }
+
+    /// Maximum disk cache size in bytes.
+    pub fn cache_max_size_bytes(&self) -> u64 {
+        self.cache_max_size_mb.saturating_mul(1024 * 1024)
+    }
+
+    /// Return a copy with `update` applied, or a description of the first invalid field.
+    ///
+    /// `body_limit_mb` is the startup body limit that `max_file_size_mb` may not exceed.
+    pub fn with_update(
+        &self,
+        update: &TunablesUpdate,
+        body_limit_mb: usize,
+    ) -> Result<Self, String> {
+        let mut next = self.clone();
+        if let Some(v) = update.initial_batch_size {
+            next.initial_batch_size = v;
+        }
+        if let Some(v) = update.max_batch_size {
+            next.max_batch_size = v;
+        }
+        if let Some(v) = update.max_file_size_mb {
+            next.max_file_size_mb = v;
+        }
+        if let Some(v) = update.default_opening_filter {
+            next.default_opening_filter = v;
+        }
+        if let Some(v) = update.tessellation {
+            next.tessellation = v;
+        }
+        if let Some(v) = update.cache_max_size_mb {
+            next.cache_max_size_mb = v;
+        }
+
+        if next.initial_batch_size == 0 {
+            return Err("initial_batch_size must be greater than 0".into());
+        }
+        if next.max_batch_size < next.initial_batch_size {
+            return Err(format!(
+                "max_batch_size ({}) must be >= initial_batch_size ({})",
+                next.max_batch_size, next.initial_batch_size
+            ));
+        }
+        if next.max_file_size_mb == 0 || next.max_file_size_mb > body_limit_mb {
+            return Err(format!(
+                "max_file_size_mb must be between 1 and the startup limit of {} MB",
+                body_limit_mb
+            ));
+        }
+        if next.cache_max_size_mb == 0 {
+            return Err("cache_max_size_mb must be greater than 0".into());
+        }
+
+        Ok(next)
+    }
+}
+
+/// Partial update for [`Tunables`]; omitted fields keep their current value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TunablesUpdate {
+    pub initial_batch_size: Option<usize>,
+    pub max_batch_size: Option<usize>,
+    pub max_file_size_mb: Option<usize>,
+    pub default_opening_filter: Option<OpeningFilterMode>,
+    pub tessellation: Option<TessellationQuality>,
+    pub cache_max_size_mb: Option<u64>,
+}
+
+/// Thread-safe handle to the live [`Tunables`].
+#[derive(Debug, Clone)]
+pub struct SharedTunables(Arc<RwLock<Tunables>>);
+
+impl SharedTunables {
+    /// Wrap the initial tunables.
+    pub fn new(tunables: Tunables) -> Self {
+        Self(Arc::new(RwLock::new(tunables)))
+    }
+
+    /// Copy of the current tunables (taken once per request).
+    pub fn snapshot(&self) -> Tunables {
+        match self.0.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Derive new tunables from the current ones and store them, all under
+    /// the write lock so concurrent updates can't overwrite each other.
+    /// Returns the previous and new tunables; nothing changes if `f` fails.
+    pub fn update<E>(
+        &self,
+        f: impl FnOnce(&Tunables) -> Result<Tunables, E>,
+    ) -> Result<(Tunables, Tunables), E> {
+        let mut guard = match self.0.write() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let next = f(&guard)?;
+        let previous = std::mem::replace(&mut *guard, next.clone());
+        Ok((previous, next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Tunables {
+        Tunables {
+            initial_batch_size: 100,
+            max_batch_size: 1000,
+            max_file_size_mb: 500,
+            default_opening_filter: OpeningFilterMode::Default,
+            tessellation: TessellationQuality::Default,
+            cache_max_size_mb: 1024,
+        }
+    }
+
+    #[test]
+    fn test_partial_update_keeps_other_fields() {
+        let update = TunablesUpdate {
+            max_batch_size: Some(2000),
+            default_opening_filter: Some(OpeningFilterMode::IgnoreAll),
+            tessellation: Some(TessellationQuality::Fast),
+            ..Default::default()
+        };
+        let next = base().with_update(&update, 500).unwrap();
+        assert_eq!(next.max_batch_size, 2000);
+        assert_eq!(next.default_opening_filter, OpeningFilterMode::IgnoreAll);
+        assert_eq!(next.tessellation, TessellationQuality::Fast);
+        assert_eq!(next.cache_max_size_mb, 1024);
+        assert_eq!(next.initial_batch_size, 100);
+        assert_eq!(next.max_file_size_mb, 500);
+    }
+
+    #[test]
+    fn test_update_rejects_invalid_values() {
+        let too_large = TunablesUpdate {
+            max_file_size_mb: Some(501),
+            ..Default::default()
+        };
+        assert!(base().with_update(&too_large, 500).is_err());
+
+        let inverted = TunablesUpdate {
+            initial_batch_size: Some(5000),
+            ..Default::default()
+        };
+        assert!(base().with_update(&inverted, 500).is_err());
+
+        let no_cache = TunablesUpdate {
+            cache_max_size_mb: Some(0),
+            ..Default::default()
+        };
+        assert!(base().with_update(&no_cache, 500).is_err());
+    }
+
+    #[test]
+    fn test_shared_tunables_update() {
+        let shared = SharedTunables::new(base());
+        let update = |max_batch_size| TunablesUpdate {
+            max_batch_size: Some(max_batch_size),
+            ..Default::default()
+        };
+
+        let (previous, next) = shared
+            .update(|current| current.with_update(&update(4000), 500))
+            .unwrap();
+        assert_eq!(previous, base());
+        assert_eq!(next.max_batch_size, 4000);
+        assert_eq!(shared.snapshot(), next);
+
+        // A rejected update leaves the tunables alone
+        assert!(shared
+            .update(|current| current.with_update(&update(10), 500))
+            .is_err());
+        assert_eq!(shared.snapshot(), next);
+
+        // Concurrent updates each apply on top of the previous one
+        let shared = SharedTunables::new(base());
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    shared
+                        .update(|current| {
+                            let next = current.max_batch_size + 100;
+                            current.with_update(&update(next), 500)
+                        })
+                        .unwrap();
+                });
+            }
+        });
+        assert_eq!(shared.snapshot().max_batch_size, 1800);
+    }
+}
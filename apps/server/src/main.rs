@@ -19,17 +19,25 @@
 //! - `POST /api/v1/parse/metadata` - Quick metadata only
 //! - `POST /api/v1/parse/parquet` - Full parse with Parquet-encoded geometry (~15x smaller)
 //! - `POST /api/v1/parse/parquet/optimized` - ara3d BOS-optimized format (~50x smaller)
+//! - `POST /api/v1/validate/all` - Schema, geometry and property validation (optional webhook delivery)
+//! - `POST /api/v1/models/:key/raycast` - Pick the closest element along a ray
+//! - `POST /api/v1/models/:key/query/aabb` - Elements overlapping a bounding box
 //! - `GET /api/v1/cache/:key` - Retrieve cached result
+//! - `GET/PUT /api/v1/admin/config` - Runtime tunables (requires `ADMIN_TOKEN`)
+//! - `POST /api/v1/admin/synthetic` - Generate and process a synthetic model (requires `ADMIN_TOKEN`)
+//! - `POST /api/v1/admin/synthetic/model` - Download a synthetic model (requires `ADMIN_TOKEN`)
 
 use axum::http::{header, HeaderValue, Method};
 use axum::{
     extract::DefaultBodyLimit,
+    middleware::from_fn_with_state,
     routing::{get, post},
     Router,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Semaphore;
 use tower_http::{
     compression::CompressionLayer, cors::CorsLayer, timeout::TimeoutLayer, trace::TraceLayer,
 };
@@ -41,8 +49,8 @@ mod routes;
 mod services;
 mod types;
 
-use config::Config;
-use services::cache::DiskCache;
+use config::{Config, SharedTunables, Tunables};
+use services::{cache::DiskCache, BvhStore};
 
 /// Build CORS layer based on configuration.
 ///
@@ -64,7 +72,7 @@ fn build_cors_layer(config: &Config) -> CorsLayer {
 
         CorsLayer::new()
             .allow_origin(origins)
-            .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::OPTIONS])
             .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION, header::ACCEPT])
             .max_age(Duration::from_secs(3600))
     }
@@ -75,6 +83,12 @@ fn build_cors_layer(config: &Config) -> CorsLayer {
 pub struct AppState {
     pub cache: Arc<DiskCache>,
     pub config: Arc<Config>,
+    /// Settings adjustable at runtime via the admin API.
+    pub tunables: SharedTunables,
+    /// Scene BVHs of parsed models, loaded on demand from the cache.
+    pub bvhs: Arc<BvhStore>,
+    /// Slots for running validation jobs (`MAX_VALIDATION_JOBS`).
+    pub validation_jobs: Arc<Semaphore>,
 }
 
 #[tokio::main]
@@ -106,13 +120,40 @@ async fn main() {
         .expect("Failed to initialize rayon thread pool");
 
     // Initialize cache
-    let cache = Arc::new(DiskCache::new(&config.cache_dir).await);
+    let tunables = Tunables::from_config(&config);
+    let cache = Arc::new(DiskCache::new(&config.cache_dir, tunables.cache_max_size_bytes()).await);
+
+    if config.admin_token.is_none() {
+        tracing::info!("ADMIN_TOKEN not set - admin API disabled");
+    }
 
     let state = AppState {
         cache,
+        tunables: SharedTunables::new(tunables),
         config: Arc::new(config.clone()),
+        bvhs: Arc::new(BvhStore::default()),
+        validation_jobs: Arc::new(Semaphore::new(config.max_validation_jobs.max(1))),
     };
 
+    // Admin routes (bearer-token protected)
+    let admin = Router::new()
+        .route(
+            "/api/v1/admin/config",
+            get(routes::admin::get_config).put(routes::admin::put_config),
+        )
+        .route(
+            "/api/v1/admin/synthetic",
+            post(routes::admin::run_synthetic),
+        )
+        .route(
+            "/api/v1/admin/synthetic/model",
+            post(routes::admin::synthetic_model),
+        )
+        .route_layer(from_fn_with_state(
+            state.clone(),
+            middleware::admin_auth::require_admin,
+        ));
+
     // Build router
     let app = Router::new()
         // Root endpoint - API information
@@ -139,6 +180,17 @@ async fn main() {
             "/api/v1/parse/data-model/{cache_key}",
             get(routes::parse::get_data_model),
         )
+        // Validation endpoints
+        .route("/api/v1/validate/all", post(routes::validate::validate_all))
+        // Picking endpoints (use the BVH cached by the Parquet parse endpoints)
+        .route(
+            "/api/v1/models/{key}/raycast",
+            post(routes::models::raycast),
+        )
+        .route(
+            "/api/v1/models/{key}/query/aabb",
+            post(routes::models::query_aabb),
+        )
         // Cache endpoints
         .route("/api/v1/cache/{key}", get(routes::cache::get_cached))
         .route("/api/v1/cache/check/{hash}", get(routes::parse::check_cache))
@@ -146,6 +198,7 @@ async fn main() {
             "/api/v1/cache/geometry/{hash}",
             get(routes::parse::get_cached_geometry),
         )
+        .merge(admin)
         // Middleware
         .layer(DefaultBodyLimit::max(config.max_file_size_mb * 1024 * 1024)) // Match max_file_size_mb
         .layer(CompressionLayer::new()) // Compress responses (gzip)
@@ -0,0 +1,113 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Admin endpoints for runtime configuration and synthetic model runs.
+//!
+//! All routes in this module are mounted behind
+//! [`require_admin`](crate::middleware::admin_auth::require_admin).
+
+use crate::config::{Tunables, TunablesUpdate};
+use crate::error::ApiError;
+use crate::services::{
+    generate_synthetic_ifc, process_geometry_filtered, OpeningFilterMode, SyntheticSpec,
+};
+use crate::types::SyntheticRunResponse;
+use crate::AppState;
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use rustc_hash::FxHashSet;
+use std::time::Instant;
+
+/// GET /api/v1/admin/config - Current runtime tunables.
+pub async fn get_config(State(state): State<AppState>) -> Json<Tunables> {
+    Json(state.tunables.snapshot())
+}
+
+/// PUT /api/v1/admin/config - Update runtime tunables.
+///
+/// Accepts a partial object; omitted fields keep their current value. The new
+/// values apply to requests received after this call returns, in-flight
+/// parses are not affected.
+pub async fn put_config(
+    State(state): State<AppState>,
+    Json(update): Json<TunablesUpdate>,
+) -> Result<Json<Tunables>, ApiError> {
+    // Validated and applied under the tunables lock, so concurrent updates
+    // can't overwrite each other or leave the cache limit out of step
+    let (_, next) = state.tunables.update(|current| -> Result<_, ApiError> {
+        let next = current
+            .with_update(&update, state.config.max_file_size_mb)
+            .map_err(ApiError::BadRequest)?;
+        if next != *current {
+            tracing::info!(?current, ?next, "Runtime configuration updated");
+            state.cache.set_max_size(next.cache_max_size_bytes());
+        }
+        Ok(next)
+    })?;
+
+    Ok(Json(next))
+}
+
+/// POST /api/v1/admin/synthetic - Generate a synthetic model and run the
+/// geometry pipeline on it end to end.
+///
+/// The body is a [`SyntheticSpec`] (omitted fields use defaults). The run is
+/// not cached, so repeated calls measure the full pipeline every time.
+pub async fn run_synthetic(
+    Json(spec): Json<SyntheticSpec>,
+) -> Result<Json<SyntheticRunResponse>, ApiError> {
+    spec.validate().map_err(ApiError::BadRequest)?;
+
+    let response = tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        let model = generate_synthetic_ifc(&spec);
+        let generate_time_ms = started.elapsed().as_millis() as u64;
+
+        let result = process_geometry_filtered(&model.content, OpeningFilterMode::Default);
+        let meshed: FxHashSet<u32> = result.meshes.iter().map(|m| m.express_id).collect();
+        let missing_walls: Vec<u32> = model
+            .wall_ids
+            .iter()
+            .copied()
+            .filter(|id| !meshed.contains(id))
+            .collect();
+
+        tracing::info!(
+            walls = model.counts.walls,
+            meshes = result.meshes.len(),
+            missing = missing_walls.len(),
+            total_time_ms = result.stats.total_time_ms,
+            "Synthetic model run complete"
+        );
+
+        SyntheticRunResponse {
+            counts: model.counts,
+            content_bytes: model.content.len(),
+            generate_time_ms,
+            total_meshes: result.meshes.len(),
+            stats: result.stats,
+            passed: missing_walls.is_empty(),
+            missing_walls,
+        }
+    })
+    .await?;
+
+    Ok(Json(response))
+}
+
+/// POST /api/v1/admin/synthetic/model - Generate a synthetic model and return
+/// the IFC file, e.g. as load-test input for the parse endpoints.
+pub async fn synthetic_model(Json(spec): Json<SyntheticSpec>) -> Result<Response, ApiError> {
+    spec.validate().map_err(ApiError::BadRequest)?;
+    let model = tokio::task::spawn_blocking(move || generate_synthetic_ifc(&spec)).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-step")],
+        model.content,
+    )
+        .into_response())
+}
@@ -68,11 +68,36 @@ pub async fn info() -> Json<ApiInfoResponse> {
                 path: "/api/v1/parse/metadata",
                 description: "Quick metadata extraction only",
             },
+            EndpointInfo {
+                method: "POST",
+                path: "/api/v1/validate/all",
+                description: "Schema, geometry and property validation report",
+            },
+            EndpointInfo {
+                method: "POST",
+                path: "/api/v1/models/:key/raycast",
+                description: "Closest element hit by a ray",
+            },
+            EndpointInfo {
+                method: "POST",
+                path: "/api/v1/models/:key/query/aabb",
+                description: "Elements overlapping a bounding box",
+            },
             EndpointInfo {
                 method: "GET",
                 path: "/api/v1/cache/:key",
                 description: "Retrieve cached result",
             },
+            EndpointInfo {
+                method: "GET",
+                path: "/api/v1/admin/config",
+                description: "Read runtime tunables (admin token required)",
+            },
+            EndpointInfo {
+                method: "PUT",
+                path: "/api/v1/admin/config",
+                description: "Update runtime tunables (admin token required)",
+            },
         ],
     })
 }
@@ -4,6 +4,9 @@
 
 //! API routes for the IFC server.
 
+pub mod admin;
 pub mod cache;
 pub mod health;
+pub mod models;
 pub mod parse;
+pub mod validate;
@@ -0,0 +1,55 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Picking and spatial query endpoints for previously parsed models.
+
+use crate::error::ApiError;
+use crate::types::{AabbQueryRequest, AabbQueryResponse, RaycastRequest, RaycastResponse};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+/// POST /api/v1/models/:key/raycast - Closest element hit by a ray.
+///
+/// `key` is the cache key returned by the Parquet parse endpoints.
+pub async fn raycast(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(request): Json<RaycastRequest>,
+) -> Result<Json<RaycastResponse>, ApiError> {
+    let finite = request
+        .origin
+        .iter()
+        .chain(&request.direction)
+        .all(|v| v.is_finite());
+    if !finite || request.direction.iter().all(|&v| v == 0.0) {
+        return Err(ApiError::BadRequest(
+            "Ray origin and direction must be finite with a non-zero direction".into(),
+        ));
+    }
+
+    let bvh = state.bvhs.load(&state.cache, &key).await?;
+    let hit = bvh.raycast(request.origin, request.direction, request.max_distance);
+    Ok(Json(RaycastResponse { hit }))
+}
+
+/// POST /api/v1/models/:key/query/aabb - Elements overlapping an axis-aligned box.
+pub async fn query_aabb(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(request): Json<AabbQueryRequest>,
+) -> Result<Json<AabbQueryResponse>, ApiError> {
+    let valid = (0..3).all(|axis| request.min[axis] <= request.max[axis]);
+    if !valid {
+        return Err(ApiError::BadRequest(
+            "Query box min must not exceed max on any axis".into(),
+        ));
+    }
+
+    let bvh = state.bvhs.load(&state.cache, &key).await?;
+    let elements = bvh.query_aabb(request.min, request.max);
+    Ok(Json(AabbQueryResponse { elements }))
+}
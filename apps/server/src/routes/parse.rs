@@ -6,12 +6,14 @@
 
 use crate::error::ApiError;
 use crate::services::{
-    cache::DiskCache, extract_data_model, process_geometry_filtered, process_streaming,
+    cache::DiskCache, cache_scene_bvh, extract_data_model,
+    process_geometry_filtered_with_tessellation, process_streaming,
     serialize_data_model_to_parquet, serialize_to_parquet,
     serialize_to_parquet_optimized_with_stats, OpeningFilterMode, OptimizedStats,
-    VERTEX_MULTIPLIER,
+    TessellationQuality, VERTEX_MULTIPLIER,
 };
 use crate::types::{MetadataResponse, ModelMetadata, ParseResponse, ProcessingStats, StreamEvent};
+use crate::config::Tunables;
 use crate::AppState;
 use axum::{
     body::Body,
@@ -34,12 +36,44 @@ use std::io::Read;
 #[derive(serde::Deserialize, Default)]
 pub struct ParseQuery {
     /// Opening filter mode: "default", "ignore_all", or "ignore_opaque".
+    /// Falls back to the server's `default_opening_filter` tunable when omitted.
     #[serde(default)]
-    pub opening_filter: OpeningFilterMode,
+    pub opening_filter: Option<OpeningFilterMode>,
 }
 
+impl ParseQuery {
+    /// Opening filter requested by the client, or the current server default.
+    fn resolve_opening_filter(&self, tunables: &Tunables) -> OpeningFilterMode {
+        self.opening_filter.unwrap_or(tunables.default_opening_filter)
+    }
+}
+
+pub(crate) fn check_file_size(data: &[u8], tunables: &Tunables) -> Result<(), ApiError> {
+    if data.len() > tunables.max_file_size_bytes() {
+        return Err(ApiError::FileTooLarge {
+            max_mb: tunables.max_file_size_mb,
+        });
+    }
+    Ok(())
+}
+
+/// Cache key options for geometry built with `opening_filter` and the
+/// current tessellation quality. The default quality adds no option, so keys
+/// match the ones clients compute for default settings.
+fn geometry_cache_options(
+    opening_filter: OpeningFilterMode,
+    tunables: &Tunables,
+) -> Vec<(&'static str, &'static str)> {
+    let mut options = vec![("opening_filter", opening_filter.cache_key_suffix())];
+    if tunables.tessellation != TessellationQuality::Default {
+        options.push(("tessellation", tunables.tessellation.cache_key_suffix()));
+    }
+    options
+}
+
+/// Streaming endpoints always use the default filter; reject explicit requests for others.
 fn reject_unsupported_streaming_opening_filter(query: &ParseQuery) -> Result<(), ApiError> {
-    if query.opening_filter == OpeningFilterMode::Default {
+    if matches!(query.opening_filter, None | Some(OpeningFilterMode::Default)) {
         return Ok(());
     }
 
@@ -57,30 +91,7 @@ async fn extract_file(multipart: &mut Multipart) -> Result<Vec<u8>, ApiError> {
 
         if field_name == "file" {
             let bytes = field.bytes().await?;
-            let original_size = bytes.len();
-            tracing::debug!(size = original_size, "Extracted file from multipart");
-
-            // Check if file is gzip-compressed (magic bytes: 1f 8b)
-            let is_gzipped = bytes.len() >= 2 && bytes[0] == 0x1f && bytes[1] == 0x8b;
-
-            if is_gzipped {
-                tracing::debug!("Detected gzip compression, decompressing...");
-                let mut decoder = GzDecoder::new(bytes.as_ref());
-                let mut decompressed = Vec::new();
-                decoder
-                    .read_to_end(&mut decompressed)
-                    .map_err(|e| ApiError::Internal(format!("Failed to decompress gzip: {}", e)))?;
-                tracing::info!(
-                    original_size = original_size,
-                    decompressed_size = decompressed.len(),
-                    compression_ratio =
-                        format!("{:.1}x", original_size as f64 / decompressed.len() as f64),
-                    "File decompressed successfully"
-                );
-                return Ok(decompressed);
-            } else {
-                return Ok(bytes.to_vec());
-            }
+            return decompress_upload(&bytes);
         }
     }
 
@@ -88,28 +99,48 @@ async fn extract_file(multipart: &mut Multipart) -> Result<Vec<u8>, ApiError> {
     Err(ApiError::MissingFile)
 }
 
+/// Return uploaded file bytes, decompressing them if gzip-compressed.
+pub(crate) fn decompress_upload(bytes: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let original_size = bytes.len();
+    tracing::debug!(size = original_size, "Extracted file from multipart");
+
+    // Check if file is gzip-compressed (magic bytes: 1f 8b)
+    let is_gzipped = bytes.len() >= 2 && bytes[0] == 0x1f && bytes[1] == 0x8b;
+    if !is_gzipped {
+        return Ok(bytes.to_vec());
+    }
+
+    tracing::debug!("Detected gzip compression, decompressing...");
+    let mut decoder = GzDecoder::new(bytes);
+    let mut decompressed = Vec::new();
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| ApiError::Internal(format!("Failed to decompress gzip: {}", e)))?;
+    tracing::info!(
+        original_size = original_size,
+        decompressed_size = decompressed.len(),
+        compression_ratio = format!("{:.1}x", original_size as f64 / decompressed.len() as f64),
+        "File decompressed successfully"
+    );
+    Ok(decompressed)
+}
+
 /// POST /api/v1/parse - Full synchronous parse.
 pub async fn parse_full(
     State(state): State<AppState>,
     Query(query): Query<ParseQuery>,
     mut multipart: Multipart,
 ) -> Result<Json<ParseResponse>, ApiError> {
+    let tunables = state.tunables.snapshot();
     // Extract file from multipart
     let data = extract_file(&mut multipart).await?;
 
-    // Check file size
-    if data.len() > state.config.max_file_size_mb * 1024 * 1024 {
-        return Err(ApiError::FileTooLarge {
-            max_mb: state.config.max_file_size_mb,
-        });
-    }
+    check_file_size(&data, &tunables)?;
 
     // Generate cache key (include opening filter so different modes get different cache entries)
-    let cache_key = format!(
-        "{}-{}",
-        DiskCache::generate_key(&data),
-        query.opening_filter.cache_key_suffix()
-    );
+    let opening_filter = query.resolve_opening_filter(&tunables);
+    let cache_key =
+        DiskCache::generate_key(&data, &geometry_cache_options(opening_filter, &tunables));
 
     // Check cache first
     if let Some(mut cached) = state.cache.get::<ParseResponse>(&cache_key).await? {
@@ -122,12 +153,13 @@ pub async fn parse_full(
 
     // Parse content
     let content = String::from_utf8(data)?;
-    let opening_filter = query.opening_filter;
 
     // Process on blocking thread pool (CPU-intensive)
-    let result =
-        tokio::task::spawn_blocking(move || process_geometry_filtered(&content, opening_filter))
-            .await?;
+    let tessellation = tunables.tessellation.config();
+    let result = tokio::task::spawn_blocking(move || {
+        process_geometry_filtered_with_tessellation(&content, opening_filter, tessellation)
+    })
+    .await?;
 
     let response = ParseResponse {
         cache_key: cache_key.clone(),
@@ -157,25 +189,22 @@ pub async fn parse_stream(
     Query(query): Query<ParseQuery>,
     mut multipart: Multipart,
 ) -> Result<Sse<impl futures::Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let tunables = state.tunables.snapshot();
     reject_unsupported_streaming_opening_filter(&query)?;
 
     // Extract file
     let data = extract_file(&mut multipart).await?;
 
-    // Check file size
-    if data.len() > state.config.max_file_size_mb * 1024 * 1024 {
-        return Err(ApiError::FileTooLarge {
-            max_mb: state.config.max_file_size_mb,
-        });
-    }
+    check_file_size(&data, &tunables)?;
 
     let content = String::from_utf8(data)?;
-    let initial_batch_size = state.config.initial_batch_size;
-    let max_batch_size = state.config.max_batch_size;
+    let initial_batch_size = tunables.initial_batch_size;
+    let max_batch_size = tunables.max_batch_size;
+    let tessellation = tunables.tessellation.config();
 
     // Create streaming response with dynamic batch sizing
-    let stream =
-        process_streaming(content, initial_batch_size, max_batch_size).map(|event: StreamEvent| {
+    let stream = process_streaming(content, initial_batch_size, max_batch_size, tessellation).map(
+        |event: StreamEvent| {
             let json = serde_json::to_string(&event).unwrap_or_else(|e| {
                 serde_json::to_string(&StreamEvent::Error {
                     message: e.to_string(),
@@ -183,7 +212,8 @@ pub async fn parse_stream(
                 .unwrap()
             });
             Ok(Event::default().data(json))
-        });
+        },
+    );
 
     Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
@@ -244,23 +274,19 @@ pub async fn parse_parquet_stream(
     use futures::StreamExt;
     use std::sync::{Arc, Mutex};
 
+    let tunables = state.tunables.snapshot();
+
     reject_unsupported_streaming_opening_filter(&query)?;
 
     // Extract file
     let data = extract_file(&mut multipart).await?;
 
-    // Check file size
-    if data.len() > state.config.max_file_size_mb * 1024 * 1024 {
-        return Err(ApiError::FileTooLarge {
-            max_mb: state.config.max_file_size_mb,
-        });
-    }
+    check_file_size(&data, &tunables)?;
 
     // Generate cache key before processing (include opening filter)
-    let cache_key = format!(
-        "{}-{}",
-        DiskCache::generate_key(&data),
-        query.opening_filter.cache_key_suffix()
+    let cache_key = DiskCache::generate_key(
+        &data,
+        &geometry_cache_options(OpeningFilterMode::Default, &tunables),
     );
     let cache_key_clone = cache_key.clone();
 
@@ -331,8 +357,9 @@ pub async fn parse_parquet_stream(
     );
 
     let content = String::from_utf8(data)?;
-    let initial_batch_size = state.config.initial_batch_size;
-    let max_batch_size = state.config.max_batch_size;
+    let initial_batch_size = tunables.initial_batch_size;
+    let max_batch_size = tunables.max_batch_size;
+    let tessellation = tunables.tessellation.config();
     let cache = state.cache.clone();
 
     // OPTIMIZATION: Accumulate meshes during streaming to avoid re-processing for cache
@@ -343,7 +370,7 @@ pub async fn parse_parquet_stream(
     let cache_key_for_geometry = cache_key.clone();
 
     // Create streaming response that yields Parquet batches
-    let stream = process_streaming(content.clone(), initial_batch_size, max_batch_size).map(move |event: StreamEvent| {
+    let stream = process_streaming(content.clone(), initial_batch_size, max_batch_size, tessellation).map(move |event: StreamEvent| {
         let sse_event = match event {
             StreamEvent::Start { total_estimate } => {
                 ParquetStreamEvent::Start {
@@ -413,10 +440,10 @@ pub async fn parse_parquet_stream(
 
                     // Serialize accumulated meshes to Parquet (no re-processing needed!)
                     let serialize_result = tokio::task::spawn_blocking(move || {
-                        serialize_to_parquet(&all_meshes)
+                        (serialize_to_parquet(&all_meshes), all_meshes)
                     }).await;
 
-                    if let Ok(Ok(geometry_parquet)) = serialize_result {
+                    if let Ok((Ok(geometry_parquet), all_meshes)) = serialize_result {
                         // Build combined format (same as non-streaming endpoint)
                         // Format: [geometry_len: u32][geometry_data][data_model_len: u32]
                         let mut combined_parquet = Vec::new();
@@ -454,6 +481,8 @@ pub async fn parse_parquet_stream(
                                 tracing::debug!(cache_key = %metadata_cache_key, "Metadata cached from stream");
                             }
                         }
+
+                        cache_scene_bvh(&cache, &key, all_meshes).await;
                     } else {
                         tracing::error!("Failed to serialize accumulated meshes for caching");
                     }
@@ -512,15 +541,11 @@ pub async fn parse_metadata(
     State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<Json<MetadataResponse>, ApiError> {
+    let tunables = state.tunables.snapshot();
     // Extract file
     let data = extract_file(&mut multipart).await?;
 
-    // Check file size
-    if data.len() > state.config.max_file_size_mb * 1024 * 1024 {
-        return Err(ApiError::FileTooLarge {
-            max_mb: state.config.max_file_size_mb,
-        });
-    }
+    check_file_size(&data, &tunables)?;
 
     let file_size = data.len();
     let content = String::from_utf8(data)?;
@@ -597,22 +622,16 @@ pub async fn parse_parquet(
     Query(query): Query<ParseQuery>,
     mut multipart: Multipart,
 ) -> Result<Response, ApiError> {
+    let tunables = state.tunables.snapshot();
     // Extract file from multipart
     let data = extract_file(&mut multipart).await?;
 
-    // Check file size
-    if data.len() > state.config.max_file_size_mb * 1024 * 1024 {
-        return Err(ApiError::FileTooLarge {
-            max_mb: state.config.max_file_size_mb,
-        });
-    }
+    check_file_size(&data, &tunables)?;
 
     // Generate cache key (include opening filter so different modes get different cache entries)
-    let cache_key = format!(
-        "{}-{}",
-        DiskCache::generate_key(&data),
-        query.opening_filter.cache_key_suffix()
-    );
+    let opening_filter = query.resolve_opening_filter(&tunables);
+    let cache_key =
+        DiskCache::generate_key(&data, &geometry_cache_options(opening_filter, &tunables));
 
     // Check cache first (before any processing)
     let parquet_cache_key = format!("{}-parquet-v2", cache_key);
@@ -653,12 +672,18 @@ pub async fn parse_parquet(
     // rayon::join works correctly here because rayon has its own thread pool
     // that's independent of tokio's blocking thread pool
     let serialize_start = tokio::time::Instant::now();
-    let opening_filter = query.opening_filter;
+    let tessellation = tunables.tessellation.config();
     let ((geometry_result, geometry_parquet), (data_model_stats, data_model_parquet)) =
         tokio::task::spawn_blocking(move || {
             // First: extract geometry and data model in parallel
             let (geometry_result, data_model) = rayon::join(
-                || process_geometry_filtered(&content, opening_filter),
+                || {
+                    process_geometry_filtered_with_tessellation(
+                        &content,
+                        opening_filter,
+                        tessellation,
+                    )
+                },
                 || extract_data_model(&content),
             );
 
@@ -730,6 +755,7 @@ pub async fn parse_parquet(
     };
 
     let metadata_json = serde_json::to_string(&metadata_header)?;
+    let scene_meshes = geometry_result.meshes;
 
     // Cache the results for future requests
     let parquet_cache_key = format!("{}-parquet-v2", cache_key_clone);
@@ -757,6 +783,7 @@ pub async fn parse_parquet(
             parquet_size = combined_parquet_clone.len(),
             "Cached Parquet response"
         );
+        cache_scene_bvh(&cache, &cache_key_clone, scene_meshes).await;
     });
 
     // Build response with binary body and metadata header
@@ -805,22 +832,16 @@ pub async fn parse_parquet_optimized(
     Query(query): Query<ParseQuery>,
     mut multipart: Multipart,
 ) -> Result<Response, ApiError> {
+    let tunables = state.tunables.snapshot();
     // Extract file from multipart
     let data = extract_file(&mut multipart).await?;
 
-    // Check file size
-    if data.len() > state.config.max_file_size_mb * 1024 * 1024 {
-        return Err(ApiError::FileTooLarge {
-            max_mb: state.config.max_file_size_mb,
-        });
-    }
+    check_file_size(&data, &tunables)?;
 
     // Generate cache key (include opening filter so different modes get different cache entries)
-    let cache_key = format!(
-        "{}-{}",
-        DiskCache::generate_key(&data),
-        query.opening_filter.cache_key_suffix()
-    );
+    let opening_filter = query.resolve_opening_filter(&tunables);
+    let cache_key =
+        DiskCache::generate_key(&data, &geometry_cache_options(opening_filter, &tunables));
 
     tracing::info!(
         cache_key = %cache_key,
@@ -830,12 +851,13 @@ pub async fn parse_parquet_optimized(
 
     // Parse content
     let content = String::from_utf8(data)?;
-    let opening_filter = query.opening_filter;
 
     // Process on blocking thread pool (CPU-intensive)
-    let result =
-        tokio::task::spawn_blocking(move || process_geometry_filtered(&content, opening_filter))
-            .await?;
+    let tessellation = tunables.tessellation.config();
+    let result = tokio::task::spawn_blocking(move || {
+        process_geometry_filtered_with_tessellation(&content, opening_filter, tessellation)
+    })
+    .await?;
 
     // Serialize to optimized Parquet (with deduplication, quantization, etc.)
     // Don't include normals by default - client can compute them
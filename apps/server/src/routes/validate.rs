@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Validation endpoints.
+
+use crate::error::ApiError;
+use crate::routes::parse::{check_file_size, decompress_upload};
+use crate::services::{
+    cache::DiskCache, check_webhook_url, deliver_report, validate_all as run_validation,
+};
+use crate::types::{ValidateOptions, ValidationAccepted};
+use crate::AppState;
+use axum::{
+    extract::{Multipart, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// POST /api/v1/validate/all - Combined schema, geometry and property validation.
+///
+/// Multipart fields:
+/// - `file`: the IFC file (optionally gzip-compressed)
+/// - `options`: JSON [`ValidateOptions`] (optional)
+///
+/// Without a `webhook_url` the report is returned directly. With one, the
+/// request is answered with `202 Accepted` and the report is POSTed to the
+/// webhook once validation finishes.
+pub async fn validate_all(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> Result<Response, ApiError> {
+    let tunables = state.tunables.snapshot();
+
+    let mut data = None;
+    let mut options = ValidateOptions::default();
+    while let Some(field) = multipart.next_field().await? {
+        match field.name().unwrap_or_default() {
+            "file" => data = Some(decompress_upload(&field.bytes().await?)?),
+            "options" => {
+                let bytes = field.bytes().await?;
+                options = serde_json::from_slice(&bytes)
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid options: {}", e)))?;
+            }
+            _ => {}
+        }
+    }
+    let data = data.ok_or(ApiError::MissingFile)?;
+    check_file_size(&data, &tunables)?;
+
+    // Reject bad webhooks before doing any work
+    let webhook = options
+        .webhook_url
+        .as_deref()
+        .map(|url| check_webhook_url(url, &state.config.webhook_allowed_hosts))
+        .transpose()?;
+
+    // Bound concurrent validations; each holds a slot until its report is built
+    let permit = state
+        .validation_jobs
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| ApiError::Busy("too many validation jobs in progress".into()))?;
+
+    let report_id = DiskCache::generate_key(&data, &[]);
+    let content = String::from_utf8(data)?;
+    let schemas = options.property_schemas;
+
+    let Some(webhook) = webhook else {
+        let report = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            run_validation(&content, report_id, &schemas)
+        })
+        .await?;
+        return Ok(Json(report).into_response());
+    };
+
+    tracing::info!(report_id = %report_id, webhook = %webhook, "Validation queued for webhook delivery");
+    let accepted = ValidationAccepted {
+        report_id: report_id.clone(),
+        status: "queued",
+        webhook_url: webhook.to_string(),
+    };
+    tokio::spawn(async move {
+        let report = match tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            run_validation(&content, report_id, &schemas)
+        })
+        .await
+        {
+            Ok(report) => report,
+            Err(e) => {
+                tracing::error!(error = %e, "Validation task failed");
+                return;
+            }
+        };
+        if let Err(e) = deliver_report(webhook, &report).await {
+            tracing::error!(report_id = %report.report_id, error = %e, "Giving up on webhook delivery");
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(accepted)).into_response())
+}
@@ -6,18 +6,23 @@
 
 use crate::error::ApiError;
 use serde::{de::DeserializeOwned, Serialize};
-use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 /// Content-addressable disk cache.
 #[derive(Debug, Clone)]
 pub struct DiskCache {
     cache_dir: PathBuf,
+    /// Total size of entries above which the oldest are evicted
+    max_size_bytes: Arc<AtomicU64>,
 }
 
 impl DiskCache {
-    /// Create a new cache in the specified directory.
-    pub async fn new(cache_dir: &str) -> Self {
+    /// Create a new cache in the specified directory, holding at most
+    /// `max_size_bytes` of entries.
+    pub async fn new(cache_dir: &str, max_size_bytes: u64) -> Self {
         let path = PathBuf::from(cache_dir);
 
         // Create cache directory if it doesn't exist
@@ -29,14 +34,24 @@ impl DiskCache {
             );
         }
 
-        Self { cache_dir: path }
+        Self {
+            cache_dir: path,
+            max_size_bytes: Arc::new(AtomicU64::new(max_size_bytes)),
+        }
+    }
+
+    /// Change the size limit; it is enforced on the next write.
+    pub fn set_max_size(&self, max_size_bytes: u64) {
+        self.max_size_bytes.store(max_size_bytes, Ordering::Relaxed);
     }
 
-    /// Generate a cache key from file content (SHA256 hash).
-    pub fn generate_key(data: &[u8]) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        hex::encode(hasher.finalize())
+    /// Generate a cache key from file content and the options that affect
+    /// the result.
+    ///
+    /// Uses the canonical key shared with the WASM and desktop frontends
+    /// (see [`ifc_lite_core::cache_key`]).
+    pub fn generate_key(data: &[u8], options: &[(&str, &str)]) -> String {
+        ifc_lite_core::cache_key(data, options)
     }
 
     /// Get a cached value by key.
@@ -56,6 +71,7 @@ impl DiskCache {
         let data = serde_json::to_vec(value)?;
         cacache::write(&self.cache_dir, key, &data).await?;
         tracing::debug!(key = %key, size = data.len(), "Cached result");
+        self.evict_over_limit().await;
         Ok(())
     }
 
@@ -91,6 +107,86 @@ impl DiskCache {
     pub async fn set_bytes(&self, key: &str, data: &[u8]) -> Result<(), ApiError> {
         cacache::write(&self.cache_dir, key, data).await?;
         tracing::debug!(key = %key, size = data.len(), "Cached raw bytes");
+        self.evict_over_limit().await;
         Ok(())
     }
+
+    /// Evict the oldest entries while the cache is over its size limit.
+    async fn evict_over_limit(&self) {
+        let cache_dir = self.cache_dir.clone();
+        let max_size = self.max_size_bytes.load(Ordering::Relaxed);
+        match tokio::task::spawn_blocking(move || evict_oldest(&cache_dir, max_size)).await {
+            Ok(Ok(0)) => {}
+            Ok(Ok(evicted)) => {
+                tracing::debug!(evicted, max_size, "Evicted cache entries over size limit");
+            }
+            Ok(Err(e)) => tracing::warn!(error = %e, "Failed to evict cache entries"),
+            Err(e) => tracing::warn!(error = %e, "Cache eviction task failed"),
+        }
+    }
+}
+
+/// Remove entries, oldest first, until their total size is at most
+/// `max_size`. Returns the number of entries removed.
+///
+/// Content shared by several keys is only deleted with the last of them.
+fn evict_oldest(cache_dir: &Path, max_size: u64) -> Result<usize, cacache::Error> {
+    let mut entries = cacache::list_sync(cache_dir).collect::<Result<Vec<_>, _>>()?;
+    let mut total: u64 = entries.iter().map(|entry| entry.size as u64).sum();
+    if total <= max_size {
+        return Ok(0);
+    }
+
+    let mut references: HashMap<String, usize> = HashMap::new();
+    for entry in &entries {
+        *references.entry(entry.integrity.to_string()).or_default() += 1;
+    }
+
+    entries.sort_by_key(|entry| entry.time);
+    let mut evicted = 0;
+    for entry in entries {
+        if total <= max_size {
+            break;
+        }
+        cacache::remove_sync(cache_dir, &entry.key)?;
+        let count = references.entry(entry.integrity.to_string()).or_default();
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            cacache::remove_hash_sync(cache_dir, &entry.integrity)?;
+        }
+        total = total.saturating_sub(entry.size as u64);
+        evicted += 1;
+    }
+    Ok(evicted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_evict_oldest_until_under_limit() {
+        let dir = std::env::temp_dir().join(format!("ifc-lite-cache-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // Sized like the index entries `DiskCache::set` writes
+        for (key, size) in [("a", 400), ("b", 400), ("c", 400)] {
+            let mut writer = cacache::WriteOpts::new()
+                .size(size)
+                .open_sync(&dir, key)
+                .unwrap();
+            writer.write_all(&vec![0; size]).unwrap();
+            writer.commit().unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        assert_eq!(evict_oldest(&dir, 2000).unwrap(), 0);
+        assert_eq!(evict_oldest(&dir, 900).unwrap(), 1);
+        assert!(cacache::read_sync(&dir, "a").is_err());
+        // The keys share their content, which stays for the remaining ones
+        assert_eq!(cacache::read_sync(&dir, "c").unwrap().len(), 400);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
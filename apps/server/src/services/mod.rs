@@ -9,8 +9,12 @@ pub mod data_model;
 pub mod parquet;
 pub mod parquet_data_model;
 pub mod parquet_optimized;
+pub mod picking;
 pub mod processor;
 pub mod streaming;
+pub mod synthetic;
+pub mod validation;
+pub mod webhook;
 
 pub use data_model::extract_data_model;
 pub use parquet::{serialize_to_parquet, ParquetError};
@@ -18,5 +22,12 @@ pub use parquet_data_model::serialize_data_model_to_parquet;
 pub use parquet_optimized::{
     serialize_to_parquet_optimized_with_stats, OptimizedStats, VERTEX_MULTIPLIER,
 };
-pub use processor::{process_geometry_filtered, OpeningFilterMode};
+pub use picking::{cache_scene_bvh, BvhStore};
+pub use processor::{
+    process_geometry_filtered, process_geometry_filtered_with_tessellation, OpeningFilterMode,
+    TessellationQuality,
+};
 pub use streaming::process_streaming;
+pub use synthetic::{generate_synthetic_ifc, SyntheticSpec};
+pub use validation::validate_all;
+pub use webhook::{check_webhook_url, deliver_report};
@@ -0,0 +1,279 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Per-model triangle BVH for server-side picking and spatial queries.
+//!
+//! Built once after parsing and cached next to the Parquet payload under
+//! [`bvh_cache_key`], so thin clients can pick without downloading geometry.
+
+use crate::error::ApiError;
+use crate::services::cache::DiskCache;
+use crate::types::{AabbQueryElement, MeshData, RaycastHit};
+use ifc_lite_geometry::{Bvh, Point3, Ray, Vector3};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Serialization format tag; bump when the layout changes.
+const MAGIC: &[u8; 8] = b"IFCBVH02";
+/// Number of deserialized BVHs kept in memory.
+const MEMORY_CACHE_CAPACITY: usize = 8;
+
+/// Cache key of the BVH stored alongside a parse result.
+pub fn bvh_cache_key(key: &str) -> String {
+    format!("{}-bvh-v2", key)
+}
+
+type Vec3 = [f32; 3];
+
+/// Triangle BVH over all meshes of a model, with the IFC type of each element.
+#[derive(Debug, Clone)]
+pub struct SceneBvh {
+    /// `(express_id, ifc_type)`, sorted by express ID.
+    elements: Vec<(u32, String)>,
+    bvh: Bvh,
+}
+
+impl SceneBvh {
+    /// Build a BVH from parsed meshes.
+    pub fn build(meshes: &[MeshData]) -> Self {
+        let mut elements: Vec<(u32, String)> = meshes
+            .iter()
+            .map(|mesh| (mesh.express_id, mesh.ifc_type.clone()))
+            .collect();
+        elements.sort_by_key(|(express_id, _)| *express_id);
+        elements.dedup_by_key(|(express_id, _)| *express_id);
+        let bvh = Bvh::build_from_buffers(
+            meshes
+                .iter()
+                .map(|mesh| (mesh.express_id, &mesh.positions[..], &mesh.indices[..])),
+        );
+        Self { elements, bvh }
+    }
+
+    fn ifc_type(&self, express_id: u32) -> String {
+        self.elements
+            .binary_search_by_key(&express_id, |(id, _)| *id)
+            .map(|i| self.elements[i].1.clone())
+            .unwrap_or_default()
+    }
+
+    /// Closest triangle hit along a ray.
+    pub fn raycast(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: Option<f32>,
+    ) -> Option<RaycastHit> {
+        let direction = Vector3::from(direction.map(f64::from));
+        let length = direction.norm();
+        if !(length.is_finite() && length > 0.0) {
+            return None;
+        }
+        let ray = Ray::new(Point3::from(origin.map(f64::from)), direction);
+        let hit = self
+            .bvh
+            .raycast(&ray, max_distance.map_or(f64::MAX, f64::from))?;
+        Some(RaycastHit {
+            express_id: hit.express_id,
+            ifc_type: self.ifc_type(hit.express_id),
+            distance: hit.distance as f32,
+            point: [hit.point.x, hit.point.y, hit.point.z].map(|v| v as f32),
+            normal: [hit.normal.x, hit.normal.y, hit.normal.z].map(|v| v as f32),
+        })
+    }
+
+    /// Elements with a triangle whose bounds overlap the box.
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> Vec<AabbQueryElement> {
+        self.bvh
+            .elements_in_box(Point3::from(min), Point3::from(max))
+            .into_iter()
+            .map(|express_id| AabbQueryElement {
+                express_id,
+                ifc_type: self.ifc_type(express_id),
+            })
+            .collect()
+    }
+
+    /// Serialize to the cached binary layout: the element table (little
+    /// endian), followed by [`Bvh::to_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bvh = self.bvh.to_bytes();
+        let mut out = Vec::with_capacity(12 + self.elements.len() * 24 + bvh.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&(self.elements.len() as u32).to_le_bytes());
+        for (express_id, ifc_type) in &self.elements {
+            out.extend_from_slice(&express_id.to_le_bytes());
+            out.extend_from_slice(&(ifc_type.len() as u32).to_le_bytes());
+            out.extend_from_slice(ifc_type.as_bytes());
+        }
+        out.extend_from_slice(&bvh);
+        out
+    }
+
+    /// Deserialize and validate bytes written by [`SceneBvh::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ApiError> {
+        let mut r = Reader(bytes);
+        if r.take(MAGIC.len())? != MAGIC {
+            return Err(ApiError::Cache("Unknown BVH format".into()));
+        }
+
+        let element_count = r.u32()? as usize;
+        let mut elements = Vec::with_capacity(element_count.min(bytes.len()));
+        for _ in 0..element_count {
+            let express_id = r.u32()?;
+            let len = r.u32()? as usize;
+            let ifc_type = String::from_utf8(r.take(len)?.to_vec())
+                .map_err(|_| ApiError::Cache("Corrupt BVH: bad type name".into()))?;
+            elements.push((express_id, ifc_type));
+        }
+        if !elements.is_sorted_by_key(|(express_id, _)| *express_id) {
+            return Err(ApiError::Cache("Corrupt BVH: unsorted elements".into()));
+        }
+
+        let bvh = Bvh::from_bytes(r.0).map_err(|e| ApiError::Cache(e.to_string()))?;
+        Ok(Self { elements, bvh })
+    }
+}
+
+/// Bounds-checked little-endian reader for the element table.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ApiError> {
+        if self.0.len() < n {
+            return Err(ApiError::Cache("Truncated BVH data".into()));
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, ApiError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+/// Small in-memory LRU of deserialized BVHs in front of the disk cache.
+#[derive(Default)]
+pub struct BvhStore {
+    entries: Mutex<VecDeque<(String, Arc<SceneBvh>)>>,
+}
+
+impl BvhStore {
+    /// BVH for a parsed model, from memory or the disk cache.
+    pub async fn load(&self, cache: &DiskCache, key: &str) -> Result<Arc<SceneBvh>, ApiError> {
+        if let Some(bvh) = self.lookup(key) {
+            return Ok(bvh);
+        }
+
+        let bytes = cache.get_bytes(&bvh_cache_key(key)).await?.ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "No BVH for model {}; parse it with /api/v1/parse/parquet first",
+                key
+            ))
+        })?;
+        let bvh =
+            Arc::new(tokio::task::spawn_blocking(move || SceneBvh::from_bytes(&bytes)).await??);
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|(k, _)| k != key);
+        if entries.len() >= MEMORY_CACHE_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back((key.to_string(), bvh.clone()));
+        Ok(bvh)
+    }
+
+    fn lookup(&self, key: &str) -> Option<Arc<SceneBvh>> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let position = entries.iter().position(|(k, _)| k == key)?;
+        let entry = entries.remove(position)?;
+        let bvh = entry.1.clone();
+        entries.push_back(entry);
+        Some(bvh)
+    }
+}
+
+/// Build a BVH for `meshes` and store it next to the parse result for `key`.
+pub async fn cache_scene_bvh(cache: &DiskCache, key: &str, meshes: Vec<MeshData>) {
+    let bytes = match tokio::task::spawn_blocking(move || SceneBvh::build(&meshes).to_bytes()).await
+    {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to build scene BVH");
+            return;
+        }
+    };
+    let bvh_key = bvh_cache_key(key);
+    match cache.set_bytes(&bvh_key, &bytes).await {
+        Ok(()) => tracing::info!(cache_key = %bvh_key, size = bytes.len(), "Scene BVH cached"),
+        Err(e) => tracing::error!(error = %e, "Failed to cache scene BVH"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ifc_lite_geometry::test_fixtures::box_mesh;
+
+    /// Axis-aligned box mesh (12 triangles).
+    fn cube(express_id: u32, min: Vec3, max: Vec3) -> MeshData {
+        let mesh = box_mesh(min, max);
+        let normals = vec![0.0; mesh.positions.len()];
+        MeshData::new(
+            express_id,
+            "IfcWall".to_string(),
+            mesh.positions,
+            normals,
+            mesh.indices,
+            [1.0; 4],
+        )
+    }
+
+    #[test]
+    fn test_raycast_and_aabb_query() {
+        let meshes: Vec<MeshData> = (0..50)
+            .map(|i| {
+                let x = i as f32 * 2.0;
+                cube(100 + i, [x, 0.0, 0.0], [x + 1.0, 1.0, 1.0])
+            })
+            .collect();
+        let bvh = SceneBvh::build(&meshes);
+
+        // Ray along +X from the left hits the first cube's x = 0 face
+        let hit = bvh
+            .raycast([-5.0, 0.5, 0.5], [2.0, 0.0, 0.0], None)
+            .unwrap();
+        assert_eq!(hit.express_id, 100);
+        assert!((hit.distance - 5.0).abs() < 1e-5);
+        assert_eq!(hit.normal, [-1.0, 0.0, 0.0]);
+
+        // Straight down onto cube 7, and a miss between cubes
+        let hit = bvh
+            .raycast([14.5, 0.5, 10.0], [0.0, 0.0, -1.0], None)
+            .unwrap();
+        assert_eq!(hit.express_id, 107);
+        assert!(bvh
+            .raycast([15.5, 0.5, 10.0], [0.0, 0.0, -1.0], None)
+            .is_none());
+        assert!(bvh
+            .raycast([14.5, 0.5, 10.0], [0.0, 0.0, -1.0], Some(5.0))
+            .is_none());
+
+        let ids: Vec<u32> = bvh
+            .query_aabb([3.5, 0.2, 0.2], [6.5, 0.8, 0.8])
+            .iter()
+            .map(|e| e.express_id)
+            .collect();
+        assert_eq!(ids, [102, 103]);
+
+        // Round trip through the cached layout
+        let restored = SceneBvh::from_bytes(&bvh.to_bytes()).unwrap();
+        let hit = restored
+            .raycast([14.5, 0.5, 10.0], [0.0, 0.0, -1.0], None)
+            .unwrap();
+        assert_eq!(hit.express_id, 107);
+        assert!(SceneBvh::from_bytes(&bvh.to_bytes()[..40]).is_err());
+    }
+}
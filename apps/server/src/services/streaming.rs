@@ -4,7 +4,7 @@
 
 //! Streaming geometry processing with Server-Sent Events.
 
-use crate::services::cache::DiskCache;
+use crate::services::{cache::DiskCache, OpeningFilterMode};
 use crate::types::{CoordinateInfo, MeshData, ModelMetadata, ProcessingStats, StreamEvent};
 use async_stream::stream;
 use futures::Stream;
@@ -12,7 +12,7 @@ use ifc_lite_core::{
     build_entity_index, scan_placement_bounds, DecodedEntity, EntityDecoder, EntityIndex,
     EntityScanner, IfcType,
 };
-use ifc_lite_geometry::{calculate_normals, GeometryRouter};
+use ifc_lite_geometry::{calculate_normals, GeometryRouter, TessellationConfig};
 use rayon::prelude::*;
 use rustc_hash::FxHashMap;
 use std::pin::Pin;
@@ -41,6 +41,8 @@ struct PreparedData {
     parse_time_ms: u64,
     /// OPTIMIZATION: Precomputed unit scale to avoid parsing content per mesh
     unit_scale: f64,
+    /// Plane angle unit in radians, `None` if the model declares none
+    plane_angle_unit: Option<f64>,
     /// RTC offset for large-coordinate models (preserves precision in f32 output)
     rtc_offset: (f64, f64, f64),
     /// IfcSite ObjectPlacement as a column-major 4×4 matrix (metres).
@@ -154,8 +156,9 @@ fn prepare_streaming_data(content: String) -> PreparedData {
     });
 
     // OPTIMIZATION: Extract unit_scale before dropping router
-    // This allows process_batch to use with_scale() instead of with_units() per mesh
+    // This allows the streaming router to use with_scale() instead of with_units()
     let unit_scale = router.unit_scale();
+    let plane_angle_unit = router.plane_angle_unit();
     drop(router); // Explicitly drop non-Send router
 
     let parse_time_ms = parse_start.elapsed().as_millis() as u64;
@@ -170,6 +173,7 @@ fn prepare_streaming_data(content: String) -> PreparedData {
         total_entities,
         parse_time_ms,
         unit_scale,
+        plane_angle_unit,
         rtc_offset,
         site_transform,
         building_transform,
@@ -183,8 +187,7 @@ fn process_batch(
     entity_index: Arc<EntityIndex>,
     style_index: Arc<FxHashMap<u32, [f32; 4]>>,
     void_index: Arc<FxHashMap<u32, Vec<u32>>>,
-    unit_scale: f64,
-    rtc_offset: (f64, f64, f64),
+    router: Arc<GeometryRouter>,
 ) -> Vec<MeshData> {
     jobs.par_iter()
         .filter_map(|job| {
@@ -196,11 +199,7 @@ fn process_batch(
                     return None;
                 }
 
-                // OPTIMIZATION: Use with_scale() instead of with_units()
-                // unit_scale is precomputed once, avoiding content parsing per mesh
-                let local_router = GeometryRouter::with_scale_and_rtc(unit_scale, rtc_offset);
-
-                if let Ok(mut mesh) = local_router.process_element_with_voids(
+                if let Ok(mut mesh) = router.process_element_with_voids(
                     &entity,
                     &mut local_decoder,
                     void_index.as_ref(),
@@ -265,6 +264,7 @@ pub fn process_streaming(
     content: String,
     initial_batch_size: usize,
     max_batch_size: usize,
+    tessellation: TessellationConfig,
 ) -> Pin<Box<dyn Stream<Item = StreamEvent> + Send>> {
     Box::pin(stream! {
         let total_start = std::time::Instant::now();
@@ -297,6 +297,13 @@ pub fn process_streaming(
             current_type: "indexing".into(),
         };
 
+        // One router serves every batch, built from the precomputed units
+        // so the content is not parsed again
+        let mut router = GeometryRouter::with_scale_and_rtc(prepared.unit_scale, prepared.rtc_offset);
+        router.set_plane_angle_unit(prepared.plane_angle_unit);
+        router.set_tessellation(tessellation);
+        let router = Arc::new(router);
+
         let mut total_processed = 0;
         let mut all_meshes: Vec<MeshData> = Vec::new();
         let mut total_vertices = 0usize;
@@ -339,14 +346,13 @@ pub fn process_streaming(
                 let index_bg = prepared.entity_index.clone();
                 let void_bg = prepared.void_index.clone();
                 let style_bg = prepared.style_index.clone();
-                let unit_scale = prepared.unit_scale;
-                let rtc_offset = prepared.rtc_offset;
+                let router_bg = router.clone();
                 let tx_clone = tx.clone();
 
                 // Spawn batch processing task
                 tokio::spawn(async move {
                     let result = tokio::task::spawn_blocking(move || {
-                        process_batch(chunk_vec, content_bg, index_bg, style_bg, void_bg, unit_scale, rtc_offset)
+                        process_batch(chunk_vec, content_bg, index_bg, style_bg, void_bg, router_bg)
                     }).await;
 
                     let batch_result = match result {
@@ -413,7 +419,10 @@ pub fn process_streaming(
         let total_time = total_start.elapsed();
 
         // Generate cache key for the complete result
-        let cache_key = DiskCache::generate_key(prepared.content.as_bytes());
+        let cache_key = DiskCache::generate_key(
+            prepared.content.as_bytes(),
+            &[("opening_filter", OpeningFilterMode::Default.cache_key_suffix())],
+        );
 
         yield StreamEvent::Complete {
             stats: ProcessingStats {
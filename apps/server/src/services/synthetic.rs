@@ -0,0 +1,407 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Parameterized synthetic IFC4 models for load and regression testing.
+//!
+//! Generates a building with `storeys` levels, each holding a row of
+//! `walls_per_storey` extruded walls. Every wall gets `openings_per_wall`
+//! voiding openings, and the first `boolean_ops` walls are clipped by a
+//! sloped half-space. The output is deterministic, so timings and mesh
+//! counts can be compared across runs without shipping real fixtures.
+
+use crate::types::SyntheticCounts;
+use serde::Deserialize;
+use std::fmt::Write;
+
+/// Upper bound on generated walls, keeps requests from exhausting memory.
+pub const MAX_SYNTHETIC_WALLS: u64 = 200_000;
+
+/// Shape of a synthetic model.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SyntheticSpec {
+    pub storeys: u32,
+    pub walls_per_storey: u32,
+    pub openings_per_wall: u32,
+    /// Number of walls (counted across all storeys) clipped by a half-space.
+    pub boolean_ops: u32,
+    /// Wall length in metres.
+    pub wall_length: f64,
+    /// Storey height (and wall height) in metres.
+    pub storey_height: f64,
+}
+
+impl Default for SyntheticSpec {
+    fn default() -> Self {
+        Self {
+            storeys: 3,
+            walls_per_storey: 10,
+            openings_per_wall: 2,
+            boolean_ops: 4,
+            wall_length: 6.0,
+            storey_height: 3.0,
+        }
+    }
+}
+
+impl SyntheticSpec {
+    /// Total number of walls.
+    pub fn wall_count(&self) -> u64 {
+        self.storeys as u64 * self.walls_per_storey as u64
+    }
+
+    /// Reject specs that are empty, degenerate or too large.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.wall_count() == 0 {
+            return Err("storeys and walls_per_storey must be at least 1".to_string());
+        }
+        if self.wall_count() > MAX_SYNTHETIC_WALLS {
+            return Err(format!(
+                "{} walls requested, at most {} allowed",
+                self.wall_count(),
+                MAX_SYNTHETIC_WALLS
+            ));
+        }
+        if self.wall_count() * self.openings_per_wall as u64 > MAX_SYNTHETIC_WALLS * 10 {
+            return Err("too many openings requested".to_string());
+        }
+        let valid = |v: f64| v.is_finite() && v > 0.0;
+        if !valid(self.wall_length) || !valid(self.storey_height) {
+            return Err("wall_length and storey_height must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Generated STEP content plus the express IDs needed to check results.
+#[derive(Debug, Clone)]
+pub struct SyntheticModel {
+    pub content: String,
+    pub counts: SyntheticCounts,
+    /// Express IDs of the generated walls, each expected to produce a mesh.
+    pub wall_ids: Vec<u32>,
+}
+
+/// Wall thickness in metres.
+const WALL_THICKNESS: f64 = 0.2;
+/// Spacing between parallel walls in metres.
+const WALL_SPACING: f64 = 3.0;
+
+/// Generate a synthetic model. Call [`SyntheticSpec::validate`] first.
+pub fn generate_synthetic_ifc(spec: &SyntheticSpec) -> SyntheticModel {
+    let mut w = StepWriter::default();
+    let mut counts = SyntheticCounts {
+        storeys: spec.storeys,
+        ..Default::default()
+    };
+    let mut wall_ids = Vec::with_capacity(spec.wall_count() as usize);
+
+    // Shared context, units and placements
+    let origin = w.add("IFCCARTESIANPOINT((0.,0.,0.))");
+    let z_dir = w.add("IFCDIRECTION((0.,0.,1.))");
+    let x_dir = w.add("IFCDIRECTION((1.,0.,0.))");
+    let world = w.add(format!("IFCAXIS2PLACEMENT3D(#{origin},#{z_dir},#{x_dir})"));
+    let context = w.add(format!(
+        "IFCGEOMETRICREPRESENTATIONCONTEXT($,'Model',3,1.E-05,#{world},$)"
+    ));
+    let unit = w.add("IFCSIUNIT(*,.LENGTHUNIT.,$,.METRE.)");
+    let units = w.add(format!("IFCUNITASSIGNMENT((#{unit}))"));
+    let project = w.add(format!(
+        "IFCPROJECT('{}',$,'Synthetic',$,$,$,$,(#{context}),#{units})",
+        w.guid()
+    ));
+
+    let site_placement = w.add(format!("IFCLOCALPLACEMENT($,#{world})"));
+    let site = w.add(format!(
+        "IFCSITE('{}',$,'Site',$,$,#{site_placement},$,$,.ELEMENT.,$,$,$,$,$)",
+        w.guid()
+    ));
+    let building_placement = w.add(format!("IFCLOCALPLACEMENT(#{site_placement},#{world})"));
+    let building = w.add(format!(
+        "IFCBUILDING('{}',$,'Building',$,$,#{building_placement},$,$,.ELEMENT.,$,$,$)",
+        w.guid()
+    ));
+    w.aggregate(project, &[site]);
+    w.aggregate(site, &[building]);
+
+    let mut storeys = Vec::with_capacity(spec.storeys as usize);
+    for level in 0..spec.storeys {
+        let elevation = level as f64 * spec.storey_height;
+        let point = w.add(format!("IFCCARTESIANPOINT((0.,0.,{}))", real(elevation)));
+        let axis = w.add(format!("IFCAXIS2PLACEMENT3D(#{point},$,$)"));
+        let placement = w.add(format!("IFCLOCALPLACEMENT(#{building_placement},#{axis})"));
+        let storey = w.add(format!(
+            "IFCBUILDINGSTOREY('{}',$,'Level {}',$,$,#{placement},$,$,.ELEMENT.,{})",
+            w.guid(),
+            level,
+            real(elevation)
+        ));
+        storeys.push(storey);
+
+        let mut contained = Vec::with_capacity(spec.walls_per_storey as usize);
+        for index in 0..spec.walls_per_storey {
+            let clipped = counts.boolean_ops < spec.boolean_ops;
+            let wall = add_wall(&mut w, spec, context, placement, index, clipped);
+            if clipped {
+                counts.boolean_ops += 1;
+            }
+            counts.openings += add_openings(&mut w, spec, context, wall.placement, wall.id);
+            counts.walls += 1;
+            contained.push(wall.id);
+            wall_ids.push(wall.id);
+        }
+        w.contain(storey, &contained);
+    }
+    w.aggregate(building, &storeys);
+
+    counts.entities = w.next_id - 1;
+    SyntheticModel {
+        content: w.finish(),
+        counts,
+        wall_ids,
+    }
+}
+
+struct WallIds {
+    id: u32,
+    placement: u32,
+}
+
+/// Add one wall of `spec.wall_length` along X, offset in Y by its index.
+fn add_wall(
+    w: &mut StepWriter,
+    spec: &SyntheticSpec,
+    context: u32,
+    storey_placement: u32,
+    index: u32,
+    clipped: bool,
+) -> WallIds {
+    let y = index as f64 * WALL_SPACING;
+    let point = w.add(format!("IFCCARTESIANPOINT((0.,{},0.))", real(y)));
+    let axis = w.add(format!("IFCAXIS2PLACEMENT3D(#{point},$,$)"));
+    let placement = w.add(format!("IFCLOCALPLACEMENT(#{storey_placement},#{axis})"));
+
+    let solid = w.extruded_box(
+        spec.wall_length * 0.5,
+        spec.wall_length,
+        WALL_THICKNESS,
+        spec.storey_height,
+    );
+    let (item, representation_type) = if clipped {
+        // Sloped cut through the top quarter of the wall
+        let plane_point = w.add(format!(
+            "IFCCARTESIANPOINT((0.,0.,{}))",
+            real(spec.storey_height * 0.75)
+        ));
+        let normal = w.add("IFCDIRECTION((0.3,0.,1.))");
+        let plane_axis = w.add(format!("IFCAXIS2PLACEMENT3D(#{plane_point},#{normal},$)"));
+        let plane = w.add(format!("IFCPLANE(#{plane_axis})"));
+        let half_space = w.add(format!("IFCHALFSPACESOLID(#{plane},.F.)"));
+        let clip = w.add(format!(
+            "IFCBOOLEANCLIPPINGRESULT(.DIFFERENCE.,#{solid},#{half_space})"
+        ));
+        (clip, "Clipping")
+    } else {
+        (solid, "SweptSolid")
+    };
+    let shape = w.body(context, item, representation_type);
+    let id = w.add(format!(
+        "IFCWALL('{}',$,'Wall {}',$,$,#{placement},#{shape},$,.STANDARD.)",
+        w.guid(),
+        index
+    ));
+    WallIds { id, placement }
+}
+
+/// Add evenly spaced window-sized openings voiding `wall`. Returns the count.
+fn add_openings(
+    w: &mut StepWriter,
+    spec: &SyntheticSpec,
+    context: u32,
+    wall_placement: u32,
+    wall: u32,
+) -> u32 {
+    let n = spec.openings_per_wall;
+    if n == 0 {
+        return 0;
+    }
+    let pitch = spec.wall_length / (n as f64 + 1.0);
+    let width = (pitch * 0.6).min(1.2);
+    let height = spec.storey_height * 0.4;
+    let sill = spec.storey_height * 0.3;
+
+    for k in 1..=n {
+        let x = pitch * k as f64;
+        let point = w.add(format!(
+            "IFCCARTESIANPOINT(({},0.,{}))",
+            real(x),
+            real(sill)
+        ));
+        let axis = w.add(format!("IFCAXIS2PLACEMENT3D(#{point},$,$)"));
+        let placement = w.add(format!("IFCLOCALPLACEMENT(#{wall_placement},#{axis})"));
+        let solid = w.extruded_box(0.0, width, WALL_THICKNESS + 0.2, height);
+        let shape = w.body(context, solid, "SweptSolid");
+        let opening = w.add(format!(
+            "IFCOPENINGELEMENT('{}',$,'Opening',$,$,#{placement},#{shape},$,.OPENING.)",
+            w.guid()
+        ));
+        w.add(format!(
+            "IFCRELVOIDSELEMENT('{}',$,$,$,#{wall},#{opening})",
+            w.guid()
+        ));
+    }
+    n
+}
+
+/// Sequential STEP instance writer.
+struct StepWriter {
+    data: String,
+    next_id: u32,
+}
+
+impl Default for StepWriter {
+    fn default() -> Self {
+        Self {
+            data: String::new(),
+            next_id: 1,
+        }
+    }
+}
+
+impl StepWriter {
+    /// Append `#id=<body>;` and return the id.
+    fn add(&mut self, body: impl AsRef<str>) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let _ = writeln!(self.data, "#{}={};", id, body.as_ref());
+        id
+    }
+
+    /// Deterministic 22-character IFC GlobalId, derived from the id the next
+    /// `add` will assign (call it while formatting that entity).
+    fn guid(&self) -> String {
+        const ALPHABET: &[u8; 64] =
+            b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz_$";
+        let mut n = self.next_id;
+        let mut out = [b'0'; 22];
+        for slot in out.iter_mut().rev() {
+            *slot = ALPHABET[(n % 64) as usize];
+            n /= 64;
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// Rectangle of `x_dim` × `y_dim` centred at (cx, 0), extruded `depth` up Z.
+    fn extruded_box(&mut self, cx: f64, x_dim: f64, y_dim: f64, depth: f64) -> u32 {
+        let centre = self.add(format!("IFCCARTESIANPOINT(({},0.))", real(cx)));
+        let position = self.add(format!("IFCAXIS2PLACEMENT2D(#{centre},$)"));
+        let profile = self.add(format!(
+            "IFCRECTANGLEPROFILEDEF(.AREA.,$,#{position},{},{})",
+            real(x_dim),
+            real(y_dim)
+        ));
+        let base = self.add("IFCCARTESIANPOINT((0.,0.,0.))");
+        let solid_position = self.add(format!("IFCAXIS2PLACEMENT3D(#{base},$,$)"));
+        let up = self.add("IFCDIRECTION((0.,0.,1.))");
+        self.add(format!(
+            "IFCEXTRUDEDAREASOLID(#{profile},#{solid_position},#{up},{})",
+            real(depth)
+        ))
+    }
+
+    /// Body shape representation wrapping a single item.
+    fn body(&mut self, context: u32, item: u32, representation_type: &str) -> u32 {
+        let representation = self.add(format!(
+            "IFCSHAPEREPRESENTATION(#{context},'Body','{representation_type}',(#{item}))"
+        ));
+        self.add(format!(
+            "IFCPRODUCTDEFINITIONSHAPE($,$,(#{representation}))"
+        ))
+    }
+
+    fn aggregate(&mut self, whole: u32, parts: &[u32]) {
+        let guid = self.guid();
+        self.add(format!(
+            "IFCRELAGGREGATES('{guid}',$,$,$,#{whole},({}))",
+            id_list(parts)
+        ));
+    }
+
+    fn contain(&mut self, structure: u32, elements: &[u32]) {
+        let guid = self.guid();
+        self.add(format!(
+            "IFCRELCONTAINEDINSPATIALSTRUCTURE('{guid}',$,$,$,({}),#{structure})",
+            id_list(elements)
+        ));
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::with_capacity(self.data.len() + 512);
+        out.push_str("ISO-10303-21;\nHEADER;\n");
+        out.push_str("FILE_DESCRIPTION(('ViewDefinition [DesignTransferView]'),'2;1');\n");
+        out.push_str(
+            "FILE_NAME('synthetic.ifc','1970-01-01T00:00:00',(''),(''),'ifc-lite','ifc-lite synthetic','');\n",
+        );
+        out.push_str("FILE_SCHEMA(('IFC4'));\nENDSEC;\nDATA;\n");
+        out.push_str(&self.data);
+        out.push_str("ENDSEC;\nEND-ISO-10303-21;\n");
+        out
+    }
+}
+
+fn id_list(ids: &[u32]) -> String {
+    ids.iter()
+        .map(|id| format!("#{id}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// STEP REAL literal (always has a decimal point).
+fn real(v: f64) -> String {
+    let s = format!("{v}");
+    if s.contains('.') {
+        s
+    } else {
+        s + "."
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{process_geometry_filtered, OpeningFilterMode};
+
+    #[test]
+    fn test_synthetic_model_processes_every_wall() {
+        let spec = SyntheticSpec {
+            storeys: 2,
+            walls_per_storey: 3,
+            openings_per_wall: 2,
+            boolean_ops: 2,
+            ..Default::default()
+        };
+        spec.validate().unwrap();
+        let model = generate_synthetic_ifc(&spec);
+        assert_eq!(model.counts.walls, 6);
+        assert_eq!(model.counts.openings, 12);
+        assert_eq!(model.counts.boolean_ops, 2);
+        assert_eq!(model.wall_ids.len(), 6);
+
+        let result = process_geometry_filtered(&model.content, OpeningFilterMode::Default);
+        for id in &model.wall_ids {
+            assert!(
+                result.meshes.iter().any(|m| m.express_id == *id),
+                "wall #{} produced no mesh",
+                id
+            );
+        }
+
+        assert!(SyntheticSpec {
+            storeys: 0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,305 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Model validation: schema conformance, geometry health and custom
+//! property requirements, combined into one [`ValidationReport`].
+
+use crate::services::{process_geometry_filtered, OpeningFilterMode};
+use crate::types::{Severity, ValidationIssue, ValidationReport, ValidationSection};
+use ifc_lite_core::{
+    build_entity_index, has_geometry_by_name, AttributeValue, EntityDecoder, EntityScanner,
+    IfcType, PropertySchema, PropertySchemaIssue, PropertySchemaRegistry,
+};
+use rustc_hash::FxHashSet;
+use std::time::Instant;
+
+fn issue(
+    severity: Severity,
+    code: &str,
+    message: String,
+    express_id: Option<u32>,
+) -> ValidationIssue {
+    ValidationIssue {
+        severity,
+        code: code.to_string(),
+        message,
+        express_id,
+    }
+}
+
+/// Run all validators over `content`.
+pub fn validate_all(
+    content: &str,
+    report_id: String,
+    property_schemas: &[PropertySchema],
+) -> ValidationReport {
+    let start = Instant::now();
+
+    let schema = validate_schema(content);
+    let geometry = validate_geometry(content);
+    let properties = validate_properties(content, property_schemas);
+
+    ValidationReport {
+        report_id,
+        passed: schema.error_count + geometry.error_count + properties.error_count == 0,
+        schema,
+        geometry,
+        properties,
+        duration_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+/// Check entity types, duplicate IDs, decodability and reference targets.
+pub fn validate_schema(content: &str) -> ValidationSection {
+    let mut section = ValidationSection::default();
+
+    let mut ids = FxHashSet::default();
+    let mut entities = Vec::new();
+    let mut scanner = EntityScanner::new(content);
+    while let Some((id, type_name, _, _)) = scanner.next_entity() {
+        if !ids.insert(id) {
+            section.push(issue(
+                Severity::Error,
+                "DUPLICATE_ID",
+                format!("Entity #{} is defined more than once", id),
+                Some(id),
+            ));
+            continue;
+        }
+        if matches!(IfcType::from_str(type_name), IfcType::Unknown(_)) {
+            section.push(issue(
+                Severity::Warning,
+                "UNKNOWN_ENTITY_TYPE",
+                format!("{} is not part of the supported IFC schemas", type_name),
+                Some(id),
+            ));
+        }
+        entities.push(id);
+    }
+    section.checked = entities.len();
+
+    let mut decoder = EntityDecoder::with_index(content, build_entity_index(content));
+    for id in entities {
+        match decoder.decode_by_id(id) {
+            Ok(entity) => {
+                let mut missing = Vec::new();
+                for attr in &entity.attributes {
+                    collect_missing_refs(attr, &ids, &mut missing);
+                }
+                for target in missing {
+                    section.push(issue(
+                        Severity::Error,
+                        "DANGLING_REFERENCE",
+                        format!("#{} references missing entity #{}", id, target),
+                        Some(id),
+                    ));
+                }
+            }
+            Err(e) => section.push(issue(
+                Severity::Error,
+                "DECODE_FAILED",
+                format!("Entity #{} could not be decoded: {}", id, e),
+                Some(id),
+            )),
+        }
+    }
+
+    section
+}
+
+fn collect_missing_refs(attr: &AttributeValue, ids: &FxHashSet<u32>, missing: &mut Vec<u32>) {
+    match attr {
+        AttributeValue::EntityRef(id) if !ids.contains(id) => missing.push(*id),
+        AttributeValue::List(items) => {
+            for item in items {
+                collect_missing_refs(item, ids, missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Process geometry and report elements that failed to produce a valid mesh.
+pub fn validate_geometry(content: &str) -> ValidationSection {
+    let mut section = ValidationSection::default();
+    let result = process_geometry_filtered(content, OpeningFilterMode::Default);
+
+    let mut meshed = FxHashSet::default();
+    for mesh in &result.meshes {
+        meshed.insert(mesh.express_id);
+        let vertex_count = (mesh.positions.len() / 3) as u32;
+        if mesh.positions.iter().any(|v| !v.is_finite()) {
+            section.push(issue(
+                Severity::Error,
+                "NON_FINITE_VERTEX",
+                format!(
+                    "{} #{} has NaN or infinite vertex positions",
+                    mesh.ifc_type, mesh.express_id
+                ),
+                Some(mesh.express_id),
+            ));
+        }
+        if mesh.indices.iter().any(|&i| i >= vertex_count) {
+            section.push(issue(
+                Severity::Error,
+                "INVALID_INDEX",
+                format!(
+                    "{} #{} has triangle indices out of range",
+                    mesh.ifc_type, mesh.express_id
+                ),
+                Some(mesh.express_id),
+            ));
+        }
+        if mesh.indices.len() < 3 {
+            section.push(issue(
+                Severity::Warning,
+                "EMPTY_MESH",
+                format!(
+                    "{} #{} produced a mesh without triangles",
+                    mesh.ifc_type, mesh.express_id
+                ),
+                Some(mesh.express_id),
+            ));
+        }
+    }
+
+    // Elements with a body representation that produced nothing at all
+    let mut decoder = EntityDecoder::with_index(content, build_entity_index(content));
+    let mut scanner = EntityScanner::new(content);
+    while let Some((id, type_name, _, _)) = scanner.next_entity() {
+        if !has_geometry_by_name(type_name) {
+            continue;
+        }
+        section.checked += 1;
+        if meshed.contains(&id) {
+            continue;
+        }
+        // IfcProduct.Representation (attribute 6)
+        let has_representation = decoder
+            .decode_by_id(id)
+            .map(|e| e.get(6).is_some_and(|a| !a.is_null()))
+            .unwrap_or(false);
+        if has_representation {
+            section.push(issue(
+                Severity::Warning,
+                "MISSING_GEOMETRY",
+                format!(
+                    "#{} ({}) has a representation but produced no geometry",
+                    id, type_name
+                ),
+                Some(id),
+            ));
+        }
+    }
+
+    section
+}
+
+/// Check every IfcPropertySet against the given property requirements.
+pub fn validate_properties(
+    content: &str,
+    property_schemas: &[PropertySchema],
+) -> ValidationSection {
+    let mut section = ValidationSection::default();
+    if property_schemas.is_empty() {
+        return section;
+    }
+
+    let mut registry = PropertySchemaRegistry::new();
+    for schema in property_schemas {
+        registry.register(schema.clone());
+    }
+
+    let mut decoder = EntityDecoder::with_index(content, build_entity_index(content));
+    let mut scanner = EntityScanner::new(content);
+    while let Some((id, type_name, _, _)) = scanner.next_entity() {
+        if type_name != "IFCPROPERTYSET" {
+            continue;
+        }
+        section.checked += 1;
+        let Ok(pset) = decoder.decode_by_id(id) else {
+            continue;
+        };
+        let warnings = match registry.validate_property_set(&pset, &mut decoder) {
+            Ok(warnings) => warnings,
+            Err(_) => continue,
+        };
+        for w in warnings {
+            let (severity, code, detail) = match w.issue {
+                PropertySchemaIssue::UnknownPropertySet { suggestion } => (
+                    Severity::Warning,
+                    "UNKNOWN_PROPERTY_SET",
+                    format!("did you mean '{}'?", suggestion),
+                ),
+                PropertySchemaIssue::UnknownProperty { suggestion } => (
+                    Severity::Warning,
+                    "UNKNOWN_PROPERTY",
+                    match suggestion {
+                        Some(s) => format!("not defined; did you mean '{}'?", s),
+                        None => "not defined".to_string(),
+                    },
+                ),
+                PropertySchemaIssue::WrongType { expected, found } => (
+                    Severity::Error,
+                    "WRONG_PROPERTY_TYPE",
+                    format!("expected {}, found {}", expected, found),
+                ),
+                PropertySchemaIssue::ValueNotAllowed { value } => (
+                    Severity::Error,
+                    "VALUE_NOT_ALLOWED",
+                    format!("value '{}' is not allowed", value),
+                ),
+            };
+            let subject = if w.property.is_empty() {
+                w.property_set
+            } else {
+                format!("{}.{}", w.property_set, w.property)
+            };
+            section.push(issue(
+                severity,
+                code,
+                format!("{}: {}", subject, detail),
+                Some(w.property_set_id),
+            ));
+        }
+    }
+
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_validation_reports_dangling_and_duplicate_ids() {
+        let content = "\
+#1=IFCCARTESIANPOINT((0.,0.,0.));
+#2=IFCAXIS2PLACEMENT3D(#1,#99,$);
+#1=IFCCARTESIANPOINT((1.,0.,0.));
+";
+        let section = validate_schema(content);
+        let codes: Vec<&str> = section.issues.iter().map(|i| i.code.as_str()).collect();
+        assert!(codes.contains(&"DUPLICATE_ID"));
+        assert!(codes.contains(&"DANGLING_REFERENCE"));
+        assert_eq!(section.checked, 2);
+        assert_eq!(section.error_count, 2);
+    }
+
+    #[test]
+    fn test_property_validation_uses_schemas() {
+        let content = "\
+#1=IFCPROPERTYSINGLEVALUE('Finish',$,IFCLABEL('Wallpaper'),$);
+#2=IFCPROPERTYSET('guid',$,'ACME_Wall',$,(#1));
+";
+        let schemas =
+            vec![PropertySchema::new("ACME_Wall", "Finish")
+                .with_allowed_values(["Paint", "Plaster"])];
+        let section = validate_properties(content, &schemas);
+        assert_eq!(section.checked, 1);
+        assert_eq!(section.error_count, 1);
+        assert_eq!(section.issues[0].code, "VALUE_NOT_ALLOWED");
+        assert!(validate_properties(content, &[]).issues.is_empty());
+    }
+}
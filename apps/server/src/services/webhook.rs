@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Webhook delivery for asynchronous validation reports.
+
+use crate::error::ApiError;
+use crate::types::ValidationReport;
+use reqwest::Url;
+use std::time::Duration;
+
+/// Delivery attempts before giving up.
+const MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubled for each further attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Parse a webhook URL and check it against the configured host allowlist.
+///
+/// Delivery is disabled (every URL rejected) when the allowlist is empty, so
+/// the server cannot be used to make requests to arbitrary internal hosts.
+pub fn check_webhook_url(url: &str, allowed_hosts: &[String]) -> Result<Url, ApiError> {
+    if allowed_hosts.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Webhook delivery is disabled on this server (WEBHOOK_ALLOWED_HOSTS is not set)".into(),
+        ));
+    }
+
+    let parsed =
+        Url::parse(url).map_err(|e| ApiError::BadRequest(format!("Invalid webhook_url: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ApiError::BadRequest(
+            "webhook_url must use http or https".into(),
+        ));
+    }
+
+    let host = parsed.host_str().unwrap_or_default().to_ascii_lowercase();
+    if !allowed_hosts.contains(&host) {
+        return Err(ApiError::BadRequest(format!(
+            "Webhook host '{}' is not in WEBHOOK_ALLOWED_HOSTS",
+            host
+        )));
+    }
+
+    Ok(parsed)
+}
+
+/// POST a report to `url`, retrying with exponential backoff on failure.
+pub async fn deliver_report(url: Url, report: &ValidationReport) -> Result<(), String> {
+    // Redirects are not followed: a 3xx from an allowed host could otherwise
+    // forward the report to a host outside WEBHOOK_ALLOWED_HOSTS
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        match client.post(url.clone()).json(report).send().await {
+            Ok(response) if response.status().is_success() => {
+                tracing::info!(
+                    report_id = %report.report_id,
+                    attempt = attempt,
+                    "Validation report delivered"
+                );
+                return Ok(());
+            }
+            Ok(response) => last_error = format!("webhook responded with {}", response.status()),
+            Err(e) => last_error = e.to_string(),
+        }
+
+        tracing::warn!(
+            report_id = %report.report_id,
+            attempt = attempt,
+            error = %last_error,
+            "Validation report delivery failed"
+        );
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_url_allowlist() {
+        let allowed = vec!["ci.example.com".to_string()];
+
+        assert!(check_webhook_url("https://ci.example.com/hooks/ifc", &allowed).is_ok());
+        assert!(check_webhook_url("https://CI.example.com/hooks", &allowed).is_ok());
+        assert!(check_webhook_url("https://evil.example.com/", &allowed).is_err());
+        assert!(check_webhook_url("ftp://ci.example.com/", &allowed).is_err());
+        assert!(check_webhook_url("not a url", &allowed).is_err());
+        assert!(check_webhook_url("https://ci.example.com/", &[]).is_err());
+    }
+}
@@ -5,9 +5,21 @@
 //! Type definitions for API requests and responses.
 
 mod mesh;
+mod picking;
 mod response;
+mod synthetic;
+mod validation;
 
 pub use mesh::MeshData;
+pub use picking::{
+    AabbQueryElement, AabbQueryRequest, AabbQueryResponse, RaycastHit, RaycastRequest,
+    RaycastResponse,
+};
 pub use response::{
     CoordinateInfo, MetadataResponse, ModelMetadata, ParseResponse, ProcessingStats, StreamEvent,
 };
+pub use synthetic::{SyntheticCounts, SyntheticRunResponse};
+pub use validation::{
+    Severity, ValidationAccepted, ValidationIssue, ValidationReport, ValidationSection,
+    ValidateOptions,
+};
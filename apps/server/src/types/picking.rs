@@ -0,0 +1,62 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Picking and spatial query types.
+//!
+//! Coordinates are in the same space as the mesh positions returned by the
+//! parse endpoints (see `mesh_coordinate_space` in the parse metadata).
+
+use serde::{Deserialize, Serialize};
+
+/// Body of `POST /api/v1/models/:key/raycast`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RaycastRequest {
+    pub origin: [f32; 3],
+    /// Ray direction; does not need to be normalized.
+    pub direction: [f32; 3],
+    /// Ignore hits further than this distance.
+    #[serde(default)]
+    pub max_distance: Option<f32>,
+}
+
+/// Closest element hit by a ray.
+#[derive(Debug, Clone, Serialize)]
+pub struct RaycastHit {
+    pub express_id: u32,
+    pub ifc_type: String,
+    /// Distance along the normalized ray direction.
+    pub distance: f32,
+    pub point: [f32; 3],
+    /// Normal of the hit triangle, facing the ray origin.
+    pub normal: [f32; 3],
+}
+
+/// Response of `POST /api/v1/models/:key/raycast`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RaycastResponse {
+    /// `None` when the ray misses the model.
+    pub hit: Option<RaycastHit>,
+}
+
+/// Body of `POST /api/v1/models/:key/query/aabb`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AabbQueryRequest {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+/// Element touching the query box.
+#[derive(Debug, Clone, Serialize)]
+pub struct AabbQueryElement {
+    pub express_id: u32,
+    pub ifc_type: String,
+}
+
+/// Response of `POST /api/v1/models/:key/query/aabb`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AabbQueryResponse {
+    /// Elements with at least one triangle whose bounds overlap the box,
+    /// sorted by express ID.
+    pub elements: Vec<AabbQueryElement>,
+}
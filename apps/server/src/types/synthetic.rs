@@ -0,0 +1,36 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Synthetic model run types.
+
+use super::ProcessingStats;
+use serde::Serialize;
+
+/// What a generated synthetic model contains.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyntheticCounts {
+    pub storeys: u32,
+    pub walls: u32,
+    pub openings: u32,
+    pub boolean_ops: u32,
+    /// Total STEP entity instances.
+    pub entities: u32,
+}
+
+/// Response of `POST /api/v1/admin/synthetic`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyntheticRunResponse {
+    pub counts: SyntheticCounts,
+    /// Size of the generated IFC file.
+    pub content_bytes: usize,
+    /// Time spent generating the model (ms).
+    pub generate_time_ms: u64,
+    pub total_meshes: usize,
+    /// Processing statistics of the geometry run.
+    pub stats: ProcessingStats,
+    /// Walls that produced no mesh (expected to be empty).
+    pub missing_walls: Vec<u32>,
+    /// Whether every generated wall produced geometry.
+    pub passed: bool,
+}
@@ -0,0 +1,93 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Validation report types.
+
+use ifc_lite_core::PropertySchema;
+use serde::{Deserialize, Serialize};
+
+/// Options for `POST /api/v1/validate/all`, sent as the `options` multipart field.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ValidateOptions {
+    /// Deliver the report to this URL instead of returning it in the response.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Company-standard property requirements checked against every IfcPropertySet.
+    #[serde(default)]
+    pub property_schemas: Vec<PropertySchema>,
+}
+
+/// Issue severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single validation finding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Stable machine-readable code (e.g., "DANGLING_REFERENCE").
+    pub code: String,
+    pub message: String,
+    /// Entity the issue refers to, when applicable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub express_id: Option<u32>,
+}
+
+/// Findings of one validator.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationSection {
+    /// Number of items the validator inspected.
+    pub checked: usize,
+    pub error_count: usize,
+    pub warning_count: usize,
+    /// Issues, capped per section; counts above include the omitted ones.
+    pub issues: Vec<ValidationIssue>,
+    /// True when issues were omitted because of the cap.
+    pub truncated: bool,
+}
+
+impl ValidationSection {
+    /// Maximum issues kept per section.
+    pub const MAX_ISSUES: usize = 500;
+
+    /// Record an issue, updating counts and honouring [`Self::MAX_ISSUES`].
+    pub fn push(&mut self, issue: ValidationIssue) {
+        match issue.severity {
+            Severity::Error => self.error_count += 1,
+            Severity::Warning => self.warning_count += 1,
+        }
+        if self.issues.len() < Self::MAX_ISSUES {
+            self.issues.push(issue);
+        } else {
+            self.truncated = true;
+        }
+    }
+}
+
+/// Combined schema, geometry and property validation report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Content hash of the validated file.
+    pub report_id: String,
+    /// True when no validator reported an error.
+    pub passed: bool,
+    pub schema: ValidationSection,
+    pub geometry: ValidationSection,
+    pub properties: ValidationSection,
+    /// Total validation time (ms).
+    pub duration_ms: u64,
+}
+
+/// Response when the report is delivered to a webhook.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationAccepted {
+    pub report_id: String,
+    /// Always "queued".
+    pub status: &'static str,
+    pub webhook_url: String,
+}
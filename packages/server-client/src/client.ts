@@ -21,6 +21,35 @@ import type {
 } from './types';
 import { decodeParquetGeometry, decodeOptimizedParquetGeometry, isParquetAvailable } from './parquet-decoder';
 
+type CacheKeyHook = NonNullable<ServerConfig['computeCacheKey']>;
+
+// `computeCacheKey` from @ifc-lite/wasm, loaded once for all clients
+let wasmCacheKey: Promise<CacheKeyHook | null> | null = null;
+
+/**
+ * Load the canonical cache key function from `@ifc-lite/wasm`, the default
+ * for clients without a `computeCacheKey` hook.
+ *
+ * @returns The function, or null when the package is not installed or its
+ * WASM cannot be loaded
+ */
+function loadWasmCacheKey(): Promise<CacheKeyHook | null> {
+  if (!wasmCacheKey) {
+    wasmCacheKey = (async (): Promise<CacheKeyHook | null> => {
+      try {
+        const wasm = await import('@ifc-lite/wasm');
+        // No-op when the app has already initialized the module
+        await wasm.default();
+        return (data, options) => wasm.computeCacheKey(new Uint8Array(data), options);
+      } catch {
+        console.log('[client] @ifc-lite/wasm not available, skipping cache checks before upload');
+        return null;
+      }
+    })();
+  }
+  return wasmCacheKey;
+}
+
 /**
  * Compress a file or ArrayBuffer using gzip compression.
  * Uses the browser's CompressionStream API for efficient compression.
@@ -35,21 +64,6 @@ async function compressGzip(file: File | ArrayBuffer): Promise<Blob> {
   return new Response(compressedStream).blob();
 }
 
-/**
- * Compute SHA-256 hash of a file or ArrayBuffer.
- * Used for cache key generation client-side to avoid uploading files that are already cached.
- *
- * @param file - File or ArrayBuffer to hash
- * @returns Hexadecimal SHA-256 hash string
- */
-async function computeFileHash(file: File | ArrayBuffer): Promise<string> {
-  const buffer = file instanceof File ? await file.arrayBuffer() : file;
-  const hashBuffer = await crypto.subtle.digest('SHA-256', buffer);
-  return Array.from(new Uint8Array(hashBuffer))
-    .map(b => b.toString(16).padStart(2, '0'))
-    .join('');
-}
-
 /**
  * Client for the IFC-Lite Server API.
  *
@@ -71,6 +85,7 @@ async function computeFileHash(file: File | ArrayBuffer): Promise<string> {
 export class IfcServerClient {
   private baseUrl: string;
   private timeout: number;
+  private computeCacheKey?: ServerConfig['computeCacheKey'];
 
   /**
    * Create a new IFC server client.
@@ -81,6 +96,22 @@ export class IfcServerClient {
     // Remove trailing slash from base URL
     this.baseUrl = config.baseUrl.replace(/\/$/, '');
     this.timeout = config.timeout ?? 300000; // 5 minutes default
+    this.computeCacheKey = config.computeCacheKey;
+  }
+
+  /**
+   * Compute the server cache key for a file with default processing options,
+   * using the `computeCacheKey` hook or else `@ifc-lite/wasm`. Null when
+   * neither is available.
+   * @private
+   */
+  private async cacheKeyFor(file: File | ArrayBuffer): Promise<string | null> {
+    const computeCacheKey = this.computeCacheKey ?? (await loadWasmCacheKey());
+    if (!computeCacheKey) {
+      return null;
+    }
+    const buffer = file instanceof File ? await file.arrayBuffer() : file;
+    return computeCacheKey(buffer, { opening_filter: 'default' });
   }
 
   /**
@@ -144,8 +175,9 @@ export class IfcServerClient {
    * This method provides ~15x smaller payload size compared to JSON,
    * which is critical for large IFC files over network connections.
    *
-   * **Cache-aware:** Computes file hash client-side and checks cache before uploading.
-   * If cached, skips upload entirely for much faster response.
+   * **Cache-aware:** Computes the cache key client-side (with `@ifc-lite/wasm` or the
+   * `computeCacheKey` hook) and checks the cache before uploading. If cached, skips
+   * upload entirely.
    *
    * **Requirements:** This method requires `parquet-wasm` and `apache-arrow`
    * to be installed as peer dependencies.
@@ -173,11 +205,14 @@ export class IfcServerClient {
       );
     }
 
-    // Step 1: Compute hash client-side (fast, ~50ms for large files)
+    // Step 1: Compute cache key client-side (fast, ~50ms for large files)
     const hashStart = performance.now();
-    const hash = await computeFileHash(file);
+    const hash = await this.cacheKeyFor(file);
+    if (hash === null) {
+      return this.uploadAndProcessParquet(file, null);
+    }
     const hashTime = performance.now() - hashStart;
-    console.log(`[client] Computed file hash in ${hashTime.toFixed(0)}ms: ${hash.substring(0, 16)}...`);
+    console.log(`[client] Computed cache key in ${hashTime.toFixed(0)}ms: ${hash.substring(0, 16)}...`);
 
     // Step 2: Check if already cached
     const cacheCheckStart = performance.now();
@@ -238,44 +273,41 @@ export class IfcServerClient {
     const fileSize = file instanceof File ? file.size : file.byteLength;
     const fileName = file instanceof File ? file.name : 'model.ifc';
 
-    // Step 1: Compute hash and check cache first (even for streaming)
-    const hashStart = performance.now();
-    const hash = await computeFileHash(file);
-    const hashTime = performance.now() - hashStart;
-    console.log(`[client] Stream: computed hash in ${hashTime.toFixed(0)}ms: ${hash.substring(0, 16)}...`);
-
-    // Step 2: Check if already cached
-    const cacheCheckStart = performance.now();
-    const cacheCheck = await fetch(`${this.baseUrl}/api/v1/cache/check/${hash}`, {
-      method: 'GET',
-      signal: AbortSignal.timeout(5000),
-    });
-    const cacheCheckTime = performance.now() - cacheCheckStart;
+    // Step 1: Compute cache key and check cache first (even for streaming)
+    const hash = await this.cacheKeyFor(file);
+    if (hash !== null) {
+      const cacheCheckStart = performance.now();
+      const cacheCheck = await fetch(`${this.baseUrl}/api/v1/cache/check/${hash}`, {
+        method: 'GET',
+        signal: AbortSignal.timeout(5000),
+      });
+      const cacheCheckTime = performance.now() - cacheCheckStart;
 
-    if (cacheCheck.ok) {
-      // CACHE HIT - fetch all geometry at once (much faster than re-parsing)
-      console.log(`[client] Stream: Cache HIT (check: ${cacheCheckTime.toFixed(0)}ms) - fetching cached geometry`);
+      if (cacheCheck.ok) {
+        // CACHE HIT - fetch all geometry at once (much faster than re-parsing)
+        console.log(`[client] Stream: Cache HIT (check: ${cacheCheckTime.toFixed(0)}ms) - fetching cached geometry`);
 
-      const cachedResult = await this.fetchCachedGeometry(hash);
+        const cachedResult = await this.fetchCachedGeometry(hash);
 
-      // Send all meshes as a single batch to the callback
-      const decodeStart = performance.now();
-      onBatch({
-        meshes: cachedResult.meshes,
-        batch_number: 1,
-        decode_time_ms: performance.now() - decodeStart,
-      });
+        // Send all meshes as a single batch to the callback
+        const decodeStart = performance.now();
+        onBatch({
+          meshes: cachedResult.meshes,
+          batch_number: 1,
+          decode_time_ms: performance.now() - decodeStart,
+        });
 
-      return {
-        cache_key: cachedResult.cache_key,
-        total_meshes: cachedResult.meshes.length,
-        stats: cachedResult.stats,
-        metadata: cachedResult.metadata,
-      };
+        return {
+          cache_key: cachedResult.cache_key,
+          total_meshes: cachedResult.meshes.length,
+          stats: cachedResult.stats,
+          metadata: cachedResult.metadata,
+        };
+      }
     }
 
     // CACHE MISS - use streaming for progressive rendering
-    console.log(`[client] Stream: Cache MISS (check: ${cacheCheckTime.toFixed(0)}ms) - starting stream for ${fileName} (${(fileSize / 1024 / 1024).toFixed(1)}MB)`);
+    console.log(`[client] Stream: starting stream for ${fileName} (${(fileSize / 1024 / 1024).toFixed(1)}MB)`);
 
     const formData = new FormData();
     formData.append('file', file instanceof File ? file : new Blob([file]), fileName);
@@ -428,7 +460,7 @@ export class IfcServerClient {
    * Upload file and process on server.
    * @private
    */
-  private async uploadAndProcessParquet(file: File | ArrayBuffer, hash: string): Promise<ParquetParseResponse> {
+  private async uploadAndProcessParquet(file: File | ArrayBuffer, hash: string | null): Promise<ParquetParseResponse> {
     const fileSize = file instanceof File ? file.size : file.byteLength;
     const fileName = file instanceof File ? file.name : 'model.ifc';
 
@@ -472,7 +504,7 @@ export class IfcServerClient {
     const metadata: ParquetMetadataHeader = JSON.parse(metadataHeader);
 
     // Verify hash matches (sanity check)
-    if (metadata.cache_key !== hash) {
+    if (hash !== null && metadata.cache_key !== hash) {
       console.warn(`[client] Cache key mismatch: expected ${hash.substring(0, 16)}..., got ${metadata.cache_key.substring(0, 16)}...`);
     }
 
@@ -853,7 +885,7 @@ export class IfcServerClient {
   /**
    * Retrieve a cached parse result by key.
    *
-   * @param key - Cache key (SHA256 hash of file content)
+   * @param key - Cache key (see `computeCacheKey`)
    * @returns Cached parse result, or null if not found
    *
    * @example
@@ -10,6 +10,13 @@ export interface ServerConfig {
   baseUrl: string;
   /** Request timeout in milliseconds (default: 300000 = 5 minutes) */
   timeout?: number;
+  /**
+   * Computes the canonical cache key for a file. Defaults to `computeCacheKey`
+   * from `@ifc-lite/wasm` when that package is installed. Keys must match the
+   * server's, so without either the client skips the cache check and always
+   * uploads; the server still serves repeat uploads from its cache.
+   */
+  computeCacheKey?: (data: ArrayBuffer, options: Record<string, string>) => string | Promise<string>;
 }
 
 /**
@@ -78,7 +85,7 @@ export interface ProcessingStats {
  * Full parse response with all meshes.
  */
 export interface ParseResponse {
-  /** Cache key for this result (SHA256 of file content) */
+  /** Canonical cache key for this result (content hash + options hash) */
   cache_key: string;
   /** All meshes extracted from the IFC file */
   meshes: MeshData[];
@@ -193,7 +200,7 @@ export interface StreamErrorEvent {
  * Metadata header from Parquet response (sent via X-IFC-Metadata header).
  */
 export interface ParquetMetadataHeader {
-  /** Cache key for this result (SHA256 of file content) */
+  /** Canonical cache key for this result (content hash + options hash) */
   cache_key: string;
   /** Model metadata */
   metadata: ModelMetadata;
@@ -218,7 +225,7 @@ export interface ParquetMetadataHeader {
  * Parquet parse response with decoded geometry.
  */
 export interface ParquetParseResponse {
-  /** Cache key for this result (SHA256 of file content) */
+  /** Canonical cache key for this result (content hash + options hash) */
   cache_key: string;
   /** All meshes extracted from the IFC file */
   meshes: MeshData[];
@@ -3,7 +3,8 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 /**
- * Minimal ambient type declarations for parquet-wasm and apache-arrow.
+ * Minimal ambient type declarations for parquet-wasm, @ifc-lite/wasm and
+ * apache-arrow.
  *
  * These cover only the API surface actually used in this package,
  * avoiding the need for @ts-ignore on every call site.
@@ -28,6 +29,15 @@ interface ParquetTable {
   intoIPCStream(): Uint8Array;
 }
 
+// ── @ifc-lite/wasm ──
+
+declare module '@ifc-lite/wasm' {
+  /** Initialize the WASM module; resolves at once if already initialized. */
+  export default function init(): Promise<unknown>;
+  /** Canonical cache key, identical to the server's for the same file and options. */
+  export function computeCacheKey(data: Uint8Array, options: Record<string, string>): string;
+}
+
 // ── apache-arrow ──
 
 declare module 'apache-arrow' {
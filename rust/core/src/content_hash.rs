@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Canonical cache keys
+//!
+//! One key derivation shared by the WASM bindings, the desktop app and the
+//! server, so a model processed by one frontend can be found in another's
+//! cache. A key combines three parts:
+//!
+//! - XXH3-128 of the raw file bytes (streamable, identical on every platform)
+//! - the parser version, so caches invalidate on upgrade
+//! - a hash of the processing options that affect the output
+//!
+//! Keys look like `<32 hex digits>-<16 hex digits>` and only contain
+//! `[0-9a-f-]`, so they are safe as file names and URL path segments.
+
+use xxhash_rust::xxh3::{xxh3_64, Xxh3};
+
+/// Version mixed into every cache key.
+pub const PARSER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Streaming content hasher for files read in chunks.
+#[derive(Clone)]
+pub struct ContentHasher {
+    state: Xxh3,
+}
+
+impl ContentHasher {
+    pub fn new() -> Self {
+        Self { state: Xxh3::new() }
+    }
+
+    /// Feed the next chunk of file content.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.state.update(chunk);
+    }
+
+    /// Hash of all content fed so far.
+    pub fn digest(&self) -> u128 {
+        self.state.digest128()
+    }
+
+    /// Cache key for the content fed so far (see [`cache_key`]).
+    pub fn cache_key(&self, options: &[(&str, &str)]) -> String {
+        format_key(self.digest(), options)
+    }
+}
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// XXH3-128 of `data`.
+pub fn content_hash(data: &[u8]) -> u128 {
+    xxhash_rust::xxh3::xxh3_128(data)
+}
+
+/// Hash of the parser version and processing options.
+///
+/// Options are `(name, value)` pairs; their order does not matter.
+pub fn options_hash(options: &[(&str, &str)]) -> u64 {
+    let mut sorted: Vec<_> = options.to_vec();
+    sorted.sort_unstable();
+
+    // Length-prefixed fields so ("ab", "c") and ("a", "bc") differ
+    let mut buf = Vec::with_capacity(64);
+    let mut push = |field: &str| {
+        buf.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        buf.extend_from_slice(field.as_bytes());
+    };
+    push(PARSER_VERSION);
+    for (name, value) in sorted {
+        push(name);
+        push(value);
+    }
+    xxh3_64(&buf)
+}
+
+/// Canonical cache key for file `data` processed with `options`.
+///
+/// # Example
+///
+/// ```
+/// use ifc_lite_core::cache_key;
+///
+/// let key = cache_key(b"ISO-10303-21;", &[("opening_filter", "default")]);
+/// assert_eq!(key.len(), 32 + 1 + 16);
+/// ```
+pub fn cache_key(data: &[u8], options: &[(&str, &str)]) -> String {
+    format_key(content_hash(data), options)
+}
+
+fn format_key(digest: u128, options: &[(&str, &str)]) -> String {
+    format!("{:032x}-{:016x}", digest, options_hash(options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let data: Vec<u8> = (0..10_000u32).flat_map(|i| i.to_le_bytes()).collect();
+        let mut hasher = ContentHasher::new();
+        for chunk in data.chunks(777) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.digest(), content_hash(&data));
+        assert_eq!(hasher.cache_key(&[]), cache_key(&data, &[]));
+
+        // Fixed value: keys must not drift between platforms or releases of xxh3
+        assert_eq!(content_hash(b""), 0x99aa06d3014798d86001c324468d497f);
+    }
+
+    #[test]
+    fn test_options_change_key_but_not_order() {
+        let data = b"#1=IFCWALL('guid',$,$,$,$,$,$,$,$);";
+        let a = cache_key(data, &[("opening_filter", "default"), ("lod", "2")]);
+        let b = cache_key(data, &[("lod", "2"), ("opening_filter", "default")]);
+        let c = cache_key(data, &[("opening_filter", "ignore_all"), ("lod", "2")]);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a[..32], c[..32]);
+        assert_ne!(options_hash(&[("ab", "c")]), options_hash(&[("a", "bc")]));
+        assert!(a.chars().all(|ch| ch.is_ascii_hexdigit() || ch == '-'));
+    }
+}
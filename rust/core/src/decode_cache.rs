@@ -0,0 +1,254 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Decoded-entity cache used by [`EntityDecoder`](crate::EntityDecoder).
+//!
+//! Unbounded by default. With the `decode-lru` feature (enabled by the WASM
+//! bindings) it becomes a bounded LRU: placement, profile and style chains keep
+//! re-resolving the same points, directions and placements, so a modest window
+//! of recently decoded entities captures most hits without holding the whole
+//! model in memory.
+
+use crate::schema_gen::DecodedEntity;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// Default entry limit with the `decode-lru` feature.
+#[cfg(feature = "decode-lru")]
+pub const DEFAULT_DECODE_CACHE_CAPACITY: Option<usize> = Some(1 << 16);
+/// Default entry limit without the `decode-lru` feature (unbounded).
+#[cfg(not(feature = "decode-lru"))]
+pub const DEFAULT_DECODE_CACHE_CAPACITY: Option<usize> = None;
+
+const NIL: u32 = u32::MAX;
+
+/// Hit/miss counters of a decode cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecodeCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl DecodeCacheStats {
+    /// Fraction of lookups served from the cache (0 when unused).
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+struct Slot {
+    id: u32,
+    entity: Arc<DecodedEntity>,
+    prev: u32,
+    next: u32,
+}
+
+/// Entity cache with optional LRU bound.
+///
+/// Slots form an intrusive doubly linked list (most recent at `head`), so
+/// lookups, inserts and evictions are O(1).
+pub struct DecodeCache {
+    map: FxHashMap<u32, u32>,
+    slots: Vec<Slot>,
+    head: u32,
+    tail: u32,
+    capacity: Option<usize>,
+    stats: DecodeCacheStats,
+}
+
+impl Default for DecodeCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_DECODE_CACHE_CAPACITY)
+    }
+}
+
+impl DecodeCache {
+    /// Create a cache holding at most `capacity` entities (`None` = unbounded).
+    pub fn with_capacity(capacity: Option<usize>) -> Self {
+        Self {
+            map: FxHashMap::default(),
+            slots: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            capacity: capacity.map(|c| c.max(1)),
+            stats: DecodeCacheStats::default(),
+        }
+    }
+
+    /// Look up an entity, marking it most recently used.
+    #[inline]
+    pub fn get(&mut self, id: u32) -> Option<&Arc<DecodedEntity>> {
+        let Some(&slot) = self.map.get(&id) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        self.stats.hits += 1;
+        if self.capacity.is_some() {
+            self.unlink(slot);
+            self.push_front(slot);
+        }
+        Some(&self.slots[slot as usize].entity)
+    }
+
+    /// Look up an entity without touching recency or metrics.
+    pub fn peek(&self, id: u32) -> Option<&Arc<DecodedEntity>> {
+        self.map
+            .get(&id)
+            .map(|&slot| &self.slots[slot as usize].entity)
+    }
+
+    /// Insert or replace an entity, evicting the least recently used if full.
+    pub fn insert(&mut self, id: u32, entity: Arc<DecodedEntity>) {
+        if let Some(&slot) = self.map.get(&id) {
+            self.slots[slot as usize].entity = entity;
+            if self.capacity.is_some() {
+                self.unlink(slot);
+                self.push_front(slot);
+            }
+            return;
+        }
+
+        let slot = match self.capacity {
+            Some(capacity) if self.map.len() >= capacity => {
+                // Reuse the least recently used slot
+                let slot = self.tail;
+                self.unlink(slot);
+                self.map.remove(&self.slots[slot as usize].id);
+                self.stats.evictions += 1;
+                let s = &mut self.slots[slot as usize];
+                s.id = id;
+                s.entity = entity;
+                slot
+            }
+            _ => {
+                self.slots.push(Slot {
+                    id,
+                    entity,
+                    prev: NIL,
+                    next: NIL,
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        self.map.insert(id, slot);
+        self.push_front(slot);
+    }
+
+    /// Reserve room for `additional` entries (clamped to the capacity).
+    pub fn reserve(&mut self, additional: usize) {
+        let additional = match self.capacity {
+            Some(capacity) => additional.min(capacity.saturating_sub(self.map.len())),
+            None => additional,
+        };
+        self.map.reserve(additional);
+        self.slots.reserve(additional);
+    }
+
+    /// Remove all entries (metrics are kept).
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.slots.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Number of cached entities.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Entry limit, `None` when unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Hit/miss counters since creation.
+    pub fn stats(&self) -> DecodeCacheStats {
+        self.stats
+    }
+
+    fn unlink(&mut self, slot: u32) {
+        let (prev, next) = {
+            let s = &self.slots[slot as usize];
+            (s.prev, s.next)
+        };
+        match prev {
+            NIL => self.head = next,
+            p => self.slots[p as usize].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            n => self.slots[n as usize].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, slot: u32) {
+        let old_head = self.head;
+        {
+            let s = &mut self.slots[slot as usize];
+            s.prev = NIL;
+            s.next = old_head;
+        }
+        match old_head {
+            NIL => self.tail = slot,
+            h => self.slots[h as usize].prev = slot,
+        }
+        self.head = slot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::IfcType;
+
+    fn entity(id: u32) -> Arc<DecodedEntity> {
+        Arc::new(DecodedEntity::new(
+            id,
+            IfcType::IfcCartesianPoint,
+            Vec::new(),
+        ))
+    }
+
+    #[test]
+    fn test_lru_eviction_and_stats() {
+        let mut cache = DecodeCache::with_capacity(Some(2));
+        cache.insert(1, entity(1));
+        cache.insert(2, entity(2));
+
+        // Touch 1 so 2 becomes least recently used
+        assert!(cache.get(1).is_some());
+        cache.insert(3, entity(3));
+
+        assert!(cache.get(2).is_none());
+        assert_eq!(cache.get(1).map(|e| e.id), Some(1));
+        assert_eq!(cache.get(3).map(|e| e.id), Some(3));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.stats(),
+            DecodeCacheStats {
+                hits: 3,
+                misses: 1,
+                evictions: 1
+            }
+        );
+        assert!((cache.stats().hit_rate() - 0.75).abs() < 1e-12);
+
+        let mut unbounded = DecodeCache::with_capacity(None);
+        (0..100).for_each(|id| unbounded.insert(id, entity(id)));
+        assert_eq!(unbounded.len(), 100);
+        assert_eq!(unbounded.stats().evictions, 0);
+    }
+}
@@ -6,86 +6,22 @@
 //!
 //! Lazily decode IFC entities from byte offsets without loading entire file into memory.
 
+use crate::decode_cache::{DecodeCache, DecodeCacheStats};
+use crate::entity_index::build_entity_index;
+pub use crate::entity_index::EntityIndex;
 use crate::error::{Error, Result};
-use crate::parser::parse_entity;
+use crate::parser::{parse_entity, Token};
 use crate::schema_gen::{AttributeValue, DecodedEntity};
+use crate::IfcType;
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 
-/// Pre-built entity index type
-pub type EntityIndex = FxHashMap<u32, (usize, usize)>;
-
-/// Build entity index from content - O(n) scan using SIMD-accelerated search
-/// Returns index mapping entity IDs to byte offsets
-#[inline]
-pub fn build_entity_index(content: &str) -> EntityIndex {
-    let bytes = content.as_bytes();
-    let len = bytes.len();
-
-    // Pre-allocate with estimated capacity (roughly 1 entity per 50 bytes)
-    let estimated_entities = len / 50;
-    let mut index = FxHashMap::with_capacity_and_hasher(estimated_entities, Default::default());
-
-    let mut pos = 0;
-
-    while pos < len {
-        // Find next '#' using SIMD-accelerated search
-        let remaining = &bytes[pos..];
-        let hash_offset = match memchr::memchr(b'#', remaining) {
-            Some(offset) => offset,
-            None => break,
-        };
-
-        let start = pos + hash_offset;
-        pos = start + 1;
-
-        // Parse entity ID (inline for speed)
-        let id_start = pos;
-        while pos < len && bytes[pos].is_ascii_digit() {
-            pos += 1;
-        }
-        let id_end = pos;
-
-        // Skip whitespace before '=' (handles both `#45=` and `#45 = ` formats)
-        while pos < len && bytes[pos].is_ascii_whitespace() {
-            pos += 1;
-        }
-
-        if id_end > id_start && pos < len && bytes[pos] == b'=' {
-            // Fast integer parsing without allocation
-            let id = parse_u32_inline(bytes, id_start, id_end);
-
-            // Find end of entity (;) using SIMD
-            let entity_content = &bytes[pos..];
-            if let Some(semicolon_offset) = memchr::memchr(b';', entity_content) {
-                pos += semicolon_offset + 1; // Include semicolon
-                index.insert(id, (start, pos));
-            } else {
-                break; // No semicolon found, malformed
-            }
-        }
-    }
-
-    index
-}
-
-/// Fast u32 parsing without string allocation
-#[inline]
-fn parse_u32_inline(bytes: &[u8], start: usize, end: usize) -> u32 {
-    let mut result: u32 = 0;
-    for &byte in &bytes[start..end] {
-        let digit = byte.wrapping_sub(b'0');
-        result = result.wrapping_mul(10).wrapping_add(digit as u32);
-    }
-    result
-}
-
 /// Entity decoder for lazy parsing - uses Arc for efficient cache sharing
 pub struct EntityDecoder<'a> {
     content: &'a str,
     /// Cache of decoded entities (entity_id -> `Arc<DecodedEntity>`)
-    /// Using Arc avoids expensive clones on cache hits
-    cache: FxHashMap<u32, Arc<DecodedEntity>>,
+    /// Using Arc avoids expensive clones on cache hits; LRU-bounded with `decode-lru`
+    cache: DecodeCache,
     /// Index of entity offsets (entity_id -> (start, end))
     /// Can be pre-built or built lazily
     /// Using Arc to allow sharing across threads without cloning the HashMap
@@ -100,7 +36,7 @@ impl<'a> EntityDecoder<'a> {
     pub fn new(content: &'a str) -> Self {
         Self {
             content,
-            cache: FxHashMap::default(),
+            cache: DecodeCache::default(),
             entity_index: None,
             point_cache: FxHashMap::default(),
         }
@@ -110,7 +46,7 @@ impl<'a> EntityDecoder<'a> {
     pub fn with_index(content: &'a str, index: EntityIndex) -> Self {
         Self {
             content,
-            cache: FxHashMap::default(),
+            cache: DecodeCache::default(),
             entity_index: Some(Arc::new(index)),
             point_cache: FxHashMap::default(),
         }
@@ -120,7 +56,7 @@ impl<'a> EntityDecoder<'a> {
     pub fn with_arc_index(content: &'a str, index: Arc<EntityIndex>) -> Self {
         Self {
             content,
-            cache: FxHashMap::default(),
+            cache: DecodeCache::default(),
             entity_index: Some(index),
             point_cache: FxHashMap::default(),
         }
@@ -139,33 +75,14 @@ impl<'a> EntityDecoder<'a> {
     /// Returns cached entity if already decoded
     #[inline]
     pub fn decode_at(&mut self, start: usize, end: usize) -> Result<DecodedEntity> {
-        let line = &self.content[start..end];
-        let (id, ifc_type, tokens) = parse_entity(line).map_err(|e| {
-            // Add debug info about what failed to parse
-            Error::parse(
-                0,
-                format!(
-                    "Failed to parse entity: {:?}, input: {:?}",
-                    e,
-                    &line[..line.len().min(100)]
-                ),
-            )
-        })?;
+        let (id, ifc_type, tokens) = self.parse_line(start, end)?;
 
         // Check cache first - return clone of inner DecodedEntity
-        if let Some(entity_arc) = self.cache.get(&id) {
+        if let Some(entity_arc) = self.cache.get(id) {
             return Ok(entity_arc.as_ref().clone());
         }
 
-        // Convert tokens to AttributeValues
-        let attributes = tokens
-            .iter()
-            .map(|token| AttributeValue::from_token(token))
-            .collect();
-
-        let entity = DecodedEntity::new(id, ifc_type, attributes);
-        self.cache.insert(id, Arc::new(entity.clone()));
-        Ok(entity)
+        Ok(self.cache_tokens(id, ifc_type, &tokens))
     }
 
     /// Decode entity at byte offset with known ID (faster - checks cache before parsing)
@@ -178,19 +95,20 @@ impl<'a> EntityDecoder<'a> {
         end: usize,
     ) -> Result<DecodedEntity> {
         // Check cache first - avoid parsing if already decoded
-        if let Some(entity_arc) = self.cache.get(&id) {
+        if let Some(entity_arc) = self.cache.get(id) {
             return Ok(entity_arc.as_ref().clone());
         }
 
         // Not in cache, parse and cache
-        self.decode_at(start, end)
+        let (id, ifc_type, tokens) = self.parse_line(start, end)?;
+        Ok(self.cache_tokens(id, ifc_type, &tokens))
     }
 
     /// Decode entity by ID - O(1) lookup using entity index
     #[inline]
     pub fn decode_by_id(&mut self, entity_id: u32) -> Result<DecodedEntity> {
         // Check cache first - return clone of inner DecodedEntity
-        if let Some(entity_arc) = self.cache.get(&entity_id) {
+        if let Some(entity_arc) = self.cache.get(entity_id) {
             return Ok(entity_arc.as_ref().clone());
         }
 
@@ -201,10 +119,38 @@ impl<'a> EntityDecoder<'a> {
         let (start, end) = self
             .entity_index
             .as_ref()
-            .and_then(|idx| idx.get(&entity_id).copied())
+            .and_then(|idx| idx.get(entity_id))
             .ok_or_else(|| Error::parse(0, format!("Entity #{} not found", entity_id)))?;
 
-        self.decode_at(start, end)
+        let (id, ifc_type, tokens) = self.parse_line(start, end)?;
+        Ok(self.cache_tokens(id, ifc_type, &tokens))
+    }
+
+    /// Parse the entity line at a byte range
+    #[inline]
+    fn parse_line(&self, start: usize, end: usize) -> Result<(u32, IfcType, Vec<Token<'a>>)> {
+        let line = &self.content[start..end];
+        parse_entity(line).map_err(|e| {
+            // Add debug info about what failed to parse
+            Error::parse(
+                0,
+                format!(
+                    "Failed to parse entity: {:?}, input: {:?}",
+                    e,
+                    &line[..line.len().min(100)]
+                ),
+            )
+        })
+    }
+
+    /// Convert parsed tokens to an entity and cache it
+    #[inline]
+    fn cache_tokens(&mut self, id: u32, ifc_type: IfcType, tokens: &[Token]) -> DecodedEntity {
+        let attributes = tokens.iter().map(AttributeValue::from_token).collect();
+
+        let entity = DecodedEntity::new(id, ifc_type, attributes);
+        self.cache.insert(id, Arc::new(entity.clone()));
+        entity
     }
 
     /// Resolve entity reference (follow #ID)
@@ -234,7 +180,7 @@ impl<'a> EntityDecoder<'a> {
 
     /// Get cached entity (without decoding)
     pub fn get_cached(&self, entity_id: u32) -> Option<DecodedEntity> {
-        self.cache.get(&entity_id).map(|arc| arc.as_ref().clone())
+        self.cache.peek(entity_id).map(|arc| arc.as_ref().clone())
     }
 
     /// Reserve cache capacity to avoid HashMap resizing during processing.
@@ -263,12 +209,24 @@ impl<'a> EntityDecoder<'a> {
         self.cache.len()
     }
 
+    /// Limit the entity cache to `capacity` entries (LRU eviction).
+    /// Drops anything cached so far.
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache = DecodeCache::with_capacity(Some(capacity));
+        self
+    }
+
+    /// Entity cache hit/miss counters
+    pub fn cache_stats(&self) -> DecodeCacheStats {
+        self.cache.stats()
+    }
+
     /// Get raw bytes for an entity (for direct/fast parsing)
     /// Returns the full entity line including type and attributes
     #[inline]
     pub fn get_raw_bytes(&mut self, entity_id: u32) -> Option<&'a [u8]> {
         self.build_index();
-        let (start, end) = self.entity_index.as_ref()?.get(&entity_id).copied()?;
+        let (start, end) = self.entity_index.as_ref()?.get(entity_id)?;
         Some(&self.content.as_bytes()[start..end])
     }
 
@@ -276,7 +234,7 @@ impl<'a> EntityDecoder<'a> {
     #[inline]
     pub fn get_raw_content(&mut self, entity_id: u32) -> Option<&'a str> {
         self.build_index();
-        let (start, end) = self.entity_index.as_ref()?.get(&entity_id).copied()?;
+        let (start, end) = self.entity_index.as_ref()?.get(entity_id)?;
         Some(&self.content[start..end])
     }
 
@@ -615,7 +573,7 @@ impl<'a> EntityDecoder<'a> {
         let bytes_full = self.content.as_bytes();
 
         // Get polyloop raw bytes
-        let (start, end) = index.get(&entity_id).copied()?;
+        let (start, end) = index.get(entity_id)?;
         let bytes = &bytes_full[start..end];
 
         // IFCPOLYLOOP((#id1,#id2,#id3,...));
@@ -671,7 +629,7 @@ impl<'a> EntityDecoder<'a> {
 
                     // INLINE: Get cartesian point coordinates directly
                     // This avoids the overhead of calling get_cartesian_point_fast for each point
-                    if let Some((pt_start, pt_end)) = index.get(&point_id).copied() {
+                    if let Some((pt_start, pt_end)) = index.get(point_id) {
                         if let Some(coord) =
                             parse_cartesian_point_inline(&bytes_full[pt_start..pt_end])
                         {
@@ -702,7 +660,7 @@ impl<'a> EntityDecoder<'a> {
         let bytes_full = self.content.as_bytes();
 
         // Get polyloop raw bytes
-        let (start, end) = index.get(&entity_id).copied()?;
+        let (start, end) = index.get(entity_id)?;
         let bytes = &bytes_full[start..end];
 
         // IFCPOLYLOOP((#id1,#id2,#id3,...));
@@ -765,7 +723,7 @@ impl<'a> EntityDecoder<'a> {
                         coords.push(coord);
                     } else {
                         // Not in cache - parse and cache
-                        if let Some((pt_start, pt_end)) = index.get(&point_id).copied() {
+                        if let Some((pt_start, pt_end)) = index.get(point_id) {
                             if let Some(coord) =
                                 parse_cartesian_point_inline(&bytes_full[pt_start..pt_end])
                             {
@@ -0,0 +1,315 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Entity index - maps entity IDs to byte ranges in the STEP content
+//!
+//! Two representations share one lookup API:
+//!
+//! - **Hash**: an `FxHashMap`, fastest lookups, ~40 bytes per entity.
+//! - **Compact**: a sorted table of fixed 16-byte records searched by binary
+//!   search. The table is a plain byte buffer, so it can be written to disk
+//!   and served back from any `AsRef<[u8]>` storage (e.g. a memory map).
+//!
+//! [`build_entity_index`] picks the compact form for files larger than
+//! [`COMPACT_INDEX_THRESHOLD`].
+
+use crate::error::{Error, Result};
+use rustc_hash::FxHashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Content size (bytes) above which `build_entity_index` builds a compact index
+pub const COMPACT_INDEX_THRESHOLD: usize = 256 * 1024 * 1024;
+
+/// Magic bytes at the start of a serialized compact index
+const COMPACT_MAGIC: &[u8; 8] = b"IFCIDX01";
+const HEADER_LEN: usize = 16;
+/// Record layout: id (u32) | length (u32) | start (u64), little-endian
+const RECORD_LEN: usize = 16;
+
+/// Backing bytes of a compact index (a `Vec<u8>`, a memory map, ...)
+pub type IndexStorage = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// Entity ID -> (start, end) byte offsets
+#[derive(Clone)]
+pub enum EntityIndex {
+    Hash(FxHashMap<u32, (usize, usize)>),
+    Compact(CompactEntityIndex),
+}
+
+impl Default for EntityIndex {
+    fn default() -> Self {
+        Self::Hash(FxHashMap::default())
+    }
+}
+
+impl From<FxHashMap<u32, (usize, usize)>> for EntityIndex {
+    fn from(map: FxHashMap<u32, (usize, usize)>) -> Self {
+        Self::Hash(map)
+    }
+}
+
+impl From<CompactEntityIndex> for EntityIndex {
+    fn from(index: CompactEntityIndex) -> Self {
+        Self::Compact(index)
+    }
+}
+
+impl EntityIndex {
+    /// Byte range of an entity
+    #[inline]
+    pub fn get(&self, id: u32) -> Option<(usize, usize)> {
+        match self {
+            Self::Hash(map) => map.get(&id).copied(),
+            Self::Compact(table) => table.get(id),
+        }
+    }
+
+    /// Whether the entity is indexed
+    #[inline]
+    pub fn contains(&self, id: u32) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Number of indexed entities
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Hash(map) => map.len(),
+            Self::Compact(table) => table.len(),
+        }
+    }
+
+    /// Whether the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether this is the compact representation
+    pub fn is_compact(&self) -> bool {
+        matches!(self, Self::Compact(_))
+    }
+
+    /// Compact copy of this index (cheap clone if already compact)
+    pub fn to_compact(&self) -> CompactEntityIndex {
+        match self {
+            Self::Hash(map) => {
+                CompactEntityIndex::from_entries(map.iter().map(|(&id, &(s, e))| (id, s, e)))
+            }
+            Self::Compact(table) => table.clone(),
+        }
+    }
+}
+
+/// Sorted offset table with binary-search lookups
+#[derive(Clone)]
+pub struct CompactEntityIndex {
+    storage: IndexStorage,
+    len: usize,
+}
+
+impl CompactEntityIndex {
+    /// Build from (id, start, end) entries; later duplicates win
+    pub fn from_entries(entries: impl IntoIterator<Item = (u32, usize, usize)>) -> Self {
+        let mut entries: Vec<(u32, usize, usize)> = entries.into_iter().collect();
+        // Stable sort keeps file order among duplicate IDs
+        entries.sort_by_key(|e| e.0);
+        entries.reverse();
+        entries.dedup_by_key(|e| e.0);
+        entries.reverse();
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + entries.len() * RECORD_LEN);
+        bytes.extend_from_slice(COMPACT_MAGIC);
+        bytes.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+        for (id, start, end) in &entries {
+            let length = u32::try_from(end - start).unwrap_or(u32::MAX);
+            bytes.extend_from_slice(&id.to_le_bytes());
+            bytes.extend_from_slice(&length.to_le_bytes());
+            bytes.extend_from_slice(&(*start as u64).to_le_bytes());
+        }
+
+        Self {
+            len: entries.len(),
+            storage: Arc::new(bytes),
+        }
+    }
+
+    /// Wrap serialized index bytes (as written by [`Self::write_to`])
+    pub fn from_storage(storage: IndexStorage) -> Result<Self> {
+        let bytes = (*storage).as_ref();
+        if bytes.len() < HEADER_LEN || &bytes[..8] != COMPACT_MAGIC {
+            return Err(Error::parse(0, "Not a compact entity index"));
+        }
+        let len = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) as usize;
+        let expected = len
+            .checked_mul(RECORD_LEN)
+            .and_then(|n| n.checked_add(HEADER_LEN));
+        if expected != Some(bytes.len()) {
+            return Err(Error::parse(
+                0,
+                format!(
+                    "Compact entity index has {} bytes for {} records",
+                    bytes.len(),
+                    len
+                ),
+            ));
+        }
+        Ok(Self { storage, len })
+    }
+
+    /// Serialized bytes (header + records)
+    pub fn as_bytes(&self) -> &[u8] {
+        (*self.storage).as_ref()
+    }
+
+    /// Write the serialized index, e.g. to a file that is later memory-mapped
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<()> {
+        writer.write_all(self.as_bytes())?;
+        Ok(())
+    }
+
+    /// Number of indexed entities
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Byte range of an entity
+    #[inline]
+    pub fn get(&self, id: u32) -> Option<(usize, usize)> {
+        let records = &self.as_bytes()[HEADER_LEN..];
+        let record = |i: usize| &records[i * RECORD_LEN..(i + 1) * RECORD_LEN];
+
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let r = record(mid);
+            let mid_id = u32::from_le_bytes(r[0..4].try_into().unwrap());
+            match mid_id.cmp(&id) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => {
+                    let length = u32::from_le_bytes(r[4..8].try_into().unwrap()) as usize;
+                    let start = u64::from_le_bytes(r[8..16].try_into().unwrap()) as usize;
+                    return Some((start, start + length));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Build entity index from content - O(n) scan using SIMD-accelerated search
+///
+/// Uses the hash representation, or the compact one for content larger than
+/// [`COMPACT_INDEX_THRESHOLD`].
+#[inline]
+pub fn build_entity_index(content: &str) -> EntityIndex {
+    if content.len() > COMPACT_INDEX_THRESHOLD {
+        return build_compact_entity_index(content).into();
+    }
+
+    // Pre-allocate with estimated capacity (roughly 1 entity per 50 bytes)
+    let estimated_entities = content.len() / 50;
+    let mut index = FxHashMap::with_capacity_and_hasher(estimated_entities, Default::default());
+    scan_entities(content, |id, start, end| {
+        index.insert(id, (start, end));
+    });
+    EntityIndex::Hash(index)
+}
+
+/// Build the compact (sorted offset table) index regardless of content size
+pub fn build_compact_entity_index(content: &str) -> CompactEntityIndex {
+    let mut entries = Vec::with_capacity(content.len() / 50);
+    scan_entities(content, |id, start, end| entries.push((id, start, end)));
+    CompactEntityIndex::from_entries(entries)
+}
+
+/// Call `visit(id, start, end)` for every `#id=...;` entity in the content
+#[inline]
+fn scan_entities(content: &str, mut visit: impl FnMut(u32, usize, usize)) {
+    let bytes = content.as_bytes();
+    let len = bytes.len();
+    let mut pos = 0;
+
+    while pos < len {
+        // Find next '#' using SIMD-accelerated search
+        let remaining = &bytes[pos..];
+        let hash_offset = match memchr::memchr(b'#', remaining) {
+            Some(offset) => offset,
+            None => break,
+        };
+
+        let start = pos + hash_offset;
+        pos = start + 1;
+
+        // Parse entity ID (inline for speed)
+        let id_start = pos;
+        while pos < len && bytes[pos].is_ascii_digit() {
+            pos += 1;
+        }
+        let id_end = pos;
+
+        // Skip whitespace before '=' (handles both `#45=` and `#45 = ` formats)
+        while pos < len && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+
+        if id_end > id_start && pos < len && bytes[pos] == b'=' {
+            // Fast integer parsing without allocation
+            let id = parse_u32_inline(bytes, id_start, id_end);
+
+            // Find end of entity (;) using SIMD
+            let entity_content = &bytes[pos..];
+            if let Some(semicolon_offset) = memchr::memchr(b';', entity_content) {
+                pos += semicolon_offset + 1; // Include semicolon
+                visit(id, start, pos);
+            } else {
+                break; // No semicolon found, malformed
+            }
+        }
+    }
+}
+
+/// Fast u32 parsing without string allocation
+#[inline]
+fn parse_u32_inline(bytes: &[u8], start: usize, end: usize) -> u32 {
+    let mut result: u32 = 0;
+    for &byte in &bytes[start..end] {
+        let digit = byte.wrapping_sub(b'0');
+        result = result.wrapping_mul(10).wrapping_add(digit as u32);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_index_matches_hash_index() {
+        let content = "#1=IFCWALL('a');\n#20 = IFCDOOR('b');\n#3=IFCSLAB('c');\n#20=IFCDOOR('d');";
+        let hash = build_entity_index(content);
+        let compact = EntityIndex::from(build_compact_entity_index(content));
+        assert!(!hash.is_compact());
+        assert!(compact.is_compact());
+        assert_eq!(compact.len(), 3);
+
+        for id in [1, 3, 20, 2, 99] {
+            assert_eq!(hash.get(id), compact.get(id), "id {}", id);
+        }
+        let (start, end) = compact.get(20).unwrap();
+        assert_eq!(&content[start..end], "#20=IFCDOOR('d');");
+
+        // Round-trip through serialized bytes, as a memory map would serve them
+        let mut file = Vec::new();
+        compact.to_compact().write_to(&mut file).unwrap();
+        let reloaded = CompactEntityIndex::from_storage(Arc::new(file)).unwrap();
+        assert_eq!(reloaded.get(3), hash.get(3));
+        assert!(CompactEntityIndex::from_storage(Arc::new(vec![0u8; 8])).is_err());
+    }
+}
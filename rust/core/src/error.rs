@@ -19,6 +19,9 @@ pub enum Error {
     #[error("Invalid IFC type: {0}")]
     InvalidIfcType(String),
 
+    #[error("Unknown attribute {name} of {ifc_type}")]
+    UnknownAttribute { ifc_type: String, name: String },
+
     #[error("Unexpected token at position {position}: expected {expected}, got {got}")]
     UnexpectedToken {
         position: usize,
@@ -0,0 +1,87 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! GlobalId index
+//!
+//! Maps the 22-character `GlobalId` of every `IfcRoot` entity to its express
+//! ID, for resolving references from outside the file (BCF topics, deep
+//! links, other models).
+
+use crate::parser::EntityScanner;
+use rustc_hash::FxHashMap;
+
+/// Length of a compressed IFC GUID
+const GUID_LEN: usize = 22;
+
+/// GlobalId -> express ID
+#[derive(Debug, Clone, Default)]
+pub struct GuidIndex {
+    ids: FxHashMap<String, u32>,
+}
+
+impl GuidIndex {
+    /// Scan `content` for entities whose first attribute is a GlobalId.
+    ///
+    /// Works on the raw entity text without decoding, so it also picks up
+    /// entities of types the schema doesn't know (IFC2X3-only types).
+    pub fn build(content: &str) -> Self {
+        let mut ids = FxHashMap::default();
+        let mut scanner = EntityScanner::new(content);
+        while let Some((id, _, start, end)) = scanner.next_entity() {
+            if let Some(guid) = leading_guid(&content[start..end]) {
+                // First definition wins, like the entity index
+                ids.entry(guid.to_string()).or_insert(id);
+            }
+        }
+        Self { ids }
+    }
+
+    /// Express ID of the entity with `guid`
+    pub fn get(&self, guid: &str) -> Option<u32> {
+        self.ids.get(guid).copied()
+    }
+
+    /// Number of indexed entities
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether no entities were indexed
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+/// The first attribute of `#ID=TYPE('...',...)` if it is shaped like a GUID
+fn leading_guid(entity: &str) -> Option<&str> {
+    let open = entity.find('(')?;
+    let rest = entity[open + 1..].trim_start().strip_prefix('\'')?;
+    let guid = rest.get(..GUID_LEN)?;
+    let is_guid = rest[GUID_LEN..].starts_with('\'')
+        && guid
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'$');
+    is_guid.then_some(guid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guid_index() {
+        let content = r#"
+#1=IFCPROJECT('0YvctVUKr0kugbFTf53O9L',$,'Project',$,$,$,$,$,$);
+#2=IFCWALL('2O2Fr$t4X7Zf8NOew3FLOH',$,'Wall',$,$,$,$,$,$);
+#3=IFCCARTESIANPOINT((0.,0.,0.));
+#4=IFCPROPERTYSINGLEVALUE('Reference',$,IFCLABEL('x'),$);
+#5=IFCWALL('2O2Fr$t4X7Zf8NOew3FLOH',$,'Duplicate',$,$,$,$,$,$);
+"#;
+        let index = GuidIndex::build(content);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get("0YvctVUKr0kugbFTf53O9L"), Some(1));
+        assert_eq!(index.get("2O2Fr$t4X7Zf8NOew3FLOH"), Some(2));
+        assert_eq!(index.get("Reference"), None);
+    }
+}
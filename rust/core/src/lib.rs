@@ -66,19 +66,36 @@
 //!
 //! - `serde`: Enable serialization support for parsed data
 
+pub mod content_hash;
+pub mod decode_cache;
 pub mod decoder;
+pub mod entity_index;
 pub mod error;
 pub mod fast_parse;
 pub mod generated;
 pub mod georef;
+pub mod guid_index;
 pub mod legacy_entities;
 pub mod model_bounds;
+pub mod model_editor;
 pub mod parser;
+pub mod properties;
+pub mod property_schema;
+pub mod relationships;
 pub mod schema_gen;
+pub mod spatial_tree;
+pub mod step_writer;
 pub mod streaming;
+pub mod type_index;
 pub mod units;
 
-pub use decoder::{build_entity_index, EntityDecoder, EntityIndex};
+pub use content_hash::{cache_key, content_hash, ContentHasher, PARSER_VERSION};
+pub use decode_cache::{DecodeCache, DecodeCacheStats};
+pub use decoder::EntityDecoder;
+pub use entity_index::{
+    build_compact_entity_index, build_entity_index, CompactEntityIndex, EntityIndex, IndexStorage,
+    COMPACT_INDEX_THRESHOLD,
+};
 pub use error::{Error, Result};
 pub use fast_parse::{
     extract_coordinate_list_from_entity, extract_entity_refs_from_list, extract_entity_type_name,
@@ -87,11 +104,26 @@ pub use fast_parse::{
 };
 pub use generated::{has_geometry_by_name, IfcType};
 pub use georef::{GeoRefExtractor, GeoReference, RtcOffset};
+pub use guid_index::GuidIndex;
 pub use legacy_entities::{
     get_legacy_entity_info, is_legacy_entity, map_legacy_to_base_type, LegacyEntityInfo,
 };
 pub use model_bounds::{scan_model_bounds, scan_placement_bounds, ModelBounds};
+pub use model_editor::ModelEditor;
 pub use parser::{parse_entity, EntityScanner, Token};
+pub use properties::{Property, PropertySet, PropertySetIndex, PropertySetKind, PropertyValue};
+pub use property_schema::{
+    PropertySchema, PropertySchemaIssue, PropertySchemaRegistry, PropertySchemaWarning,
+};
+pub use relationships::{RelationKind, RelationshipIndex};
 pub use schema_gen::{AttributeValue, DecodedEntity, GeometryCategory, IfcSchema, ProfileCategory};
+pub use spatial_tree::{build_spatial_tree, SpatialNode};
+pub use step_writer::{
+    decode_string, encode_string, format_entity, format_value, is_defined_type_name, write_step,
+    StepEdits,
+};
 pub use streaming::{parse_stream, ParseEvent, StreamConfig};
-pub use units::{extract_length_unit_scale, get_si_prefix_multiplier};
+pub use type_index::TypeIndex;
+pub use units::{
+    extract_length_unit_scale, extract_plane_angle_unit_scale, get_si_prefix_multiplier,
+};
@@ -0,0 +1,425 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Model editing
+//!
+//! [`ModelEditor`] records changes to a model as [`StepEdits`] on top of the
+//! unchanged content: attribute updates, new property sets and element
+//! deletion. Reads see the edited state, and [`ModelEditor::write`] produces
+//! the edited file.
+//!
+//! Attribute values use the decoder's form ([`AttributeValue`] with STEP-encoded
+//! strings, see [`encode_string`]); names passed as `&str` are plain text.
+
+use crate::content_hash::content_hash;
+use crate::decoder::EntityDecoder;
+use crate::entity_index::{build_entity_index, EntityIndex};
+use crate::error::{Error, Result};
+use crate::generated::IfcType;
+use crate::parser::EntityScanner;
+use crate::relationships::{RelationKind, RelationshipIndex};
+use crate::schema_gen::AttributeValue;
+use crate::step_writer::{encode_string, typed_value, write_step, StepEdits};
+use std::cell::OnceCell;
+use std::sync::Arc;
+
+/// Characters of compressed IFC GUIDs, by 6-bit value
+const GUID_CHARS: &[u8; 64] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz_$";
+
+/// Edits to one model
+#[derive(Clone)]
+pub struct ModelEditor {
+    index: Arc<EntityIndex>,
+    edits: StepEdits,
+    /// ID for the next added entity
+    next_id: u32,
+    relationships: OnceCell<RelationshipIndex>,
+}
+
+impl ModelEditor {
+    /// Start editing `content`. All other methods must be given the same
+    /// content.
+    pub fn new(content: &str) -> Self {
+        let mut max_id = 0;
+        let mut scanner = EntityScanner::new(content);
+        while let Some((id, ..)) = scanner.next_entity() {
+            max_id = max_id.max(id);
+        }
+        Self {
+            index: Arc::new(build_entity_index(content)),
+            edits: StepEdits::default(),
+            next_id: max_id + 1,
+            relationships: OnceCell::new(),
+        }
+    }
+
+    /// Changes made so far
+    pub fn edits(&self) -> &StepEdits {
+        &self.edits
+    }
+
+    /// The edited model as STEP text
+    pub fn write(&self, content: &str) -> String {
+        write_step(content, &self.edits)
+    }
+
+    /// Type name (uppercase) and attributes of an entity in its edited state;
+    /// `None` if it doesn't exist or was deleted.
+    pub fn entity(&self, content: &str, id: u32) -> Option<(String, Vec<AttributeValue>)> {
+        if let Some((type_name, attributes)) = self.edits.entity(id) {
+            return Some((type_name.to_string(), attributes.to_vec()));
+        }
+        if self.edits.is_deleted(id) {
+            return None;
+        }
+        let (start, end) = self.index.get(id)?;
+        let entity = EntityDecoder::with_arc_index(content, self.index.clone())
+            .decode_at_with_id(id, start, end)
+            .ok()?;
+        // Type name from the text, so types the schema doesn't know survive
+        let text = &content[start..end];
+        let type_name = text[text.find('=')? + 1..text.find('(')?].trim();
+        Some((type_name.to_uppercase(), entity.attributes))
+    }
+
+    /// Set attribute `name` of entity `id`. `name` is an `IfcRoot`..`IfcElement`
+    /// attribute (`Name`, `Description`, `ObjectType`, `Tag`, ...) or a
+    /// 0-based attribute index.
+    ///
+    /// The value keeps the kind of the current one: a bare value replacing a
+    /// typed value (`IFCLABEL('x')`) is wrapped in the same type, an integer
+    /// replacing a real becomes a real.
+    pub fn set_attribute(
+        &mut self,
+        content: &str,
+        id: u32,
+        name: &str,
+        value: AttributeValue,
+    ) -> Result<()> {
+        let (type_name, mut attributes) = self
+            .entity(content, id)
+            .ok_or(Error::InvalidEntityRef(id))?;
+        let index = name
+            .parse::<usize>()
+            .ok()
+            .or_else(|| attribute_index(IfcType::from_str(&type_name), name))
+            .filter(|&index| index < attributes.len())
+            .ok_or_else(|| Error::UnknownAttribute {
+                ifc_type: type_name.clone(),
+                name: name.to_string(),
+            })?;
+        attributes[index] = coerce(&attributes[index], value);
+        self.edits.set_entity(id, &type_name, attributes);
+        Ok(())
+    }
+
+    /// Attach a new `IfcPropertySet` named `name` with one
+    /// `IfcPropertySingleValue` per `(name, nominal value)` to object `id`.
+    /// Nominal values should be typed (`IFCLABEL('x')`, `IFCREAL(1.)`).
+    ///
+    /// Returns the express ID of the property set.
+    pub fn add_property_set(
+        &mut self,
+        content: &str,
+        id: u32,
+        name: &str,
+        properties: Vec<(String, AttributeValue)>,
+    ) -> Result<u32> {
+        let (type_name, object) = self
+            .entity(content, id)
+            .ok_or(Error::InvalidEntityRef(id))?;
+        let object_guid = match object.first() {
+            Some(AttributeValue::String(guid))
+                if IfcType::from_str(&type_name).is_subtype_of(IfcType::IfcObjectDefinition) =>
+            {
+                guid.clone()
+            }
+            _ => {
+                return Err(Error::InvalidIfcType(format!(
+                    "{type_name} (#{id}) cannot have property sets"
+                )))
+            }
+        };
+        let owner_history = object.get(1).cloned().unwrap_or(AttributeValue::Null);
+        let label = |text: &str| AttributeValue::String(encode_string(text));
+
+        let mut property_ids = Vec::with_capacity(properties.len());
+        for (property_name, value) in properties {
+            let property_id = self.add_entity(
+                "IFCPROPERTYSINGLEVALUE",
+                vec![
+                    label(&property_name),
+                    AttributeValue::Null,
+                    value,
+                    AttributeValue::Null,
+                ],
+            );
+            property_ids.push(AttributeValue::EntityRef(property_id));
+        }
+
+        let pset_id = self.next_id;
+        let pset_guid = new_guid(&format!("{object_guid}/{name}/{pset_id}"));
+        self.add_entity(
+            "IFCPROPERTYSET",
+            vec![
+                AttributeValue::String(pset_guid),
+                owner_history.clone(),
+                label(name),
+                AttributeValue::Null,
+                AttributeValue::List(property_ids),
+            ],
+        );
+        let rel_guid = new_guid(&format!("{object_guid}/{name}/{}", self.next_id));
+        self.add_entity(
+            "IFCRELDEFINESBYPROPERTIES",
+            vec![
+                AttributeValue::String(rel_guid),
+                owner_history,
+                AttributeValue::Null,
+                AttributeValue::Null,
+                AttributeValue::List(vec![AttributeValue::EntityRef(id)]),
+                AttributeValue::EntityRef(pset_id),
+            ],
+        );
+        Ok(pset_id)
+    }
+
+    /// Delete product `id` (an element, opening, spatial structure...) and
+    /// drop it from the relationships that reference it. Relationships left
+    /// without a relating or related side are deleted too. Other entities
+    /// referencing the product are left as they are.
+    pub fn delete_element(&mut self, content: &str, id: u32) -> Result<()> {
+        let (type_name, _) = self
+            .entity(content, id)
+            .ok_or(Error::InvalidEntityRef(id))?;
+        if !IfcType::from_str(&type_name).is_subtype_of(IfcType::IfcProduct) {
+            return Err(Error::InvalidIfcType(format!(
+                "{type_name} (#{id}) is not an element"
+            )));
+        }
+        self.edits.delete_entity(id);
+
+        let mut relationships = self.relationships(content).relationships_of(id).to_vec();
+        // Relationships added or rewritten by earlier edits
+        relationships.extend(self.edits.ids().filter(|&rel_id| {
+            self.edits
+                .entity(rel_id)
+                .is_some_and(|(type_name, _)| RelationKind::of_entity(type_name).is_some())
+        }));
+        relationships.sort_unstable();
+        relationships.dedup();
+
+        for rel_id in relationships {
+            let Some((type_name, mut attributes)) = self.entity(content, rel_id) else {
+                continue;
+            };
+            let Some((_, relating, related)) = RelationKind::of_entity(&type_name) else {
+                continue;
+            };
+            let mut changed = false;
+            let mut orphaned = false;
+            for index in [relating, related] {
+                match attributes.get_mut(index) {
+                    Some(AttributeValue::EntityRef(target)) if *target == id => orphaned = true,
+                    Some(AttributeValue::List(items)) => {
+                        let len = items.len();
+                        items.retain(|item| item.as_entity_ref() != Some(id));
+                        if items.len() != len {
+                            changed = true;
+                            orphaned |= items.is_empty();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if orphaned {
+                self.edits.delete_entity(rel_id);
+            } else if changed {
+                self.edits.set_entity(rel_id, &type_name, attributes);
+            }
+        }
+        Ok(())
+    }
+
+    /// Relationships of the loaded content, built on first use
+    fn relationships(&self, content: &str) -> &RelationshipIndex {
+        self.relationships.get_or_init(|| {
+            let mut decoder = EntityDecoder::with_arc_index(content, self.index.clone());
+            RelationshipIndex::build(content, &mut decoder)
+        })
+    }
+
+    fn add_entity(&mut self, type_name: &str, attributes: Vec<AttributeValue>) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.edits.set_entity(id, type_name, attributes);
+        id
+    }
+}
+
+/// Index of a named attribute inherited from the common supertypes
+fn attribute_index(ifc_type: IfcType, name: &str) -> Option<usize> {
+    let is_a = |parent| ifc_type.is_subtype_of(parent);
+    if !is_a(IfcType::IfcRoot) {
+        return None;
+    }
+    let index = match name {
+        "GlobalId" => 0,
+        "OwnerHistory" => 1,
+        "Name" => 2,
+        "Description" => 3,
+        "ObjectType" if is_a(IfcType::IfcObject) => 4,
+        "ObjectPlacement" if is_a(IfcType::IfcProduct) => 5,
+        "Representation" if is_a(IfcType::IfcProduct) => 6,
+        "Tag" if is_a(IfcType::IfcElement) || is_a(IfcType::IfcTypeProduct) => 7,
+        "LongName" if is_a(IfcType::IfcSpatialElement) => 7,
+        "ApplicableOccurrence" if is_a(IfcType::IfcTypeObject) => 4,
+        "HasPropertySets" if is_a(IfcType::IfcTypeObject) => 5,
+        "RepresentationMaps" if is_a(IfcType::IfcTypeProduct) => 6,
+        "ElementType" if is_a(IfcType::IfcElementType) => 8,
+        _ => return None,
+    };
+    Some(index)
+}
+
+/// `value` in the form of the `current` attribute value
+fn coerce(current: &AttributeValue, value: AttributeValue) -> AttributeValue {
+    match (current, value) {
+        (AttributeValue::List(items), value)
+            if !matches!(value, AttributeValue::List(_) | AttributeValue::Null) =>
+        {
+            match typed_value(items) {
+                Some((type_name, args)) => AttributeValue::List(vec![
+                    AttributeValue::String(type_name.to_string()),
+                    coerce(&args[0], value),
+                ]),
+                None => value,
+            }
+        }
+        (AttributeValue::Float(_), AttributeValue::Integer(i)) => AttributeValue::Float(i as f64),
+        (_, value) => value,
+    }
+}
+
+/// Compressed GUID (22 characters) derived from `seed`
+fn new_guid(seed: &str) -> String {
+    let bits = content_hash(seed.as_bytes());
+    // 2 bits in the first character, 6 in each of the other 21
+    (0..22)
+        .map(|i| {
+            let shift = if i == 0 { 126 } else { 126 - 6 * i };
+            GUID_CHARS[((bits >> shift) & 0x3f) as usize] as char
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::properties::PropertySetIndex;
+
+    const CONTENT: &str = "ISO-10303-21;\nHEADER;\nENDSEC;\nDATA;\n\
+#1=IFCBUILDINGSTOREY('0YvctVUKr0kugbFTf53O9L',#9,'Ground',$,$,$,$,$,.ELEMENT.,0.);\n\
+#2=IFCWALL('2O2Fr$t4X7Zf8NOew3FLOH',#9,'Wall',$,$,$,$,$,$);\n\
+#3=IFCDOOR('1hOSvn6df7F8_7GcBWlRGQ',#9,'Door',$,$,$,$,$,$,$,$,$,$);\n\
+#4=IFCPROPERTYSINGLEVALUE('Width',$,IFCLENGTHMEASURE(0.9),$);\n\
+#9=IFCOWNERHISTORY($,$,$,$,$,$,$,0);\n\
+#10=IFCRELCONTAINEDINSPATIALSTRUCTURE('3Ax6m$4Dj0uQ3hQmuGNWqH',#9,$,$,(#2,#3),#1);\n\
+#11=IFCRELCONTAINEDINSPATIALSTRUCTURE('0Kx3jF9tP1XvG7XGrzN5gK',#9,$,$,(#3),#1);\n\
+ENDSEC;\nEND-ISO-10303-21;\n";
+
+    #[test]
+    fn test_set_attribute() {
+        let mut editor = ModelEditor::new(CONTENT);
+        editor
+            .set_attribute(CONTENT, 2, "Name", AttributeValue::String("Wall 1".into()))
+            .unwrap();
+        editor
+            .set_attribute(CONTENT, 4, "2", AttributeValue::Integer(1))
+            .unwrap();
+        assert!(editor
+            .set_attribute(CONTENT, 4, "Name", AttributeValue::Null)
+            .is_err());
+        assert!(editor
+            .set_attribute(CONTENT, 99, "Name", AttributeValue::Null)
+            .is_err());
+
+        let written = editor.write(CONTENT);
+        assert!(written.contains("#2=IFCWALL('2O2Fr$t4X7Zf8NOew3FLOH',#9,'Wall 1',$,$,$,$,$,$);"));
+        // Typed values keep their type
+        assert!(written.contains("#4=IFCPROPERTYSINGLEVALUE('Width',$,IFCLENGTHMEASURE(1.0),$);"));
+        assert_eq!(editor.edits().len(), 2);
+    }
+
+    #[test]
+    fn test_add_property_set() {
+        let mut editor = ModelEditor::new(CONTENT);
+        let label = AttributeValue::List(vec![
+            AttributeValue::String("IFCLABEL".into()),
+            AttributeValue::String("EI30".into()),
+        ]);
+        let pset_id = editor
+            .add_property_set(
+                CONTENT,
+                3,
+                "Pset_DoorCommon",
+                vec![("FireRating".into(), label)],
+            )
+            .unwrap();
+        assert_eq!(pset_id, 13);
+        assert!(editor.add_property_set(CONTENT, 4, "Pset", vec![]).is_err());
+
+        let written = editor.write(CONTENT);
+        let mut decoder = EntityDecoder::new(&written);
+        let index = PropertySetIndex::build(&written, &mut decoder);
+        let psets = index.property_sets(3, &mut decoder).unwrap();
+        assert_eq!(psets.len(), 1);
+        assert_eq!(psets[0].name, "Pset_DoorCommon");
+        assert_eq!(
+            psets[0].get("FireRating").unwrap().value,
+            crate::properties::PropertyValue::Text("EI30".into())
+        );
+
+        let pset = decoder.decode_by_id(pset_id).unwrap();
+        let guid = pset.get_string(0).unwrap();
+        assert_eq!(guid.len(), 22);
+        assert!(guid.bytes().all(|b| GUID_CHARS.contains(&b)));
+        assert_eq!(pset.get_ref(1), Some(9));
+    }
+
+    #[test]
+    fn test_delete_element() {
+        let mut editor = ModelEditor::new(CONTENT);
+        editor.delete_element(CONTENT, 3).unwrap();
+        assert!(editor.entity(CONTENT, 3).is_none());
+        assert!(editor.delete_element(CONTENT, 3).is_err());
+
+        // #10 loses the door, #11 is left empty and goes
+        let (_, containment) = editor.entity(CONTENT, 10).unwrap();
+        assert_eq!(
+            containment[4].as_list().map(<[AttributeValue]>::len),
+            Some(1)
+        );
+        assert!(editor.entity(CONTENT, 11).is_none());
+        assert_eq!(editor.edits().ids().collect::<Vec<_>>(), [10, 3, 11]);
+
+        // Only products; the owner history all relationships share stays
+        assert!(editor.delete_element(CONTENT, 9).is_err());
+        assert!(editor.delete_element(CONTENT, 4).is_err());
+
+        // Relationships added by edits are updated too
+        let label = AttributeValue::List(vec![
+            AttributeValue::String("IFCLABEL".into()),
+            AttributeValue::String("x".into()),
+        ]);
+        let pset_id = editor
+            .add_property_set(CONTENT, 2, "Pset", vec![("P".into(), label)])
+            .unwrap();
+        editor.delete_element(CONTENT, 2).unwrap();
+        assert!(editor.entity(CONTENT, 10).is_none());
+        assert!(editor.entity(CONTENT, pset_id + 1).is_none());
+        assert!(editor.entity(CONTENT, pset_id).is_some());
+        assert!(editor.entity(CONTENT, 9).is_some());
+    }
+}
@@ -0,0 +1,486 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Property and quantity set extraction
+//!
+//! Collects the `IfcPropertySet` and `IfcElementQuantity` definitions of an
+//! object: those assigned to it through `IfcRelDefinesByProperties`, then
+//! those inherited from its type object (`IfcRelDefinesByType`, the type's
+//! `HasPropertySets`). Typed values such as `IFCLENGTHMEASURE(2.5)` are
+//! unwrapped into [`PropertyValue`]s, keeping the type keyword alongside.
+//! Complex properties are flattened into `Complex.Member` names. Names and
+//! text values are decoded from their STEP form (`''`, `\X2\...\X0\`), and
+//! definitions or members that don't resolve are skipped.
+//!
+//! Property sets can optionally be checked against a
+//! [`PropertySchemaRegistry`] while they are extracted, see
+//! [`PropertySetIndex::property_sets_validated`].
+
+use crate::decoder::EntityDecoder;
+use crate::error::Result;
+use crate::generated::IfcType;
+use crate::parser::EntityScanner;
+use crate::property_schema::{split_typed_value, PropertySchemaRegistry, PropertySchemaWarning};
+use crate::schema_gen::{AttributeValue, DecodedEntity};
+use crate::step_writer::decode_string;
+use rustc_hash::FxHashMap;
+
+/// Maximum nesting of `IfcComplexProperty`.
+const MAX_COMPLEX_DEPTH: usize = 4;
+
+/// Value of a property or quantity.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PropertyValue {
+    /// Unset (`$`) or unknown (`.U.`) value.
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Real(f64),
+    /// Labels, texts, identifiers and enumeration literals.
+    Text(String),
+    /// Reference to another entity (e.g. an `IfcPropertyReferenceValue` target).
+    Reference(u32),
+    /// `IfcPropertyListValue` and `IfcPropertyEnumeratedValue` members.
+    List(Vec<PropertyValue>),
+    /// `IfcPropertyBoundedValue`.
+    Range {
+        lower: Box<PropertyValue>,
+        upper: Box<PropertyValue>,
+    },
+}
+
+impl PropertyValue {
+    /// Convert an attribute, unwrapping typed values and decoding text.
+    pub fn from_attribute(value: &AttributeValue) -> Self {
+        match value {
+            AttributeValue::Null | AttributeValue::Derived => PropertyValue::Null,
+            AttributeValue::Integer(i) => PropertyValue::Integer(*i),
+            AttributeValue::Float(f) => PropertyValue::Real(*f),
+            AttributeValue::String(s) => PropertyValue::Text(decode_string(s)),
+            AttributeValue::Enum(e) => match e.as_str() {
+                "T" => PropertyValue::Boolean(true),
+                "F" => PropertyValue::Boolean(false),
+                "U" => PropertyValue::Null,
+                _ => PropertyValue::Text(e.clone()),
+            },
+            AttributeValue::EntityRef(id) => PropertyValue::Reference(*id),
+            AttributeValue::List(items) => match split_typed_value(value) {
+                (Some(_), inner) => Self::from_attribute(inner),
+                (None, _) => PropertyValue::List(items.iter().map(Self::from_attribute).collect()),
+            },
+        }
+    }
+
+    /// Whether the value is [`PropertyValue::Null`].
+    pub fn is_null(&self) -> bool {
+        matches!(self, PropertyValue::Null)
+    }
+}
+
+/// One property or quantity.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Property {
+    pub name: String,
+    pub value: PropertyValue,
+    /// IFC type of the value: the typed-value keyword as written in the file
+    /// (e.g. `"IFCLABEL"`) for properties, the entity type (e.g.
+    /// `"IfcQuantityLength"`) for quantities.
+    pub value_type: Option<String>,
+}
+
+/// Whether a set holds properties or quantities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PropertySetKind {
+    /// `IfcPropertySet`
+    Properties,
+    /// `IfcElementQuantity`
+    Quantities,
+}
+
+/// A property or quantity set.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PropertySet {
+    /// Express ID of the set entity.
+    pub id: u32,
+    pub name: String,
+    pub kind: PropertySetKind,
+    pub properties: Vec<Property>,
+    /// Inherited from the object's type rather than assigned to the object.
+    pub from_type: bool,
+}
+
+impl PropertySet {
+    /// Parse an `IfcPropertySet` or `IfcElementQuantity`. Returns `None` for
+    /// other property definitions (e.g. predefined property sets).
+    pub fn from_entity(
+        entity: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Option<Self>> {
+        // IfcPropertySet: GlobalId, OwnerHistory, Name, Description, HasProperties(4)
+        // IfcElementQuantity: ..., Description, MethodOfMeasurement, Quantities(5)
+        let (kind, members_index) = match entity.ifc_type {
+            IfcType::IfcPropertySet => (PropertySetKind::Properties, 4),
+            IfcType::IfcElementQuantity => (PropertySetKind::Quantities, 5),
+            _ => return Ok(None),
+        };
+
+        let mut properties = Vec::new();
+        if let Some(members) = entity.get(members_index) {
+            for member in resolve_each(members, decoder) {
+                collect_property(&member, decoder, "", &mut properties, 0)?;
+            }
+        }
+
+        Ok(Some(Self {
+            id: entity.id,
+            name: decode_string(entity.get_string(2).unwrap_or_default()),
+            kind,
+            properties,
+            from_type: false,
+        }))
+    }
+
+    /// Look up a property by name.
+    pub fn get(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+}
+
+/// Property set assignments of a model, for looking up the sets of any
+/// object without rescanning the file.
+#[derive(Debug, Clone, Default)]
+pub struct PropertySetIndex {
+    /// Property definitions assigned to each object
+    definitions: FxHashMap<u32, Vec<u32>>,
+    /// Type object of each occurrence
+    types: FxHashMap<u32, u32>,
+}
+
+impl PropertySetIndex {
+    /// Scan the model's `IfcRelDefinesByProperties` and `IfcRelDefinesByType`
+    /// relationships.
+    pub fn build(content: &str, decoder: &mut EntityDecoder) -> Self {
+        let mut index = Self::default();
+        let mut scanner = EntityScanner::new(content);
+        while let Some((id, type_name, start, end)) = scanner.next_entity() {
+            let by_type = match type_name {
+                "IFCRELDEFINESBYPROPERTIES" => false,
+                "IFCRELDEFINESBYTYPE" => true,
+                _ => continue,
+            };
+            let Ok(rel) = decoder.decode_at_with_id(id, start, end) else {
+                continue;
+            };
+            // RelatedObjects(4), RelatingPropertyDefinition / RelatingType(5)
+            let related = rel.get_list(4).unwrap_or_default();
+            let related = related.iter().filter_map(AttributeValue::as_entity_ref);
+            if by_type {
+                if let Some(type_id) = rel.get_ref(5) {
+                    index.types.extend(related.map(|object| (object, type_id)));
+                }
+            } else {
+                // IFC4 also allows an IfcPropertySetDefinitionSet (a list) here
+                let definitions: Vec<u32> = match rel.get(5) {
+                    Some(AttributeValue::List(items)) => items
+                        .iter()
+                        .filter_map(AttributeValue::as_entity_ref)
+                        .collect(),
+                    Some(value) => value.as_entity_ref().into_iter().collect(),
+                    None => Vec::new(),
+                };
+                for object in related {
+                    index
+                        .definitions
+                        .entry(object)
+                        .or_default()
+                        .extend(&definitions);
+                }
+            }
+        }
+        index
+    }
+
+    /// Property definitions assigned directly to an object.
+    pub fn definitions_of(&self, object_id: u32) -> &[u32] {
+        self.definitions
+            .get(&object_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Type object of an occurrence.
+    pub fn type_of(&self, object_id: u32) -> Option<u32> {
+        self.types.get(&object_id).copied()
+    }
+
+    /// Property and quantity sets of an object: its own, then those of its
+    /// type (flagged [`PropertySet::from_type`]).
+    pub fn property_sets(
+        &self,
+        object_id: u32,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Vec<PropertySet>> {
+        self.collect_property_sets(object_id, decoder, None)
+    }
+
+    /// [`property_sets`](Self::property_sets), also validating each
+    /// `IfcPropertySet` against `registry` as it is extracted.
+    pub fn property_sets_validated(
+        &self,
+        object_id: u32,
+        decoder: &mut EntityDecoder,
+        registry: &PropertySchemaRegistry,
+    ) -> Result<(Vec<PropertySet>, Vec<PropertySchemaWarning>)> {
+        let mut warnings = Vec::new();
+        let sets =
+            self.collect_property_sets(object_id, decoder, Some((registry, &mut warnings)))?;
+        Ok((sets, warnings))
+    }
+
+    fn collect_property_sets(
+        &self,
+        object_id: u32,
+        decoder: &mut EntityDecoder,
+        mut validation: Option<(&PropertySchemaRegistry, &mut Vec<PropertySchemaWarning>)>,
+    ) -> Result<Vec<PropertySet>> {
+        // Dangling definitions are skipped rather than hiding every other set
+        let mut definitions = Vec::new();
+        for &id in self.definitions_of(object_id) {
+            if let Ok(definition) = decoder.decode_by_id(id) {
+                definitions.push((definition, false));
+            }
+        }
+        if let Some(type_object) = self
+            .type_of(object_id)
+            .and_then(|type_id| decoder.decode_by_id(type_id).ok())
+        {
+            // IfcTypeObject attr 5: HasPropertySets
+            if let Some(has_property_sets) = type_object.get(5) {
+                for definition in resolve_each(has_property_sets, decoder) {
+                    definitions.push((definition, true));
+                }
+            }
+        }
+
+        let mut sets = Vec::new();
+        for (definition, from_type) in definitions {
+            let Ok(Some(mut set)) = PropertySet::from_entity(&definition, decoder) else {
+                continue;
+            };
+            if let Some((registry, warnings)) = validation.as_mut() {
+                if set.kind == PropertySetKind::Properties {
+                    warnings.extend(registry.validate_property_set(&definition, decoder)?);
+                }
+            }
+            set.from_type = from_type;
+            sets.push(set);
+        }
+        Ok(sets)
+    }
+}
+
+/// Append one property, quantity or flattened complex property.
+fn collect_property(
+    entity: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+    prefix: &str,
+    properties: &mut Vec<Property>,
+    depth: usize,
+) -> Result<()> {
+    // IfcProperty / IfcPhysicalQuantity: Name(0), Description(1)
+    let Some(name) = entity.get_string(0) else {
+        return Ok(());
+    };
+    let name = format!("{prefix}{}", decode_string(name));
+    let attribute = |index: usize| entity.get(index).unwrap_or(&AttributeValue::Null);
+
+    let (value, value_type) = match entity.ifc_type {
+        IfcType::IfcPropertySingleValue => {
+            let nominal = attribute(2);
+            (
+                PropertyValue::from_attribute(nominal),
+                split_typed_value(nominal).0.map(str::to_string),
+            )
+        }
+        IfcType::IfcPropertyEnumeratedValue | IfcType::IfcPropertyListValue => {
+            let values = attribute(2);
+            let value_type = values
+                .as_list()
+                .and_then(|items| items.first())
+                .and_then(|first| split_typed_value(first).0)
+                .map(str::to_string);
+            let values = values.as_list().unwrap_or_default();
+            (
+                PropertyValue::List(values.iter().map(PropertyValue::from_attribute).collect()),
+                value_type,
+            )
+        }
+        // UpperBoundValue(2), LowerBoundValue(3)
+        IfcType::IfcPropertyBoundedValue => (
+            PropertyValue::Range {
+                lower: Box::new(PropertyValue::from_attribute(attribute(3))),
+                upper: Box::new(PropertyValue::from_attribute(attribute(2))),
+            },
+            split_typed_value(attribute(2)).0.map(str::to_string),
+        ),
+        // UsageName(2), PropertyReference(3)
+        IfcType::IfcPropertyReferenceValue => (PropertyValue::from_attribute(attribute(3)), None),
+        // UsageName(2), HasProperties(3)
+        IfcType::IfcComplexProperty => {
+            if depth < MAX_COMPLEX_DEPTH {
+                let prefix = format!("{name}.");
+                for member in resolve_each(attribute(3), decoder) {
+                    collect_property(&member, decoder, &prefix, properties, depth + 1)?;
+                }
+            }
+            return Ok(());
+        }
+        // IfcQuantityLength/Area/Volume/Count/Weight/Time: Unit(2), Value(3)
+        ref quantity if quantity.name().starts_with("IfcQuantity") => (
+            PropertyValue::from_attribute(attribute(3)),
+            Some(quantity.name().to_string()),
+        ),
+        _ => return Ok(()),
+    };
+
+    properties.push(Property {
+        name,
+        value,
+        value_type,
+    });
+    Ok(())
+}
+
+/// Entities referenced by a list attribute, skipping any that don't resolve
+fn resolve_each(list: &AttributeValue, decoder: &mut EntityDecoder) -> Vec<DecodedEntity> {
+    list.as_list()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| decoder.decode_by_id(item.as_entity_ref()?).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = r#"
+#1=IFCWALL('w',$,'Wall',$,$,$,$,$,$);
+#2=IFCWALLTYPE('t',$,'Type',$,$,(#30),$,$,$,$);
+#10=IFCPROPERTYSINGLEVALUE('IsExternal',$,IFCBOOLEAN(.T.),$);
+#11=IFCPROPERTYSINGLEVALUE('FireRating',$,IFCLABEL('EI60'),$);
+#12=IFCPROPERTYBOUNDEDVALUE('Range',$,IFCREAL(2.),IFCREAL(1.),$,$);
+#13=IFCPROPERTYENUMERATEDVALUE('Finish',$,(IFCLABEL('Paint')),$);
+#14=IFCCOMPLEXPROPERTY('Layer',$,'usage',(#11));
+#15=IFCPROPERTYSET('p',$,'Pset_WallCommon',$,(#10,#11,#12,#13,#14));
+#20=IFCQUANTITYLENGTH('Length',$,$,5.,$);
+#21=IFCELEMENTQUANTITY('q',$,'Qto_WallBaseQuantities',$,$,(#20));
+#30=IFCPROPERTYSET('tp',$,'Pset_TypeCommon',$,(#11));
+#40=IFCRELDEFINESBYPROPERTIES('r1',$,$,$,(#1),#15);
+#41=IFCRELDEFINESBYPROPERTIES('r2',$,$,$,(#1),#21);
+#42=IFCRELDEFINESBYTYPE('r3',$,$,$,(#1),#2);
+"#;
+
+    #[test]
+    fn test_property_sets_of_element() {
+        let mut decoder = EntityDecoder::new(CONTENT);
+        let index = PropertySetIndex::build(CONTENT, &mut decoder);
+        assert_eq!(index.definitions_of(1), &[15, 21]);
+        assert_eq!(index.type_of(1), Some(2));
+
+        let sets = index.property_sets(1, &mut decoder).unwrap();
+        assert_eq!(sets.len(), 3);
+
+        let common = &sets[0];
+        assert_eq!(common.name, "Pset_WallCommon");
+        assert_eq!(common.kind, PropertySetKind::Properties);
+        assert_eq!(
+            common.get("IsExternal").unwrap().value,
+            PropertyValue::Boolean(true)
+        );
+        let rating = common.get("FireRating").unwrap();
+        assert_eq!(rating.value, PropertyValue::Text("EI60".to_string()));
+        assert_eq!(rating.value_type.as_deref(), Some("IFCLABEL"));
+        assert_eq!(
+            common.get("Range").unwrap().value,
+            PropertyValue::Range {
+                lower: Box::new(PropertyValue::Real(1.0)),
+                upper: Box::new(PropertyValue::Real(2.0)),
+            }
+        );
+        assert_eq!(
+            common.get("Finish").unwrap().value,
+            PropertyValue::List(vec![PropertyValue::Text("Paint".to_string())])
+        );
+        assert!(common.get("Layer.FireRating").is_some());
+
+        let quantities = &sets[1];
+        assert_eq!(quantities.kind, PropertySetKind::Quantities);
+        let length = quantities.get("Length").unwrap();
+        assert_eq!(length.value, PropertyValue::Real(5.0));
+        assert_eq!(length.value_type.as_deref(), Some("IfcQuantityLength"));
+
+        assert_eq!(sets[2].name, "Pset_TypeCommon");
+        assert!(sets[2].from_type && !sets[0].from_type);
+
+        assert!(index.property_sets(99, &mut decoder).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dangling_definitions_and_encoded_text() {
+        let content = r#"
+#1=IFCWALL('w',$,'Wall',$,$,$,$,$,$);
+#10=IFCPROPERTYSINGLEVALUE('Owner''s \X2\00DC\X0\',$,IFCLABEL('Wall''s'),$);
+#11=IFCPROPERTYSET('p',$,'Pset_\X2\00C4\X0\',$,(#10,#98));
+#40=IFCRELDEFINESBYPROPERTIES('r1',$,$,$,(#1),#99);
+#41=IFCRELDEFINESBYPROPERTIES('r2',$,$,$,(#1),#11);
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let index = PropertySetIndex::build(content, &mut decoder);
+        assert_eq!(index.definitions_of(1), &[99, 11]);
+
+        let sets = index.property_sets(1, &mut decoder).unwrap();
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].name, "Pset_\u{c4}");
+        assert_eq!(sets[0].properties.len(), 1);
+        let owner = sets[0].get("Owner's \u{dc}").unwrap();
+        assert_eq!(owner.value, PropertyValue::Text("Wall's".to_string()));
+    }
+
+    #[test]
+    fn test_property_sets_validated() {
+        use crate::property_schema::{PropertySchema, PropertySchemaIssue};
+
+        let mut registry = PropertySchemaRegistry::new();
+        registry.register(
+            PropertySchema::new("Pset_WallCommon", "FireRating")
+                .with_allowed_values(["EI30", "EI90"]),
+        );
+
+        let mut decoder = EntityDecoder::new(CONTENT);
+        let index = PropertySetIndex::build(CONTENT, &mut decoder);
+        let (sets, warnings) = index
+            .property_sets_validated(1, &mut decoder, &registry)
+            .unwrap();
+        assert_eq!(sets, index.property_sets(1, &mut decoder).unwrap());
+
+        let issues: Vec<_> = warnings
+            .iter()
+            .map(|w| (w.property_set_id, w.property.as_str(), &w.issue))
+            .collect();
+        assert!(issues.contains(&(
+            15,
+            "FireRating",
+            &PropertySchemaIssue::ValueNotAllowed {
+                value: "EI60".to_string()
+            }
+        )));
+        // Only registered sets are checked
+        assert!(warnings.iter().all(|w| w.property_set == "Pset_WallCommon"));
+    }
+}
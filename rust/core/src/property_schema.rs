@@ -0,0 +1,400 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Custom property schema registry
+//!
+//! Lets applications register company-standard property definitions
+//! (property set, property name, expected IFC value type, allowed values) and
+//! check `IfcPropertySet` entities against them while extracting properties.
+//! Only property sets that have at least one registered property are checked,
+//! so standard `Pset_*` data and unrelated vendor sets pass through silently.
+
+use crate::decoder::EntityDecoder;
+use crate::error::Result;
+use crate::generated::IfcType;
+use crate::schema_gen::{AttributeValue, DecodedEntity};
+use crate::step_writer::decode_string;
+use rustc_hash::FxHashMap;
+
+/// Maximum edit distance for "did you mean" suggestions.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Definition of one custom property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct PropertySchema {
+    /// Property set name (e.g., `"ACME_WallCommon"`).
+    pub property_set: String,
+    /// Property name within the set.
+    pub name: String,
+    /// Expected IFC value type (e.g., `"IfcLabel"`, `"IfcLengthMeasure"`).
+    /// `None` accepts any type.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub data_type: Option<String>,
+    /// Permitted values; empty accepts any value.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub allowed_values: Vec<String>,
+}
+
+impl PropertySchema {
+    /// Create a definition accepting any type and value.
+    pub fn new(property_set: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            property_set: property_set.into(),
+            name: name.into(),
+            data_type: None,
+            allowed_values: Vec::new(),
+        }
+    }
+
+    /// Require values of the given IFC type.
+    pub fn with_data_type(mut self, data_type: impl Into<String>) -> Self {
+        self.data_type = Some(data_type.into());
+        self
+    }
+
+    /// Restrict values to the given set.
+    pub fn with_allowed_values<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_values = values.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// What a [`PropertySchemaWarning`] is about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertySchemaIssue {
+    /// Property set name is close to, but not exactly, a registered one.
+    UnknownPropertySet { suggestion: String },
+    /// Property is not registered in its (registered) property set.
+    UnknownProperty { suggestion: Option<String> },
+    /// Value type differs from the registered `data_type`. `found` is the
+    /// type keyword as written in the file (e.g., `"IFCINTEGER"`).
+    WrongType { expected: String, found: String },
+    /// Value is not among the registered `allowed_values`.
+    ValueNotAllowed { value: String },
+}
+
+/// Validation finding for a single property or property set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertySchemaWarning {
+    /// Express ID of the `IfcPropertySet` (0 when validating loose values).
+    pub property_set_id: u32,
+    pub property_set: String,
+    /// Property name; empty for set-level issues.
+    pub property: String,
+    pub issue: PropertySchemaIssue,
+}
+
+/// Registry of custom property definitions, keyed by property set and name.
+#[derive(Debug, Clone, Default)]
+pub struct PropertySchemaRegistry {
+    sets: FxHashMap<String, FxHashMap<String, PropertySchema>>,
+}
+
+impl PropertySchemaRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a definition, replacing any previous one with the same set and name.
+    pub fn register(&mut self, schema: PropertySchema) {
+        self.sets
+            .entry(schema.property_set.clone())
+            .or_default()
+            .insert(schema.name.clone(), schema);
+    }
+
+    /// Number of registered properties.
+    pub fn len(&self) -> usize {
+        self.sets.values().map(|s| s.len()).sum()
+    }
+
+    /// Check if no properties are registered.
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Look up a definition.
+    pub fn get(&self, property_set: &str, name: &str) -> Option<&PropertySchema> {
+        self.sets.get(property_set)?.get(name)
+    }
+
+    /// Validate one property value. `value` is the NominalValue attribute of
+    /// an `IfcPropertySingleValue` (a typed value such as `IFCLABEL('x')`).
+    pub fn validate_value(
+        &self,
+        property_set: &str,
+        name: &str,
+        value: &AttributeValue,
+    ) -> Vec<PropertySchemaWarning> {
+        let warning = |issue| PropertySchemaWarning {
+            property_set_id: 0,
+            property_set: property_set.to_string(),
+            property: name.to_string(),
+            issue,
+        };
+
+        let Some(properties) = self.sets.get(property_set) else {
+            return Vec::new();
+        };
+        let Some(schema) = properties.get(name) else {
+            let suggestion = closest(name, properties.keys());
+            return vec![warning(PropertySchemaIssue::UnknownProperty { suggestion })];
+        };
+
+        let mut warnings = Vec::new();
+        let (type_name, inner) = split_typed_value(value);
+        if let (Some(expected), Some(found)) = (&schema.data_type, type_name) {
+            if !expected.eq_ignore_ascii_case(found) {
+                warnings.push(warning(PropertySchemaIssue::WrongType {
+                    expected: expected.clone(),
+                    found: found.to_string(),
+                }));
+            }
+        }
+        if !schema.allowed_values.is_empty() {
+            if let Some(text) = value_text(inner) {
+                if !schema.allowed_values.contains(&text) {
+                    warnings.push(warning(PropertySchemaIssue::ValueNotAllowed {
+                        value: text,
+                    }));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Validate an `IfcPropertySet` entity and its `IfcPropertySingleValue`
+    /// and `IfcPropertyEnumeratedValue` members. Every enumerated value is
+    /// checked; members that don't resolve are skipped.
+    pub fn validate_property_set(
+        &self,
+        property_set: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Vec<PropertySchemaWarning>> {
+        // IfcPropertySet: GlobalId(0), OwnerHistory(1), Name(2), Description(3), HasProperties(4)
+        let Some(set_name) = property_set.get_string(2).map(decode_string) else {
+            return Ok(Vec::new());
+        };
+
+        if !self.sets.contains_key(&set_name) {
+            let suggestion = closest(&set_name, self.sets.keys());
+            return Ok(suggestion
+                .map(|suggestion| PropertySchemaWarning {
+                    property_set_id: property_set.id,
+                    property_set: set_name.clone(),
+                    property: String::new(),
+                    issue: PropertySchemaIssue::UnknownPropertySet { suggestion },
+                })
+                .into_iter()
+                .collect());
+        }
+
+        let mut warnings = Vec::new();
+        let properties = property_set
+            .get_list(4)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|item| decoder.decode_by_id(item.as_entity_ref()?).ok());
+        for property in properties {
+            // Name(0), Description(1), NominalValue / EnumerationValues(2)
+            let Some(name) = property.get_string(0).map(decode_string) else {
+                continue;
+            };
+            let values: Vec<&AttributeValue> = match property.ifc_type {
+                IfcType::IfcPropertySingleValue => property.get(2).into_iter().collect(),
+                IfcType::IfcPropertyEnumeratedValue => {
+                    property.get_list(2).unwrap_or_default().iter().collect()
+                }
+                _ => continue,
+            };
+            // A missing value still reports an unregistered property
+            let values = if values.is_empty() {
+                vec![&AttributeValue::Null]
+            } else {
+                values
+            };
+            for value in values {
+                for mut w in self.validate_value(&set_name, &name, value) {
+                    w.property_set_id = property_set.id;
+                    // Property-level issues are reported once, not per value
+                    if !warnings.contains(&w) {
+                        warnings.push(w);
+                    }
+                }
+            }
+        }
+        Ok(warnings)
+    }
+}
+
+/// Split `IFCLABEL('x')` (stored as `List([String(type), value])`) into its parts.
+pub(crate) fn split_typed_value(value: &AttributeValue) -> (Option<&str>, &AttributeValue) {
+    match value.as_list() {
+        Some([AttributeValue::String(type_name), inner]) => (Some(type_name.as_str()), inner),
+        _ => (None, value),
+    }
+}
+
+/// Render a scalar value for comparison with `allowed_values`, decoding
+/// STEP-encoded text.
+fn value_text(value: &AttributeValue) -> Option<String> {
+    match value {
+        AttributeValue::String(s) => Some(decode_string(s)),
+        AttributeValue::Enum(e) => Some(e.clone()),
+        AttributeValue::Integer(i) => Some(i.to_string()),
+        AttributeValue::Float(f) => Some(f.to_string()),
+        _ => None,
+    }
+}
+
+/// Registered name within [`MAX_SUGGESTION_DISTANCE`] edits of `name`
+/// (case-insensitive), preferring the closest.
+fn closest<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<String> {
+    let lower = name.to_lowercase();
+    candidates
+        .map(|c| (edit_distance(&lower, &c.to_lowercase()), c))
+        .filter(|(d, _)| *d <= MAX_SUGGESTION_DISTANCE)
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)))
+        .map(|(_, c)| c.clone())
+}
+
+/// Levenshtein distance over chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            row[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> PropertySchemaRegistry {
+        let mut registry = PropertySchemaRegistry::new();
+        registry
+            .register(PropertySchema::new("ACME_Wall", "FireRating").with_data_type("IfcLabel"));
+        registry.register(
+            PropertySchema::new("ACME_Wall", "Finish")
+                .with_data_type("IfcLabel")
+                .with_allowed_values(["Paint", "Plaster"]),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_validate_property_set() {
+        let content = r#"
+#1=IFCPROPERTYSINGLEVALUE('FireRatng',$,IFCLABEL('EI60'),$);
+#2=IFCPROPERTYSINGLEVALUE('Finish',$,IFCLABEL('Wallpaper'),$);
+#3=IFCPROPERTYSINGLEVALUE('FireRating',$,IFCINTEGER(60),$);
+#4=IFCPROPERTYSET('guid',$,'ACME_Wall',$,(#1,#2,#3));
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let pset = decoder.decode_by_id(4).unwrap();
+        let warnings = registry()
+            .validate_property_set(&pset, &mut decoder)
+            .unwrap();
+
+        let issues: Vec<_> = warnings.iter().map(|w| &w.issue).collect();
+        assert_eq!(warnings.len(), 3);
+        assert!(issues.contains(&&PropertySchemaIssue::UnknownProperty {
+            suggestion: Some("FireRating".to_string())
+        }));
+        assert!(issues.contains(&&PropertySchemaIssue::ValueNotAllowed {
+            value: "Wallpaper".to_string()
+        }));
+        assert!(issues.contains(&&PropertySchemaIssue::WrongType {
+            expected: "IfcLabel".to_string(),
+            found: "IFCINTEGER".to_string()
+        }));
+        assert!(warnings.iter().all(|w| w.property_set_id == 4));
+    }
+
+    #[test]
+    fn test_validate_enumerated_and_encoded_values() {
+        let mut registry = registry();
+        registry.register(
+            PropertySchema::new("ACME_Wall", "Owner")
+                .with_allowed_values(["Smith's", "M\u{fc}ller"]),
+        );
+        let content = r#"
+#1=IFCPROPERTYENUMERATEDVALUE('Finish',$,(IFCLABEL('Paint'),IFCLABEL('Wallpaper'),IFCLABEL('Tiles')),$);
+#2=IFCPROPERTYSINGLEVALUE('Owner',$,IFCLABEL('Smith''s'),$);
+#3=IFCPROPERTYSINGLEVALUE('Owner',$,IFCLABEL('M\X2\00FC\X0\ller'),$);
+#4=IFCPROPERTYENUMERATEDVALUE('Colour',$,(IFCLABEL('Red'),IFCLABEL('Blue')),$);
+#5=IFCPROPERTYSET('guid',$,'ACME_Wall',$,(#1,#2,#3,#4,#99));
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let pset = decoder.decode_by_id(5).unwrap();
+        let warnings = registry.validate_property_set(&pset, &mut decoder).unwrap();
+
+        let issues: Vec<_> = warnings
+            .iter()
+            .map(|w| (w.property.as_str(), &w.issue))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                (
+                    "Finish",
+                    &PropertySchemaIssue::ValueNotAllowed {
+                        value: "Wallpaper".to_string()
+                    }
+                ),
+                (
+                    "Finish",
+                    &PropertySchemaIssue::ValueNotAllowed {
+                        value: "Tiles".to_string()
+                    }
+                ),
+                (
+                    "Colour",
+                    &PropertySchemaIssue::UnknownProperty { suggestion: None }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_misspelled_and_unrelated_property_sets() {
+        let content = r#"
+#1=IFCPROPERTYSET('a',$,'ACME_Wal',$,());
+#2=IFCPROPERTYSET('b',$,'Pset_WallCommon',$,());
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let registry = registry();
+
+        let misspelled = decoder.decode_by_id(1).unwrap();
+        let warnings = registry
+            .validate_property_set(&misspelled, &mut decoder)
+            .unwrap();
+        assert_eq!(
+            warnings[0].issue,
+            PropertySchemaIssue::UnknownPropertySet {
+                suggestion: "ACME_Wall".to_string()
+            }
+        );
+
+        let standard = decoder.decode_by_id(2).unwrap();
+        assert!(registry
+            .validate_property_set(&standard, &mut decoder)
+            .unwrap()
+            .is_empty());
+    }
+}
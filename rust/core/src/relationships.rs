@@ -0,0 +1,247 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Relationship index
+//!
+//! Collects the objectified relationships viewers navigate most (spatial
+//! containment, decomposition, openings, connections, type, property and
+//! material assignment) in one scan. Each relationship links its *relating* side (the structure,
+//! the whole, the opening, the host, the type) to its *related* side, and
+//! can be followed in both directions.
+
+use crate::decoder::EntityDecoder;
+use crate::parser::EntityScanner;
+use crate::schema_gen::{AttributeValue, DecodedEntity};
+use rustc_hash::FxHashMap;
+
+/// Kind of relationship, grouping the IFC relationship entities it covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RelationKind {
+    /// `IfcRelContainedInSpatialStructure`: structure -> elements
+    ContainedIn,
+    /// `IfcRelAggregates`, `IfcRelNests`: whole -> parts
+    Decomposes,
+    /// `IfcRelFillsElement`: opening -> door/window
+    Fills,
+    /// `IfcRelVoidsElement`: host element -> opening
+    Voids,
+    /// `IfcRelConnectsElements` and subtypes: relating -> related element
+    Connects,
+    /// `IfcRelDefinesByType`: type -> objects
+    DefinesByType,
+    /// `IfcRelDefinesByProperties`: property set -> objects
+    DefinesByProperties,
+    /// `IfcRelAssociates*`: material, classification, document... -> objects
+    Associates,
+}
+
+impl RelationKind {
+    /// All kinds
+    pub const ALL: [RelationKind; 8] = [
+        RelationKind::ContainedIn,
+        RelationKind::Decomposes,
+        RelationKind::Fills,
+        RelationKind::Voids,
+        RelationKind::Connects,
+        RelationKind::DefinesByType,
+        RelationKind::DefinesByProperties,
+        RelationKind::Associates,
+    ];
+
+    /// Kebab-case name (`contained-in`, `defines-by-type`, ...)
+    pub fn name(&self) -> &'static str {
+        match self {
+            RelationKind::ContainedIn => "contained-in",
+            RelationKind::Decomposes => "decomposes",
+            RelationKind::Fills => "fills",
+            RelationKind::Voids => "voids",
+            RelationKind::Connects => "connects",
+            RelationKind::DefinesByType => "defines-by-type",
+            RelationKind::DefinesByProperties => "defines-by-properties",
+            RelationKind::Associates => "associates",
+        }
+    }
+
+    /// Parse a kebab-case name as returned by [`RelationKind::name`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// Kind and (relating, related) attribute indexes of a relationship
+    /// entity type
+    pub(crate) fn of_entity(type_name: &str) -> Option<(Self, usize, usize)> {
+        Some(match type_name {
+            // RelatedElements(4), RelatingStructure(5)
+            "IFCRELCONTAINEDINSPATIALSTRUCTURE" => (RelationKind::ContainedIn, 5, 4),
+            // RelatingObject(4), RelatedObjects(5)
+            "IFCRELAGGREGATES" | "IFCRELNESTS" => (RelationKind::Decomposes, 4, 5),
+            // RelatingOpeningElement(4), RelatedBuildingElement(5)
+            "IFCRELFILLSELEMENT" => (RelationKind::Fills, 4, 5),
+            // RelatingBuildingElement(4), RelatedOpeningElement(5)
+            "IFCRELVOIDSELEMENT" => (RelationKind::Voids, 4, 5),
+            // ConnectionGeometry(4), RelatingElement(5), RelatedElement(6)
+            "IFCRELCONNECTSELEMENTS"
+            | "IFCRELCONNECTSPATHELEMENTS"
+            | "IFCRELCONNECTSWITHREALIZINGELEMENTS" => (RelationKind::Connects, 5, 6),
+            // RelatedObjects(4), RelatingType(5)
+            "IFCRELDEFINESBYTYPE" => (RelationKind::DefinesByType, 5, 4),
+            // RelatedObjects(4), RelatingPropertyDefinition(5)
+            "IFCRELDEFINESBYPROPERTIES" => (RelationKind::DefinesByProperties, 5, 4),
+            // RelatedObjects(4), RelatingMaterial/Classification/...(5)
+            "IFCRELASSOCIATESMATERIAL"
+            | "IFCRELASSOCIATESCLASSIFICATION"
+            | "IFCRELASSOCIATESDOCUMENT"
+            | "IFCRELASSOCIATESLIBRARY"
+            | "IFCRELASSOCIATESAPPROVAL" => (RelationKind::Associates, 5, 4),
+            // RelatedObjects(4), Intent(5), RelatingConstraint(6)
+            "IFCRELASSOCIATESCONSTRAINT" => (RelationKind::Associates, 6, 4),
+            _ => return None,
+        })
+    }
+}
+
+/// Relationships of a model, by kind and entity
+#[derive(Debug, Clone, Default)]
+pub struct RelationshipIndex {
+    /// (kind, relating) -> related
+    related: FxHashMap<(RelationKind, u32), Vec<u32>>,
+    /// (kind, related) -> relating
+    relating: FxHashMap<(RelationKind, u32), Vec<u32>>,
+    /// entity -> relationship entities referencing it on either side
+    relationships: FxHashMap<u32, Vec<u32>>,
+}
+
+impl RelationshipIndex {
+    /// Scan `content` for relationship entities.
+    pub fn build(content: &str, decoder: &mut EntityDecoder) -> Self {
+        let mut index = Self::default();
+        let mut scanner = EntityScanner::new(content);
+        while let Some((id, type_name, start, end)) = scanner.next_entity() {
+            let Some((kind, relating_index, related_index)) = RelationKind::of_entity(type_name)
+            else {
+                continue;
+            };
+            let Ok(rel) = decoder.decode_at_with_id(id, start, end) else {
+                continue;
+            };
+            let related = refs(&rel, related_index);
+            let relating = refs(&rel, relating_index);
+            for &object in related.iter().chain(&relating) {
+                index.relationships.entry(object).or_default().push(id);
+            }
+            for relating in relating {
+                index
+                    .related
+                    .entry((kind, relating))
+                    .or_default()
+                    .extend(&related);
+                for &object in &related {
+                    index
+                        .relating
+                        .entry((kind, object))
+                        .or_default()
+                        .push(relating);
+                }
+            }
+        }
+        index
+    }
+
+    /// Entities on the related side of `kind` relationships where
+    /// `express_id` is relating (elements of a storey, parts of a whole,
+    /// openings of a wall, objects of a type).
+    pub fn related(&self, express_id: u32, kind: RelationKind) -> &[u32] {
+        self.related
+            .get(&(kind, express_id))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Entities on the relating side of `kind` relationships where
+    /// `express_id` is related (storey of an element, whole of a part,
+    /// opening a window fills, host of an opening, type of an object).
+    pub fn relating(&self, express_id: u32, kind: RelationKind) -> &[u32] {
+        self.relating
+            .get(&(kind, express_id))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// IDs of the relationship entities that reference `express_id` on their
+    /// relating or related side
+    pub fn relationships_of(&self, express_id: u32) -> &[u32] {
+        self.relationships
+            .get(&express_id)
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Entity references of an attribute holding one reference or a list
+fn refs(entity: &DecodedEntity, index: usize) -> Vec<u32> {
+    match entity.get(index) {
+        Some(AttributeValue::List(items)) => items
+            .iter()
+            .filter_map(AttributeValue::as_entity_ref)
+            .collect(),
+        Some(value) => value.as_entity_ref().into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relationship_index() {
+        let content = r#"
+#1=IFCBUILDINGSTOREY('s',$,'Ground',$,$,$,$,$,.ELEMENT.,0.);
+#2=IFCWALL('w',$,'Wall',$,$,$,$,$,$);
+#3=IFCOPENINGELEMENT('o',$,$,$,$,$,$,$,$);
+#4=IFCWINDOW('n',$,'Window',$,$,$,$,$,$,$,$,$,$);
+#5=IFCWALLTYPE('t',$,'Type',$,$,$,$,$,$,.STANDARD.);
+#6=IFCWALL('w2',$,'Other wall',$,$,$,$,$,$);
+#10=IFCRELCONTAINEDINSPATIALSTRUCTURE('r1',$,$,$,(#2,#4,#6),#1);
+#11=IFCRELVOIDSELEMENT('r2',$,$,$,#2,#3);
+#12=IFCRELFILLSELEMENT('r3',$,$,$,#3,#4);
+#13=IFCRELDEFINESBYTYPE('r4',$,$,$,(#2,#6),#5);
+#14=IFCRELCONNECTSPATHELEMENTS('r5',$,$,$,$,#2,#6,(),(),.ATEND.,.ATSTART.);
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let index = RelationshipIndex::build(content, &mut decoder);
+
+        assert_eq!(index.related(1, RelationKind::ContainedIn), [2, 4, 6]);
+        assert_eq!(index.relating(4, RelationKind::ContainedIn), [1]);
+
+        // Host wall of a window: window -> opening -> wall
+        let opening = index.relating(4, RelationKind::Fills)[0];
+        assert_eq!(index.relating(opening, RelationKind::Voids), [2]);
+
+        assert_eq!(index.relating(6, RelationKind::DefinesByType), [5]);
+        assert_eq!(index.related(5, RelationKind::DefinesByType), [2, 6]);
+        assert_eq!(index.related(2, RelationKind::Connects), [6]);
+        assert!(index.related(2, RelationKind::Decomposes).is_empty());
+        assert_eq!(index.relationships_of(2), [10, 11, 13, 14]);
+
+        assert_eq!(
+            RelationKind::from_name("defines-by-type"),
+            Some(RelationKind::DefinesByType)
+        );
+    }
+
+    #[test]
+    fn test_constraint_association() {
+        // Intent (5) precedes RelatingConstraint (6)
+        let content = r#"
+#1=IFCWALL('w',$,'Wall',$,$,$,$,$,$);
+#2=IFCOBJECTIVE('Fire rating',$,.HARD.,$,$,$,$,(),$,.REQUIREMENT.,$);
+#3=IFCRELASSOCIATESCONSTRAINT('r',$,$,$,(#1),'Design',#2);
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let index = RelationshipIndex::build(content, &mut decoder);
+
+        assert_eq!(index.relating(1, RelationKind::Associates), [2]);
+        assert_eq!(index.related(2, RelationKind::Associates), [1]);
+        assert_eq!(index.relationships_of(1), [3]);
+    }
+}
@@ -298,6 +298,14 @@ impl IfcSchema {
         // Swept solids (P0)
         geometry_types.insert(IfcType::IfcExtrudedAreaSolid, GeometryCategory::SweptSolid);
         geometry_types.insert(IfcType::IfcRevolvedAreaSolid, GeometryCategory::SweptSolid);
+        geometry_types.insert(
+            IfcType::IfcExtrudedAreaSolidTapered,
+            GeometryCategory::SweptSolid,
+        );
+        geometry_types.insert(
+            IfcType::IfcRevolvedAreaSolidTapered,
+            GeometryCategory::SweptSolid,
+        );
 
         // Boolean operations (P0)
         geometry_types.insert(IfcType::IfcBooleanResult, GeometryCategory::Boolean);
@@ -309,6 +317,10 @@ impl IfcSchema {
             IfcType::IfcTriangulatedFaceSet,
             GeometryCategory::ExplicitMesh,
         );
+        geometry_types.insert(
+            IfcType::IfcTriangulatedIrregularNetwork,
+            GeometryCategory::ExplicitMesh,
+        );
         geometry_types.insert(IfcType::IfcPolygonalFaceSet, GeometryCategory::ExplicitMesh);
         geometry_types.insert(IfcType::IfcFaceBasedSurfaceModel, GeometryCategory::Surface);
         geometry_types.insert(
@@ -340,6 +352,11 @@ impl IfcSchema {
         profile_types.insert(IfcType::IfcTShapeProfileDef, ProfileCategory::Parametric);
         profile_types.insert(IfcType::IfcCShapeProfileDef, ProfileCategory::Parametric);
         profile_types.insert(IfcType::IfcZShapeProfileDef, ProfileCategory::Parametric);
+        profile_types.insert(
+            IfcType::IfcAsymmetricIShapeProfileDef,
+            ProfileCategory::Parametric,
+        );
+        profile_types.insert(IfcType::IfcTrapeziumProfileDef, ProfileCategory::Parametric);
 
         // Profile types - Arbitrary
         profile_types.insert(
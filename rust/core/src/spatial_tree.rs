@@ -0,0 +1,237 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Spatial structure tree
+//!
+//! Builds the Project → Site → Building → Storey → Element hierarchy from
+//! `IfcRelAggregates` (decomposition into parts) and
+//! `IfcRelContainedInSpatialStructure` (elements placed in a spatial
+//! element), ready for a tree view. Storey elevations are converted to
+//! meters.
+
+use crate::decoder::EntityDecoder;
+use crate::error::Result;
+use crate::generated::IfcType;
+use crate::parser::EntityScanner;
+use crate::schema_gen::AttributeValue;
+use crate::units::extract_length_unit_scale;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// One entity in the spatial tree.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SpatialNode {
+    pub express_id: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub ifc_type: IfcType,
+    pub name: Option<String>,
+    pub global_id: Option<String>,
+    /// Elevation of storeys, in meters.
+    pub elevation: Option<f64>,
+    /// Parts the entity is decomposed into (sites, storeys, spaces, element parts).
+    pub children: Vec<SpatialNode>,
+    /// Elements contained in the spatial element.
+    pub elements: Vec<SpatialNode>,
+}
+
+impl SpatialNode {
+    /// Number of nodes in this subtree, including this one.
+    pub fn node_count(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .chain(&self.elements)
+            .map(SpatialNode::node_count)
+            .sum::<usize>()
+    }
+
+    /// Find a node in this subtree by express ID.
+    pub fn find(&self, express_id: u32) -> Option<&SpatialNode> {
+        if self.express_id == express_id {
+            return Some(self);
+        }
+        self.children
+            .iter()
+            .chain(&self.elements)
+            .find_map(|node| node.find(express_id))
+    }
+
+    /// Nodes from this one down to the node with `express_id`, both
+    /// included.
+    pub fn path_to(&self, express_id: u32) -> Option<Vec<&SpatialNode>> {
+        if self.express_id == express_id {
+            return Some(vec![self]);
+        }
+        self.children
+            .iter()
+            .chain(&self.elements)
+            .find_map(|node| node.path_to(express_id))
+            .map(|mut path| {
+                path.insert(0, self);
+                path
+            })
+    }
+
+    /// Nearest spatial element (site, building, storey, space, ...) above
+    /// the node with `express_id`. For parts of an element (stair flights)
+    /// this is the container of the whole element.
+    pub fn spatial_container(&self, express_id: u32) -> Option<&SpatialNode> {
+        let path = self.path_to(express_id)?;
+        let (_, ancestors) = path.split_last()?;
+        ancestors
+            .iter()
+            .rev()
+            .find(|node| node.ifc_type.is_subtype_of(IfcType::IfcSpatialElement))
+            .copied()
+    }
+}
+
+/// Build the spatial tree rooted at the model's `IfcProject`. Returns `None`
+/// for models without one.
+pub fn build_spatial_tree(
+    content: &str,
+    decoder: &mut EntityDecoder,
+) -> Result<Option<SpatialNode>> {
+    let mut project_id = None;
+    let mut parts: FxHashMap<u32, Vec<u32>> = FxHashMap::default();
+    let mut contained: FxHashMap<u32, Vec<u32>> = FxHashMap::default();
+
+    let mut scanner = EntityScanner::new(content);
+    while let Some((id, type_name, start, end)) = scanner.next_entity() {
+        // IfcRelAggregates: RelatingObject(4), RelatedObjects(5)
+        // IfcRelContainedInSpatialStructure: RelatedElements(4), RelatingStructure(5)
+        let (parent_index, children_index, targets) = match type_name {
+            "IFCPROJECT" => {
+                project_id.get_or_insert(id);
+                continue;
+            }
+            "IFCRELAGGREGATES" => (4, 5, &mut parts),
+            "IFCRELCONTAINEDINSPATIALSTRUCTURE" => (5, 4, &mut contained),
+            _ => continue,
+        };
+        let Ok(rel) = decoder.decode_at_with_id(id, start, end) else {
+            continue;
+        };
+        let Some(parent) = rel.get_ref(parent_index) else {
+            continue;
+        };
+        let children = rel.get_list(children_index).unwrap_or_default();
+        targets
+            .entry(parent)
+            .or_default()
+            .extend(children.iter().filter_map(AttributeValue::as_entity_ref));
+    }
+
+    let Some(project_id) = project_id else {
+        return Ok(None);
+    };
+    let unit_scale = extract_length_unit_scale(decoder, project_id).unwrap_or(1.0);
+    let mut builder = TreeBuilder {
+        parts,
+        contained,
+        unit_scale,
+        visited: FxHashSet::default(),
+    };
+    builder.node(project_id, decoder)
+}
+
+struct TreeBuilder {
+    parts: FxHashMap<u32, Vec<u32>>,
+    contained: FxHashMap<u32, Vec<u32>>,
+    unit_scale: f64,
+    /// Guards against relationship cycles in malformed files
+    visited: FxHashSet<u32>,
+}
+
+impl TreeBuilder {
+    fn node(&mut self, id: u32, decoder: &mut EntityDecoder) -> Result<Option<SpatialNode>> {
+        if !self.visited.insert(id) {
+            return Ok(None);
+        }
+        // IfcRoot: GlobalId(0), OwnerHistory(1), Name(2)
+        let entity = decoder.decode_by_id(id)?;
+        let text = |index: usize| entity.get_string(index).map(str::to_string);
+        // IfcBuildingStorey attr 9: Elevation
+        let elevation = (entity.ifc_type == IfcType::IfcBuildingStorey)
+            .then(|| entity.get_float(9))
+            .flatten()
+            .map(|elevation| elevation * self.unit_scale);
+
+        let mut node = SpatialNode {
+            express_id: id,
+            ifc_type: entity.ifc_type,
+            name: text(2),
+            global_id: text(0),
+            elevation,
+            children: Vec::new(),
+            elements: Vec::new(),
+        };
+        for child_id in self.parts.remove(&id).unwrap_or_default() {
+            // Skip unresolvable references rather than dropping the whole tree
+            if let Ok(Some(child)) = self.node(child_id, decoder) {
+                node.children.push(child);
+            }
+        }
+        for element_id in self.contained.remove(&id).unwrap_or_default() {
+            if let Ok(Some(element)) = self.node(element_id, decoder) {
+                node.elements.push(element);
+            }
+        }
+        // Storeys bottom to top
+        node.children
+            .sort_by(|a, b| match (a.elevation, b.elevation) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                _ => std::cmp::Ordering::Equal,
+            });
+        Ok(Some(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_spatial_tree() {
+        let content = r#"
+#1=IFCPROJECT('p',$,'Project',$,$,$,$,$,#2);
+#2=IFCUNITASSIGNMENT((#3));
+#3=IFCSIUNIT(*,.LENGTHUNIT.,.MILLI.,.METRE.);
+#10=IFCSITE('s',$,'Site',$,$,$,$,$,.ELEMENT.,$,$,$,$,$);
+#11=IFCBUILDING('b',$,'Building',$,$,$,$,$,.ELEMENT.,$,$,$);
+#12=IFCBUILDINGSTOREY('u',$,'Upper',$,$,$,$,$,.ELEMENT.,3000.);
+#13=IFCBUILDINGSTOREY('g',$,'Ground',$,$,$,$,$,.ELEMENT.,0.);
+#20=IFCWALL('w',$,'Wall',$,$,$,$,$,$);
+#21=IFCSTAIR('st',$,'Stair',$,$,$,$,$,$);
+#22=IFCSTAIRFLIGHT('f',$,'Flight',$,$,$,$,$,$,$,$,$);
+#30=IFCRELAGGREGATES('r1',$,$,$,#1,(#10));
+#31=IFCRELAGGREGATES('r2',$,$,$,#10,(#11));
+#32=IFCRELAGGREGATES('r3',$,$,$,#11,(#12,#13));
+#33=IFCRELCONTAINEDINSPATIALSTRUCTURE('r4',$,$,$,(#20,#21),#13);
+#34=IFCRELAGGREGATES('r5',$,$,$,#21,(#22));
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let tree = build_spatial_tree(content, &mut decoder).unwrap().unwrap();
+
+        assert_eq!(tree.express_id, 1);
+        assert_eq!(tree.name.as_deref(), Some("Project"));
+        assert_eq!(tree.node_count(), 8);
+
+        let building = &tree.children[0].children[0];
+        assert_eq!(building.ifc_type, IfcType::IfcBuilding);
+        let storeys: Vec<_> = building.children.iter().map(|s| s.express_id).collect();
+        assert_eq!(storeys, [13, 12]);
+        assert_eq!(building.children[1].elevation, Some(3.0));
+
+        let ground = &building.children[0];
+        assert_eq!(ground.global_id.as_deref(), Some("g"));
+        assert_eq!(ground.elements.len(), 2);
+        assert_eq!(tree.find(22).unwrap().name.as_deref(), Some("Flight"));
+        assert_eq!(ground.elements[1].children[0].express_id, 22);
+        assert_eq!(tree.path_to(22).unwrap().len(), 6);
+        assert_eq!(tree.spatial_container(22).unwrap().express_id, 13);
+        assert_eq!(tree.spatial_container(12).unwrap().express_id, 11);
+        assert!(tree.spatial_container(10).is_none());
+    }
+}
@@ -0,0 +1,637 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! STEP writer
+//!
+//! Writes a model back to STEP text with a set of entity edits applied.
+//! Entities that were not edited are copied verbatim from the source, so the
+//! output only differs where the model changed and nothing is lost to
+//! decoding (header, comments, formatting of untouched entities).
+//!
+//! Edited entities are serialized from [`AttributeValue`]s in the form the
+//! decoder produces them: strings are already STEP-encoded (see
+//! [`encode_string`] for new text) and typed values such as `IFCLABEL('x')`
+//! are `List([String("IFCLABEL"), String("x")])`, recognised by their
+//! defined type name.
+
+use crate::parser::EntityScanner;
+use crate::schema_gen::AttributeValue;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Entity-level changes to a STEP file
+#[derive(Debug, Clone, Default)]
+pub struct StepEdits {
+    /// Replaced or added entities: type name (uppercase) and attributes
+    written: BTreeMap<u32, (String, Vec<AttributeValue>)>,
+    deleted: BTreeSet<u32>,
+}
+
+impl StepEdits {
+    /// Replace entity `id`, or add it if the file has no such entity.
+    pub fn set_entity(&mut self, id: u32, type_name: &str, attributes: Vec<AttributeValue>) {
+        self.deleted.remove(&id);
+        self.written
+            .insert(id, (type_name.to_uppercase(), attributes));
+    }
+
+    /// Remove entity `id` from the output.
+    pub fn delete_entity(&mut self, id: u32) {
+        self.written.remove(&id);
+        self.deleted.insert(id);
+    }
+
+    /// Type name and attributes of a replaced or added entity
+    pub fn entity(&self, id: u32) -> Option<(&str, &[AttributeValue])> {
+        self.written
+            .get(&id)
+            .map(|(type_name, attributes)| (type_name.as_str(), attributes.as_slice()))
+    }
+
+    /// Whether entity `id` is deleted
+    pub fn is_deleted(&self, id: u32) -> bool {
+        self.deleted.contains(&id)
+    }
+
+    /// IDs of replaced, added and deleted entities
+    pub fn ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.written.keys().chain(&self.deleted).copied()
+    }
+
+    /// Number of replaced, added and deleted entities
+    pub fn len(&self) -> usize {
+        self.written.len() + self.deleted.len()
+    }
+
+    /// Whether there are no edits
+    pub fn is_empty(&self) -> bool {
+        self.written.is_empty() && self.deleted.is_empty()
+    }
+
+    /// Discard all edits
+    pub fn clear(&mut self) {
+        self.written.clear();
+        self.deleted.clear();
+    }
+}
+
+/// Write `content` with `edits` applied. Added entities go at the end of the
+/// DATA section, in ID order.
+pub fn write_step(content: &str, edits: &StepEdits) -> String {
+    if edits.is_empty() {
+        return content.to_string();
+    }
+
+    let mut out = String::with_capacity(content.len() + edits.written.len() * 64);
+    let mut copied = 0;
+    let mut seen = BTreeSet::new();
+    let mut scanner = EntityScanner::new(content);
+    while let Some((id, _, start, end)) = scanner.next_entity() {
+        let replacement = edits.written.get(&id);
+        if replacement.is_none() && !edits.deleted.contains(&id) {
+            continue;
+        }
+        out.push_str(&content[copied..start]);
+        copied = end;
+        match replacement {
+            // Later duplicates of a replaced ID are dropped
+            Some((type_name, attributes)) if seen.insert(id) => {
+                out.push_str(&format_entity(id, type_name, attributes));
+            }
+            _ => copied += line_break_len(&content[end..]),
+        }
+    }
+
+    // New entities before the ENDSEC closing the DATA section
+    let data_end = data_section_end(content).max(copied);
+    out.push_str(&content[copied..data_end]);
+    for (&id, (type_name, attributes)) in &edits.written {
+        if !seen.contains(&id) {
+            out.push_str(&format_entity(id, type_name, attributes));
+            out.push('\n');
+        }
+    }
+    out.push_str(&content[data_end..]);
+    out
+}
+
+/// Position of the `ENDSEC;` closing the DATA section (end of content if
+/// there is none)
+fn data_section_end(content: &str) -> usize {
+    let data_start = section_keyword(content, 0, "DATA;").unwrap_or(0);
+    section_keyword(content, data_start, "ENDSEC;").unwrap_or(content.len())
+}
+
+/// Position of the first `keyword` at or after `from` that starts a line
+/// (after optional indentation), so text inside header strings is skipped.
+fn section_keyword(content: &str, from: usize, keyword: &str) -> Option<usize> {
+    let mut line_start = from;
+    for line in content[from..].split_inclusive('\n') {
+        let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+        if line[indent..].starts_with(keyword) {
+            return Some(line_start + indent);
+        }
+        line_start += line.len();
+    }
+    None
+}
+
+/// Length of the line break at the start of `rest`
+fn line_break_len(rest: &str) -> usize {
+    if rest.starts_with("\r\n") {
+        2
+    } else if rest.starts_with('\n') {
+        1
+    } else {
+        0
+    }
+}
+
+/// `#id=TYPE(attributes);`
+pub fn format_entity(id: u32, type_name: &str, attributes: &[AttributeValue]) -> String {
+    let mut out = format!("#{id}={type_name}(");
+    format_list(attributes, &mut out);
+    out.push_str(");");
+    out
+}
+
+/// Append the STEP form of `value` to `out`.
+pub fn format_value(value: &AttributeValue, out: &mut String) {
+    match value {
+        AttributeValue::EntityRef(id) => {
+            let _ = write!(out, "#{id}");
+        }
+        AttributeValue::String(s) => {
+            out.push('\'');
+            out.push_str(s);
+            out.push('\'');
+        }
+        AttributeValue::Integer(i) => {
+            let _ = write!(out, "{i}");
+        }
+        AttributeValue::Float(f) => format_real(*f, out),
+        AttributeValue::Enum(e) => {
+            let _ = write!(out, ".{e}.");
+        }
+        AttributeValue::List(items) => match typed_value(items) {
+            Some((type_name, args)) => {
+                out.push_str(type_name);
+                out.push('(');
+                format_list(args, out);
+                out.push(')');
+            }
+            None => {
+                out.push('(');
+                format_list(items, out);
+                out.push(')');
+            }
+        },
+        AttributeValue::Null => out.push('$'),
+        AttributeValue::Derived => out.push('*'),
+    }
+}
+
+fn format_list(items: &[AttributeValue], out: &mut String) {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        format_value(item, out);
+    }
+}
+
+/// Type name and arguments of a decoded typed value (`IFCLABEL('x')`).
+///
+/// The decoder keeps no marker for these, so a list is taken as a typed
+/// value only when it starts with a known defined type name (see
+/// [`is_defined_type_name`]); a plain list such as `('IFC2X3','x')` is not.
+pub(crate) fn typed_value(items: &[AttributeValue]) -> Option<(&str, &[AttributeValue])> {
+    let (AttributeValue::String(type_name), args) = items.split_first()? else {
+        return None;
+    };
+    (!args.is_empty() && is_defined_type_name(type_name)).then_some((type_name.as_str(), args))
+}
+
+/// Defined types that appear as typed values (members of `IfcValue` and
+/// the other value selects, IFC2X3 to IFC4X3), uppercase and sorted.
+const DEFINED_TYPE_NAMES: &[&str] = &[
+    "IFCABSORBEDDOSEMEASURE",
+    "IFCACCELERATIONMEASURE",
+    "IFCAMOUNTOFSUBSTANCEMEASURE",
+    "IFCANGULARVELOCITYMEASURE",
+    "IFCAREADENSITYMEASURE",
+    "IFCAREAMEASURE",
+    "IFCBINARY",
+    "IFCBOOLEAN",
+    "IFCBOXALIGNMENT",
+    "IFCCOMPLEXNUMBER",
+    "IFCCOMPOUNDPLANEANGLEMEASURE",
+    "IFCCONTEXTDEPENDENTMEASURE",
+    "IFCCOUNTMEASURE",
+    "IFCCURVATUREMEASURE",
+    "IFCDATE",
+    "IFCDATETIME",
+    "IFCDESCRIPTIVEMEASURE",
+    "IFCDOSEEQUIVALENTMEASURE",
+    "IFCDURATION",
+    "IFCDYNAMICVISCOSITYMEASURE",
+    "IFCELECTRICCAPACITANCEMEASURE",
+    "IFCELECTRICCHARGEMEASURE",
+    "IFCELECTRICCONDUCTANCEMEASURE",
+    "IFCELECTRICCURRENTMEASURE",
+    "IFCELECTRICRESISTANCEMEASURE",
+    "IFCELECTRICVOLTAGEMEASURE",
+    "IFCENERGYMEASURE",
+    "IFCFORCEMEASURE",
+    "IFCFREQUENCYMEASURE",
+    "IFCGLOBALLYUNIQUEID",
+    "IFCHEATFLUXDENSITYMEASURE",
+    "IFCHEATINGVALUEMEASURE",
+    "IFCIDENTIFIER",
+    "IFCILLUMINANCEMEASURE",
+    "IFCINDUCTANCEMEASURE",
+    "IFCINTEGER",
+    "IFCINTEGERCOUNTRATEMEASURE",
+    "IFCIONCONCENTRATIONMEASURE",
+    "IFCISOTHERMALMOISTURECAPACITYMEASURE",
+    "IFCKINEMATICVISCOSITYMEASURE",
+    "IFCLABEL",
+    "IFCLENGTHMEASURE",
+    "IFCLINEARFORCEMEASURE",
+    "IFCLINEARMOMENTMEASURE",
+    "IFCLINEARSTIFFNESSMEASURE",
+    "IFCLINEARVELOCITYMEASURE",
+    "IFCLOGICAL",
+    "IFCLUMINOUSFLUXMEASURE",
+    "IFCLUMINOUSINTENSITYDISTRIBUTIONMEASURE",
+    "IFCLUMINOUSINTENSITYMEASURE",
+    "IFCMAGNETICFLUXDENSITYMEASURE",
+    "IFCMAGNETICFLUXMEASURE",
+    "IFCMASSDENSITYMEASURE",
+    "IFCMASSFLOWRATEMEASURE",
+    "IFCMASSMEASURE",
+    "IFCMASSPERLENGTHMEASURE",
+    "IFCMODULUSOFELASTICITYMEASURE",
+    "IFCMODULUSOFLINEARSUBGRADEREACTIONMEASURE",
+    "IFCMODULUSOFROTATIONALSUBGRADEREACTIONMEASURE",
+    "IFCMODULUSOFSUBGRADEREACTIONMEASURE",
+    "IFCMOISTUREDIFFUSIVITYMEASURE",
+    "IFCMOLECULARWEIGHTMEASURE",
+    "IFCMOMENTOFINERTIAMEASURE",
+    "IFCMONETARYMEASURE",
+    "IFCNONNEGATIVELENGTHMEASURE",
+    "IFCNORMALISEDRATIOMEASURE",
+    "IFCNUMERICMEASURE",
+    "IFCPARAMETERVALUE",
+    "IFCPHMEASURE",
+    "IFCPLANARFORCEMEASURE",
+    "IFCPLANEANGLEMEASURE",
+    "IFCPOSITIVEINTEGER",
+    "IFCPOSITIVELENGTHMEASURE",
+    "IFCPOSITIVEPLANEANGLEMEASURE",
+    "IFCPOSITIVERATIOMEASURE",
+    "IFCPOWERMEASURE",
+    "IFCPRESSUREMEASURE",
+    "IFCPROPERTYSETDEFINITIONSET",
+    "IFCRADIOACTIVITYMEASURE",
+    "IFCRATIOMEASURE",
+    "IFCREAL",
+    "IFCROTATIONALFREQUENCYMEASURE",
+    "IFCROTATIONALMASSMEASURE",
+    "IFCROTATIONALSTIFFNESSMEASURE",
+    "IFCSECTIONALAREAINTEGRALMEASURE",
+    "IFCSECTIONMODULUSMEASURE",
+    "IFCSHEARMODULUSMEASURE",
+    "IFCSOLIDANGLEMEASURE",
+    "IFCSOUNDPOWERLEVELMEASURE",
+    "IFCSOUNDPOWERMEASURE",
+    "IFCSOUNDPRESSURELEVELMEASURE",
+    "IFCSOUNDPRESSUREMEASURE",
+    "IFCSPECIFICHEATCAPACITYMEASURE",
+    "IFCSPECULAREXPONENT",
+    "IFCSPECULARROUGHNESS",
+    "IFCTEMPERATUREGRADIENTMEASURE",
+    "IFCTEMPERATURERATEOFCHANGEMEASURE",
+    "IFCTEXT",
+    "IFCTHERMALADMITTANCEMEASURE",
+    "IFCTHERMALCONDUCTIVITYMEASURE",
+    "IFCTHERMALEXPANSIONCOEFFICIENTMEASURE",
+    "IFCTHERMALRESISTANCEMEASURE",
+    "IFCTHERMALTRANSMITTANCEMEASURE",
+    "IFCTHERMODYNAMICTEMPERATUREMEASURE",
+    "IFCTIME",
+    "IFCTIMEMEASURE",
+    "IFCTIMESTAMP",
+    "IFCTORQUEMEASURE",
+    "IFCURIREFERENCE",
+    "IFCVAPORPERMEABILITYMEASURE",
+    "IFCVOLUMEMEASURE",
+    "IFCVOLUMETRICFLOWRATEMEASURE",
+    "IFCWARPINGCONSTANTMEASURE",
+    "IFCWARPINGMOMENTMEASURE",
+];
+
+/// Whether `name` (case-insensitive) is a defined type that can wrap a
+/// value, such as `IFCLENGTHMEASURE` or `IfcLabel`.
+pub fn is_defined_type_name(name: &str) -> bool {
+    DEFINED_TYPE_NAMES
+        .binary_search(&name.to_ascii_uppercase().as_str())
+        .is_ok()
+}
+
+/// REAL with the decimal point STEP requires (`1.`, `2.5E-7`)
+fn format_real(value: f64, out: &mut String) {
+    if !value.is_finite() {
+        out.push_str("0.");
+        return;
+    }
+    let text = format!("{value:?}");
+    match text.split_once('e') {
+        Some((mantissa, exponent)) => {
+            out.push_str(mantissa);
+            if !mantissa.contains('.') {
+                out.push('.');
+            }
+            out.push('E');
+            out.push_str(exponent);
+        }
+        None => out.push_str(&text),
+    }
+}
+
+/// Encode text as the contents of a STEP string: quotes and backslashes
+/// are escaped, characters outside printable ASCII become `\X2\...\X0\`.
+pub fn encode_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut wide = false;
+    for c in text.chars() {
+        if (' '..='~').contains(&c) {
+            if wide {
+                out.push_str("\\X0\\");
+                wide = false;
+            }
+            match c {
+                '\'' => out.push_str("''"),
+                '\\' => out.push_str("\\\\"),
+                _ => out.push(c),
+            }
+        } else {
+            if !wide {
+                out.push_str("\\X2\\");
+                wide = true;
+            }
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                let _ = write!(out, "{unit:04X}");
+            }
+        }
+    }
+    if wide {
+        out.push_str("\\X0\\");
+    }
+    out
+}
+
+/// Decode the contents of a STEP string to text, the inverse of
+/// [`encode_string`]: `''` and `\\` are unescaped, and `\X2\`/`\X4\`
+/// (UTF-16/UTF-32), `\X\hh` and `\S\c` (ISO 8859-1) are decoded. Code page
+/// switches (`\PA\`) are dropped; malformed escapes are kept as written.
+pub fn decode_string(text: &str) -> String {
+    if !text.contains(['\'', '\\']) {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(['\'', '\\']) {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if let Some(after) = rest.strip_prefix("''") {
+            out.push('\'');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("\\\\") {
+            out.push('\\');
+            rest = after;
+        } else if let Some((decoded, after)) = decode_escape(rest) {
+            out.push_str(&decoded);
+            rest = after;
+        } else {
+            out.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decode the escape directive at the start of `rest`, returning the text
+/// it stands for and what follows it
+fn decode_escape(rest: &str) -> Option<(String, &str)> {
+    for (directive, width) in [("\\X2\\", 4), ("\\X4\\", 8)] {
+        let Some(after) = rest.strip_prefix(directive) else {
+            continue;
+        };
+        let (hex, after) = after.split_once("\\X0\\")?;
+        if !hex.is_ascii() || hex.len() % width != 0 {
+            return None;
+        }
+        let mut units = (0..hex.len())
+            .step_by(width)
+            .map(|i| u32::from_str_radix(&hex[i..i + width], 16).ok());
+        let text = if width == 4 {
+            let units = units
+                .map(|u| u.map(|u| u as u16))
+                .collect::<Option<Vec<u16>>>()?;
+            String::from_utf16(&units).ok()?
+        } else {
+            units.try_fold(String::new(), |mut text, u| {
+                text.push(char::from_u32(u?)?);
+                Some(text)
+            })?
+        };
+        return Some((text, after));
+    }
+    if let Some(after) = rest.strip_prefix("\\X\\") {
+        let byte = u8::from_str_radix(after.get(..2)?, 16).ok()?;
+        return Some((char::from(byte).to_string(), &after[2..]));
+    }
+    if let Some(after) = rest.strip_prefix("\\S\\") {
+        let c = after.chars().next().filter(char::is_ascii)?;
+        return Some((char::from(c as u8 + 0x80).to_string(), &after[1..]));
+    }
+    // Code page switch (`\PA\`)
+    let after = rest.strip_prefix("\\P")?;
+    if !after.as_bytes().first()?.is_ascii_uppercase() {
+        return None;
+    }
+    Some((String::new(), after[1..].strip_prefix('\\')?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::EntityDecoder;
+
+    #[test]
+    fn test_format_value() {
+        let value = AttributeValue::List(vec![
+            AttributeValue::EntityRef(5),
+            AttributeValue::Float(2.0),
+            AttributeValue::Float(1.5e-7),
+            AttributeValue::Enum("T".into()),
+            AttributeValue::List(vec![
+                AttributeValue::String("IFCLABEL".into()),
+                AttributeValue::String("it''s".into()),
+            ]),
+            AttributeValue::Null,
+        ]);
+        let mut out = String::new();
+        format_value(&value, &mut out);
+        assert_eq!(out, "(#5,2.0,1.5E-7,.T.,IFCLABEL('it''s'),$)");
+
+        assert_eq!(
+            encode_string("Wand 'A' Größe"),
+            "Wand ''A'' Gr\\X2\\00F600DF\\X0\\e"
+        );
+    }
+
+    #[test]
+    fn test_decode_string() {
+        for text in ["Wand 'A' Größe", "C:\\path", "😀 emoji", "plain"] {
+            assert_eq!(decode_string(&encode_string(text)), text);
+        }
+        assert_eq!(decode_string("\\X4\\0001F600\\X0\\"), "😀");
+        assert_eq!(decode_string("Stra\\X\\DFe"), "Straße");
+        assert_eq!(decode_string("\\PA\\\\S\\Vrin"), "Örin");
+        // Malformed escapes are kept
+        assert_eq!(decode_string("\\X2\\00F"), "\\X2\\00F");
+        assert_eq!(decode_string("it's"), "it's");
+    }
+
+    #[test]
+    fn test_write_step() {
+        let content = "ISO-10303-21;\nHEADER;\nENDSEC;\nDATA;\n\
+#1=IFCWALL('a',$,'Wall',$,$,$,$,$,$);\n\
+#2=IFCDOOR('b',$,'Door',$,$,$,$,$);\n\
+#3=IFCSLAB('c',$,'Slab',$,$,$,$,$,$);\n\
+ENDSEC;\nEND-ISO-10303-21;\n";
+
+        assert_eq!(write_step(content, &StepEdits::default()), content);
+
+        let mut decoder = EntityDecoder::new(content);
+        let wall = decoder.decode_by_id(1).unwrap();
+        let mut attributes = wall.attributes.clone();
+        attributes[2] = AttributeValue::String(encode_string("Wall's new name"));
+
+        let mut edits = StepEdits::default();
+        edits.set_entity(1, "IfcWall", attributes);
+        edits.delete_entity(2);
+        edits.set_entity(
+            10,
+            "IFCPROPERTYSINGLEVALUE",
+            vec![
+                AttributeValue::String("Note".into()),
+                AttributeValue::Null,
+                AttributeValue::List(vec![
+                    AttributeValue::String("IFCTEXT".into()),
+                    AttributeValue::String("x".into()),
+                ]),
+                AttributeValue::Null,
+            ],
+        );
+        assert_eq!(edits.len(), 3);
+
+        let written = write_step(content, &edits);
+        assert_eq!(
+            written,
+            "ISO-10303-21;\nHEADER;\nENDSEC;\nDATA;\n\
+#1=IFCWALL('a',$,'Wall''s new name',$,$,$,$,$,$);\n\
+#3=IFCSLAB('c',$,'Slab',$,$,$,$,$,$);\n\
+#10=IFCPROPERTYSINGLEVALUE('Note',$,IFCTEXT('x'),$);\n\
+ENDSEC;\nEND-ISO-10303-21;\n"
+        );
+
+        // The written file decodes like the edited model
+        let mut decoder = EntityDecoder::new(&written);
+        let wall = decoder.decode_by_id(1).unwrap();
+        assert_eq!(wall.get_string(2), Some("Wall''s new name"));
+        assert!(decoder.decode_by_id(2).is_err());
+    }
+
+    #[test]
+    fn test_write_step_additions_only() {
+        let content = "ISO-10303-21;\nHEADER;\n\
+FILE_NAME('a.ifc','2024-01-01',(''),(''),'','','');\nENDSEC;\nDATA;\n\
+#1=IFCWALL('a',$,'Wall',$,$,$,$,$,$);\n\
+ENDSEC;\nEND-ISO-10303-21;\n";
+
+        let mut edits = StepEdits::default();
+        edits.set_entity(
+            2,
+            "IFCPROPERTYSINGLEVALUE",
+            vec![
+                AttributeValue::String("Note".into()),
+                AttributeValue::Null,
+                AttributeValue::Null,
+                AttributeValue::Null,
+            ],
+        );
+        assert_eq!(
+            write_step(content, &edits),
+            "ISO-10303-21;\nHEADER;\n\
+FILE_NAME('a.ifc','2024-01-01',(''),(''),'','','');\nENDSEC;\nDATA;\n\
+#1=IFCWALL('a',$,'Wall',$,$,$,$,$,$);\n\
+#2=IFCPROPERTYSINGLEVALUE('Note',$,$,$);\n\
+ENDSEC;\nEND-ISO-10303-21;\n"
+        );
+    }
+
+    #[test]
+    fn test_write_step_round_trips_plain_lists_and_header_keywords() {
+        // `DATA;` inside a header string and a label list starting with an IFC-like name
+        let content = "ISO-10303-21;\nHEADER;\n\
+FILE_DESCRIPTION(('ViewDefinition [DATA;ENDSEC;]'),'2;1');\nENDSEC;\nDATA;\n\
+#1=IFCCLASSIFICATION('src','1',$,'Name',$,$,('IFC2X3','x'));\n\
+ENDSEC;\nEND-ISO-10303-21;\n";
+
+        let mut decoder = EntityDecoder::new(content);
+        let original = decoder.decode_by_id(1).unwrap().attributes.clone();
+        let mut edits = StepEdits::default();
+        edits.set_entity(1, "IFCCLASSIFICATION", original);
+        edits.set_entity(
+            2,
+            "IFCPROPERTYSINGLEVALUE",
+            vec![
+                AttributeValue::String("Note".into()),
+                AttributeValue::Null,
+                AttributeValue::Null,
+                AttributeValue::Null,
+            ],
+        );
+
+        let written = write_step(content, &edits);
+        assert_eq!(
+            written,
+            "ISO-10303-21;\nHEADER;\n\
+FILE_DESCRIPTION(('ViewDefinition [DATA;ENDSEC;]'),'2;1');\nENDSEC;\nDATA;\n\
+#1=IFCCLASSIFICATION('src','1',$,'Name',$,$,('IFC2X3','x'));\n\
+#2=IFCPROPERTYSINGLEVALUE('Note',$,$,$);\n\
+ENDSEC;\nEND-ISO-10303-21;\n"
+        );
+        let mut decoder = EntityDecoder::new(&written);
+        let references = decoder.decode_by_id(1).unwrap().get(6).cloned().unwrap();
+        let mut out = String::new();
+        format_value(&references, &mut out);
+        assert_eq!(out, "('IFC2X3','x')");
+    }
+
+    #[test]
+    fn test_defined_type_names() {
+        assert!(is_defined_type_name("IFCLENGTHMEASURE"));
+        assert!(is_defined_type_name("IfcLabel"));
+        assert!(is_defined_type_name("IFCPHMEASURE"));
+        assert!(!is_defined_type_name("IFC2X3"));
+        assert!(!is_defined_type_name("IFCWALL"));
+        assert!(!is_defined_type_name("LABEL"));
+    }
+}
@@ -0,0 +1,115 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Entity type index
+//!
+//! Groups express IDs by entity type in one scan, so type filters and
+//! entity statistics don't need to rescan the file.
+
+use crate::generated::IfcType;
+use crate::parser::EntityScanner;
+use rustc_hash::FxHashMap;
+
+/// Express IDs grouped by entity type
+#[derive(Debug, Clone, Default)]
+pub struct TypeIndex {
+    /// STEP type name (uppercase) -> express IDs in file order
+    ids: FxHashMap<String, Vec<u32>>,
+}
+
+impl TypeIndex {
+    /// Scan `content` and group its entities by type.
+    pub fn build(content: &str) -> Self {
+        let mut ids: FxHashMap<String, Vec<u32>> = FxHashMap::default();
+        let mut scanner = EntityScanner::new(content);
+        while let Some((id, type_name, _, _)) = scanner.next_entity() {
+            match ids.get_mut(type_name) {
+                Some(type_ids) => type_ids.push(id),
+                None => {
+                    ids.insert(type_name.to_string(), vec![id]);
+                }
+            }
+        }
+        Self { ids }
+    }
+
+    /// Express IDs of entities of `type_name` (case-insensitive), optionally
+    /// including entities of its subtypes. Sorted ascending.
+    pub fn ids_of_type(&self, type_name: &str, include_subtypes: bool) -> Vec<u32> {
+        let upper = type_name.to_uppercase();
+        let mut ids = if include_subtypes {
+            let ifc_type = IfcType::from_str(&upper);
+            self.ids
+                .iter()
+                .filter(|(name, _)| {
+                    **name == upper || IfcType::from_str(name).is_subtype_of(ifc_type)
+                })
+                .flat_map(|(_, ids)| ids.iter().copied())
+                .collect()
+        } else {
+            self.ids.get(&upper).cloned().unwrap_or_default()
+        };
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Number of entities of each type, as (display name, count) sorted by
+    /// descending count. Unknown types keep their STEP name.
+    pub fn census(&self) -> Vec<(&str, usize)> {
+        let mut census: Vec<(&str, usize)> = self
+            .ids
+            .iter()
+            .map(|(name, ids)| {
+                let display = match IfcType::from_str(name) {
+                    IfcType::Unknown(_) => name.as_str(),
+                    ifc_type => ifc_type.name(),
+                };
+                (display, ids.len())
+            })
+            .collect();
+        census.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        census
+    }
+
+    /// Total number of entities
+    pub fn len(&self) -> usize {
+        self.ids.values().map(Vec::len).sum()
+    }
+
+    /// Whether no entities were indexed
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_index() {
+        let content = r#"
+#1=IFCWALL('a',$,$,$,$,$,$,$,$);
+#2=IFCDOOR('b',$,$,$,$,$,$,$);
+#3=IFCWALLSTANDARDCASE('c',$,$,$,$,$,$,$,$);
+#4=IFCWALL('d',$,$,$,$,$,$,$,$);
+#5=IFCCUSTOMTHING('e');
+"#;
+        let index = TypeIndex::build(content);
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.ids_of_type("IfcWall", false), [1, 4]);
+        assert_eq!(index.ids_of_type("IfcWall", true), [1, 3, 4]);
+        assert_eq!(index.ids_of_type("IfcBuiltElement", true), [1, 2, 3, 4]);
+        assert!(index.ids_of_type("IfcSlab", true).is_empty());
+        assert_eq!(
+            index.census(),
+            [
+                ("IfcWall", 2),
+                ("IFCCUSTOMTHING", 1),
+                ("IfcDoor", 1),
+                ("IfcWallStandardCase", 1)
+            ]
+        );
+    }
+}
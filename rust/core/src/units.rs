@@ -260,6 +260,72 @@ pub fn extract_length_unit_scale(decoder: &mut EntityDecoder, project_id: u32) -
     Ok(1.0)
 }
 
+/// Extract plane angle unit scale factor from IFC file
+///
+/// Follows the chain: IFCPROJECT → IFCUNITASSIGNMENT → PLANEANGLEUNIT, an
+/// IFCSIUNIT (radian) or IFCCONVERSIONBASEDUNIT (typically degree).
+///
+/// # Returns
+/// Radians per file angle unit (π/180 for degrees), or `None` when the
+/// project assigns no plane angle unit
+pub fn extract_plane_angle_unit_scale(
+    decoder: &mut EntityDecoder,
+    project_id: u32,
+) -> Result<Option<f64>> {
+    let project = decoder.decode_by_id(project_id)?;
+    if project.ifc_type.as_str() != "IFCPROJECT" {
+        return Ok(None);
+    }
+
+    // Attribute 8: UnitsInContext (IFCUNITASSIGNMENT)
+    let Some(units_ref) = project.get_ref(8) else {
+        return Ok(None);
+    };
+    let unit_assignment = decoder.decode_by_id(units_ref)?;
+    let unit_refs: Vec<u32> = unit_assignment
+        .get_list(0)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|unit| unit.as_entity_ref())
+        .collect();
+
+    for unit_ref in unit_refs {
+        let Ok(unit_entity) = decoder.decode_by_id(unit_ref) else {
+            continue;
+        };
+        if unit_entity.get(1).and_then(|attr| attr.as_enum()) != Some("PLANEANGLEUNIT") {
+            continue;
+        }
+
+        match unit_entity.ifc_type.as_str() {
+            // IFCSIUNIT: [0] Dimensions, [1] UnitType, [2] Prefix, [3] Name (.RADIAN.)
+            "IFCSIUNIT" => {
+                let prefix = unit_entity.get(2).and_then(|attr| attr.as_enum());
+                return Ok(Some(prefix.map_or(1.0, get_si_prefix_multiplier)));
+            }
+            // IFCCONVERSIONBASEDUNIT: [0] Dimensions, [1] UnitType, [2] Name,
+            // [3] ConversionFactor (IFCMEASUREWITHUNIT in radians)
+            "IFCCONVERSIONBASEDUNIT" => {
+                let factor = unit_entity
+                    .get_ref(3)
+                    .and_then(|id| decoder.decode_by_id(id).ok())
+                    .and_then(|measure| measure.get_float(0))
+                    .filter(|factor| *factor > 0.0);
+                if let Some(factor) = factor {
+                    return Ok(Some(factor));
+                }
+                let name = unit_entity.get_string(2).unwrap_or_default();
+                if name.eq_ignore_ascii_case("DEGREE") {
+                    return Ok(Some(std::f64::consts::PI / 180.0));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -382,4 +448,32 @@ END-ISO-10303-21;
             scale
         );
     }
+
+    #[test]
+    fn test_extract_plane_angle_unit() {
+        let content = r#"
+#1=IFCPROJECT('guid',$,'Test',$,$,$,$,$,#2);
+#2=IFCUNITASSIGNMENT((#3,#4));
+#3=IFCSIUNIT(*,.LENGTHUNIT.,.MILLI.,.METRE.);
+#4=IFCCONVERSIONBASEDUNIT(#5,.PLANEANGLEUNIT.,'DEGREE',#6);
+#5=IFCDIMENSIONALEXPONENTS(0,0,0,0,0,0,0);
+#6=IFCMEASUREWITHUNIT(IFCPLANEANGLEMEASURE(0.0174532925199433),#7);
+#7=IFCSIUNIT(*,.PLANEANGLEUNIT.,$,.RADIAN.);
+#11=IFCPROJECT('guid',$,'Test',$,$,$,$,$,#12);
+#12=IFCUNITASSIGNMENT((#3,#7));
+#21=IFCPROJECT('guid',$,'Test',$,$,$,$,$,#22);
+#22=IFCUNITASSIGNMENT((#3));
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let degree = extract_plane_angle_unit_scale(&mut decoder, 1).unwrap();
+        assert!((degree.unwrap() - 1f64.to_radians()).abs() < 1e-12);
+        assert_eq!(
+            extract_plane_angle_unit_scale(&mut decoder, 11).unwrap(),
+            Some(1.0)
+        );
+        assert_eq!(
+            extract_plane_angle_unit_scale(&mut decoder, 21).unwrap(),
+            None
+        );
+    }
 }
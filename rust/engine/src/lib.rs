@@ -151,6 +151,7 @@ pub fn stream_ifc_text_with_bootstrap(
             include_presentation_layers: options.include_presentation_layers,
             emit_quick_metadata_bootstrap: options.emit_quick_metadata_bootstrap,
             retain_emitted_meshes: options.retain_emitted_meshes,
+            ..ProcessingStreamingOptions::default()
         },
         |meshes, processed, total| {
         on_chunk(GeometryChunk {
@@ -0,0 +1,543 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! IFC4X3 alignment curve evaluation
+//!
+//! Alignments are layered: an `IfcCompositeCurve` of `IfcCurveSegment`s is
+//! the horizontal layout, an `IfcGradientCurve` adds a vertical profile over
+//! it (segments drawn in distance/height space) and an
+//! `IfcSegmentedReferenceCurve` adds cant on top of a gradient curve.
+//! [`ProfileProcessor::get_alignment_curve`] evaluates the stack into an
+//! [`AlignmentCurve`] stationed by horizontal distance, so linear placements
+//! and sweeps along road and rail alignments sit at the right elevation.
+//!
+//! Each `IfcCurveSegment` takes the part of its parent curve from
+//! SegmentStart over SegmentLength and moves it so that its start point and
+//! tangent coincide with the segment Placement. Lines, circles, clothoids
+//! and polynomial curves are evaluated; other spirals fall back to a
+//! straight segment of the same length.
+
+use crate::linear_placement::AlignmentCurve;
+use crate::profiles::{ProfileProcessor, MAX_CURVE_DEPTH};
+use crate::tessellation::TessellationConfig;
+use crate::transform::{parse_cartesian_point, parse_direction};
+use crate::{Error, Point2, Point3, Result, Vector2};
+use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcType};
+
+/// Points probed along a curved segment to size its tessellation
+const PROBE_SAMPLES: usize = 16;
+
+/// Simpson intervals per radian of clothoid heading change
+const CLOTHOID_STEPS_PER_RADIAN: f64 = 32.0;
+
+impl ProfileProcessor {
+    /// Evaluate an alignment curve into points with stations and cant.
+    ///
+    /// Gradient and segmented reference curves are stationed along their
+    /// horizontal base curve; any other curve is stationed by its length.
+    pub fn get_alignment_curve(
+        &self,
+        curve: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<AlignmentCurve> {
+        self.alignment_curve_with_depth(curve, decoder, 0)
+    }
+
+    /// Alignment curve with depth tracking shared with curve tessellation
+    pub(crate) fn alignment_curve_with_depth(
+        &self,
+        curve: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        depth: u32,
+    ) -> Result<AlignmentCurve> {
+        if depth > MAX_CURVE_DEPTH {
+            return Err(Error::geometry(format!(
+                "Curve nesting depth {} exceeds limit {}",
+                depth, MAX_CURVE_DEPTH
+            )));
+        }
+
+        // IfcGradientCurve / IfcSegmentedReferenceCurve:
+        // Segments, SelfIntersect, BaseCurve, EndPoint
+        if !matches!(
+            curve.ifc_type,
+            IfcType::IfcGradientCurve | IfcType::IfcSegmentedReferenceCurve
+        ) {
+            let points = self.get_curve_points_with_depth(curve, decoder, depth)?;
+            return Ok(AlignmentCurve::from_polyline(points));
+        }
+
+        let base_attr = curve
+            .get(2)
+            .ok_or_else(|| Error::geometry(format!("{} missing BaseCurve", curve.ifc_type)))?;
+        let base_curve = decoder
+            .resolve_ref(base_attr)?
+            .ok_or_else(|| Error::geometry("Failed to resolve BaseCurve".to_string()))?;
+        let base = self.alignment_curve_with_depth(&base_curve, decoder, depth + 1)?;
+
+        let segments = match curve.get(0) {
+            Some(attr) => decoder.resolve_ref_list(attr)?,
+            None => Vec::new(),
+        };
+
+        if curve.ifc_type == IfcType::IfcGradientCurve {
+            let mut profile: Vec<(f64, f64)> = Vec::new();
+            for segment in segments
+                .iter()
+                .filter(|s| s.ifc_type == IfcType::IfcCurveSegment)
+            {
+                for point in curve_segment_points(segment, decoder, self.tessellation())? {
+                    if profile
+                        .last()
+                        .is_none_or(|&(station, _)| point.x > station + 1e-9)
+                    {
+                        profile.push((point.x, point.y));
+                    }
+                }
+            }
+            return Ok(base.with_gradient(&profile));
+        }
+
+        // Cant is taken from the roll of each segment placement at its
+        // start station (and of EndPoint), interpolated linearly between
+        let mut cant: Vec<(f64, f64)> = Vec::new();
+        for segment in segments
+            .iter()
+            .filter(|s| s.ifc_type == IfcType::IfcCurveSegment)
+        {
+            if let Some(sample) = cant_sample(segment.get(1), decoder)? {
+                cant.push(sample);
+            }
+        }
+        if let Some(sample) = cant_sample(curve.get(3), decoder)? {
+            cant.push(sample);
+        }
+        cant.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(base.with_cant(&cant))
+    }
+}
+
+/// Tessellate an IfcCurveSegment in the plane of its placement.
+///
+/// IfcCurveSegment: Transition, Placement, SegmentStart, SegmentLength,
+/// ParentCurve. Horizontal segments yield plan coordinates, vertical
+/// (gradient) segments yield `(distance along, height)`.
+pub(crate) fn curve_segment_points(
+    segment: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+    tessellation: &TessellationConfig,
+) -> Result<Vec<Point2<f64>>> {
+    let placement = match segment.get(1) {
+        Some(attr) => decoder.resolve_ref(attr)?,
+        None => None,
+    };
+    let placement = match placement {
+        Some(placement) => Frame2::from_placement(&placement, decoder)?,
+        None => Frame2::default(),
+    };
+
+    let start = segment.get_float(2).unwrap_or(0.0);
+    let length = segment
+        .get_float(3)
+        .ok_or_else(|| Error::geometry("CurveSegment missing SegmentLength".to_string()))?;
+
+    let parent_attr = segment
+        .get(4)
+        .ok_or_else(|| Error::geometry("CurveSegment missing ParentCurve".to_string()))?;
+    let parent = decoder
+        .resolve_ref(parent_attr)?
+        .ok_or_else(|| Error::geometry("Failed to resolve ParentCurve".to_string()))?;
+    let parent = ParentCurve::from_entity(&parent, decoder)?;
+
+    Ok(parent.sample(start, length, &placement, tessellation))
+}
+
+/// `(station, roll)` of an IfcAxis2Placement3D used by a cant segment:
+/// the station is the location's X and the roll turns Z about X
+fn cant_sample(
+    attr: Option<&ifc_lite_core::AttributeValue>,
+    decoder: &mut EntityDecoder,
+) -> Result<Option<(f64, f64)>> {
+    let placement = match attr {
+        Some(attr) if !attr.is_null() => decoder.resolve_ref(attr)?,
+        _ => None,
+    };
+    let Some(placement) = placement.filter(|p| p.ifc_type == IfcType::IfcAxis2Placement3D) else {
+        return Ok(None);
+    };
+
+    let station = parse_cartesian_point(&placement, decoder, 0)?.x;
+    let roll = match placement.get(1) {
+        Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+            Some(axis) => {
+                let axis = parse_direction(&axis)?;
+                (-axis.y).atan2(axis.z)
+            }
+            None => 0.0,
+        },
+        _ => 0.0,
+    };
+    Ok(Some((station, roll)))
+}
+
+/// Planar frame: origin and unit X axis (Y is X turned left)
+#[derive(Debug, Clone, Copy)]
+struct Frame2 {
+    origin: Point2<f64>,
+    x_axis: Vector2<f64>,
+}
+
+impl Default for Frame2 {
+    fn default() -> Self {
+        Self {
+            origin: Point2::origin(),
+            x_axis: Vector2::x(),
+        }
+    }
+}
+
+impl Frame2 {
+    /// IfcAxis2Placement2D (Location, RefDirection) or IfcAxis2Placement3D
+    /// (Location, Axis, RefDirection), projected onto its XY plane
+    fn from_placement(placement: &DecodedEntity, decoder: &mut EntityDecoder) -> Result<Self> {
+        let location = parse_cartesian_point(placement, decoder, 0)?;
+        let ref_index = if placement.ifc_type == IfcType::IfcAxis2Placement3D {
+            2
+        } else {
+            1
+        };
+        let x_axis = match placement.get(ref_index) {
+            Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                Some(dir) => {
+                    let dir = parse_direction(&dir)?;
+                    Vector2::new(dir.x, dir.y)
+                        .try_normalize(1e-12)
+                        .unwrap_or_else(Vector2::x)
+                }
+                None => Vector2::x(),
+            },
+            _ => Vector2::x(),
+        };
+        Ok(Self {
+            origin: Point2::new(location.x, location.y),
+            x_axis,
+        })
+    }
+
+    /// Frame positioned by the `Position` (attribute 0) of a parent curve
+    fn from_position(curve: &DecodedEntity, decoder: &mut EntityDecoder) -> Result<Self> {
+        match curve.get(0) {
+            Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                Some(placement) => Self::from_placement(&placement, decoder),
+                None => Ok(Self::default()),
+            },
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn y_axis(&self) -> Vector2<f64> {
+        Vector2::new(-self.x_axis.y, self.x_axis.x)
+    }
+
+    fn point(&self, local: Point2<f64>) -> Point2<f64> {
+        self.origin + self.x_axis * local.x + self.y_axis() * local.y
+    }
+
+    fn vector(&self, local: Vector2<f64>) -> Vector2<f64> {
+        self.x_axis * local.x + self.y_axis() * local.y
+    }
+}
+
+/// Parent curve of an IfcCurveSegment, parameterised by arc length
+/// (polynomial curves by their own parameter)
+#[derive(Debug, Clone)]
+enum ParentCurve {
+    /// IfcLine: Pnt, Dir
+    Line {
+        origin: Point2<f64>,
+        direction: Vector2<f64>,
+    },
+    /// IfcCircle: Position, Radius
+    Circle { frame: Frame2, radius: f64 },
+    /// IfcClothoid: Position, ClothoidConstant
+    Clothoid { frame: Frame2, constant: f64 },
+    /// IfcPolynomialCurve: Position, CoefficientsX, CoefficientsY, CoefficientsZ
+    Polynomial {
+        frame: Frame2,
+        x: Vec<f64>,
+        y: Vec<f64>,
+    },
+}
+
+impl ParentCurve {
+    fn from_entity(curve: &DecodedEntity, decoder: &mut EntityDecoder) -> Result<Self> {
+        Ok(match curve.ifc_type {
+            IfcType::IfcLine => {
+                let origin = parse_cartesian_point(curve, decoder, 0)?;
+                // IfcVector: Orientation, Magnitude
+                let direction = match curve.get(1) {
+                    Some(attr) => match decoder.resolve_ref(attr)? {
+                        Some(vector) => match vector.get(0) {
+                            Some(attr) => match decoder.resolve_ref(attr)? {
+                                Some(dir) => parse_direction(&dir)?,
+                                None => crate::Vector3::x(),
+                            },
+                            None => crate::Vector3::x(),
+                        },
+                        None => crate::Vector3::x(),
+                    },
+                    None => crate::Vector3::x(),
+                };
+                Self::Line {
+                    origin: Point2::new(origin.x, origin.y),
+                    direction: Vector2::new(direction.x, direction.y)
+                        .try_normalize(1e-12)
+                        .unwrap_or_else(Vector2::x),
+                }
+            }
+            IfcType::IfcCircle => Self::Circle {
+                frame: Frame2::from_position(curve, decoder)?,
+                radius: curve
+                    .get_float(1)
+                    .filter(|r| *r > 0.0)
+                    .ok_or_else(|| Error::geometry("Circle missing Radius".to_string()))?,
+            },
+            IfcType::IfcClothoid => Self::Clothoid {
+                frame: Frame2::from_position(curve, decoder)?,
+                constant: curve
+                    .get_float(1)
+                    .filter(|a| a.abs() > 1e-12)
+                    .ok_or_else(|| {
+                        Error::geometry("Clothoid missing ClothoidConstant".to_string())
+                    })?,
+            },
+            IfcType::IfcPolynomialCurve => {
+                let coefficients = |index: usize| -> Vec<f64> {
+                    curve
+                        .get(index)
+                        .and_then(|a| a.as_list())
+                        .map(|l| l.iter().filter_map(|v| v.as_float()).collect())
+                        .unwrap_or_default()
+                };
+                Self::Polynomial {
+                    frame: Frame2::from_position(curve, decoder)?,
+                    x: coefficients(1),
+                    y: coefficients(2),
+                }
+            }
+            // Other spirals: a straight segment of the same length
+            _ => Self::Line {
+                origin: Point2::origin(),
+                direction: Vector2::x(),
+            },
+        })
+    }
+
+    /// Point at arc length (or parameter) `s`
+    fn point(&self, s: f64) -> Point2<f64> {
+        match self {
+            Self::Line { origin, direction } => origin + direction * s,
+            Self::Circle { frame, radius } => {
+                let angle = s / radius;
+                frame.point(Point2::new(radius * angle.cos(), radius * angle.sin()))
+            }
+            Self::Clothoid { frame, constant } => {
+                // x = ∫cos θ, y = ∫sin θ with θ(t) = t²/(2A²), mirrored for A < 0
+                let heading = |t: f64| t * t / (2.0 * constant * constant);
+                let intervals =
+                    ((heading(s) * CLOTHOID_STEPS_PER_RADIAN).ceil() as usize).max(8) * 2;
+                let h = s / intervals as f64;
+                let mut sum = Vector2::zeros();
+                for i in 0..=intervals {
+                    let weight = match i {
+                        0 => 1.0,
+                        i if i == intervals => 1.0,
+                        i if i % 2 == 1 => 4.0,
+                        _ => 2.0,
+                    };
+                    let theta = heading(i as f64 * h);
+                    sum += Vector2::new(theta.cos(), theta.sin()) * weight;
+                }
+                let local = sum * h / 3.0;
+                frame.point(Point2::new(local.x, local.y * constant.signum()))
+            }
+            Self::Polynomial { frame, x, y } => {
+                let eval = |c: &[f64]| c.iter().rev().fold(0.0, |acc, &k| acc * s + k);
+                let px = if x.is_empty() { s } else { eval(x) };
+                frame.point(Point2::new(px, eval(y)))
+            }
+        }
+    }
+
+    /// Unit tangent at `s`, in the direction of increasing `s`
+    fn tangent(&self, s: f64) -> Vector2<f64> {
+        let local = match self {
+            Self::Line { direction, .. } => return *direction,
+            Self::Circle { frame, radius } => {
+                let angle = s / radius;
+                frame.vector(Vector2::new(-angle.sin(), angle.cos()))
+            }
+            Self::Clothoid { frame, constant } => {
+                let theta = s * s / (2.0 * constant * constant);
+                frame.vector(Vector2::new(theta.cos(), theta.sin() * constant.signum()))
+            }
+            Self::Polynomial { frame, x, y } => {
+                let derivative = |c: &[f64]| {
+                    c.iter()
+                        .enumerate()
+                        .skip(1)
+                        .rev()
+                        .fold(0.0, |acc, (i, &k)| acc * s + i as f64 * k)
+                };
+                let dx = if x.is_empty() { 1.0 } else { derivative(x) };
+                frame.vector(Vector2::new(dx, derivative(y)))
+            }
+        };
+        local.try_normalize(1e-12).unwrap_or_else(Vector2::x)
+    }
+
+    /// Points from `start` over `length`, moved so the segment starts at
+    /// the placement origin heading along its X axis
+    fn sample(
+        &self,
+        start: f64,
+        length: f64,
+        placement: &Frame2,
+        tessellation: &TessellationConfig,
+    ) -> Vec<Point2<f64>> {
+        let origin = self.point(start);
+        let heading = Frame2 {
+            origin,
+            x_axis: self.tangent(start) * if length < 0.0 { -1.0 } else { 1.0 },
+        };
+        let local = |s: f64| {
+            let d = self.point(s) - origin;
+            placement.point(Point2::new(
+                d.dot(&heading.x_axis),
+                d.dot(&heading.y_axis()),
+            ))
+        };
+
+        let segments = if matches!(self, Self::Line { .. }) {
+            1
+        } else {
+            let probes: Vec<Point3<f64>> = (0..=PROBE_SAMPLES)
+                .map(|i| {
+                    let p = local(start + length * i as f64 / PROBE_SAMPLES as f64);
+                    Point3::new(p.x, p.y, 0.0)
+                })
+                .collect();
+            tessellation.polygon_segments(&probes).max(1)
+        };
+
+        (0..=segments)
+            .map(|i| local(start + length * i as f64 / segments as f64))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_placement::{linear_placement_transform, DistanceExpression};
+    use ifc_lite_core::IfcSchema;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_clothoid_heading_and_mirror() {
+        let tessellation = TessellationConfig::default();
+        let clothoid = ParentCurve::Clothoid {
+            frame: Frame2::default(),
+            constant: 10.0,
+        };
+        // At s = A the heading is 0.5 rad and the curve turns left
+        assert!((clothoid.tangent(10.0).y.atan2(clothoid.tangent(10.0).x) - 0.5).abs() < 1e-9);
+        let points = clothoid.sample(0.0, 10.0, &Frame2::default(), &tessellation);
+        // Fresnel integrals of t²/200 over [0, 10]
+        let end = points.last().unwrap();
+        assert!(
+            (end - Point2::new(9.752877, 1.637140)).norm() < 1e-5,
+            "{end:?}"
+        );
+
+        let mirrored = ParentCurve::Clothoid {
+            frame: Frame2::default(),
+            constant: -10.0,
+        };
+        let mirrored_end = *mirrored
+            .sample(0.0, 10.0, &Frame2::default(), &tessellation)
+            .last()
+            .unwrap();
+        assert!((mirrored_end - Point2::new(end.x, -end.y)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_gradient_and_cant_on_linear_placement() {
+        // 100 m straight alignment along X; vertical profile rises at 2%
+        // from height 10 for 50 m, then runs level. A 2° cant applies
+        // throughout.
+        let content = r#"
+#1=IFCCARTESIANPOINT((0.,0.));
+#2=IFCDIRECTION((1.,0.));
+#3=IFCAXIS2PLACEMENT2D(#1,#2);
+#4=IFCVECTOR(#2,1.);
+#5=IFCLINE(#1,#4);
+#6=IFCCURVESEGMENT(.CONTINUOUS.,#3,IFCLENGTHMEASURE(0.),IFCLENGTHMEASURE(100.),#5);
+#7=IFCCOMPOSITECURVE((#6),.F.);
+#10=IFCCARTESIANPOINT((0.,10.));
+#11=IFCDIRECTION((0.9998,0.019996));
+#12=IFCAXIS2PLACEMENT2D(#10,#11);
+#13=IFCCURVESEGMENT(.CONTINUOUS.,#12,IFCLENGTHMEASURE(0.),IFCLENGTHMEASURE(50.0099990002),#5);
+#14=IFCCARTESIANPOINT((50.,11.));
+#15=IFCAXIS2PLACEMENT2D(#14,#2);
+#16=IFCCURVESEGMENT(.CONTINUOUS.,#15,IFCLENGTHMEASURE(0.),IFCLENGTHMEASURE(50.),#5);
+#17=IFCGRADIENTCURVE((#13,#16),.F.,#7,$);
+#20=IFCCARTESIANPOINT((0.,0.,0.));
+#21=IFCDIRECTION((0.,-0.0348995,0.9993908));
+#22=IFCDIRECTION((1.,0.,0.));
+#23=IFCAXIS2PLACEMENT3D(#20,#21,#22);
+#24=IFCCURVESEGMENT(.CONTINUOUS.,#23,IFCLENGTHMEASURE(0.),IFCLENGTHMEASURE(100.),#5);
+#25=IFCSEGMENTEDREFERENCECURVE((#24),.F.,#17,$);
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let processor = ProfileProcessor::new(IfcSchema::new());
+
+        let gradient = decoder.decode_by_id(17).unwrap();
+        let curve = processor
+            .get_alignment_curve(&gradient, &mut decoder)
+            .unwrap();
+        let (point, tangent, cant) = curve.frame_at(25.0).unwrap();
+        assert!(
+            (point - Point3::new(25.0, 0.0, 10.5)).norm() < 1e-3,
+            "{point:?}"
+        );
+        assert!((tangent.z - 0.02).abs() < 1e-3);
+        assert_eq!(cant, 0.0);
+        let (point, _, _) = curve.frame_at(80.0).unwrap();
+        assert!((point.z - 11.0).abs() < 1e-3);
+
+        // The swept directrix of a gradient curve keeps its elevation
+        let points = processor.get_curve_points(&gradient, &mut decoder).unwrap();
+        assert!((points.last().unwrap().z - 11.0).abs() < 1e-3);
+
+        let reference = decoder.decode_by_id(25).unwrap();
+        let curve = processor
+            .get_alignment_curve(&reference, &mut decoder)
+            .unwrap();
+        let expression = DistanceExpression {
+            distance_along: 75.0,
+            offset_lateral: 1.0,
+            ..Default::default()
+        };
+        let m = linear_placement_transform(&curve, &expression, None, None).unwrap();
+        let origin = m.transform_point(&Point3::origin());
+        let roll = 2.0_f64.to_radians();
+        assert!(
+            (origin - Point3::new(75.0, roll.cos(), 11.0 + roll.sin())).norm() < 1e-3,
+            "{origin:?}"
+        );
+        let up = m.transform_vector(&Vector3::z());
+        assert!((up - Vector3::new(0.0, -roll.sin(), roll.cos())).norm() < 1e-3);
+    }
+}
@@ -7,12 +7,19 @@
 //! This module provides efficient 2D polygon boolean operations using the i_overlay crate.
 //! It is used to subtract void footprints from profiles before extrusion, which is much
 //! more efficient and reliable than performing 3D CSG operations on finished geometry.
+//!
+//! All operations use the non-zero fill rule on consistently oriented contours, so
+//! overlapping voids merge instead of cancelling out, and self-touching contours keep
+//! their filled area. Void vertices within a small tolerance of the profile boundary
+//! are snapped onto it first so openings flush with an edge do not leave slivers.
 
 use crate::error::{Error, Result};
 use crate::profile::Profile2D;
 use i_overlay::core::fill_rule::FillRule;
 use i_overlay::core::overlay_rule::OverlayRule;
-use i_overlay::float::single::SingleFloatOverlay;
+use i_overlay::core::solver::Solver;
+use i_overlay::float::filter::ContourFilter;
+use i_overlay::float::overlay::FloatOverlay;
 use nalgebra::Point2;
 
 /// Epsilon for floating point comparisons in 2D operations
@@ -22,15 +29,22 @@ const EPSILON_2D: f64 = 1e-9;
 /// Minimum area threshold - polygons smaller than this are considered degenerate
 const MIN_AREA_THRESHOLD: f64 = 1e-10;
 
+/// Snap distance for void vertices, relative to the profile's bounding-box diagonal
+const SNAP_TOLERANCE_FRACTION: f64 = 1e-6;
+
+/// Result pieces smaller than this fraction of the input profile area are dropped
+const SLIVER_AREA_FRACTION: f64 = 1e-9;
+
 /// Perform 2D boolean difference: profile - void_contour
 ///
 /// Takes a profile and subtracts a void contour from it, returning a new profile
 /// with the void added as a hole (or modifying the outer boundary if the void
-/// cuts through it).
+/// cuts through it). If the void splits the profile, only the largest piece is
+/// returned; use [`subtract_voids_2d`] to keep all pieces.
 ///
 /// # Arguments
 /// * `profile` - The base profile to subtract from
-/// * `void_contour` - The void footprint to subtract (should be counter-clockwise)
+/// * `void_contour` - The void footprint to subtract (any winding)
 ///
 /// # Returns
 /// * `Ok(Profile2D)` - The resulting profile with the void subtracted
@@ -42,31 +56,13 @@ pub fn subtract_2d(profile: &Profile2D, void_contour: &[Point2<f64>]) -> Result<
         ));
     }
 
-    if profile.outer.len() < 3 {
-        return Err(Error::InvalidProfile(
-            "Profile must have at least 3 vertices".to_string(),
-        ));
-    }
-
-    // Convert profile to i_overlay format
-    // Subject is the profile (outer boundary + holes)
-    let subject = profile_to_paths(profile);
-
-    // Clip is the void contour
-    let clip = vec![contour_to_path(void_contour)];
-
-    // Perform boolean difference using i_overlay
-    // Result is Vec<Vec<Vec<[f64; 2]>>> - Vec of shapes, each shape is Vec of contours
-    let result = subject.overlay(&clip, OverlayRule::Difference, FillRule::EvenOdd);
-
-    // Convert result back to Profile2D (take first shape if multiple)
-    shapes_to_profile(&result)
+    largest_piece(subtract_voids_2d(profile, &[void_contour.to_vec()])?)
 }
 
 /// Perform 2D boolean difference with multiple voids at once
 ///
 /// More efficient than calling subtract_2d multiple times as it performs
-/// a single boolean operation.
+/// a single boolean operation. Returns the largest resulting piece.
 ///
 /// # Arguments
 /// * `profile` - The base profile to subtract from
@@ -78,28 +74,96 @@ pub fn subtract_multiple_2d(
     profile: &Profile2D,
     void_contours: &[Vec<Point2<f64>>],
 ) -> Result<Profile2D> {
-    if void_contours.is_empty() {
+    if void_contours.iter().all(|c| c.len() < 3) {
         return Ok(profile.clone());
     }
 
-    // Filter out invalid contours
-    let valid_contours: Vec<_> = void_contours.iter().filter(|c| c.len() >= 3).collect();
+    largest_piece(subtract_voids_2d(profile, void_contours)?)
+}
+
+/// Subtract voids from a profile, keeping every resulting piece
+///
+/// Voids may overlap each other, touch or cross the outer boundary, and be
+/// self-touching. When voids split the profile (e.g. a full-height opening in
+/// a wall), each piece is returned as its own profile, largest first. An empty
+/// result means the voids cover the whole profile.
+///
+/// # Arguments
+/// * `profile` - The base profile to subtract from
+/// * `void_contours` - List of void footprints to subtract (any winding)
+///
+/// # Returns
+/// * `Ok(Vec<Profile2D>)` - The remaining pieces, each with its own holes
+/// * `Err` - If the profile itself is degenerate
+pub fn subtract_voids_2d(
+    profile: &Profile2D,
+    void_contours: &[Vec<Point2<f64>>],
+) -> Result<Vec<Profile2D>> {
+    if profile.outer.len() < 3 {
+        return Err(Error::InvalidProfile(
+            "Profile must have at least 3 vertices".to_string(),
+        ));
+    }
 
+    let valid_contours: Vec<_> = void_contours.iter().filter(|c| c.len() >= 3).collect();
     if valid_contours.is_empty() {
-        return Ok(profile.clone());
+        return Ok(vec![profile.clone()]);
     }
 
-    // Convert profile to i_overlay format
+    // Subject is the profile (outer boundary + holes)
     let subject = profile_to_paths(profile);
 
-    // Convert all void contours - union them first if multiple
-    let clip: Vec<Vec<[f64; 2]>> = valid_contours.iter().map(|c| contour_to_path(c)).collect();
+    // Clip is the union of all voids: CCW winding + NonZero fill merges overlaps
+    let tolerance = contour_bounds(&profile.outer)
+        .map(|(min, max)| (max - min).norm() * SNAP_TOLERANCE_FRACTION)
+        .unwrap_or(0.0);
+    let clip: Vec<Vec<[f64; 2]>> = valid_contours
+        .iter()
+        .map(|c| contour_to_path(&ensure_ccw(&snap_to_boundary(c, profile, tolerance))))
+        .collect();
 
-    // Perform boolean difference
-    let result = subject.overlay(&clip, OverlayRule::Difference, FillRule::EvenOdd);
+    let min_area =
+        (compute_signed_area(&profile.outer).abs() * SLIVER_AREA_FRACTION).max(MIN_AREA_THRESHOLD);
+    let result = overlay(&subject, &clip, OverlayRule::Difference, min_area);
 
-    // Convert result back to Profile2D
-    shapes_to_profile(&result)
+    Ok(shapes_to_profiles(&result))
+}
+
+/// Subtract one set of profiles from another, keeping every resulting piece
+///
+/// Both sides may hold several disjoint pieces with holes, as returned by
+/// [`subtract_voids_2d`]. Used to find where two stacked extrusion layers
+/// differ, i.e. where a cap is needed between them.
+pub fn subtract_profiles(subject: &[Profile2D], clip: &[Profile2D]) -> Vec<Profile2D> {
+    let subject_paths: Vec<Vec<[f64; 2]>> = subject
+        .iter()
+        .filter(|p| p.outer.len() >= 3)
+        .flat_map(profile_to_paths)
+        .collect();
+    if subject_paths.is_empty() {
+        return Vec::new();
+    }
+    let clip_paths: Vec<Vec<[f64; 2]>> = clip
+        .iter()
+        .filter(|p| p.outer.len() >= 3)
+        .flat_map(profile_to_paths)
+        .collect();
+    if clip_paths.is_empty() {
+        return subject.to_vec();
+    }
+
+    let area: f64 = subject
+        .iter()
+        .map(|p| compute_signed_area(&p.outer).abs())
+        .sum();
+    let min_area = (area * SLIVER_AREA_FRACTION).max(MIN_AREA_THRESHOLD);
+    let result = overlay(
+        &subject_paths,
+        &clip_paths,
+        OverlayRule::Difference,
+        min_area,
+    );
+    shapes_to_profiles(&result)
 }
 
 /// Union multiple void contours into a single shape
@@ -115,14 +179,14 @@ pub fn union_contours(contours: &[Vec<Point2<f64>>]) -> Result<Vec<Vec<Point2<f6
     }
 
     // Start with first contour as subject
-    let subject: Vec<Vec<[f64; 2]>> = vec![contour_to_path(&contours[0])];
+    let subject: Vec<Vec<[f64; 2]>> = vec![contour_to_path(&ensure_ccw(&contours[0]))];
 
     // Collect all other contours as clip
     let clip: Vec<Vec<[f64; 2]>> = contours
         .iter()
         .skip(1)
         .filter(|c| c.len() >= 3)
-        .map(|c| contour_to_path(c))
+        .map(|c| contour_to_path(&ensure_ccw(c)))
         .collect();
 
     if clip.is_empty() {
@@ -130,7 +194,7 @@ pub fn union_contours(contours: &[Vec<Point2<f64>>]) -> Result<Vec<Vec<Point2<f6
     }
 
     // Perform union
-    let result = subject.overlay(&clip, OverlayRule::Union, FillRule::EvenOdd);
+    let result = overlay(&subject, &clip, OverlayRule::Union, MIN_AREA_THRESHOLD);
 
     // Convert back to Point2 format - flatten all shapes and contours
     let mut all_contours = Vec::new();
@@ -294,15 +358,33 @@ pub fn bounds_overlap(
 // Internal Helper Functions
 // ============================================================================
 
+/// Run a non-zero overlay, dropping output contours below `min_area`
+fn overlay(
+    subject: &[Vec<[f64; 2]>],
+    clip: &[Vec<[f64; 2]>],
+    rule: OverlayRule,
+    min_area: f64,
+) -> Vec<Vec<Vec<[f64; 2]>>> {
+    let filter = ContourFilter {
+        min_area,
+        simplify: true,
+    };
+    FloatOverlay::with_subj_and_clip(subject, clip).overlay_with_filter_and_solver(
+        rule,
+        FillRule::NonZero,
+        filter,
+        Solver::AUTO,
+    )
+}
+
 /// Convert Profile2D to i_overlay path format
 fn profile_to_paths(profile: &Profile2D) -> Vec<Vec<[f64; 2]>> {
     let mut paths = Vec::with_capacity(1 + profile.holes.len());
 
-    // Add outer boundary (must be counter-clockwise for i_overlay)
+    // Outer boundary counter-clockwise, holes clockwise so NonZero fill cuts them out
     let outer = ensure_ccw(&profile.outer);
     paths.push(contour_to_path(&outer));
 
-    // Add holes (must be clockwise for i_overlay, but we'll use EvenOdd fill rule)
     for hole in &profile.holes {
         let hole_cw = ensure_cw(hole);
         paths.push(contour_to_path(&hole_cw));
@@ -316,62 +398,92 @@ fn contour_to_path(contour: &[Point2<f64>]) -> Vec<[f64; 2]> {
     contour.iter().map(|p| [p.x, p.y]).collect()
 }
 
-/// Convert i_overlay result shapes back to Profile2D
+/// Move void vertices lying within `tolerance` of a profile edge onto that edge
 ///
-/// i_overlay returns Vec<Vec<Vec<[f64; 2]>>> where:
-/// - Outer Vec: list of shapes
-/// - Middle Vec: list of contours per shape (first is outer, rest are holes)
-/// - Inner Vec: list of points per contour
-fn shapes_to_profile(shapes: &[Vec<Vec<[f64; 2]>>]) -> Result<Profile2D> {
-    if shapes.is_empty() {
-        return Err(Error::InvalidProfile(
-            "Boolean operation resulted in empty geometry".to_string(),
-        ));
+/// Openings modelled flush with a wall end are rarely exactly coincident after
+/// placement transforms; without snapping they leave zero-width slivers.
+fn snap_to_boundary(
+    contour: &[Point2<f64>],
+    profile: &Profile2D,
+    tolerance: f64,
+) -> Vec<Point2<f64>> {
+    if tolerance <= 0.0 {
+        return contour.to_vec();
     }
 
-    // Find the largest shape by outer boundary area
-    let mut best_shape_idx = 0;
-    let mut largest_area = 0.0f64;
+    let rings = std::iter::once(&profile.outer).chain(&profile.holes);
+    let edges: Vec<(Point2<f64>, Point2<f64>)> = rings
+        .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+        .collect();
 
-    for (idx, shape) in shapes.iter().enumerate() {
-        if shape.is_empty() {
-            continue;
-        }
-        // First contour in shape is the outer boundary
-        let outer_contour: Vec<Point2<f64>> =
-            shape[0].iter().map(|p| Point2::new(p[0], p[1])).collect();
-        let area = compute_signed_area(&outer_contour).abs();
-        if area > largest_area {
-            largest_area = area;
-            best_shape_idx = idx;
-        }
-    }
+    contour
+        .iter()
+        .map(|&p| {
+            let mut best = (tolerance, p);
+            for &(a, b) in &edges {
+                let ab = b - a;
+                let len_sq = ab.norm_squared();
+                let t = if len_sq > 0.0 {
+                    ((p - a).dot(&ab) / len_sq).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let q = a + ab * t;
+                let d = (p - q).norm();
+                if d < best.0 {
+                    best = (d, q);
+                }
+            }
+            best.1
+        })
+        .collect()
+}
 
-    let best_shape = &shapes[best_shape_idx];
-    if best_shape.is_empty() {
-        return Err(Error::InvalidProfile(
-            "Selected shape has no contours".to_string(),
-        ));
-    }
+/// Convert i_overlay result shapes back to profiles, largest first
+///
+/// i_overlay returns Vec<Vec<Vec<[f64; 2]>>> where:
+/// - Outer Vec: list of shapes
+/// - Middle Vec: list of contours per shape (first is outer, rest are holes)
+/// - Inner Vec: list of points per contour
+fn shapes_to_profiles(shapes: &[Vec<Vec<[f64; 2]>>]) -> Vec<Profile2D> {
+    let to_points = |contour: &Vec<[f64; 2]>| -> Vec<Point2<f64>> {
+        contour.iter().map(|p| Point2::new(p[0], p[1])).collect()
+    };
 
-    // First contour is outer boundary
-    let outer: Vec<Point2<f64>> = best_shape[0]
+    let mut pieces: Vec<(f64, Profile2D)> = shapes
         .iter()
-        .map(|p| Point2::new(p[0], p[1]))
+        .filter_map(|shape| {
+            let outer = to_points(shape.first()?);
+            if !is_valid_contour(&outer) {
+                return None;
+            }
+            let holes = shape
+                .iter()
+                .skip(1)
+                .map(to_points)
+                .filter(|hole| is_valid_contour(hole))
+                .map(|hole| ensure_cw(&hole))
+                .collect();
+            let area = compute_signed_area(&outer).abs();
+            Some((
+                area,
+                Profile2D {
+                    outer: ensure_ccw(&outer),
+                    holes,
+                },
+            ))
+        })
         .collect();
-    let outer = ensure_ccw(&outer);
-
-    // Rest are holes
-    let mut holes = Vec::new();
-    for contour in best_shape.iter().skip(1) {
-        let hole: Vec<Point2<f64>> = contour.iter().map(|p| Point2::new(p[0], p[1])).collect();
 
-        if is_valid_contour(&hole) {
-            holes.push(ensure_cw(&hole));
-        }
-    }
+    pieces.sort_by(|a, b| b.0.total_cmp(&a.0));
+    pieces.into_iter().map(|(_, profile)| profile).collect()
+}
 
-    Ok(Profile2D { outer, holes })
+/// Pick the largest piece of a subtraction result
+fn largest_piece(pieces: Vec<Profile2D>) -> Result<Profile2D> {
+    pieces.into_iter().next().ok_or_else(|| {
+        Error::InvalidProfile("Boolean operation resulted in empty geometry".to_string())
+    })
 }
 
 #[cfg(test)]
@@ -476,6 +588,60 @@ mod tests {
         assert_eq!(result.holes.len(), 2);
     }
 
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> Vec<Point2<f64>> {
+        vec![
+            Point2::new(x0, y0),
+            Point2::new(x1, y0),
+            Point2::new(x1, y1),
+            Point2::new(x0, y1),
+        ]
+    }
+
+    fn net_area(profile: &Profile2D) -> f64 {
+        compute_signed_area(&profile.outer)
+            + profile
+                .holes
+                .iter()
+                .map(|h| compute_signed_area(h))
+                .sum::<f64>()
+    }
+
+    #[test]
+    fn test_subtract_voids_2d_overlapping_and_touching() {
+        // 10x3 wall elevation
+        let profile = Profile2D::new(rect(0.0, 0.0, 10.0, 3.0));
+
+        // Two overlapping openings (opposite windings) merge into one hole
+        let overlapping = vec![rect(2.0, 1.0, 4.0, 2.0), {
+            let mut r = rect(3.0, 1.0, 5.0, 2.0);
+            r.reverse();
+            r
+        }];
+        let pieces = subtract_voids_2d(&profile, &overlapping).unwrap();
+        assert_eq!(pieces.len(), 1);
+        assert_eq!(pieces[0].holes.len(), 1);
+        assert!((net_area(&pieces[0]) - 27.0).abs() < 1e-6);
+
+        // Door touching the bottom edge, offset by float noise: notch, no sliver
+        let door = vec![rect(6.0, -1e-9, 7.0, 2.0)];
+        let pieces = subtract_voids_2d(&profile, &door).unwrap();
+        assert_eq!(pieces.len(), 1);
+        assert!(pieces[0].holes.is_empty());
+        assert_eq!(pieces[0].outer.len(), 8);
+        assert!((net_area(&pieces[0]) - 28.0).abs() < 1e-6);
+
+        // Full-height opening splits the wall into two pieces, largest first
+        let split = vec![rect(3.0, 0.0, 4.0, 3.0), rect(3.5, 1.0, 4.5, 2.0)];
+        let pieces = subtract_voids_2d(&profile, &split).unwrap();
+        assert_eq!(pieces.len(), 2);
+        assert!((net_area(&pieces[0]) - 17.5).abs() < 1e-6);
+        assert!((net_area(&pieces[1]) - 9.0).abs() < 1e-6);
+
+        // Largest-piece wrapper keeps the old single-profile contract
+        let largest = subtract_multiple_2d(&profile, &split).unwrap();
+        assert!((net_area(&largest) - 17.5).abs() < 1e-6);
+    }
+
     #[test]
     fn test_point_in_contour() {
         let contour = vec![
@@ -565,4 +731,41 @@ mod tests {
         assert!(bounds_overlap(&a_min, &a_max, &b_min, &b_max));
         assert!(!bounds_overlap(&a_min, &a_max, &c_min, &c_max));
     }
+
+    #[test]
+    fn test_subtract_profiles() {
+        let square = |x0: f64, y0: f64, size: f64| {
+            Profile2D::new(vec![
+                Point2::new(x0, y0),
+                Point2::new(x0 + size, y0),
+                Point2::new(x0 + size, y0 + size),
+                Point2::new(x0, y0 + size),
+            ])
+        };
+        let total_area = |pieces: &[Profile2D]| -> f64 {
+            pieces
+                .iter()
+                .map(|p| {
+                    compute_signed_area(&p.outer)
+                        - p.holes
+                            .iter()
+                            .map(|h| compute_signed_area(h).abs())
+                            .sum::<f64>()
+                })
+                .sum()
+        };
+
+        // A layer with a hole minus the full layer leaves nothing
+        let holed =
+            subtract_voids_2d(&square(0.0, 0.0, 4.0), &[square(1.0, 1.0, 2.0).outer]).unwrap();
+        assert!(subtract_profiles(&holed, &[square(0.0, 0.0, 4.0)]).is_empty());
+
+        // The full layer minus the holed one is exactly the hole
+        let cap = subtract_profiles(&[square(0.0, 0.0, 4.0)], &holed);
+        assert_eq!(cap.len(), 1);
+        assert!((total_area(&cap) - 4.0).abs() < 1e-6);
+
+        // Empty clip keeps the subject
+        assert_eq!(subtract_profiles(&holed, &[]).len(), 1);
+    }
 }
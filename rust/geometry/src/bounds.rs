@@ -0,0 +1,275 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Axis-aligned and oriented bounds of router output
+//!
+//! [`ModelBounds`] collects per-element AABBs and their union in one pass over
+//! the meshes, for camera framing and model extent reports. [`Obb`] fits a
+//! tighter oriented box via PCA of the vertices, for rotated elements where
+//! the AABB overstates the footprint.
+//!
+//! Unlike [`ElementBounds`](crate::ElementBounds), which is estimated from
+//! the IFC definitions before meshing, these are computed from the mesh
+//! vertices in the router's coordinate space.
+
+use crate::mesh::Mesh;
+use nalgebra::{Matrix3, Point3, SymmetricEigen, Vector3};
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Point3<f64>,
+    pub max: Point3<f64>,
+}
+
+impl Aabb {
+    /// Box around a mesh's vertices, `None` for an empty mesh
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        let mut points = mesh
+            .positions
+            .chunks_exact(3)
+            .map(|p| Point3::new(p[0] as f64, p[1] as f64, p[2] as f64));
+        let first = points.next()?;
+        Some(points.fold(Self::from_point(first), |aabb, p| aabb.with_point(&p)))
+    }
+
+    /// Degenerate box at a point
+    pub fn from_point(point: Point3<f64>) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// Grow to contain a point
+    pub fn with_point(self, point: &Point3<f64>) -> Self {
+        Self {
+            min: self.min.inf(point),
+            max: self.max.sup(point),
+        }
+    }
+
+    /// Smallest box containing both
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    pub fn center(&self) -> Point3<f64> {
+        nalgebra::center(&self.min, &self.max)
+    }
+
+    pub fn size(&self) -> Vector3<f64> {
+        self.max - self.min
+    }
+
+    pub fn volume(&self) -> f64 {
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+
+    /// Radius of the bounding sphere around the center, for camera framing
+    pub fn radius(&self) -> f64 {
+        0.5 * self.size().norm()
+    }
+
+    pub fn contains(&self, point: &Point3<f64>) -> bool {
+        (0..3).all(|axis| self.min[axis] <= point[axis] && point[axis] <= self.max[axis])
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|axis| self.min[axis] <= other.max[axis] && other.min[axis] <= self.max[axis])
+    }
+}
+
+/// Oriented bounding box. A point inside is `center + Σ axes[i] * t_i` with
+/// `|t_i| <= half_extents[i]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Obb {
+    pub center: Point3<f64>,
+    pub half_extents: Vector3<f64>,
+    /// Orthonormal, right-handed box axes
+    pub axes: [Vector3<f64>; 3],
+}
+
+impl Obb {
+    /// Axis-aligned box as an OBB
+    pub fn from_aabb(aabb: &Aabb) -> Self {
+        Self {
+            center: aabb.center(),
+            half_extents: aabb.size() * 0.5,
+            axes: [Vector3::x(), Vector3::y(), Vector3::z()],
+        }
+    }
+
+    /// Fit a box along the principal axes of the mesh vertices.
+    ///
+    /// PCA is only a heuristic (vertex density skews it), so the AABB is
+    /// returned instead whenever it is the smaller box.
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        let aabb = Aabb::from_mesh(mesh)?;
+        let points: Vec<Point3<f64>> = mesh
+            .positions
+            .chunks_exact(3)
+            .map(|p| Point3::new(p[0] as f64, p[1] as f64, p[2] as f64))
+            .collect();
+
+        // Centre on the AABB centre first to keep the covariance well
+        // conditioned for georeferenced coordinates
+        let origin = aabb.center();
+        let n = points.len() as f64;
+        let mean = points.iter().map(|p| p - origin).sum::<Vector3<f64>>() / n;
+        let covariance = points.iter().fold(Matrix3::zeros(), |acc, p| {
+            let d = p - origin - mean;
+            acc + d * d.transpose()
+        }) / n;
+
+        let eigen = SymmetricEigen::new(covariance);
+        let mut axes = [0, 1, 2].map(|i| eigen.eigenvectors.column(i).into_owned());
+        if axes
+            .iter()
+            .any(|a| !a.iter().all(|c| c.is_finite()) || a.norm() < 0.5)
+        {
+            return Some(Self::from_aabb(&aabb));
+        }
+        axes[2] = axes[0].cross(&axes[1]).normalize();
+
+        let mut min = Vector3::repeat(f64::MAX);
+        let mut max = Vector3::repeat(f64::MIN);
+        for p in &points {
+            let d = p - origin;
+            for (i, axis) in axes.iter().enumerate() {
+                let t = d.dot(axis);
+                min[i] = min[i].min(t);
+                max[i] = max[i].max(t);
+            }
+        }
+        let half_extents = (max - min) * 0.5;
+        let mid = (max + min) * 0.5;
+        let obb = Self {
+            center: origin + axes[0] * mid[0] + axes[1] * mid[1] + axes[2] * mid[2],
+            half_extents,
+            axes,
+        };
+
+        Some(if obb.volume() < aabb.volume() {
+            obb
+        } else {
+            Self::from_aabb(&aabb)
+        })
+    }
+
+    pub fn volume(&self) -> f64 {
+        8.0 * self.half_extents.x * self.half_extents.y * self.half_extents.z
+    }
+
+    /// The eight corners
+    pub fn corners(&self) -> [Point3<f64>; 8] {
+        std::array::from_fn(|i| {
+            let sign = |bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+            (0..3).fold(self.center, |p, axis| {
+                p + self.axes[axis] * (sign(axis) * self.half_extents[axis])
+            })
+        })
+    }
+}
+
+/// Per-element AABBs plus the whole-model box
+#[derive(Debug, Clone, Default)]
+pub struct ModelBounds {
+    elements: Vec<(u32, Aabb)>,
+    model: Option<Aabb>,
+}
+
+impl ModelBounds {
+    /// Bounds of element meshes keyed by express ID; empty meshes are skipped
+    pub fn from_meshes<'a>(meshes: impl IntoIterator<Item = (u32, &'a Mesh)>) -> Self {
+        let mut bounds = Self::default();
+        for (express_id, mesh) in meshes {
+            bounds.add(express_id, mesh);
+        }
+        bounds
+    }
+
+    /// Add one element mesh
+    pub fn add(&mut self, express_id: u32, mesh: &Mesh) {
+        let Some(aabb) = Aabb::from_mesh(mesh) else {
+            return;
+        };
+        self.model = Some(self.model.map_or(aabb, |model| model.union(&aabb)));
+        self.elements.push((express_id, aabb));
+    }
+
+    /// Box around all elements, `None` when nothing was added
+    pub fn model(&self) -> Option<Aabb> {
+        self.model
+    }
+
+    /// Box of one element (the union if it was added more than once)
+    pub fn element(&self, express_id: u32) -> Option<Aabb> {
+        self.elements
+            .iter()
+            .filter(|(id, _)| *id == express_id)
+            .map(|(_, aabb)| *aabb)
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Element boxes in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &(u32, Aabb)> {
+        self.elements.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extrusion::apply_transform;
+    use crate::{extrude_profile, Profile2D};
+    use nalgebra::{Matrix4, Point2};
+
+    #[test]
+    fn test_model_bounds_and_pca_obb() {
+        // 4 × 0.2 × 3 wall, rotated 30° about Z and moved away from the origin
+        let profile = Profile2D::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(4.0, 0.2),
+            Point2::new(0.0, 0.2),
+        ]);
+        let mut wall = extrude_profile(&profile, 3.0, None).unwrap();
+        let placement = Matrix4::new_translation(&Vector3::new(100.0, 50.0, 0.0))
+            * Matrix4::new_rotation(Vector3::new(0.0, 0.0, 30f64.to_radians()));
+        apply_transform(&mut wall, &placement);
+
+        let aabb = Aabb::from_mesh(&wall).unwrap();
+        let obb = Obb::from_mesh(&wall).unwrap();
+        assert!((obb.volume() - 4.0 * 0.2 * 3.0).abs() < 1e-3);
+        assert!(obb.volume() < aabb.volume() / 2.0);
+        let mut extents: Vec<f64> = obb.half_extents.iter().map(|h| h * 2.0).collect();
+        extents.sort_by(f64::total_cmp);
+        assert!((extents[0] - 0.2).abs() < 1e-4 && (extents[2] - 4.0).abs() < 1e-4);
+        for corner in obb.corners() {
+            assert!(aabb.with_point(&corner).volume() - aabb.volume() < 1e-3);
+        }
+
+        let column = extrude_profile(&profile, 1.0, None).unwrap();
+        let bounds = ModelBounds::from_meshes([(1, &wall), (2, &column), (3, &Mesh::new())]);
+        assert_eq!(bounds.len(), 2);
+        let model = bounds.model().unwrap();
+        assert_eq!(model.min, Point3::new(0.0, 0.0, 0.0));
+        assert!((model.max.z - 3.0).abs() < 1e-6 && model.max.x > 100.0);
+        assert_eq!(bounds.element(1), Some(aabb));
+        assert!(bounds.element(3).is_none());
+    }
+}
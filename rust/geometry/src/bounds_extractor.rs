@@ -0,0 +1,443 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Bounding-box-only extraction for instant preview rendering.
+//!
+//! Resolves element placements and the extents of each representation item
+//! without triangulating anything, producing one oriented box per element.
+//! Intended for placeholder rendering and coarse spatial queries while the
+//! full geometry parse is still running.
+//!
+//! # Coverage
+//! - `IfcExtrudedAreaSolid` — profile 2D extents swept along the extrusion vector
+//! - `IfcTriangulatedFaceSet` / `IfcPolygonalFaceSet` — coordinate list extents
+//! - `IfcFacetedBrep` — poly loop vertex extents
+//! - `IfcBoundingBox` — taken as-is (`Box` representations)
+//! - `IfcBooleanResult` / `IfcBooleanClippingResult` — bounded by the first operand
+//! - `IfcMappedItem` — recurses with composed transforms
+//!
+//! Other item types are ignored; elements with no supported items are omitted.
+//!
+//! # Coordinate system
+//! Boxes are aligned with the element's ObjectPlacement and reported in WebGL
+//! Y-up space (metres), matching [`extract_profiles`](crate::extract_profiles).
+
+use crate::profile_extractor::{
+    detect_unit_scale, get_placement_transform, parse_axis2_placement_3d, parse_cartesian_point,
+    parse_cartesian_transformation_operator, scale_translation,
+};
+use crate::profiles::ProfileProcessor;
+use crate::{Point3, Vector3};
+use ifc_lite_core::{
+    build_entity_index, extract_coordinate_list_from_entity, DecodedEntity, EntityDecoder,
+    EntityScanner, IfcSchema, IfcType,
+};
+use nalgebra::Matrix4;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PUBLIC TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Oriented bounding box of a single IFC element.
+///
+/// All values are in **WebGL Y-up world space** (metres). A point inside the
+/// box is `center + Σ axis_i * t_i` with `|t_i| <= half_extents[i]`.
+#[derive(Debug, Clone)]
+pub struct ElementBounds {
+    /// Express ID of the element.
+    pub express_id: u32,
+    /// IFC type name (e.g., `"IfcWall"`).
+    pub ifc_type: String,
+    /// Box centre.
+    pub center: [f32; 3],
+    /// Half size along each of the three box axes.
+    pub half_extents: [f32; 3],
+    /// Box axes as three consecutive unit vectors `[x0, y0, z0, x1, …]`.
+    pub axes: [f32; 9],
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PUBLIC ENTRY POINT
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Compute an oriented bounding box for every element in `content` that has
+/// a supported Body, SweptSolid or Box representation.
+pub fn extract_bounds(content: &str) -> Vec<ElementBounds> {
+    let entity_index = build_entity_index(content);
+    let mut decoder = EntityDecoder::with_index(content, entity_index);
+    let unit_scale = detect_unit_scale(content, &mut decoder);
+    let profile_processor = ProfileProcessor::new(IfcSchema::new());
+
+    let mut results = Vec::new();
+    let mut scanner = EntityScanner::new(content);
+
+    while let Some((id, type_name, start, end)) = scanner.next_entity() {
+        if !ifc_lite_core::has_geometry_by_name(type_name) {
+            continue;
+        }
+        let entity = match decoder.decode_at_with_id(id, start, end) {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+
+        let representations = match entity
+            .get(6)
+            .filter(|a| !a.is_null())
+            .and_then(|a| decoder.resolve_ref(a).ok().flatten())
+            .and_then(|shape| shape.get(2).and_then(|a| decoder.resolve_ref_list(a).ok()))
+        {
+            Some(r) => r,
+            None => continue,
+        };
+
+        let mut ctx = BoundsContext {
+            unit_scale,
+            profile_processor: &profile_processor,
+            decoder: &mut decoder,
+            acc: LocalBox::default(),
+        };
+
+        for shape_rep in &representations {
+            if shape_rep.ifc_type != IfcType::IfcShapeRepresentation {
+                continue;
+            }
+            let rep_id = shape_rep.get(1).and_then(|a| a.as_string()).unwrap_or("");
+            if !matches!(rep_id, "Body" | "SweptSolid" | "Box") {
+                continue;
+            }
+            let items = match shape_rep.get(3).map(|a| ctx.decoder.resolve_ref_list(a)) {
+                Some(Ok(items)) => items,
+                _ => continue,
+            };
+            for item in &items {
+                ctx.accumulate_item(item, &Matrix4::identity(), 0);
+            }
+        }
+
+        let local = ctx.acc;
+        if local.is_empty() {
+            continue;
+        }
+
+        let placement = scale_translation(
+            get_placement_transform(entity.get(5), &mut decoder),
+            unit_scale,
+        );
+        results.push(local.to_world(id, entity.ifc_type.name(), &placement));
+    }
+
+    results
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PRIVATE: ITEM TRAVERSAL
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Maximum recursion depth for nested mapped items and boolean operands.
+const MAX_ITEM_DEPTH: usize = 8;
+
+struct BoundsContext<'a, 'b> {
+    unit_scale: f64,
+    profile_processor: &'a ProfileProcessor,
+    decoder: &'a mut EntityDecoder<'b>,
+    /// Extents in the element's placement frame (IFC Z-up, metres).
+    acc: LocalBox,
+}
+
+impl BoundsContext<'_, '_> {
+    /// Add the extents of `item` to the accumulator. `to_element` maps the
+    /// item's coordinate space (metres) into the element placement frame.
+    fn accumulate_item(&mut self, item: &DecodedEntity, to_element: &Matrix4<f64>, depth: usize) {
+        if depth > MAX_ITEM_DEPTH {
+            return;
+        }
+        match item.ifc_type {
+            IfcType::IfcExtrudedAreaSolid => self.accumulate_extrusion(item, to_element),
+            IfcType::IfcTriangulatedFaceSet
+            | IfcType::IfcTriangulatedIrregularNetwork
+            | IfcType::IfcPolygonalFaceSet => self.accumulate_coordinate_list(item, to_element),
+            IfcType::IfcFacetedBrep => self.accumulate_faceted_brep(item, to_element),
+            IfcType::IfcBoundingBox => self.accumulate_bounding_box(item, to_element),
+            IfcType::IfcBooleanResult | IfcType::IfcBooleanClippingResult => {
+                // Attr 1: FirstOperand — a difference never grows past it
+                if let Some(first) = item
+                    .get(1)
+                    .and_then(|a| self.decoder.resolve_ref(a).ok().flatten())
+                {
+                    self.accumulate_item(&first, to_element, depth + 1);
+                }
+            }
+            IfcType::IfcMappedItem => self.accumulate_mapped_item(item, to_element, depth),
+            _ => {}
+        }
+    }
+
+    fn accumulate_extrusion(&mut self, solid: &DecodedEntity, to_element: &Matrix4<f64>) {
+        // SweptArea (attr 0) → 2D extents in profile space
+        let profile = match solid
+            .get(0)
+            .and_then(|a| self.decoder.resolve_ref(a).ok().flatten())
+            .and_then(|p| self.profile_processor.process(&p, self.decoder).ok())
+        {
+            Some(p) if !p.outer.is_empty() => p,
+            _ => return,
+        };
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for p in &profile.outer {
+            min_x = min_x.min(p.x);
+            min_y = min_y.min(p.y);
+            max_x = max_x.max(p.x);
+            max_y = max_y.max(p.y);
+        }
+
+        let position = self.resolve_placement(solid, 1);
+        let dir = self.resolve_direction(solid, 2);
+        let depth = solid.get(3).and_then(|v| v.as_float()).unwrap_or(1.0);
+        let sweep = dir * depth;
+
+        let tf = to_element * position;
+        for (x, y) in [
+            (min_x, min_y),
+            (max_x, min_y),
+            (max_x, max_y),
+            (min_x, max_y),
+        ] {
+            let base = Point3::new(x, y, 0.0);
+            self.acc.add(&tf, &base, self.unit_scale);
+            self.acc.add(&tf, &(base + sweep), self.unit_scale);
+        }
+    }
+
+    fn accumulate_coordinate_list(&mut self, face_set: &DecodedEntity, to_element: &Matrix4<f64>) {
+        // Coordinates (attr 0) → IfcCartesianPointList3D, parsed from raw bytes
+        let Some(coord_id) = face_set.get(0).and_then(|a| a.as_entity_ref()) else {
+            return;
+        };
+        let Some(coords) = self
+            .decoder
+            .get_raw_bytes(coord_id)
+            .and_then(extract_coordinate_list_from_entity)
+        else {
+            return;
+        };
+        for c in coords.chunks_exact(3) {
+            let p = Point3::new(c[0] as f64, c[1] as f64, c[2] as f64);
+            self.acc.add(to_element, &p, self.unit_scale);
+        }
+    }
+
+    fn accumulate_faceted_brep(&mut self, brep: &DecodedEntity, to_element: &Matrix4<f64>) {
+        // Outer (attr 0) → IfcClosedShell → faces → bounds → poly loops
+        let Some(shell_id) = brep.get(0).and_then(|a| a.as_entity_ref()) else {
+            return;
+        };
+        let face_ids = self
+            .decoder
+            .get_entity_ref_list_fast(shell_id)
+            .unwrap_or_default();
+        for face_id in face_ids {
+            let bound_ids = self
+                .decoder
+                .get_entity_ref_list_fast(face_id)
+                .unwrap_or_default();
+            for bound_id in bound_ids {
+                let Some((loop_id, _, _)) = self.decoder.get_face_bound_fast(bound_id) else {
+                    continue;
+                };
+                let coords = self
+                    .decoder
+                    .get_polyloop_coords_cached(loop_id)
+                    .unwrap_or_default();
+                for (x, y, z) in coords {
+                    self.acc
+                        .add(to_element, &Point3::new(x, y, z), self.unit_scale);
+                }
+            }
+        }
+    }
+
+    fn accumulate_bounding_box(&mut self, bbox: &DecodedEntity, to_element: &Matrix4<f64>) {
+        // Corner (attr 0), XDim / YDim / ZDim (attrs 1-3)
+        let corner =
+            parse_cartesian_point(bbox, self.decoder, 0).unwrap_or(Point3::new(0.0, 0.0, 0.0));
+        let dims: Vec<f64> = (1..=3)
+            .map(|i| bbox.get(i).and_then(|v| v.as_float()).unwrap_or(0.0))
+            .collect();
+        for i in 0..8 {
+            let offset = Vector3::new(
+                if i & 1 != 0 { dims[0] } else { 0.0 },
+                if i & 2 != 0 { dims[1] } else { 0.0 },
+                if i & 4 != 0 { dims[2] } else { 0.0 },
+            );
+            self.acc
+                .add(to_element, &(corner + offset), self.unit_scale);
+        }
+    }
+
+    fn accumulate_mapped_item(
+        &mut self,
+        mapped: &DecodedEntity,
+        to_element: &Matrix4<f64>,
+        depth: usize,
+    ) {
+        // MappingSource (attr 0) → IfcRepresentationMap
+        let Some(source) = mapped
+            .get(0)
+            .and_then(|a| self.decoder.resolve_ref(a).ok().flatten())
+        else {
+            return;
+        };
+        // MappingTarget (attr 1) → IfcCartesianTransformationOperator3D
+        let target = mapped
+            .get(1)
+            .filter(|a| !a.is_null())
+            .and_then(|a| self.decoder.resolve_ref(a).ok().flatten())
+            .and_then(|e| parse_cartesian_transformation_operator(&e, self.decoder).ok())
+            .unwrap_or_else(Matrix4::identity);
+        // MappingOrigin (attr 0 of RepresentationMap)
+        let origin = self.resolve_placement(&source, 0);
+        let composed = to_element * scale_translation(target, self.unit_scale) * origin;
+
+        let items = source
+            .get(1)
+            .and_then(|a| self.decoder.resolve_ref(a).ok().flatten())
+            .and_then(|rep| {
+                rep.get(3)
+                    .and_then(|a| self.decoder.resolve_ref_list(a).ok())
+            })
+            .unwrap_or_default();
+        for item in &items {
+            self.accumulate_item(item, &composed, depth + 1);
+        }
+    }
+
+    /// Resolve an IfcAxis2Placement3D attribute with its translation in metres.
+    fn resolve_placement(&mut self, entity: &DecodedEntity, attr: usize) -> Matrix4<f64> {
+        entity
+            .get(attr)
+            .filter(|a| !a.is_null())
+            .and_then(|a| self.decoder.resolve_ref(a).ok().flatten())
+            .filter(|p| p.ifc_type == IfcType::IfcAxis2Placement3D)
+            .and_then(|p| parse_axis2_placement_3d(&p, self.decoder).ok())
+            .map(|m| scale_translation(m, self.unit_scale))
+            .unwrap_or_else(Matrix4::identity)
+    }
+
+    /// Resolve an IfcDirection attribute, defaulting to +Z.
+    fn resolve_direction(&mut self, entity: &DecodedEntity, attr: usize) -> Vector3<f64> {
+        entity
+            .get(attr)
+            .filter(|a| !a.is_null())
+            .and_then(|a| self.decoder.resolve_ref(a).ok().flatten())
+            .and_then(|d| crate::parse_direction(&d).ok())
+            .filter(|v| v.norm() > 1e-10)
+            .unwrap_or(Vector3::new(0.0, 0.0, 1.0))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PRIVATE: BOX ACCUMULATION
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Axis-aligned extents in the element placement frame.
+#[derive(Clone, Copy)]
+struct LocalBox {
+    min: Point3<f64>,
+    max: Point3<f64>,
+}
+
+impl Default for LocalBox {
+    fn default() -> Self {
+        Self {
+            min: Point3::new(f64::MAX, f64::MAX, f64::MAX),
+            max: Point3::new(f64::MIN, f64::MIN, f64::MIN),
+        }
+    }
+}
+
+impl LocalBox {
+    fn is_empty(&self) -> bool {
+        self.min.x > self.max.x
+    }
+
+    /// Add a point given in file units; `tf` carries translations in metres.
+    fn add(&mut self, tf: &Matrix4<f64>, p: &Point3<f64>, unit_scale: f64) {
+        let q = tf.transform_point(&Point3::from(p.coords * unit_scale));
+        if !q.coords.iter().all(|c| c.is_finite()) {
+            return;
+        }
+        self.min = self.min.inf(&q);
+        self.max = self.max.sup(&q);
+    }
+
+    /// Place the box with the element's world placement and convert to WebGL Y-up.
+    fn to_world(self, express_id: u32, ifc_type: &str, placement: &Matrix4<f64>) -> ElementBounds {
+        let center = placement.transform_point(&nalgebra::center(&self.min, &self.max));
+        let half = (self.max - self.min) * 0.5;
+
+        let mut axes = [0.0f32; 9];
+        for i in 0..3 {
+            let axis = placement.fixed_view::<3, 1>(0, i).normalize();
+            let gl = to_webgl(&axis);
+            axes[i * 3..i * 3 + 3].copy_from_slice(&gl);
+        }
+
+        ElementBounds {
+            express_id,
+            ifc_type: ifc_type.to_string(),
+            center: to_webgl(&center.coords),
+            half_extents: [half.x as f32, half.y as f32, half.z as f32],
+            axes,
+        }
+    }
+}
+
+/// IFC Z-up → WebGL Y-up (new_y = old_z, new_z = -old_y).
+fn to_webgl(v: &Vector3<f64>) -> [f32; 3] {
+    [v.x as f32, v.z as f32, -v.y as f32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extruded_wall_bounds() {
+        let content = r#"
+#1=IFCCARTESIANPOINT((0.,0.,0.));
+#2=IFCCARTESIANPOINT((10.,0.,0.));
+#3=IFCAXIS2PLACEMENT3D(#2,$,$);
+#4=IFCLOCALPLACEMENT($,#3);
+#5=IFCAXIS2PLACEMENT2D(#1,$);
+#6=IFCRECTANGLEPROFILEDEF(.AREA.,$,#5,4.,0.2);
+#7=IFCDIRECTION((0.,0.,1.));
+#8=IFCAXIS2PLACEMENT3D(#1,$,$);
+#9=IFCEXTRUDEDAREASOLID(#6,#8,#7,3.);
+#10=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#9));
+#11=IFCPRODUCTDEFINITIONSHAPE($,$,(#10));
+#12=IFCWALL('guid',$,$,$,$,#4,#11,$,$);
+"#;
+        let bounds = extract_bounds(content);
+        assert_eq!(bounds.len(), 1);
+        let b = &bounds[0];
+        assert_eq!(b.express_id, 12);
+        assert_eq!(b.ifc_type, "IfcWall");
+
+        // Centre at IFC (10, 0, 1.5) → WebGL (10, 1.5, 0)
+        assert!((b.center[0] - 10.0).abs() < 1e-5);
+        assert!((b.center[1] - 1.5).abs() < 1e-5);
+        assert!(b.center[2].abs() < 1e-5);
+        assert!((b.half_extents[0] - 2.0).abs() < 1e-5);
+        assert!((b.half_extents[1] - 0.1).abs() < 1e-5);
+        assert!((b.half_extents[2] - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_element_without_body_is_skipped() {
+        let content = r#"
+#1=IFCWALL('guid',$,$,$,$,$,$,$,$);
+"#;
+        assert!(extract_bounds(content).is_empty());
+    }
+}
@@ -0,0 +1,474 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! BSP trees for mesh boolean operations
+//!
+//! Port of the classic csg.js algorithm with every tree walk (build, clip,
+//! invert) driven by an explicit work stack over a node arena. Nearly coplanar
+//! input can still produce deep, unbalanced trees, but that now hits the
+//! configured [`CsgLimits`] and fails the operation instead of overflowing the
+//! native or WASM call stack.
+//!
+//! The tree is generic over [`BspPolygon`]: [`Polygon`] splits in floating
+//! point, while [`crate::exact`] provides a plane-based exact implementation.
+
+use crate::csg::CsgLimits;
+use crate::error::{Error, Result};
+use nalgebra::{Point3, Vector3};
+
+/// Distance within which a vertex is treated as lying on a splitting plane.
+const PLANE_EPSILON: f64 = 1e-5;
+
+/// Oriented plane `normal · p = w`.
+#[derive(Debug, Clone, Copy)]
+pub struct BspPlane {
+    pub normal: Vector3<f64>,
+    pub w: f64,
+}
+
+impl BspPlane {
+    /// Plane through three points, or `None` if they are collinear.
+    pub fn from_points(a: &Point3<f64>, b: &Point3<f64>, c: &Point3<f64>) -> Option<Self> {
+        let normal = (b - a).cross(&(c - a)).try_normalize(1e-12)?;
+        Some(Self {
+            normal,
+            w: normal.dot(&a.coords),
+        })
+    }
+
+    #[inline]
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        self.normal.dot(&point.coords) - self.w
+    }
+
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+}
+
+/// Convex planar polygon.
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    pub vertices: Vec<Point3<f64>>,
+    pub plane: BspPlane,
+}
+
+impl Polygon {
+    /// Polygon with its plane taken from the first three vertices.
+    pub fn new(vertices: Vec<Point3<f64>>) -> Option<Self> {
+        if vertices.len() < 3 {
+            return None;
+        }
+        let plane = BspPlane::from_points(&vertices[0], &vertices[1], &vertices[2])?;
+        Some(Self { vertices, plane })
+    }
+}
+
+/// Polygon type a BSP tree can be built from.
+pub trait BspPolygon: Sized {
+    type Plane: Copy;
+
+    /// Supporting plane, used as the splitting plane of tree nodes.
+    fn plane(&self) -> Self::Plane;
+
+    /// Classify (and if needed split) this polygon against `plane`.
+    fn split(self, plane: &Self::Plane) -> Side<Self>;
+
+    /// Reverse orientation.
+    fn flip(&mut self);
+
+    /// Reverse orientation of a plane.
+    fn flip_plane(plane: &mut Self::Plane);
+}
+
+/// Where a polygon lies relative to a splitting plane.
+pub enum Side<P> {
+    CoplanarFront(P),
+    CoplanarBack(P),
+    Front(P),
+    Back(P),
+    Spanning(Option<P>, Option<P>),
+}
+
+impl BspPolygon for Polygon {
+    type Plane = BspPlane;
+
+    fn plane(&self) -> BspPlane {
+        self.plane
+    }
+
+    fn split(self, plane: &BspPlane) -> Side<Self> {
+        split_polygon(plane, self)
+    }
+
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        self.plane.flip();
+    }
+
+    fn flip_plane(plane: &mut BspPlane) {
+        plane.flip();
+    }
+}
+
+const COPLANAR: u8 = 0;
+const FRONT: u8 = 1;
+const BACK: u8 = 2;
+const SPANNING: u8 = 3;
+
+fn split_polygon(plane: &BspPlane, polygon: Polygon) -> Side<Polygon> {
+    let mut polygon_type = COPLANAR;
+    let types: Vec<u8> = polygon
+        .vertices
+        .iter()
+        .map(|v| {
+            let t = plane.distance(v);
+            let vertex_type = if t < -PLANE_EPSILON {
+                BACK
+            } else if t > PLANE_EPSILON {
+                FRONT
+            } else {
+                COPLANAR
+            };
+            polygon_type |= vertex_type;
+            vertex_type
+        })
+        .collect();
+
+    match polygon_type {
+        COPLANAR if plane.normal.dot(&polygon.plane.normal) > 0.0 => Side::CoplanarFront(polygon),
+        COPLANAR => Side::CoplanarBack(polygon),
+        FRONT => Side::Front(polygon),
+        BACK => Side::Back(polygon),
+        _ => {
+            let n = polygon.vertices.len();
+            let mut front = Vec::with_capacity(n + 1);
+            let mut back = Vec::with_capacity(n + 1);
+            for i in 0..n {
+                let j = (i + 1) % n;
+                let (ti, tj) = (types[i], types[j]);
+                let (vi, vj) = (polygon.vertices[i], polygon.vertices[j]);
+                if ti != BACK {
+                    front.push(vi);
+                }
+                if ti != FRONT {
+                    back.push(vi);
+                }
+                if (ti | tj) == SPANNING {
+                    let t = (plane.w - plane.normal.dot(&vi.coords)) / plane.normal.dot(&(vj - vi));
+                    let v = vi + (vj - vi) * t;
+                    front.push(v);
+                    back.push(v);
+                }
+            }
+            // Fragments keep the parent plane; recomputing it from sliver
+            // vertices is what makes nearly coplanar input drift
+            let fragment = |vertices: Vec<Point3<f64>>| {
+                (vertices.len() >= 3).then_some(Polygon {
+                    vertices,
+                    plane: polygon.plane,
+                })
+            };
+            Side::Spanning(fragment(front), fragment(back))
+        }
+    }
+}
+
+/// Remaining polygon splits shared by all trees of one boolean operation.
+struct SplitBudget(usize);
+
+impl SplitBudget {
+    fn spend(&mut self) -> Result<()> {
+        self.0 = self.0.checked_sub(1).ok_or_else(|| {
+            Error::geometry("CSG aborted: polygon split limit exceeded".to_string())
+        })?;
+        Ok(())
+    }
+}
+
+struct Node<P: BspPolygon> {
+    plane: P::Plane,
+    polygons: Vec<P>,
+    front: Option<usize>,
+    back: Option<usize>,
+}
+
+impl<P: BspPolygon> Node<P> {
+    fn new(plane: P::Plane) -> Self {
+        Self {
+            plane,
+            polygons: Vec::new(),
+            front: None,
+            back: None,
+        }
+    }
+}
+
+/// BSP tree stored as a node arena; node 0 is the root.
+struct BspTree<P: BspPolygon> {
+    nodes: Vec<Node<P>>,
+    max_depth: usize,
+}
+
+impl<P: BspPolygon> BspTree<P> {
+    fn new(polygons: Vec<P>, max_depth: usize, budget: &mut SplitBudget) -> Result<Self> {
+        let mut tree = Self {
+            nodes: Vec::new(),
+            max_depth,
+        };
+        tree.build(polygons, budget)?;
+        Ok(tree)
+    }
+
+    /// Insert polygons, splitting them along existing node planes.
+    fn build(&mut self, polygons: Vec<P>, budget: &mut SplitBudget) -> Result<()> {
+        let Some(first) = polygons.first() else {
+            return Ok(());
+        };
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::new(first.plane()));
+        }
+
+        let mut stack = vec![(0usize, polygons, 1usize)];
+        while let Some((index, polygons, depth)) = stack.pop() {
+            if depth > self.max_depth {
+                return Err(Error::geometry(format!(
+                    "CSG aborted: BSP depth limit ({}) exceeded",
+                    self.max_depth
+                )));
+            }
+
+            let plane = self.nodes[index].plane;
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+            for polygon in polygons {
+                match polygon.split(&plane) {
+                    Side::CoplanarFront(p) | Side::CoplanarBack(p) => {
+                        self.nodes[index].polygons.push(p)
+                    }
+                    Side::Front(p) => front.push(p),
+                    Side::Back(p) => back.push(p),
+                    Side::Spanning(f, b) => {
+                        budget.spend()?;
+                        front.extend(f);
+                        back.extend(b);
+                    }
+                }
+            }
+
+            if !front.is_empty() {
+                let child = self.child(index, true, front[0].plane());
+                stack.push((child, front, depth + 1));
+            }
+            if !back.is_empty() {
+                let child = self.child(index, false, back[0].plane());
+                stack.push((child, back, depth + 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Existing front/back child of `index`, or a new one split by `plane`.
+    fn child(&mut self, index: usize, front: bool, plane: P::Plane) -> usize {
+        let existing = if front {
+            self.nodes[index].front
+        } else {
+            self.nodes[index].back
+        };
+        if let Some(child) = existing {
+            return child;
+        }
+        let child = self.nodes.len();
+        self.nodes.push(Node::new(plane));
+        if front {
+            self.nodes[index].front = Some(child);
+        } else {
+            self.nodes[index].back = Some(child);
+        }
+        child
+    }
+
+    /// Remove the parts of `polygons` that lie inside this tree's solid.
+    fn clip_polygons(&self, polygons: Vec<P>, budget: &mut SplitBudget) -> Result<Vec<P>> {
+        if self.nodes.is_empty() {
+            return Ok(polygons);
+        }
+
+        let mut kept = Vec::new();
+        let mut stack = vec![(0usize, polygons)];
+        while let Some((index, polygons)) = stack.pop() {
+            let node = &self.nodes[index];
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+            for polygon in polygons {
+                match polygon.split(&node.plane) {
+                    Side::CoplanarFront(p) | Side::Front(p) => front.push(p),
+                    Side::CoplanarBack(p) | Side::Back(p) => back.push(p),
+                    Side::Spanning(f, b) => {
+                        budget.spend()?;
+                        front.extend(f);
+                        back.extend(b);
+                    }
+                }
+            }
+
+            match node.front {
+                Some(child) if !front.is_empty() => stack.push((child, front)),
+                Some(_) => {}
+                None => kept.extend(front),
+            }
+            // Polygons behind a leaf are inside the solid and dropped
+            if let Some(child) = node.back {
+                if !back.is_empty() {
+                    stack.push((child, back));
+                }
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Clip every polygon in this tree against `other`.
+    fn clip_to(&mut self, other: &BspTree<P>, budget: &mut SplitBudget) -> Result<()> {
+        for node in &mut self.nodes {
+            let polygons = std::mem::take(&mut node.polygons);
+            node.polygons = other.clip_polygons(polygons, budget)?;
+        }
+        Ok(())
+    }
+
+    /// Swap solid and empty space.
+    fn invert(&mut self) {
+        for node in &mut self.nodes {
+            for polygon in &mut node.polygons {
+                polygon.flip();
+            }
+            P::flip_plane(&mut node.plane);
+            std::mem::swap(&mut node.front, &mut node.back);
+        }
+    }
+
+    fn into_polygons(self) -> Vec<P> {
+        self.nodes.into_iter().flat_map(|n| n.polygons).collect()
+    }
+}
+
+type Trees<P> = (BspTree<P>, BspTree<P>, SplitBudget);
+
+fn trees<P: BspPolygon>(a: Vec<P>, b: Vec<P>, limits: &CsgLimits) -> Result<Trees<P>> {
+    let mut budget = SplitBudget(limits.max_splits);
+    let a = BspTree::new(a, limits.max_depth, &mut budget)?;
+    let b = BspTree::new(b, limits.max_depth, &mut budget)?;
+    Ok((a, b, budget))
+}
+
+/// `a - b`
+pub fn difference<P: BspPolygon>(a: Vec<P>, b: Vec<P>, limits: &CsgLimits) -> Result<Vec<P>> {
+    let (mut a, mut b, mut budget) = trees(a, b, limits)?;
+    a.invert();
+    a.clip_to(&b, &mut budget)?;
+    b.clip_to(&a, &mut budget)?;
+    b.invert();
+    b.clip_to(&a, &mut budget)?;
+    b.invert();
+    a.build(b.into_polygons(), &mut budget)?;
+    a.invert();
+    Ok(a.into_polygons())
+}
+
+/// `a ∪ b`
+pub fn union<P: BspPolygon>(a: Vec<P>, b: Vec<P>, limits: &CsgLimits) -> Result<Vec<P>> {
+    let (mut a, mut b, mut budget) = trees(a, b, limits)?;
+    a.clip_to(&b, &mut budget)?;
+    b.clip_to(&a, &mut budget)?;
+    b.invert();
+    b.clip_to(&a, &mut budget)?;
+    b.invert();
+    a.build(b.into_polygons(), &mut budget)?;
+    Ok(a.into_polygons())
+}
+
+/// `a ∩ b`
+pub fn intersection<P: BspPolygon>(a: Vec<P>, b: Vec<P>, limits: &CsgLimits) -> Result<Vec<P>> {
+    let (mut a, mut b, mut budget) = trees(a, b, limits)?;
+    a.invert();
+    b.clip_to(&a, &mut budget)?;
+    b.invert();
+    a.clip_to(&b, &mut budget)?;
+    b.clip_to(&a, &mut budget)?;
+    a.build(b.into_polygons(), &mut budget)?;
+    a.invert();
+    Ok(a.into_polygons())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Axis-aligned box as 6 outward-facing quads.
+    fn cube(min: f64, max: f64) -> Vec<Polygon> {
+        let p = |x: f64, y: f64, z: f64| Point3::new(x, y, z);
+        let (a, b) = (min, max);
+        [
+            [p(a, a, a), p(a, b, a), p(b, b, a), p(b, a, a)],
+            [p(a, a, b), p(b, a, b), p(b, b, b), p(a, b, b)],
+            [p(a, a, a), p(a, a, b), p(a, b, b), p(a, b, a)],
+            [p(b, a, a), p(b, b, a), p(b, b, b), p(b, a, b)],
+            [p(a, a, a), p(b, a, a), p(b, a, b), p(a, a, b)],
+            [p(a, b, a), p(a, b, b), p(b, b, b), p(b, b, a)],
+        ]
+        .into_iter()
+        .map(|quad| Polygon::new(quad.to_vec()).unwrap())
+        .collect()
+    }
+
+    fn area(polygons: &[Polygon]) -> f64 {
+        polygons
+            .iter()
+            .map(|p| {
+                let v = &p.vertices;
+                (1..v.len() - 1)
+                    .map(|i| (v[i] - v[0]).cross(&(v[i + 1] - v[0])).norm() / 2.0)
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_boolean_surface_areas() {
+        let limits = CsgLimits::default();
+
+        // Overlapping unit cubes offset by half along every axis
+        let a = cube(0.0, 1.0);
+        let b = cube(0.5, 1.5);
+        let inter = intersection(a.clone(), b.clone(), &limits).unwrap();
+        assert!((area(&inter) - 6.0 * 0.25).abs() < 1e-9);
+
+        let uni = union(a.clone(), b.clone(), &limits).unwrap();
+        assert!((area(&uni) - (2.0 * 6.0 - 2.0 * 3.0 * 0.25)).abs() < 1e-9);
+
+        let diff = difference(a, b, &limits).unwrap();
+        assert!((area(&diff) - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_limits_abort_instead_of_overflowing() {
+        // A fan of nearly coplanar slivers produces a degenerate, list-like tree
+        let slivers: Vec<Polygon> = (0..3_000)
+            .filter_map(|i| {
+                let z = i as f64 * 1e-3;
+                Polygon::new(vec![
+                    Point3::new(0.0, 0.0, z),
+                    Point3::new(1.0, 0.0, z),
+                    Point3::new(0.0, 1.0, z + 1e-4),
+                ])
+            })
+            .collect();
+        let limits = CsgLimits {
+            max_depth: 1_000,
+            ..CsgLimits::default()
+        };
+        assert!(union(slivers, cube(0.0, 1.0), &limits).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! B-spline curve evaluation
+//!
+//! [`BSplineCurve`] evaluates IfcBSplineCurveWithKnots and
+//! IfcRationalBSplineCurveWithKnots with de Boor's algorithm (in homogeneous
+//! coordinates for the rational case). It is shared by profile boundaries,
+//! sweep directrices and Brep edges so all of them follow the curve rather
+//! than its control polygon.
+
+use crate::tessellation::TessellationConfig;
+use crate::transform::parse_cartesian_point_from_id;
+use crate::{Error, Point3, Result};
+use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcType};
+use nalgebra::Vector4;
+
+/// Knot spans shorter than this are treated as empty
+const KNOT_TOLERANCE: f64 = 1e-12;
+
+/// A (possibly rational) B-spline curve with an expanded knot vector
+#[derive(Debug, Clone)]
+pub struct BSplineCurve {
+    degree: usize,
+    control_points: Vec<Point3<f64>>,
+    knots: Vec<f64>,
+    weights: Option<Vec<f64>>,
+}
+
+impl BSplineCurve {
+    /// Create a curve from its control points and full knot vector (each knot
+    /// repeated by its multiplicity).
+    ///
+    /// Requires `control_points.len() + degree + 1` non-decreasing knots
+    /// spanning a non-empty domain and, if given, one positive weight per
+    /// control point.
+    pub fn new(
+        degree: usize,
+        control_points: Vec<Point3<f64>>,
+        knots: Vec<f64>,
+        weights: Option<Vec<f64>>,
+    ) -> Result<Self> {
+        let n = control_points.len();
+        if degree == 0 || n <= degree {
+            return Err(Error::geometry(format!(
+                "B-spline of degree {} needs more than {} control points, got {}",
+                degree, degree, n
+            )));
+        }
+        if knots.len() != n + degree + 1 {
+            return Err(Error::geometry(format!(
+                "B-spline with {} control points and degree {} needs {} knots, got {}",
+                n,
+                degree,
+                n + degree + 1,
+                knots.len()
+            )));
+        }
+        if knots.iter().any(|k| !k.is_finite())
+            || knots.windows(2).any(|k| k[1] < k[0])
+            || knots[n] - knots[degree] <= KNOT_TOLERANCE
+        {
+            return Err(Error::geometry(
+                "B-spline knots must be non-decreasing with a non-empty domain".to_string(),
+            ));
+        }
+        if let Some(w) = &weights {
+            if w.len() != n || w.iter().any(|&w| !w.is_finite() || w <= 0.0) {
+                return Err(Error::geometry(
+                    "B-spline needs one positive weight per control point".to_string(),
+                ));
+            }
+        }
+        Ok(Self {
+            degree,
+            control_points,
+            knots,
+            weights,
+        })
+    }
+
+    /// Parse an IfcBSplineCurveWithKnots or IfcRationalBSplineCurveWithKnots.
+    ///
+    /// IfcBSplineCurve: Degree, ControlPointsList, CurveForm, ClosedCurve, SelfIntersect
+    /// IfcBSplineCurveWithKnots: + KnotMultiplicities, Knots, KnotSpec
+    /// IfcRationalBSplineCurveWithKnots: + WeightsData
+    pub fn from_entity(curve: &DecodedEntity, decoder: &mut EntityDecoder) -> Result<Self> {
+        let degree = curve
+            .get(0)
+            .and_then(|v| v.as_int())
+            .ok_or_else(|| Error::geometry("BSplineCurve missing Degree".to_string()))?;
+
+        let points_attr = curve
+            .get(1)
+            .ok_or_else(|| Error::geometry("BSplineCurve missing ControlPointsList".to_string()))?;
+        let control_points = points_attr
+            .as_list()
+            .ok_or_else(|| Error::geometry("ControlPointsList is not a list".to_string()))?
+            .iter()
+            .map(|point| {
+                let id = point.as_entity_ref().ok_or_else(|| {
+                    Error::geometry("ControlPointsList entry is not a reference".to_string())
+                })?;
+                parse_cartesian_point_from_id(id, decoder)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let multiplicities: Vec<i64> = curve
+            .get(5)
+            .and_then(|a| a.as_list())
+            .map(|l| l.iter().filter_map(|v| v.as_int()).collect())
+            .unwrap_or_default();
+        let knot_values: Vec<f64> = curve
+            .get(6)
+            .and_then(|a| a.as_list())
+            .map(|l| l.iter().filter_map(|v| v.as_float()).collect())
+            .unwrap_or_default();
+        if knot_values.is_empty() || multiplicities.len() != knot_values.len() {
+            return Err(Error::geometry(
+                "BSplineCurve knot multiplicities do not match knots".to_string(),
+            ));
+        }
+
+        let weights = if curve.ifc_type == IfcType::IfcRationalBSplineCurveWithKnots {
+            let weights: Vec<f64> = curve
+                .get(8)
+                .and_then(|a| a.as_list())
+                .map(|l| l.iter().filter_map(|v| v.as_float()).collect())
+                .ok_or_else(|| {
+                    Error::geometry("RationalBSplineCurve missing WeightsData".to_string())
+                })?;
+            Some(weights)
+        } else {
+            None
+        };
+
+        Self::new(
+            degree.max(0) as usize,
+            control_points,
+            expand_knots(&knot_values, &multiplicities),
+            weights,
+        )
+    }
+
+    /// Polynomial degree
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Control points (unweighted)
+    pub fn control_points(&self) -> &[Point3<f64>] {
+        &self.control_points
+    }
+
+    /// Parameter range the curve is defined over
+    pub fn domain(&self) -> (f64, f64) {
+        (
+            self.knots[self.degree],
+            self.knots[self.control_points.len()],
+        )
+    }
+
+    /// Point at parameter `t`, clamped to the domain
+    pub fn evaluate(&self, t: f64) -> Point3<f64> {
+        let (t_min, t_max) = self.domain();
+        let t = t.clamp(t_min, t_max);
+        let p = self.degree;
+        let span = self.span(t);
+
+        let mut d: Vec<Vector4<f64>> = (0..=p)
+            .map(|j| {
+                let i = span - p + j;
+                let w = self.weights.as_ref().map_or(1.0, |w| w[i]);
+                let c = self.control_points[i];
+                Vector4::new(c.x * w, c.y * w, c.z * w, w)
+            })
+            .collect();
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let i = span - p + j;
+                let denom = self.knots[i + p + 1 - r] - self.knots[i];
+                let alpha = if denom.abs() < KNOT_TOLERANCE {
+                    0.0
+                } else {
+                    (t - self.knots[i]) / denom
+                };
+                d[j] = d[j - 1] * (1.0 - alpha) + d[j] * alpha;
+            }
+        }
+
+        let h = d[p];
+        Point3::new(h.x / h.w, h.y / h.w, h.z / h.w)
+    }
+
+    /// Sample the whole curve, start and end included.
+    ///
+    /// Every knot span gets at least one segment so degree-1 curves keep
+    /// their corners exactly; the total count follows the control polygon's
+    /// length and turning angle.
+    pub fn tessellate(&self, tessellation: &TessellationConfig) -> Vec<Point3<f64>> {
+        let (t_min, t_max) = self.domain();
+        let spans: Vec<(f64, f64)> = self
+            .knots
+            .windows(2)
+            .map(|k| (k[0].max(t_min), k[1].min(t_max)))
+            .filter(|(a, b)| b - a > KNOT_TOLERANCE)
+            .collect();
+
+        let per_span = if self.degree == 1 {
+            1
+        } else {
+            tessellation
+                .polygon_segments(&self.control_points)
+                .div_ceil(spans.len())
+                .max(1)
+        };
+
+        let mut points = Vec::with_capacity(spans.len() * per_span + 1);
+        points.push(self.evaluate(t_min));
+        for (a, b) in spans {
+            for i in 1..=per_span {
+                let pt = self.evaluate(a + (b - a) * i as f64 / per_span as f64);
+                if points
+                    .last()
+                    .is_some_and(|prev| (pt - prev).norm_squared() < KNOT_TOLERANCE)
+                {
+                    continue;
+                }
+                points.push(pt);
+            }
+        }
+        points
+    }
+
+    /// Index `k` of the non-empty knot span with `knots[k] <= t < knots[k + 1]`,
+    /// using the last span for `t` at the end of the domain
+    fn span(&self, t: f64) -> usize {
+        let p = self.degree;
+        let n = self.control_points.len();
+        let last = (p..n)
+            .rev()
+            .find(|&k| self.knots[k + 1] - self.knots[k] > KNOT_TOLERANCE)
+            .unwrap_or(p);
+        (p..=last).rev().find(|&k| self.knots[k] <= t).unwrap_or(p)
+    }
+}
+
+/// Expand knot values by their multiplicities into a full knot vector
+pub(crate) fn expand_knots(knot_values: &[f64], multiplicities: &[i64]) -> Vec<f64> {
+    let mut expanded = Vec::new();
+    for (knot, &mult) in knot_values.iter().zip(multiplicities.iter()) {
+        for _ in 0..mult {
+            expanded.push(*knot);
+        }
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quadratic_bezier() {
+        let curve = BSplineCurve::new(
+            2,
+            vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 2.0, 0.0),
+                Point3::new(2.0, 0.0, 0.0),
+            ],
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(curve.evaluate(0.0), Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(curve.evaluate(1.0), Point3::new(2.0, 0.0, 0.0));
+        let mid = curve.evaluate(0.5);
+        assert!((mid - Point3::new(1.0, 1.0, 0.0)).norm() < 1e-12);
+
+        let points = curve.tessellate(&TessellationConfig::default());
+        assert!(points.len() > 3);
+        assert_eq!(points.first(), Some(&Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(points.last(), Some(&Point3::new(2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_rational_quarter_circle() {
+        let w = std::f64::consts::FRAC_1_SQRT_2;
+        let curve = BSplineCurve::new(
+            2,
+            vec![
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            Some(vec![1.0, w, 1.0]),
+        )
+        .unwrap();
+
+        for p in curve.tessellate(&TessellationConfig::default()) {
+            assert!(
+                (p.coords.norm() - 1.0).abs() < 1e-12,
+                "{p:?} off the circle"
+            );
+        }
+    }
+
+    #[test]
+    fn test_linear_keeps_corners() {
+        let corners = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+        ];
+        let knots = expand_knots(&[0.0, 1.0, 2.0], &[2, 1, 2]);
+        let curve = BSplineCurve::new(1, corners.clone(), knots, None).unwrap();
+        assert_eq!(curve.tessellate(&TessellationConfig::default()), corners);
+    }
+
+    #[test]
+    fn test_invalid_knots_rejected() {
+        let points = vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0)];
+        assert!(BSplineCurve::new(1, points.clone(), vec![0.0, 0.0, 1.0], None).is_err());
+        assert!(BSplineCurve::new(1, points.clone(), vec![0.0, 0.0, 0.0, 0.0], None).is_err());
+        assert!(
+            BSplineCurve::new(1, points, vec![0.0, 0.0, 1.0, 1.0], Some(vec![1.0, 0.0])).is_err()
+        );
+    }
+}
@@ -0,0 +1,666 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Bounding volume hierarchy over element meshes
+//!
+//! Triangles of all elements go into one binary tree of axis-aligned boxes,
+//! split at the median centroid along the widest axis. Ray queries (picking,
+//! measurement) and box queries (clash candidates) then touch a handful of
+//! nodes instead of every triangle, without round-tripping geometry to JS.
+//!
+//! Triangles are tested double-sided, since IFC winding is not reliable.
+//! Point containment therefore uses ray parity rather than facing.
+//!
+//! [`Bvh::to_bytes`] and [`Bvh::from_bytes`] store a built hierarchy (e.g.
+//! in a server cache); loading validates every node range, so corrupt data
+//! is rejected instead of panicking or looping during traversal.
+
+use crate::error::{Error, Result};
+use crate::mesh::Mesh;
+use nalgebra::{Point3, Vector3};
+use rustc_hash::FxHashMap;
+
+/// Triangles per leaf
+const LEAF_SIZE: usize = 4;
+
+/// Containment ray directions, skewed off the axes so they rarely run
+/// along the edges and faces of axis-aligned geometry
+const CONTAINMENT_DIRECTIONS: [[f64; 3]; 3] = [
+    [0.61, 0.49, 0.63],
+    [-0.43, 0.71, -0.56],
+    [0.37, -0.52, -0.77],
+];
+
+/// Hits of one element closer than this along a ray are one crossing
+/// (a ray through a shared edge hits both triangles)
+const CROSSING_TOLERANCE: f64 = 1e-9;
+
+/// A ray; `direction` need not be normalized, distances are in its units
+#[derive(Debug, Clone, Copy)]
+pub struct Ray {
+    pub origin: Point3<f64>,
+    pub direction: Vector3<f64>,
+}
+
+impl Ray {
+    /// Ray with a normalized direction, so hit distances are metric
+    pub fn new(origin: Point3<f64>, direction: Vector3<f64>) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+}
+
+/// Ray/triangle intersection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub express_id: u32,
+    /// Triangle index within the element's mesh
+    pub triangle: u32,
+    /// Ray parameter of the hit (distance for a normalized direction)
+    pub distance: f64,
+    pub point: Point3<f64>,
+    /// Unit normal of the triangle, facing the ray origin
+    pub normal: Vector3<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Aabb {
+    min: [f32; 3],
+    max: [f32; 3],
+}
+
+impl Aabb {
+    const EMPTY: Self = Self {
+        min: [f32::MAX; 3],
+        max: [f32::MIN; 3],
+    };
+
+    fn grow(&mut self, p: &[f32; 3]) {
+        for (axis, &c) in p.iter().enumerate() {
+            self.min[axis] = self.min[axis].min(c);
+            self.max[axis] = self.max[axis].max(c);
+        }
+    }
+
+    fn overlaps(&self, min: &[f32; 3], max: &[f32; 3]) -> bool {
+        (0..3).all(|axis| self.min[axis] <= max[axis] && self.max[axis] >= min[axis])
+    }
+
+    /// Entry distance of the ray into the box, if it enters within `t_max`
+    fn ray_entry(&self, origin: &[f64; 3], inv_dir: &[f64; 3], t_max: f64) -> Option<f64> {
+        let mut t0 = 0.0f64;
+        let mut t1 = t_max;
+        for axis in 0..3 {
+            let a = (self.min[axis] as f64 - origin[axis]) * inv_dir[axis];
+            let b = (self.max[axis] as f64 - origin[axis]) * inv_dir[axis];
+            // NaN (0 * inf on a slab boundary) must not shrink the interval
+            t0 = t0.max(a.min(b).max(f64::MIN));
+            t1 = t1.min(a.max(b).min(f64::MAX));
+            if t0 > t1 {
+                return None;
+            }
+        }
+        Some(t0)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Triangle {
+    vertices: [[f32; 3]; 3],
+    express_id: u32,
+    index: u32,
+}
+
+impl Triangle {
+    fn centroid(&self, axis: usize) -> f32 {
+        (self.vertices[0][axis] + self.vertices[1][axis] + self.vertices[2][axis]) / 3.0
+    }
+
+    fn points(&self) -> [Point3<f64>; 3] {
+        self.vertices
+            .map(|v| Point3::new(v[0] as f64, v[1] as f64, v[2] as f64))
+    }
+
+    /// Möller–Trumbore, double-sided
+    fn intersect(&self, ray: &Ray) -> Option<f64> {
+        let [v0, v1, v2] = self.points();
+        let e1 = v1 - v0;
+        let e2 = v2 - v0;
+        let p = ray.direction.cross(&e2);
+        let det = e1.dot(&p);
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+        let s = ray.origin - v0;
+        let u = s.dot(&p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(&e1);
+        let v = ray.direction.dot(&q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = e2.dot(&q) * inv_det;
+        (t >= 0.0).then_some(t)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    bounds: Aabb,
+    /// Leaf: first triangle; inner: left child (right child follows it)
+    start: u32,
+    /// Triangles in a leaf, 0 for inner nodes
+    count: u32,
+}
+
+/// Bounding volume hierarchy over the triangles of many elements
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    triangles: Vec<Triangle>,
+}
+
+impl Bvh {
+    /// Build over element meshes keyed by express ID
+    pub fn build<'a>(meshes: impl IntoIterator<Item = (u32, &'a Mesh)>) -> Self {
+        Self::build_from_buffers(
+            meshes
+                .into_iter()
+                .map(|(express_id, mesh)| (express_id, &mesh.positions[..], &mesh.indices[..])),
+        )
+    }
+
+    /// Build over raw `(express ID, positions, indices)` buffers, for meshes
+    /// held outside [`Mesh`] (e.g. serialized batches)
+    pub fn build_from_buffers<'a>(
+        meshes: impl IntoIterator<Item = (u32, &'a [f32], &'a [u32])>,
+    ) -> Self {
+        let mut triangles = Vec::new();
+        for (express_id, positions, indices) in meshes {
+            let vertex_count = positions.len() / 3;
+            for (index, tri) in indices.chunks_exact(3).enumerate() {
+                if tri.iter().any(|&i| i as usize >= vertex_count) {
+                    continue;
+                }
+                let vertices = [tri[0], tri[1], tri[2]].map(|i| {
+                    let i = i as usize * 3;
+                    [positions[i], positions[i + 1], positions[i + 2]]
+                });
+                if vertices.iter().flatten().all(|c| c.is_finite()) {
+                    triangles.push(Triangle {
+                        vertices,
+                        express_id,
+                        index: index as u32,
+                    });
+                }
+            }
+        }
+
+        let mut bvh = Self {
+            nodes: Vec::with_capacity(2 * triangles.len() / LEAF_SIZE + 1),
+            triangles,
+        };
+        if !bvh.triangles.is_empty() {
+            bvh.nodes.push(Node {
+                bounds: Aabb::EMPTY,
+                start: 0,
+                count: bvh.triangles.len() as u32,
+            });
+            bvh.subdivide(0);
+        }
+        bvh
+    }
+
+    fn subdivide(&mut self, node_index: usize) {
+        let Node { start, count, .. } = self.nodes[node_index];
+        let range = start as usize..(start + count) as usize;
+
+        let mut bounds = Aabb::EMPTY;
+        let mut centroids = Aabb::EMPTY;
+        for tri in &self.triangles[range.clone()] {
+            tri.vertices.iter().for_each(|v| bounds.grow(v));
+            centroids.grow(&[0, 1, 2].map(|axis| tri.centroid(axis)));
+        }
+        self.nodes[node_index].bounds = bounds;
+        if (count as usize) <= LEAF_SIZE {
+            return;
+        }
+
+        let axis = (0..3)
+            .max_by(|&a, &b| {
+                let extent = |axis: usize| centroids.max[axis] - centroids.min[axis];
+                extent(a).total_cmp(&extent(b))
+            })
+            .unwrap_or(0);
+        let half = count as usize / 2;
+        self.triangles[range]
+            .select_nth_unstable_by(half, |a, b| a.centroid(axis).total_cmp(&b.centroid(axis)));
+
+        let left = self.nodes.len();
+        self.nodes.push(Node {
+            bounds: Aabb::EMPTY,
+            start,
+            count: half as u32,
+        });
+        self.nodes.push(Node {
+            bounds: Aabb::EMPTY,
+            start: start + half as u32,
+            count: count - half as u32,
+        });
+        self.nodes[node_index].start = left as u32;
+        self.nodes[node_index].count = 0;
+        self.subdivide(left);
+        self.subdivide(left + 1);
+    }
+
+    /// Number of indexed triangles
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Whether the hierarchy holds no triangles
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Bounds of all triangles as (min, max)
+    pub fn bounds(&self) -> Option<(Point3<f32>, Point3<f32>)> {
+        self.nodes
+            .first()
+            .map(|root| (Point3::from(root.bounds.min), Point3::from(root.bounds.max)))
+    }
+
+    /// Closest hit within `max_distance`
+    pub fn raycast(&self, ray: &Ray, max_distance: f64) -> Option<RayHit> {
+        let mut closest: Option<(f64, usize)> = None;
+        self.traverse(ray, max_distance, |t, slot| {
+            if closest.is_none_or(|(best, _)| t < best) {
+                closest = Some((t, slot));
+            }
+            // Later nodes only matter if they can beat this hit
+            closest.map_or(max_distance, |(best, _)| best)
+        });
+        closest.map(|(t, slot)| self.hit(ray, t, slot))
+    }
+
+    /// All hits within `max_distance`, nearest first
+    pub fn raycast_all(&self, ray: &Ray, max_distance: f64) -> Vec<RayHit> {
+        let mut hits = Vec::new();
+        self.traverse(ray, max_distance, |t, slot| {
+            hits.push(self.hit(ray, t, slot));
+            max_distance
+        });
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Express IDs (sorted, deduplicated) with a triangle whose bounds
+    /// overlap the box
+    pub fn elements_in_box(&self, min: Point3<f32>, max: Point3<f32>) -> Vec<u32> {
+        let (min, max) = (min.coords.into(), max.coords.into());
+        let mut ids = Vec::new();
+        let mut stack = vec![0usize];
+        while let Some(index) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else {
+                continue;
+            };
+            if !node.bounds.overlaps(&min, &max) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.extend([node.start as usize, node.start as usize + 1]);
+                continue;
+            }
+            for tri in &self.triangles[node.start as usize..(node.start + node.count) as usize] {
+                let mut bounds = Aabb::EMPTY;
+                tri.vertices.iter().for_each(|v| bounds.grow(v));
+                if bounds.overlaps(&min, &max) {
+                    ids.push(tri.express_id);
+                }
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Express IDs (sorted) of elements enclosing `point`.
+    ///
+    /// Each of three skewed rays counts how often it crosses each element's
+    /// surface; the point is inside an element when at least two rays cross
+    /// it an odd number of times. Only meaningful for closed meshes such as
+    /// spaces and solids.
+    pub fn elements_containing(&self, point: Point3<f64>) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.containment(point).into_keys().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Innermost element enclosing `point`, e.g. the room a sensor is in.
+    ///
+    /// Of the [containing](Self::elements_containing) elements, returns the
+    /// one whose surface is nearest to the point.
+    pub fn which_element_contains(&self, point: Point3<f64>) -> Option<u32> {
+        self.containment(point)
+            .into_iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+            .map(|(id, _)| id)
+    }
+
+    /// Containing elements with the distance to their nearest crossing
+    fn containment(&self, point: Point3<f64>) -> FxHashMap<u32, f64> {
+        // express ID → (rays with odd parity, nearest crossing)
+        let mut votes: FxHashMap<u32, (usize, f64)> = FxHashMap::default();
+        for direction in CONTAINMENT_DIRECTIONS {
+            let ray = Ray::new(point, Vector3::from(direction));
+            // express ID → (crossings, distance of the last one)
+            let mut crossings: FxHashMap<u32, (usize, f64)> = FxHashMap::default();
+            for hit in self.raycast_all(&ray, f64::MAX) {
+                let entry = crossings
+                    .entry(hit.express_id)
+                    .or_insert((0, f64::NEG_INFINITY));
+                if hit.distance - entry.1 > CROSSING_TOLERANCE {
+                    *entry = (entry.0 + 1, hit.distance);
+                }
+                let vote = votes.entry(hit.express_id).or_insert((0, f64::MAX));
+                vote.1 = vote.1.min(hit.distance);
+            }
+            for (id, (count, _)) in crossings {
+                if count % 2 == 1 {
+                    votes.entry(id).or_insert((0, f64::MAX)).0 += 1;
+                }
+            }
+        }
+        votes
+            .into_iter()
+            .filter(|(_, (odd, _))| *odd >= 2)
+            .map(|(id, (_, nearest))| (id, nearest))
+            .collect()
+    }
+
+    /// Visit triangle hits front to back where possible. `on_hit` returns
+    /// the distance beyond which nodes can be skipped.
+    fn traverse(&self, ray: &Ray, max_distance: f64, mut on_hit: impl FnMut(f64, usize) -> f64) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let origin = [ray.origin.x, ray.origin.y, ray.origin.z];
+        let inv_dir = [ray.direction.x, ray.direction.y, ray.direction.z].map(|d| 1.0 / d);
+        let mut limit = max_distance;
+
+        let mut stack = vec![0usize];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.bounds.ray_entry(&origin, &inv_dir, limit).is_none() {
+                continue;
+            }
+            if node.count > 0 {
+                let start = node.start as usize;
+                for slot in start..start + node.count as usize {
+                    if let Some(t) = self.triangles[slot].intersect(ray) {
+                        if t <= limit {
+                            limit = on_hit(t, slot);
+                        }
+                    }
+                }
+                continue;
+            }
+
+            // Push the farther child first so the nearer one is visited first
+            let (left, right) = (node.start as usize, node.start as usize + 1);
+            let entry = |i: usize| self.nodes[i].bounds.ray_entry(&origin, &inv_dir, limit);
+            match (entry(left), entry(right)) {
+                (Some(l), Some(r)) if l <= r => stack.extend([right, left]),
+                (Some(_), Some(_)) => stack.extend([left, right]),
+                (Some(_), None) => stack.push(left),
+                (None, Some(_)) => stack.push(right),
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn hit(&self, ray: &Ray, t: f64, slot: usize) -> RayHit {
+        let tri = &self.triangles[slot];
+        let [v0, v1, v2] = tri.points();
+        let normal = (v1 - v0)
+            .cross(&(v2 - v0))
+            .try_normalize(0.0)
+            .unwrap_or_else(Vector3::zeros);
+        RayHit {
+            express_id: tri.express_id,
+            triangle: tri.index,
+            distance: t,
+            point: ray.origin + ray.direction * t,
+            normal: if normal.dot(&ray.direction) > 0.0 {
+                -normal
+            } else {
+                normal
+            },
+        }
+    }
+
+    /// Serialize (little endian): triangle count, then per triangle nine
+    /// coordinates, express ID and mesh triangle index; node count, then
+    /// per node its bounds, start and count.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(
+            8 + self.triangles.len() * TRIANGLE_BYTES + self.nodes.len() * NODE_BYTES,
+        );
+        out.extend_from_slice(&(self.triangles.len() as u32).to_le_bytes());
+        for tri in &self.triangles {
+            for c in tri.vertices.iter().flatten() {
+                out.extend_from_slice(&c.to_le_bytes());
+            }
+            out.extend_from_slice(&tri.express_id.to_le_bytes());
+            out.extend_from_slice(&tri.index.to_le_bytes());
+        }
+        out.extend_from_slice(&(self.nodes.len() as u32).to_le_bytes());
+        for node in &self.nodes {
+            for c in node.bounds.min.iter().chain(&node.bounds.max) {
+                out.extend_from_slice(&c.to_le_bytes());
+            }
+            out.extend_from_slice(&node.start.to_le_bytes());
+            out.extend_from_slice(&node.count.to_le_bytes());
+        }
+        out
+    }
+
+    /// Load bytes written by [`Self::to_bytes`], checking that every leaf
+    /// range lies within the triangles and every inner node points forward
+    /// to a pair of existing children
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader(bytes);
+
+        let triangle_count = r.u32()? as usize;
+        let mut triangles = Vec::with_capacity(triangle_count.min(bytes.len() / TRIANGLE_BYTES));
+        for _ in 0..triangle_count {
+            let mut vertices = [[0.0; 3]; 3];
+            for c in vertices.iter_mut().flatten() {
+                *c = r.f32()?;
+            }
+            triangles.push(Triangle {
+                vertices,
+                express_id: r.u32()?,
+                index: r.u32()?,
+            });
+        }
+
+        let node_count = r.u32()? as usize;
+        let mut nodes = Vec::with_capacity(node_count.min(bytes.len() / NODE_BYTES));
+        for index in 0..node_count {
+            let mut bounds = Aabb::EMPTY;
+            for c in bounds.min.iter_mut().chain(bounds.max.iter_mut()) {
+                *c = r.f32()?;
+            }
+            let (start, count) = (r.u32()? as usize, r.u32()? as usize);
+            let in_range = if count == 0 {
+                start > index && start + 1 < node_count
+            } else {
+                start + count <= triangle_count
+            };
+            if !in_range {
+                return Err(Error::geometry("Corrupt BVH: bad node range"));
+            }
+            nodes.push(Node {
+                bounds,
+                start: start as u32,
+                count: count as u32,
+            });
+        }
+
+        if !r.0.is_empty() {
+            return Err(Error::geometry("Corrupt BVH: trailing data"));
+        }
+        if nodes.is_empty() != triangles.is_empty() {
+            return Err(Error::geometry("Corrupt BVH: no root node"));
+        }
+        Ok(Self { nodes, triangles })
+    }
+}
+
+/// Serialized size of a triangle: 9 coordinates, express ID, index
+const TRIANGLE_BYTES: usize = 11 * 4;
+/// Serialized size of a node: 6 bound coordinates, start, count
+const NODE_BYTES: usize = 8 * 4;
+
+/// Bounds-checked little-endian reader
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let (head, tail) = self
+            .0
+            .split_first_chunk::<N>()
+            .ok_or_else(|| Error::geometry("Truncated BVH data"))?;
+        self.0 = tail;
+        Ok(*head)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Result<f32> {
+        self.take().map(f32::from_le_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extrude_profile, Profile2D};
+    use nalgebra::{Matrix4, Point2};
+
+    fn unit_box(x: f64) -> Mesh {
+        let square = Profile2D::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(0.0, 1.0),
+        ]);
+        let transform = Matrix4::new_translation(&Vector3::new(x, 0.0, 0.0));
+        extrude_profile(&square, 1.0, Some(transform)).unwrap()
+    }
+
+    #[test]
+    fn test_bvh_ray_and_box_queries() {
+        let boxes: Vec<(u32, Mesh)> = (0..50)
+            .map(|i| (100 + i, unit_box(i as f64 * 2.0)))
+            .collect();
+        let bvh = Bvh::build(boxes.iter().map(|(id, mesh)| (*id, mesh)));
+        assert_eq!(bvh.triangle_count(), 50 * 12);
+        assert_eq!(bvh.bounds().unwrap().1.x, 99.0);
+
+        // Along +X through the middle of the row: first box 5, entered at x = 10
+        let ray = Ray::new(Point3::new(9.5, 0.5, 0.25), Vector3::new(3.0, 0.0, 0.0));
+        let hit = bvh.raycast(&ray, f64::MAX).unwrap();
+        assert_eq!(hit.express_id, 105);
+        assert!((hit.distance - 0.5).abs() < 1e-9);
+        assert!((hit.point.x - 10.0).abs() < 1e-9);
+        assert_eq!(hit.normal, Vector3::new(-1.0, 0.0, 0.0));
+
+        // Limited range: boxes 5 and 6 only, entry and exit each
+        let hits = bvh.raycast_all(&ray, 4.0);
+        let ids: Vec<u32> = hits.iter().map(|h| h.express_id).collect();
+        assert_eq!(ids, [105, 105, 106, 106]);
+        assert!(hits.windows(2).all(|w| w[0].distance <= w[1].distance));
+
+        // Straight down between boxes misses
+        let gap = Ray::new(Point3::new(11.5, 0.5, 5.0), Vector3::new(0.0, 0.0, -1.0));
+        assert!(bvh.raycast(&gap, f64::MAX).is_none());
+
+        let ids = bvh.elements_in_box(Point3::new(3.5, 0.2, 0.2), Point3::new(6.5, 0.8, 0.8));
+        assert_eq!(ids, [102, 103]);
+        assert!(Bvh::build([]).raycast(&ray, f64::MAX).is_none());
+    }
+
+    #[test]
+    fn test_bvh_bytes_round_trip() {
+        let boxes: Vec<(u32, Mesh)> = (0..20).map(|i| (i, unit_box(i as f64 * 2.0))).collect();
+        let bvh = Bvh::build(boxes.iter().map(|(id, mesh)| (*id, mesh)));
+        let bytes = bvh.to_bytes();
+
+        let restored = Bvh::from_bytes(&bytes).unwrap();
+        let ray = Ray::new(Point3::new(14.5, 0.5, 10.0), Vector3::new(0.0, 0.0, -1.0));
+        assert_eq!(
+            restored.raycast(&ray, f64::MAX),
+            bvh.raycast(&ray, f64::MAX)
+        );
+        assert_eq!(restored.triangle_count(), bvh.triangle_count());
+        assert!(Bvh::from_bytes(&Bvh::default().to_bytes())
+            .unwrap()
+            .is_empty());
+
+        // Truncated, trailing bytes, and a root pointing at itself
+        assert!(Bvh::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Bvh::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        let mut cyclic = bytes.clone();
+        let root = 8 + bvh.triangle_count() * TRIANGLE_BYTES;
+        cyclic[root + 24..root + 32].copy_from_slice(&[0; 8]);
+        assert!(Bvh::from_bytes(&cyclic).is_err());
+    }
+
+    #[test]
+    fn test_point_containment() {
+        let cuboid = |min: f64, size: f64| {
+            let square = Profile2D::new(vec![
+                Point2::new(min, min),
+                Point2::new(min + size, min),
+                Point2::new(min + size, min + size),
+                Point2::new(min, min + size),
+            ]);
+            let transform = Matrix4::new_translation(&Vector3::new(0.0, 0.0, min));
+            extrude_profile(&square, size, Some(transform)).unwrap()
+        };
+        // A room with a column-like box inside it, and one outside
+        let meshes = [
+            (1, cuboid(0.0, 10.0)),
+            (2, cuboid(2.0, 1.0)),
+            (3, cuboid(20.0, 1.0)),
+        ];
+        let bvh = Bvh::build(meshes.iter().map(|(id, mesh)| (*id, mesh)));
+
+        let inside_both = Point3::new(2.5, 2.5, 2.5);
+        assert_eq!(bvh.elements_containing(inside_both), [1, 2]);
+        assert_eq!(bvh.which_element_contains(inside_both), Some(2));
+
+        // Level with the box's faces, so rays pass its edges
+        let room_only = Point3::new(5.0, 2.0, 3.0);
+        assert_eq!(bvh.elements_containing(room_only), [1]);
+        assert_eq!(bvh.which_element_contains(room_only), Some(1));
+
+        assert!(bvh
+            .elements_containing(Point3::new(15.0, 5.0, 5.0))
+            .is_empty());
+        assert_eq!(
+            bvh.which_element_contains(Point3::new(-1.0, 5.0, 5.0)),
+            None
+        );
+    }
+}
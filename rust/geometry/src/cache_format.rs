@@ -0,0 +1,366 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Binary format for cached processed geometry
+//!
+//! One encoding of per-element [`Mesh`]es and [`SubMeshCollection`]s shared
+//! by every geometry cache (desktop, server disk cache, browser storage), so
+//! a cache written by one can be read by the others.
+//!
+//! Layout, all little-endian:
+//!
+//! ```text
+//! header   magic "IFCG" | version u16 | kind u8 | reserved u8
+//!          payload length u64 | payload CRC-32 u32
+//! payload  element count u32, then per element:
+//!          kind Meshes:    express ID u32 | mesh
+//!          kind SubMeshes: express ID u32 | count u32 |
+//!                          (geometry ID u32 | material u32 | mesh) × count
+//! mesh     flags u8 | vertex count u32 | index count u32 |
+//!          positions f32 × 3n | normals f32 × 3n (if flagged) | indices u32
+//! ```
+//!
+//! A material of `u32::MAX` means none. Readers reject unknown versions,
+//! truncated data and checksum mismatches rather than returning partial
+//! geometry; a stale cache entry should simply be rebuilt.
+
+use crate::error::{Error, Result};
+use crate::materials::MaterialId;
+use crate::mesh::{Mesh, SubMesh, SubMeshCollection};
+
+/// File signature
+const MAGIC: [u8; 4] = *b"IFCG";
+
+/// Current format version; bump on any layout change
+pub const CACHE_FORMAT_VERSION: u16 = 1;
+
+/// Header size in bytes
+const HEADER_LEN: usize = 20;
+
+/// Mesh flag: normals are stored
+const FLAG_NORMALS: u8 = 1;
+/// Mesh flag: [`Mesh::rtc_applied`]
+const FLAG_RTC_APPLIED: u8 = 1 << 1;
+
+/// Material slot of sub-meshes without one
+const NO_MATERIAL: u32 = u32::MAX;
+
+/// What a cache blob holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum Kind {
+    Meshes = 0,
+    SubMeshes = 1,
+}
+
+/// Encode one mesh per element
+pub fn encode_meshes<'a>(meshes: impl IntoIterator<Item = (u32, &'a Mesh)>) -> Vec<u8> {
+    let mut payload = Vec::new();
+    let mut count = 0u32;
+    for (express_id, mesh) in meshes {
+        put_u32(&mut payload, express_id);
+        write_mesh(&mut payload, mesh);
+        count += 1;
+    }
+    finish(Kind::Meshes, count, payload)
+}
+
+/// Decode a blob written by [`encode_meshes`]
+pub fn decode_meshes(bytes: &[u8]) -> Result<Vec<(u32, Mesh)>> {
+    let (mut reader, count) = open(bytes, Kind::Meshes)?;
+    let mut meshes = Vec::new();
+    for _ in 0..count {
+        let express_id = reader.u32()?;
+        meshes.push((express_id, reader.mesh()?));
+    }
+    reader.finish()?;
+    Ok(meshes)
+}
+
+/// Encode the sub-meshes of each element, keeping geometry IDs and materials
+pub fn encode_sub_meshes<'a>(
+    elements: impl IntoIterator<Item = (u32, &'a SubMeshCollection)>,
+) -> Vec<u8> {
+    let mut payload = Vec::new();
+    let mut count = 0u32;
+    for (express_id, collection) in elements {
+        put_u32(&mut payload, express_id);
+        put_u32(&mut payload, collection.sub_meshes.len() as u32);
+        for sub in &collection.sub_meshes {
+            put_u32(&mut payload, sub.geometry_id);
+            put_u32(&mut payload, sub.material.unwrap_or(NO_MATERIAL));
+            write_mesh(&mut payload, &sub.mesh);
+        }
+        count += 1;
+    }
+    finish(Kind::SubMeshes, count, payload)
+}
+
+/// Decode a blob written by [`encode_sub_meshes`]
+pub fn decode_sub_meshes(bytes: &[u8]) -> Result<Vec<(u32, SubMeshCollection)>> {
+    let (mut reader, count) = open(bytes, Kind::SubMeshes)?;
+    let mut elements = Vec::new();
+    for _ in 0..count {
+        let express_id = reader.u32()?;
+        let sub_count = reader.u32()?;
+        let mut collection = SubMeshCollection::new();
+        for _ in 0..sub_count {
+            let geometry_id = reader.u32()?;
+            let material = reader.u32()?;
+            let mesh = reader.mesh()?;
+            collection.sub_meshes.push(
+                SubMesh::new(geometry_id, mesh)
+                    .with_material((material != NO_MATERIAL).then_some(material as MaterialId)),
+            );
+        }
+        elements.push((express_id, collection));
+    }
+    reader.finish()?;
+    Ok(elements)
+}
+
+fn write_mesh(out: &mut Vec<u8>, mesh: &Mesh) {
+    let vertex_count = mesh.positions.len() / 3;
+    let has_normals = mesh.normals.len() == vertex_count * 3 && vertex_count > 0;
+    let mut flags = 0;
+    if has_normals {
+        flags |= FLAG_NORMALS;
+    }
+    if mesh.rtc_applied {
+        flags |= FLAG_RTC_APPLIED;
+    }
+    out.push(flags);
+    put_u32(out, vertex_count as u32);
+    put_u32(out, mesh.indices.len() as u32);
+    out.extend(
+        mesh.positions[..vertex_count * 3]
+            .iter()
+            .flat_map(|v| v.to_le_bytes()),
+    );
+    if has_normals {
+        out.extend(mesh.normals.iter().flat_map(|v| v.to_le_bytes()));
+    }
+    out.extend(mesh.indices.iter().flat_map(|i| i.to_le_bytes()));
+}
+
+/// Prefix the payload with its element count and the header
+fn finish(kind: Kind, count: u32, payload: Vec<u8>) -> Vec<u8> {
+    let mut body = Vec::with_capacity(4 + payload.len());
+    put_u32(&mut body, count);
+    body.extend_from_slice(&payload);
+
+    let mut out = Vec::with_capacity(HEADER_LEN + body.len());
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+    out.push(kind as u8);
+    out.push(0);
+    out.extend_from_slice(&(body.len() as u64).to_le_bytes());
+    put_u32(&mut out, crc32(&body));
+    out.extend_from_slice(&body);
+    out
+}
+
+/// Validate the header and checksum, returning a reader over the payload
+/// and its element count
+fn open(bytes: &[u8], kind: Kind) -> Result<(Reader<'_>, u32)> {
+    let corrupt = |what: &str| Error::geometry(format!("Geometry cache: {}", what));
+    if bytes.len() < HEADER_LEN || bytes[..4] != MAGIC {
+        return Err(corrupt("not a geometry cache"));
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version != CACHE_FORMAT_VERSION {
+        return Err(corrupt(&format!(
+            "unsupported version {} (expected {})",
+            version, CACHE_FORMAT_VERSION
+        )));
+    }
+    if bytes[6] != kind as u8 {
+        return Err(corrupt("unexpected content kind"));
+    }
+
+    let mut header = Reader {
+        bytes: &bytes[8..HEADER_LEN],
+    };
+    let payload_len = u64::from_le_bytes(header.take(8)?.try_into().unwrap_or_default());
+    let checksum = header.u32()?;
+    let payload = &bytes[HEADER_LEN..];
+    if payload.len() as u64 != payload_len {
+        return Err(corrupt("truncated data"));
+    }
+    if crc32(payload) != checksum {
+        return Err(corrupt("checksum mismatch"));
+    }
+
+    let mut reader = Reader { bytes: payload };
+    let count = reader.u32()?;
+    Ok((reader, count))
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Bounds-checked cursor over a payload
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(Error::geometry("Geometry cache: truncated data"));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// `count` 4-byte little-endian words
+    fn words<T>(&mut self, count: usize, from_le: impl Fn([u8; 4]) -> T) -> Result<Vec<T>> {
+        let len = count
+            .checked_mul(4)
+            .ok_or_else(|| Error::geometry("Geometry cache: truncated data"))?;
+        Ok(self
+            .take(len)?
+            .chunks_exact(4)
+            .map(|w| from_le([w[0], w[1], w[2], w[3]]))
+            .collect())
+    }
+
+    fn mesh(&mut self) -> Result<Mesh> {
+        let flags = self.take(1)?[0];
+        let vertex_count = self.u32()? as usize;
+        let index_count = self.u32()? as usize;
+        let positions = self.words(vertex_count * 3, f32::from_le_bytes)?;
+        let normals = if flags & FLAG_NORMALS != 0 {
+            self.words(vertex_count * 3, f32::from_le_bytes)?
+        } else {
+            Vec::new()
+        };
+        let indices = self.words(index_count, u32::from_le_bytes)?;
+        if indices.iter().any(|&i| i as usize >= vertex_count) {
+            return Err(Error::geometry("Geometry cache: index out of range"));
+        }
+        Ok(Mesh {
+            positions,
+            normals,
+            indices,
+            rtc_applied: flags & FLAG_RTC_APPLIED != 0,
+        })
+    }
+
+    /// Reject trailing bytes after the last element
+    fn finish(self) -> Result<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::geometry("Geometry cache: trailing data"))
+        }
+    }
+}
+
+/// CRC-32 (IEEE 802.3, as used by zip and PNG)
+fn crc32(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 {
+                    0xEDB8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                k += 1;
+            }
+            table[i] = c;
+            i += 1;
+        }
+        table
+    };
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(offset: f32, with_normals: bool) -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.positions = vec![offset, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        if with_normals {
+            mesh.normals = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+        }
+        mesh.indices = vec![0, 1, 2];
+        mesh
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_meshes_round_trip() {
+        let mut shifted = triangle(0.5, false);
+        shifted.rtc_applied = true;
+        let meshes = [(10, triangle(0.0, true)), (11, shifted), (12, Mesh::new())];
+        let bytes = encode_meshes(meshes.iter().map(|(id, mesh)| (*id, mesh)));
+
+        let decoded = decode_meshes(&bytes).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for ((id, mesh), (expected_id, expected)) in decoded.iter().zip(&meshes) {
+            assert_eq!(id, expected_id);
+            assert_eq!(mesh.positions, expected.positions);
+            assert_eq!(mesh.normals, expected.normals);
+            assert_eq!(mesh.indices, expected.indices);
+            assert_eq!(mesh.rtc_applied, expected.rtc_applied);
+        }
+        // Wrong kind is rejected rather than misread
+        assert!(decode_sub_meshes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_sub_meshes_round_trip() {
+        let mut collection = SubMeshCollection::new();
+        collection.add_with_material(100, triangle(0.0, true), Some(3));
+        collection.add(101, triangle(2.0, false));
+        let bytes = encode_sub_meshes([(42, &collection)]);
+
+        let decoded = decode_sub_meshes(&bytes).unwrap();
+        let (id, subs) = &decoded[0];
+        assert_eq!(*id, 42);
+        assert_eq!(subs.len(), 2);
+        assert_eq!(subs.sub_meshes[0].geometry_id, 100);
+        assert_eq!(subs.sub_meshes[0].material, Some(3));
+        assert_eq!(subs.sub_meshes[1].material, None);
+        assert_eq!(subs.sub_meshes[1].mesh.positions[0], 2.0);
+    }
+
+    #[test]
+    fn test_corrupt_data_rejected() {
+        let bytes = encode_meshes([(1, &triangle(0.0, true))]);
+
+        let mut flipped = bytes.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert!(decode_meshes(&flipped).is_err());
+
+        assert!(decode_meshes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut future = bytes.clone();
+        future[4] = 2;
+        assert!(decode_meshes(&future).is_err());
+
+        assert!(decode_meshes(b"not a cache").is_err());
+    }
+}
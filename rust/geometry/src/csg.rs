@@ -6,9 +6,11 @@
 //!
 //! Fast triangle clipping and boolean operations.
 
+use crate::bsp::{self, Polygon};
 use crate::error::Result;
+use crate::exact;
 use crate::mesh::Mesh;
-use crate::triangulation::{calculate_polygon_normal, project_to_2d, triangulate_polygon};
+use crate::triangulation::calculate_polygon_normal;
 use nalgebra::{Point3, Vector3};
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
@@ -109,19 +111,66 @@ impl Triangle {
     }
 }
 
-/// Maximum polygon count for either operand in a csgrs boolean operation.
+/// Distance below which CSG output vertices with matching normals are merged.
+const CSG_WELD_TOLERANCE: f32 = 1e-5;
+
+/// Arithmetic used for mesh boolean operations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsgBackend {
+    /// Floating-point BSP, retried with `Exact` when it fails or produces
+    /// degenerate output
+    #[default]
+    Auto,
+    /// Floating-point BSP only (fastest)
+    Bsp,
+    /// Plane-based exact arithmetic (see [`crate::exact`])
+    Exact,
+}
+
+/// Boolean operation kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BooleanOp {
+    Difference,
+    Union,
+    Intersection,
+}
+
+/// Work limits for mesh boolean operations.
 ///
-/// Rectangular solids are 12 triangles, so this still allows the simple box-like
-/// boolean cases we expect while avoiding the complex BSP trees that can overflow
-/// the browser's native call stack in WASM.
-const MAX_CSG_POLYGONS_PER_MESH: usize = 24;
-/// Maximum combined polygon count for CSG operations.
-const MAX_CSG_POLYGONS: usize = MAX_CSG_POLYGONS_PER_MESH * 2;
+/// Operations that exceed a limit fall back the same way as any other failed
+/// boolean (host left uncut, union merged, intersection empty).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsgLimits {
+    /// Maximum polygon count for either operand.
+    ///
+    /// Rectangular solids are 12 triangles, so the default allows the simple
+    /// box-like cases we expect; complex operands are left to the cheaper
+    /// clipping paths, which produce fewer slivers on real models.
+    pub max_operand_polygons: usize,
+    /// Maximum BSP tree depth.
+    pub max_depth: usize,
+    /// Maximum number of polygon splits across the whole operation.
+    pub max_splits: usize,
+}
+
+impl Default for CsgLimits {
+    fn default() -> Self {
+        Self {
+            max_operand_polygons: 24,
+            max_depth: 512,
+            max_splits: 20_000,
+        }
+    }
+}
 
 /// CSG Clipping Processor
 pub struct ClippingProcessor {
     /// Epsilon for floating point comparisons
     pub epsilon: f64,
+    /// Limits for BSP-based boolean operations
+    pub limits: CsgLimits,
+    /// Boolean backend
+    pub backend: CsgBackend,
 }
 
 /// Create a box mesh from AABB min/max bounds
@@ -169,27 +218,30 @@ fn aabb_to_mesh(min: Point3<f64>, max: Point3<f64>) -> Mesh {
 
 impl ClippingProcessor {
     #[inline]
-    fn can_run_csgrs_operation(
-        csg_a: &csgrs::mesh::Mesh<()>,
-        csg_b: &csgrs::mesh::Mesh<()>,
-    ) -> bool {
-        let polygons_a = csg_a.polygons.len();
-        let polygons_b = csg_b.polygons.len();
-
-        if polygons_a < 4 || polygons_b < 4 {
-            return false;
-        }
+    fn can_run_csg_operation(&self, polygons_a: &[Polygon], polygons_b: &[Polygon]) -> bool {
+        let max = self.limits.max_operand_polygons;
+        (4..=max).contains(&polygons_a.len()) && (4..=max).contains(&polygons_b.len())
+    }
 
-        if polygons_a > MAX_CSG_POLYGONS_PER_MESH || polygons_b > MAX_CSG_POLYGONS_PER_MESH {
-            return false;
+    /// Create a new clipping processor
+    pub fn new() -> Self {
+        Self {
+            epsilon: 1e-6,
+            limits: CsgLimits::default(),
+            backend: CsgBackend::default(),
         }
+    }
 
-        polygons_a + polygons_b <= MAX_CSG_POLYGONS
+    /// Use a specific boolean backend
+    pub fn with_backend(mut self, backend: CsgBackend) -> Self {
+        self.backend = backend;
+        self
     }
 
-    /// Create a new clipping processor
-    pub fn new() -> Self {
-        Self { epsilon: 1e-6 }
+    /// Use custom limits for boolean operations
+    pub fn with_limits(mut self, limits: CsgLimits) -> Self {
+        self.limits = limits;
+        self
     }
 
     /// Clip a triangle against a plane
@@ -526,183 +578,123 @@ impl ClippingProcessor {
         Some((contour, normalized_normal))
     }
 
-    /// Convert our Mesh format to csgrs Mesh format
-    fn mesh_to_csgrs(mesh: &Mesh) -> Result<csgrs::mesh::Mesh<()>> {
-        use csgrs::mesh::{polygon::Polygon, vertex::Vertex, Mesh as CSGMesh};
-        use std::sync::OnceLock;
-
-        if mesh.is_empty() {
-            return Ok(CSGMesh {
-                polygons: Vec::new(),
-                bounding_box: OnceLock::new(),
-                metadata: None,
-            });
-        }
-
-        // Validate mesh has enough indices for at least one triangle
-        if mesh.indices.len() < 3 {
-            return Ok(CSGMesh {
-                polygons: Vec::new(),
-                bounding_box: OnceLock::new(),
-                metadata: None,
-            });
-        }
-
+    /// Convert a mesh to BSP polygons, skipping invalid and degenerate triangles
+    fn mesh_to_polygons(mesh: &Mesh) -> Vec<Polygon> {
         let vertex_count = mesh.positions.len() / 3;
-        let triangle_count = mesh.indices.len() / 3;
-
-        // Pre-allocate for expected number of triangles (avoids reallocations)
-        let mut polygons = Vec::with_capacity(triangle_count);
-
-        // Process each triangle using chunks_exact to ensure bounds safety
-        // (handles the case where indices.len() is not divisible by 3)
-        for chunk in mesh.indices.chunks_exact(3) {
-            let i0 = chunk[0] as usize;
-            let i1 = chunk[1] as usize;
-            let i2 = chunk[2] as usize;
-
-            // Bounds check for vertex indices - skip invalid triangles
-            if i0 >= vertex_count || i1 >= vertex_count || i2 >= vertex_count {
-                continue;
-            }
-
-            // Get triangle vertices
-            // Note: bounds are guaranteed by the vertex_count check above
-            let p0_idx = i0 * 3;
-            let p1_idx = i1 * 3;
-            let p2_idx = i2 * 3;
-
-            let v0 = Point3::new(
-                mesh.positions[p0_idx] as f64,
-                mesh.positions[p0_idx + 1] as f64,
-                mesh.positions[p0_idx + 2] as f64,
-            );
-            let v1 = Point3::new(
-                mesh.positions[p1_idx] as f64,
-                mesh.positions[p1_idx + 1] as f64,
-                mesh.positions[p1_idx + 2] as f64,
-            );
-            let v2 = Point3::new(
-                mesh.positions[p2_idx] as f64,
-                mesh.positions[p2_idx + 1] as f64,
-                mesh.positions[p2_idx + 2] as f64,
-            );
-
-            // Skip triangles with NaN or Infinity values
-            if !v0.x.is_finite()
-                || !v0.y.is_finite()
-                || !v0.z.is_finite()
-                || !v1.x.is_finite()
-                || !v1.y.is_finite()
-                || !v1.z.is_finite()
-                || !v2.x.is_finite()
-                || !v2.y.is_finite()
-                || !v2.z.is_finite()
-            {
-                continue;
-            }
-
-            // Calculate face normal from triangle edges
-            // Use try_normalize to handle degenerate (zero-area/collinear) triangles
-            let edge1 = v1 - v0;
-            let edge2 = v2 - v0;
-            let face_normal = match edge1.cross(&edge2).try_normalize(1e-10) {
-                Some(n) => n,
-                None => continue, // Skip degenerate triangles to avoid NaN propagation
-            };
-            // Note: try_normalize returns a unit vector, which is always finite
-
-            // Create csgrs vertices (use face normal for all vertices)
-            let vertices = vec![
-                Vertex::new(v0, face_normal),
-                Vertex::new(v1, face_normal),
-                Vertex::new(v2, face_normal),
-            ];
-
-            polygons.push(Polygon::new(vertices, None));
-        }
+        let point = |i: usize| {
+            Point3::new(
+                mesh.positions[i * 3] as f64,
+                mesh.positions[i * 3 + 1] as f64,
+                mesh.positions[i * 3 + 2] as f64,
+            )
+        };
 
-        Ok(CSGMesh::from_polygons(&polygons, None))
+        // chunks_exact ignores a trailing partial triangle
+        mesh.indices
+            .chunks_exact(3)
+            .filter_map(|chunk| {
+                let (i0, i1, i2) = (chunk[0] as usize, chunk[1] as usize, chunk[2] as usize);
+                if i0 >= vertex_count || i1 >= vertex_count || i2 >= vertex_count {
+                    return None;
+                }
+                let vertices = vec![point(i0), point(i1), point(i2)];
+                if vertices
+                    .iter()
+                    .any(|v| !(v.x.is_finite() && v.y.is_finite() && v.z.is_finite()))
+                {
+                    return None;
+                }
+                // None for zero-area (collinear) triangles
+                Polygon::new(vertices)
+            })
+            .collect()
     }
 
-    /// Convert csgrs Mesh format back to our Mesh format
-    fn csgrs_to_mesh(csg_mesh: &csgrs::mesh::Mesh<()>) -> Result<Mesh> {
+    /// Convert BSP polygons back to a mesh
+    fn polygons_to_mesh(polygons: &[Polygon]) -> Mesh {
         let mut mesh = Mesh::new();
 
-        for polygon in &csg_mesh.polygons {
+        for polygon in polygons {
+            // BSP fragments of triangles stay convex, so a fan is enough
             let vertices = &polygon.vertices;
-            if vertices.len() < 3 {
-                continue;
+            let normal = polygon.plane.normal;
+            let base_idx = mesh.vertex_count() as u32;
+            for v in vertices {
+                mesh.add_vertex(*v, normal);
             }
-
-            // Extract 3D positions
-            let points_3d: Vec<Point3<f64>> = vertices
-                .iter()
-                .map(|v| Point3::new(v.pos[0], v.pos[1], v.pos[2]))
-                .collect();
-
-            // Get the CSG polygon's intended normal (from first vertex)
-            // Validate and normalize to avoid NaN propagation in project_to_2d
-            let raw_normal = Vector3::new(
-                vertices[0].normal[0],
-                vertices[0].normal[1],
-                vertices[0].normal[2],
-            );
-
-            // Try to normalize the CSG normal; if it fails (zero or NaN), compute from points
-            let csg_normal = match raw_normal.try_normalize(1e-10) {
-                Some(n) if n.x.is_finite() && n.y.is_finite() && n.z.is_finite() => n,
-                _ => {
-                    // Fall back to computing normal from polygon points
-                    let computed = calculate_polygon_normal(&points_3d);
-                    match computed.try_normalize(1e-10) {
-                        Some(n) => n,
-                        None => continue, // Skip degenerate polygon
-                    }
+            for i in 1..vertices.len() - 1 {
+                let area = (vertices[i] - vertices[0])
+                    .cross(&(vertices[i + 1] - vertices[0]))
+                    .norm();
+                if area > 1e-12 {
+                    mesh.add_triangle(base_idx, base_idx + i as u32, base_idx + i as u32 + 1);
                 }
-            };
-
-            // FAST PATH: Triangle - no triangulation needed
-            if points_3d.len() == 3 {
-                let base_idx = mesh.vertex_count();
-                for v in vertices {
-                    mesh.add_vertex(v.pos, v.normal);
-                }
-                mesh.add_triangle(
-                    base_idx as u32,
-                    (base_idx + 1) as u32,
-                    (base_idx + 2) as u32,
-                );
-                continue;
             }
+        }
 
-            // Project 3D polygon to 2D using CSG normal (preserves winding intent)
-            let (points_2d, _, _, _) = project_to_2d(&points_3d, &csg_normal);
-
-            // Triangulate (handles convex AND concave polygons)
-            let indices = match triangulate_polygon(&points_2d) {
-                Ok(idx) => idx,
-                Err(_) => continue, // Skip degenerate polygons
-            };
+        // BSP splitting emits every polygon fragment with its own vertices
+        mesh.weld(CSG_WELD_TOLERANCE);
+        mesh
+    }
 
-            // Add vertices and create triangles (winding is correct from projection)
-            let base_idx = mesh.vertex_count();
-            for v in vertices {
-                mesh.add_vertex(v.pos, v.normal);
+    /// Run a boolean operation with the configured backend
+    fn boolean(
+        &self,
+        op: BooleanOp,
+        mesh_a: &Mesh,
+        mesh_b: &Mesh,
+        polygons_a: Vec<Polygon>,
+        polygons_b: Vec<Polygon>,
+    ) -> Result<Mesh> {
+        if self.backend != CsgBackend::Exact {
+            let result = match op {
+                BooleanOp::Difference => bsp::difference(polygons_a, polygons_b, &self.limits),
+                BooleanOp::Union => bsp::union(polygons_a, polygons_b, &self.limits),
+                BooleanOp::Intersection => bsp::intersection(polygons_a, polygons_b, &self.limits),
             }
+            .map(|polygons| Self::polygons_to_mesh(&polygons));
 
-            for tri in indices.chunks(3) {
-                if tri.len() == 3 {
-                    mesh.add_triangle(
-                        (base_idx + tri[0]) as u32,
-                        (base_idx + tri[1]) as u32,
-                        (base_idx + tri[2]) as u32,
+            match result {
+                Ok(mesh) if self.backend == CsgBackend::Bsp => return Ok(mesh),
+                Err(e) if self.backend == CsgBackend::Bsp => return Err(e),
+                Ok(mesh) if !Self::is_degenerate_result(op, mesh_a, mesh_b, &mesh) => {
+                    return Ok(mesh)
+                }
+                _ => {
+                    #[cfg(debug_assertions)]
+                    eprintln!(
+                        "[ifc-lite] BSP {:?} failed or degenerate, retrying exact",
+                        op
                     );
                 }
             }
         }
+        exact::boolean(op, mesh_a, mesh_b, &self.limits)
+    }
 
-        Ok(mesh)
+    /// Whether a boolean result encloses a volume its operands rule out.
+    ///
+    /// Holes and inverted slivers both show up as a wrong enclosed volume, while
+    /// the T-junctions BSP output always has do not affect it.
+    fn is_degenerate_result(op: BooleanOp, mesh_a: &Mesh, mesh_b: &Mesh, result: &Mesh) -> bool {
+        if result.is_empty() {
+            return true;
+        }
+        let volume_a = mesh_a.signed_volume();
+        let volume_b = mesh_b.signed_volume();
+        // Open or inside-out operands: their volume says nothing about the result
+        if volume_a <= 0.0 || volume_b <= 0.0 {
+            return false;
+        }
+
+        let volume = result.signed_volume();
+        let tolerance = 1e-3 * (volume_a + volume_b);
+        let (low, high) = match op {
+            BooleanOp::Difference => (volume_a - volume_b, volume_a),
+            BooleanOp::Union => (volume_a.max(volume_b), volume_a + volume_b),
+            BooleanOp::Intersection => (0.0, volume_a.min(volume_b)),
+        };
+        volume < low - tolerance || volume > high + tolerance
     }
 
     /// Check if two meshes' bounding boxes overlap
@@ -718,10 +710,8 @@ impl ClippingProcessor {
         overlap_x && overlap_y && overlap_z
     }
 
-    /// Subtract opening mesh from host mesh using csgrs CSG boolean operations
+    /// Subtract opening mesh from host mesh using BSP boolean operations
     pub fn subtract_mesh(&self, host_mesh: &Mesh, opening_mesh: &Mesh) -> Result<Mesh> {
-        use csgrs::traits::CSG;
-
         // Validate input meshes - early exit for empty host (no clone needed)
         if host_mesh.is_empty() {
             return Ok(Mesh::new());
@@ -736,49 +726,36 @@ impl ClippingProcessor {
             return Ok(host_mesh.clone());
         }
 
-        // Convert meshes to csgrs format
-        let host_csg = match Self::mesh_to_csgrs(host_mesh) {
-            Ok(csg) => csg,
-            Err(_) => return Ok(host_mesh.clone()),
-        };
+        let host_polygons = Self::mesh_to_polygons(host_mesh);
+        let opening_polygons = Self::mesh_to_polygons(opening_mesh);
 
-        let opening_csg = match Self::mesh_to_csgrs(opening_mesh) {
-            Ok(csg) => csg,
-            Err(_) => return Ok(host_mesh.clone()),
-        };
-
-        // Validate CSG meshes have enough polygons for a valid operation
-        // Empty or near-empty meshes can cause panics in csgrs
-        if host_csg.polygons.is_empty() || opening_csg.polygons.is_empty() {
-            return Ok(host_mesh.clone());
-        }
-
-        // Safety: only allow simple low-polygon CSG cases. Complex operands are
-        // left uncut rather than risking runaway BSP recursion in csgrs.
-        if !Self::can_run_csgrs_operation(&host_csg, &opening_csg) {
+        // Only allow simple low-polygon CSG cases; complex operands are left uncut
+        if !self.can_run_csg_operation(&host_polygons, &opening_polygons) {
             return Ok(host_mesh.clone());
         }
 
         // Perform CSG difference (host - opening)
-        let result_csg = host_csg.difference(&opening_csg);
-
-        // Check if result is empty
-        if result_csg.polygons.is_empty() {
-            return Ok(host_mesh.clone());
-        }
-
-        // Convert back to our Mesh format
-        match Self::csgrs_to_mesh(&result_csg) {
-            Ok(result) => {
-                // Clean up degenerate triangles (thin slivers from CSG numerical issues)
-                // Note: We don't use remove_triangles_inside_bounds here because it uses
-                // the opening's bounding box, which can incorrectly remove valid triangles
-                // for complex non-rectangular openings.
-                let cleaned = Self::remove_degenerate_triangles(&result, host_mesh);
-                Ok(cleaned)
+        let mesh = match self.boolean(
+            BooleanOp::Difference,
+            host_mesh,
+            opening_mesh,
+            host_polygons,
+            opening_polygons,
+        ) {
+            Ok(mesh) if !mesh.is_empty() => mesh,
+            Ok(_) => return Ok(host_mesh.clone()),
+            Err(_e) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[ifc-lite] CSG difference skipped: {}", _e);
+                return Ok(host_mesh.clone());
             }
-            Err(_) => Ok(host_mesh.clone()),
-        }
+        };
+
+        // Clean up degenerate triangles (thin slivers from CSG numerical issues)
+        // Note: We don't use remove_triangles_inside_bounds here because it uses
+        // the opening's bounding box, which can incorrectly remove valid triangles
+        // for complex non-rectangular openings.
+        Ok(Self::remove_degenerate_triangles(&mesh, host_mesh))
     }
 
     /// Remove degenerate triangles from CSG result
@@ -1022,10 +999,8 @@ impl ClippingProcessor {
         cleaned
     }
 
-    /// Union two meshes together using csgrs CSG boolean operations
+    /// Union two meshes together using BSP boolean operations
     pub fn union_mesh(&self, mesh_a: &Mesh, mesh_b: &Mesh) -> Result<Mesh> {
-        use csgrs::traits::CSG;
-
         // Fast paths
         if mesh_a.is_empty() {
             return Ok(mesh_b.clone());
@@ -1034,59 +1009,59 @@ impl ClippingProcessor {
             return Ok(mesh_a.clone());
         }
 
-        // Convert meshes to csgrs format
-        let csg_a = Self::mesh_to_csgrs(mesh_a)?;
-        let csg_b = Self::mesh_to_csgrs(mesh_b)?;
+        let polygons_a = Self::mesh_to_polygons(mesh_a);
+        let polygons_b = Self::mesh_to_polygons(mesh_b);
 
-        // Validate CSG meshes - fall back to simple merge if invalid
-        if csg_a.polygons.is_empty() || csg_b.polygons.is_empty() {
+        // Fall back to a simple merge for operands we can't combine
+        let merged = || {
             let mut merged = mesh_a.clone();
             merged.merge(mesh_b);
-            return Ok(merged);
+            merged
+        };
+        if !self.can_run_csg_operation(&polygons_a, &polygons_b) {
+            return Ok(merged());
         }
 
-        if !Self::can_run_csgrs_operation(&csg_a, &csg_b) {
-            let mut merged = mesh_a.clone();
-            merged.merge(mesh_b);
-            return Ok(merged);
+        match self.boolean(BooleanOp::Union, mesh_a, mesh_b, polygons_a, polygons_b) {
+            Ok(result) => Ok(result),
+            Err(_e) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[ifc-lite] CSG union skipped: {}", _e);
+                Ok(merged())
+            }
         }
-
-        // Perform CSG union
-        let result_csg = csg_a.union(&csg_b);
-
-        // Convert back to our Mesh format
-        Self::csgrs_to_mesh(&result_csg)
     }
 
-    /// Intersect two meshes using csgrs CSG boolean operations
+    /// Intersect two meshes using BSP boolean operations
     ///
     /// Returns the intersection of two meshes (the volume where both overlap).
     pub fn intersection_mesh(&self, mesh_a: &Mesh, mesh_b: &Mesh) -> Result<Mesh> {
-        use csgrs::traits::CSG;
-
         // Fast paths: intersection with empty mesh is empty
         if mesh_a.is_empty() || mesh_b.is_empty() {
             return Ok(Mesh::new());
         }
 
-        // Convert meshes to csgrs format
-        let csg_a = Self::mesh_to_csgrs(mesh_a)?;
-        let csg_b = Self::mesh_to_csgrs(mesh_b)?;
+        let polygons_a = Self::mesh_to_polygons(mesh_a);
+        let polygons_b = Self::mesh_to_polygons(mesh_b);
 
-        // Validate CSG meshes - return empty if invalid
-        if csg_a.polygons.is_empty() || csg_b.polygons.is_empty() {
+        if !self.can_run_csg_operation(&polygons_a, &polygons_b) {
             return Ok(Mesh::new());
         }
 
-        if !Self::can_run_csgrs_operation(&csg_a, &csg_b) {
-            return Ok(Mesh::new());
+        match self.boolean(
+            BooleanOp::Intersection,
+            mesh_a,
+            mesh_b,
+            polygons_a,
+            polygons_b,
+        ) {
+            Ok(result) => Ok(result),
+            Err(_e) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[ifc-lite] CSG intersection skipped: {}", _e);
+                Ok(Mesh::new())
+            }
         }
-
-        // Perform CSG intersection
-        let result_csg = csg_a.intersection(&csg_b);
-
-        // Convert back to our Mesh format
-        Self::csgrs_to_mesh(&result_csg)
     }
 
     /// Union multiple meshes together
@@ -1494,27 +1469,46 @@ mod tests {
     }
 
     #[test]
-    fn test_csgrs_operation_guard_allows_simple_boxes() {
+    fn test_csg_operation_guard_allows_simple_boxes() {
         let box_a = aabb_to_mesh(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
         let box_b = aabb_to_mesh(Point3::new(0.25, 0.25, 0.25), Point3::new(0.75, 0.75, 0.75));
 
-        let csg_a = ClippingProcessor::mesh_to_csgrs(&box_a).unwrap();
-        let csg_b = ClippingProcessor::mesh_to_csgrs(&box_b).unwrap();
+        let csg_a = ClippingProcessor::mesh_to_polygons(&box_a);
+        let csg_b = ClippingProcessor::mesh_to_polygons(&box_b);
 
-        assert!(ClippingProcessor::can_run_csgrs_operation(&csg_a, &csg_b));
+        assert!(ClippingProcessor::new().can_run_csg_operation(&csg_a, &csg_b));
     }
 
     #[test]
-    fn test_csgrs_operation_guard_rejects_complex_operands() {
+    fn test_csg_operation_guard_rejects_complex_operands() {
         let box_mesh = aabb_to_mesh(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
         let mut complex_mesh = Mesh::new();
         complex_mesh.merge(&box_mesh);
         complex_mesh.merge(&box_mesh);
         complex_mesh.merge(&box_mesh);
 
-        let csg_a = ClippingProcessor::mesh_to_csgrs(&complex_mesh).unwrap();
-        let csg_b = ClippingProcessor::mesh_to_csgrs(&box_mesh).unwrap();
+        let csg_a = ClippingProcessor::mesh_to_polygons(&complex_mesh);
+        let csg_b = ClippingProcessor::mesh_to_polygons(&box_mesh);
+
+        assert!(!ClippingProcessor::new().can_run_csg_operation(&csg_a, &csg_b));
+    }
 
-        assert!(!ClippingProcessor::can_run_csgrs_operation(&csg_a, &csg_b));
+    #[test]
+    fn test_backends_subtract_flush_opening() {
+        // Opening flush with both wall faces: every cut face is coplanar
+        let wall = aabb_to_mesh(Point3::new(0.0, 0.0, 0.0), Point3::new(4.0, 0.2, 3.0));
+        let opening = aabb_to_mesh(Point3::new(1.0, 0.0, 0.5), Point3::new(2.0, 0.2, 2.5));
+
+        for backend in [CsgBackend::Auto, CsgBackend::Exact] {
+            let clipper = ClippingProcessor::new().with_backend(backend);
+            let result = clipper.subtract_mesh(&wall, &opening).unwrap();
+            let volume = result.signed_volume();
+            assert!(
+                (volume - 2.0).abs() < 1e-4,
+                "{:?}: volume {}",
+                backend,
+                volume
+            );
+        }
     }
 }
@@ -0,0 +1,458 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Plane-based exact booleans ([`CsgBackend::Exact`](crate::csg::CsgBackend))
+//!
+//! Input vertices are snapped to an integer grid once. From then on a polygon
+//! is a supporting plane plus bounding edge planes with integer coefficients,
+//! and every vertex is the implicit intersection of three of those planes.
+//! Splitting only reuses existing planes, so coefficients never grow and
+//! "which side of this plane is that vertex" is the sign of a determinant that
+//! can be evaluated exactly. Rounding happens once, when vertices are emitted,
+//! so the slivers and cracks that floating-point BSP splits accumulate on
+//! nearly coplanar opening faces cannot occur.
+
+use crate::bsp::{self, BspPolygon, Side};
+use crate::csg::{BooleanOp, CsgLimits};
+use crate::error::Result;
+use crate::mesh::Mesh;
+use nalgebra::{Point3, Vector3};
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+/// Largest snapped coordinate magnitude, chosen so plane coefficients and
+/// normal dot products fit in `i128`.
+const GRID_EXTENT: f64 = (1u64 << 26) as f64;
+/// Finest snapping resolution in model units.
+const MIN_RESOLUTION: f64 = 1e-6;
+/// Relative error bound of the floating-point determinant filter.
+const FILTER_EPSILON: f64 = 1e-12;
+/// Distance below which output vertices with matching normals are merged.
+const WELD_TOLERANCE: f32 = 1e-5;
+
+/// Plane `a·x + b·y + c·z + d = 0` with integer coefficients; front is positive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExactPlane {
+    pub a: i128,
+    pub b: i128,
+    pub c: i128,
+    pub d: i128,
+}
+
+impl ExactPlane {
+    /// Plane through three grid points, front side where `(q-p)×(r-p)` points.
+    fn through(p: [i64; 3], q: [i64; 3], r: [i64; 3]) -> Option<Self> {
+        let u = [q[0] - p[0], q[1] - p[1], q[2] - p[2]].map(i128::from);
+        let v = [r[0] - p[0], r[1] - p[1], r[2] - p[2]].map(i128::from);
+        let n = [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ];
+        if n == [0, 0, 0] {
+            return None;
+        }
+        let p = p.map(i128::from);
+        Some(Self {
+            a: n[0],
+            b: n[1],
+            c: n[2],
+            d: -(n[0] * p[0] + n[1] * p[1] + n[2] * p[2]),
+        })
+    }
+
+    fn eval(&self, p: [i64; 3]) -> i128 {
+        let p = p.map(i128::from);
+        self.a * p[0] + self.b * p[1] + self.c * p[2] + self.d
+    }
+
+    fn negated(self) -> Self {
+        Self {
+            a: -self.a,
+            b: -self.b,
+            c: -self.c,
+            d: -self.d,
+        }
+    }
+
+    fn normal_dot(&self, other: &Self) -> i128 {
+        self.a * other.a + self.b * other.b + self.c * other.c
+    }
+
+    fn row(&self) -> [i128; 4] {
+        [self.a, self.b, self.c, self.d]
+    }
+}
+
+fn det3_f64(m: &[[f64; 3]; 3]) -> (f64, f64) {
+    let terms = [
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]),
+        m[0][1] * (m[1][2] * m[2][0] - m[1][0] * m[2][2]),
+        m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]),
+    ];
+    let a = m.map(|r| r.map(f64::abs));
+    let permanent = a[0][0] * (a[1][1] * a[2][2] + a[1][2] * a[2][1])
+        + a[0][1] * (a[1][2] * a[2][0] + a[1][0] * a[2][2])
+        + a[0][2] * (a[1][0] * a[2][1] + a[1][1] * a[2][0]);
+    (terms.iter().sum(), permanent)
+}
+
+fn det3_big(m: &[[BigInt; 3]; 3]) -> BigInt {
+    &m[0][0] * (&m[1][1] * &m[2][2] - &m[1][2] * &m[2][1])
+        + &m[0][1] * (&m[1][2] * &m[2][0] - &m[1][0] * &m[2][2])
+        + &m[0][2] * (&m[1][0] * &m[2][1] - &m[1][1] * &m[2][0])
+}
+
+/// Rows of `m` except `skip`, first three columns.
+fn minor<T: Clone>(m: &[[T; 4]; 4], skip: usize) -> [[T; 3]; 3] {
+    let mut rows = (0..4).filter(|&r| r != skip).map(|r| {
+        let row = &m[r];
+        [row[0].clone(), row[1].clone(), row[2].clone()]
+    });
+    [
+        rows.next().unwrap(),
+        rows.next().unwrap(),
+        rows.next().unwrap(),
+    ]
+}
+
+fn sign_of_big(v: &BigInt) -> i32 {
+    if v.is_zero() {
+        0
+    } else if v.is_positive() {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Exact sign of the determinant of the three plane normals.
+fn det3_sign(planes: [&ExactPlane; 3]) -> i32 {
+    let m = planes.map(|p| [p.a as f64, p.b as f64, p.c as f64]);
+    let (det, permanent) = det3_f64(&m);
+    if det.abs() > permanent * FILTER_EPSILON {
+        return det.signum() as i32;
+    }
+    let m = planes.map(|p| [p.a, p.b, p.c].map(BigInt::from));
+    sign_of_big(&det3_big(&m))
+}
+
+/// Exact sign of the 4×4 determinant of the planes' coefficient rows.
+fn det4_sign(planes: [&ExactPlane; 4]) -> i32 {
+    let m = planes.map(|p| p.row().map(|v| v as f64));
+    let mut det = 0.0;
+    let mut permanent = 0.0;
+    for (row, plane) in m.iter().enumerate() {
+        let (minor_det, minor_permanent) = det3_f64(&minor(&m, row));
+        let cofactor = if row % 2 == 0 { -minor_det } else { minor_det };
+        det += plane[3] * cofactor;
+        permanent += plane[3].abs() * minor_permanent;
+    }
+    if det.abs() > permanent * FILTER_EPSILON {
+        return det.signum() as i32;
+    }
+
+    let m = planes.map(|p| p.row().map(BigInt::from));
+    let mut det = BigInt::zero();
+    for (row, plane) in m.iter().enumerate() {
+        let minor_det = det3_big(&minor(&m, row));
+        if row % 2 == 0 {
+            det -= &plane[3] * minor_det;
+        } else {
+            det += &plane[3] * minor_det;
+        }
+    }
+    sign_of_big(&det)
+}
+
+/// Side of `plane` the intersection point of `a`, `b` and `c` lies on.
+///
+/// `plane(v) = det4(a, b, c, plane) / det3(a, b, c)` for the vertex `v`.
+fn vertex_side(a: &ExactPlane, b: &ExactPlane, c: &ExactPlane, plane: &ExactPlane) -> i32 {
+    det4_sign([a, b, c, plane]) * det3_sign([a, b, c])
+}
+
+/// Intersection point of three planes in grid coordinates (Cramer's rule).
+fn intersect(a: &ExactPlane, b: &ExactPlane, c: &ExactPlane) -> Option<[f64; 3]> {
+    let rows = [a, b, c].map(|p| p.row().map(BigInt::from));
+    let normals = || rows.clone().map(|[a, b, c, _]| [a, b, c]);
+    let denominator = det3_big(&normals());
+    if denominator.is_zero() {
+        return None;
+    }
+    let denominator = denominator.to_f64()?;
+    let coordinate = |i: usize| {
+        let mut m = normals();
+        for (row, source) in m.iter_mut().zip(&rows) {
+            row[i] = -&source[3];
+        }
+        det3_big(&m).to_f64().unwrap_or(f64::NAN) / denominator
+    };
+    Some([coordinate(0), coordinate(1), coordinate(2)])
+}
+
+/// Convex polygon: vertex `i` is `support ∩ bounds[i-1] ∩ bounds[i]`.
+///
+/// Bounding planes face outwards, so the interior is on their back side.
+#[derive(Debug, Clone)]
+pub struct ExactPolygon {
+    support: ExactPlane,
+    bounds: Vec<ExactPlane>,
+}
+
+impl ExactPolygon {
+    /// Triangle through three grid points, or `None` if degenerate.
+    fn triangle(points: [[i64; 3]; 3]) -> Option<Self> {
+        let support = ExactPlane::through(points[0], points[1], points[2])?;
+        // Edge planes contain the edge and the axis the support plane is least
+        // parallel to, which keeps their coefficients small
+        let axis = [support.a, support.b, support.c]
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, n)| n.unsigned_abs())
+            .map(|(i, _)| i)?;
+        let mut bounds = Vec::with_capacity(3);
+        for i in 0..3 {
+            let (p, q, r) = (points[i], points[(i + 1) % 3], points[(i + 2) % 3]);
+            let mut offset = p;
+            offset[axis] += 1;
+            let edge = ExactPlane::through(p, q, offset)?;
+            bounds.push(if edge.eval(r) > 0 {
+                edge.negated()
+            } else {
+                edge
+            });
+        }
+        Some(Self { support, bounds })
+    }
+
+    fn vertex_sides(&self, plane: &ExactPlane) -> Vec<i32> {
+        let n = self.bounds.len();
+        (0..n)
+            .map(|i| {
+                let prev = &self.bounds[(i + n - 1) % n];
+                vertex_side(&self.support, prev, &self.bounds[i], plane)
+            })
+            .collect()
+    }
+
+    fn vertices(&self) -> Vec<[f64; 3]> {
+        let n = self.bounds.len();
+        (0..n)
+            .filter_map(|i| {
+                intersect(
+                    &self.support,
+                    &self.bounds[(i + n - 1) % n],
+                    &self.bounds[i],
+                )
+            })
+            .collect()
+    }
+}
+
+impl BspPolygon for ExactPolygon {
+    type Plane = ExactPlane;
+
+    fn plane(&self) -> ExactPlane {
+        self.support
+    }
+
+    fn split(self, plane: &ExactPlane) -> Side<Self> {
+        let sides = self.vertex_sides(plane);
+        let any_front = sides.iter().any(|&s| s > 0);
+        let any_back = sides.iter().any(|&s| s < 0);
+        match (any_front, any_back) {
+            (false, false) if self.support.normal_dot(plane) > 0 => {
+                return Side::CoplanarFront(self)
+            }
+            (false, false) => return Side::CoplanarBack(self),
+            (true, false) => return Side::Front(self),
+            (false, true) => return Side::Back(self),
+            (true, true) => {}
+        }
+
+        // Walk the boundary; each emitted plane is the edge leaving a vertex of
+        // the front or back part, with the split plane closing each part
+        let n = sides.len();
+        let outward_front = plane.negated();
+        let mut front = Vec::with_capacity(n + 1);
+        let mut back = Vec::with_capacity(n + 1);
+        for i in 0..n {
+            let (si, sj) = (sides[i], sides[(i + 1) % n]);
+            let edge = self.bounds[i];
+            if si >= 0 {
+                front.push(if si == 0 && sj < 0 {
+                    outward_front
+                } else {
+                    edge
+                });
+            }
+            if si <= 0 {
+                back.push(if si == 0 && sj > 0 { *plane } else { edge });
+            }
+            if si > 0 && sj < 0 {
+                front.push(outward_front);
+                back.push(edge);
+            } else if si < 0 && sj > 0 {
+                back.push(*plane);
+                front.push(edge);
+            }
+        }
+        let part = |bounds: Vec<ExactPlane>| {
+            (bounds.len() >= 3).then_some(Self {
+                support: self.support,
+                bounds,
+            })
+        };
+        Side::Spanning(part(front), part(back))
+    }
+
+    fn flip(&mut self) {
+        self.support = self.support.negated();
+        self.bounds.reverse();
+    }
+
+    fn flip_plane(plane: &mut ExactPlane) {
+        *plane = plane.negated();
+    }
+}
+
+/// Snapping grid shared by both operands of one operation.
+struct Grid {
+    origin: Point3<f64>,
+    scale: f64,
+}
+
+impl Grid {
+    fn new(a: &Mesh, b: &Mesh) -> Self {
+        let (min_a, max_a) = a.bounds();
+        let (min_b, max_b) = b.bounds();
+        let min = min_a.coords.inf(&min_b.coords).map(f64::from);
+        let max = max_a.coords.sup(&max_b.coords).map(f64::from);
+        let half_extent = ((max - min) / 2.0).amax().max(f64::EPSILON);
+        Self {
+            origin: Point3::from((min + max) / 2.0),
+            scale: (GRID_EXTENT / half_extent).min(1.0 / MIN_RESOLUTION),
+        }
+    }
+
+    fn snap(&self, mesh: &Mesh, index: usize) -> Option<[i64; 3]> {
+        let p = mesh.positions.get(index * 3..index * 3 + 3)?;
+        let mut out = [0i64; 3];
+        for (axis, value) in p.iter().enumerate() {
+            let v = ((*value as f64 - self.origin[axis]) * self.scale).round();
+            if !v.is_finite() {
+                return None;
+            }
+            out[axis] = v as i64;
+        }
+        Some(out)
+    }
+
+    fn polygons(&self, mesh: &Mesh) -> Vec<ExactPolygon> {
+        mesh.indices
+            .chunks_exact(3)
+            .filter_map(|tri| {
+                let p0 = self.snap(mesh, tri[0] as usize)?;
+                let p1 = self.snap(mesh, tri[1] as usize)?;
+                let p2 = self.snap(mesh, tri[2] as usize)?;
+                ExactPolygon::triangle([p0, p1, p2])
+            })
+            .collect()
+    }
+
+    fn to_mesh(&self, polygons: &[ExactPolygon]) -> Mesh {
+        let mut mesh = Mesh::new();
+        for polygon in polygons {
+            let s = &polygon.support;
+            let Some(normal) = Vector3::new(s.a as f64, s.b as f64, s.c as f64).try_normalize(0.0)
+            else {
+                continue;
+            };
+            let vertices = polygon.vertices();
+            if vertices.len() < 3 {
+                continue;
+            }
+            let base = mesh.vertex_count() as u32;
+            for v in &vertices {
+                let world = self.origin + Vector3::from(*v) / self.scale;
+                mesh.add_vertex(world, normal);
+            }
+            // Split parts of convex polygons stay convex
+            for i in 1..vertices.len() as u32 - 1 {
+                mesh.add_triangle(base, base + i, base + i + 1);
+            }
+        }
+        mesh.weld(WELD_TOLERANCE);
+        mesh
+    }
+}
+
+/// Run a boolean operation with exact plane-based polygons.
+pub(crate) fn boolean(op: BooleanOp, a: &Mesh, b: &Mesh, limits: &CsgLimits) -> Result<Mesh> {
+    let grid = Grid::new(a, b);
+    let polygons_a = grid.polygons(a);
+    let polygons_b = grid.polygons(b);
+    let result = match op {
+        BooleanOp::Difference => bsp::difference(polygons_a, polygons_b, limits)?,
+        BooleanOp::Union => bsp::union(polygons_a, polygons_b, limits)?,
+        BooleanOp::Intersection => bsp::intersection(polygons_a, polygons_b, limits)?,
+    };
+    Ok(grid.to_mesh(&result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plane(a: i128, b: i128, c: i128, d: i128) -> ExactPlane {
+        ExactPlane { a, b, c, d }
+    }
+
+    #[test]
+    fn test_vertex_side_is_exact() {
+        // Vertex (1, 2, 3) from three skewed planes
+        let a = plane(1, 1, 0, -3);
+        let b = plane(0, 1, 1, -5);
+        let c = plane(1, 0, 1, -4);
+        let above = plane(0, 0, 1, -2); // z = 2, vertex in front
+        let on = plane(3, -1, 2, -7); // passes exactly through the vertex
+        assert_eq!(vertex_side(&a, &b, &c, &above), 1);
+        assert_eq!(vertex_side(&a, &b, &c, &above.negated()), -1);
+        assert_eq!(vertex_side(&a, &b, &c, &on), 0);
+        assert_eq!(vertex_side(&b, &a, &c, &above), 1);
+
+        // Coefficients large enough that the float filter must defer
+        let big = 1i128 << 80;
+        let skewed = plane(big + 1, big, 0, -(big + 1) - 2 * big);
+        assert_eq!(vertex_side(&a, &b, &c, &skewed), 0);
+
+        let p = intersect(&a, &b, &c).unwrap();
+        assert_eq!(p, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_split_triangle_parts() {
+        let tri = ExactPolygon::triangle([[0, 0, 0], [4, 0, 0], [0, 4, 0]]).unwrap();
+        match tri.split(&plane(1, 0, 0, -1)) {
+            Side::Spanning(Some(front), Some(back)) => {
+                let mut f = front.vertices();
+                let mut b = back.vertices();
+                f.sort_by(|x, y| x.partial_cmp(y).unwrap());
+                b.sort_by(|x, y| x.partial_cmp(y).unwrap());
+                assert_eq!(f, [[1.0, 0.0, 0.0], [1.0, 3.0, 0.0], [4.0, 0.0, 0.0]]);
+                assert_eq!(
+                    b,
+                    [
+                        [0.0, 0.0, 0.0],
+                        [0.0, 4.0, 0.0],
+                        [1.0, 0.0, 0.0],
+                        [1.0, 3.0, 0.0]
+                    ]
+                );
+            }
+            _ => panic!("expected a spanning split"),
+        }
+    }
+}
@@ -0,0 +1,611 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Binary glTF (GLB) export
+//!
+//! Every element becomes one node named after its express ID (also stored in
+//! `extras.expressId`) holding one mesh with a primitive per sub-mesh. Element
+//! nodes hang off a root node that rotates IFC Z-up into glTF Y-up, so vertex
+//! data is written exactly as the router produced it.
+//!
+//! With [`GltfOptions::quantize`], positions are stored as normalized `i16`
+//! relative to each element's bounds (the node's translation/scale undoes it)
+//! and normals as normalized `i8`, per `KHR_mesh_quantization`.
+
+use crate::materials::{MaterialId, MaterialPalette};
+use crate::mesh::{Mesh, SubMeshCollection};
+use rustc_hash::FxHashMap;
+use std::f32::consts::FRAC_1_SQRT_2;
+use std::fmt::Write;
+
+const BYTE: u32 = 5120;
+const SHORT: u32 = 5122;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Quaternion taking IFC Z-up to glTF Y-up (-90° about X)
+const Z_UP_TO_Y_UP: [f32; 4] = [-FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2];
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+/// GLB export settings
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GltfOptions {
+    /// Store positions and normals quantized (`KHR_mesh_quantization`)
+    pub quantize: bool,
+}
+
+impl GltfOptions {
+    /// Default options (float attributes)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable `KHR_mesh_quantization`
+    pub fn with_quantization(mut self, quantize: bool) -> Self {
+        self.quantize = quantize;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Positions,
+    Normals,
+    Indices,
+}
+
+struct Accessor {
+    view: View,
+    byte_offset: usize,
+    component_type: u32,
+    normalized: bool,
+    count: usize,
+    /// VEC3 bounds; `None` for the SCALAR index accessors
+    bounds: Option<([f32; 3], [f32; 3])>,
+}
+
+struct Primitive {
+    position: usize,
+    normal: Option<usize>,
+    indices: usize,
+    material: Option<usize>,
+}
+
+struct Node {
+    express_id: u32,
+    primitives: Vec<Primitive>,
+    /// Translation and uniform scale undoing position quantization
+    dequantize: Option<([f32; 3], f32)>,
+}
+
+struct GltfMaterial {
+    name: Option<String>,
+    color: [f32; 4],
+}
+
+/// Incremental GLB builder
+pub struct GlbWriter<'a> {
+    options: GltfOptions,
+    palette: Option<&'a MaterialPalette>,
+    positions: Vec<u8>,
+    normals: Vec<u8>,
+    indices: Vec<u8>,
+    accessors: Vec<Accessor>,
+    nodes: Vec<Node>,
+    materials: Vec<GltfMaterial>,
+    palette_materials: FxHashMap<MaterialId, usize>,
+    colour_materials: FxHashMap<[u32; 4], usize>,
+}
+
+impl<'a> GlbWriter<'a> {
+    /// Create an empty writer
+    pub fn new(options: GltfOptions) -> Self {
+        Self {
+            options,
+            palette: None,
+            positions: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+            accessors: Vec::new(),
+            nodes: Vec::new(),
+            materials: Vec::new(),
+            palette_materials: FxHashMap::default(),
+            colour_materials: FxHashMap::default(),
+        }
+    }
+
+    /// Resolve sub-mesh material IDs against this palette
+    pub fn with_palette(mut self, palette: &'a MaterialPalette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    /// Add an element mesh with an optional RGBA colour
+    pub fn add_mesh(&mut self, express_id: u32, mesh: &Mesh, color: Option<[f32; 4]>) {
+        let material = color.map(|color| self.colour_material(color));
+        self.add_node(express_id, &[(mesh, material)]);
+    }
+
+    /// Add an element's sub-meshes, one primitive each
+    pub fn add_submeshes(&mut self, express_id: u32, sub_meshes: &SubMeshCollection) {
+        let materials: Vec<Option<usize>> = sub_meshes
+            .iter()
+            .map(|sub| sub.material.and_then(|id| self.palette_material(id)))
+            .collect();
+        let parts: Vec<(&Mesh, Option<usize>)> = sub_meshes
+            .iter()
+            .map(|sub| &sub.mesh)
+            .zip(materials)
+            .collect();
+        self.add_node(express_id, &parts);
+    }
+
+    /// Number of element nodes written so far
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Assemble the GLB file
+    pub fn finish(self) -> Vec<u8> {
+        let json = self.build_json();
+        let mut bin = self.positions;
+        bin.extend_from_slice(&self.normals);
+        bin.extend_from_slice(&self.indices);
+        pack_glb(json, bin)
+    }
+
+    fn palette_material(&mut self, id: MaterialId) -> Option<usize> {
+        if let Some(&index) = self.palette_materials.get(&id) {
+            return Some(index);
+        }
+        let material = self.palette?.get(id)?;
+        let index = self.materials.len();
+        self.materials.push(GltfMaterial {
+            name: material.name.clone(),
+            color: material.color,
+        });
+        self.palette_materials.insert(id, index);
+        Some(index)
+    }
+
+    fn colour_material(&mut self, color: [f32; 4]) -> usize {
+        let key = color.map(f32::to_bits);
+        if let Some(&index) = self.colour_materials.get(&key) {
+            return index;
+        }
+        let index = self.materials.len();
+        self.materials.push(GltfMaterial { name: None, color });
+        self.colour_materials.insert(key, index);
+        index
+    }
+
+    fn add_node(&mut self, express_id: u32, parts: &[(&Mesh, Option<usize>)]) {
+        let parts: Vec<_> = parts
+            .iter()
+            .filter(|(mesh, _)| !mesh.is_empty() && mesh.positions.len() % 3 == 0)
+            .collect();
+        if parts.is_empty() {
+            return;
+        }
+
+        let dequantize = self.options.quantize.then(|| {
+            let (min, max) = parts
+                .iter()
+                .flat_map(|(mesh, _)| mesh.positions.chunks_exact(3))
+                .fold(([f32::MAX; 3], [f32::MIN; 3]), |(mut min, mut max), p| {
+                    for axis in 0..3 {
+                        min[axis] = min[axis].min(p[axis]);
+                        max[axis] = max[axis].max(p[axis]);
+                    }
+                    (min, max)
+                });
+            let centre = [0, 1, 2].map(|axis| 0.5 * (min[axis] + max[axis]));
+            let half = (0..3)
+                .map(|axis| 0.5 * (max[axis] - min[axis]))
+                .fold(0.0, f32::max);
+            let scale = if half.is_finite() && half > 0.0 {
+                half
+            } else {
+                1.0
+            };
+            (centre, scale)
+        });
+
+        let primitives = parts
+            .iter()
+            .map(|(mesh, material)| self.write_primitive(mesh, *material, dequantize))
+            .collect();
+        self.nodes.push(Node {
+            express_id,
+            primitives,
+            dequantize,
+        });
+    }
+
+    fn write_primitive(
+        &mut self,
+        mesh: &Mesh,
+        material: Option<usize>,
+        dequantize: Option<([f32; 3], f32)>,
+    ) -> Primitive {
+        let count = mesh.positions.len() / 3;
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+
+        let byte_offset = self.positions.len();
+        for p in mesh.positions.chunks_exact(3) {
+            let value = match dequantize {
+                Some((centre, scale)) => {
+                    let q = [0, 1, 2].map(|axis| quantize_i16((p[axis] - centre[axis]) / scale));
+                    for v in q {
+                        self.positions.extend_from_slice(&v.to_le_bytes());
+                    }
+                    // Pad each element to a 4-byte stride
+                    self.positions.extend_from_slice(&[0, 0]);
+                    q.map(|v| v as f32 / i16::MAX as f32)
+                }
+                None => {
+                    let v = [p[0], p[1], p[2]].map(finite);
+                    for c in v {
+                        self.positions.extend_from_slice(&c.to_le_bytes());
+                    }
+                    v
+                }
+            };
+            for axis in 0..3 {
+                min[axis] = min[axis].min(value[axis]);
+                max[axis] = max[axis].max(value[axis]);
+            }
+        }
+        let position = self.push_accessor(Accessor {
+            view: View::Positions,
+            byte_offset,
+            component_type: if dequantize.is_some() { SHORT } else { FLOAT },
+            normalized: dequantize.is_some(),
+            count,
+            bounds: Some((min, max)),
+        });
+
+        // Meshes without per-vertex normals leave shading to the viewer
+        let normal = (mesh.normals.len() == mesh.positions.len()).then(|| {
+            let byte_offset = self.normals.len();
+            for n in mesh.normals.chunks_exact(3) {
+                if self.options.quantize {
+                    let q = [n[0], n[1], n[2]]
+                        .map(|c| (finite(c) * 127.0).round().clamp(-127.0, 127.0) as i8);
+                    self.normals.extend(q.map(|c| c as u8));
+                    self.normals.push(0);
+                } else {
+                    for c in n {
+                        self.normals.extend_from_slice(&finite(*c).to_le_bytes());
+                    }
+                }
+            }
+            self.push_accessor(Accessor {
+                view: View::Normals,
+                byte_offset,
+                component_type: if self.options.quantize { BYTE } else { FLOAT },
+                normalized: self.options.quantize,
+                count,
+                bounds: None,
+            })
+        });
+
+        let byte_offset = self.indices.len();
+        for index in &mesh.indices {
+            self.indices.extend_from_slice(&index.to_le_bytes());
+        }
+        let indices = self.push_accessor(Accessor {
+            view: View::Indices,
+            byte_offset,
+            component_type: UNSIGNED_INT,
+            normalized: false,
+            count: mesh.indices.len(),
+            bounds: None,
+        });
+
+        Primitive {
+            position,
+            normal,
+            indices,
+            material,
+        }
+    }
+
+    fn push_accessor(&mut self, accessor: Accessor) -> usize {
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn build_json(&self) -> String {
+        let quantized = self.options.quantize && !self.nodes.is_empty();
+        let mut json = String::from(r#"{"asset":{"version":"2.0","generator":"IFC-Lite"}"#);
+        if quantized {
+            json.push_str(r#","extensionsUsed":["KHR_mesh_quantization"]"#);
+            json.push_str(r#","extensionsRequired":["KHR_mesh_quantization"]"#);
+        }
+        json.push_str(r#","scene":0,"scenes":[{"nodes":[0]}]"#);
+
+        let [x, y, z, w] = Z_UP_TO_Y_UP;
+        let _ = write!(
+            json,
+            r#","nodes":[{{"name":"IfcModel","rotation":[{x},{y},{z},{w}]"#
+        );
+        if !self.nodes.is_empty() {
+            json.push_str(r#","children":["#);
+            push_list(&mut json, 1..=self.nodes.len(), |json, child| {
+                let _ = write!(json, "{child}");
+            });
+            json.push(']');
+        }
+        json.push('}');
+        for (mesh, node) in self.nodes.iter().enumerate() {
+            let id = node.express_id;
+            let _ = write!(
+                json,
+                r##",{{"name":"#{id}","mesh":{mesh},"extras":{{"expressId":{id}}}"##
+            );
+            if let Some(([tx, ty, tz], s)) = node.dequantize {
+                let _ = write!(
+                    json,
+                    r#","translation":[{tx},{ty},{tz}],"scale":[{s},{s},{s}]"#
+                );
+            }
+            json.push('}');
+        }
+        json.push(']');
+
+        // Views only exist for non-empty data; glTF rejects zero-length views
+        let views: Vec<(View, usize, usize)> = [
+            (
+                View::Positions,
+                self.positions.len(),
+                if quantized { 8 } else { 12 },
+            ),
+            (
+                View::Normals,
+                self.normals.len(),
+                if quantized { 4 } else { 12 },
+            ),
+            (View::Indices, self.indices.len(), 0),
+        ]
+        .into_iter()
+        .filter(|(_, len, _)| *len > 0)
+        .collect();
+        let view_index = |view: View| views.iter().position(|(v, ..)| *v == view).unwrap_or(0);
+
+        if !self.nodes.is_empty() {
+            json.push_str(r#","meshes":["#);
+            push_list(&mut json, &self.nodes, |json, node| {
+                json.push_str(r#"{"primitives":["#);
+                push_list(json, &node.primitives, |json, p| {
+                    let _ = write!(json, r#"{{"attributes":{{"POSITION":{}"#, p.position);
+                    if let Some(normal) = p.normal {
+                        let _ = write!(json, r#","NORMAL":{normal}"#);
+                    }
+                    let _ = write!(json, r#"}},"indices":{}"#, p.indices);
+                    if let Some(material) = p.material {
+                        let _ = write!(json, r#","material":{material}"#);
+                    }
+                    json.push('}');
+                });
+                json.push_str("]}");
+            });
+            json.push(']');
+
+            json.push_str(r#","accessors":["#);
+            push_list(&mut json, &self.accessors, |json, a| {
+                let kind = if a.view == View::Indices {
+                    "SCALAR"
+                } else {
+                    "VEC3"
+                };
+                let _ = write!(
+                    json,
+                    r#"{{"bufferView":{},"byteOffset":{},"componentType":{},"count":{},"type":"{kind}""#,
+                    view_index(a.view),
+                    a.byte_offset,
+                    a.component_type,
+                    a.count
+                );
+                if a.normalized {
+                    json.push_str(r#","normalized":true"#);
+                }
+                if let Some(([x0, y0, z0], [x1, y1, z1])) = a.bounds {
+                    let _ = write!(json, r#","min":[{x0},{y0},{z0}],"max":[{x1},{y1},{z1}]"#);
+                }
+                json.push('}');
+            });
+            json.push(']');
+
+            json.push_str(r#","bufferViews":["#);
+            let mut offset = 0;
+            push_list(&mut json, &views, |json, (view, len, stride)| {
+                let _ = write!(
+                    json,
+                    r#"{{"buffer":0,"byteOffset":{offset},"byteLength":{len}"#
+                );
+                if *view == View::Indices {
+                    let _ = write!(json, r#","target":{ELEMENT_ARRAY_BUFFER}}}"#);
+                } else {
+                    let _ = write!(json, r#","byteStride":{stride},"target":{ARRAY_BUFFER}}}"#);
+                }
+                offset += len;
+            });
+            let _ = write!(json, r#"],"buffers":[{{"byteLength":{offset}}}]"#);
+        }
+
+        if !self.materials.is_empty() {
+            json.push_str(r#","materials":["#);
+            push_list(&mut json, &self.materials, |json, material| {
+                json.push('{');
+                if let Some(name) = &material.name {
+                    json.push_str(r#""name":"#);
+                    push_json_string(json, name);
+                    json.push(',');
+                }
+                let [r, g, b, a] = material.color.map(finite);
+                let _ = write!(
+                    json,
+                    r#""pbrMetallicRoughness":{{"baseColorFactor":[{r},{g},{b},{a}],"metallicFactor":0,"roughnessFactor":1}}"#
+                );
+                if a < 1.0 {
+                    json.push_str(r#","alphaMode":"BLEND""#);
+                }
+                json.push('}');
+            });
+            json.push(']');
+        }
+
+        json.push('}');
+        json
+    }
+}
+
+/// Export elements' sub-meshes with their palette materials in one call
+pub fn export_glb<'m>(
+    elements: impl IntoIterator<Item = (u32, &'m SubMeshCollection)>,
+    palette: &MaterialPalette,
+    options: GltfOptions,
+) -> Vec<u8> {
+    let mut writer = GlbWriter::new(options).with_palette(palette);
+    for (express_id, sub_meshes) in elements {
+        writer.add_submeshes(express_id, sub_meshes);
+    }
+    writer.finish()
+}
+
+/// Write `items` comma-separated
+fn push_list<I: IntoIterator>(
+    json: &mut String,
+    items: I,
+    mut f: impl FnMut(&mut String, I::Item),
+) {
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        f(json, item);
+    }
+}
+
+fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// JSON has no NaN/Infinity
+#[inline]
+fn finite(value: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        0.0
+    }
+}
+
+#[inline]
+fn quantize_i16(value: f32) -> i16 {
+    (finite(value) * i16::MAX as f32)
+        .round()
+        .clamp(-(i16::MAX as f32), i16::MAX as f32) as i16
+}
+
+fn pack_glb(json: String, mut bin: Vec<u8>) -> Vec<u8> {
+    let mut json = json.into_bytes();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+    let bin_chunk = if bin.is_empty() { 0 } else { 8 + bin.len() };
+    let total = 12 + 8 + json.len() + bin_chunk;
+
+    let mut glb = Vec::with_capacity(total);
+    for word in [GLB_MAGIC, 2, total as u32, json.len() as u32, CHUNK_JSON] {
+        glb.extend_from_slice(&word.to_le_bytes());
+    }
+    glb.extend_from_slice(&json);
+    if !bin.is_empty() {
+        glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&CHUNK_BIN.to_le_bytes());
+        glb.extend_from_slice(&bin);
+    }
+    glb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Material;
+    use nalgebra::{Point3, Vector3};
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_glb_layout_and_quantization() {
+        let triangle = Mesh::from_triangle(
+            &Point3::new(10.0, 0.0, 0.0),
+            &Point3::new(14.0, 0.0, 0.0),
+            &Point3::new(10.0, 0.0, 3.0),
+            &Vector3::new(0.0, -1.0, 0.0),
+        );
+        let mut palette = MaterialPalette::new();
+        let glass = palette.insert(Material {
+            style_id: 3,
+            name: Some("Glass \"clear\"".to_string()),
+            color: [0.5, 0.5, 1.0, 0.4],
+        });
+        let mut window = SubMeshCollection::new();
+        window.add_with_material(20, triangle.clone(), Some(glass));
+        window.add_with_material(21, triangle.clone(), Some(glass));
+
+        let export = |options: GltfOptions| {
+            let mut writer = GlbWriter::new(options).with_palette(&palette);
+            writer.add_mesh(7, &triangle, Some([1.0, 0.0, 0.0, 1.0]));
+            writer.add_mesh(8, &Mesh::new(), None);
+            writer.add_submeshes(9, &window);
+            assert_eq!(writer.node_count(), 2);
+            writer.finish()
+        };
+
+        let glb = export(GltfOptions::new());
+        assert_eq!(read_u32(&glb, 0), GLB_MAGIC);
+        assert_eq!(read_u32(&glb, 8) as usize, glb.len());
+        let json_len = read_u32(&glb, 12) as usize;
+        let json = std::str::from_utf8(&glb[20..20 + json_len]).unwrap();
+        assert!(json.contains(r#""extras":{"expressId":7}"#));
+        assert!(json.contains(r#""name":"Glass \"clear\"""#));
+        assert_eq!(json.matches(r#""material":1"#).count(), 2);
+        assert!(!json.contains("KHR_mesh_quantization"));
+        // 3 triangles: positions + normals (36 bytes each) + indices (12 bytes)
+        assert_eq!(read_u32(&glb, 20 + json_len) as usize, 3 * (36 + 36 + 12));
+
+        let quantized = export(GltfOptions::new().with_quantization(true));
+        let json_len = read_u32(&quantized, 12) as usize;
+        let json = std::str::from_utf8(&quantized[20..20 + json_len]).unwrap();
+        assert!(json.contains(r#""extensionsRequired":["KHR_mesh_quantization"]"#));
+        assert!(json.contains(r#""translation":[12,0,1.5],"scale":[2,2,2]"#));
+        assert!(json.contains(r#""max":[1,0,"#));
+        assert_eq!(
+            read_u32(&quantized, 20 + json_len) as usize,
+            3 * (24 + 12 + 12)
+        );
+    }
+}
@@ -0,0 +1,18 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Mesh export formats
+//!
+//! Writers take router output ([`Mesh`](crate::Mesh) /
+//! [`SubMeshCollection`](crate::SubMeshCollection)) keyed by express ID and
+//! produce a self-contained file, so native and server consumers get an
+//! IFC → mesh file path without going through the JS exporters.
+
+pub mod gltf;
+pub mod obj;
+pub mod ply;
+
+pub use gltf::{export_glb, GlbWriter, GltfOptions};
+pub use obj::ObjWriter;
+pub use ply::PlyWriter;
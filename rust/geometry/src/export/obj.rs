@@ -0,0 +1,116 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Wavefront OBJ export
+//!
+//! Each element is written as a group (`g #<express id>`), so Blender and
+//! MeshLab can select or hide single elements. Coordinates stay in IFC Z-up;
+//! pick "Z up" in the importer.
+
+use crate::mesh::{Mesh, SubMeshCollection};
+use std::fmt::Write;
+
+/// Text OBJ builder
+#[derive(Debug, Default)]
+pub struct ObjWriter {
+    out: String,
+    vertex_count: usize,
+    group_count: usize,
+}
+
+impl ObjWriter {
+    /// Create an empty writer
+    pub fn new() -> Self {
+        Self {
+            out: String::from("# IFC-Lite OBJ export\n"),
+            ..Self::default()
+        }
+    }
+
+    /// Add an element mesh as its own group
+    pub fn add_mesh(&mut self, express_id: u32, mesh: &Mesh) {
+        self.add_group(express_id, &[mesh]);
+    }
+
+    /// Add all sub-meshes of an element as one group
+    pub fn add_submeshes(&mut self, express_id: u32, sub_meshes: &SubMeshCollection) {
+        let meshes: Vec<&Mesh> = sub_meshes.iter().map(|sub| &sub.mesh).collect();
+        self.add_group(express_id, &meshes);
+    }
+
+    /// Number of groups written so far
+    pub fn group_count(&self) -> usize {
+        self.group_count
+    }
+
+    /// The OBJ file contents
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn add_group(&mut self, express_id: u32, meshes: &[&Mesh]) {
+        if meshes.iter().all(|mesh| mesh.is_empty()) {
+            return;
+        }
+        let _ = writeln!(self.out, "g #{express_id}");
+        self.group_count += 1;
+
+        for mesh in meshes {
+            let has_normals = mesh.normals.len() == mesh.positions.len();
+            for p in mesh.positions.chunks_exact(3) {
+                let _ = writeln!(self.out, "v {} {} {}", p[0], p[1], p[2]);
+            }
+            if has_normals {
+                for n in mesh.normals.chunks_exact(3) {
+                    let _ = writeln!(self.out, "vn {} {} {}", n[0], n[1], n[2]);
+                }
+            }
+
+            // OBJ indices are 1-based and global across the file
+            let base = self.vertex_count + 1;
+            let vertex_total = mesh.positions.len() / 3;
+            for tri in mesh.indices.chunks_exact(3) {
+                if tri.iter().any(|&i| i as usize >= vertex_total) {
+                    continue;
+                }
+                let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| base + i as usize);
+                if has_normals {
+                    let _ = writeln!(self.out, "f {a}//{a} {b}//{b} {c}//{c}");
+                } else {
+                    let _ = writeln!(self.out, "f {a} {b} {c}");
+                }
+            }
+            self.vertex_count += vertex_total;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Point3, Vector3};
+
+    #[test]
+    fn test_obj_groups_and_global_indices() {
+        let triangle = Mesh::from_triangle(
+            &Point3::new(0.0, 0.0, 0.0),
+            &Point3::new(1.0, 0.0, 0.0),
+            &Point3::new(0.0, 0.5, 0.0),
+            &Vector3::new(0.0, 0.0, 1.0),
+        );
+        let mut bare = triangle.clone();
+        bare.normals.clear();
+
+        let mut writer = ObjWriter::new();
+        writer.add_mesh(7, &triangle);
+        writer.add_mesh(8, &Mesh::new());
+        writer.add_mesh(9, &bare);
+        assert_eq!(writer.group_count(), 2);
+
+        let obj = writer.finish();
+        assert!(obj.contains("g #7\nv 0 0 0\nv 1 0 0\nv 0 0.5 0\n"));
+        assert!(obj.contains("f 1//1 2//2 3//3\ng #9\n"));
+        assert!(obj.ends_with("f 4 5 6\n"));
+    }
+}
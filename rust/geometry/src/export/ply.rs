@@ -0,0 +1,132 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Binary PLY export
+//!
+//! Little-endian PLY with per-vertex position, normal and RGBA colour, and a
+//! per-face `express_id` property so a face picked in MeshLab leads back to
+//! its IFC element. Coordinates stay in IFC Z-up.
+
+use crate::mesh::Mesh;
+
+/// Colour of vertices added without one
+const DEFAULT_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+
+/// Binary PLY builder
+#[derive(Debug, Default)]
+pub struct PlyWriter {
+    vertices: Vec<u8>,
+    faces: Vec<u8>,
+    vertex_count: usize,
+    face_count: usize,
+}
+
+impl PlyWriter {
+    /// Create an empty writer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an element mesh with an optional RGBA colour
+    pub fn add_mesh(&mut self, express_id: u32, mesh: &Mesh, color: Option<[f32; 4]>) {
+        let vertex_total = mesh.positions.len() / 3;
+        let rgba = color
+            .unwrap_or(DEFAULT_COLOR)
+            .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+
+        for i in 0..vertex_total {
+            let normal = mesh.normals.get(i * 3..i * 3 + 3).unwrap_or(&[0.0; 3]);
+            for value in mesh.positions[i * 3..i * 3 + 3].iter().chain(normal) {
+                self.vertices.extend_from_slice(&value.to_le_bytes());
+            }
+            self.vertices.extend_from_slice(&rgba);
+        }
+
+        let base = self.vertex_count as u32;
+        for tri in mesh.indices.chunks_exact(3) {
+            if tri.iter().any(|&i| i as usize >= vertex_total) {
+                continue;
+            }
+            self.faces.push(3);
+            for index in tri {
+                self.faces.extend_from_slice(&(base + index).to_le_bytes());
+            }
+            self.faces.extend_from_slice(&express_id.to_le_bytes());
+            self.face_count += 1;
+        }
+        self.vertex_count += vertex_total;
+    }
+
+    /// Number of faces written so far
+    pub fn face_count(&self) -> usize {
+        self.face_count
+    }
+
+    /// Assemble the PLY file
+    pub fn finish(self) -> Vec<u8> {
+        let header = format!(
+            "ply\n\
+             format binary_little_endian 1.0\n\
+             comment IFC-Lite PLY export\n\
+             element vertex {}\n\
+             property float x\n\
+             property float y\n\
+             property float z\n\
+             property float nx\n\
+             property float ny\n\
+             property float nz\n\
+             property uchar red\n\
+             property uchar green\n\
+             property uchar blue\n\
+             property uchar alpha\n\
+             element face {}\n\
+             property list uchar uint vertex_indices\n\
+             property uint express_id\n\
+             end_header\n",
+            self.vertex_count, self.face_count
+        );
+        let mut ply = header.into_bytes();
+        ply.extend_from_slice(&self.vertices);
+        ply.extend_from_slice(&self.faces);
+        ply
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Point3, Vector3};
+
+    #[test]
+    fn test_ply_binary_layout() {
+        let mut triangle = Mesh::from_triangle(
+            &Point3::new(0.0, 0.0, 0.0),
+            &Point3::new(1.0, 0.0, 0.0),
+            &Point3::new(0.0, 1.0, 0.0),
+            &Vector3::new(0.0, 0.0, 1.0),
+        );
+        triangle.indices.extend_from_slice(&[0, 1, 5]);
+
+        let mut writer = PlyWriter::new();
+        writer.add_mesh(7, &triangle, None);
+        writer.add_mesh(42, &triangle, Some([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(writer.face_count(), 2);
+
+        let ply = writer.finish();
+        let header_end = ply.windows(11).position(|w| w == b"end_header\n").unwrap() + 11;
+        let header = std::str::from_utf8(&ply[..header_end]).unwrap();
+        assert!(header.contains("element vertex 6\n"));
+        assert!(header.contains("element face 2\n"));
+
+        // 6 vertices of 28 bytes, then 2 faces of 17 bytes
+        let body = &ply[header_end..];
+        assert_eq!(body.len(), 6 * 28 + 2 * 17);
+        let vertex = &body[3 * 28..4 * 28];
+        assert_eq!(&vertex[24..], &[255, 0, 0, 255]);
+        let face = &body[6 * 28 + 17..];
+        assert_eq!(face[0], 3);
+        assert_eq!(u32::from_le_bytes(face[1..5].try_into().unwrap()), 3);
+        assert_eq!(u32::from_le_bytes(face[13..17].try_into().unwrap()), 42);
+    }
+}
@@ -4,11 +4,16 @@
 
 //! Extrusion operations - converting 2D profiles to 3D meshes
 
+use crate::bool2d::{subtract_profiles, subtract_voids_2d};
 use crate::error::{Error, Result};
 use crate::mesh::Mesh;
 use crate::profile::{Profile2D, Profile2DWithVoids, Triangulation, VoidInfo};
 use nalgebra::{Matrix4, Point2, Point3, Vector3};
 
+/// Void depth boundaries closer than this fraction of the extrusion depth
+/// share one layer boundary in [`extrude_profile_layered`]
+const LAYER_TOLERANCE_FRACTION: f64 = 1e-6;
+
 /// Extrude a 2D profile along the Z axis
 #[inline]
 pub fn extrude_profile(
@@ -70,6 +75,116 @@ pub fn extrude_profile(
     Ok(mesh)
 }
 
+/// How extrusions thinner than [`ThinExtrusionConfig::min_depth`] are meshed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThinExtrusionPolicy {
+    /// Extrude at the minimum depth instead
+    Clamp,
+    /// Produce no geometry
+    Drop,
+    /// Emit the profile once, as a double-sided surface at mid-depth
+    Surface,
+}
+
+/// Minimum-thickness handling for near-zero extrusion depths (thin films,
+/// paint layers). Top and bottom caps closer than this z-fight in viewers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThinExtrusionConfig {
+    /// Depths below this are thin, in metres
+    pub min_depth: f64,
+    pub policy: ThinExtrusionPolicy,
+}
+
+impl Default for ThinExtrusionConfig {
+    fn default() -> Self {
+        Self {
+            min_depth: 0.0001,
+            policy: ThinExtrusionPolicy::Surface,
+        }
+    }
+}
+
+impl ThinExtrusionConfig {
+    /// Express the minimum depth in model units, given the model's length
+    /// unit scale (e.g., `0.001` for millimetres).
+    pub fn in_model_units(mut self, unit_scale: f64) -> Self {
+        if unit_scale > 0.0 && unit_scale.is_finite() {
+            self.min_depth /= unit_scale;
+        }
+        self
+    }
+
+    /// Whether `depth` falls below the minimum thickness
+    #[inline]
+    pub fn is_thin(&self, depth: f64) -> bool {
+        depth < self.min_depth
+    }
+}
+
+/// Extrude a 2D profile, applying `thin` when `depth` is below its minimum.
+///
+/// Zero depth is accepted here and handled by the policy; negative or
+/// non-finite depths are still rejected.
+pub fn extrude_profile_with_policy(
+    profile: &Profile2D,
+    depth: f64,
+    transform: Option<Matrix4<f64>>,
+    thin: &ThinExtrusionConfig,
+) -> Result<Mesh> {
+    if !depth.is_finite() || depth < 0.0 {
+        return Err(Error::InvalidExtrusion(format!(
+            "Depth must be positive, got {}",
+            depth
+        )));
+    }
+    if !thin.is_thin(depth) {
+        return extrude_profile(profile, depth, transform);
+    }
+
+    match thin.policy {
+        ThinExtrusionPolicy::Drop => Ok(Mesh::new()),
+        ThinExtrusionPolicy::Clamp => extrude_profile(profile, thin.min_depth, transform),
+        // Elongated profiles skip caps, so a surface would be empty
+        ThinExtrusionPolicy::Surface if profile_has_extreme_aspect_ratio(&profile.outer) => {
+            extrude_profile(profile, thin.min_depth, transform)
+        }
+        ThinExtrusionPolicy::Surface => {
+            let triangulation = profile.triangulate()?;
+            let mut mesh = Mesh::with_capacity(
+                triangulation.points.len() * 2,
+                triangulation.indices.len() * 2,
+            );
+            let z = depth * 0.5;
+            // Front face, then the same points with reversed winding as the back face
+            for (normal, flip) in [
+                (Vector3::new(0.0, 0.0, 1.0), false),
+                (Vector3::new(0.0, 0.0, -1.0), true),
+            ] {
+                let base = mesh.vertex_count() as u32;
+                for point in &triangulation.points {
+                    mesh.add_vertex(Point3::new(point.x, point.y, z), normal);
+                }
+                for tri in triangulation.indices.chunks_exact(3) {
+                    let (i0, i1, i2) = (
+                        base + tri[0] as u32,
+                        base + tri[1] as u32,
+                        base + tri[2] as u32,
+                    );
+                    if flip {
+                        mesh.add_triangle(i0, i2, i1);
+                    } else {
+                        mesh.add_triangle(i0, i1, i2);
+                    }
+                }
+            }
+            if let Some(mat) = transform {
+                apply_transform(&mut mesh, &mat);
+            }
+            Ok(mesh)
+        }
+    }
+}
+
 /// Check if a profile has an extreme aspect ratio (very elongated shape)
 /// Returns true if the profile's aspect ratio exceeds 100:1
 /// This catches profiles like railings that span building perimeters but have
@@ -188,6 +303,109 @@ pub fn extrude_profile_with_voids(
     Ok(mesh)
 }
 
+/// Extrude a profile with voids by stacking depth layers
+///
+/// The depth range is split at every partial void's start and end. Each layer
+/// is extruded from the profile minus the voids spanning it, so unlike
+/// [`extrude_profile_with_voids`] a partial void may cross the outer boundary
+/// (a window seen in the plan footprint of a wall). Caps between layers are
+/// only generated where material starts or stops, so the result has no
+/// internal faces.
+///
+/// # Arguments
+/// * `profile` - Base profile (outer boundary + existing holes)
+/// * `voids` - Void footprints with their depth ranges
+/// * `depth` - Total extrusion depth
+/// * `transform` - Optional transformation matrix
+pub fn extrude_profile_layered(
+    profile: &Profile2D,
+    voids: &[VoidInfo],
+    depth: f64,
+    transform: Option<Matrix4<f64>>,
+) -> Result<Mesh> {
+    if depth <= 0.0 {
+        return Err(Error::InvalidExtrusion(
+            "Depth must be positive".to_string(),
+        ));
+    }
+
+    let tolerance = depth * LAYER_TOLERANCE_FRACTION;
+    let mut cuts = vec![0.0, depth];
+    for void in voids.iter().filter(|v| !v.is_through) {
+        for z in [void.depth_start, void.depth_end] {
+            if z > tolerance && z < depth - tolerance {
+                cuts.push(z);
+            }
+        }
+    }
+    cuts.sort_by(f64::total_cmp);
+    cuts.dedup_by(|a, b| *a - *b <= tolerance);
+
+    let layers: Vec<Vec<Profile2D>> = cuts
+        .windows(2)
+        .map(|w| {
+            let active: Vec<Vec<Point2<f64>>> = voids
+                .iter()
+                .filter(|v| {
+                    v.is_through
+                        || (v.depth_start <= w[0] + tolerance && v.depth_end >= w[1] - tolerance)
+                })
+                .map(|v| v.contour.clone())
+                .collect();
+            subtract_voids_2d(profile, &active)
+        })
+        .collect::<Result<_>>()?;
+
+    let mut mesh = Mesh::new();
+    for (k, pieces) in layers.iter().enumerate() {
+        let (z0, z1) = (cuts[k], cuts[k + 1]);
+        let mut walls = Mesh::new();
+        for piece in pieces {
+            create_side_walls(&piece.outer, z1 - z0, &mut walls);
+            for hole in &piece.holes {
+                create_side_walls(hole, z1 - z0, &mut walls);
+            }
+        }
+        for z in walls.positions.iter_mut().skip(2).step_by(3) {
+            *z += z0 as f32;
+        }
+        mesh.merge(&walls);
+
+        // Material starting here faces down, material ending here faces up
+        let below: &[Profile2D] = if k == 0 { &[] } else { &layers[k - 1] };
+        add_layer_caps(&subtract_profiles(pieces, below), z0, false, &mut mesh)?;
+        let above: &[Profile2D] = layers.get(k + 1).map_or(&[], |l| l.as_slice());
+        add_layer_caps(&subtract_profiles(pieces, above), z1, true, &mut mesh)?;
+    }
+
+    if let Some(mat) = transform {
+        apply_transform(&mut mesh, &mat);
+    }
+
+    Ok(mesh)
+}
+
+/// Triangulate `pieces` as a horizontal cap at height `z`
+fn add_layer_caps(pieces: &[Profile2D], z: f64, facing_up: bool, mesh: &mut Mesh) -> Result<()> {
+    let normal = Vector3::new(0.0, 0.0, if facing_up { 1.0 } else { -1.0 });
+    for piece in pieces {
+        let triangulation = piece.triangulate()?;
+        let base_index = mesh.vertex_count() as u32;
+        for point in &triangulation.points {
+            mesh.add_vertex(Point3::new(point.x, point.y, z), normal);
+        }
+        for tri in triangulation.indices.chunks_exact(3) {
+            let [i0, i1, i2] = [0, 1, 2].map(|k| base_index + tri[k] as u32);
+            if facing_up {
+                mesh.add_triangle(i0, i1, i2);
+            } else {
+                mesh.add_triangle(i0, i2, i1);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Create geometry for a partial-depth void
 ///
 /// Generates:
@@ -578,6 +796,51 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_thin_extrusion_policies() {
+        let profile = create_rectangle(10.0, 5.0);
+        let thin = |policy| ThinExtrusionConfig {
+            min_depth: 0.01,
+            policy,
+        };
+
+        let surface =
+            extrude_profile_with_policy(&profile, 0.0, None, &thin(ThinExtrusionPolicy::Surface))
+                .unwrap();
+        assert_eq!(surface.triangle_count(), 4);
+        let (min, max) = surface.bounds();
+        assert_eq!(min.z, max.z);
+
+        let clamped =
+            extrude_profile_with_policy(&profile, 0.001, None, &thin(ThinExtrusionPolicy::Clamp))
+                .unwrap();
+        let (min, max) = clamped.bounds();
+        assert!((max.z - min.z - 0.01).abs() < 1e-6);
+
+        let dropped =
+            extrude_profile_with_policy(&profile, 0.001, None, &thin(ThinExtrusionPolicy::Drop))
+                .unwrap();
+        assert!(dropped.is_empty());
+
+        // Thick enough: unchanged
+        let normal =
+            extrude_profile_with_policy(&profile, 1.0, None, &thin(ThinExtrusionPolicy::Drop))
+                .unwrap();
+        assert_eq!(
+            normal.triangle_count(),
+            extrude_profile(&profile, 1.0, None)
+                .unwrap()
+                .triangle_count()
+        );
+        assert!(extrude_profile_with_policy(
+            &profile,
+            -1.0,
+            None,
+            &thin(ThinExtrusionPolicy::Clamp)
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_circular_profile_detection() {
         use crate::profile::create_circle;
@@ -609,4 +872,46 @@ mod tests {
 
         assert!(!is_approximately_circular_profile(&rect.outer, cx, cy));
     }
+
+    #[test]
+    fn test_layered_extrusion_window_through_wall() {
+        // Wall footprint in plan, window crossing its thickness from z=1 to z=2
+        let wall = create_rectangle(4.0, 0.2);
+        let window = VoidInfo {
+            contour: create_rectangle(1.0, 1.0).outer,
+            depth_start: 1.0,
+            depth_end: 2.0,
+            is_through: false,
+        };
+        let mesh = extrude_profile_layered(&wall, &[window], 3.0, None).unwrap();
+
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            Vector3::new(
+                mesh.positions[i] as f64,
+                mesh.positions[i + 1] as f64,
+                mesh.positions[i + 2] as f64,
+            )
+        };
+        let volume: f64 = mesh
+            .indices
+            .chunks_exact(3)
+            .map(|t| p(t[0]).dot(&p(t[1]).cross(&p(t[2]))) / 6.0)
+            .sum();
+        assert!((volume - (2.4 - 0.2)).abs() < 1e-5, "volume {volume}");
+
+        // Sill and head caps span the wall thickness under and over the window
+        let caps_at = |z: f32| {
+            mesh.indices
+                .chunks_exact(3)
+                .filter(|t| {
+                    t.iter()
+                        .all(|&i| (mesh.positions[i as usize * 3 + 2] - z).abs() < 1e-6)
+                })
+                .count()
+        };
+        assert!(caps_at(1.0) > 0);
+        assert!(caps_at(2.0) > 0);
+        assert!(mesh.validate().is_valid());
+    }
 }
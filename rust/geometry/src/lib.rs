@@ -24,6 +24,7 @@
 //! | `IfcExtrudedAreaSolid` | Full | Most common - extruded profiles |
 //! | `IfcFacetedBrep` | Full | Boundary representation meshes |
 //! | `IfcTriangulatedFaceSet` | Full | Pre-triangulated (IFC4) |
+//! | `IfcTriangulatedIrregularNetwork` | Full | Terrain TIN with void triangles (IFC4x3) |
 //! | `IfcBooleanClippingResult` | Full | CSG operations (difference, union, intersection) |
 //! | `IfcMappedItem` | Full | Instanced geometry |
 //! | `IfcSweptDiskSolid` | Full | Pipe/tube geometry |
@@ -61,55 +62,129 @@
 //! }
 //! ```
 //!
+//! Processors for further entity types, including proprietary ones, plug in
+//! with [`GeometryRouter::register`]; see [`ProcessorContext`].
+//!
 //! ## Performance
 //!
 //! - **Simple extrusions**: ~2000 entities/sec
 //! - **Complex Breps**: ~200 entities/sec
 //! - **Boolean operations**: ~20 entities/sec
 
+pub mod alignment;
 pub mod bool2d;
+pub mod bounds;
+pub mod bounds_extractor;
+pub mod bsp;
+pub mod bspline;
+pub mod bvh;
+pub mod cache_format;
 pub mod csg;
 pub mod error;
+pub mod exact;
+pub mod export;
 pub mod extrusion;
+pub mod lights;
+pub mod linear_placement;
+pub mod lines;
+pub mod lod;
+pub mod materials;
 pub mod mesh;
+pub mod meshopt;
+pub mod offset_curve;
+pub mod orientation;
 pub mod processors;
 pub mod profile;
 pub mod profile_extractor;
+pub mod profile_shapes;
 pub mod profiles;
+pub mod progressive;
+pub mod quantities;
+pub mod quantize;
 pub mod router;
+pub mod section;
+pub mod simplify;
+pub mod smoothing;
+pub mod spatial_grid;
+pub mod terrain;
+pub mod tessellation;
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod test_fixtures;
 pub mod transform;
 pub mod triangulation;
+pub mod uv;
+pub mod validation;
+pub mod visual_merge;
 pub mod void_analysis;
 pub mod void_index;
+pub mod voxel;
 
 // Re-export nalgebra types for convenience
 pub use nalgebra::{Point2, Point3, Vector2, Vector3};
 
 pub use bool2d::{
     compute_signed_area, ensure_ccw, ensure_cw, is_valid_contour, point_in_contour, subtract_2d,
-    subtract_multiple_2d, union_contours,
+    subtract_multiple_2d, subtract_profiles, subtract_voids_2d, union_contours,
+};
+pub use bounds::{Aabb, ModelBounds, Obb};
+pub use bounds_extractor::{extract_bounds, ElementBounds};
+pub use bspline::BSplineCurve;
+pub use bvh::{Bvh, Ray, RayHit};
+pub use cache_format::{
+    decode_meshes, decode_sub_meshes, encode_meshes, encode_sub_meshes, CACHE_FORMAT_VERSION,
 };
-pub use csg::{calculate_normals, ClippingProcessor, Plane, Triangle};
+pub use csg::{calculate_normals, ClippingProcessor, CsgBackend, CsgLimits, Plane, Triangle};
 pub use error::{Error, Result};
-pub use extrusion::{extrude_profile, extrude_profile_with_voids};
+pub use export::{export_glb, GlbWriter, GltfOptions, ObjWriter, PlyWriter};
+pub use extrusion::{
+    extrude_profile, extrude_profile_layered, extrude_profile_with_policy,
+    extrude_profile_with_voids, ThinExtrusionConfig, ThinExtrusionPolicy,
+};
+pub use lights::{Attenuation, Light, LightKind, Lights};
+pub use linear_placement::{
+    linear_placement_transform, point_at_distance, AlignmentCurve, DistanceExpression,
+};
+pub use lines::LineMesh;
+pub use lod::{generate_lods, ElementLods, LodLevels, LodOptions, LodSimplifier};
+pub use materials::{Material, MaterialId, MaterialPalette, StyleIndex};
 pub use mesh::{CoordinateShift, Mesh, SubMesh, SubMeshCollection};
+pub use meshopt::{CompressedMesh, CompressionOptions};
+pub use orientation::repair_orientation;
 pub use processors::{
     AdvancedBrepProcessor, BooleanClippingProcessor, ExtrudedAreaSolidProcessor,
-    FaceBasedSurfaceModelProcessor, FacetedBrepProcessor, MappedItemProcessor,
-    PolygonalFaceSetProcessor, RevolvedAreaSolidProcessor, SurfaceOfLinearExtrusionProcessor,
-    SweptDiskSolidProcessor, TriangulatedFaceSetProcessor,
+    ExtrudedAreaSolidTaperedProcessor, FaceBasedSurfaceModelProcessor, FacetedBrepProcessor,
+    MappedItemProcessor, PolygonalFaceSetProcessor, RevolvedAreaSolidProcessor,
+    RevolvedAreaSolidTaperedProcessor, SurfaceOfLinearExtrusionProcessor, SweptDiskSolidProcessor,
+    TriangulatedFaceSetProcessor, TriangulatedIrregularNetworkProcessor,
 };
 pub use profile::{Profile2D, Profile2DWithVoids, ProfileType, VoidInfo};
 pub use profile_extractor::{extract_profiles, ExtractedProfile};
 pub use profiles::ProfileProcessor;
-pub use router::{GeometryProcessor, GeometryRouter};
+pub use progressive::{
+    encode_chunks, ChunkInfo, ChunkManifest, ChunkOptions, ChunkOrder, ChunkedMeshes,
+};
+pub use quantities::{mass_properties, MassProperties, Quantities};
+pub use quantize::{PositionQuantization, QuantizedMesh};
+pub use router::{GeometryProcessor, GeometryRouter, MappedInstance, ProcessorContext};
+pub use section::{section_mesh, section_model, SectionCut, SectionOptions, SectionPlane};
+pub use simplify::{simplify, SimplifyOptions};
+pub use smoothing::{smooth_normals, NormalSmoothing};
+pub use spatial_grid::SpatialGrid;
+pub use terrain::{decimate_terrain, TerrainDecimation};
+pub use tessellation::TessellationConfig;
 pub use transform::{
     apply_rtc_offset, parse_axis2_placement_3d, parse_axis2_placement_3d_from_id,
     parse_cartesian_point, parse_cartesian_point_from_id, parse_direction, parse_direction_from_id,
 };
 pub use triangulation::triangulate_polygon;
+pub use uv::{UvMapping, UvOptions};
+pub use validation::{FilterMode, FilterPolicy, FilterReport, MeshReport};
+pub use visual_merge::{
+    merge_adjacent_walls, IdRange, MergeCandidate, MergedMesh, VisualMergeOptions,
+};
 pub use void_analysis::{
     classify_voids_batch, extract_coplanar_voids, extract_nonplanar_voids, VoidAnalyzer,
     VoidClassification,
 };
 pub use void_index::{propagate_voids_to_parts, VoidIndex, VoidStatistics};
+pub use voxel::{voxelize, voxelize_with_options, VoxelGrid, VoxelOptions};
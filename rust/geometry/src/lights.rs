@@ -0,0 +1,275 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Light sources
+//!
+//! IfcLightSource items carry a model's authored lighting, usually in a
+//! 'LightSource' representation of IfcLightFixture elements. [`Light`] holds
+//! one of them (colour, intensities and, depending on the kind, position,
+//! direction and cone) so viewers can reproduce the lighting instead of
+//! guessing. Goniometric sources, which need photometric data files, are
+//! not covered.
+
+use crate::transform::{parse_cartesian_point, parse_direction};
+use crate::Result;
+use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcType};
+use nalgebra::{Point3, Vector3};
+
+/// Distance falloff of positional and spot lights:
+/// `1 / (constant + distance * d + quadric * d²)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    pub constant: f64,
+    pub distance: f64,
+    pub quadric: f64,
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Self {
+            constant: 1.0,
+            distance: 0.0,
+            quadric: 0.0,
+        }
+    }
+}
+
+/// Kind of light and its geometry
+#[derive(Debug, Clone, PartialEq)]
+pub enum LightKind {
+    /// IfcLightSourceAmbient: uniform light without position
+    Ambient,
+    /// IfcLightSourceDirectional: parallel rays, e.g. sunlight
+    Directional { direction: Vector3<f64> },
+    /// IfcLightSourcePositional: point light
+    Positional {
+        position: Point3<f64>,
+        /// Distance beyond which the light has no effect
+        radius: f64,
+        attenuation: Attenuation,
+    },
+    /// IfcLightSourceSpot: point light limited to a cone
+    Spot {
+        position: Point3<f64>,
+        direction: Vector3<f64>,
+        radius: f64,
+        attenuation: Attenuation,
+        /// Falloff of intensity away from the cone axis
+        concentration_exponent: f64,
+        /// Half angle of the cone, in radians
+        spread_angle: f64,
+        /// Half angle of the full-intensity inner cone, in radians
+        beam_width_angle: f64,
+    },
+}
+
+/// One light source
+#[derive(Debug, Clone, PartialEq)]
+pub struct Light {
+    /// Express ID of the IfcLightSource item
+    pub express_id: u32,
+    /// Element whose representation contains the light, `None` for
+    /// light sources not used by any element
+    pub element_id: Option<u32>,
+    pub name: Option<String>,
+    /// Linear RGB colour, 0..1
+    pub color: [f32; 3],
+    /// Contribution to the ambient light, 0..1
+    pub ambient_intensity: f64,
+    /// Direct intensity, 0..1
+    pub intensity: f64,
+    pub kind: LightKind,
+}
+
+impl Light {
+    /// Parse an IfcLightSource subtype in its item coordinates (file units).
+    /// Returns `None` for other entities and goniometric sources.
+    pub fn from_entity(
+        entity: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Option<Self>> {
+        // IfcLightSource: Name, LightColour, AmbientIntensity, Intensity
+        let kind = match entity.ifc_type {
+            IfcType::IfcLightSourceAmbient => LightKind::Ambient,
+            IfcType::IfcLightSourceDirectional => LightKind::Directional {
+                direction: parse_orientation(entity, decoder, 4)?,
+            },
+            // IfcLightSourcePositional: Position, Radius, ConstantAttenuation,
+            // DistanceAttenuation, QuadricAttenuation
+            IfcType::IfcLightSourcePositional => LightKind::Positional {
+                position: parse_cartesian_point(entity, decoder, 4)?,
+                radius: entity.get_float(5).unwrap_or(0.0),
+                attenuation: parse_attenuation(entity),
+            },
+            // IfcLightSourceSpot adds: Orientation, ConcentrationExponent,
+            // SpreadAngle, BeamWidthAngle
+            IfcType::IfcLightSourceSpot => LightKind::Spot {
+                position: parse_cartesian_point(entity, decoder, 4)?,
+                direction: parse_orientation(entity, decoder, 9)?,
+                radius: entity.get_float(5).unwrap_or(0.0),
+                attenuation: parse_attenuation(entity),
+                concentration_exponent: entity.get_float(10).unwrap_or(0.0),
+                spread_angle: entity.get_float(11).unwrap_or(0.0),
+                beam_width_angle: entity.get_float(12).unwrap_or(0.0),
+            },
+            _ => return Ok(None),
+        };
+
+        // IfcColourRgb: Name, Red, Green, Blue
+        let color = match entity.get_ref(1) {
+            Some(colour_id) => {
+                let colour = decoder.decode_by_id(colour_id)?;
+                let channel = |i: usize| colour.get_float(i).unwrap_or(1.0) as f32;
+                [channel(1), channel(2), channel(3)]
+            }
+            None => [1.0; 3],
+        };
+
+        Ok(Some(Self {
+            express_id: entity.id,
+            element_id: None,
+            name: entity
+                .get(0)
+                .and_then(|attr| attr.as_string())
+                .map(str::to_string),
+            color,
+            ambient_intensity: entity.get_float(2).unwrap_or(0.0),
+            intensity: entity.get_float(3).unwrap_or(1.0),
+            kind,
+        }))
+    }
+
+    /// Position of positional and spot lights
+    pub fn position(&self) -> Option<Point3<f64>> {
+        match &self.kind {
+            LightKind::Positional { position, .. } | LightKind::Spot { position, .. } => {
+                Some(*position)
+            }
+            _ => None,
+        }
+    }
+
+    /// Unit direction of directional and spot lights
+    pub fn direction(&self) -> Option<Vector3<f64>> {
+        match &self.kind {
+            LightKind::Directional { direction } | LightKind::Spot { direction, .. } => {
+                Some(*direction)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Light sources of a model
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lights {
+    pub lights: Vec<Light>,
+}
+
+impl Lights {
+    /// Create an empty collection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of lights
+    pub fn len(&self) -> usize {
+        self.lights.len()
+    }
+
+    /// Whether the model has no lights
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    /// Iterate over the lights
+    pub fn iter(&self) -> std::slice::Iter<'_, Light> {
+        self.lights.iter()
+    }
+
+    /// Add a light
+    pub fn push(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+}
+
+/// Normalised IfcDirection at `index`; lights point down (-Z) when unset
+fn parse_orientation(
+    entity: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+    index: usize,
+) -> Result<Vector3<f64>> {
+    let Some(direction_id) = entity.get_ref(index) else {
+        return Ok(-Vector3::z());
+    };
+    let direction = parse_direction(&decoder.decode_by_id(direction_id)?)?;
+    Ok(direction.try_normalize(1e-12).unwrap_or(-Vector3::z()))
+}
+
+fn parse_attenuation(entity: &DecodedEntity) -> Attenuation {
+    let defaults = Attenuation::default();
+    Attenuation {
+        constant: entity.get_float(6).unwrap_or(defaults.constant),
+        distance: entity.get_float(7).unwrap_or(defaults.distance),
+        quadric: entity.get_float(8).unwrap_or(defaults.quadric),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_light_sources() {
+        let content = r#"
+#1=IFCCOLOURRGB($,1.,0.8,0.5);
+#2=IFCCARTESIANPOINT((1.,2.,3.));
+#3=IFCDIRECTION((0.,0.,-2.));
+#4=IFCLIGHTSOURCEAMBIENT('fill',#1,0.2,$);
+#5=IFCLIGHTSOURCEDIRECTIONAL('sun',$,$,0.9,#3);
+#6=IFCLIGHTSOURCEPOSITIONAL($,#1,$,0.5,#2,10.,1.,0.1,0.01);
+#7=IFCLIGHTSOURCESPOT('spot',#1,0.,1.,#2,5.,1.,0.,0.,#3,2.,0.6,0.3);
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let mut parse = |id| {
+            let entity = decoder.decode_by_id(id).unwrap();
+            Light::from_entity(&entity, &mut decoder).unwrap()
+        };
+
+        let ambient = parse(4).unwrap();
+        assert_eq!(ambient.kind, LightKind::Ambient);
+        assert_eq!(ambient.color, [1.0, 0.8, 0.5]);
+        assert_eq!((ambient.ambient_intensity, ambient.intensity), (0.2, 1.0));
+
+        let sun = parse(5).unwrap();
+        assert_eq!(sun.color, [1.0; 3]);
+        assert_eq!(sun.direction(), Some(-Vector3::z()));
+        assert_eq!(sun.position(), None);
+
+        let LightKind::Positional {
+            radius,
+            attenuation,
+            ..
+        } = parse(6).unwrap().kind
+        else {
+            panic!("expected a positional light");
+        };
+        assert_eq!(radius, 10.0);
+        assert_eq!(attenuation.quadric, 0.01);
+
+        let spot = parse(7).unwrap();
+        assert_eq!(spot.position(), Some(Point3::new(1.0, 2.0, 3.0)));
+        let LightKind::Spot {
+            spread_angle,
+            beam_width_angle,
+            ..
+        } = spot.kind
+        else {
+            panic!("expected a spot light");
+        };
+        assert_eq!((spread_angle, beam_width_angle), (0.6, 0.3));
+
+        assert!(parse(2).is_none());
+    }
+}
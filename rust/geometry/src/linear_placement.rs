@@ -0,0 +1,269 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Distance-along evaluation for IFC4X3 linear placements.
+//!
+//! `IfcLinearPlacement` positions an element at a station along an alignment
+//! curve (`IfcPointByDistanceExpression`) with lateral, vertical and
+//! longitudinal offsets. The curve is evaluated on its tessellated polyline;
+//! the local frame at the station has X along the curve tangent, Y to the
+//! left in plan and Z up, rolled about X by the cant of the alignment.
+
+use crate::{Point3, Vector3};
+use nalgebra::Matrix4;
+
+/// Stations closer than this are merged when resampling a curve
+const STATION_TOLERANCE: f64 = 1e-9;
+
+/// Station and offsets of an `IfcPointByDistanceExpression`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DistanceExpression {
+    /// Distance along the basis curve from its start
+    pub distance_along: f64,
+    /// Offset perpendicular to the curve in plan, positive to the left
+    pub offset_lateral: f64,
+    /// Offset along the frame's up axis
+    pub offset_vertical: f64,
+    /// Offset along the curve tangent
+    pub offset_longitudinal: f64,
+}
+
+/// Tessellated alignment curve with a station and cant per point.
+///
+/// Plain curves are stationed by their 3D length. Gradient curves keep the
+/// stations of their horizontal base curve, which is how IFC4X3 measures
+/// distance along an alignment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlignmentCurve {
+    points: Vec<Point3<f64>>,
+    stations: Vec<f64>,
+    cant: Vec<f64>,
+}
+
+impl AlignmentCurve {
+    /// Curve through `points`, stationed by 3D length, without cant
+    pub fn from_polyline(points: Vec<Point3<f64>>) -> Self {
+        let mut stations = Vec::with_capacity(points.len());
+        let mut travelled = 0.0;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                travelled += (point - points[i - 1]).norm();
+            }
+            stations.push(travelled);
+        }
+        let cant = vec![0.0; points.len()];
+        Self {
+            points,
+            stations,
+            cant,
+        }
+    }
+
+    /// Tessellated points
+    pub fn points(&self) -> &[Point3<f64>] {
+        &self.points
+    }
+
+    /// Distance along the alignment of each point
+    pub fn stations(&self) -> &[f64] {
+        &self.stations
+    }
+
+    /// Lift the curve by a vertical profile of `(station, height)` samples.
+    ///
+    /// Profile samples become curve points so vertical curves keep their
+    /// shape; heights are linearly interpolated in between and held
+    /// constant past either end of the profile.
+    pub fn with_gradient(&self, profile: &[(f64, f64)]) -> Self {
+        if profile.is_empty() {
+            return self.clone();
+        }
+        let mut curve = self.resample(profile.iter().map(|&(station, _)| station));
+        for (point, &station) in curve.points.iter_mut().zip(&curve.stations) {
+            point.z += interpolate(profile, station);
+        }
+        curve
+    }
+
+    /// Apply cant from `(station, roll)` samples, roll in radians with
+    /// positive values raising the left side.
+    pub fn with_cant(&self, cant: &[(f64, f64)]) -> Self {
+        if cant.is_empty() {
+            return self.clone();
+        }
+        let mut curve = self.resample(cant.iter().map(|&(station, _)| station));
+        for (roll, &station) in curve.cant.iter_mut().zip(&curve.stations) {
+            *roll = interpolate(cant, station);
+        }
+        curve
+    }
+
+    /// Point, unit tangent and cant at `distance` along the curve.
+    ///
+    /// Distances outside the curve are extrapolated along the first/last
+    /// segment. Returns `None` for curves without a non-degenerate segment.
+    pub fn frame_at(&self, distance: f64) -> Option<(Point3<f64>, Vector3<f64>, f64)> {
+        let segments: Vec<usize> = (0..self.points.len().saturating_sub(1))
+            .filter(|&i| {
+                self.stations[i + 1] - self.stations[i] > 1e-12
+                    && (self.points[i + 1] - self.points[i]).norm() > 1e-12
+            })
+            .collect();
+        let (&first, &last) = (segments.first()?, segments.last()?);
+
+        let i = if distance <= self.stations[first] {
+            first
+        } else {
+            segments
+                .iter()
+                .copied()
+                .find(|&i| distance <= self.stations[i + 1])
+                .unwrap_or(last)
+        };
+
+        let delta = self.points[i + 1] - self.points[i];
+        let t = (distance - self.stations[i]) / (self.stations[i + 1] - self.stations[i]);
+        let cant = self.cant[i] + (self.cant[i + 1] - self.cant[i]) * t.clamp(0.0, 1.0);
+        Some((self.points[i] + delta * t, delta.normalize(), cant))
+    }
+
+    /// The curve with extra points at `stations` (within its range)
+    fn resample(&self, stations: impl Iterator<Item = f64>) -> Self {
+        let (Some(&start), Some(&end)) = (self.stations.first(), self.stations.last()) else {
+            return self.clone();
+        };
+        let mut merged: Vec<f64> = self
+            .stations
+            .iter()
+            .copied()
+            .chain(stations.filter(|s| (start..=end).contains(s)))
+            .collect();
+        merged.sort_by(f64::total_cmp);
+        merged.dedup_by(|a, b| (*a - *b).abs() < STATION_TOLERANCE);
+
+        let mut curve = Self::default();
+        for station in merged {
+            if let Some((point, _, cant)) = self.frame_at(station) {
+                curve.points.push(point);
+                curve.stations.push(station);
+                curve.cant.push(cant);
+            }
+        }
+        curve
+    }
+}
+
+/// Linear interpolation of `(station, value)` samples sorted by station,
+/// constant past either end
+fn interpolate(samples: &[(f64, f64)], station: f64) -> f64 {
+    let index = samples.partition_point(|&(s, _)| s <= station);
+    match (index.checked_sub(1).map(|i| samples[i]), samples.get(index)) {
+        (Some((s0, v0)), Some(&(s1, v1))) if s1 - s0 > 1e-12 => {
+            v0 + (v1 - v0) * (station - s0) / (s1 - s0)
+        }
+        (Some((_, v)), _) | (None, Some(&(_, v))) => v,
+        (None, None) => 0.0,
+    }
+}
+
+/// Point and unit tangent of a polyline at `distance` from its start.
+///
+/// Distances outside the polyline are extrapolated along the first/last
+/// segment. Returns `None` for polylines without a non-degenerate segment.
+pub fn point_at_distance(
+    polyline: &[Point3<f64>],
+    distance: f64,
+) -> Option<(Point3<f64>, Vector3<f64>)> {
+    AlignmentCurve::from_polyline(polyline.to_vec())
+        .frame_at(distance)
+        .map(|(point, tangent, _)| (point, tangent))
+}
+
+/// World transform of a linear placement relative to its basis curve.
+///
+/// `axis` and `ref_direction` are the `IfcAxis2PlacementLinear` directions,
+/// expressed in the curve frame at the station (defaults: Z up, X along the
+/// tangent).
+pub fn linear_placement_transform(
+    curve: &AlignmentCurve,
+    expression: &DistanceExpression,
+    axis: Option<Vector3<f64>>,
+    ref_direction: Option<Vector3<f64>>,
+) -> Option<Matrix4<f64>> {
+    let (point, tangent, cant) = curve.frame_at(expression.distance_along)?;
+
+    // Curve frame: tangent, horizontal left, and the up vector completing it
+    let up = Vector3::z();
+    let left = up.cross(&tangent);
+    let left = if left.norm() > 1e-12 {
+        left.normalize()
+    } else {
+        // Vertical tangent: pick any horizontal direction
+        Vector3::y()
+    };
+    let normal = tangent.cross(&left).normalize();
+
+    // Cant rolls the frame about the tangent
+    let (sin, cos) = cant.sin_cos();
+    let (left, normal) = (left * cos + normal * sin, normal * cos - left * sin);
+
+    let origin = point
+        + tangent * expression.offset_longitudinal
+        + left * expression.offset_lateral
+        + normal * expression.offset_vertical;
+
+    let to_world = |v: Vector3<f64>| tangent * v.x + left * v.y + normal * v.z;
+    let z_axis = to_world(axis.unwrap_or_else(Vector3::z)).try_normalize(1e-12)?;
+    let x_ref = to_world(ref_direction.unwrap_or_else(Vector3::x));
+    let y_axis = z_axis.cross(&x_ref).try_normalize(1e-12)?;
+    let x_axis = y_axis.cross(&z_axis);
+
+    let mut transform = Matrix4::identity();
+    for (col, v) in [x_axis, y_axis, z_axis, origin.coords].iter().enumerate() {
+        transform[(0, col)] = v.x;
+        transform[(1, col)] = v.y;
+        transform[(2, col)] = v.z;
+    }
+    Some(transform)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_placement_station_and_offsets() {
+        // L-shaped alignment: 10 m east, then 10 m north
+        let alignment = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(10.0, 0.0, 0.0),
+            Point3::new(10.0, 10.0, 0.0),
+        ];
+
+        let (point, tangent) = point_at_distance(&alignment, 15.0).unwrap();
+        assert!((point - Point3::new(10.0, 5.0, 0.0)).norm() < 1e-9);
+        assert!((tangent - Vector3::y()).norm() < 1e-9);
+
+        // 2 m left (west) of station 15, 1 m up: local X follows the alignment
+        let expression = DistanceExpression {
+            distance_along: 15.0,
+            offset_lateral: 2.0,
+            offset_vertical: 1.0,
+            offset_longitudinal: 0.0,
+        };
+        let curve = AlignmentCurve::from_polyline(alignment.to_vec());
+        let m = linear_placement_transform(&curve, &expression, None, None).unwrap();
+        let origin = m.transform_point(&Point3::origin());
+        assert!((origin - Point3::new(8.0, 5.0, 1.0)).norm() < 1e-9);
+        let x = m.transform_vector(&Vector3::x());
+        assert!((x - Vector3::y()).norm() < 1e-9);
+        let z = m.transform_vector(&Vector3::z());
+        assert!((z - Vector3::z()).norm() < 1e-9);
+
+        // Before the start the first segment is extrapolated
+        let (point, _) = point_at_distance(&alignment, -2.0).unwrap();
+        assert!((point - Point3::new(-2.0, 0.0, 0.0)).norm() < 1e-9);
+        assert!(point_at_distance(&alignment[..1], 0.0).is_none());
+    }
+}
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Line geometry
+//!
+//! Annotation curves, survey points and text placements have no surface to
+//! triangulate. [`LineMesh`] holds them as an indexed line list that viewers
+//! draw with line primitives, so annotations can be shown and toggled like
+//! any other element instead of being dropped.
+
+use nalgebra::{Matrix4, Point3};
+
+/// Indexed line list: every two indices form one segment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineMesh {
+    /// Vertex positions (x, y, z)
+    pub positions: Vec<f32>,
+    /// Segment indices (i0, i1)
+    pub indices: Vec<u32>,
+}
+
+impl LineMesh {
+    /// Create an empty line mesh
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the mesh has no segments
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len() / 3
+    }
+
+    /// Number of segments
+    pub fn segment_count(&self) -> usize {
+        self.indices.len() / 2
+    }
+
+    /// Add an open polyline. Zero-length segments are skipped.
+    pub fn add_polyline(&mut self, points: &[Point3<f64>]) {
+        let mut previous: Option<(u32, Point3<f64>)> = None;
+        for point in points {
+            if !point.iter().all(|v| v.is_finite()) {
+                previous = None;
+                continue;
+            }
+            if previous.is_some_and(|(_, p)| p == *point) {
+                continue;
+            }
+            let index = self.push_vertex(point);
+            if let Some((start, _)) = previous {
+                self.indices.extend_from_slice(&[start, index]);
+            }
+            previous = Some((index, *point));
+        }
+    }
+
+    /// Add a three-axis cross of half size `size` centred on `point`, the
+    /// marker for survey points and text placeholders
+    pub fn add_marker(&mut self, point: &Point3<f64>, size: f64) {
+        for axis in 0..3 {
+            let (mut start, mut end) = (*point, *point);
+            start[axis] -= size;
+            end[axis] += size;
+            self.add_polyline(&[start, end]);
+        }
+    }
+
+    /// Append another line mesh
+    pub fn merge(&mut self, other: &LineMesh) {
+        let offset = self.vertex_count() as u32;
+        self.positions.extend_from_slice(&other.positions);
+        self.indices
+            .extend(other.indices.iter().map(|&i| i + offset));
+    }
+
+    /// Transform every vertex by `transform`
+    pub fn transform(&mut self, transform: &Matrix4<f64>) {
+        for p in self.positions.chunks_exact_mut(3) {
+            let point = Point3::new(p[0] as f64, p[1] as f64, p[2] as f64);
+            let moved = transform.transform_point(&point);
+            p[0] = moved.x as f32;
+            p[1] = moved.y as f32;
+            p[2] = moved.z as f32;
+        }
+    }
+
+    fn push_vertex(&mut self, point: &Point3<f64>) -> u32 {
+        let index = self.vertex_count() as u32;
+        self.positions
+            .extend_from_slice(&[point.x as f32, point.y as f32, point.z as f32]);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_polylines_and_markers() {
+        let mut lines = LineMesh::new();
+        lines.add_polyline(&[
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+        ]);
+        assert_eq!(lines.segment_count(), 2);
+        assert_eq!(lines.vertex_count(), 3);
+
+        let mut marker = LineMesh::new();
+        marker.add_marker(&Point3::new(5.0, 5.0, 5.0), 0.5);
+        assert_eq!(marker.segment_count(), 3);
+
+        lines.merge(&marker);
+        assert_eq!(lines.segment_count(), 5);
+        assert_eq!(lines.indices[4..6], [3, 4]);
+
+        lines.transform(&Matrix4::new_translation(&Vector3::new(0.0, 0.0, 10.0)));
+        assert_eq!(lines.positions[2], 10.0);
+        assert_eq!(lines.positions[lines.positions.len() - 1], 15.5);
+    }
+}
@@ -0,0 +1,376 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Level-of-detail generation
+//!
+//! Derives coarser versions of an element mesh so viewers can switch detail
+//! by camera distance without reprocessing the IFC file:
+//!
+//! - **Full**: the mesh as produced by the processors
+//! - **Simplified**: vertex clustering on a uniform grid over the mesh bounds,
+//!   or quadric edge collapse (see [`crate::simplify`])
+//! - **Proxy**: a 12-triangle axis-aligned box
+//!
+//! Clustering keeps one vertex per grid cell and face orientation, so flat
+//! regions stay flat-shaded and neighbouring faces share positions (no cracks).
+//! It is cheap but coarse; quadric decimation is slower and keeps the shape of
+//! dense tessellated surfaces (terrain, freeform facades) much better.
+
+use crate::mesh::Mesh;
+use crate::simplify::{simplify, SimplifyOptions};
+use nalgebra::{Point3, Vector3};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Which levels [`generate_lods`] produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LodLevels {
+    /// Full mesh and bounding-box proxy
+    Two,
+    /// Full mesh, simplified mesh and bounding-box proxy
+    Three,
+}
+
+/// How the simplified level is derived
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LodSimplifier {
+    /// Vertex clustering on a grid of [`LodOptions::grid_resolution`] cells
+    Clustering,
+    /// Quadric edge collapse keeping `ratio` (0–1) of the triangles
+    Quadric { ratio: f64 },
+}
+
+/// Level-of-detail options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodOptions {
+    /// Levels to generate
+    pub levels: LodLevels,
+    /// Simplification method for the middle level
+    pub simplifier: LodSimplifier,
+    /// Grid cells along the longest bounding-box axis for clustering
+    pub grid_resolution: u32,
+}
+
+impl Default for LodOptions {
+    fn default() -> Self {
+        Self {
+            levels: LodLevels::Three,
+            simplifier: LodSimplifier::Clustering,
+            grid_resolution: 8,
+        }
+    }
+}
+
+/// Meshes for one element, from finest to coarsest
+#[derive(Debug, Clone)]
+pub struct ElementLods {
+    pub full: Mesh,
+    /// `None` when [`LodLevels::Two`] was requested
+    pub simplified: Option<Mesh>,
+    pub proxy: Mesh,
+}
+
+impl ElementLods {
+    /// Meshes ordered from finest to coarsest
+    pub fn levels(&self) -> Vec<&Mesh> {
+        let mut levels = vec![&self.full];
+        levels.extend(self.simplified.as_ref());
+        levels.push(&self.proxy);
+        levels
+    }
+}
+
+/// Build all requested levels of detail from a full-resolution mesh
+pub fn generate_lods(full: Mesh, options: &LodOptions) -> ElementLods {
+    let simplified = match options.levels {
+        LodLevels::Three => Some(match options.simplifier {
+            LodSimplifier::Clustering => simplify_by_clustering(&full, options.grid_resolution),
+            LodSimplifier::Quadric { ratio } => {
+                simplify(&full, &SimplifyOptions::with_ratio(&full, ratio))
+            }
+        }),
+        LodLevels::Two => None,
+    };
+    let proxy = bounding_box_proxy(&full);
+    ElementLods {
+        full,
+        simplified,
+        proxy,
+    }
+}
+
+/// Simplify a mesh by merging all vertices within each grid cell.
+///
+/// Returns a copy of `mesh` when clustering would not reduce its triangle count.
+pub fn simplify_by_clustering(mesh: &Mesh, grid_resolution: u32) -> Mesh {
+    if mesh.is_empty() || mesh.indices.len() < 3 {
+        return mesh.clone();
+    }
+
+    let (min, max) = mesh.bounds();
+    let extent = (max - min).max();
+    if extent <= 0.0 || grid_resolution == 0 {
+        return mesh.clone();
+    }
+    let cell_size = extent / grid_resolution as f32;
+    let cell_of = |i: usize| -> (i32, i32, i32) {
+        let p = &mesh.positions[i * 3..i * 3 + 3];
+        (
+            ((p[0] - min.x) / cell_size).floor() as i32,
+            ((p[1] - min.y) / cell_size).floor() as i32,
+            ((p[2] - min.z) / cell_size).floor() as i32,
+        )
+    };
+
+    // Representative position per cell: average of its vertices
+    let vertex_count = mesh.vertex_count();
+    let mut cell_ids: FxHashMap<(i32, i32, i32), usize> = FxHashMap::default();
+    let mut cell_sums: Vec<(Vector3<f64>, f64)> = Vec::new();
+    let mut vertex_cell = Vec::with_capacity(vertex_count);
+    for i in 0..vertex_count {
+        let next_id = cell_ids.len();
+        let id = *cell_ids.entry(cell_of(i)).or_insert(next_id);
+        if id == cell_sums.len() {
+            cell_sums.push((Vector3::zeros(), 0.0));
+        }
+        let p = &mesh.positions[i * 3..i * 3 + 3];
+        cell_sums[id].0 += Vector3::new(p[0] as f64, p[1] as f64, p[2] as f64);
+        cell_sums[id].1 += 1.0;
+        vertex_cell.push(id);
+    }
+    let cell_positions: Vec<Point3<f64>> = cell_sums
+        .iter()
+        .map(|(sum, n)| Point3::from(sum / *n))
+        .collect();
+
+    // Emit triangles between distinct cells; output vertices are keyed by
+    // cell and face orientation so normals do not smear across hard edges
+    let mut out = Mesh::with_capacity(cell_positions.len(), mesh.indices.len());
+    let mut out_vertices: FxHashMap<(usize, u8), u32> = FxHashMap::default();
+    let mut out_normals: Vec<Vector3<f64>> = Vec::new();
+    let mut seen_triangles: FxHashSet<[u32; 3]> = FxHashSet::default();
+
+    for tri in mesh.indices.chunks_exact(3) {
+        let cells = [
+            vertex_cell[tri[0] as usize],
+            vertex_cell[tri[1] as usize],
+            vertex_cell[tri[2] as usize],
+        ];
+        if cells[0] == cells[1] || cells[1] == cells[2] || cells[0] == cells[2] {
+            continue;
+        }
+        let [a, b, c] = cells.map(|id| cell_positions[id]);
+        let normal = (b - a).cross(&(c - a));
+        if normal.norm_squared() < 1e-20 {
+            continue;
+        }
+        let bucket = orientation_bucket(&normal);
+
+        let mut out_tri = [0u32; 3];
+        for (slot, &cell) in out_tri.iter_mut().zip(cells.iter()) {
+            let index = *out_vertices.entry((cell, bucket)).or_insert_with(|| {
+                let p = cell_positions[cell];
+                out.positions
+                    .extend_from_slice(&[p.x as f32, p.y as f32, p.z as f32]);
+                out_normals.push(Vector3::zeros());
+                (out_normals.len() - 1) as u32
+            });
+            out_normals[index as usize] += normal;
+            *slot = index;
+        }
+
+        let mut key = out_tri;
+        key.sort_unstable();
+        if seen_triangles.insert(key) {
+            out.indices.extend_from_slice(&out_tri);
+        }
+    }
+
+    if out.indices.len() >= mesh.indices.len() {
+        return mesh.clone();
+    }
+
+    for n in &out_normals {
+        let n = n.try_normalize(1e-12).unwrap_or_else(Vector3::z);
+        out.normals
+            .extend_from_slice(&[n.x as f32, n.y as f32, n.z as f32]);
+    }
+    out.rtc_applied = mesh.rtc_applied;
+    out
+}
+
+/// Axis-aligned box covering the mesh, with outward face normals
+pub fn bounding_box_proxy(mesh: &Mesh) -> Mesh {
+    if mesh.is_empty() {
+        return Mesh::new();
+    }
+    let (min, max) = mesh.bounds();
+    let (min, max) = (min.cast::<f64>(), max.cast::<f64>());
+    let corner = |x: bool, y: bool, z: bool| {
+        Point3::new(
+            if x { max.x } else { min.x },
+            if y { max.y } else { min.y },
+            if z { max.z } else { min.z },
+        )
+    };
+
+    // (normal, four corners counter-clockwise seen from outside)
+    let faces = [
+        (
+            Vector3::x(),
+            [
+                (true, false, false),
+                (true, true, false),
+                (true, true, true),
+                (true, false, true),
+            ],
+        ),
+        (
+            -Vector3::x(),
+            [
+                (false, false, false),
+                (false, false, true),
+                (false, true, true),
+                (false, true, false),
+            ],
+        ),
+        (
+            Vector3::y(),
+            [
+                (false, true, false),
+                (false, true, true),
+                (true, true, true),
+                (true, true, false),
+            ],
+        ),
+        (
+            -Vector3::y(),
+            [
+                (false, false, false),
+                (true, false, false),
+                (true, false, true),
+                (false, false, true),
+            ],
+        ),
+        (
+            Vector3::z(),
+            [
+                (false, false, true),
+                (true, false, true),
+                (true, true, true),
+                (false, true, true),
+            ],
+        ),
+        (
+            -Vector3::z(),
+            [
+                (false, false, false),
+                (false, true, false),
+                (true, true, false),
+                (true, false, false),
+            ],
+        ),
+    ];
+
+    let mut proxy = Mesh::with_capacity(24, 36);
+    for (normal, corners) in faces {
+        let base = proxy.vertex_count() as u32;
+        for (x, y, z) in corners {
+            proxy.add_vertex(corner(x, y, z), normal);
+        }
+        proxy.add_triangle(base, base + 1, base + 2);
+        proxy.add_triangle(base, base + 2, base + 3);
+    }
+    proxy.rtc_applied = mesh.rtc_applied;
+    proxy
+}
+
+/// Dominant axis and sign of a face normal (0..6)
+fn orientation_bucket(normal: &Vector3<f64>) -> u8 {
+    let abs = normal.abs();
+    let axis = if abs.x >= abs.y && abs.x >= abs.z {
+        0
+    } else if abs.y >= abs.z {
+        1
+    } else {
+        2
+    };
+    axis * 2 + u8::from(normal[axis as usize] < 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UV sphere with `rings * segments` quads
+    fn sphere(rings: usize, segments: usize) -> Mesh {
+        let mut mesh = Mesh::new();
+        for i in 0..=rings {
+            let v = std::f64::consts::PI * i as f64 / rings as f64;
+            for j in 0..=segments {
+                let u = std::f64::consts::TAU * j as f64 / segments as f64;
+                let n = Vector3::new(v.sin() * u.cos(), v.sin() * u.sin(), v.cos());
+                mesh.add_vertex(Point3::from(n), n);
+            }
+        }
+        let cols = segments as u32 + 1;
+        for i in 0..rings as u32 {
+            for j in 0..segments as u32 {
+                let a = i * cols + j;
+                let b = a + cols;
+                mesh.add_triangle(a, b, b + 1);
+                mesh.add_triangle(a, b + 1, a + 1);
+            }
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_generate_three_levels() {
+        let full = sphere(32, 64);
+        let full_triangles = full.triangle_count();
+        let lods = generate_lods(full, &LodOptions::default());
+
+        let simplified = lods.simplified.as_ref().unwrap();
+        assert!(simplified.triangle_count() > 0);
+        assert!(simplified.triangle_count() < full_triangles / 4);
+        assert_eq!(simplified.normals.len(), simplified.positions.len());
+        assert_eq!(lods.proxy.triangle_count(), 12);
+        assert_eq!(lods.levels().len(), 3);
+
+        let (min, max) = lods.proxy.bounds();
+        assert!((min.x + 1.0).abs() < 1e-3 && (max.z - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_two_levels_and_small_meshes() {
+        let options = LodOptions {
+            levels: LodLevels::Two,
+            ..Default::default()
+        };
+        let lods = generate_lods(sphere(4, 8), &options);
+        assert!(lods.simplified.is_none());
+        assert_eq!(lods.levels().len(), 2);
+
+        let options = LodOptions {
+            simplifier: LodSimplifier::Quadric { ratio: 0.25 },
+            ..Default::default()
+        };
+        let full = sphere(16, 32);
+        let full_triangles = full.triangle_count();
+        let lods = generate_lods(full, &options);
+        let simplified = lods.simplified.as_ref().unwrap();
+        assert!(simplified.triangle_count() < full_triangles);
+        assert!(simplified.triangle_count() > 0);
+
+        // Nothing to gain: the input is returned unchanged
+        let mut tiny = Mesh::new();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+            tiny.add_vertex(Point3::new(x, y, 0.0), Vector3::z());
+        }
+        tiny.add_triangle(0, 1, 2);
+        tiny.add_triangle(0, 2, 3);
+        let simplified = simplify_by_clustering(&tiny, 64);
+        assert_eq!(simplified.indices, tiny.indices);
+    }
+}
@@ -0,0 +1,250 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Material palette for styled sub-meshes
+//!
+//! Surface styles are resolved once per file (IfcStyledItem → IfcSurfaceStyle →
+//! IfcSurfaceStyleShading/Rendering → IfcColourRgb) and deduplicated into a
+//! palette. Sub-meshes then carry a stable [`MaterialId`] into that palette
+//! instead of every consumer re-walking the style chain per element.
+
+use ifc_lite_core::{AttributeValue, EntityDecoder, EntityScanner, IfcType};
+use rustc_hash::FxHashMap;
+
+/// Index into a [`MaterialPalette`]
+pub type MaterialId = u32;
+
+/// A resolved surface style
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    /// Express ID of the IfcSurfaceStyle
+    pub style_id: u32,
+    /// IfcSurfaceStyle.Name, if set
+    pub name: Option<String>,
+    /// RGBA, alpha = 1 - Transparency
+    pub color: [f32; 4],
+}
+
+/// Deduplicated materials, addressed by [`MaterialId`]
+#[derive(Debug, Clone, Default)]
+pub struct MaterialPalette {
+    materials: Vec<Material>,
+    by_style: FxHashMap<u32, MaterialId>,
+}
+
+impl MaterialPalette {
+    /// Create an empty palette
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a material, returning the existing ID if its style is already known
+    pub fn insert(&mut self, material: Material) -> MaterialId {
+        if let Some(&id) = self.by_style.get(&material.style_id) {
+            return id;
+        }
+        let id = self.materials.len() as MaterialId;
+        self.by_style.insert(material.style_id, id);
+        self.materials.push(material);
+        id
+    }
+
+    /// Material by ID
+    pub fn get(&self, id: MaterialId) -> Option<&Material> {
+        self.materials.get(id as usize)
+    }
+
+    /// ID of the material resolved from an IfcSurfaceStyle
+    pub fn id_for_style(&self, style_id: u32) -> Option<MaterialId> {
+        self.by_style.get(&style_id).copied()
+    }
+
+    /// Number of distinct materials
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    /// Whether the palette is empty
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    /// Materials in ID order
+    pub fn iter(&self) -> impl Iterator<Item = (MaterialId, &Material)> {
+        self.materials
+            .iter()
+            .enumerate()
+            .map(|(id, material)| (id as MaterialId, material))
+    }
+}
+
+/// Geometry item → material assignments of a file, with their palette
+#[derive(Debug, Clone, Default)]
+pub struct StyleIndex {
+    palette: MaterialPalette,
+    items: FxHashMap<u32, MaterialId>,
+}
+
+impl StyleIndex {
+    /// Scan all IfcStyledItem entities and resolve their surface styles
+    pub fn build(content: &str, decoder: &mut EntityDecoder) -> Self {
+        let mut index = Self::default();
+        let mut scanner = EntityScanner::new(content);
+
+        while let Some((id, type_name, start, end)) = scanner.next_entity() {
+            if type_name != "IFCSTYLEDITEM" {
+                continue;
+            }
+            let Ok(styled_item) = decoder.decode_at_with_id(id, start, end) else {
+                continue;
+            };
+            // IfcStyledItem: Item, Styles, Name - the first styled item wins
+            let Some(item_id) = styled_item.get_ref(0) else {
+                continue;
+            };
+            if index.items.contains_key(&item_id) {
+                continue;
+            }
+            let Some(styles) = styled_item.get(1) else {
+                continue;
+            };
+            if let Some(material) = index.resolve_styles(styles, decoder, 0) {
+                index.items.insert(item_id, material);
+            }
+        }
+
+        index
+    }
+
+    /// Material assigned to a geometry item
+    pub fn material_for_item(&self, item_id: u32) -> Option<MaterialId> {
+        self.items.get(&item_id).copied()
+    }
+
+    /// Distinct materials referenced by the index
+    pub fn palette(&self) -> &MaterialPalette {
+        &self.palette
+    }
+
+    /// Number of styled geometry items
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether no geometry item is styled
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Resolve a style reference or list of them to the first surface style
+    /// with a colour. Handles IfcPresentationStyleAssignment (IFC2X3), which
+    /// wraps the surface styles one level deeper.
+    fn resolve_styles(
+        &mut self,
+        styles: &AttributeValue,
+        decoder: &mut EntityDecoder,
+        depth: u32,
+    ) -> Option<MaterialId> {
+        if depth > 2 {
+            return None;
+        }
+        let refs: Vec<u32> = match styles.as_list() {
+            Some(list) => list.iter().filter_map(|v| v.as_entity_ref()).collect(),
+            None => styles.as_entity_ref().into_iter().collect(),
+        };
+
+        for style_id in refs {
+            if let Some(id) = self.palette.id_for_style(style_id) {
+                return Some(id);
+            }
+            let Ok(style) = decoder.decode_by_id(style_id) else {
+                continue;
+            };
+            let resolved = if style.ifc_type == IfcType::IfcSurfaceStyle {
+                resolve_surface_style(&style, decoder).map(|m| self.palette.insert(m))
+            } else {
+                style
+                    .get(0)
+                    .and_then(|inner| self.resolve_styles(inner, decoder, depth + 1))
+            };
+            if resolved.is_some() {
+                return resolved;
+            }
+        }
+        None
+    }
+}
+
+/// Name and colour of an IfcSurfaceStyle (Name, Side, Styles)
+fn resolve_surface_style(
+    style: &ifc_lite_core::DecodedEntity,
+    decoder: &mut EntityDecoder,
+) -> Option<Material> {
+    let name = style.get_string(0).map(str::to_string);
+    let elements = style.get(2)?.as_list()?;
+
+    for element in elements {
+        let Some(element_id) = element.as_entity_ref() else {
+            continue;
+        };
+        let Ok(shading) = decoder.decode_by_id(element_id) else {
+            continue;
+        };
+        if !matches!(
+            shading.ifc_type,
+            IfcType::IfcSurfaceStyleShading | IfcType::IfcSurfaceStyleRendering
+        ) {
+            continue;
+        }
+        // SurfaceColour, Transparency
+        let Some(colour) = shading
+            .get_ref(0)
+            .and_then(|id| decoder.decode_by_id(id).ok())
+        else {
+            continue;
+        };
+        if colour.ifc_type != IfcType::IfcColourRgb {
+            continue;
+        }
+        let channel = |i: usize| colour.get_float(i).unwrap_or(0.8) as f32;
+        let alpha = 1.0 - shading.get_float(1).unwrap_or(0.0) as f32;
+        return Some(Material {
+            style_id: style.id,
+            name,
+            color: [channel(1), channel(2), channel(3), alpha.clamp(0.0, 1.0)],
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_index_dedups_materials() {
+        let content = r#"
+#1=IFCCOLOURRGB($,0.5,0.25,1.);
+#2=IFCSURFACESTYLESHADING(#1,0.4);
+#3=IFCSURFACESTYLE('Glass',.BOTH.,(#2));
+#4=IFCPRESENTATIONSTYLEASSIGNMENT((#3));
+#10=IFCSTYLEDITEM(#20,(#3),$);
+#11=IFCSTYLEDITEM(#21,(#4),$);
+#12=IFCSTYLEDITEM(#22,(#99),$);
+"#;
+        let mut decoder = EntityDecoder::new(content);
+        let index = StyleIndex::build(content, &mut decoder);
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.palette().len(), 1);
+        let id = index.material_for_item(20).unwrap();
+        assert_eq!(index.material_for_item(21), Some(id));
+        assert_eq!(index.material_for_item(22), None);
+
+        let material = index.palette().get(id).unwrap();
+        assert_eq!(material.style_id, 3);
+        assert_eq!(material.name.as_deref(), Some("Glass"));
+        assert_eq!(material.color, [0.5, 0.25, 1.0, 0.6]);
+    }
+}
@@ -4,7 +4,13 @@
 
 //! Mesh data structures
 
+use crate::materials::MaterialId;
 use nalgebra::{Point3, Vector3};
+use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
+
+/// Minimum cosine between normals of vertices merged by [`Mesh::weld`] (~1°)
+const WELD_NORMAL_COS: f32 = 0.9998;
 
 /// Coordinate shift for RTC (Relative-to-Center) rendering
 /// Stores the offset subtracted from coordinates to improve Float32 precision
@@ -73,12 +79,24 @@ pub struct SubMesh {
     pub geometry_id: u32,
     /// The triangulated mesh data
     pub mesh: Mesh,
+    /// Resolved surface style, an index into the router's material palette
+    pub material: Option<MaterialId>,
 }
 
 impl SubMesh {
     /// Create a new sub-mesh
     pub fn new(geometry_id: u32, mesh: Mesh) -> Self {
-        Self { geometry_id, mesh }
+        Self {
+            geometry_id,
+            mesh,
+            material: None,
+        }
+    }
+
+    /// Set the resolved material
+    pub fn with_material(mut self, material: Option<MaterialId>) -> Self {
+        self.material = material;
+        self
     }
 }
 
@@ -98,8 +116,19 @@ impl SubMeshCollection {
 
     /// Add a sub-mesh
     pub fn add(&mut self, geometry_id: u32, mesh: Mesh) {
+        self.add_with_material(geometry_id, mesh, None);
+    }
+
+    /// Add a sub-mesh with its resolved material
+    pub fn add_with_material(
+        &mut self,
+        geometry_id: u32,
+        mesh: Mesh,
+        material: Option<MaterialId>,
+    ) {
         if !mesh.is_empty() {
-            self.sub_meshes.push(SubMesh::new(geometry_id, mesh));
+            self.sub_meshes
+                .push(SubMesh::new(geometry_id, mesh).with_material(material));
         }
     }
 
@@ -397,6 +426,31 @@ impl Mesh {
         )
     }
 
+    /// Enclosed volume of a closed mesh (divergence theorem): positive when
+    /// triangles face outward, negative when the mesh is inside out.
+    ///
+    /// Summed relative to one of the mesh's own vertices so large world
+    /// coordinates keep their precision. Triangles with invalid indices or
+    /// non-finite vertices are skipped.
+    pub fn signed_volume(&self) -> f64 {
+        let finite = |p: &[f32]| {
+            p.iter()
+                .all(|c| c.is_finite())
+                .then(|| Vector3::new(p[0] as f64, p[1] as f64, p[2] as f64))
+        };
+        let Some(origin) = self.positions.chunks_exact(3).find_map(finite) else {
+            return 0.0;
+        };
+        let point = |i: u32| {
+            let i = i as usize * 3;
+            Some(finite(self.positions.get(i..i + 3)?)? - origin)
+        };
+        self.indices
+            .chunks_exact(3)
+            .filter_map(|t| Some(point(t[0])?.dot(&point(t[1])?.cross(&point(t[2])?)) / 6.0))
+            .sum()
+    }
+
     /// Clear the mesh
     #[inline]
     pub fn clear(&mut self) {
@@ -481,6 +535,169 @@ impl Mesh {
         self.indices = valid_indices;
         removed_count
     }
+    /// Merge coincident vertices and rebuild indices.
+    ///
+    /// Vertices closer than `tolerance` are merged when their normals agree,
+    /// so hard edges keep separate vertices. Meshes without normals use the
+    /// normal of each vertex's first triangle instead. A `tolerance` of zero
+    /// merges only bit-identical positions. Unreferenced vertices and
+    /// triangles that collapse to a line are removed.
+    ///
+    /// # Returns
+    /// Number of vertices removed
+    pub fn weld(&mut self, tolerance: f32) -> usize {
+        let vertex_count = self.vertex_count();
+        self.validate_indices();
+        if vertex_count == 0 {
+            return 0;
+        }
+
+        let has_normals = self.normals.len() == self.positions.len();
+        let normals = if has_normals {
+            self.normals
+                .chunks_exact(3)
+                .map(|n| Vector3::new(n[0], n[1], n[2]))
+                .collect()
+        } else {
+            self.first_face_normals()
+        };
+
+        let cell_key = |p: &[f32]| -> [i64; 3] {
+            if tolerance > 0.0 {
+                [0, 1, 2].map(|i| (p[i] / tolerance).floor() as i64)
+            } else {
+                // Bit pattern, with -0.0 folded into 0.0
+                [0, 1, 2].map(|i| (p[i] + 0.0).to_bits() as i64)
+            }
+        };
+        let tolerance_sq = tolerance * tolerance;
+
+        let mut referenced = vec![false; vertex_count];
+        for &i in &self.indices {
+            referenced[i as usize] = true;
+        }
+
+        let mut grid: FxHashMap<[i64; 3], SmallVec<[u32; 2]>> = FxHashMap::default();
+        let mut remap = vec![u32::MAX; vertex_count];
+        let mut positions: Vec<f32> = Vec::with_capacity(self.positions.len());
+        let mut new_normals = Vec::new();
+        let mut kept_normals: Vec<Vector3<f32>> = Vec::new();
+
+        for i in (0..vertex_count).filter(|&i| referenced[i]) {
+            let p = &self.positions[i * 3..i * 3 + 3];
+            let n = normals[i];
+            let key = cell_key(p);
+
+            let matches = |j: &u32| {
+                let q = &positions[*j as usize * 3..*j as usize * 3 + 3];
+                let dist_sq = (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2);
+                dist_sq <= tolerance_sq && n.dot(&kept_normals[*j as usize]) >= WELD_NORMAL_COS
+            };
+            let found = if tolerance > 0.0 {
+                (-1..=1)
+                    .flat_map(|dx| {
+                        (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz)))
+                    })
+                    .find_map(|(dx, dy, dz)| {
+                        grid.get(&[key[0] + dx, key[1] + dy, key[2] + dz])?
+                            .iter()
+                            .copied()
+                            .find(|j| matches(j))
+                    })
+            } else {
+                grid.get(&key)
+                    .and_then(|c| c.iter().copied().find(|j| matches(j)))
+            };
+
+            remap[i] = match found {
+                Some(j) => j,
+                None => {
+                    let j = kept_normals.len() as u32;
+                    positions.extend_from_slice(p);
+                    if has_normals {
+                        new_normals.extend_from_slice(&self.normals[i * 3..i * 3 + 3]);
+                    }
+                    kept_normals.push(n);
+                    grid.entry(key).or_default().push(j);
+                    j
+                }
+            };
+        }
+
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for tri in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| remap[tri[k] as usize]);
+            if a != b && b != c && a != c {
+                indices.extend_from_slice(&[a, b, c]);
+            }
+        }
+
+        self.positions = positions;
+        self.normals = new_normals;
+        self.indices = indices;
+        vertex_count - self.vertex_count()
+    }
+
+    /// Group vertices by position only (ignoring normals): the distinct points
+    /// and, per vertex, the index of its point. Used where topology matters
+    /// across hard edges, which [`Self::weld`] deliberately keeps split.
+    pub(crate) fn position_ids(&self, tolerance: f64) -> (Vec<Point3<f64>>, Vec<u32>) {
+        let tolerance = tolerance.max(f64::EPSILON);
+        let mut grid: FxHashMap<[i64; 3], SmallVec<[u32; 2]>> = FxHashMap::default();
+        let mut points: Vec<Point3<f64>> = Vec::new();
+        let mut canonical = Vec::with_capacity(self.vertex_count());
+
+        for p in self.positions.chunks_exact(3) {
+            let p = Point3::new(p[0] as f64, p[1] as f64, p[2] as f64);
+            let key = [p.x, p.y, p.z].map(|c| (c / tolerance).floor() as i64);
+            let found = (0..27).find_map(|n| {
+                let cell = [
+                    key[0] + n % 3 - 1,
+                    key[1] + (n / 3) % 3 - 1,
+                    key[2] + n / 9 - 1,
+                ];
+                grid.get(&cell)?
+                    .iter()
+                    .copied()
+                    .find(|&j| (points[j as usize] - p).norm() <= tolerance)
+            });
+            canonical.push(found.unwrap_or_else(|| {
+                let j = points.len() as u32;
+                points.push(p);
+                grid.entry(key).or_default().push(j);
+                j
+            }));
+        }
+        (points, canonical)
+    }
+
+    /// Per-vertex normal of the first triangle referencing each vertex
+    fn first_face_normals(&self) -> Vec<Vector3<f32>> {
+        let mut normals = vec![Vector3::zeros(); self.vertex_count()];
+        let mut assigned = vec![false; self.vertex_count()];
+        let position = |i: u32| {
+            let i = i as usize * 3;
+            Vector3::new(
+                self.positions[i],
+                self.positions[i + 1],
+                self.positions[i + 2],
+            )
+        };
+        for tri in self.indices.chunks_exact(3) {
+            let (a, b, c) = (position(tri[0]), position(tri[1]), position(tri[2]));
+            let n = (b - a)
+                .cross(&(c - a))
+                .try_normalize(1e-12)
+                .unwrap_or_else(Vector3::zeros);
+            for &i in tri {
+                if !assigned[i as usize] {
+                    assigned[i as usize] = true;
+                    normals[i as usize] = n;
+                }
+            }
+        }
+        normals
+    }
 }
 
 impl Default for Mesh {
@@ -510,6 +727,20 @@ mod tests {
         assert_eq!(mesh.normals, vec![0.0, 0.0, 1.0]);
     }
 
+    #[test]
+    fn test_signed_volume() {
+        let mut mesh = crate::test_fixtures::box_mesh([1e6, 2e6, 0.0], [1e6 + 2.0, 2e6 + 1.0, 3.0]);
+        assert!((mesh.signed_volume() - 6.0).abs() < 1e-6);
+
+        // Inside out, and a triangle past the positions is ignored
+        for tri in mesh.indices.chunks_exact_mut(3) {
+            tri.swap(1, 2);
+        }
+        mesh.indices.extend_from_slice(&[0, 1, 99]);
+        assert!((mesh.signed_volume() + 6.0).abs() < 1e-6);
+        assert_eq!(Mesh::new().signed_volume(), 0.0);
+    }
+
     #[test]
     fn test_merge() {
         let mut mesh1 = Mesh::new();
@@ -651,6 +882,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_weld_merges_shared_vertices_but_keeps_hard_edges() {
+        // Two coplanar quads emitted with separate vertices
+        let mut mesh = Mesh::new();
+        for x in [0.0, 1.0] {
+            let base = mesh.vertex_count() as u32;
+            for (dx, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+                mesh.add_vertex(Point3::new(x + dx, y, 0.0), Vector3::z());
+            }
+            mesh.add_triangle(base, base + 1, base + 2);
+            mesh.add_triangle(base, base + 2, base + 3);
+        }
+        assert_eq!(mesh.weld(0.0), 2);
+        assert_eq!(mesh.vertex_count(), 6);
+        assert_eq!(mesh.triangle_count(), 4);
+        assert_eq!(mesh.normals.len(), mesh.positions.len());
+
+        // Fold the second quad up: the shared edge is now a crease
+        let mut folded = Mesh::new();
+        let faces = [
+            (
+                [
+                    [0.0, 0.0, 0.0],
+                    [1.0, 0.0, 0.0],
+                    [1.0, 1.0, 0.0],
+                    [0.0, 1.0, 0.0],
+                ],
+                Vector3::z(),
+            ),
+            (
+                [
+                    [1.0, 0.0, 0.0],
+                    [1.0, 0.0, 1.0],
+                    [1.0, 1.0, 1.0],
+                    [1.0, 1.0, 0.0],
+                ],
+                Vector3::x(),
+            ),
+        ];
+        for (corners, normal) in faces {
+            let base = folded.vertex_count() as u32;
+            for [x, y, z] in corners {
+                folded.add_vertex(Point3::new(x, y, z), normal);
+            }
+            folded.add_triangle(base, base + 1, base + 2);
+            folded.add_triangle(base, base + 2, base + 3);
+        }
+        let mut without_normals = folded.clone();
+        without_normals.normals.clear();
+        assert_eq!(folded.weld(1e-4), 0);
+        assert_eq!(without_normals.weld(1e-4), 0);
+    }
+
+    #[test]
+    fn test_weld_tolerance() {
+        let mut mesh = Mesh::new();
+        mesh.add_vertex(Point3::new(0.0, 0.0, 0.0), Vector3::z());
+        mesh.add_vertex(Point3::new(1.0, 0.0, 0.0), Vector3::z());
+        mesh.add_vertex(Point3::new(0.0, 1.0, 0.0), Vector3::z());
+        mesh.add_vertex(Point3::new(1.0, 0.0, 0.00001), Vector3::z());
+        mesh.add_vertex(Point3::new(1.0, 1.0, 0.0), Vector3::z());
+        mesh.add_vertex(Point3::new(0.0, 1.0, 0.0), Vector3::z());
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(3, 4, 5);
+
+        let mut exact = mesh.clone();
+        assert_eq!(exact.weld(0.0), 1);
+        assert_eq!(mesh.weld(0.001), 2);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 1, 3, 2]);
+    }
+
     #[test]
     fn test_validate_indices_strips_out_of_bounds() {
         let mut mesh = Mesh {
@@ -0,0 +1,331 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Index buffer codec (meshoptimizer `encodeIndexBuffer`, version 1)
+//!
+//! Triangles are coded against a 16-entry edge FIFO and a 16-entry vertex
+//! FIFO: one code byte per triangle, plus a data stream of aux bytes and
+//! zigzag varint deltas for indices that hit neither FIFO. The stream ends
+//! with the 16-byte aux table the decoder reads back.
+
+const INDEX_HEADER: u8 = 0xe0;
+const INDEX_VERSION: u8 = 1;
+
+/// Vertex FIFO codes at or above this are "last ± 1" / explicit (version 1)
+const FEC_MAX: u32 = 13;
+
+/// Common (feb, fec) pairs coded in the triangle byte instead of a data byte
+const CODEAUX_TABLE: [u8; 16] = [
+    0x00, 0x76, 0x87, 0x56, 0x67, 0x78, 0xa9, 0x86, 0x65, 0x89, 0x68, 0x98, 0x01, 0x69, 0x00, 0x00,
+];
+
+/// Vertex order of a triangle rotated to start at corner 0, 1 or 2
+const TRIANGLE_ORDER: [[usize; 3]; 3] = [[0, 1, 2], [1, 2, 0], [2, 0, 1]];
+
+const EMPTY: u32 = u32::MAX;
+
+struct Fifos {
+    edges: [[u32; 2]; 16],
+    edge_offset: usize,
+    vertices: [u32; 16],
+    vertex_offset: usize,
+}
+
+impl Fifos {
+    fn new() -> Self {
+        Self {
+            edges: [[EMPTY; 2]; 16],
+            edge_offset: 0,
+            vertices: [EMPTY; 16],
+            vertex_offset: 0,
+        }
+    }
+
+    /// Edge slot (age << 2 | rotation) holding one of the triangle's edges
+    fn find_edge(&self, a: u32, b: u32, c: u32) -> Option<usize> {
+        (0..16).find_map(|i| {
+            let [e0, e1] = self.edges[(self.edge_offset.wrapping_sub(1 + i)) & 15];
+            if e0 == a && e1 == b {
+                Some(i << 2)
+            } else if e0 == b && e1 == c {
+                Some((i << 2) | 1)
+            } else if e0 == c && e1 == a {
+                Some((i << 2) | 2)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Age of a vertex in the FIFO (0 = most recent)
+    fn find_vertex(&self, v: u32) -> Option<u32> {
+        (0..16u32)
+            .find(|&i| self.vertices[self.vertex_offset.wrapping_sub(1 + i as usize) & 15] == v)
+    }
+
+    fn push_vertex(&mut self, v: u32, advance: bool) {
+        self.vertices[self.vertex_offset] = v;
+        self.vertex_offset = (self.vertex_offset + advance as usize) & 15;
+    }
+
+    fn push_edge(&mut self, a: u32, b: u32) {
+        self.edges[self.edge_offset] = [a, b];
+        self.edge_offset = (self.edge_offset + 1) & 15;
+    }
+}
+
+/// Encode a triangle list; decodable with meshoptimizer's `decodeIndexBuffer`
+pub fn encode_index_buffer(indices: &[u32]) -> Vec<u8> {
+    let triangle_count = indices.len() / 3;
+    let mut codes = Vec::with_capacity(1 + triangle_count);
+    let mut data = Vec::with_capacity(triangle_count);
+    codes.push(INDEX_HEADER | INDEX_VERSION);
+
+    let mut fifos = Fifos::new();
+    let mut next = 0u32;
+    let mut last = 0u32;
+
+    for tri in indices.chunks_exact(3) {
+        if let Some(fer) = fifos
+            .find_edge(tri[0], tri[1], tri[2])
+            .filter(|fer| fer >> 2 < 15)
+        {
+            // Triangle shares an edge with a recent one: code the edge age and
+            // the third vertex
+            let [a, b, c] = TRIANGLE_ORDER[fer & 3].map(|k| tri[k]);
+            let fe = (fer >> 2) as u32;
+
+            let mut fec = match fifos.find_vertex(c) {
+                Some(fc) if (1..FEC_MAX).contains(&fc) => fc,
+                _ if c == next => {
+                    next += 1;
+                    0
+                }
+                _ => 15,
+            };
+            if fec == 15 && c.wrapping_add(1) == last {
+                fec = 13;
+                last = c;
+            }
+            if fec == 15 && c == last.wrapping_add(1) {
+                fec = 14;
+                last = c;
+            }
+
+            codes.push(((fe << 4) | fec) as u8);
+            if fec == 15 {
+                encode_index(&mut data, c, last);
+                last = c;
+            }
+
+            fifos.push_vertex(c, fec == 0 || fec >= FEC_MAX);
+            fifos.push_edge(c, b);
+            fifos.push_edge(a, c);
+        } else {
+            // Rotate so the first vertex is the next new one where possible
+            let rotation = if tri[1] == next {
+                1
+            } else if tri[2] == next {
+                2
+            } else {
+                0
+            };
+            let [a, b, c] = TRIANGLE_ORDER[rotation].map(|k| tri[k]);
+
+            // A restarted 0/1/2 sequence (e.g. concatenated meshes) resets `next`
+            let reset = a == 0 && b == 1 && c == 2 && next > 0;
+            if reset {
+                next = 0;
+                fifos.vertices = [EMPTY; 16];
+            }
+
+            let fb = fifos.find_vertex(b);
+            let fc = fifos.find_vertex(c);
+            let mut fifo_or_next = |v: u32, age: Option<u32>| match age {
+                Some(age) if age < 14 => age + 1,
+                _ if v == next => {
+                    next += 1;
+                    0
+                }
+                _ => 15,
+            };
+            let fea = fifo_or_next(a, None);
+            let feb = fifo_or_next(b, fb);
+            let fec = fifo_or_next(c, fc);
+
+            let codeaux = ((feb << 4) | fec) as u8;
+            match CODEAUX_TABLE.iter().position(|&entry| entry == codeaux) {
+                Some(tc) if fea == 0 && tc < 14 && !reset => codes.push(0xf0 | tc as u8),
+                _ => {
+                    codes.push(0xf0 | 14 | fea as u8);
+                    data.push(codeaux);
+                }
+            }
+
+            for (v, fe) in [(a, fea), (b, feb), (c, fec)] {
+                if fe == 15 {
+                    encode_index(&mut data, v, last);
+                    last = v;
+                }
+            }
+
+            fifos.push_vertex(a, true);
+            fifos.push_vertex(b, feb == 0 || feb == 15);
+            fifos.push_vertex(c, fec == 0 || fec == 15);
+            fifos.push_edge(b, a);
+            fifos.push_edge(c, b);
+            fifos.push_edge(a, c);
+        }
+    }
+
+    codes.extend_from_slice(&data);
+    codes.extend_from_slice(&CODEAUX_TABLE);
+    codes
+}
+
+/// Zigzag varint of the delta to the previous explicit index
+fn encode_index(data: &mut Vec<u8>, index: u32, last: u32) {
+    let d = index.wrapping_sub(last);
+    let mut v = (d << 1) ^ ((d as i32 >> 31) as u32);
+    loop {
+        let byte = (v & 127) as u8;
+        v >>= 7;
+        if v == 0 {
+            data.push(byte);
+            break;
+        }
+        data.push(byte | 128);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference decoder following meshoptimizer's `decodeIndexBuffer`
+    fn decode(buffer: &[u8], index_count: usize) -> Vec<u32> {
+        assert_eq!(buffer[0], INDEX_HEADER | INDEX_VERSION);
+        let table_start = buffer.len() - 16;
+        let table = &buffer[table_start..];
+        let mut code = 1;
+        let mut data = 1 + index_count / 3;
+        let read_index = |data: &mut usize, last: u32| {
+            let (mut v, mut shift) = (0u32, 0);
+            loop {
+                let byte = buffer[*data];
+                *data += 1;
+                v |= ((byte & 127) as u32) << shift;
+                shift += 7;
+                if byte < 128 {
+                    break;
+                }
+            }
+            last.wrapping_add((v >> 1) ^ (v & 1).wrapping_neg())
+        };
+
+        let mut fifos = Fifos::new();
+        let (mut next, mut last) = (0u32, 0u32);
+        let mut out = Vec::with_capacity(index_count);
+        for _ in 0..index_count / 3 {
+            let codetri = buffer[code];
+            code += 1;
+            let fifo = |fifos: &Fifos, age: u32| {
+                fifos.vertices[fifos.vertex_offset.wrapping_sub(age as usize) & 15]
+            };
+            if codetri < 0xf0 {
+                let [a, b] =
+                    fifos.edges[fifos.edge_offset.wrapping_sub(1 + (codetri >> 4) as usize) & 15];
+                let fec = (codetri & 15) as u32;
+                let c = if fec == 0 {
+                    next += 1;
+                    next - 1
+                } else if fec < FEC_MAX {
+                    fifo(&fifos, fec + 1)
+                } else {
+                    last = if fec == 15 {
+                        read_index(&mut data, last)
+                    } else if fec == 13 {
+                        last - 1
+                    } else {
+                        last + 1
+                    };
+                    last
+                };
+                out.extend([a, b, c]);
+                fifos.push_vertex(c, fec == 0 || fec >= FEC_MAX);
+                fifos.push_edge(c, b);
+                fifos.push_edge(a, c);
+            } else {
+                let (fea, codeaux) = if codetri < 0xfe {
+                    (0, table[(codetri & 15) as usize])
+                } else {
+                    data += 1;
+                    (if codetri == 0xfe { 0 } else { 15 }, buffer[data - 1])
+                };
+                if codetri >= 0xfe && codeaux == 0 {
+                    next = 0;
+                }
+                let (feb, fec) = ((codeaux >> 4) as u32, (codeaux & 15) as u32);
+                let mut vertex = |fe: u32| match fe {
+                    0 => {
+                        next += 1;
+                        next - 1
+                    }
+                    15 => 0,
+                    age => fifo(&fifos, age),
+                };
+                let mut a = vertex(fea);
+                let mut b = vertex(feb);
+                let mut c = vertex(fec);
+                for (v, fe) in [(&mut a, fea), (&mut b, feb), (&mut c, fec)] {
+                    if fe == 15 {
+                        last = read_index(&mut data, last);
+                        *v = last;
+                    }
+                }
+                out.extend([a, b, c]);
+                fifos.push_vertex(a, true);
+                fifos.push_vertex(b, feb == 0 || feb == 15);
+                fifos.push_vertex(c, fec == 0 || fec == 15);
+                fifos.push_edge(b, a);
+                fifos.push_edge(c, b);
+                fifos.push_edge(a, c);
+            }
+        }
+        assert_eq!(data, table_start);
+        out
+    }
+
+    /// Compare triangles up to rotation
+    fn canonical(indices: &[u32]) -> Vec<[u32; 3]> {
+        indices
+            .chunks_exact(3)
+            .map(|t| {
+                let r = (0..3).min_by_key(|&r| t[r]).unwrap();
+                [t[r], t[(r + 1) % 3], t[(r + 2) % 3]]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_index_codec_roundtrip() {
+        // Strip-like grid, a restarted second mesh and scattered far indices
+        let mut indices = Vec::new();
+        for row in 0..6u32 {
+            for col in 0..20u32 {
+                let i = row * 21 + col;
+                indices.extend([i, i + 1, i + 21, i + 1, i + 22, i + 21]);
+            }
+        }
+        indices.extend([0, 1, 2, 2, 1, 3]);
+        indices.extend([900, 5, 70_000, 3, 2, 1, 40, 41, 39]);
+
+        let encoded = encode_index_buffer(&indices);
+        assert!(encoded.len() < indices.len() * 4 / 3);
+        assert_eq!(
+            canonical(&decode(&encoded, indices.len())),
+            canonical(&indices)
+        );
+    }
+}
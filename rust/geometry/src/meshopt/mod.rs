@@ -0,0 +1,209 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! meshoptimizer-compatible mesh compression
+//!
+//! An optional pass for shrinking mesh payloads (WASM → GPU transfer, cached
+//! or parquet-serialized geometry): cache/fetch reordering, attribute
+//! quantization, then the meshoptimizer vertex and index codecs. The buffers
+//! decode with the stock `meshopt_decoder` in JS:
+//!
+//! ```js
+//! MeshoptDecoder.decodeVertexBuffer(positions, m.vertexCount, m.positionStride, m.positions);
+//! MeshoptDecoder.decodeIndexBuffer(indices, m.indexCount, 4, m.indices);
+//! ```
+
+pub mod index_codec;
+pub mod optimize;
+pub mod vertex_codec;
+
+pub use index_codec::encode_index_buffer;
+pub use optimize::{optimize_vertex_cache, optimize_vertex_fetch};
+pub use vertex_codec::encode_vertex_buffer;
+
+use crate::mesh::Mesh;
+use crate::quantize::{quantize_normal, PositionQuantization};
+
+/// Compression pass settings
+#[derive(Debug, Clone, Copy)]
+pub struct CompressionOptions {
+    /// Reorder triangles and vertices before encoding
+    pub optimize: bool,
+    /// Store positions as `u16` and normals as `i8` instead of `f32`
+    pub quantize: bool,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        Self {
+            optimize: true,
+            quantize: true,
+        }
+    }
+}
+
+impl CompressionOptions {
+    /// Enable or disable cache/fetch reordering
+    pub fn with_optimize(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
+    /// Enable or disable attribute quantization
+    pub fn with_quantization(mut self, quantize: bool) -> Self {
+        self.quantize = quantize;
+        self
+    }
+}
+
+/// A mesh encoded for meshoptimizer decoding
+#[derive(Debug, Clone)]
+pub struct CompressedMesh {
+    pub vertex_count: usize,
+    pub index_count: usize,
+    /// Encoded vertex buffer: `u16 × 4` (normalized, w = 0) when quantized,
+    /// `f32 × 3` otherwise
+    pub positions: Vec<u8>,
+    pub position_stride: usize,
+    /// Encoded vertex buffer: `i8 × 4` (normalized, w = 0) when quantized,
+    /// `f32 × 3` otherwise; empty when the mesh has no normals
+    pub normals: Vec<u8>,
+    pub normal_stride: usize,
+    /// Encoded `u32` triangle list
+    pub indices: Vec<u8>,
+    /// Dequantization for positions: `offset + q / 65535 * scale`
+    pub position_offset: [f32; 3],
+    pub position_scale: f32,
+}
+
+impl CompressedMesh {
+    /// Total encoded size in bytes
+    pub fn byte_len(&self) -> usize {
+        self.positions.len() + self.normals.len() + self.indices.len()
+    }
+}
+
+impl Mesh {
+    /// Encode the mesh for meshoptimizer decoding.
+    ///
+    /// Triangles referencing missing vertices are dropped, and with
+    /// `optimize` so are unreferenced vertices, so the decoded vertex order
+    /// differs from `self`.
+    pub fn compress(&self, options: &CompressionOptions) -> CompressedMesh {
+        let vertex_count = self.vertex_count();
+        let mut indices: Vec<u32> = self
+            .indices
+            .chunks_exact(3)
+            .filter(|tri| tri.iter().all(|&i| (i as usize) < vertex_count))
+            .flatten()
+            .copied()
+            .collect();
+
+        let order: Vec<u32> = if options.optimize {
+            indices = optimize_vertex_cache(&indices, vertex_count);
+            optimize_vertex_fetch(&mut indices, vertex_count)
+        } else {
+            (0..vertex_count as u32).collect()
+        };
+        let has_normals = self.normals.len() == self.positions.len();
+
+        let (min, max) =
+            order
+                .iter()
+                .fold(([f32::MAX; 3], [f32::MIN; 3]), |(mut min, mut max), &v| {
+                    for axis in 0..3 {
+                        let p = self.positions[v as usize * 3 + axis];
+                        min[axis] = min[axis].min(p);
+                        max[axis] = max[axis].max(p);
+                    }
+                    (min, max)
+                });
+        let quantization = if options.quantize && !order.is_empty() {
+            PositionQuantization::from_bounds(min, max)
+        } else {
+            PositionQuantization::default()
+        };
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        for &v in &order {
+            let v = v as usize * 3;
+            let p = &self.positions[v..v + 3];
+            let n = self.normals.get(v..v + 3).filter(|_| has_normals);
+            if options.quantize {
+                positions.extend(
+                    quantization
+                        .quantize(p)
+                        .iter()
+                        .flat_map(|q| q.to_le_bytes()),
+                );
+                if let Some(n) = n {
+                    normals.extend(quantize_normal(n).map(|c| c as u8));
+                }
+            } else {
+                positions.extend(p.iter().flat_map(|c| c.to_le_bytes()));
+                if let Some(n) = n {
+                    normals.extend(n.iter().flat_map(|c| c.to_le_bytes()));
+                }
+            }
+        }
+
+        let (position_stride, normal_stride) = if options.quantize { (8, 4) } else { (12, 12) };
+        CompressedMesh {
+            vertex_count: order.len(),
+            index_count: indices.len(),
+            positions: encode_vertex_buffer(&positions, position_stride),
+            position_stride,
+            normals: if has_normals {
+                encode_vertex_buffer(&normals, normal_stride)
+            } else {
+                Vec::new()
+            },
+            normal_stride,
+            indices: encode_index_buffer(&indices),
+            position_offset: quantization.offset,
+            position_scale: quantization.scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Point3, Vector3};
+
+    #[test]
+    fn test_compress_shrinks_grid() {
+        // 40 × 40 quad terrain patch, 8 m across
+        let mut mesh = Mesh::new();
+        for y in 0..=40 {
+            for x in 0..=40 {
+                let z = ((x * y) % 7) as f64 * 0.01;
+                mesh.add_vertex(
+                    Point3::new(x as f64 * 0.2, y as f64 * 0.2, z),
+                    Vector3::new(0.0, 0.0, 1.0),
+                );
+            }
+        }
+        for y in 0..40 {
+            for x in 0..40 {
+                let i = y * 41 + x;
+                mesh.add_triangle(i, i + 1, i + 42);
+                mesh.add_triangle(i, i + 42, i + 41);
+            }
+        }
+        let raw = (mesh.positions.len() + mesh.normals.len() + mesh.indices.len()) * 4;
+
+        let compressed = mesh.compress(&CompressionOptions::default());
+        assert_eq!(compressed.vertex_count, mesh.vertex_count());
+        assert_eq!(compressed.index_count, mesh.indices.len());
+        assert!((compressed.position_scale - 8.0).abs() < 1e-5);
+        assert!(compressed.byte_len() * 5 < raw);
+
+        let plain = mesh.compress(&CompressionOptions::default().with_quantization(false));
+        assert_eq!(plain.position_stride, 12);
+        assert!(compressed.byte_len() < plain.byte_len());
+        assert!(plain.byte_len() < raw);
+    }
+}
@@ -0,0 +1,154 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Triangle and vertex reordering
+//!
+//! Cache-ordering triangles (Forsyth's linear-speed algorithm) puts
+//! neighbouring triangles next to each other, which is what both the GPU
+//! post-transform cache and the index codec's FIFOs reward. Fetch-ordering
+//! vertices afterwards makes indices mostly "next vertex" and keeps
+//! neighbouring vertices close, which the vertex codec rewards.
+
+/// Simulated post-transform cache size
+const CACHE_SIZE: usize = 16;
+const LAST_TRIANGLE_SCORE: f32 = 0.75;
+const CACHE_DECAY_POWER: f32 = 1.5;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+fn vertex_score(cache_position: Option<usize>, live_triangles: u32) -> f32 {
+    if live_triangles == 0 {
+        return -1.0;
+    }
+    let cache_score = match cache_position {
+        // The three vertices of the last triangle get a fixed score so the
+        // next triangle doesn't simply reuse them
+        Some(position) if position < 3 => LAST_TRIANGLE_SCORE,
+        Some(position) => {
+            let scale = 1.0 / (CACHE_SIZE - 3) as f32;
+            (1.0 - (position - 3) as f32 * scale).powf(CACHE_DECAY_POWER)
+        }
+        None => 0.0,
+    };
+    // Prefer vertices with few remaining triangles to finish them off
+    cache_score + VALENCE_BOOST_SCALE * (live_triangles as f32).powf(-VALENCE_BOOST_POWER)
+}
+
+/// Reorder triangles for vertex cache locality
+pub fn optimize_vertex_cache(indices: &[u32], vertex_count: usize) -> Vec<u32> {
+    let triangle_count = indices.len() / 3;
+    if triangle_count == 0 || indices.iter().any(|&i| i as usize >= vertex_count) {
+        return indices[..triangle_count * 3].to_vec();
+    }
+
+    // Vertex → triangle adjacency in CSR form
+    let mut live = vec![0u32; vertex_count];
+    for &i in &indices[..triangle_count * 3] {
+        live[i as usize] += 1;
+    }
+    let mut offsets = Vec::with_capacity(vertex_count + 1);
+    offsets.push(0usize);
+    for &count in &live {
+        offsets.push(offsets.last().copied().unwrap_or(0) + count as usize);
+    }
+    let mut adjacency = vec![0u32; offsets[vertex_count]];
+    let mut fill = offsets[..vertex_count].to_vec();
+    for (t, tri) in indices.chunks_exact(3).enumerate() {
+        for &v in tri {
+            adjacency[fill[v as usize]] = t as u32;
+            fill[v as usize] += 1;
+        }
+    }
+
+    let mut cache_position: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut score: Vec<f32> = live.iter().map(|&l| vertex_score(None, l)).collect();
+    let mut triangle_score: Vec<f32> = indices
+        .chunks_exact(3)
+        .map(|tri| tri.iter().map(|&v| score[v as usize]).sum())
+        .collect();
+    let mut emitted = vec![false; triangle_count];
+
+    let mut cache: Vec<u32> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut result = Vec::with_capacity(triangle_count * 3);
+    let mut cursor = 0usize;
+    let mut best = Some(0usize);
+
+    while result.len() < triangle_count * 3 {
+        // Fall back to the first remaining triangle when the cache has no
+        // live neighbours left
+        let triangle = match best {
+            Some(t) => t,
+            None => {
+                while emitted[cursor] {
+                    cursor += 1;
+                }
+                cursor
+            }
+        };
+        emitted[triangle] = true;
+        let tri = &indices[triangle * 3..triangle * 3 + 3];
+        result.extend_from_slice(tri);
+
+        // Remove the emitted triangle from its vertices' live lists
+        for &v in tri {
+            let v = v as usize;
+            let list = &mut adjacency[offsets[v]..offsets[v] + live[v] as usize];
+            if let Some(k) = list.iter().position(|&t| t as usize == triangle) {
+                list.swap(k, live[v] as usize - 1);
+            }
+            live[v] -= 1;
+        }
+
+        // Move the triangle's vertices to the front of the cache
+        let mut new_cache: Vec<u32> = tri.to_vec();
+        new_cache.extend(cache.iter().copied().filter(|v| !tri.contains(v)));
+        for &v in &cache {
+            cache_position[v as usize] = None;
+        }
+        for (position, &v) in new_cache.iter().enumerate() {
+            cache_position[v as usize] = (position < CACHE_SIZE).then_some(position);
+        }
+
+        // Rescore touched vertices and their live triangles, picking the best
+        best = None;
+        let mut best_score = f32::MIN;
+        for &v in &new_cache {
+            let v = v as usize;
+            let updated = vertex_score(cache_position[v], live[v]);
+            let delta = updated - score[v];
+            score[v] = updated;
+            for &t in &adjacency[offsets[v]..offsets[v] + live[v] as usize] {
+                let t = t as usize;
+                triangle_score[t] += delta;
+                if triangle_score[t] > best_score {
+                    best_score = triangle_score[t];
+                    best = Some(t);
+                }
+            }
+        }
+
+        new_cache.truncate(CACHE_SIZE);
+        cache = new_cache;
+    }
+
+    result
+}
+
+/// Renumber vertices in order of first use. Rewrites `indices` in place and
+/// returns the old index of every new vertex; unreferenced vertices are dropped.
+pub fn optimize_vertex_fetch(indices: &mut [u32], vertex_count: usize) -> Vec<u32> {
+    let mut remap = vec![u32::MAX; vertex_count];
+    let mut order = Vec::with_capacity(vertex_count);
+    for index in indices.iter_mut() {
+        let Some(slot) = remap.get_mut(*index as usize) else {
+            continue;
+        };
+        if *slot == u32::MAX {
+            *slot = order.len() as u32;
+            order.push(*index);
+        }
+        *index = *slot;
+    }
+    order
+}
@@ -0,0 +1,199 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Vertex buffer codec (meshoptimizer `encodeVertexBuffer`, version 0)
+//!
+//! Vertices are processed in blocks of up to 256. Within a block every byte
+//! lane of the vertex is delta-coded against the previous vertex, zigzagged,
+//! and packed in groups of 16 at 0, 2, 4 or 8 bits per value, whichever is
+//! smallest. Works best on quantized, fetch-ordered vertices where
+//! neighbouring vertices differ in their low bytes only.
+
+const VERTEX_HEADER: u8 = 0xa0;
+const BYTE_GROUP_SIZE: usize = 16;
+const VERTEX_BLOCK_MAX_SIZE: usize = 256;
+const VERTEX_BLOCK_SIZE_BYTES: usize = 8192;
+/// The first vertex is stored at the end, padded to this size
+const TAIL_MAX_SIZE: usize = 32;
+
+/// Bits per value for each 2-bit group header code
+const GROUP_BITS: [usize; 4] = [0, 2, 4, 8];
+
+/// Encode `vertex_count` vertices of `vertex_size` bytes each; decodable with
+/// meshoptimizer's `decodeVertexBuffer`. `vertex_size` must be a multiple of 4
+/// and at most 256.
+pub fn encode_vertex_buffer(vertices: &[u8], vertex_size: usize) -> Vec<u8> {
+    debug_assert!(vertex_size > 0 && vertex_size <= 256 && vertex_size & 3 == 0);
+    let vertex_count = vertices.len() / vertex_size;
+    let mut out = Vec::with_capacity(vertices.len() / 2 + TAIL_MAX_SIZE + 1);
+    out.push(VERTEX_HEADER);
+
+    let first_vertex = vertices.get(..vertex_size).unwrap_or_default().to_vec();
+    let mut last_vertex = first_vertex.clone();
+    let block_size = block_size(vertex_size);
+
+    for block in vertices[..vertex_count * vertex_size].chunks(block_size * vertex_size) {
+        encode_block(&mut out, block, vertex_size, &mut last_vertex);
+    }
+
+    let tail = vertex_size.max(TAIL_MAX_SIZE);
+    out.resize(out.len() + tail - first_vertex.len(), 0);
+    out.extend_from_slice(&first_vertex);
+    out
+}
+
+/// Vertices per block, keeping a block's bytes within the decoder's scratch buffer
+fn block_size(vertex_size: usize) -> usize {
+    ((VERTEX_BLOCK_SIZE_BYTES / vertex_size) & !(BYTE_GROUP_SIZE - 1)).min(VERTEX_BLOCK_MAX_SIZE)
+}
+
+fn encode_block(out: &mut Vec<u8>, block: &[u8], vertex_size: usize, last_vertex: &mut [u8]) {
+    let count = block.len() / vertex_size;
+    let padded = count.next_multiple_of(BYTE_GROUP_SIZE);
+    let mut lane = vec![0u8; padded];
+
+    for k in 0..vertex_size {
+        let mut previous = last_vertex[k];
+        for (i, vertex) in block.chunks_exact(vertex_size).enumerate() {
+            lane[i] = zigzag8(vertex[k].wrapping_sub(previous));
+            previous = vertex[k];
+        }
+        encode_bytes(out, &lane);
+    }
+
+    last_vertex.copy_from_slice(&block[(count - 1) * vertex_size..]);
+}
+
+#[inline]
+fn zigzag8(v: u8) -> u8 {
+    ((v as i8 >> 7) as u8) ^ (v << 1)
+}
+
+/// Header of 2-bit codes (4 groups per byte), then each group's payload
+fn encode_bytes(out: &mut Vec<u8>, lane: &[u8]) {
+    let group_count = lane.len() / BYTE_GROUP_SIZE;
+    let header_start = out.len();
+    out.resize(header_start + group_count.div_ceil(4), 0);
+
+    for (g, group) in lane.chunks_exact(BYTE_GROUP_SIZE).enumerate() {
+        let code = (0..GROUP_BITS.len())
+            .min_by_key(|&code| group_size(group, GROUP_BITS[code]))
+            .unwrap_or(3);
+        out[header_start + g / 4] |= (code as u8) << ((g % 4) * 2);
+        encode_group(out, group, GROUP_BITS[code]);
+    }
+}
+
+fn group_size(group: &[u8], bits: usize) -> usize {
+    match bits {
+        0 if group.iter().all(|&b| b == 0) => 0,
+        0 => usize::MAX,
+        8 => BYTE_GROUP_SIZE,
+        _ => {
+            let sentinel = (1u8 << bits) - 1;
+            BYTE_GROUP_SIZE * bits / 8 + group.iter().filter(|&&b| b >= sentinel).count()
+        }
+    }
+}
+
+/// Values packed MSB first; values that don't fit are written as the
+/// all-ones sentinel and appended as full bytes after the packed part
+fn encode_group(out: &mut Vec<u8>, group: &[u8], bits: usize) {
+    match bits {
+        0 => {}
+        8 => out.extend_from_slice(group),
+        _ => {
+            let sentinel = (1u8 << bits) - 1;
+            for chunk in group.chunks_exact(8 / bits) {
+                let byte = chunk
+                    .iter()
+                    .fold(0u8, |byte, &v| (byte << bits) | v.min(sentinel));
+                out.push(byte);
+            }
+            out.extend(group.iter().filter(|&&b| b >= sentinel));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference decoder following meshoptimizer's `decodeVertexBuffer`
+    fn decode(buffer: &[u8], vertex_count: usize, vertex_size: usize) -> Vec<u8> {
+        assert_eq!(buffer[0], VERTEX_HEADER);
+        let tail = vertex_size.max(TAIL_MAX_SIZE);
+        let mut last_vertex = buffer[buffer.len() - vertex_size..].to_vec();
+        let mut data = 1;
+        let mut out = vec![0u8; vertex_count * vertex_size];
+        let block = block_size(vertex_size);
+
+        for start in (0..vertex_count).step_by(block) {
+            let count = block.min(vertex_count - start);
+            let padded = count.next_multiple_of(BYTE_GROUP_SIZE);
+            for k in 0..vertex_size {
+                let groups = padded / BYTE_GROUP_SIZE;
+                let header = data;
+                data += groups.div_ceil(4);
+                let mut lane = Vec::with_capacity(padded);
+                for g in 0..groups {
+                    let bits = GROUP_BITS[((buffer[header + g / 4] >> ((g % 4) * 2)) & 3) as usize];
+                    match bits {
+                        0 => lane.extend([0; BYTE_GROUP_SIZE]),
+                        8 => {
+                            lane.extend_from_slice(&buffer[data..data + BYTE_GROUP_SIZE]);
+                            data += BYTE_GROUP_SIZE;
+                        }
+                        _ => {
+                            let sentinel = (1u8 << bits) - 1;
+                            let mut escape = data + BYTE_GROUP_SIZE * bits / 8;
+                            for byte in &buffer[data..data + BYTE_GROUP_SIZE * bits / 8] {
+                                for j in (0..8 / bits).rev() {
+                                    let v = (byte >> (j * bits)) & sentinel;
+                                    if v == sentinel {
+                                        lane.push(buffer[escape]);
+                                        escape += 1;
+                                    } else {
+                                        lane.push(v);
+                                    }
+                                }
+                            }
+                            data = escape;
+                        }
+                    }
+                }
+                for i in 0..count {
+                    let v = lane[i];
+                    let delta = (v >> 1) ^ (v & 1).wrapping_neg();
+                    last_vertex[k] = last_vertex[k].wrapping_add(delta);
+                    out[(start + i) * vertex_size + k] = last_vertex[k];
+                }
+            }
+        }
+        assert_eq!(buffer.len() - data, tail);
+        out
+    }
+
+    #[test]
+    fn test_vertex_codec_roundtrip() {
+        // 300 vertices (two blocks) of 8 bytes: a slowly varying u16 lane
+        // pattern plus an occasional large jump
+        let vertices: Vec<u8> = (0..300u32)
+            .flat_map(|i| {
+                let x = (i * 37) as u16;
+                let y = if i % 50 == 0 { 60_000 } else { (i / 3) as u16 };
+                let z = 1000u16;
+                [x, y, z, 0].into_iter().flat_map(u16::to_le_bytes)
+            })
+            .collect();
+
+        let encoded = encode_vertex_buffer(&vertices, 8);
+        assert!(encoded.len() < vertices.len());
+        assert_eq!(decode(&encoded, 300, 8), vertices);
+
+        let empty = encode_vertex_buffer(&[], 12);
+        assert_eq!(empty.len(), 1 + TAIL_MAX_SIZE);
+        assert!(decode(&empty, 0, 12).is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Offset curves (IfcOffsetCurve2D, IfcOffsetCurve3D)
+//!
+//! Offsets the tessellated basis curve: each segment moves by the distance
+//! along its offset direction and neighbouring segments are joined at their
+//! intersection (a miter). Very sharp corners, where the miter would shoot
+//! far past the corner, get a bevel instead. Loops that a large offset
+//! creates on the inside of tight bends are kept, like `SelfIntersect = .T.`.
+//!
+//! Directions follow ISO 10303-42: a 2D curve offsets to the left of its
+//! direction, a 3D curve along `tangent × ref_direction`.
+
+use nalgebra::{Point2, Point3, Vector3};
+
+/// Longest miter, as a multiple of the offset distance, before a corner is
+/// bevelled
+const MITER_LIMIT: f64 = 4.0;
+
+/// Offset a 2D polyline by `distance` to the left of its direction
+pub fn offset_polyline_2d(points: &[Point2<f64>], distance: f64) -> Vec<Point2<f64>> {
+    let lifted: Vec<Point3<f64>> = points.iter().map(|p| Point3::new(p.x, p.y, 0.0)).collect();
+    // tangent × -Z is the left normal of a curve in the XY plane
+    offset_polyline_3d(&lifted, distance, &-Vector3::z())
+        .into_iter()
+        .map(|p| Point2::new(p.x, p.y))
+        .collect()
+}
+
+/// Offset a 3D polyline by `distance` along `tangent × ref_direction`
+pub fn offset_polyline_3d(
+    points: &[Point3<f64>],
+    distance: f64,
+    ref_direction: &Vector3<f64>,
+) -> Vec<Point3<f64>> {
+    let mut path: Vec<Point3<f64>> = Vec::with_capacity(points.len());
+    for point in points {
+        if path.last().is_none_or(|last| (point - last).norm() > 1e-9) {
+            path.push(*point);
+        }
+    }
+    if path.len() < 2 || distance == 0.0 {
+        return path;
+    }
+
+    // Offset direction per segment; segments parallel to the reference
+    // direction reuse their neighbour's
+    let mut normals: Vec<Option<Vector3<f64>>> = path
+        .windows(2)
+        .map(|segment| {
+            (segment[1] - segment[0])
+                .cross(ref_direction)
+                .try_normalize(1e-9)
+        })
+        .collect();
+    let fallback = normals.iter().flatten().next().copied();
+    let Some(mut previous) = fallback else {
+        return path;
+    };
+    for normal in &mut normals {
+        previous = *normal.get_or_insert(previous);
+    }
+    let normals: Vec<Vector3<f64>> = normals.into_iter().flatten().collect();
+
+    let closed = path.len() > 3 && (path[0] - path[path.len() - 1]).norm() < 1e-9;
+    let last = normals.len() - 1;
+    let mut offset = Vec::with_capacity(path.len() + 2);
+    for (i, point) in path.iter().enumerate() {
+        let (before, after) = match (i, closed) {
+            (0, true) => (normals[last], normals[0]),
+            (0, false) => (normals[0], normals[0]),
+            (i, true) if i == path.len() - 1 => {
+                // Same corner as the first vertex
+                offset.push(offset[0]);
+                continue;
+            }
+            (i, false) if i == path.len() - 1 => (normals[last], normals[last]),
+            (i, _) => (normals[i - 1], normals[i]),
+        };
+        push_corner(&mut offset, point, &before, &after, distance);
+    }
+    offset
+}
+
+/// Offset one vertex between segments with offset directions `before` and
+/// `after`
+fn push_corner(
+    offset: &mut Vec<Point3<f64>>,
+    point: &Point3<f64>,
+    before: &Vector3<f64>,
+    after: &Vector3<f64>,
+    distance: f64,
+) {
+    if let Some(miter) = (before + after).try_normalize(1e-9) {
+        let cos = miter.dot(before);
+        if cos * MITER_LIMIT >= 1.0 {
+            offset.push(point + miter * (distance / cos));
+            return;
+        }
+    }
+    offset.push(point + before * distance);
+    offset.push(point + after * distance);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &Point2<f64>, x: f64, y: f64) -> bool {
+        (a.x - x).abs() < 1e-9 && (a.y - y).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_offset_open_and_closed() {
+        // L-shaped path offset to the left: the corner is mitered
+        let path = [
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(10.0, 5.0),
+        ];
+        let left = offset_polyline_2d(&path, 1.0);
+        assert_eq!(left.len(), 3);
+        assert!(close(&left[0], 0.0, 1.0));
+        assert!(close(&left[1], 9.0, 1.0));
+        assert!(close(&left[2], 9.0, 5.0));
+
+        // Counter-clockwise square grows with a negative (rightward) offset
+        let square = [
+            Point2::new(0.0, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(4.0, 4.0),
+            Point2::new(0.0, 4.0),
+            Point2::new(0.0, 0.0),
+        ];
+        let grown = offset_polyline_2d(&square, -0.5);
+        assert_eq!(grown.len(), 5);
+        assert!(close(&grown[0], -0.5, -0.5));
+        assert!(close(&grown[2], 4.5, 4.5));
+        assert_eq!(grown[0], grown[4]);
+
+        // A hairpin turn is bevelled instead of mitered to infinity
+        let hairpin = [
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(0.0, 0.1),
+        ];
+        let bevelled = offset_polyline_2d(&hairpin, 1.0);
+        assert_eq!(bevelled.len(), 4);
+        assert!(bevelled.iter().all(|p| p.x < 11.5));
+    }
+
+    #[test]
+    fn test_offset_3d_along_ref_direction() {
+        // Rail path rising along X, offset sideways with Z as reference:
+        // tangent × Z points to -Y
+        let path = [Point3::new(0.0, 0.0, 0.0), Point3::new(5.0, 0.0, 1.0)];
+        let offset = offset_polyline_3d(&path, 0.3, &Vector3::z());
+        assert!((offset[0] - Point3::new(0.0, -0.3, 0.0)).norm() < 1e-9);
+        assert!((offset[1] - Point3::new(5.0, -0.3, 1.0)).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,287 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Winding repair for boundary-represented geometry
+//!
+//! IfcFacetedBrep and surface models list faces in whatever orientation the
+//! exporter produced, and mixed windings show up as holes in renderers that
+//! cull back faces. [`repair_orientation`] makes each connected shell
+//! consistent and then points it outwards:
+//!
+//! 1. Faces are grown outwards from a seed across shared edges. Each new face
+//!    takes the orientation most of its already-oriented neighbours imply
+//!    (two faces agree when they run their shared edge in opposite
+//!    directions), so a single bad edge can't flip a whole region.
+//! 2. The signed volume of each shell then decides whether the shell as a
+//!    whole is inside-out. Shells nested inside another closed shell's box
+//!    are treated as voids and face inwards instead.
+
+use crate::mesh::Mesh;
+use nalgebra::{Point3, Vector3};
+use rustc_hash::{FxHashMap, FxHashSet};
+use smallvec::SmallVec;
+use std::collections::VecDeque;
+
+/// Vertices closer than this share a position when building adjacency
+const WELD_TOLERANCE: f64 = 1e-6;
+
+/// Shells with |volume| below this fraction of their box volume are too flat
+/// to have an inside (open sheets, single faces) and keep their orientation
+const MIN_VOLUME_FRACTION: f64 = 1e-6;
+
+/// Undirected edge (low, high position) -> faces using it, with whether
+/// they run it low-to-high
+type EdgeFaces = FxHashMap<(u32, u32), SmallVec<[(usize, bool); 2]>>;
+
+struct Shell {
+    faces: Vec<usize>,
+    closed: bool,
+    min: Point3<f64>,
+    max: Point3<f64>,
+    volume: f64,
+}
+
+/// Make triangle winding consistent per shell and outward-facing.
+///
+/// Flipped triangles get their normals negated; vertices shared between
+/// flipped and kept triangles are duplicated. Returns the number of
+/// triangles flipped.
+pub fn repair_orientation(mesh: &mut Mesh) -> usize {
+    mesh.validate_indices();
+    let (points, ids) = mesh.position_ids(WELD_TOLERANCE);
+    let faces: Vec<[u32; 3]> = mesh
+        .indices
+        .chunks_exact(3)
+        .map(|t| [ids[t[0] as usize], ids[t[1] as usize], ids[t[2] as usize]])
+        .collect();
+
+    let mut edges = EdgeFaces::default();
+    for (f, face) in faces.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (face[k], face[(k + 1) % 3]);
+            if a != b {
+                edges
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push((f, a < b));
+            }
+        }
+    }
+
+    let mut flipped: Vec<Option<bool>> = vec![None; faces.len()];
+    let mut shells = Vec::new();
+    for seed in 0..faces.len() {
+        if flipped[seed].is_some() {
+            continue;
+        }
+        shells.push(grow_shell(seed, &faces, &edges, &mut flipped));
+    }
+
+    // Shell volumes with the chosen orientation
+    for shell in &mut shells {
+        let (min, max) = shell.faces.iter().flat_map(|&f| faces[f]).fold(
+            (Point3::from([f64::MAX; 3]), Point3::from([f64::MIN; 3])),
+            |(lo, hi), p| (lo.inf(&points[p as usize]), hi.sup(&points[p as usize])),
+        );
+        let origin = nalgebra::center(&min, &max);
+        shell.volume = shell
+            .faces
+            .iter()
+            .map(|&f| {
+                let [a, b, c] = faces[f].map(|p| points[p as usize] - origin);
+                let v = a.dot(&b.cross(&c)) / 6.0;
+                if flipped[f] == Some(true) {
+                    -v
+                } else {
+                    v
+                }
+            })
+            .sum();
+        (shell.min, shell.max) = (min, max);
+    }
+
+    for (i, shell) in shells.iter().enumerate() {
+        let size: Vector3<f64> = shell.max - shell.min;
+        if shell.volume.abs() <= MIN_VOLUME_FRACTION * size.x * size.y * size.z {
+            continue;
+        }
+        let depth = shells
+            .iter()
+            .enumerate()
+            .filter(|(j, other)| {
+                *j != i
+                    && other.closed
+                    && (0..3).all(|axis| {
+                        other.min[axis] <= shell.min[axis] && shell.max[axis] <= other.max[axis]
+                    })
+                    && (other.max - other.min).norm() > size.norm()
+            })
+            .count();
+        let want_positive = depth % 2 == 0;
+        if (shell.volume > 0.0) != want_positive {
+            for &f in &shell.faces {
+                flipped[f] = flipped[f].map(|flip| !flip);
+            }
+        }
+    }
+
+    apply_flips(mesh, &flipped)
+}
+
+/// Orient the connected faces around `seed` by neighbour majority
+fn grow_shell(
+    seed: usize,
+    faces: &[[u32; 3]],
+    edges: &EdgeFaces,
+    flipped: &mut [Option<bool>],
+) -> Shell {
+    let mut shell = Shell {
+        faces: Vec::new(),
+        closed: true,
+        min: Point3::origin(),
+        max: Point3::origin(),
+        volume: 0.0,
+    };
+    let mut queue = VecDeque::from([seed]);
+    let mut queued = FxHashSet::from_iter([seed]);
+
+    while let Some(f) = queue.pop_front() {
+        let face = faces[f];
+        let mut votes = [0usize; 2];
+        for k in 0..3 {
+            let (a, b) = (face[k], face[(k + 1) % 3]);
+            let Some(users) = edges.get(&(a.min(b), a.max(b))) else {
+                continue;
+            };
+            if users.len() != 2 {
+                shell.closed = false;
+                if users.len() > 2 {
+                    // Non-manifold edge: no reliable vote across it
+                    continue;
+                }
+            }
+            let forward = a < b;
+            for &(g, g_forward) in users.iter().filter(|(g, _)| *g != f) {
+                match flipped[g] {
+                    // Agreeing faces run the edge in opposite directions
+                    Some(g_flip) => votes[usize::from(g_flip ^ (g_forward == forward))] += 1,
+                    None if users.len() == 2 && queued.insert(g) => queue.push_back(g),
+                    None => {}
+                }
+            }
+        }
+        flipped[f] = Some(votes[1] > votes[0]);
+        shell.faces.push(f);
+    }
+    shell
+}
+
+/// Swap winding of flipped triangles, duplicating vertices whose normal
+/// must point both ways
+fn apply_flips(mesh: &mut Mesh, flipped: &[Option<bool>]) -> usize {
+    let has_normals = mesh.normals.len() == mesh.positions.len();
+    let mut negated: FxHashMap<u32, u32> = FxHashMap::default();
+    let mut used_unflipped = vec![false; mesh.vertex_count()];
+    for (t, tri) in mesh.indices.chunks_exact(3).enumerate() {
+        if flipped[t] != Some(true) {
+            tri.iter().for_each(|&i| used_unflipped[i as usize] = true);
+        }
+    }
+
+    let mut count = 0;
+    let flipped_triangles = (0..flipped.len()).filter(|&t| flipped[t] == Some(true));
+    for t in flipped_triangles {
+        count += 1;
+        mesh.indices.swap(t * 3 + 1, t * 3 + 2);
+        if !has_normals {
+            continue;
+        }
+        for k in t * 3..t * 3 + 3 {
+            let i = mesh.indices[k];
+            let target = *negated.entry(i).or_insert_with(|| {
+                let base = i as usize * 3;
+                let normal = [0, 1, 2].map(|c| -mesh.normals[base + c]);
+                if used_unflipped[i as usize] {
+                    let position: [f32; 3] = std::array::from_fn(|c| mesh.positions[base + c]);
+                    mesh.positions.extend(position);
+                    mesh.normals.extend(normal);
+                    (mesh.positions.len() / 3 - 1) as u32
+                } else {
+                    mesh.normals[base..base + 3].copy_from_slice(&normal);
+                    i
+                }
+            });
+            mesh.indices[k] = target;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extrude_profile, Profile2D};
+    use nalgebra::Point2;
+
+    #[test]
+    fn test_repair_mixed_and_inside_out_shells() {
+        let square = |c: f64, r: f64| {
+            Profile2D::new(vec![
+                Point2::new(c - r, c - r),
+                Point2::new(c + r, c - r),
+                Point2::new(c + r, c + r),
+                Point2::new(c - r, c + r),
+            ])
+        };
+        // Triangle soup with normals following each triangle's winding, as
+        // the Brep processors emit it
+        let soup = |mesh: &Mesh, flip: &dyn Fn(usize) -> bool| {
+            let mut out = Mesh::new();
+            for (t, tri) in mesh.indices.chunks_exact(3).enumerate() {
+                let p = |i: u32| {
+                    let i = i as usize * 3;
+                    Point3::new(
+                        mesh.positions[i] as f64,
+                        mesh.positions[i + 1] as f64,
+                        mesh.positions[i + 2] as f64,
+                    )
+                };
+                let (a, mut b, mut c) = (p(tri[0]), p(tri[1]), p(tri[2]));
+                if flip(t) {
+                    std::mem::swap(&mut b, &mut c);
+                }
+                let normal = (b - a).cross(&(c - a)).normalize();
+                out.merge(&Mesh::from_triangle(&a, &b, &c, &normal));
+            }
+            out
+        };
+
+        // Box with a few faces flipped, and a second box entirely inside-out
+        let cube = extrude_profile(&square(0.0, 1.0), 2.0, None).unwrap();
+        let mut mesh = soup(&cube, &|t| [0, 3, 7].contains(&t));
+        let small = extrude_profile(&square(10.0, 0.5), 1.0, None).unwrap();
+        mesh.merge(&soup(&small, &|_| true));
+
+        let flips = repair_orientation(&mut mesh);
+        assert_eq!(flips, 3 + 12);
+        assert!((mesh.signed_volume() - (8.0 + 1.0)).abs() < 1e-4);
+        assert_eq!(repair_orientation(&mut mesh), 0);
+
+        // Normals follow the flipped winding
+        for tri in mesh.indices.chunks_exact(3) {
+            let p = |i: u32| {
+                let i = i as usize * 3;
+                Vector3::new(
+                    mesh.positions[i],
+                    mesh.positions[i + 1],
+                    mesh.positions[i + 2],
+                )
+            };
+            let face = (p(tri[1]) - p(tri[0])).cross(&(p(tri[2]) - p(tri[0])));
+            let n = tri[0] as usize * 3;
+            let normal = Vector3::new(mesh.normals[n], mesh.normals[n + 1], mesh.normals[n + 2]);
+            assert!(face.dot(&normal) > 0.0);
+        }
+    }
+}
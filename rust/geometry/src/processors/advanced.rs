@@ -7,7 +7,7 @@
 //! Handles IfcAdvancedBrep and IfcAdvancedBrepWithVoids.
 //! Delegates per-face processing to shared advanced_face module.
 
-use crate::{Error, Mesh, Result};
+use crate::{tessellation::TessellationConfig, Error, Mesh, Result};
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema, IfcType};
 
 use crate::router::GeometryProcessor;
@@ -16,11 +16,28 @@ use super::advanced_face::process_advanced_face;
 /// AdvancedBrep processor
 /// Handles IfcAdvancedBrep and IfcAdvancedBrepWithVoids - NURBS/B-spline surfaces
 /// Supports planar faces and B-spline surface tessellation
-pub struct AdvancedBrepProcessor;
+pub struct AdvancedBrepProcessor {
+    tessellation: TessellationConfig,
+    plane_angle_unit: Option<f64>,
+}
 
 impl AdvancedBrepProcessor {
     pub fn new() -> Self {
-        Self
+        Self::with_tessellation(TessellationConfig::default())
+    }
+
+    /// Create processor with custom curved-surface tessellation quality
+    pub fn with_tessellation(tessellation: TessellationConfig) -> Self {
+        Self {
+            tessellation,
+            plane_angle_unit: None,
+        }
+    }
+
+    /// Set the model's plane angle unit in radians, used for surface angles
+    pub fn with_plane_angle_unit(mut self, plane_angle_unit: Option<f64>) -> Self {
+        self.plane_angle_unit = plane_angle_unit;
+        self
     }
 }
 
@@ -60,7 +77,12 @@ impl GeometryProcessor for AdvancedBrepProcessor {
                 let face = decoder.decode_by_id(face_id)?;
 
                 // Delegate to shared advanced face processing
-                let (positions, indices) = process_advanced_face(&face, decoder)?;
+                let (positions, indices) = process_advanced_face(
+                    &face,
+                    decoder,
+                    &self.tessellation,
+                    self.plane_angle_unit,
+                )?;
 
                 if !positions.is_empty() {
                     // Merge into combined mesh
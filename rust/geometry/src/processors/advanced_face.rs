@@ -4,24 +4,33 @@
 
 //! Shared advanced face processing logic.
 //!
-//! Handles IfcAdvancedFace with B-spline, planar, and cylindrical surface types.
+//! Handles IfcAdvancedFace with B-spline and planar surface types, delegating
+//! elementary surfaces (cylinder, cone, sphere, torus) to `analytic_surface`.
 //! Used by both AdvancedBrepProcessor and ShellBasedSurfaceModelProcessor/FaceBasedSurfaceModelProcessor
 //! when shells contain IfcAdvancedFace entities (common in CATIA exports).
 
+use crate::bspline::{expand_knots, BSplineCurve};
+use crate::tessellation::TessellationConfig;
 use crate::triangulation::{calculate_polygon_normal, project_to_2d, triangulate_polygon};
 use crate::{Error, Point3, Result};
 use ifc_lite_core::{DecodedEntity, EntityDecoder};
 use nalgebra::Matrix4;
 
+use super::analytic_surface::{process_analytic_face, AnalyticSurface};
 use super::helpers::get_axis2_placement_transform_by_id;
 
 /// Process a single IfcAdvancedFace entity, dispatching to the appropriate
 /// surface handler based on FaceSurface type.
 ///
+/// `plane_angle_unit` is the model's angle unit in radians, `None` when the
+/// project declares none.
+///
 /// Returns (positions, indices) for the tessellated face.
 pub(super) fn process_advanced_face(
     face: &DecodedEntity,
     decoder: &mut EntityDecoder,
+    tessellation: &TessellationConfig,
+    plane_angle_unit: Option<f64>,
 ) -> Result<(Vec<f32>, Vec<u32>)> {
     // IfcAdvancedFace has:
     // 0: Bounds (list of FaceBound)
@@ -46,25 +55,22 @@ pub(super) fn process_advanced_face(
         .unwrap_or(true);
 
     let result = if surface_type == "IFCPLANE" {
-        process_planar_face(face, decoder)
+        process_planar_face(face, decoder, tessellation)
     } else if surface_type == "IFCBSPLINESURFACEWITHKNOTS" {
-        process_bspline_face(&surface, decoder, None)
+        process_bspline_face(&surface, decoder, None, tessellation)
     } else if surface_type == "IFCRATIONALBSPLINESURFACEWITHKNOTS" {
         let weights = parse_rational_weights(&surface);
-        process_bspline_face(&surface, decoder, weights.as_deref())
-    } else if surface_type == "IFCCYLINDRICALSURFACE" {
-        process_cylindrical_face(face, &surface, decoder)
+        process_bspline_face(&surface, decoder, weights.as_deref(), tessellation)
+    } else if let Some(analytic) = AnalyticSurface::from_entity(&surface, plane_angle_unit) {
+        process_analytic_face(face, &surface, analytic, same_sense, decoder, tessellation)
     } else if surface_type == "IFCSURFACEOFLINEAREXTRUSION"
         || surface_type == "IFCSURFACEOFREVOLUTION"
-        || surface_type == "IFCCONICALSURFACE"
-        || surface_type == "IFCSPHERICALSURFACE"
-        || surface_type == "IFCTOROIDALSURFACE"
     {
         // For these surface types, the edge loop boundary vertices already lie
         // on the surface. Extracting and triangulating them gives a reasonable
         // polygonal approximation. This covers IfcSurfaceOfLinearExtrusion
-        // (common in CATIA exports) and other analytic surface types.
-        process_planar_face(face, decoder)
+        // (common in CATIA exports).
+        process_planar_face(face, decoder, tessellation)
     } else {
         // Unsupported surface type - return empty geometry
         Ok((Vec::new(), Vec::new()))
@@ -306,17 +312,6 @@ fn parse_control_points(
     Ok(result)
 }
 
-/// Expand knot vector based on multiplicities
-fn expand_knots(knot_values: &[f64], multiplicities: &[i64]) -> Vec<f64> {
-    let mut expanded = Vec::new();
-    for (knot, &mult) in knot_values.iter().zip(multiplicities.iter()) {
-        for _ in 0..mult {
-            expanded.push(*knot);
-        }
-    }
-    expanded
-}
-
 /// Parse knot vectors from B-spline surface entity
 fn parse_knot_vectors(bspline: &DecodedEntity) -> Result<(Vec<f64>, Vec<f64>)> {
     // IFCBSPLINESURFACEWITHKNOTS attributes:
@@ -397,25 +392,6 @@ fn extract_vertex_coords(vertex: &DecodedEntity, decoder: &mut EntityDecoder) ->
     Some(Point3::new(x, y, z))
 }
 
-/// Evaluate a B-spline CURVE at parameter t (1D, not surface).
-fn evaluate_bspline_curve(
-    t: f64,
-    degree: usize,
-    control_points: &[Point3<f64>],
-    knots: &[f64],
-) -> Point3<f64> {
-    let mut result = Point3::new(0.0, 0.0, 0.0);
-    for (i, cp) in control_points.iter().enumerate() {
-        let basis = bspline_basis(i, degree, t, knots);
-        if basis.abs() > 1e-10 {
-            result.x += basis * cp.x;
-            result.y += basis * cp.y;
-            result.z += basis * cp.z;
-        }
-    }
-    result
-}
-
 /// Sample points along a B-spline curve edge.
 /// Returns the start vertex plus intermediate sample points.
 /// The end vertex is omitted (provided by the next edge's start in the loop).
@@ -424,87 +400,90 @@ fn sample_bspline_edge_curve(
     start: &Point3<f64>,
     curve_forward: bool,
     decoder: &mut EntityDecoder,
+    tessellation: &TessellationConfig,
 ) -> Vec<Point3<f64>> {
-    // Parse B-spline curve: degree(0), control_points(1), ..., knot_mults(6), knots(7)
-    let degree = curve.get_float(0).unwrap_or(3.0) as usize;
-
-    // Parse control points (attribute 1: LIST of IfcCartesianPoint)
-    let cp_list = match curve.get(1).and_then(|a| a.as_list()) {
-        Some(list) => list,
-        None => return vec![*start],
+    let mut samples = match BSplineCurve::from_entity(curve, decoder) {
+        Ok(bspline) => bspline.tessellate(tessellation),
+        Err(_) => return vec![*start],
     };
-    let control_points: Vec<Point3<f64>> = cp_list
-        .iter()
-        .filter_map(|ref_val| {
-            let id = ref_val.as_entity_ref()?;
-            let pt = decoder.decode_by_id(id).ok()?;
-            let coords = pt.get(0)?.as_list()?;
-            let x = coords.first()?.as_float().unwrap_or(0.0);
-            let y = coords.get(1).and_then(|v| v.as_float()).unwrap_or(0.0);
-            let z = coords.get(2).and_then(|v| v.as_float()).unwrap_or(0.0);
-            Some(Point3::new(x, y, z))
-        })
-        .collect();
-
-    if control_points.len() <= degree {
-        return vec![*start];
+    if !curve_forward {
+        samples.reverse();
     }
 
-    // Parse knot multiplicities (attribute 6) and knot values (attribute 7)
-    let mults: Vec<i64> = curve
-        .get(6)
-        .and_then(|a| a.as_list())
-        .map(|l| l.iter().filter_map(|v| v.as_int()).collect())
-        .unwrap_or_default();
-    let knot_values: Vec<f64> = curve
-        .get(7)
-        .and_then(|a| a.as_list())
-        .map(|l| l.iter().filter_map(|v| v.as_float()).collect())
-        .unwrap_or_default();
-
-    if mults.is_empty() || knot_values.is_empty() {
-        return vec![*start];
-    }
+    // The edge's vertices replace the curve's own end points
+    let interior = samples.len().saturating_sub(2);
+    let mut points = Vec::with_capacity(interior + 1);
+    points.push(*start);
+    points.extend(samples.into_iter().skip(1).take(interior));
+    points
+}
 
-    let knots = expand_knots(&knot_values, &mults);
-    let t_min = knots[degree];
-    let t_max = knots[knots.len() - degree - 1];
+/// Sample points along an IfcCircle edge from `start` towards `end`.
+///
+/// Returns the start vertex plus intermediate points, omitting the end vertex
+/// like [`sample_bspline_edge_curve`]. A missing or coincident end vertex is
+/// treated as a full circle (closed edges on cylinder and cone caps).
+fn sample_circle_edge_curve(
+    circle: &DecodedEntity,
+    start: &Point3<f64>,
+    end: Option<&Point3<f64>>,
+    curve_forward: bool,
+    decoder: &mut EntityDecoder,
+    tessellation: &TessellationConfig,
+) -> Vec<Point3<f64>> {
+    // IfcCircle: Position(0), Radius(1)
+    let radius = match circle.get_float(1) {
+        Some(r) if r > 0.0 => r,
+        _ => return vec![*start],
+    };
+    let transform = match circle.get(0).and_then(|a| a.as_entity_ref()) {
+        Some(id) => match get_axis2_placement_transform_by_id(id, decoder) {
+            Ok(t) => t,
+            Err(_) => return vec![*start],
+        },
+        None => Matrix4::identity(),
+    };
+    let inverse = match transform.try_inverse() {
+        Some(inv) => inv,
+        None => return vec![*start],
+    };
 
-    // Adaptive segment count based on control point density
-    let n_segments = (control_points.len() * 2).clamp(4, 16);
+    let local_start = inverse.transform_point(start);
+    let start_angle = local_start.y.atan2(local_start.x);
+    let two_pi = std::f64::consts::TAU;
+
+    // Sweep in curve parameter direction (counter-clockwise about the circle axis)
+    let ccw_sweep = match end {
+        Some(e) if (e - start).norm() > radius * 1e-6 => {
+            let local_end = inverse.transform_point(e);
+            let delta = (local_end.y.atan2(local_end.x) - start_angle).rem_euclid(two_pi);
+            if curve_forward {
+                delta
+            } else {
+                delta - two_pi
+            }
+        }
+        _ if curve_forward => two_pi,
+        _ => -two_pi,
+    };
 
-    let mut points = Vec::with_capacity(n_segments + 1);
-    // Add the start vertex first
+    let n_segments = tessellation.arc_segments(radius, ccw_sweep).max(2);
+    let mut points = Vec::with_capacity(n_segments);
     points.push(*start);
-
-    // Sample intermediate points (skip last = next edge's start vertex)
     for i in 1..n_segments {
-        let frac = i as f64 / n_segments as f64;
-        let t = if curve_forward {
-            t_min + (t_max - t_min) * frac
-        } else {
-            t_max - (t_max - t_min) * frac
-        };
-        let t_clamped = t.min(t_max - 1e-6).max(t_min);
-        let pt = evaluate_bspline_curve(t_clamped, degree, &control_points, &knots);
-        // Skip degenerate points (too close to previous)
-        if let Some(prev) = points.last() {
-            let dist_sq = (pt.x - prev.x).powi(2) + (pt.y - prev.y).powi(2) + (pt.z - prev.z).powi(2);
-            if dist_sq < 1e-12 {
-                continue;
-            }
-        }
-        points.push(pt);
+        let angle = start_angle + ccw_sweep * (i as f64 / n_segments as f64);
+        let local = Point3::new(radius * angle.cos(), radius * angle.sin(), 0.0);
+        points.push(transform.transform_point(&local));
     }
-
     points
 }
 
-/// Extract polygon points from an edge loop, sampling B-spline curve edges
-/// for intermediate points to preserve curvature.
-fn extract_edge_loop_points(
+/// Extract polygon points from an edge loop, sampling B-spline and circle
+/// edges for intermediate points to preserve curvature.
+pub(super) fn extract_edge_loop_points(
     loop_entity: &DecodedEntity,
     decoder: &mut EntityDecoder,
+    tessellation: &TessellationConfig,
 ) -> Vec<Point3<f64>> {
     let edges = match loop_entity.get(0).and_then(|a| a.as_list()) {
         Some(e) => e,
@@ -577,7 +556,7 @@ fn extract_edge_loop_points(
         // Walk direction is based on Orientation only (not SameSense):
         //   Orientation TRUE  → we encounter EdgeStart first
         //   Orientation FALSE → we encounter EdgeEnd first
-        let (walk_start, _walk_end) = if orientation {
+        let (walk_start, walk_end) = if orientation {
             (edge_start_pt, edge_end_pt)
         } else {
             (edge_end_pt, edge_start_pt)
@@ -593,11 +572,27 @@ fn extract_edge_loop_points(
             if geom_type == "IFCBSPLINECURVEWITHKNOTS" {
                 // Sample B-spline curve for intermediate points
                 let s = walk_start.unwrap_or(Point3::new(0.0, 0.0, 0.0));
-                let sampled = sample_bspline_edge_curve(&geom, &s, curve_forward, decoder);
+                let sampled =
+                    sample_bspline_edge_curve(&geom, &s, curve_forward, decoder, tessellation);
                 polygon_points.extend(sampled);
                 continue;
             }
-            // For IfcLine, IfcCircle, etc.: just use start vertex
+            if geom_type == "IFCCIRCLE" {
+                if let Some(s) = walk_start {
+                    let sampled =
+                        sample_circle_edge_curve(
+                            &geom,
+                            &s,
+                            walk_end.as_ref(),
+                            curve_forward,
+                            decoder,
+                            tessellation,
+                        );
+                    polygon_points.extend(sampled);
+                    continue;
+                }
+            }
+            // For IfcLine, IfcEllipse, etc.: just use start vertex
         }
 
         // Default: add start vertex only
@@ -615,6 +610,7 @@ fn extract_edge_loop_points(
 fn process_planar_face(
     face: &DecodedEntity,
     decoder: &mut EntityDecoder,
+    tessellation: &TessellationConfig,
 ) -> Result<(Vec<f32>, Vec<u32>)> {
     let bounds_attr = face
         .get(0)
@@ -643,7 +639,7 @@ fn process_planar_face(
             }
 
             // Extract polygon points with B-spline curve sampling
-            let polygon_points = extract_edge_loop_points(&loop_entity, decoder);
+            let polygon_points = extract_edge_loop_points(&loop_entity, decoder, tessellation);
 
             if polygon_points.len() >= 3 {
                 let base_idx = (positions.len() / 3) as u32;
@@ -686,6 +682,7 @@ fn process_bspline_face(
     bspline: &DecodedEntity,
     decoder: &mut EntityDecoder,
     weights: Option<&[Vec<f64>]>,
+    tessellation: &TessellationConfig,
 ) -> Result<(Vec<f32>, Vec<u32>)> {
     // Get degrees
     let u_degree = bspline.get_float(0).unwrap_or(3.0) as usize;
@@ -697,13 +694,22 @@ fn process_bspline_face(
     // Parse knot vectors
     let (u_knots, v_knots) = parse_knot_vectors(bspline)?;
 
-    // Determine tessellation resolution based on surface complexity
-    let u_segments = (control_points.len() * 3).clamp(8, 24);
-    let v_segments = if !control_points.is_empty() {
-        (control_points[0].len() * 3).clamp(4, 24)
-    } else {
-        4
-    };
+    // Resolution per direction from the most curved row/column of the control net
+    let u_segments = (0..control_points.first().map_or(0, |row| row.len()))
+        .map(|j| {
+            let column: Vec<Point3<f64>> = control_points
+                .iter()
+                .filter_map(|row| row.get(j).copied())
+                .collect();
+            tessellation.polygon_segments(&column)
+        })
+        .max()
+        .unwrap_or(1);
+    let v_segments = control_points
+        .iter()
+        .map(|row| tessellation.polygon_segments(row))
+        .max()
+        .unwrap_or(1);
 
     // Tessellate the surface (returns None if knot data is inconsistent)
     match tessellate_bspline_surface(
@@ -720,227 +726,3 @@ fn process_bspline_face(
         None => Ok((Vec::new(), Vec::new())),
     }
 }
-
-/// Process a cylindrical surface face
-fn process_cylindrical_face(
-    face: &DecodedEntity,
-    surface: &DecodedEntity,
-    decoder: &mut EntityDecoder,
-) -> Result<(Vec<f32>, Vec<u32>)> {
-    // Get the radius from IfcCylindricalSurface (attribute 1)
-    let radius = surface
-        .get(1)
-        .and_then(|v| v.as_float())
-        .ok_or_else(|| Error::geometry("CylindricalSurface missing Radius".to_string()))?;
-
-    // Get position/axis from IfcCylindricalSurface (attribute 0)
-    let position_attr = surface.get(0);
-    let axis_transform = if let Some(attr) = position_attr {
-        if let Some(pos_id) = attr.as_entity_ref() {
-            get_axis2_placement_transform_by_id(pos_id, decoder)?
-        } else {
-            Matrix4::identity()
-        }
-    } else {
-        Matrix4::identity()
-    };
-
-    // Extract boundary edges to determine angular and height extent
-    let bounds_attr = face
-        .get(0)
-        .ok_or_else(|| Error::geometry("AdvancedFace missing Bounds".to_string()))?;
-
-    let bounds = bounds_attr
-        .as_list()
-        .ok_or_else(|| Error::geometry("Expected bounds list".to_string()))?;
-
-    // Collect all boundary points to determine the extent
-    let mut boundary_points: Vec<Point3<f64>> = Vec::new();
-
-    for bound in bounds {
-        if let Some(bound_id) = bound.as_entity_ref() {
-            let bound_entity = decoder.decode_by_id(bound_id)?;
-            let loop_attr = bound_entity
-                .get(0)
-                .ok_or_else(|| Error::geometry("FaceBound missing Bound".to_string()))?;
-
-            if let Some(loop_entity) = decoder.resolve_ref(loop_attr)? {
-                if loop_entity
-                    .ifc_type
-                    .as_str()
-                    .eq_ignore_ascii_case("IFCEDGELOOP")
-                {
-                    if let Some(edges_attr) = loop_entity.get(0) {
-                        if let Some(edges) = edges_attr.as_list() {
-                            for edge_ref in edges {
-                                if let Some(edge_id) = edge_ref.as_entity_ref() {
-                                    if let Ok(oriented_edge) = decoder.decode_by_id(edge_id) {
-                                        // IfcOrientedEdge: 0=EdgeStart, 1=EdgeEnd, 2=EdgeElement, 3=Orientation
-                                        // EdgeStart/EdgeEnd can be * (null), get from EdgeElement if needed
-
-                                        // Try to get start vertex from OrientedEdge first
-                                        let start_vertex = oriented_edge
-                                            .get(0)
-                                            .and_then(|attr| {
-                                                decoder.resolve_ref(attr).ok().flatten()
-                                            });
-
-                                        // If null, get from EdgeElement (attribute 2)
-                                        let vertex = if start_vertex.is_some() {
-                                            start_vertex
-                                        } else if let Some(edge_elem_attr) =
-                                            oriented_edge.get(2)
-                                        {
-                                            // Get EdgeElement (IfcEdgeCurve)
-                                            if let Some(edge_curve) = decoder
-                                                .resolve_ref(edge_elem_attr)
-                                                .ok()
-                                                .flatten()
-                                            {
-                                                // IfcEdgeCurve: 0=EdgeStart, 1=EdgeEnd, 2=EdgeGeometry
-                                                edge_curve.get(0).and_then(|attr| {
-                                                    decoder.resolve_ref(attr).ok().flatten()
-                                                })
-                                            } else {
-                                                None
-                                            }
-                                        } else {
-                                            None
-                                        };
-
-                                        if let Some(vertex) = vertex {
-                                            // IfcVertexPoint: 0=VertexGeometry (IfcCartesianPoint)
-                                            if let Some(point_attr) = vertex.get(0) {
-                                                if let Some(point) = decoder
-                                                    .resolve_ref(point_attr)
-                                                    .ok()
-                                                    .flatten()
-                                                {
-                                                    if let Some(coords) =
-                                                        point.get(0).and_then(|v| v.as_list())
-                                                    {
-                                                        let x = coords
-                                                            .first()
-                                                            .and_then(|v| v.as_float())
-                                                            .unwrap_or(0.0);
-                                                        let y = coords
-                                                            .get(1)
-                                                            .and_then(|v| v.as_float())
-                                                            .unwrap_or(0.0);
-                                                        let z = coords
-                                                            .get(2)
-                                                            .and_then(|v| v.as_float())
-                                                            .unwrap_or(0.0);
-                                                        boundary_points
-                                                            .push(Point3::new(x, y, z));
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    if boundary_points.is_empty() {
-        return Ok((Vec::new(), Vec::new()));
-    }
-
-    // Transform boundary points to local cylinder coordinates
-    let inv_transform = axis_transform
-        .try_inverse()
-        .unwrap_or(Matrix4::identity());
-    let local_points: Vec<Point3<f64>> = boundary_points
-        .iter()
-        .map(|p| inv_transform.transform_point(p))
-        .collect();
-
-    // Determine angular extent (from local x,y) and height extent (from local z)
-    let mut min_angle = f64::MAX;
-    let mut max_angle = f64::MIN;
-    let mut min_z = f64::MAX;
-    let mut max_z = f64::MIN;
-
-    for p in &local_points {
-        let angle = p.y.atan2(p.x);
-        min_angle = min_angle.min(angle);
-        max_angle = max_angle.max(angle);
-        min_z = min_z.min(p.z);
-        max_z = max_z.max(p.z);
-    }
-
-    // Handle angle wrapping (if angles span across -pi/pi boundary)
-    if max_angle - min_angle > std::f64::consts::PI * 1.5 {
-        // Likely wraps around, recalculate with positive angles
-        let positive_angles: Vec<f64> = local_points
-            .iter()
-            .map(|p| {
-                let a = p.y.atan2(p.x);
-                if a < 0.0 {
-                    a + 2.0 * std::f64::consts::PI
-                } else {
-                    a
-                }
-            })
-            .collect();
-        min_angle = positive_angles.iter().cloned().fold(f64::MAX, f64::min);
-        max_angle = positive_angles.iter().cloned().fold(f64::MIN, f64::max);
-    }
-
-    // Tessellation parameters
-    let angle_span = max_angle - min_angle;
-    let height = max_z - min_z;
-
-    // Balance between accuracy and matching web-ifc's output
-    // Use ~15 degrees per segment (pi/12) for good curvature approximation
-    let angle_segments =
-        ((angle_span / (std::f64::consts::PI / 12.0)).ceil() as usize).clamp(3, 16);
-    // Height segments based on aspect ratio - at least 1, more for tall cylinders
-    let height_segments = ((height / (radius * 2.0)).ceil() as usize).clamp(1, 4);
-
-    let mut positions = Vec::new();
-    let mut indices = Vec::new();
-
-    // Generate cylinder patch vertices
-    for h in 0..=height_segments {
-        let z = min_z + (height * h as f64 / height_segments as f64);
-        for a in 0..=angle_segments {
-            let angle = min_angle + (angle_span * a as f64 / angle_segments as f64);
-            let x = radius * angle.cos();
-            let y = radius * angle.sin();
-
-            // Transform back to world coordinates
-            let local_point = Point3::new(x, y, z);
-            let world_point = axis_transform.transform_point(&local_point);
-
-            positions.push(world_point.x as f32);
-            positions.push(world_point.y as f32);
-            positions.push(world_point.z as f32);
-        }
-    }
-
-    // Generate indices for quad strip
-    let cols = angle_segments + 1;
-    for h in 0..height_segments {
-        for a in 0..angle_segments {
-            let base = (h * cols + a) as u32;
-            let next_row = base + cols as u32;
-
-            // Two triangles per quad
-            indices.push(base);
-            indices.push(base + 1);
-            indices.push(next_row + 1);
-
-            indices.push(base);
-            indices.push(next_row + 1);
-            indices.push(next_row);
-        }
-    }
-
-    Ok((positions, indices))
-}
@@ -0,0 +1,370 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tessellation of IfcAdvancedFace on elementary analytic surfaces.
+//!
+//! IfcCylindricalSurface, IfcConicalSurface, IfcSphericalSurface and
+//! IfcToroidalSurface are all parameterised by an angle `u` around the
+//! Position Z axis and a second coordinate `v` (height or latitude). Face
+//! boundaries are mapped into (u, v) space, a regular grid covering their
+//! extent is evaluated on the surface, and grid cells falling outside the
+//! trimmed region (or inside holes) are dropped.
+
+use super::advanced_face::extract_edge_loop_points;
+use super::helpers::get_axis2_placement_transform_by_id;
+use crate::tessellation::TessellationConfig;
+use crate::{Error, Point3, Result};
+use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcType};
+use nalgebra::Matrix4;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+/// Elementary surface in its local Position frame.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum AnalyticSurface {
+    /// `(r cos u, r sin u, v)`
+    Cylinder { radius: f64 },
+    /// `((r + v tan a) cos u, (r + v tan a) sin u, v)`
+    Cone { radius: f64, tan_semi_angle: f64 },
+    /// `(r cos v cos u, r cos v sin u, r sin v)`
+    Sphere { radius: f64 },
+    /// `((R + r cos v) cos u, (R + r cos v) sin u, r sin v)`
+    Torus { major: f64, minor: f64 },
+}
+
+impl AnalyticSurface {
+    /// Read surface parameters; returns `None` for non-analytic surface types.
+    ///
+    /// `plane_angle_unit` is the model's angle unit in radians (see
+    /// [`ifc_lite_core::extract_plane_angle_unit_scale`]).
+    pub(super) fn from_entity(
+        surface: &DecodedEntity,
+        plane_angle_unit: Option<f64>,
+    ) -> Option<Self> {
+        let positive = |i: usize| surface.get_float(i).filter(|v| *v > 0.0);
+        match surface.ifc_type {
+            // IfcCylindricalSurface: Position(0), Radius(1)
+            IfcType::IfcCylindricalSurface => Some(Self::Cylinder {
+                radius: positive(1)?,
+            }),
+            // IfcConicalSurface (IFC4X3, outside the generated schema): Position(0),
+            // Radius(1), SemiAngle(2)
+            t if t == IfcType::from_str("IFCCONICALSURFACE") => {
+                let semi = surface.get_float(2)?;
+                // SemiAngle is a plane angle measure in the model's unit.
+                // Without a declared unit, values above a right angle can
+                // only be degrees
+                let semi = match plane_angle_unit {
+                    Some(radians_per_unit) => semi * radians_per_unit,
+                    None if semi.abs() > FRAC_PI_2 => semi.to_radians(),
+                    None => semi,
+                };
+                Some(Self::Cone {
+                    radius: positive(1)?,
+                    tan_semi_angle: semi.tan(),
+                })
+            }
+            // IfcSphericalSurface: Position(0), Radius(1)
+            IfcType::IfcSphericalSurface => Some(Self::Sphere {
+                radius: positive(1)?,
+            }),
+            // IfcToroidalSurface: Position(0), MajorRadius(1), MinorRadius(2)
+            IfcType::IfcToroidalSurface => Some(Self::Torus {
+                major: positive(1)?,
+                minor: positive(2)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn evaluate(&self, u: f64, v: f64) -> Point3<f64> {
+        let (rho, z) = match *self {
+            Self::Cylinder { radius } => (radius, v),
+            Self::Cone {
+                radius,
+                tan_semi_angle,
+            } => (radius + v * tan_semi_angle, v),
+            Self::Sphere { radius } => (radius * v.cos(), radius * v.sin()),
+            Self::Torus { major, minor } => (major + minor * v.cos(), minor * v.sin()),
+        };
+        Point3::new(rho * u.cos(), rho * u.sin(), z)
+    }
+
+    /// Parameters of a local point assumed to lie on (or near) the surface.
+    fn invert(&self, p: &Point3<f64>) -> (f64, f64) {
+        let u = p.y.atan2(p.x);
+        let v = match *self {
+            Self::Cylinder { .. } | Self::Cone { .. } => p.z,
+            Self::Sphere { .. } => p.z.atan2((p.x * p.x + p.y * p.y).sqrt()),
+            Self::Torus { major, .. } => p.z.atan2((p.x * p.x + p.y * p.y).sqrt() - major),
+        };
+        (u, v)
+    }
+
+    fn v_is_periodic(&self) -> bool {
+        matches!(self, Self::Torus { .. })
+    }
+
+    /// Largest distance from the axis over the `v` range, for sizing `u` segments.
+    fn max_u_radius(&self, v_min: f64, v_max: f64) -> f64 {
+        match *self {
+            Self::Cylinder { radius } | Self::Sphere { radius } => radius,
+            Self::Cone {
+                radius,
+                tan_semi_angle,
+            } => (radius + v_min * tan_semi_angle)
+                .abs()
+                .max((radius + v_max * tan_semi_angle).abs()),
+            Self::Torus { major, minor } => major + minor,
+        }
+    }
+
+    fn v_segments(&self, v_span: f64, tessellation: &TessellationConfig) -> usize {
+        match *self {
+            // Straight rulings: only split long patches
+            Self::Cylinder { radius } | Self::Cone { radius, .. } => {
+                ((v_span / (radius * 2.0)).ceil() as usize).clamp(1, 4)
+            }
+            Self::Sphere { radius } => tessellation.arc_segments(radius, v_span),
+            Self::Torus { minor, .. } => tessellation.arc_segments(minor, v_span),
+        }
+    }
+
+    /// `v` of the pole a loop winding once around the axis closes towards.
+    fn pole(&self, towards_positive_v: bool) -> Option<f64> {
+        match *self {
+            Self::Sphere { .. } => Some(if towards_positive_v {
+                FRAC_PI_2
+            } else {
+                -FRAC_PI_2
+            }),
+            Self::Cone {
+                radius,
+                tan_semi_angle,
+            } if tan_semi_angle.abs() > 1e-9 => {
+                let apex = -radius / tan_semi_angle;
+                (towards_positive_v == (apex > 0.0)).then_some(apex)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Boundary loop mapped into surface parameter space.
+struct ParamLoop {
+    points: Vec<(f64, f64)>,
+    /// Net change in `u` along the loop; ±2π for loops around the axis.
+    u_winding: f64,
+    /// Face-side sense of the loop (bound orientation combined with SameSense).
+    forward: bool,
+}
+
+/// Tessellate an IfcAdvancedFace whose FaceSurface is an [`AnalyticSurface`].
+pub(super) fn process_analytic_face(
+    face: &DecodedEntity,
+    surface_entity: &DecodedEntity,
+    surface: AnalyticSurface,
+    same_sense: bool,
+    decoder: &mut EntityDecoder,
+    tessellation: &TessellationConfig,
+) -> Result<(Vec<f32>, Vec<u32>)> {
+    let transform = match surface_entity.get(0).and_then(|a| a.as_entity_ref()) {
+        Some(id) => get_axis2_placement_transform_by_id(id, decoder)?,
+        None => Matrix4::identity(),
+    };
+    let inverse = transform.try_inverse().unwrap_or(Matrix4::identity());
+
+    let loops = collect_param_loops(face, &surface, &inverse, same_sense, decoder, tessellation)?;
+    if loops.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    // Parameter extent of all boundaries
+    let (mut u_min, mut u_max) = (f64::MAX, f64::MIN);
+    let (mut v_min, mut v_max) = (f64::MAX, f64::MIN);
+    for l in &loops {
+        for &(u, v) in &l.points {
+            u_min = u_min.min(u);
+            u_max = u_max.max(u);
+            v_min = v_min.min(v);
+            v_max = v_max.max(v);
+        }
+        // A loop around the axis on a sphere or cone encloses a pole
+        if l.u_winding.abs() > PI {
+            if let Some(pole) = surface.pole((l.u_winding > 0.0) == l.forward) {
+                v_min = v_min.min(pole);
+                v_max = v_max.max(pole);
+            }
+        }
+    }
+    let u_span = u_max - u_min;
+    let v_span = v_max - v_min;
+    if u_span < 1e-9 || v_span < 1e-9 {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    // Only loops that close in parameter space bound a region. Loops running
+    // around the axis mark a full revolution: the whole band is kept and any
+    // closed loops are holes in it.
+    let full_revolution = loops.iter().any(|l| l.u_winding.abs() > PI);
+    let trims: Vec<&[(f64, f64)]> = loops
+        .iter()
+        .filter(|l| l.u_winding.abs() < PI && l.points.len() >= 3)
+        .map(|l| l.points.as_slice())
+        .collect();
+
+    let u_radius = surface.max_u_radius(v_min, v_max);
+    let u_segments = tessellation.arc_segments(u_radius, u_span).max(3);
+    let v_segments = surface.v_segments(v_span, tessellation);
+
+    let mut positions = Vec::with_capacity((u_segments + 1) * (v_segments + 1) * 3);
+    for j in 0..=v_segments {
+        let v = v_min + v_span * (j as f64 / v_segments as f64);
+        for i in 0..=u_segments {
+            let u = u_min + u_span * (i as f64 / u_segments as f64);
+            let p = transform.transform_point(&surface.evaluate(u, v));
+            positions.extend_from_slice(&[p.x as f32, p.y as f32, p.z as f32]);
+        }
+    }
+
+    // Two triangles per cell, ordered so the normal follows dS/du x dS/dv
+    let cols = u_segments + 1;
+    let mut indices = Vec::with_capacity(u_segments * v_segments * 6);
+    for j in 0..v_segments {
+        for i in 0..u_segments {
+            let base = (j * cols + i) as u32;
+            let next_row = base + cols as u32;
+            let corners = [
+                [(i, j), (i + 1, j), (i + 1, j + 1)],
+                [(i, j), (i + 1, j + 1), (i, j + 1)],
+            ];
+            let tris = [
+                [base, base + 1, next_row + 1],
+                [base, next_row + 1, next_row],
+            ];
+            for (tri, cell) in tris.iter().zip(corners.iter()) {
+                let cu = cell.iter().map(|c| c.0 as f64).sum::<f64>() / 3.0;
+                let cv = cell.iter().map(|c| c.1 as f64).sum::<f64>() / 3.0;
+                let centroid = (
+                    u_min + u_span * cu / u_segments as f64,
+                    v_min + v_span * cv / v_segments as f64,
+                );
+                let keep = trims.is_empty() || full_revolution != inside_trims(centroid, &trims);
+                if keep {
+                    indices.extend_from_slice(tri);
+                }
+            }
+        }
+    }
+
+    Ok((positions, indices))
+}
+
+/// Map every IfcEdgeLoop bound of `face` into continuous (u, v) coordinates.
+fn collect_param_loops(
+    face: &DecodedEntity,
+    surface: &AnalyticSurface,
+    inverse: &Matrix4<f64>,
+    same_sense: bool,
+    decoder: &mut EntityDecoder,
+    tessellation: &TessellationConfig,
+) -> Result<Vec<ParamLoop>> {
+    let bounds = face
+        .get(0)
+        .and_then(|a| a.as_list())
+        .ok_or_else(|| Error::geometry("AdvancedFace missing Bounds".to_string()))?;
+
+    let mut loops = Vec::with_capacity(bounds.len());
+    for bound in bounds {
+        let Some(bound_id) = bound.as_entity_ref() else {
+            continue;
+        };
+        let bound_entity = decoder.decode_by_id(bound_id)?;
+        // IfcFaceBound: Bound(0), Orientation(1)
+        let orientation = bound_entity
+            .get(1)
+            .and_then(|a| a.as_enum())
+            .map(|e| e == "T" || e == "TRUE")
+            .unwrap_or(true);
+        let Some(loop_entity) = bound_entity
+            .get(0)
+            .and_then(|a| decoder.resolve_ref(a).ok().flatten())
+        else {
+            continue;
+        };
+        if loop_entity.ifc_type != IfcType::IfcEdgeLoop {
+            continue;
+        }
+
+        let points = extract_edge_loop_points(&loop_entity, decoder, tessellation);
+        if points.len() < 2 {
+            continue;
+        }
+        let raw: Vec<(f64, f64)> = points
+            .iter()
+            .map(|p| surface.invert(&inverse.transform_point(p)))
+            .collect();
+        let (points, u_winding) = unwrap_loop(&raw, surface.v_is_periodic());
+        loops.push(ParamLoop {
+            points,
+            u_winding,
+            forward: orientation == same_sense,
+        });
+    }
+
+    // Periodic u: shift loops into the same 2π window as the first one
+    let mean_u = |l: &ParamLoop| l.points.iter().map(|p| p.0).sum::<f64>() / l.points.len() as f64;
+    if let Some(first) = loops.first() {
+        let window_start = if first.u_winding.abs() > PI {
+            first.points.iter().map(|p| p.0).fold(f64::MAX, f64::min)
+        } else {
+            mean_u(first) - PI
+        };
+        for l in loops.iter_mut().skip(1) {
+            let shift = -((mean_u(l) - window_start) / TAU).floor() * TAU;
+            for p in &mut l.points {
+                p.0 += shift;
+            }
+        }
+    }
+
+    Ok(loops)
+}
+
+/// Remove ±2π jumps between consecutive samples. Returns the unwrapped points
+/// and the net `u` change around the closed loop.
+fn unwrap_loop(raw: &[(f64, f64)], v_periodic: bool) -> (Vec<(f64, f64)>, f64) {
+    let unwrap = |prev: f64, cur: f64| prev + (cur - prev + PI).rem_euclid(TAU) - PI;
+
+    let mut out: Vec<(f64, f64)> = Vec::with_capacity(raw.len());
+    for &(u, v) in raw {
+        let next = match out.last() {
+            Some(&(pu, pv)) => (unwrap(pu, u), if v_periodic { unwrap(pv, v) } else { v }),
+            None => (u, v),
+        };
+        out.push(next);
+    }
+
+    // Closing segment back to the first point
+    let first = out[0].0;
+    let winding = unwrap(out[out.len() - 1].0, first) - first;
+    (out, winding)
+}
+
+/// Even-odd containment against every closed boundary loop.
+fn inside_trims(p: (f64, f64), trims: &[&[(f64, f64)]]) -> bool {
+    let mut inside = false;
+    for poly in trims {
+        let n = poly.len();
+        let mut j = n - 1;
+        for i in 0..n {
+            let (xi, yi) = poly[i];
+            let (xj, yj) = poly[j];
+            if (yi > p.1) != (yj > p.1) && p.0 < (xj - xi) * (p.1 - yi) / (yj - yi) + xi {
+                inside = !inside;
+            }
+            j = i;
+        }
+    }
+    inside
+}
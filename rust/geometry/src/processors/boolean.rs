@@ -8,7 +8,8 @@
 //! (DIFFERENCE, UNION, INTERSECTION).
 
 use crate::{
-    calculate_normals, ClippingProcessor, Error, Mesh, Point2, Point3, Profile2D, Result, Vector3,
+    calculate_normals, ClippingProcessor, Error, Mesh, Point2, Point3, Profile2D, Result,
+    TessellationConfig, ThinExtrusionConfig, Vector3,
 };
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema, IfcType};
 
@@ -16,6 +17,7 @@ use super::brep::FacetedBrepProcessor;
 use super::extrusion::ExtrudedAreaSolidProcessor;
 use super::helpers::parse_axis2_placement_3d;
 use super::swept::{RevolvedAreaSolidProcessor, SweptDiskSolidProcessor};
+use super::tapered::{ExtrudedAreaSolidTaperedProcessor, RevolvedAreaSolidTaperedProcessor};
 use super::tessellated::TriangulatedFaceSetProcessor;
 use crate::router::GeometryProcessor;
 
@@ -41,15 +43,30 @@ const MAX_BOOLEAN_DEPTH: u32 = 10;
 /// - Graceful fallback to first operand if CSG fails on degenerate meshes
 pub struct BooleanClippingProcessor {
     schema: IfcSchema,
+    tessellation: TessellationConfig,
+    thin_extrusion: ThinExtrusionConfig,
 }
 
 impl BooleanClippingProcessor {
     pub fn new() -> Self {
+        Self::with_tessellation(TessellationConfig::default())
+    }
+
+    /// Create processor with custom tessellation quality for curved operands
+    pub fn with_tessellation(tessellation: TessellationConfig) -> Self {
         Self {
             schema: IfcSchema::new(),
+            tessellation,
+            thin_extrusion: ThinExtrusionConfig::default(),
         }
     }
 
+    /// Set how near-zero extrusion depths in operands are handled
+    pub fn with_thin_extrusion(mut self, thin_extrusion: ThinExtrusionConfig) -> Self {
+        self.thin_extrusion = thin_extrusion;
+        self
+    }
+
     /// Process a solid operand with depth tracking
     fn process_operand_with_depth(
         &self,
@@ -59,7 +76,11 @@ impl BooleanClippingProcessor {
     ) -> Result<Mesh> {
         match operand.ifc_type {
             IfcType::IfcExtrudedAreaSolid => {
-                let processor = ExtrudedAreaSolidProcessor::new(self.schema.clone());
+                let processor = ExtrudedAreaSolidProcessor::with_tessellation(
+                    self.schema.clone(),
+                    self.tessellation,
+                )
+                .with_thin_extrusion(self.thin_extrusion);
                 processor.process(operand, decoder, &self.schema)
             }
             IfcType::IfcFacetedBrep => {
@@ -71,11 +92,31 @@ impl BooleanClippingProcessor {
                 processor.process(operand, decoder, &self.schema)
             }
             IfcType::IfcSweptDiskSolid => {
-                let processor = SweptDiskSolidProcessor::new(self.schema.clone());
+                let processor = SweptDiskSolidProcessor::with_tessellation(
+                    self.schema.clone(),
+                    self.tessellation,
+                );
                 processor.process(operand, decoder, &self.schema)
             }
             IfcType::IfcRevolvedAreaSolid => {
-                let processor = RevolvedAreaSolidProcessor::new(self.schema.clone());
+                let processor = RevolvedAreaSolidProcessor::with_tessellation(
+                    self.schema.clone(),
+                    self.tessellation,
+                );
+                processor.process(operand, decoder, &self.schema)
+            }
+            IfcType::IfcExtrudedAreaSolidTapered => {
+                let processor = ExtrudedAreaSolidTaperedProcessor::with_tessellation(
+                    self.schema.clone(),
+                    self.tessellation,
+                );
+                processor.process(operand, decoder, &self.schema)
+            }
+            IfcType::IfcRevolvedAreaSolidTapered => {
+                let processor = RevolvedAreaSolidTaperedProcessor::with_tessellation(
+                    self.schema.clone(),
+                    self.tessellation,
+                );
                 processor.process(operand, decoder, &self.schema)
             }
             IfcType::IfcBooleanResult | IfcType::IfcBooleanClippingResult => {
@@ -484,9 +525,9 @@ impl BooleanClippingProcessor {
 
             // Solid-solid difference: return base geometry (first operand).
             //
-            // The csgrs BSP tree can infinite-recurse on arbitrary solid combinations,
-            // causing unrecoverable stack overflow in WASM. Unlike half-space clipping
-            // (handled above), solid-solid CSG cannot be safely bounded.
+            // Arbitrary solid combinations blow past the BSP depth/split limits
+            // on real CAD exports, so the work would mostly be wasted. Unlike
+            // half-space clipping (handled above), the result is rarely worth it.
             //
             // Opening subtraction (windows/doors from walls) is handled separately by
             // the router via subtract_mesh, which works on controlled geometry. Here we
@@ -509,8 +550,8 @@ impl BooleanClippingProcessor {
 
         // Handle INTERSECTION operation
         if operator == ".INTERSECTION." || operator == "INTERSECTION" {
-            // Return empty mesh - we can't safely compute the intersection due to
-            // csgrs BSP recursion, and returning the first operand would over-approximate
+            // Return empty mesh - arbitrary solid intersections exceed the BSP
+            // limits, and returning the first operand would over-approximate
             return Ok(Mesh::new());
         }
 
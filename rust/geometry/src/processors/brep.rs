@@ -7,7 +7,7 @@
 //! Handles IfcFacetedBrep, IfcFaceBasedSurfaceModel, and IfcShellBasedSurfaceModel.
 //! All deal with boundary representations composed of face loops.
 
-use crate::{Error, Mesh, Point3, Result};
+use crate::{tessellation::TessellationConfig, Error, Mesh, Point3, Result};
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema, IfcType};
 
 use crate::router::GeometryProcessor;
@@ -468,12 +468,14 @@ impl FacetedBrepProcessor {
                 indices.push(base_idx + idx);
             }
         }
-        Ok(Mesh {
+        let mut mesh = Mesh {
             positions,
             normals: Vec::new(),
             indices,
             rtc_applied: true, // RTC already subtracted during f64→f32 conversion
-        })
+        };
+        mesh.weld(0.0);
+        Ok(mesh)
     }
 }
 
@@ -582,12 +584,16 @@ impl GeometryProcessor for FacetedBrepProcessor {
             }
         }
 
-        Ok(Mesh {
+        // Faces repeat the vertices they share with neighbours; merge those on
+        // coplanar faces (hard edges keep their own vertices)
+        let mut mesh = Mesh {
             positions,
             normals: Vec::new(),
             indices,
             rtc_applied: false,
-        })
+        };
+        mesh.weld(0.0);
+        Ok(mesh)
     }
 
     fn supported_types(&self) -> Vec<IfcType> {
@@ -612,11 +618,28 @@ impl Default for FacetedBrepProcessor {
 ///
 /// Structure (simple): FaceBasedSurfaceModel -> ConnectedFaceSet[] -> Face[] -> FaceBound -> PolyLoop
 /// Structure (advanced): FaceBasedSurfaceModel -> ConnectedFaceSet[] -> AdvancedFace[] -> FaceSurface
-pub struct FaceBasedSurfaceModelProcessor;
+pub struct FaceBasedSurfaceModelProcessor {
+    tessellation: TessellationConfig,
+    plane_angle_unit: Option<f64>,
+}
 
 impl FaceBasedSurfaceModelProcessor {
     pub fn new() -> Self {
-        Self
+        Self::with_tessellation(TessellationConfig::default())
+    }
+
+    /// Create processor with custom tessellation quality for advanced faces
+    pub fn with_tessellation(tessellation: TessellationConfig) -> Self {
+        Self {
+            tessellation,
+            plane_angle_unit: None,
+        }
+    }
+
+    /// Set the model's plane angle unit in radians, used for surface angles
+    pub fn with_plane_angle_unit(mut self, plane_angle_unit: Option<f64>) -> Self {
+        self.plane_angle_unit = plane_angle_unit;
+        self
     }
 }
 
@@ -665,7 +688,12 @@ impl GeometryProcessor for FaceBasedSurfaceModelProcessor {
 
                 if face.ifc_type == IfcType::IfcAdvancedFace {
                     // Advanced face: delegate to shared NURBS/planar/cylindrical handler
-                    let (positions, indices) = match process_advanced_face(&face, decoder) {
+                    let (positions, indices) = match process_advanced_face(
+                        &face,
+                        decoder,
+                        &self.tessellation,
+                        self.plane_angle_unit,
+                    ) {
                         Ok(result) => result,
                         Err(_) => continue,
                     };
@@ -767,11 +795,28 @@ impl Default for FaceBasedSurfaceModelProcessor {
 ///
 /// Structure (simple): ShellBasedSurfaceModel -> Shell[] -> Face[] -> FaceBound -> PolyLoop
 /// Structure (advanced): ShellBasedSurfaceModel -> Shell[] -> AdvancedFace[] -> FaceSurface
-pub struct ShellBasedSurfaceModelProcessor;
+pub struct ShellBasedSurfaceModelProcessor {
+    tessellation: TessellationConfig,
+    plane_angle_unit: Option<f64>,
+}
 
 impl ShellBasedSurfaceModelProcessor {
     pub fn new() -> Self {
-        Self
+        Self::with_tessellation(TessellationConfig::default())
+    }
+
+    /// Create processor with custom tessellation quality for advanced faces
+    pub fn with_tessellation(tessellation: TessellationConfig) -> Self {
+        Self {
+            tessellation,
+            plane_angle_unit: None,
+        }
+    }
+
+    /// Set the model's plane angle unit in radians, used for surface angles
+    pub fn with_plane_angle_unit(mut self, plane_angle_unit: Option<f64>) -> Self {
+        self.plane_angle_unit = plane_angle_unit;
+        self
     }
 }
 
@@ -822,7 +867,12 @@ impl GeometryProcessor for ShellBasedSurfaceModelProcessor {
 
                 if face.ifc_type == IfcType::IfcAdvancedFace {
                     // Advanced face: delegate to shared NURBS/planar/cylindrical handler
-                    let (positions, indices) = match process_advanced_face(&face, decoder) {
+                    let (positions, indices) = match process_advanced_face(
+                        &face,
+                        decoder,
+                        &self.tessellation,
+                        self.plane_angle_unit,
+                    ) {
                         Ok(result) => result,
                         Err(_) => continue,
                     };
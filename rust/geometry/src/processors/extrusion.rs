@@ -5,8 +5,11 @@
 //! ExtrudedAreaSolid processor - extrusion of 2D profiles.
 
 use crate::{
-    extrusion::{apply_transform, extrude_profile},
+    extrusion::{
+        apply_transform, extrude_profile_with_policy, ThinExtrusionConfig, ThinExtrusionPolicy,
+    },
     profiles::ProfileProcessor,
+    tessellation::TessellationConfig,
     Error, Mesh, Result, Vector3,
 };
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema, IfcType};
@@ -19,15 +22,28 @@ use crate::router::GeometryProcessor;
 /// Handles IfcExtrudedAreaSolid - extrusion of 2D profiles
 pub struct ExtrudedAreaSolidProcessor {
     profile_processor: ProfileProcessor,
+    thin_extrusion: ThinExtrusionConfig,
 }
 
 impl ExtrudedAreaSolidProcessor {
     /// Create new processor
     pub fn new(schema: IfcSchema) -> Self {
+        Self::with_tessellation(schema, TessellationConfig::default())
+    }
+
+    /// Create processor with custom curve tessellation quality for profiles
+    pub fn with_tessellation(schema: IfcSchema, tessellation: TessellationConfig) -> Self {
         Self {
-            profile_processor: ProfileProcessor::new(schema),
+            profile_processor: ProfileProcessor::with_tessellation(schema, tessellation),
+            thin_extrusion: ThinExtrusionConfig::default(),
         }
     }
+
+    /// Set how near-zero depths are handled (minimum depth in file units)
+    pub fn with_thin_extrusion(mut self, thin_extrusion: ThinExtrusionConfig) -> Self {
+        self.thin_extrusion = thin_extrusion;
+        self
+    }
 }
 
 impl GeometryProcessor for ExtrudedAreaSolidProcessor {
@@ -58,46 +74,7 @@ impl GeometryProcessor for ExtrudedAreaSolidProcessor {
             return Ok(Mesh::new());
         }
 
-        // Get extrusion direction
-        let direction_attr = entity.get(2).ok_or_else(|| {
-            Error::geometry("ExtrudedAreaSolid missing ExtrudedDirection".to_string())
-        })?;
-
-        let direction_entity = decoder
-            .resolve_ref(direction_attr)?
-            .ok_or_else(|| Error::geometry("Failed to resolve ExtrudedDirection".to_string()))?;
-
-        if direction_entity.ifc_type != IfcType::IfcDirection {
-            return Err(Error::geometry(format!(
-                "Expected IfcDirection, got {}",
-                direction_entity.ifc_type
-            )));
-        }
-
-        // Parse direction
-        let ratios_attr = direction_entity
-            .get(0)
-            .ok_or_else(|| Error::geometry("IfcDirection missing ratios".to_string()))?;
-
-        let ratios = ratios_attr
-            .as_list()
-            .ok_or_else(|| Error::geometry("Expected ratio list".to_string()))?;
-
-        use ifc_lite_core::AttributeValue;
-        let dir_x = ratios
-            .first()
-            .and_then(|v: &AttributeValue| v.as_float())
-            .unwrap_or(0.0);
-        let dir_y = ratios
-            .get(1)
-            .and_then(|v: &AttributeValue| v.as_float())
-            .unwrap_or(0.0);
-        let dir_z = ratios
-            .get(2)
-            .and_then(|v: &AttributeValue| v.as_float())
-            .unwrap_or(1.0);
-
-        let local_direction = Vector3::new(dir_x, dir_y, dir_z).normalize();
+        let local_direction = parse_extruded_direction(entity, decoder)?;
 
         // Get depth
         let depth = entity
@@ -169,8 +146,20 @@ impl GeometryProcessor for ExtrudedAreaSolidProcessor {
             Some(shear_mat)
         };
 
+        if self.thin_extrusion.policy == ThinExtrusionPolicy::Drop
+            && self.thin_extrusion.is_thin(depth)
+        {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "[ifc-lite] Dropping ExtrudedAreaSolid #{}: depth {} below minimum thickness {}",
+                entity.id, depth, self.thin_extrusion.min_depth
+            );
+            return Ok(Mesh::new());
+        }
+
         // Extrude the profile
-        let mut mesh = extrude_profile(&profile, depth, transform)?;
+        let mut mesh =
+            extrude_profile_with_policy(&profile, depth, transform, &self.thin_extrusion)?;
 
         // Apply Position transform
         if let Some(pos) = pos_transform {
@@ -184,3 +173,49 @@ impl GeometryProcessor for ExtrudedAreaSolidProcessor {
         vec![IfcType::IfcExtrudedAreaSolid]
     }
 }
+
+/// Parse `ExtrudedDirection` (attribute 2) of an extruded area solid
+pub(super) fn parse_extruded_direction(
+    entity: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+) -> Result<Vector3<f64>> {
+    let direction_attr = entity.get(2).ok_or_else(|| {
+        Error::geometry("ExtrudedAreaSolid missing ExtrudedDirection".to_string())
+    })?;
+
+    let direction_entity = decoder
+        .resolve_ref(direction_attr)?
+        .ok_or_else(|| Error::geometry("Failed to resolve ExtrudedDirection".to_string()))?;
+
+    if direction_entity.ifc_type != IfcType::IfcDirection {
+        return Err(Error::geometry(format!(
+            "Expected IfcDirection, got {}",
+            direction_entity.ifc_type
+        )));
+    }
+
+    // Parse direction
+    let ratios_attr = direction_entity
+        .get(0)
+        .ok_or_else(|| Error::geometry("IfcDirection missing ratios".to_string()))?;
+
+    let ratios = ratios_attr
+        .as_list()
+        .ok_or_else(|| Error::geometry("Expected ratio list".to_string()))?;
+
+    use ifc_lite_core::AttributeValue;
+    let dir_x = ratios
+        .first()
+        .and_then(|v: &AttributeValue| v.as_float())
+        .unwrap_or(0.0);
+    let dir_y = ratios
+        .get(1)
+        .and_then(|v: &AttributeValue| v.as_float())
+        .unwrap_or(0.0);
+    let dir_z = ratios
+        .get(2)
+        .and_then(|v: &AttributeValue| v.as_float())
+        .unwrap_or(1.0);
+
+    Ok(Vector3::new(dir_x, dir_y, dir_z).normalize())
+}
@@ -203,7 +203,8 @@ pub(super) fn extract_loop_points_by_id(
 /// Get transform from IfcAxis2Placement3D by entity ID.
 ///
 /// Uses fast-path cartesian point extraction. Shared by SurfaceOfLinearExtrusionProcessor
-/// and AdvancedBrepProcessor.
+/// and AdvancedBrepProcessor. IfcAxis2Placement2D is also accepted (its attr 1 is
+/// RefDirection, with Z fixed to +Z), which occurs for IfcCircle edge curves.
 pub(super) fn get_axis2_placement_transform_by_id(
     placement_id: u32,
     decoder: &mut EntityDecoder,
@@ -217,16 +218,20 @@ pub(super) fn get_axis2_placement_transform_by_id(
         .and_then(|id| decoder.get_cartesian_point_fast(id))
         .unwrap_or((0.0, 0.0, 0.0));
 
+    let (z_attr, x_attr) = if placement.ifc_type == IfcType::IfcAxis2Placement2D {
+        (None, placement.get(1))
+    } else {
+        (placement.get(1), placement.get(2))
+    };
+
     // Get axis (Z direction)
-    let z_axis = placement
-        .get(1)
+    let z_axis = z_attr
         .and_then(|a| a.as_entity_ref())
         .and_then(|id| get_direction_by_id(id, decoder))
         .unwrap_or(Vector3::new(0.0, 0.0, 1.0));
 
     // Get ref direction (X direction)
-    let x_axis = placement
-        .get(2)
+    let x_axis = x_attr
         .and_then(|a| a.as_entity_ref())
         .and_then(|id| get_direction_by_id(id, decoder))
         .unwrap_or(Vector3::new(1.0, 0.0, 0.0));
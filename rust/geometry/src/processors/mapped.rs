@@ -4,23 +4,43 @@
 
 //! MappedItem processor - geometry instancing.
 
-use crate::{Error, Mesh, Result};
+use crate::{
+    extrusion::ThinExtrusionConfig, tessellation::TessellationConfig, Error, Mesh, Result,
+};
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema, IfcType};
 
 use super::boolean::BooleanClippingProcessor;
 use super::brep::FacetedBrepProcessor;
 use super::extrusion::ExtrudedAreaSolidProcessor;
 use super::swept::{RevolvedAreaSolidProcessor, SweptDiskSolidProcessor};
-use super::tessellated::TriangulatedFaceSetProcessor;
+use super::tapered::{ExtrudedAreaSolidTaperedProcessor, RevolvedAreaSolidTaperedProcessor};
+use super::tessellated::{TriangulatedFaceSetProcessor, TriangulatedIrregularNetworkProcessor};
 use crate::router::GeometryProcessor;
 
 /// MappedItem processor (P0)
 /// Handles IfcMappedItem - geometry instancing
-pub struct MappedItemProcessor;
+pub struct MappedItemProcessor {
+    tessellation: TessellationConfig,
+    thin_extrusion: ThinExtrusionConfig,
+}
 
 impl MappedItemProcessor {
     pub fn new() -> Self {
-        Self
+        Self::with_tessellation(TessellationConfig::default())
+    }
+
+    /// Create processor with custom tessellation quality for mapped curved geometry
+    pub fn with_tessellation(tessellation: TessellationConfig) -> Self {
+        Self {
+            tessellation,
+            thin_extrusion: ThinExtrusionConfig::default(),
+        }
+    }
+
+    /// Set how near-zero extrusion depths in mapped items are handled
+    pub fn with_thin_extrusion(mut self, thin_extrusion: ThinExtrusionConfig) -> Self {
+        self.thin_extrusion = thin_extrusion;
+        self
     }
 }
 
@@ -68,27 +88,56 @@ impl GeometryProcessor for MappedItemProcessor {
         for item in items {
             let item_mesh = match item.ifc_type {
                 IfcType::IfcExtrudedAreaSolid => {
-                    let processor = ExtrudedAreaSolidProcessor::new(schema.clone());
+                    let processor = ExtrudedAreaSolidProcessor::with_tessellation(
+                        schema.clone(),
+                        self.tessellation,
+                    )
+                    .with_thin_extrusion(self.thin_extrusion);
                     processor.process(&item, decoder, schema)?
                 }
                 IfcType::IfcTriangulatedFaceSet => {
                     let processor = TriangulatedFaceSetProcessor::new();
                     processor.process(&item, decoder, schema)?
                 }
+                IfcType::IfcTriangulatedIrregularNetwork => {
+                    let processor = TriangulatedIrregularNetworkProcessor::new();
+                    processor.process(&item, decoder, schema)?
+                }
                 IfcType::IfcFacetedBrep => {
                     let processor = FacetedBrepProcessor::new();
                     processor.process(&item, decoder, schema)?
                 }
                 IfcType::IfcSweptDiskSolid => {
-                    let processor = SweptDiskSolidProcessor::new(schema.clone());
+                    let processor = SweptDiskSolidProcessor::with_tessellation(
+                        schema.clone(),
+                        self.tessellation,
+                    );
                     processor.process(&item, decoder, schema)?
                 }
                 IfcType::IfcBooleanClippingResult | IfcType::IfcBooleanResult => {
-                    let processor = BooleanClippingProcessor::new();
+                    let processor = BooleanClippingProcessor::with_tessellation(self.tessellation)
+                        .with_thin_extrusion(self.thin_extrusion);
                     processor.process(&item, decoder, schema)?
                 }
                 IfcType::IfcRevolvedAreaSolid => {
-                    let processor = RevolvedAreaSolidProcessor::new(schema.clone());
+                    let processor = RevolvedAreaSolidProcessor::with_tessellation(
+                        schema.clone(),
+                        self.tessellation,
+                    );
+                    processor.process(&item, decoder, schema)?
+                }
+                IfcType::IfcExtrudedAreaSolidTapered => {
+                    let processor = ExtrudedAreaSolidTaperedProcessor::with_tessellation(
+                        schema.clone(),
+                        self.tessellation,
+                    );
+                    processor.process(&item, decoder, schema)?
+                }
+                IfcType::IfcRevolvedAreaSolidTapered => {
+                    let processor = RevolvedAreaSolidTaperedProcessor::with_tessellation(
+                        schema.clone(),
+                        self.tessellation,
+                    );
                     processor.process(&item, decoder, schema)?
                 }
                 _ => continue, // Skip unsupported types
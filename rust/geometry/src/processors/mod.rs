@@ -14,12 +14,15 @@
 //! - `boolean`: BooleanClippingResult (CSG operations)
 //! - `mapped`: MappedItem (geometry instancing)
 //! - `swept`: SweptDiskSolid, RevolvedAreaSolid (swept geometry)
+//! - `tapered`: ExtrudedAreaSolidTapered, RevolvedAreaSolidTapered (lofted between two profiles)
 //! - `advanced`: AdvancedBrep (NURBS/B-spline)
-//! - `advanced_face`: Shared IfcAdvancedFace processing (B-spline, planar, cylindrical)
+//! - `advanced_face`: Shared IfcAdvancedFace processing (B-spline, planar)
+//! - `analytic_surface`: Cylindrical, conical, spherical and toroidal advanced faces
 //! - `helpers`: Shared parse functions used by multiple processors
 
 mod advanced;
 mod advanced_face;
+mod analytic_surface;
 mod boolean;
 mod brep;
 mod extrusion;
@@ -27,6 +30,7 @@ mod helpers;
 mod mapped;
 mod surface;
 mod swept;
+mod tapered;
 mod tessellated;
 
 #[cfg(test)]
@@ -42,7 +46,10 @@ pub use extrusion::ExtrudedAreaSolidProcessor;
 pub use mapped::MappedItemProcessor;
 pub use surface::SurfaceOfLinearExtrusionProcessor;
 pub use swept::{RevolvedAreaSolidProcessor, SweptDiskSolidProcessor};
-pub use tessellated::{PolygonalFaceSetProcessor, TriangulatedFaceSetProcessor};
+pub use tapered::{ExtrudedAreaSolidTaperedProcessor, RevolvedAreaSolidTaperedProcessor};
+pub use tessellated::{
+    PolygonalFaceSetProcessor, TriangulatedFaceSetProcessor, TriangulatedIrregularNetworkProcessor,
+};
 
 /// Extract CoordIndex bytes from IfcTriangulatedFaceSet raw entity
 ///
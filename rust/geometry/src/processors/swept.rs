@@ -4,7 +4,10 @@
 
 //! Swept geometry processors - SweptDiskSolid and RevolvedAreaSolid.
 
-use crate::{profiles::ProfileProcessor, Error, Mesh, Point3, Result, Vector3};
+use crate::{
+    profiles::ProfileProcessor, tessellation::TessellationConfig, Error, Mesh, Point2, Point3,
+    Result, Vector3,
+};
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema, IfcType};
 
 use crate::router::GeometryProcessor;
@@ -17,8 +20,13 @@ pub struct SweptDiskSolidProcessor {
 
 impl SweptDiskSolidProcessor {
     pub fn new(schema: IfcSchema) -> Self {
+        Self::with_tessellation(schema, TessellationConfig::default())
+    }
+
+    /// Create processor with custom tessellation quality
+    pub fn with_tessellation(schema: IfcSchema, tessellation: TessellationConfig) -> Self {
         Self {
-            profile_processor: ProfileProcessor::new(schema),
+            profile_processor: ProfileProcessor::with_tessellation(schema, tessellation),
         }
     }
 }
@@ -63,7 +71,10 @@ impl GeometryProcessor for SweptDiskSolidProcessor {
         }
 
         // Generate tube mesh by sweeping circle along curve
-        let segments = 24; // Number of segments around the circle
+        let segments = self
+            .profile_processor
+            .tessellation()
+            .circle_segments(radius);
         let mut positions = Vec::new();
         let mut indices = Vec::new();
 
@@ -179,8 +190,13 @@ pub struct RevolvedAreaSolidProcessor {
 
 impl RevolvedAreaSolidProcessor {
     pub fn new(schema: IfcSchema) -> Self {
+        Self::with_tessellation(schema, TessellationConfig::default())
+    }
+
+    /// Create processor with custom tessellation quality
+    pub fn with_tessellation(schema: IfcSchema, tessellation: TessellationConfig) -> Self {
         Self {
-            profile_processor: ProfileProcessor::new(schema),
+            profile_processor: ProfileProcessor::with_tessellation(schema, tessellation),
         }
     }
 }
@@ -226,176 +242,221 @@ impl GeometryProcessor for RevolvedAreaSolidProcessor {
             return Ok(Mesh::new());
         }
 
-        // Parse axis placement to get axis point and direction
-        // IfcAxis1Placement: Location, Axis (optional)
-        let axis_location = {
-            let loc_attr = axis_placement
-                .get(0)
-                .ok_or_else(|| Error::geometry("Axis1Placement missing Location".to_string()))?;
-            let loc = decoder
-                .resolve_ref(loc_attr)?
-                .ok_or_else(|| Error::geometry("Failed to resolve axis location".to_string()))?;
-            let coords = loc
-                .get(0)
-                .and_then(|v| v.as_list())
-                .ok_or_else(|| Error::geometry("Axis location missing coordinates".to_string()))?;
-            Point3::new(
-                coords.first().and_then(|v| v.as_float()).unwrap_or(0.0),
-                coords.get(1).and_then(|v| v.as_float()).unwrap_or(0.0),
-                coords.get(2).and_then(|v| v.as_float()).unwrap_or(0.0),
-            )
-        };
+        let (axis_location, axis_direction) = parse_revolution_axis(&axis_placement, decoder)?;
 
-        let axis_direction = {
-            if let Some(dir_attr) = axis_placement.get(1) {
-                if !dir_attr.is_null() {
-                    let dir = decoder.resolve_ref(dir_attr)?.ok_or_else(|| {
-                        Error::geometry("Failed to resolve axis direction".to_string())
-                    })?;
-                    let coords = dir.get(0).and_then(|v| v.as_list()).ok_or_else(|| {
-                        Error::geometry("Axis direction missing coordinates".to_string())
-                    })?;
-                    Vector3::new(
-                        coords.first().and_then(|v| v.as_float()).unwrap_or(0.0),
-                        coords.get(1).and_then(|v| v.as_float()).unwrap_or(1.0),
-                        coords.get(2).and_then(|v| v.as_float()).unwrap_or(0.0),
-                    )
-                    .normalize()
-                } else {
-                    Vector3::new(0.0, 1.0, 0.0) // Default Y axis
-                }
+        Ok(revolve_profile(
+            &profile_2d.outer,
+            None,
+            axis_location,
+            axis_direction,
+            angle,
+            self.profile_processor.tessellation(),
+        ))
+    }
+
+    fn supported_types(&self) -> Vec<IfcType> {
+        vec![IfcType::IfcRevolvedAreaSolid]
+    }
+}
+
+impl Default for RevolvedAreaSolidProcessor {
+    fn default() -> Self {
+        Self::new(IfcSchema::new())
+    }
+}
+
+/// Axis point and unit direction of an `IfcAxis1Placement` (Axis defaults to +Y)
+pub(super) fn parse_revolution_axis(
+    placement: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+) -> Result<(Point3<f64>, Vector3<f64>)> {
+    // IfcAxis1Placement: Location, Axis (optional)
+    let location = {
+        let loc_attr = placement
+            .get(0)
+            .ok_or_else(|| Error::geometry("Axis1Placement missing Location".to_string()))?;
+        let loc = decoder
+            .resolve_ref(loc_attr)?
+            .ok_or_else(|| Error::geometry("Failed to resolve axis location".to_string()))?;
+        let coords = loc
+            .get(0)
+            .and_then(|v| v.as_list())
+            .ok_or_else(|| Error::geometry("Axis location missing coordinates".to_string()))?;
+        Point3::new(
+            coords.first().and_then(|v| v.as_float()).unwrap_or(0.0),
+            coords.get(1).and_then(|v| v.as_float()).unwrap_or(0.0),
+            coords.get(2).and_then(|v| v.as_float()).unwrap_or(0.0),
+        )
+    };
+
+    let direction = {
+        if let Some(dir_attr) = placement.get(1) {
+            if !dir_attr.is_null() {
+                let dir = decoder.resolve_ref(dir_attr)?.ok_or_else(|| {
+                    Error::geometry("Failed to resolve axis direction".to_string())
+                })?;
+                let coords = dir.get(0).and_then(|v| v.as_list()).ok_or_else(|| {
+                    Error::geometry("Axis direction missing coordinates".to_string())
+                })?;
+                Vector3::new(
+                    coords.first().and_then(|v| v.as_float()).unwrap_or(0.0),
+                    coords.get(1).and_then(|v| v.as_float()).unwrap_or(1.0),
+                    coords.get(2).and_then(|v| v.as_float()).unwrap_or(0.0),
+                )
+                .normalize()
             } else {
                 Vector3::new(0.0, 1.0, 0.0) // Default Y axis
             }
-        };
-
-        // Generate revolved mesh
-        // Number of segments depends on angle
-        let full_circle = angle.abs() >= std::f64::consts::PI * 1.99;
-        let segments = if full_circle {
-            24 // Full revolution
         } else {
-            ((angle.abs() / std::f64::consts::PI * 12.0).ceil() as usize).max(4)
-        };
+            Vector3::new(0.0, 1.0, 0.0) // Default Y axis
+        }
+    };
 
-        let profile_points = &profile_2d.outer;
-        let num_profile_points = profile_points.len();
+    Ok((location, direction))
+}
 
-        let mut positions = Vec::new();
-        let mut indices = Vec::new();
+/// Revolve a profile outline about an axis.
+///
+/// With `end`, the outline blends linearly from `start` to `end` over the
+/// sweep (`IfcRevolvedAreaSolidTapered`); both must have the same length.
+pub(super) fn revolve_profile(
+    start: &[Point2<f64>],
+    end: Option<&[Point2<f64>]>,
+    axis_location: Point3<f64>,
+    axis_direction: Vector3<f64>,
+    angle: f64,
+    tessellation: &TessellationConfig,
+) -> Mesh {
+    // Number of segments depends on angle and the profile's distance from the axis.
+    // A tapered revolution ends on a different profile, so it never closes.
+    let full_circle = end.is_none() && angle.abs() >= std::f64::consts::PI * 1.99;
+    let max_radius = start
+        .iter()
+        .chain(end.unwrap_or_default())
+        .map(|p| {
+            let offset = Point3::new(p.x, p.y, 0.0) - axis_location;
+            (offset - axis_direction * offset.dot(&axis_direction)).norm()
+        })
+        .fold(0.0, f64::max);
+    let segments = if full_circle {
+        tessellation.circle_segments(max_radius)
+    } else {
+        tessellation.arc_segments(max_radius, angle).max(2)
+    };
+
+    let num_profile_points = start.len();
+
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+
+    // For each segment around the revolution
+    for i in 0..=segments {
+        let t = if full_circle && i == segments {
+            0.0 // Close the loop exactly
+        } else {
+            angle * i as f64 / segments as f64
+        };
 
-        // For each segment around the revolution
-        for i in 0..=segments {
-            let t = if full_circle && i == segments {
-                0.0 // Close the loop exactly
-            } else {
-                angle * i as f64 / segments as f64
-            };
+        // Rotation matrix around axis
+        let cos_t = t.cos();
+        let sin_t = t.sin();
+        let (ax, ay, az) = (axis_direction.x, axis_direction.y, axis_direction.z);
+
+        // Rodrigues' rotation formula components
+        let k_matrix = |v: Vector3<f64>| -> Vector3<f64> {
+            Vector3::new(
+                ay * v.z - az * v.y,
+                az * v.x - ax * v.z,
+                ax * v.y - ay * v.x,
+            )
+        };
 
-            // Rotation matrix around axis
-            let cos_t = t.cos();
-            let sin_t = t.sin();
-            let (ax, ay, az) = (axis_direction.x, axis_direction.y, axis_direction.z);
+        // Tapered revolutions blend linearly from the start to the end profile
+        let blend = i as f64 / segments as f64;
 
-            // Rodrigues' rotation formula components
-            let k_matrix = |v: Vector3<f64>| -> Vector3<f64> {
-                Vector3::new(
-                    ay * v.z - az * v.y,
-                    az * v.x - ax * v.z,
-                    ax * v.y - ay * v.x,
-                )
+        // For each point in the profile
+        for (j, p2d) in start.iter().enumerate() {
+            let p2d = match end {
+                Some(end) => p2d + (end[j] - p2d) * blend,
+                None => *p2d,
             };
 
-            // For each point in the profile
-            for (j, p2d) in profile_points.iter().enumerate() {
-                // Profile point in 3D (assume profile is in XY plane, rotated around Y axis)
-                // The 2D profile X becomes distance from axis, Y becomes height along axis
-                let radius = p2d.x;
-                let height = p2d.y;
+            // Profile point in 3D (assume profile is in XY plane, rotated around Y axis)
+            // The 2D profile X becomes distance from axis, Y becomes height along axis
+            let radius = p2d.x;
+            let height = p2d.y;
 
-                // Initial position before rotation (in the plane containing the axis)
-                let v = Vector3::new(radius, 0.0, 0.0);
+            // Initial position before rotation (in the plane containing the axis)
+            let v = Vector3::new(radius, 0.0, 0.0);
 
-                // Rodrigues' rotation: v_rot = v*cos(t) + (k x v)*sin(t) + k*(k.v)*(1-cos(t))
-                let k_cross_v = k_matrix(v);
-                let k_dot_v = ax * v.x + ay * v.y + az * v.z;
+            // Rodrigues' rotation: v_rot = v*cos(t) + (k x v)*sin(t) + k*(k.v)*(1-cos(t))
+            let k_cross_v = k_matrix(v);
+            let k_dot_v = ax * v.x + ay * v.y + az * v.z;
 
-                let v_rot =
-                    v * cos_t + k_cross_v * sin_t + axis_direction * k_dot_v * (1.0 - cos_t);
+            let v_rot = v * cos_t + k_cross_v * sin_t + axis_direction * k_dot_v * (1.0 - cos_t);
 
-                // Final position = axis_location + height along axis + rotated radius
-                let pos = axis_location + axis_direction * height + v_rot;
+            // Final position = axis_location + height along axis + rotated radius
+            let pos = axis_location + axis_direction * height + v_rot;
 
-                positions.push(pos.x as f32);
-                positions.push(pos.y as f32);
-                positions.push(pos.z as f32);
+            positions.push(pos.x as f32);
+            positions.push(pos.y as f32);
+            positions.push(pos.z as f32);
 
-                // Create triangles (except for the last segment if it connects back)
-                if i < segments && j < num_profile_points - 1 {
-                    let current = (i * num_profile_points + j) as u32;
-                    let next_seg = ((i + 1) * num_profile_points + j) as u32;
-                    let current_next = current + 1;
-                    let next_seg_next = next_seg + 1;
+            // Create triangles (except for the last segment if it connects back)
+            if i < segments && j < num_profile_points - 1 {
+                let current = (i * num_profile_points + j) as u32;
+                let next_seg = ((i + 1) * num_profile_points + j) as u32;
+                let current_next = current + 1;
+                let next_seg_next = next_seg + 1;
 
-                    // Two triangles per quad
-                    indices.push(current);
-                    indices.push(next_seg);
-                    indices.push(next_seg_next);
+                // Two triangles per quad
+                indices.push(current);
+                indices.push(next_seg);
+                indices.push(next_seg_next);
 
-                    indices.push(current);
-                    indices.push(next_seg_next);
-                    indices.push(current_next);
-                }
+                indices.push(current);
+                indices.push(next_seg_next);
+                indices.push(current_next);
             }
         }
+    }
 
-        // Add end caps if not a full revolution
-        if !full_circle {
-            // Start cap
-            let start_center_idx = (positions.len() / 3) as u32;
-            let start_center = axis_location
-                + axis_direction
-                    * (profile_points.iter().map(|p| p.y).sum::<f64>()
-                        / profile_points.len() as f64);
-            positions.push(start_center.x as f32);
-            positions.push(start_center.y as f32);
-            positions.push(start_center.z as f32);
-
-            for j in 0..num_profile_points - 1 {
-                indices.push(start_center_idx);
-                indices.push(j as u32 + 1);
-                indices.push(j as u32);
-            }
-
-            // End cap
-            let end_center_idx = (positions.len() / 3) as u32;
-            let end_base = (segments * num_profile_points) as u32;
-            positions.push(start_center.x as f32);
-            positions.push(start_center.y as f32);
-            positions.push(start_center.z as f32);
-
-            for j in 0..num_profile_points - 1 {
-                indices.push(end_center_idx);
-                indices.push(end_base + j as u32);
-                indices.push(end_base + j as u32 + 1);
-            }
+    // Add end caps if not a full revolution
+    if !full_circle {
+        // Start cap
+        let start_center_idx = (positions.len() / 3) as u32;
+        let mean_height =
+            |points: &[Point2<f64>]| points.iter().map(|p| p.y).sum::<f64>() / points.len() as f64;
+        let start_center = axis_location + axis_direction * mean_height(start);
+        positions.push(start_center.x as f32);
+        positions.push(start_center.y as f32);
+        positions.push(start_center.z as f32);
+
+        for j in 0..num_profile_points - 1 {
+            indices.push(start_center_idx);
+            indices.push(j as u32 + 1);
+            indices.push(j as u32);
         }
 
-        Ok(Mesh {
-            positions,
-            normals: Vec::new(),
-            indices,
-            rtc_applied: false,
-        })
-    }
+        // End cap
+        let end_center_idx = (positions.len() / 3) as u32;
+        let end_base = (segments * num_profile_points) as u32;
+        let end_center = axis_location + axis_direction * mean_height(end.unwrap_or(start));
+        positions.push(end_center.x as f32);
+        positions.push(end_center.y as f32);
+        positions.push(end_center.z as f32);
 
-    fn supported_types(&self) -> Vec<IfcType> {
-        vec![IfcType::IfcRevolvedAreaSolid]
+        for j in 0..num_profile_points - 1 {
+            indices.push(end_center_idx);
+            indices.push(end_base + j as u32);
+            indices.push(end_base + j as u32 + 1);
+        }
     }
-}
 
-impl Default for RevolvedAreaSolidProcessor {
-    fn default() -> Self {
-        Self::new(IfcSchema::new())
+    Mesh {
+        positions,
+        normals: Vec::new(),
+        indices,
+        rtc_applied: false,
     }
 }
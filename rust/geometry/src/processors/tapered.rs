@@ -0,0 +1,376 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tapered swept solids - ExtrudedAreaSolidTapered and RevolvedAreaSolidTapered.
+//!
+//! Both loft between the start profile (`SweptArea`) and `EndSweptArea`.
+//! Outlines are paired point by point; when the two profiles tessellate to a
+//! different number of points they are resampled by arc length first.
+
+use crate::{
+    profiles::ProfileProcessor, tessellation::TessellationConfig, Error, Mesh, Point2, Point3,
+    Profile2D, Result, Vector3,
+};
+use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema, IfcType};
+use nalgebra::Matrix4;
+
+use super::extrusion::parse_extruded_direction;
+use super::helpers::parse_axis2_placement_3d;
+use super::swept::{parse_revolution_axis, revolve_profile};
+use crate::extrusion::apply_transform;
+use crate::router::GeometryProcessor;
+
+/// ExtrudedAreaSolidTapered processor
+/// Handles IfcExtrudedAreaSolidTapered - extrusion that morphs into an end profile
+pub struct ExtrudedAreaSolidTaperedProcessor {
+    profile_processor: ProfileProcessor,
+}
+
+impl ExtrudedAreaSolidTaperedProcessor {
+    pub fn new(schema: IfcSchema) -> Self {
+        Self::with_tessellation(schema, TessellationConfig::default())
+    }
+
+    /// Create processor with custom curve tessellation quality for profiles
+    pub fn with_tessellation(schema: IfcSchema, tessellation: TessellationConfig) -> Self {
+        Self {
+            profile_processor: ProfileProcessor::with_tessellation(schema, tessellation),
+        }
+    }
+}
+
+impl GeometryProcessor for ExtrudedAreaSolidTaperedProcessor {
+    fn process(
+        &self,
+        entity: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        _schema: &IfcSchema,
+    ) -> Result<Mesh> {
+        // IfcExtrudedAreaSolidTapered attributes:
+        // 0: SweptArea, 1: Position, 2: ExtrudedDirection, 3: Depth
+        // 4: EndSweptArea (IfcProfileDef)
+        let Some((start, end)) = process_profiles(&self.profile_processor, entity, decoder)? else {
+            return Ok(Mesh::new());
+        };
+
+        let direction = parse_extruded_direction(entity, decoder)?;
+        let depth = entity
+            .get_float(3)
+            .ok_or_else(|| Error::geometry("ExtrudedAreaSolidTapered missing Depth".to_string()))?;
+
+        let mut mesh = loft_extrusion(&start, &end, direction * depth)?;
+        if let Some(position) = parse_position(entity, decoder)? {
+            apply_transform(&mut mesh, &position);
+        }
+        Ok(mesh)
+    }
+
+    fn supported_types(&self) -> Vec<IfcType> {
+        vec![IfcType::IfcExtrudedAreaSolidTapered]
+    }
+}
+
+impl Default for ExtrudedAreaSolidTaperedProcessor {
+    fn default() -> Self {
+        Self::new(IfcSchema::new())
+    }
+}
+
+/// RevolvedAreaSolidTapered processor
+/// Handles IfcRevolvedAreaSolidTapered - revolution that morphs into an end profile
+pub struct RevolvedAreaSolidTaperedProcessor {
+    profile_processor: ProfileProcessor,
+}
+
+impl RevolvedAreaSolidTaperedProcessor {
+    pub fn new(schema: IfcSchema) -> Self {
+        Self::with_tessellation(schema, TessellationConfig::default())
+    }
+
+    /// Create processor with custom tessellation quality
+    pub fn with_tessellation(schema: IfcSchema, tessellation: TessellationConfig) -> Self {
+        Self {
+            profile_processor: ProfileProcessor::with_tessellation(schema, tessellation),
+        }
+    }
+}
+
+impl GeometryProcessor for RevolvedAreaSolidTaperedProcessor {
+    fn process(
+        &self,
+        entity: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        _schema: &IfcSchema,
+    ) -> Result<Mesh> {
+        // IfcRevolvedAreaSolidTapered attributes:
+        // 0: SweptArea, 1: Position, 2: Axis (IfcAxis1Placement), 3: Angle
+        // 4: EndSweptArea (IfcProfileDef)
+        let Some((start, end)) = process_profiles(&self.profile_processor, entity, decoder)? else {
+            return Ok(Mesh::new());
+        };
+
+        let axis_attr = entity
+            .get(2)
+            .ok_or_else(|| Error::geometry("RevolvedAreaSolidTapered missing Axis".to_string()))?;
+        let axis_placement = decoder
+            .resolve_ref(axis_attr)?
+            .ok_or_else(|| Error::geometry("Failed to resolve Axis".to_string()))?;
+        let (axis_location, axis_direction) = parse_revolution_axis(&axis_placement, decoder)?;
+        let angle = entity
+            .get_float(3)
+            .ok_or_else(|| Error::geometry("RevolvedAreaSolidTapered missing Angle".to_string()))?;
+
+        let mut mesh = revolve_profile(
+            &start.outer,
+            Some(&end.outer),
+            axis_location,
+            axis_direction,
+            angle,
+            self.profile_processor.tessellation(),
+        );
+        if let Some(position) = parse_position(entity, decoder)? {
+            apply_transform(&mut mesh, &position);
+        }
+        Ok(mesh)
+    }
+
+    fn supported_types(&self) -> Vec<IfcType> {
+        vec![IfcType::IfcRevolvedAreaSolidTapered]
+    }
+}
+
+impl Default for RevolvedAreaSolidTaperedProcessor {
+    fn default() -> Self {
+        Self::new(IfcSchema::new())
+    }
+}
+
+/// Start and end profiles (attributes 0 and 4) with matching point counts,
+/// or `None` when either profile is empty
+fn process_profiles(
+    profile_processor: &ProfileProcessor,
+    entity: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+) -> Result<Option<(Profile2D, Profile2D)>> {
+    let mut process = |index: usize, name: &str| -> Result<Profile2D> {
+        let attr = entity
+            .get(index)
+            .ok_or_else(|| Error::geometry(format!("Tapered solid missing {}", name)))?;
+        let profile_entity = decoder
+            .resolve_ref(attr)?
+            .ok_or_else(|| Error::geometry(format!("Failed to resolve {}", name)))?;
+        profile_processor.process(&profile_entity, decoder)
+    };
+    let start = process(0, "SweptArea")?;
+    let end = process(4, "EndSweptArea")?;
+    if start.outer.len() < 3 || end.outer.len() < 3 {
+        return Ok(None);
+    }
+    Ok(Some(match_profiles(start, end)))
+}
+
+/// Pair up outlines so both profiles have the same point count per loop.
+///
+/// Holes are kept only when both profiles have the same number of them.
+fn match_profiles(start: Profile2D, end: Profile2D) -> (Profile2D, Profile2D) {
+    let (start_outer, end_outer) = match_loops(&start.outer, &end.outer);
+    let mut start_profile = Profile2D::new(start_outer);
+    let mut end_profile = Profile2D::new(end_outer);
+
+    if start.holes.len() == end.holes.len() {
+        for (a, b) in start.holes.iter().zip(&end.holes) {
+            if a.len() < 3 || b.len() < 3 {
+                continue;
+            }
+            let (a, b) = match_loops(a, b);
+            start_profile.add_hole(a);
+            end_profile.add_hole(b);
+        }
+    }
+    (start_profile, end_profile)
+}
+
+fn match_loops(a: &[Point2<f64>], b: &[Point2<f64>]) -> (Vec<Point2<f64>>, Vec<Point2<f64>>) {
+    if a.len() == b.len() {
+        return (a.to_vec(), b.to_vec());
+    }
+    let count = a.len().max(b.len());
+    (resample_loop(a, count), resample_loop(b, count))
+}
+
+/// `count` points evenly spaced by arc length along a closed loop
+fn resample_loop(points: &[Point2<f64>], count: usize) -> Vec<Point2<f64>> {
+    let n = points.len();
+    let edge = |i: usize| (points[i], points[(i + 1) % n]);
+    let perimeter: f64 = (0..n)
+        .map(|i| {
+            let (p, q) = edge(i);
+            (q - p).norm()
+        })
+        .sum();
+    if perimeter <= f64::EPSILON {
+        return vec![points[0]; count];
+    }
+
+    let step = perimeter / count as f64;
+    let mut result = Vec::with_capacity(count);
+    let (mut i, mut travelled) = (0, 0.0);
+    for k in 0..count {
+        let target = k as f64 * step;
+        loop {
+            let (p, q) = edge(i);
+            let length = (q - p).norm();
+            if travelled + length >= target || i == n - 1 {
+                let t = if length > 0.0 {
+                    ((target - travelled) / length).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                result.push(p + (q - p) * t);
+                break;
+            }
+            travelled += length;
+            i += 1;
+        }
+    }
+    result
+}
+
+/// Loft between two matched profiles: `start` at z = 0, `end` moved by `offset`
+fn loft_extrusion(start: &Profile2D, end: &Profile2D, offset: Vector3<f64>) -> Result<Mesh> {
+    let lift = |p: &Point2<f64>| Point3::new(p.x, p.y, 0.0) + offset;
+    let mut mesh = Mesh::new();
+
+    // Caps: bottom faces -Z, top faces +Z
+    for (profile, top) in [(start, false), (end, true)] {
+        let triangulation = profile.triangulate()?;
+        let base = mesh.vertex_count() as u32;
+        let normal = if top { Vector3::z() } else { -Vector3::z() };
+        for p in &triangulation.points {
+            let position = if top {
+                lift(p)
+            } else {
+                Point3::new(p.x, p.y, 0.0)
+            };
+            mesh.add_vertex(position, normal);
+        }
+        for tri in triangulation.indices.chunks_exact(3) {
+            let (i0, i1, i2) = (tri[0] as u32, tri[1] as u32, tri[2] as u32);
+            if top {
+                mesh.add_triangle(base + i0, base + i1, base + i2);
+            } else {
+                mesh.add_triangle(base + i0, base + i2, base + i1);
+            }
+        }
+    }
+
+    // Side quads between corresponding loop edges
+    let loops =
+        std::iter::once((&start.outer, &end.outer)).chain(start.holes.iter().zip(&end.holes));
+    for (bottom, top) in loops {
+        let n = bottom.len();
+        for i in 0..n {
+            let j = (i + 1) % n;
+            let quad = [
+                Point3::new(bottom[i].x, bottom[i].y, 0.0),
+                Point3::new(bottom[j].x, bottom[j].y, 0.0),
+                lift(&top[j]),
+                lift(&top[i]),
+            ];
+            let normal = (quad[1] - quad[0])
+                .cross(&(quad[3] - quad[0]))
+                .try_normalize(1e-12)
+                .or_else(|| {
+                    (quad[2] - quad[3])
+                        .cross(&(quad[3] - quad[0]))
+                        .try_normalize(1e-12)
+                });
+            let Some(normal) = normal else {
+                continue;
+            };
+            let base = mesh.vertex_count() as u32;
+            for v in quad {
+                mesh.add_vertex(v, normal);
+            }
+            mesh.add_triangle(base, base + 1, base + 2);
+            mesh.add_triangle(base, base + 2, base + 3);
+        }
+    }
+
+    // Extruding towards -Z turns the solid inside out
+    if offset.z < 0.0 {
+        for tri in mesh.indices.chunks_exact_mut(3) {
+            tri.swap(1, 2);
+        }
+        for n in mesh.normals.iter_mut() {
+            *n = -*n;
+        }
+    }
+    Ok(mesh)
+}
+
+/// `Position` (attribute 1) as a transform, if present
+fn parse_position(
+    entity: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+) -> Result<Option<Matrix4<f64>>> {
+    let Some(attr) = entity.get(1).filter(|a| !a.is_null()) else {
+        return Ok(None);
+    };
+    match decoder.resolve_ref(attr)? {
+        Some(placement) if placement.ifc_type == IfcType::IfcAxis2Placement3D => {
+            Ok(Some(parse_axis2_placement_3d(&placement, decoder)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(half: f64) -> Profile2D {
+        Profile2D::new(vec![
+            Point2::new(-half, -half),
+            Point2::new(half, -half),
+            Point2::new(half, half),
+            Point2::new(-half, half),
+        ])
+    }
+
+    #[test]
+    fn test_tapered_extrusion_lofts_between_profiles() {
+        // 1 m square tapering to 0.5 m over 3 m, like a precast column
+        let (start, end) = match_profiles(square(0.5), square(0.25));
+        let mesh = loft_extrusion(&start, &end, Vector3::new(0.0, 0.0, 3.0)).unwrap();
+
+        let (min, max) = mesh.bounds();
+        assert!((min.x + 0.5).abs() < 1e-6 && (max.x - 0.5).abs() < 1e-6);
+        assert!(min.z.abs() < 1e-6 && (max.z - 3.0).abs() < 1e-6);
+        let top_xs: Vec<f32> = mesh
+            .positions
+            .chunks_exact(3)
+            .filter(|p| (p[2] - 3.0).abs() < 1e-6)
+            .map(|p| p[0].abs())
+            .collect();
+        assert!(top_xs.iter().all(|x| (x - 0.25).abs() < 1e-6));
+
+        // Side normals point outwards and lean up with the taper
+        let side = &mesh.normals[mesh.normals.len() - 12..mesh.normals.len() - 9];
+        assert!(side[0] < -0.9 && side[2] > 0.0);
+
+        // Mismatched point counts are resampled to the larger count
+        let octagon = Profile2D::new(
+            (0..8)
+                .map(|i| {
+                    let a = i as f64 * std::f64::consts::FRAC_PI_4;
+                    Point2::new(a.cos(), a.sin())
+                })
+                .collect(),
+        );
+        let (start, end) = match_profiles(square(0.5), octagon);
+        assert_eq!(start.outer.len(), 8);
+        assert_eq!(end.outer.len(), 8);
+    }
+}
@@ -4,7 +4,8 @@
 
 //! Tessellated geometry processors - pre-tessellated/polygon meshes.
 //!
-//! Handles IfcTriangulatedFaceSet (explicit triangle meshes) and
+//! Handles IfcTriangulatedFaceSet (explicit triangle meshes),
+//! IfcTriangulatedIrregularNetwork (terrain face sets with void triangles) and
 //! IfcPolygonalFaceSet (polygon meshes requiring triangulation).
 
 use crate::{Error, Mesh, Result};
@@ -22,14 +23,10 @@ impl TriangulatedFaceSetProcessor {
     }
 }
 
-impl GeometryProcessor for TriangulatedFaceSetProcessor {
+impl TriangulatedFaceSetProcessor {
+    /// Read coordinates and triangles as stored, without validating indices
     #[inline]
-    fn process(
-        &self,
-        entity: &DecodedEntity,
-        decoder: &mut EntityDecoder,
-        _schema: &IfcSchema,
-    ) -> Result<Mesh> {
+    fn read_face_set(entity: &DecodedEntity, decoder: &mut EntityDecoder) -> Result<Mesh> {
         // IfcTriangulatedFaceSet attributes:
         // 0: Coordinates (IfcCartesianPointList3D)
         // 1: Normals (optional)
@@ -98,12 +95,24 @@ impl GeometryProcessor for TriangulatedFaceSetProcessor {
         };
 
         // Create mesh (normals will be computed later)
-        let mut mesh = Mesh {
+        Ok(Mesh {
             positions,
             normals: Vec::new(),
             indices,
             rtc_applied: false,
-        };
+        })
+    }
+}
+
+impl GeometryProcessor for TriangulatedFaceSetProcessor {
+    #[inline]
+    fn process(
+        &self,
+        entity: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        _schema: &IfcSchema,
+    ) -> Result<Mesh> {
+        let mut mesh = Self::read_face_set(entity, decoder)?;
         // Validate: IFC files (especially Revit exports) may have indices beyond vertex count
         mesh.validate_indices();
         Ok(mesh)
@@ -120,6 +129,62 @@ impl Default for TriangulatedFaceSetProcessor {
     }
 }
 
+/// TriangulatedIrregularNetwork processor
+/// Handles IfcTriangulatedIrregularNetwork (IFC4x3) - terrain surfaces
+/// stored as a triangulated face set with a flag per triangle
+pub struct TriangulatedIrregularNetworkProcessor;
+
+impl TriangulatedIrregularNetworkProcessor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl GeometryProcessor for TriangulatedIrregularNetworkProcessor {
+    fn process(
+        &self,
+        entity: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        _schema: &IfcSchema,
+    ) -> Result<Mesh> {
+        // IfcTriangulatedIrregularNetwork attributes:
+        // 0-4: IfcTriangulatedFaceSet attributes
+        // 5: Flags (one per triangle, -1 marks a void)
+        let mut mesh = TriangulatedFaceSetProcessor::read_face_set(entity, decoder)?;
+
+        if let Some(flags) = entity.get(5).and_then(|a| a.as_list()) {
+            let is_void = |triangle: usize| {
+                flags
+                    .get(triangle)
+                    .and_then(|f| f.as_int())
+                    .is_some_and(|f| f < 0)
+            };
+            if (0..flags.len()).any(is_void) {
+                mesh.indices = mesh
+                    .indices
+                    .chunks_exact(3)
+                    .enumerate()
+                    .filter(|(triangle, _)| !is_void(*triangle))
+                    .flat_map(|(_, tri)| tri.iter().copied())
+                    .collect();
+            }
+        }
+
+        mesh.validate_indices();
+        Ok(mesh)
+    }
+
+    fn supported_types(&self) -> Vec<IfcType> {
+        vec![IfcType::IfcTriangulatedIrregularNetwork]
+    }
+}
+
+impl Default for TriangulatedIrregularNetworkProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Handles IfcPolygonalFaceSet - explicit polygon meshes that need triangulation
 /// Unlike IfcTriangulatedFaceSet, faces can be arbitrary polygons (not just triangles)
 pub struct PolygonalFaceSetProcessor;
@@ -270,9 +335,13 @@ impl PolygonalFaceSetProcessor {
         let abs_y = sum_y.abs();
         let abs_z = sum_z.abs();
 
+        // A void loop with unresolvable vertices is dropped on its own rather
+        // than taking the whole face (and the other voids) with it
         let valid_holes: Vec<&[u32]> = inner_indices
             .iter()
-            .filter(|loop_indices| loop_indices.len() >= 3)
+            .filter(|loop_indices| {
+                loop_indices.len() >= 3 && loop_indices.iter().all(|&idx| get_pos(idx).is_some())
+            })
             .map(|loop_indices| loop_indices.as_slice())
             .collect();
 
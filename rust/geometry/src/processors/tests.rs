@@ -6,6 +6,7 @@
 
 use super::*;
 use crate::router::GeometryProcessor;
+use crate::TessellationConfig;
 use ifc_lite_core::{EntityDecoder, IfcSchema, IfcType};
 
 #[test]
@@ -476,3 +477,267 @@ fn test_triangulated_face_set_all_invalid_indices() {
     // All indices invalid — mesh should have positions but no valid triangles
     assert!(mesh.indices.is_empty(), "All invalid indices should be stripped");
 }
+
+/// Shared entities for analytic surface face tests: a unit circle at z=0
+/// (#16, one closed IfcCircle edge) and one at z=2 (#17, walked reversed).
+const ANALYTIC_FACE_BASE: &str = r#"
+#1=IFCCARTESIANPOINT((0.,0.,0.));
+#2=IFCDIRECTION((0.,0.,1.));
+#3=IFCDIRECTION((1.,0.,0.));
+#4=IFCAXIS2PLACEMENT3D(#1,#2,#3);
+#5=IFCCYLINDRICALSURFACE(#4,1.);
+#6=IFCCARTESIANPOINT((1.,0.,0.));
+#7=IFCCARTESIANPOINT((1.,0.,2.));
+#8=IFCVERTEXPOINT(#6);
+#9=IFCVERTEXPOINT(#7);
+#10=IFCCARTESIANPOINT((0.,0.,2.));
+#11=IFCAXIS2PLACEMENT3D(#10,#2,#3);
+#12=IFCCIRCLE(#4,1.);
+#13=IFCCIRCLE(#11,1.);
+#14=IFCEDGECURVE(#8,#8,#12,.T.);
+#15=IFCEDGECURVE(#9,#9,#13,.T.);
+#16=IFCORIENTEDEDGE(*,*,#14,.T.);
+#17=IFCORIENTEDEDGE(*,*,#15,.F.);
+#18=IFCEDGELOOP((#16));
+#19=IFCEDGELOOP((#17));
+"#;
+
+fn process_test_advanced_face(content: &str, face_id: u32) -> (Vec<f32>, Vec<u32>) {
+    let mut decoder = EntityDecoder::new(content);
+    let face = decoder.decode_by_id(face_id).unwrap();
+    super::advanced_face::process_advanced_face(
+        &face,
+        &mut decoder,
+        &TessellationConfig::default(),
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_cylindrical_face_full_revolution() {
+    // Tank wall: cylinder bounded by two full circles
+    let content = format!(
+        "{ANALYTIC_FACE_BASE}
+#20=IFCFACEOUTERBOUND(#18,.T.);
+#21=IFCFACEBOUND(#19,.T.);
+#22=IFCADVANCEDFACE((#20,#21),#5,.T.);
+"
+    );
+    let (positions, indices) = process_test_advanced_face(&content, 22);
+    assert!(!indices.is_empty());
+
+    let (mut min_z, mut max_z) = (f32::MAX, f32::MIN);
+    let (mut min_a, mut max_a) = (f32::MAX, f32::MIN);
+    for p in positions.chunks_exact(3) {
+        assert!(((p[0] * p[0] + p[1] * p[1]).sqrt() - 1.0).abs() < 1e-4);
+        min_z = min_z.min(p[2]);
+        max_z = max_z.max(p[2]);
+        let a = p[1].atan2(p[0]);
+        min_a = min_a.min(a);
+        max_a = max_a.max(a);
+    }
+    assert!(min_z.abs() < 1e-4 && (max_z - 2.0).abs() < 1e-4);
+    assert!(max_a - min_a > 5.5, "Face should wrap all the way around");
+}
+
+#[test]
+fn test_spherical_face_closes_to_pole() {
+    // Hemisphere bounded only by its equator
+    let content = format!(
+        "{ANALYTIC_FACE_BASE}
+#30=IFCSPHERICALSURFACE(#4,1.);
+#31=IFCFACEOUTERBOUND(#18,.T.);
+#32=IFCADVANCEDFACE((#31),#30,.T.);
+"
+    );
+    let (positions, indices) = process_test_advanced_face(&content, 32);
+    assert!(!indices.is_empty());
+
+    let max_z = positions.chunks_exact(3).map(|p| p[2]).fold(f32::MIN, f32::max);
+    let min_z = positions.chunks_exact(3).map(|p| p[2]).fold(f32::MAX, f32::min);
+    assert!((max_z - 1.0).abs() < 1e-4, "Should reach the north pole");
+    assert!(min_z > -1e-4, "Should stay in the upper hemisphere");
+
+    // Triangles face away from the sphere centre
+    let v = |i: u32| {
+        let i = i as usize * 3;
+        nalgebra::Vector3::new(positions[i], positions[i + 1], positions[i + 2])
+    };
+    for tri in indices.chunks_exact(3) {
+        let (a, b, c) = (v(tri[0]), v(tri[1]), v(tri[2]));
+        let normal = (b - a).cross(&(c - a));
+        if normal.norm() > 1e-6 {
+            assert!(normal.dot(&(a + b + c)) > 0.0);
+        }
+    }
+}
+
+#[test]
+fn test_cylindrical_face_trimmed_quarter() {
+    // Quarter cylinder: two arcs joined by two vertical lines
+    let content = format!(
+        "{ANALYTIC_FACE_BASE}
+#40=IFCCARTESIANPOINT((0.,1.,0.));
+#41=IFCCARTESIANPOINT((0.,1.,2.));
+#42=IFCVERTEXPOINT(#40);
+#43=IFCVERTEXPOINT(#41);
+#44=IFCVECTOR(#2,1.);
+#45=IFCLINE(#40,#44);
+#46=IFCLINE(#6,#44);
+#47=IFCEDGECURVE(#8,#42,#12,.T.);
+#48=IFCEDGECURVE(#42,#43,#45,.T.);
+#49=IFCEDGECURVE(#9,#43,#13,.T.);
+#50=IFCEDGECURVE(#8,#9,#46,.T.);
+#51=IFCORIENTEDEDGE(*,*,#47,.T.);
+#52=IFCORIENTEDEDGE(*,*,#48,.T.);
+#53=IFCORIENTEDEDGE(*,*,#49,.F.);
+#54=IFCORIENTEDEDGE(*,*,#50,.F.);
+#55=IFCEDGELOOP((#51,#52,#53,#54));
+#56=IFCFACEOUTERBOUND(#55,.T.);
+#57=IFCADVANCEDFACE((#56),#5,.T.);
+"
+    );
+    let (positions, indices) = process_test_advanced_face(&content, 57);
+    assert!(!indices.is_empty());
+
+    for p in positions.chunks_exact(3) {
+        let a = p[1].atan2(p[0]);
+        assert!(
+            (-1e-4..=std::f32::consts::FRAC_PI_2 + 1e-4).contains(&a),
+            "Vertex outside the trimmed quarter: angle {a}"
+        );
+    }
+}
+
+#[test]
+fn test_toroidal_face_quarter_tube() {
+    // Top outer quarter of a torus tube (R = 2, r = 0.5), between the outer
+    // equator (radius 2.5, z = 0) and the top circle (radius 2, z = 0.5)
+    let content = format!(
+        "{ANALYTIC_FACE_BASE}
+#60=IFCTOROIDALSURFACE(#4,2.,0.5);
+#61=IFCCARTESIANPOINT((2.5,0.,0.));
+#62=IFCCARTESIANPOINT((2.,0.,0.5));
+#63=IFCVERTEXPOINT(#61);
+#64=IFCVERTEXPOINT(#62);
+#65=IFCCARTESIANPOINT((0.,0.,0.5));
+#66=IFCAXIS2PLACEMENT3D(#65,#2,#3);
+#67=IFCCIRCLE(#4,2.5);
+#68=IFCCIRCLE(#66,2.);
+#69=IFCEDGECURVE(#63,#63,#67,.T.);
+#70=IFCEDGECURVE(#64,#64,#68,.T.);
+#71=IFCORIENTEDEDGE(*,*,#69,.T.);
+#72=IFCORIENTEDEDGE(*,*,#70,.F.);
+#73=IFCEDGELOOP((#71));
+#74=IFCEDGELOOP((#72));
+#75=IFCFACEOUTERBOUND(#73,.T.);
+#76=IFCFACEBOUND(#74,.T.);
+#77=IFCADVANCEDFACE((#75,#76),#60,.T.);
+"
+    );
+    let (positions, indices) = process_test_advanced_face(&content, 77);
+    assert!(!indices.is_empty());
+
+    let (mut min_rho, mut max_rho) = (f32::MAX, f32::MIN);
+    let (mut min_a, mut max_a) = (f32::MAX, f32::MIN);
+    for p in positions.chunks_exact(3) {
+        // Distance from the axis, and from the tube's centre circle
+        let rho = (p[0] * p[0] + p[1] * p[1]).sqrt();
+        let tube = ((rho - 2.0).powi(2) + p[2] * p[2]).sqrt();
+        assert!((tube - 0.5).abs() < 1e-4, "Vertex off the tube: {p:?}");
+        assert!(p[2] > -1e-4, "Vertex below the equator: {p:?}");
+        min_rho = min_rho.min(rho);
+        max_rho = max_rho.max(rho);
+        let a = p[1].atan2(p[0]);
+        min_a = min_a.min(a);
+        max_a = max_a.max(a);
+    }
+    assert!((min_rho - 2.0).abs() < 1e-4 && (max_rho - 2.5).abs() < 1e-4);
+    assert!(max_a - min_a > 5.5, "Face should wrap all the way around");
+}
+
+#[test]
+fn test_conical_surface_semi_angle_unit() {
+    use super::analytic_surface::AnalyticSurface;
+
+    let tan_semi_angle = |semi: &str, plane_angle_unit: Option<f64>| {
+        let content = format!("{ANALYTIC_FACE_BASE}#30=IFCCONICALSURFACE(#4,1.,{semi});\n");
+        let mut decoder = EntityDecoder::new(&content);
+        let surface = decoder.decode_by_id(30).unwrap();
+        match AnalyticSurface::from_entity(&surface, plane_angle_unit) {
+            Some(AnalyticSurface::Cone { tan_semi_angle, .. }) => tan_semi_angle,
+            other => panic!("Expected a cone, got {other:?}"),
+        }
+    };
+    let degree = Some(1f64.to_radians());
+
+    // The declared unit decides, even for small degree values
+    assert!((tan_semi_angle("1.", degree) - 1f64.to_radians().tan()).abs() < 1e-12);
+    assert!((tan_semi_angle("0.5", Some(1.0)) - 0.5f64.tan()).abs() < 1e-12);
+    // Without one, only values above a right angle are taken as degrees
+    assert!((tan_semi_angle("30.", None) - 30f64.to_radians().tan()).abs() < 1e-12);
+    assert!((tan_semi_angle("0.5", None) - 0.5f64.tan()).abs() < 1e-12);
+}
+
+#[test]
+fn test_swept_disk_tessellation_quality() {
+    let content = r#"
+#1=IFCCARTESIANPOINT((0.0,0.0,0.0));
+#2=IFCCARTESIANPOINT((0.0,0.0,1000.0));
+#3=IFCPOLYLINE((#1,#2));
+#4=IFCSWEPTDISKSOLID(#3,200.0,$,$,$);
+"#;
+
+    let schema = IfcSchema::new();
+    let vertex_count = |config: TessellationConfig| {
+        let mut decoder = EntityDecoder::new(content);
+        let entity = decoder.decode_by_id(4).unwrap();
+        let processor =
+            SweptDiskSolidProcessor::with_tessellation(schema.clone(), config.in_model_units(0.001));
+        processor
+            .process(&entity, &mut decoder, &schema)
+            .unwrap()
+            .vertex_count()
+    };
+
+    let fast = vertex_count(TessellationConfig::fast());
+    let default = vertex_count(TessellationConfig::default());
+    let high = vertex_count(TessellationConfig::high_quality());
+    assert!(fast < default && default < high, "{fast} {default} {high}");
+}
+
+#[test]
+fn test_polygonal_face_with_voids_keeps_penetration_open() {
+    // 4 x 4 slab face with a 1 x 1 penetration in the middle, plus a void
+    // loop referencing a missing point that must not discard the face
+    let content = r#"
+#1=IFCCARTESIANPOINTLIST3D(((0.,0.,0.),(4.,0.,0.),(4.,4.,0.),(0.,4.,0.),(1.5,1.5,0.),(2.5,1.5,0.),(2.5,2.5,0.),(1.5,2.5,0.)),$);
+#2=IFCINDEXEDPOLYGONALFACEWITHVOIDS((1,2,3,4),((5,8,7,6),(5,6,99)));
+#3=IFCPOLYGONALFACESET(#1,$,(#2),$);
+"#;
+
+    let mut decoder = EntityDecoder::new(content);
+    let schema = IfcSchema::new();
+    let entity = decoder.decode_by_id(3).unwrap();
+    let mesh = PolygonalFaceSetProcessor::new()
+        .process(&entity, &mut decoder, &schema)
+        .unwrap();
+
+    let area: f64 = mesh
+        .indices
+        .chunks_exact(3)
+        .map(|t| {
+            let p = |i: u32| {
+                let i = i as usize * 3;
+                nalgebra::Vector3::new(
+                    mesh.positions[i] as f64,
+                    mesh.positions[i + 1] as f64,
+                    mesh.positions[i + 2] as f64,
+                )
+            };
+            0.5 * (p(t[1]) - p(t[0])).cross(&(p(t[2]) - p(t[0]))).norm()
+        })
+        .sum();
+    assert!((area - 15.0).abs() < 1e-6, "area {}", area);
+}
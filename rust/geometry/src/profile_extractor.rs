@@ -295,7 +295,7 @@ fn extract_mapped_item_profiles(
 ///   2: LocalOrigin (IfcCartesianPoint)
 ///   3: Scale (f64, default 1.0)
 ///   4: Axis3 (Z direction, optional, 3D only)
-fn parse_cartesian_transformation_operator(
+pub(crate) fn parse_cartesian_transformation_operator(
     entity: &DecodedEntity,
     decoder: &mut EntityDecoder,
 ) -> Result<Matrix4<f64>> {
@@ -451,10 +451,11 @@ fn extract_extruded_solid(
 // ═══════════════════════════════════════════════════════════════════════════
 // PRIVATE: PLACEMENT TRAVERSAL
 // Duplicated from router/transforms.rs (pub(super) there) to avoid coupling.
+// Also used by `bounds_extractor`.
 // ═══════════════════════════════════════════════════════════════════════════
 
 /// Resolve an element's ObjectPlacement attribute to a world Matrix4 in IFC Z-up space.
-fn get_placement_transform(
+pub(crate) fn get_placement_transform(
     placement_attr: Option<&AttributeValue>,
     decoder: &mut EntityDecoder,
 ) -> Matrix4<f64> {
@@ -518,7 +519,7 @@ fn get_placement_recursive(
 // ═══════════════════════════════════════════════════════════════════════════
 
 /// Parse IfcAxis2Placement3D → Matrix4<f64> in IFC Z-up space (native units).
-fn parse_axis2_placement_3d(
+pub(crate) fn parse_axis2_placement_3d(
     placement: &DecodedEntity,
     decoder: &mut EntityDecoder,
 ) -> Result<Matrix4<f64>> {
@@ -585,7 +586,7 @@ fn parse_axis2_placement_3d(
 }
 
 /// Parse IfcCartesianPoint from a parent entity at the given attribute index.
-fn parse_cartesian_point(
+pub(crate) fn parse_cartesian_point(
     parent: &DecodedEntity,
     decoder: &mut EntityDecoder,
     attr_index: usize,
@@ -660,7 +661,7 @@ fn parse_extrusion_direction(solid: &DecodedEntity, decoder: &mut EntityDecoder)
 // ═══════════════════════════════════════════════════════════════════════════
 
 /// Scale only the translation column of a matrix (rows 0-2 of column 3).
-fn scale_translation(mut m: Matrix4<f64>, scale: f64) -> Matrix4<f64> {
+pub(crate) fn scale_translation(mut m: Matrix4<f64>, scale: f64) -> Matrix4<f64> {
     if scale != 1.0 {
         m[(0, 3)] *= scale;
         m[(1, 3)] *= scale;
@@ -685,7 +686,7 @@ fn convert_ifc_to_webgl(m: &Matrix4<f64>) -> [f32; 16] {
 }
 
 /// Detect the IFC length unit scale factor from IFCPROJECT.
-fn detect_unit_scale(content: &str, decoder: &mut EntityDecoder) -> f64 {
+pub(crate) fn detect_unit_scale(content: &str, decoder: &mut EntityDecoder) -> f64 {
     let mut scanner = EntityScanner::new(content);
     while let Some((id, type_name, _, _)) = scanner.next_entity() {
         if type_name == "IFCPROJECT" {
@@ -0,0 +1,436 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Outlines of parameterized steel and trapezium profiles
+//!
+//! All shapes are centred on their bounding box, as IFC places the profile
+//! Position there, and returned counter-clockwise. Fillet and edge radii are
+//! tessellated with the caller's [`TessellationConfig`]; a missing or zero
+//! radius gives a sharp corner.
+
+use crate::error::{Error, Result};
+use crate::profile::Profile2D;
+use crate::tessellation::TessellationConfig;
+use nalgebra::{Point2, Vector2};
+
+/// Build a closed outline from corners with optional rounding radii
+///
+/// Corners must be in counter-clockwise order. Each radius is shrunk when it
+/// would not fit between the neighbouring corners.
+pub fn rounded_outline(
+    corners: &[(Point2<f64>, f64)],
+    tessellation: &TessellationConfig,
+) -> Vec<Point2<f64>> {
+    let n = corners.len();
+    let mut points = Vec::with_capacity(n * 2);
+
+    for i in 0..n {
+        let (p, radius) = corners[i];
+        let prev = corners[(i + n - 1) % n].0;
+        let next = corners[(i + 1) % n].0;
+        let (to_prev, to_next) = (prev - p, next - p);
+        let (len_prev, len_next) = (to_prev.norm(), to_next.norm());
+
+        if radius <= 0.0 || len_prev <= 0.0 || len_next <= 0.0 {
+            points.push(p);
+            continue;
+        }
+
+        let d1 = to_prev / len_prev;
+        let d2 = to_next / len_next;
+        let corner_angle = d1.dot(&d2).clamp(-1.0, 1.0).acos();
+        if corner_angle <= 1e-9 || corner_angle >= std::f64::consts::PI - 1e-9 {
+            points.push(p);
+            continue;
+        }
+
+        // Distance from the corner to both tangent points, capped at half of
+        // each adjacent edge so neighbouring fillets cannot overlap
+        let half = corner_angle / 2.0;
+        let tangent = (radius / half.tan())
+            .min(len_prev / 2.0)
+            .min(len_next / 2.0);
+        let radius = tangent * half.tan();
+        let center = p + (d1 + d2).normalize() * (radius / half.sin());
+
+        let start = p + d1 * tangent;
+        let a0 = (start.y - center.y).atan2(start.x - center.x);
+        let convex = cross(&(p - prev), &(next - p)) > 0.0;
+        let sweep = (std::f64::consts::PI - corner_angle) * if convex { 1.0 } else { -1.0 };
+
+        let segments = tessellation.arc_segments(radius, sweep);
+        for s in 0..=segments {
+            let a = a0 + sweep * (s as f64 / segments as f64);
+            points.push(center + Vector2::new(a.cos(), a.sin()) * radius);
+        }
+    }
+
+    points
+}
+
+fn cross(a: &Vector2<f64>, b: &Vector2<f64>) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn require_positive(name: &str, values: &[(&str, f64)]) -> Result<()> {
+    for (attr, value) in values {
+        if !(value.is_finite() && *value > 0.0) {
+            return Err(Error::geometry(format!(
+                "{} has non-positive {}: {}",
+                name, attr, value
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Cold-formed C section (lipped channel), web on the negative X side
+pub struct CShape {
+    pub depth: f64,
+    pub width: f64,
+    pub wall_thickness: f64,
+    /// Length of the lips; no lips when not longer than the wall thickness
+    pub girth: f64,
+    pub internal_fillet_radius: f64,
+}
+
+impl CShape {
+    pub fn outline(&self, tessellation: &TessellationConfig) -> Result<Profile2D> {
+        let (d, t) = (self.depth, self.wall_thickness);
+        require_positive(
+            "C-Shape",
+            &[("Depth", d), ("Width", self.width), ("WallThickness", t)],
+        )?;
+        if 2.0 * t >= d.min(2.0 * self.width) {
+            return Err(Error::geometry(format!(
+                "C-Shape WallThickness {} too large for {}x{}",
+                t, d, self.width
+            )));
+        }
+
+        let (hw, hd) = (self.width / 2.0, d / 2.0);
+        let r = self.internal_fillet_radius.max(0.0);
+        // Bends keep a constant wall thickness: outer radius = inner + t
+        let outer = if r > 0.0 { r + t } else { 0.0 };
+        let girth = self.girth.min(hd);
+        let p = Point2::new;
+
+        let mut corners = vec![(p(-hw, -hd), outer), (p(hw, -hd), 0.0)];
+        if girth > t {
+            corners[1].1 = outer;
+            corners.extend([
+                (p(hw, -hd + girth), 0.0),
+                (p(hw - t, -hd + girth), 0.0),
+                (p(hw - t, -hd + t), r),
+            ]);
+        } else {
+            corners.push((p(hw, -hd + t), 0.0));
+        }
+        corners.extend([(p(-hw + t, -hd + t), r), (p(-hw + t, hd - t), r)]);
+        if girth > t {
+            corners.extend([
+                (p(hw - t, hd - t), r),
+                (p(hw - t, hd - girth), 0.0),
+                (p(hw, hd - girth), 0.0),
+                (p(hw, hd), outer),
+            ]);
+        } else {
+            corners.extend([(p(hw, hd - t), 0.0), (p(hw, hd), 0.0)]);
+        }
+        corners.push((p(-hw, hd), outer));
+
+        Ok(Profile2D::new(rounded_outline(&corners, tessellation)))
+    }
+}
+
+/// Z section: bottom flange towards negative X, top flange towards positive X
+pub struct ZShape {
+    pub depth: f64,
+    /// Flange width including the web thickness
+    pub flange_width: f64,
+    pub web_thickness: f64,
+    pub flange_thickness: f64,
+    pub fillet_radius: f64,
+    pub edge_radius: f64,
+}
+
+impl ZShape {
+    pub fn outline(&self, tessellation: &TessellationConfig) -> Result<Profile2D> {
+        let (d, fw, tw, tf) = (
+            self.depth,
+            self.flange_width,
+            self.web_thickness,
+            self.flange_thickness,
+        );
+        require_positive(
+            "Z-Shape",
+            &[
+                ("Depth", d),
+                ("FlangeWidth", fw),
+                ("WebThickness", tw),
+                ("FlangeThickness", tf),
+            ],
+        )?;
+        if tw >= fw || 2.0 * tf >= d {
+            return Err(Error::geometry(format!(
+                "Z-Shape web/flange thickness ({}, {}) too large for {}x{}",
+                tw, tf, fw, d
+            )));
+        }
+
+        let (hd, hw) = (d / 2.0, tw / 2.0);
+        let (fillet, edge) = (self.fillet_radius.max(0.0), self.edge_radius.max(0.0));
+        let p = Point2::new;
+        let corners = [
+            (p(hw - fw, -hd), 0.0),
+            (p(hw, -hd), 0.0),
+            (p(hw, hd - tf), fillet),
+            (p(fw - hw, hd - tf), edge),
+            (p(fw - hw, hd), 0.0),
+            (p(-hw, hd), 0.0),
+            (p(-hw, -hd + tf), fillet),
+            (p(hw - fw, -hd + tf), edge),
+        ];
+
+        Ok(Profile2D::new(rounded_outline(&corners, tessellation)))
+    }
+}
+
+/// T section with the flange at positive Y
+pub struct TShape {
+    pub depth: f64,
+    pub flange_width: f64,
+    pub web_thickness: f64,
+    pub flange_thickness: f64,
+    pub fillet_radius: f64,
+    pub flange_edge_radius: f64,
+    pub web_edge_radius: f64,
+}
+
+impl TShape {
+    pub fn outline(&self, tessellation: &TessellationConfig) -> Result<Profile2D> {
+        let (d, fw, tw, tf) = (
+            self.depth,
+            self.flange_width,
+            self.web_thickness,
+            self.flange_thickness,
+        );
+        require_positive(
+            "T-Shape",
+            &[
+                ("Depth", d),
+                ("FlangeWidth", fw),
+                ("WebThickness", tw),
+                ("FlangeThickness", tf),
+            ],
+        )?;
+        if tw >= fw || tf >= d {
+            return Err(Error::geometry(format!(
+                "T-Shape web/flange thickness ({}, {}) too large for {}x{}",
+                tw, tf, fw, d
+            )));
+        }
+
+        let (hd, hf, hw) = (d / 2.0, fw / 2.0, tw / 2.0);
+        let fillet = self.fillet_radius.max(0.0);
+        let flange_edge = self.flange_edge_radius.max(0.0);
+        let web_edge = self.web_edge_radius.max(0.0);
+        let p = Point2::new;
+        let corners = [
+            (p(-hw, -hd), web_edge),
+            (p(hw, -hd), web_edge),
+            (p(hw, hd - tf), fillet),
+            (p(hf, hd - tf), flange_edge),
+            (p(hf, hd), 0.0),
+            (p(-hf, hd), 0.0),
+            (p(-hf, hd - tf), flange_edge),
+            (p(-hw, hd - tf), fillet),
+        ];
+
+        Ok(Profile2D::new(rounded_outline(&corners, tessellation)))
+    }
+}
+
+/// I section with independent top and bottom flanges
+pub struct AsymmetricIShape {
+    pub overall_depth: f64,
+    pub web_thickness: f64,
+    pub bottom_flange_width: f64,
+    pub bottom_flange_thickness: f64,
+    pub bottom_flange_fillet_radius: f64,
+    pub bottom_flange_edge_radius: f64,
+    pub top_flange_width: f64,
+    pub top_flange_thickness: f64,
+    pub top_flange_fillet_radius: f64,
+    pub top_flange_edge_radius: f64,
+}
+
+impl AsymmetricIShape {
+    pub fn outline(&self, tessellation: &TessellationConfig) -> Result<Profile2D> {
+        let d = self.overall_depth;
+        let tw = self.web_thickness;
+        let (wb, tb) = (self.bottom_flange_width, self.bottom_flange_thickness);
+        let (wt, tt) = (self.top_flange_width, self.top_flange_thickness);
+        require_positive(
+            "I-Shape",
+            &[
+                ("OverallDepth", d),
+                ("WebThickness", tw),
+                ("BottomFlangeWidth", wb),
+                ("BottomFlangeThickness", tb),
+                ("TopFlangeWidth", wt),
+                ("TopFlangeThickness", tt),
+            ],
+        )?;
+        if tw >= wb.min(wt) || tb + tt >= d {
+            return Err(Error::geometry(format!(
+                "I-Shape web/flange thickness too large for depth {} and widths ({}, {})",
+                d, wb, wt
+            )));
+        }
+
+        let (hd, hw, hb, ht) = (d / 2.0, tw / 2.0, wb / 2.0, wt / 2.0);
+        let rb = self.bottom_flange_fillet_radius.max(0.0);
+        let rt = self.top_flange_fillet_radius.max(0.0);
+        let eb = self.bottom_flange_edge_radius.max(0.0);
+        let et = self.top_flange_edge_radius.max(0.0);
+        let p = Point2::new;
+        let corners = [
+            (p(-hb, -hd), 0.0),
+            (p(hb, -hd), 0.0),
+            (p(hb, -hd + tb), eb),
+            (p(hw, -hd + tb), rb),
+            (p(hw, hd - tt), rt),
+            (p(ht, hd - tt), et),
+            (p(ht, hd), 0.0),
+            (p(-ht, hd), 0.0),
+            (p(-ht, hd - tt), et),
+            (p(-hw, hd - tt), rt),
+            (p(-hw, -hd + tb), rb),
+            (p(-hb, -hd + tb), eb),
+        ];
+
+        Ok(Profile2D::new(rounded_outline(&corners, tessellation)))
+    }
+}
+
+/// Trapezium with the bottom edge centred on the origin's X and the top edge
+/// shifted by `top_x_offset` from the bottom-left corner
+pub fn trapezium(
+    bottom_x_dim: f64,
+    top_x_dim: f64,
+    y_dim: f64,
+    top_x_offset: f64,
+) -> Result<Profile2D> {
+    require_positive(
+        "Trapezium",
+        &[
+            ("BottomXDim", bottom_x_dim),
+            ("TopXDim", top_x_dim),
+            ("YDim", y_dim),
+        ],
+    )?;
+    if !top_x_offset.is_finite() {
+        return Err(Error::geometry(format!(
+            "Trapezium has invalid TopXOffset: {}",
+            top_x_offset
+        )));
+    }
+
+    let (hx, hy) = (bottom_x_dim / 2.0, y_dim / 2.0);
+    Ok(Profile2D::new(vec![
+        Point2::new(-hx, -hy),
+        Point2::new(hx, -hy),
+        Point2::new(-hx + top_x_offset + top_x_dim, hy),
+        Point2::new(-hx + top_x_offset, hy),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bool2d::compute_signed_area;
+
+    #[test]
+    fn test_shapes_are_ccw_with_expected_area() {
+        let tess = TessellationConfig::default();
+
+        let c = CShape {
+            depth: 200.0,
+            width: 80.0,
+            wall_thickness: 2.0,
+            girth: 20.0,
+            internal_fillet_radius: 0.0,
+        }
+        .outline(&tess)
+        .unwrap();
+        let c_area = 200.0 * 2.0 + 2.0 * 78.0 * 2.0 + 2.0 * 18.0 * 2.0;
+        assert!((compute_signed_area(&c.outer) - c_area).abs() < 1e-9);
+
+        let z = ZShape {
+            depth: 200.0,
+            flange_width: 70.0,
+            web_thickness: 6.0,
+            flange_thickness: 10.0,
+            fillet_radius: 0.0,
+            edge_radius: 0.0,
+        }
+        .outline(&tess)
+        .unwrap();
+        let z_area = 180.0 * 6.0 + 2.0 * 70.0 * 10.0;
+        assert!((compute_signed_area(&z.outer) - z_area).abs() < 1e-9);
+
+        let trap = trapezium(100.0, 60.0, 50.0, 10.0).unwrap();
+        assert!((compute_signed_area(&trap.outer) - 4000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fillets_round_t_and_asymmetric_i() {
+        let tess = TessellationConfig::default();
+        let t = TShape {
+            depth: 100.0,
+            flange_width: 100.0,
+            web_thickness: 10.0,
+            flange_thickness: 10.0,
+            fillet_radius: 8.0,
+            flange_edge_radius: 0.0,
+            web_edge_radius: 0.0,
+        };
+        let sharp = TShape {
+            fillet_radius: 0.0,
+            ..t
+        }
+        .outline(&tess)
+        .unwrap();
+        let rounded = t.outline(&tess).unwrap();
+
+        assert_eq!(sharp.outer.len(), 8);
+        assert!(rounded.outer.len() > 8);
+        // Root fillets add material: 2 * (r^2 - pi r^2 / 4)
+        let fillet_area = 2.0 * 64.0 * (1.0 - std::f64::consts::FRAC_PI_4);
+        let gained = compute_signed_area(&rounded.outer) - compute_signed_area(&sharp.outer);
+        assert!(gained > 0.0 && (gained - fillet_area).abs() < fillet_area * 0.02);
+        // Bounding box centred on the origin
+        let max_y = rounded.outer.iter().map(|p| p.y).fold(f64::MIN, f64::max);
+        assert!((max_y - 50.0).abs() < 1e-9);
+
+        let i = AsymmetricIShape {
+            overall_depth: 300.0,
+            web_thickness: 8.0,
+            bottom_flange_width: 200.0,
+            bottom_flange_thickness: 15.0,
+            bottom_flange_fillet_radius: 10.0,
+            bottom_flange_edge_radius: 0.0,
+            top_flange_width: 120.0,
+            top_flange_thickness: 12.0,
+            top_flange_fillet_radius: 10.0,
+            top_flange_edge_radius: 0.0,
+        }
+        .outline(&tess)
+        .unwrap();
+        assert!(compute_signed_area(&i.outer) > 200.0 * 15.0 + 120.0 * 12.0 + 273.0 * 8.0);
+        let max_x = i.outer.iter().map(|p| p.x).fold(f64::MIN, f64::max);
+        assert!((max_x - 100.0).abs() < 1e-9);
+    }
+}
@@ -6,7 +6,13 @@
 //!
 //! Dynamic profile processing for parametric, arbitrary, and composite profiles.
 
+use crate::alignment::curve_segment_points;
+use crate::bspline::BSplineCurve;
+use crate::offset_curve::{offset_polyline_2d, offset_polyline_3d};
 use crate::profile::Profile2D;
+use crate::profile_shapes::{trapezium, AsymmetricIShape, CShape, TShape, ZShape};
+use crate::tessellation::TessellationConfig;
+use crate::transform::parse_direction;
 use crate::{Error, Point2, Point3, Result, Vector3};
 use ifc_lite_core::{
     AttributeValue, DecodedEntity, EntityDecoder, IfcSchema, IfcType, ProfileCategory,
@@ -15,7 +21,7 @@ use std::f64::consts::PI;
 
 /// Maximum recursion depth for nested curve processing.
 /// Prevents stack overflow from deeply nested CompositeCurve → TrimmedCurve → CompositeCurve chains.
-const MAX_CURVE_DEPTH: u32 = 50;
+pub(crate) const MAX_CURVE_DEPTH: u32 = 50;
 
 /// Maximum recursion depth for nested profile definitions (DerivedProfile → parent → parent...).
 /// Prevents stack overflow in WASM from Revit exports with deep profile nesting.
@@ -24,12 +30,26 @@ const MAX_PROFILE_DEPTH: u32 = 16;
 /// Profile processor - processes IFC profiles into 2D contours
 pub struct ProfileProcessor {
     schema: IfcSchema,
+    tessellation: TessellationConfig,
 }
 
 impl ProfileProcessor {
     /// Create new profile processor
     pub fn new(schema: IfcSchema) -> Self {
-        Self { schema }
+        Self::with_tessellation(schema, TessellationConfig::default())
+    }
+
+    /// Create profile processor with custom curve tessellation quality
+    pub fn with_tessellation(schema: IfcSchema, tessellation: TessellationConfig) -> Self {
+        Self {
+            schema,
+            tessellation,
+        }
+    }
+
+    /// Curve tessellation quality used for circles, ellipses and arcs
+    pub fn tessellation(&self) -> &TessellationConfig {
+        &self.tessellation
     }
 
     /// Process any IFC profile definition
@@ -90,6 +110,8 @@ impl ProfileProcessor {
             IfcType::IfcTShapeProfileDef => self.process_t_shape(profile),
             IfcType::IfcCShapeProfileDef => self.process_c_shape(profile),
             IfcType::IfcZShapeProfileDef => self.process_z_shape(profile),
+            IfcType::IfcAsymmetricIShapeProfileDef => self.process_asymmetric_i_shape(profile),
+            IfcType::IfcTrapeziumProfileDef => self.process_trapezium(profile),
             _ => Err(Error::geometry(format!(
                 "Unsupported parametric profile: {}",
                 profile.ifc_type
@@ -219,8 +241,15 @@ impl ProfileProcessor {
 
         let mut result = self.process_with_depth(&parent_profile, decoder, depth + 1)?;
 
-        let operator_attr = profile
-            .get(3)
+        // IfcMirroredProfileDef derives its Operator (`*`): mirror about the
+        // profile's Y axis
+        let operator_attr = profile.get(3).filter(|attr| !attr.is_null());
+        if operator_attr.is_none() && profile.ifc_type == IfcType::IfcMirroredProfileDef {
+            Self::apply_affine_2d(&mut result, (-1.0, 0.0), (0.0, 1.0), (0.0, 0.0));
+            return Ok(result);
+        }
+
+        let operator_attr = operator_attr
             .ok_or_else(|| Error::geometry("Derived profile missing Operator".to_string()))?;
         let operator = decoder
             .resolve_ref(operator_attr)?
@@ -230,7 +259,8 @@ impl ProfileProcessor {
         Ok(result)
     }
 
-    /// Apply IfcCartesianTransformationOperator2D to all profile contours.
+    /// Apply IfcCartesianTransformationOperator2D (or 2DnonUniform) to all profile contours.
+    /// Attributes: Axis1, Axis2, LocalOrigin, Scale[, Scale2]
     fn apply_cartesian_transformation_operator_2d(
         &self,
         profile: &mut Profile2D,
@@ -267,41 +297,57 @@ impl ProfileProcessor {
         let axis1 = self.parse_operator_axis_2d(operator.get(0), decoder, (1.0, 0.0))?;
         let axis2 = self.parse_operator_axis_2d(operator.get(1), decoder, (0.0, 1.0))?;
 
+        // IfcBaseAxis: Axis1 fixes U1, U2 is its orthogonal complement flipped
+        // towards Axis2 (this is how mirroring is expressed); with only Axis2,
+        // U1 is the negated complement of U2
         let (x_axis, y_axis) = match (axis1, axis2) {
-            (Some(x_axis), Some(y_axis)) => (x_axis, y_axis),
-            (Some(x_axis), None) => (x_axis, (-x_axis.1, x_axis.0)),
+            (Some(x_axis), axis2) => {
+                let complement = (-x_axis.1, x_axis.0);
+                let flip = axis2
+                    .map(|a| a.0 * complement.0 + a.1 * complement.1 < 0.0)
+                    .unwrap_or(false);
+                if flip {
+                    (x_axis, (-complement.0, -complement.1))
+                } else {
+                    (x_axis, complement)
+                }
+            }
             (None, Some(y_axis)) => ((y_axis.1, -y_axis.0), y_axis),
             (None, None) => ((1.0, 0.0), (0.0, 1.0)),
         };
 
-        for point in &mut profile.outer {
-            let old_x = point.x;
-            let old_y = point.y;
-            point.x = old_x * x_axis.0 * scale_x + old_y * y_axis.0 * scale_y + origin_x;
-            point.y = old_x * x_axis.1 * scale_x + old_y * y_axis.1 * scale_y + origin_y;
-        }
+        Self::apply_affine_2d(
+            profile,
+            (x_axis.0 * scale_x, x_axis.1 * scale_x),
+            (y_axis.0 * scale_y, y_axis.1 * scale_y),
+            (origin_x, origin_y),
+        );
+        Ok(())
+    }
 
-        for hole in &mut profile.holes {
-            for point in hole {
-                let old_x = point.x;
-                let old_y = point.y;
-                point.x = old_x * x_axis.0 * scale_x + old_y * y_axis.0 * scale_y + origin_x;
-                point.y = old_x * x_axis.1 * scale_x + old_y * y_axis.1 * scale_y + origin_y;
-            }
+    /// Map every contour point p to `origin + p.x * x_col + p.y * y_col`.
+    fn apply_affine_2d(
+        profile: &mut Profile2D,
+        x_col: (f64, f64),
+        y_col: (f64, f64),
+        origin: (f64, f64),
+    ) {
+        let contours = std::iter::once(&mut profile.outer).chain(profile.holes.iter_mut());
+        for point in contours.flatten() {
+            let (old_x, old_y) = (point.x, point.y);
+            point.x = old_x * x_col.0 + old_y * y_col.0 + origin.0;
+            point.y = old_x * x_col.1 + old_y * y_col.1 + origin.1;
         }
 
         // If the transformation reverses orientation (negative determinant),
         // the winding order of contours is flipped. Reverse them so that
         // extrusion normals point outward correctly.
-        let det = scale_x * scale_y * (x_axis.0 * y_axis.1 - y_axis.0 * x_axis.1);
-        if det < 0.0 {
+        if x_col.0 * y_col.1 - y_col.0 * x_col.1 < 0.0 {
             profile.outer.reverse();
             for hole in &mut profile.holes {
                 hole.reverse();
             }
         }
-
-        Ok(())
     }
 
     fn parse_operator_axis_2d(
@@ -375,8 +421,7 @@ impl ProfileProcessor {
             .get_float(3)
             .ok_or_else(|| Error::geometry("Circle missing Radius".to_string()))?;
 
-        // Generate circle with 36 segments for smooth appearance
-        let segments = 36;
+        let segments = self.tessellation.circle_segments(radius);
         let mut points = Vec::with_capacity(segments);
 
         for i in 0..segments {
@@ -389,8 +434,8 @@ impl ProfileProcessor {
         Ok(Profile2D::new(points))
     }
 
-    /// Process I-shape profile (simplified - basic I-beam)
-    /// IfcIShapeProfileDef: ProfileType, ProfileName, Position, OverallWidth, OverallDepth, WebThickness, FlangeThickness, ...
+    /// Process I-shape profile
+    /// IfcIShapeProfileDef: ProfileType, ProfileName, Position, OverallWidth, OverallDepth, WebThickness, FlangeThickness, FilletRadius, FlangeEdgeRadius, FlangeSlope
     fn process_i_shape(&self, profile: &DecodedEntity) -> Result<Profile2D> {
         // Get dimensions
         let overall_width = profile
@@ -405,32 +450,86 @@ impl ProfileProcessor {
         let flange_thickness = profile
             .get_float(6)
             .ok_or_else(|| Error::geometry("I-Shape missing FlangeThickness".to_string()))?;
+        let fillet_radius = profile.get_float(7).unwrap_or(0.0);
+        let edge_radius = profile.get_float(8).unwrap_or(0.0);
+
+        AsymmetricIShape {
+            overall_depth,
+            web_thickness,
+            bottom_flange_width: overall_width,
+            bottom_flange_thickness: flange_thickness,
+            bottom_flange_fillet_radius: fillet_radius,
+            bottom_flange_edge_radius: edge_radius,
+            top_flange_width: overall_width,
+            top_flange_thickness: flange_thickness,
+            top_flange_fillet_radius: fillet_radius,
+            top_flange_edge_radius: edge_radius,
+        }
+        .outline(&self.tessellation)
+    }
 
-        let half_width = overall_width / 2.0;
-        let half_depth = overall_depth / 2.0;
-        let half_web = web_thickness / 2.0;
+    /// Process asymmetric I-shape profile (flange slopes are ignored)
+    /// IfcAsymmetricIShapeProfileDef: ProfileType, ProfileName, Position, BottomFlangeWidth, OverallDepth, WebThickness, BottomFlangeThickness, BottomFlangeFilletRadius, TopFlangeWidth, TopFlangeThickness, TopFlangeFilletRadius, BottomFlangeEdgeRadius, BottomFlangeSlope, TopFlangeEdgeRadius, TopFlangeSlope
+    /// (IFC2X3 names the first attributes OverallWidth, FlangeThickness and FilletRadius; positions match)
+    fn process_asymmetric_i_shape(&self, profile: &DecodedEntity) -> Result<Profile2D> {
+        let bottom_flange_width = profile.get_float(3).ok_or_else(|| {
+            Error::geometry("AsymmetricI-Shape missing BottomFlangeWidth".to_string())
+        })?;
+        let overall_depth = profile
+            .get_float(4)
+            .ok_or_else(|| Error::geometry("AsymmetricI-Shape missing OverallDepth".to_string()))?;
+        let web_thickness = profile
+            .get_float(5)
+            .ok_or_else(|| Error::geometry("AsymmetricI-Shape missing WebThickness".to_string()))?;
+        let bottom_flange_thickness = profile.get_float(6).ok_or_else(|| {
+            Error::geometry("AsymmetricI-Shape missing BottomFlangeThickness".to_string())
+        })?;
+        let bottom_flange_fillet_radius = profile.get_float(7).unwrap_or(0.0);
+        let top_flange_width = profile.get_float(8).unwrap_or(bottom_flange_width);
+        let top_flange_thickness = profile.get_float(9).unwrap_or(bottom_flange_thickness);
+        let top_flange_fillet_radius = profile.get_float(10).unwrap_or(bottom_flange_fillet_radius);
+        // Edge radii only exist in IFC4; IFC2X3 has CentreOfGravityInY at index 11
+        let is_ifc4 = profile.attributes.len() > 12;
+        let edge_radius = |index| {
+            if is_ifc4 {
+                profile.get_float(index).unwrap_or(0.0)
+            } else {
+                0.0
+            }
+        };
 
-        // Create I-shape profile (counter-clockwise from bottom-left)
-        let points = vec![
-            // Bottom flange
-            Point2::new(-half_width, -half_depth),
-            Point2::new(half_width, -half_depth),
-            Point2::new(half_width, -half_depth + flange_thickness),
-            // Right side of web
-            Point2::new(half_web, -half_depth + flange_thickness),
-            Point2::new(half_web, half_depth - flange_thickness),
-            // Top flange
-            Point2::new(half_width, half_depth - flange_thickness),
-            Point2::new(half_width, half_depth),
-            Point2::new(-half_width, half_depth),
-            Point2::new(-half_width, half_depth - flange_thickness),
-            // Left side of web
-            Point2::new(-half_web, half_depth - flange_thickness),
-            Point2::new(-half_web, -half_depth + flange_thickness),
-            Point2::new(-half_width, -half_depth + flange_thickness),
-        ];
+        AsymmetricIShape {
+            overall_depth,
+            web_thickness,
+            bottom_flange_width,
+            bottom_flange_thickness,
+            bottom_flange_fillet_radius,
+            bottom_flange_edge_radius: edge_radius(11),
+            top_flange_width,
+            top_flange_thickness,
+            top_flange_fillet_radius,
+            top_flange_edge_radius: edge_radius(13),
+        }
+        .outline(&self.tessellation)
+    }
 
-        Ok(Profile2D::new(points))
+    /// Process trapezium profile
+    /// IfcTrapeziumProfileDef: ProfileType, ProfileName, Position, BottomXDim, TopXDim, YDim, TopXOffset
+    fn process_trapezium(&self, profile: &DecodedEntity) -> Result<Profile2D> {
+        let bottom_x_dim = profile
+            .get_float(3)
+            .ok_or_else(|| Error::geometry("Trapezium missing BottomXDim".to_string()))?;
+        let top_x_dim = profile
+            .get_float(4)
+            .ok_or_else(|| Error::geometry("Trapezium missing TopXDim".to_string()))?;
+        let y_dim = profile
+            .get_float(5)
+            .ok_or_else(|| Error::geometry("Trapezium missing YDim".to_string()))?;
+        let top_x_offset = profile
+            .get_float(6)
+            .ok_or_else(|| Error::geometry("Trapezium missing TopXOffset".to_string()))?;
+
+        trapezium(bottom_x_dim, top_x_dim, y_dim, top_x_offset)
     }
 
     /// Process circle hollow profile (tube/pipe)
@@ -444,7 +543,7 @@ impl ProfileProcessor {
             .ok_or_else(|| Error::geometry("CircleHollow missing WallThickness".to_string()))?;
 
         let inner_radius = radius - wall_thickness;
-        let segments = 36;
+        let segments = self.tessellation.circle_segments(radius);
 
         // Outer circle
         let mut outer_points = Vec::with_capacity(segments);
@@ -575,8 +674,8 @@ impl ProfileProcessor {
         Ok(Profile2D::new(points))
     }
 
-    /// Process T-shape profile
-    /// IfcTShapeProfileDef: ProfileType, ProfileName, Position, Depth, FlangeWidth, WebThickness, FlangeThickness, ...
+    /// Process T-shape profile (web and flange slopes are ignored)
+    /// IfcTShapeProfileDef: ProfileType, ProfileName, Position, Depth, FlangeWidth, WebThickness, FlangeThickness, FilletRadius, FlangeEdgeRadius, WebEdgeRadius, WebSlope, FlangeSlope
     fn process_t_shape(&self, profile: &DecodedEntity) -> Result<Profile2D> {
         let depth = profile
             .get_float(3)
@@ -591,57 +690,46 @@ impl ProfileProcessor {
             .get_float(6)
             .ok_or_else(|| Error::geometry("T-Shape missing FlangeThickness".to_string()))?;
 
-        let half_flange = flange_width / 2.0;
-        let half_web = web_thickness / 2.0;
-
-        // T-shape profile (counter-clockwise)
-        let points = vec![
-            Point2::new(-half_web, 0.0),
-            Point2::new(-half_web, depth - flange_thickness),
-            Point2::new(-half_flange, depth - flange_thickness),
-            Point2::new(-half_flange, depth),
-            Point2::new(half_flange, depth),
-            Point2::new(half_flange, depth - flange_thickness),
-            Point2::new(half_web, depth - flange_thickness),
-            Point2::new(half_web, 0.0),
-        ];
-
-        Ok(Profile2D::new(points))
+        TShape {
+            depth,
+            flange_width,
+            web_thickness,
+            flange_thickness,
+            fillet_radius: profile.get_float(7).unwrap_or(0.0),
+            flange_edge_radius: profile.get_float(8).unwrap_or(0.0),
+            web_edge_radius: profile.get_float(9).unwrap_or(0.0),
+        }
+        .outline(&self.tessellation)
     }
 
     /// Process C-shape profile (channel with lips)
-    /// IfcCShapeProfileDef: ProfileType, ProfileName, Position, Depth, Width, WallThickness, Girth, ...
+    /// IfcCShapeProfileDef: ProfileType, ProfileName, Position, Depth, Width, WallThickness, Girth, InternalFilletRadius
     fn process_c_shape(&self, profile: &DecodedEntity) -> Result<Profile2D> {
         let depth = profile
             .get_float(3)
             .ok_or_else(|| Error::geometry("C-Shape missing Depth".to_string()))?;
-        let _width = profile
+        let width = profile
             .get_float(4)
             .ok_or_else(|| Error::geometry("C-Shape missing Width".to_string()))?;
         let wall_thickness = profile
             .get_float(5)
             .ok_or_else(|| Error::geometry("C-Shape missing WallThickness".to_string()))?;
-        let girth = profile.get_float(6).unwrap_or(wall_thickness * 2.0); // Lip length
-
-        let half_depth = depth / 2.0;
-
-        // C-shape profile (counter-clockwise)
-        let points = vec![
-            Point2::new(girth, -half_depth),
-            Point2::new(0.0, -half_depth),
-            Point2::new(0.0, half_depth),
-            Point2::new(girth, half_depth),
-            Point2::new(girth, half_depth - wall_thickness),
-            Point2::new(wall_thickness, half_depth - wall_thickness),
-            Point2::new(wall_thickness, -half_depth + wall_thickness),
-            Point2::new(girth, -half_depth + wall_thickness),
-        ];
-
-        Ok(Profile2D::new(points))
+        let girth = profile
+            .get_float(6)
+            .ok_or_else(|| Error::geometry("C-Shape missing Girth".to_string()))?;
+
+        CShape {
+            depth,
+            width,
+            wall_thickness,
+            girth,
+            internal_fillet_radius: profile.get_float(7).unwrap_or(0.0),
+        }
+        .outline(&self.tessellation)
     }
 
     /// Process Z-shape profile
-    /// IfcZShapeProfileDef: ProfileType, ProfileName, Position, Depth, FlangeWidth, WebThickness, FlangeThickness, ...
+    /// IfcZShapeProfileDef: ProfileType, ProfileName, Position, Depth, FlangeWidth, WebThickness, FlangeThickness, FilletRadius, EdgeRadius
     fn process_z_shape(&self, profile: &DecodedEntity) -> Result<Profile2D> {
         let depth = profile
             .get_float(3)
@@ -656,26 +744,15 @@ impl ProfileProcessor {
             .get_float(6)
             .ok_or_else(|| Error::geometry("Z-Shape missing FlangeThickness".to_string()))?;
 
-        let half_depth = depth / 2.0;
-        let half_web = web_thickness / 2.0;
-
-        // Z-shape profile (counter-clockwise)
-        let points = vec![
-            Point2::new(-half_web, -half_depth),
-            Point2::new(-half_web - flange_width, -half_depth),
-            Point2::new(-half_web - flange_width, -half_depth + flange_thickness),
-            Point2::new(-half_web, -half_depth + flange_thickness),
-            Point2::new(-half_web, half_depth - flange_thickness),
-            Point2::new(half_web, half_depth - flange_thickness),
-            Point2::new(half_web, half_depth),
-            Point2::new(half_web + flange_width, half_depth),
-            Point2::new(half_web + flange_width, half_depth - flange_thickness),
-            Point2::new(half_web, half_depth - flange_thickness),
-            Point2::new(half_web, -half_depth + flange_thickness),
-            Point2::new(-half_web, -half_depth + flange_thickness),
-        ];
-
-        Ok(Profile2D::new(points))
+        ZShape {
+            depth,
+            flange_width,
+            web_thickness,
+            flange_thickness,
+            fillet_radius: profile.get_float(7).unwrap_or(0.0),
+            edge_radius: profile.get_float(8).unwrap_or(0.0),
+        }
+        .outline(&self.tessellation)
     }
 
     /// Process arbitrary closed profile (polyline-based)
@@ -748,6 +825,10 @@ impl ProfileProcessor {
             }
             IfcType::IfcCircle => self.process_circle_curve(curve, decoder),
             IfcType::IfcEllipse => self.process_ellipse_curve(curve, decoder),
+            IfcType::IfcBSplineCurveWithKnots | IfcType::IfcRationalBSplineCurveWithKnots => {
+                self.process_bspline_curve(curve, decoder)
+            }
+            IfcType::IfcOffsetCurve2D => self.process_offset_curve_2d(curve, decoder, depth),
             _ => Err(Error::geometry(format!(
                 "Unsupported curve type: {}",
                 curve.ifc_type
@@ -766,7 +847,7 @@ impl ProfileProcessor {
     }
 
     /// Get 3D curve points with depth tracking to prevent stack overflow
-    fn get_curve_points_with_depth(
+    pub(crate) fn get_curve_points_with_depth(
         &self,
         curve: &DecodedEntity,
         decoder: &mut EntityDecoder,
@@ -780,10 +861,19 @@ impl ProfileProcessor {
         }
         match curve.ifc_type {
             IfcType::IfcPolyline => self.process_polyline_3d(curve, decoder),
+            IfcType::IfcIndexedPolyCurve => self.process_indexed_polycurve_3d(curve, decoder),
             IfcType::IfcCompositeCurve => {
                 self.process_composite_curve_3d_with_depth(curve, decoder, depth)
             }
             IfcType::IfcCircle => self.process_circle_3d(curve, decoder),
+            IfcType::IfcOffsetCurve3D => self.process_offset_curve_3d(curve, decoder, depth),
+            IfcType::IfcBSplineCurveWithKnots | IfcType::IfcRationalBSplineCurveWithKnots => {
+                Ok(BSplineCurve::from_entity(curve, decoder)?.tessellate(&self.tessellation))
+            }
+            IfcType::IfcGradientCurve | IfcType::IfcSegmentedReferenceCurve => Ok(self
+                .alignment_curve_with_depth(curve, decoder, depth)?
+                .points()
+                .to_vec()),
             IfcType::IfcTrimmedCurve => {
                 // For trimmed curve, get 2D points and convert to 3D
                 let points_2d = self.process_trimmed_curve_with_depth(curve, decoder, depth)?;
@@ -926,7 +1016,7 @@ impl ProfileProcessor {
         };
 
         // Generate circle points in 3D
-        let segments = 24usize;
+        let segments = self.tessellation.circle_segments(radius);
         let mut points = Vec::with_capacity(segments + 1);
 
         for i in 0..=segments {
@@ -972,6 +1062,66 @@ impl ProfileProcessor {
         Ok(result)
     }
 
+    /// Process indexed polycurve into 3D points. Curves over an
+    /// IfcCartesianPointList2D go through the 2D path (arcs tessellated) and
+    /// lie in z = 0; for 3D point lists the segment points are joined with
+    /// straight lines.
+    fn process_indexed_polycurve_3d(
+        &self,
+        curve: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Vec<Point3<f64>>> {
+        // IfcIndexedPolyCurve: Points, Segments (optional), SelfIntersect
+        let points_attr = curve
+            .get(0)
+            .ok_or_else(|| Error::geometry("IndexedPolyCurve missing Points".to_string()))?;
+        let points_list = decoder
+            .resolve_ref(points_attr)?
+            .ok_or_else(|| Error::geometry("Failed to resolve Points list".to_string()))?;
+
+        if points_list.ifc_type != IfcType::IfcCartesianPointList3D {
+            return Ok(self
+                .process_indexed_polycurve(curve, decoder)?
+                .into_iter()
+                .map(|p| Point3::new(p.x, p.y, 0.0))
+                .collect());
+        }
+
+        let coord_list = points_list
+            .get(0)
+            .and_then(|attr| attr.as_list())
+            .ok_or_else(|| Error::geometry("CartesianPointList3D missing CoordList".to_string()))?;
+        let all_points: Vec<Point3<f64>> = AttributeValue::parse_coordinate_list_3d_f64(coord_list)
+            .into_iter()
+            .map(|(x, y, z)| Point3::new(x, y, z))
+            .collect();
+
+        let Some(segments) = curve.get(1).and_then(|attr| attr.as_list()) else {
+            return Ok(all_points);
+        };
+
+        let mut result = Vec::new();
+        for segment in segments {
+            // IFCLINEINDEX((i1,i2,...)) / IFCARCINDEX((i1,i2,i3)), stored as
+            // List([String(type_name), List(indices)])
+            let Some(segment_list) = segment.as_list() else {
+                continue;
+            };
+            let indices = match segment_list.get(1) {
+                Some(AttributeValue::List(indices)) => indices.as_slice(),
+                _ => segment_list,
+            };
+            for index in indices.iter().filter_map(|v| v.as_float()) {
+                if let Some(&point) = all_points.get((index as usize).wrapping_sub(1)) {
+                    if result.last() != Some(&point) {
+                        result.push(point);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
     /// Process composite curve into 3D points
     fn process_composite_curve_3d_with_depth(
         &self,
@@ -988,6 +1138,19 @@ impl ProfileProcessor {
         let mut result = Vec::new();
 
         for segment in segments {
+            // IFC4X3 alignment segments carry their own placement
+            if segment.ifc_type == IfcType::IfcCurveSegment {
+                let points = curve_segment_points(&segment, decoder, &self.tessellation)?;
+                let skip = usize::from(!result.is_empty());
+                result.extend(
+                    points
+                        .into_iter()
+                        .skip(skip)
+                        .map(|p| Point3::new(p.x, p.y, 0.0)),
+                );
+                continue;
+            }
+
             // IfcCompositeCurveSegment: Transition, SameSense, ParentCurve
             let parent_curve_attr = segment.get(2).ok_or_else(|| {
                 Error::geometry("CompositeCurveSegment missing ParentCurve".to_string())
@@ -1120,10 +1283,12 @@ impl ProfileProcessor {
             end_angle -= 2.0 * std::f64::consts::PI;
         }
 
-        // Calculate arc angle and adaptive segment count
-        // Use ~8 segments per 90° (quarter circle), minimum 2
+        // Calculate arc angle and adaptive segment count, minimum 2
         let arc_angle = (end_angle - start_angle).abs();
-        let num_segments = ((arc_angle / std::f64::consts::FRAC_PI_2 * 8.0).ceil() as usize).max(2);
+        let num_segments = self
+            .tessellation
+            .arc_segments(radius.max(radius2), arc_angle)
+            .max(2);
         let mut points = Vec::with_capacity(num_segments + 1);
 
         let angle_range = if sense {
@@ -1215,7 +1380,7 @@ impl ProfileProcessor {
         let radius = curve.get_float(1).unwrap_or(1.0);
         let (center, rotation) = self.get_placement_2d(curve, decoder)?;
 
-        let segments = 36;
+        let segments = self.tessellation.circle_segments(radius);
         let mut points = Vec::with_capacity(segments);
 
         for i in 0..segments {
@@ -1242,7 +1407,9 @@ impl ProfileProcessor {
         let semi_axis2 = curve.get_float(2).unwrap_or(1.0);
         let (center, rotation) = self.get_placement_2d(curve, decoder)?;
 
-        let segments = 36;
+        let segments = self
+            .tessellation
+            .circle_segments(semi_axis1.max(semi_axis2));
         let mut points = Vec::with_capacity(segments);
 
         for i in 0..segments {
@@ -1259,6 +1426,50 @@ impl ProfileProcessor {
         Ok(points)
     }
 
+    /// Process B-spline curve into 2D points
+    /// IfcBSplineCurveWithKnots / IfcRationalBSplineCurveWithKnots
+    fn process_bspline_curve(
+        &self,
+        curve: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Vec<Point2<f64>>> {
+        Ok(BSplineCurve::from_entity(curve, decoder)?
+            .tessellate(&self.tessellation)
+            .into_iter()
+            .map(|p| Point2::new(p.x, p.y))
+            .collect())
+    }
+
+    /// Process offset curve into 2D points
+    /// IfcOffsetCurve2D: BasisCurve, Distance, SelfIntersect
+    fn process_offset_curve_2d(
+        &self,
+        curve: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        depth: u32,
+    ) -> Result<Vec<Point2<f64>>> {
+        let (basis, distance) = offset_curve_basis(curve, decoder)?;
+        let points = self.process_curve_with_depth(&basis, decoder, depth + 1)?;
+        Ok(offset_polyline_2d(&points, distance))
+    }
+
+    /// Process offset curve into 3D points
+    /// IfcOffsetCurve3D: BasisCurve, Distance, SelfIntersect, RefDirection
+    fn process_offset_curve_3d(
+        &self,
+        curve: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        depth: u32,
+    ) -> Result<Vec<Point3<f64>>> {
+        let (basis, distance) = offset_curve_basis(curve, decoder)?;
+        let ref_direction = match curve.get_ref(3) {
+            Some(direction_id) => parse_direction(&decoder.decode_by_id(direction_id)?)?,
+            None => Vector3::z(),
+        };
+        let points = self.get_curve_points_with_depth(&basis, decoder, depth + 1)?;
+        Ok(offset_polyline_3d(&points, distance, &ref_direction))
+    }
+
     /// Process polyline into 2D points
     /// IfcPolyline: Points (list of IfcCartesianPoint)
     #[inline]
@@ -1394,21 +1605,12 @@ impl ProfileProcessor {
 
                     if let (Some(start), Some(mid), Some(end)) = (p1, p2, p3) {
                         // Approximate arc with adaptive segment count based on arc size
-                        // Calculate approximate arc angle from chord length vs radius
-                        let chord_len =
-                            ((end.x - start.x).powi(2) + (end.y - start.y).powi(2)).sqrt();
-                        let mid_chord = ((mid.x - (start.x + end.x) / 2.0).powi(2)
-                            + (mid.y - (start.y + end.y) / 2.0).powi(2))
-                        .sqrt();
-                        // Estimate arc angle: larger mid deviation = larger arc
-                        let arc_estimate = if chord_len > 1e-10 {
-                            (mid_chord / chord_len).abs().min(1.0).acos() * 2.0
-                        } else {
-                            0.5
+                        let num_segments = match arc_radius_and_sweep(start, mid, end) {
+                            Some((radius, sweep)) => {
+                                self.tessellation.arc_segments(radius, sweep).max(2)
+                            }
+                            None => 2,
                         };
-                        let num_segments = ((arc_estimate / std::f64::consts::FRAC_PI_2 * 8.0)
-                            .ceil() as usize)
-                            .clamp(4, 16);
                         let arc_points = self.approximate_arc_3pt(start, mid, end, num_segments);
                         for pt in arc_points {
                             if result_points.last() != Some(&pt) {
@@ -1548,6 +1750,16 @@ impl ProfileProcessor {
         let mut all_points = Vec::new();
 
         for segment in segments {
+            // IFC4X3 alignment segments carry their own placement
+            if segment.ifc_type == IfcType::IfcCurveSegment {
+                for pt in curve_segment_points(&segment, decoder, &self.tessellation)? {
+                    if all_points.last() != Some(&pt) {
+                        all_points.push(pt);
+                    }
+                }
+                continue;
+            }
+
             // IfcCompositeCurveSegment: Transition, SameSense, ParentCurve
             if segment.ifc_type != IfcType::IfcCompositeCurveSegment {
                 continue;
@@ -1625,6 +1837,40 @@ impl ProfileProcessor {
     }
 }
 
+/// Radius and swept angle of the circular arc through three points,
+/// or `None` if they are collinear.
+fn arc_radius_and_sweep(
+    start: Point2<f64>,
+    mid: Point2<f64>,
+    end: Point2<f64>,
+) -> Option<(f64, f64)> {
+    let a = (mid - start).norm();
+    let b = (end - mid).norm();
+    let c = (end - start).norm();
+    let cross = (mid - start).perp(&(end - start)).abs();
+    if cross < 1e-12 {
+        return None;
+    }
+    let radius = a * b * c / (2.0 * cross);
+    // Each half-arc subtends twice the half-chord angle
+    let half_angle = |chord: f64| 2.0 * (chord / (2.0 * radius)).min(1.0).asin();
+    Some((radius, half_angle(a) + half_angle(b)))
+}
+
+/// BasisCurve and Distance of an IfcOffsetCurve2D / IfcOffsetCurve3D
+fn offset_curve_basis(
+    curve: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+) -> Result<(DecodedEntity, f64)> {
+    let basis_id = curve
+        .get_ref(0)
+        .ok_or_else(|| Error::geometry("OffsetCurve missing BasisCurve".to_string()))?;
+    let distance = curve
+        .get_float(1)
+        .ok_or_else(|| Error::geometry("OffsetCurve missing Distance".to_string()))?;
+    Ok((decoder.decode_by_id(basis_id)?, distance))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1680,6 +1926,35 @@ mod tests {
         assert!(!profile.outer.is_empty());
     }
 
+    #[test]
+    fn test_asymmetric_i_and_trapezium_profiles() {
+        let content = r#"
+#1=IFCASYMMETRICISHAPEPROFILEDEF(.AREA.,$,$,200.0,300.0,8.0,15.0,$,120.0,12.0,$,$,$,$,$);
+#2=IFCTRAPEZIUMPROFILEDEF(.AREA.,$,$,100.0,60.0,50.0,10.0);
+"#;
+
+        let mut decoder = EntityDecoder::new(content);
+        let processor = ProfileProcessor::new(IfcSchema::new());
+
+        let entity = decoder.decode_by_id(1).unwrap();
+        let profile = processor.process(&entity, &mut decoder).unwrap();
+        assert_eq!(profile.outer.len(), 12);
+        let max_y = profile.outer.iter().map(|p| p.y).fold(f64::MIN, f64::max);
+        let top_width = profile
+            .outer
+            .iter()
+            .filter(|p| (p.y - max_y).abs() < 1e-9)
+            .map(|p| p.x.abs() * 2.0)
+            .fold(0.0, f64::max);
+        assert!((max_y - 150.0).abs() < 1e-9);
+        assert!((top_width - 120.0).abs() < 1e-9);
+
+        let entity = decoder.decode_by_id(2).unwrap();
+        let profile = processor.process(&entity, &mut decoder).unwrap();
+        assert_eq!(profile.outer.len(), 4);
+        assert!((profile.outer[2].x - 20.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_arbitrary_profile() {
         let content = r#"
@@ -1702,6 +1977,84 @@ mod tests {
         assert!(!profile.outer.is_empty());
     }
 
+    #[test]
+    fn test_bspline_profile_and_directrix() {
+        let content = r#"
+#1=IFCCARTESIANPOINT((2.0,0.0));
+#2=IFCCARTESIANPOINT((1.0,2.0));
+#3=IFCCARTESIANPOINT((0.0,0.0));
+#4=IFCBSPLINECURVEWITHKNOTS(2,(#1,#2,#3),.UNSPECIFIED.,.F.,.F.,(3,3),(0.0,1.0),.UNSPECIFIED.);
+#5=IFCPOLYLINE((#3,#1));
+#6=IFCCOMPOSITECURVESEGMENT(.CONTINUOUS.,.T.,#4);
+#7=IFCCOMPOSITECURVESEGMENT(.CONTINUOUS.,.T.,#5);
+#8=IFCCOMPOSITECURVE((#6,#7),.F.);
+#9=IFCARBITRARYCLOSEDPROFILEDEF(.AREA.,$,#8);
+#10=IFCCARTESIANPOINT((0.0,0.0,0.0));
+#11=IFCCARTESIANPOINT((1.0,0.0,1.0));
+#12=IFCCARTESIANPOINT((1.0,1.0,2.0));
+#13=IFCRATIONALBSPLINECURVEWITHKNOTS(2,(#10,#11,#12),.UNSPECIFIED.,.F.,.F.,(3,3),(0.0,1.0),.UNSPECIFIED.,(1.0,0.5,1.0));
+"#;
+
+        let mut decoder = EntityDecoder::new(content);
+        let processor = ProfileProcessor::new(IfcSchema::new());
+
+        // Parabolic arch over a 2 m base, 1 m high: area = 2/3 * 2 * 1
+        let profile_entity = decoder.decode_by_id(9).unwrap();
+        let profile = processor.process(&profile_entity, &mut decoder).unwrap();
+        assert!(
+            profile.outer.len() > 5,
+            "curve degraded to its control polygon"
+        );
+        let area = crate::bool2d::compute_signed_area(&profile.outer).abs();
+        assert!((area - 4.0 / 3.0).abs() < 0.01, "area {area}");
+
+        // 3D directrix keeps its elevation and ends on the end control points
+        let directrix = decoder.decode_by_id(13).unwrap();
+        let points = processor
+            .get_curve_points(&directrix, &mut decoder)
+            .unwrap();
+        assert_eq!(points.first(), Some(&Point3::new(0.0, 0.0, 0.0)));
+        assert_eq!(points.last(), Some(&Point3::new(1.0, 1.0, 2.0)));
+        assert!(points.iter().all(|p| p.z >= 0.0 && p.z <= 2.0));
+    }
+
+    #[test]
+    fn test_offset_curve_profile_and_directrix() {
+        let content = r#"
+#1=IFCCARTESIANPOINT((0.0,0.0));
+#2=IFCCARTESIANPOINT((4.0,0.0));
+#3=IFCCARTESIANPOINT((4.0,2.0));
+#4=IFCCARTESIANPOINT((0.0,2.0));
+#5=IFCPOLYLINE((#1,#2,#3,#4,#1));
+#6=IFCOFFSETCURVE2D(#5,-0.5,.F.);
+#7=IFCARBITRARYCLOSEDPROFILEDEF(.AREA.,$,#6);
+#8=IFCCARTESIANPOINT((0.0,0.0,1.0));
+#9=IFCCARTESIANPOINT((6.0,0.0,1.0));
+#10=IFCPOLYLINE((#8,#9));
+#11=IFCDIRECTION((0.0,0.0,1.0));
+#12=IFCOFFSETCURVE3D(#10,0.25,.F.,#11);
+"#;
+
+        let mut decoder = EntityDecoder::new(content);
+        let processor = ProfileProcessor::new(IfcSchema::new());
+
+        // Slab edge grown by 0.5 m all round: 5 x 3
+        let profile_entity = decoder.decode_by_id(7).unwrap();
+        let profile = processor.process(&profile_entity, &mut decoder).unwrap();
+        let area = crate::bool2d::compute_signed_area(&profile.outer).abs();
+        assert!((area - 15.0).abs() < 1e-9, "area {area}");
+
+        // Railing path moved 0.25 m sideways, keeping its height
+        let directrix = decoder.decode_by_id(12).unwrap();
+        let points = processor
+            .get_curve_points(&directrix, &mut decoder)
+            .unwrap();
+        assert_eq!(
+            points,
+            vec![Point3::new(0.0, -0.25, 1.0), Point3::new(6.0, -0.25, 1.0)]
+        );
+    }
+
     #[test]
     fn test_derived_profile_applies_translation_rotation_and_scale() {
         let content = r#"
@@ -1750,4 +2103,48 @@ mod tests {
         assert!(profile.outer.contains(&Point2::new(-1.0, 2.0)));
         assert!(profile.outer.contains(&Point2::new(1.0, 2.0)));
     }
+
+    #[test]
+    fn test_mirrored_and_non_uniform_derived_profiles_keep_handedness() {
+        let content = r#"
+#1=IFCLSHAPEPROFILEDEF(.AREA.,$,$,100.0,60.0,10.0,$,$,$);
+#2=IFCMIRROREDPROFILEDEF(.AREA.,$,#1,*,$);
+#3=IFCDIRECTION((1.0,0.0));
+#4=IFCDIRECTION((0.2,-1.0));
+#5=IFCCARTESIANPOINT((5.0,0.0));
+#6=IFCCARTESIANTRANSFORMATIONOPERATOR2DNONUNIFORM(#3,#4,#5,2.0,0.5);
+#7=IFCDERIVEDPROFILEDEF(.AREA.,$,#1,#6,$);
+"#;
+
+        let mut decoder = EntityDecoder::new(content);
+        let processor = ProfileProcessor::new(IfcSchema::new());
+
+        let parent_entity = decoder.decode_by_id(1).unwrap();
+        let parent = processor.process(&parent_entity, &mut decoder).unwrap();
+        let parent_area = crate::bool2d::compute_signed_area(&parent.outer);
+        assert!(parent_area > 0.0);
+
+        // Derived `*` operator mirrors about the Y axis
+        let mirrored_entity = decoder.decode_by_id(2).unwrap();
+        let mirrored = processor.process(&mirrored_entity, &mut decoder).unwrap();
+        assert_eq!(mirrored.outer.len(), parent.outer.len());
+        for p in &parent.outer {
+            assert!(mirrored.outer.contains(&Point2::new(-p.x, p.y)));
+        }
+        let area = crate::bool2d::compute_signed_area(&mirrored.outer);
+        assert!((area - parent_area).abs() < 1e-9);
+
+        // Axis2 pointing against the complement of Axis1 mirrors about X;
+        // scales apply per axis
+        let derived_entity = decoder.decode_by_id(7).unwrap();
+        let derived = processor.process(&derived_entity, &mut decoder).unwrap();
+        for p in &parent.outer {
+            assert!(derived
+                .outer
+                .iter()
+                .any(|q| (q.x - (5.0 + 2.0 * p.x)).abs() < 1e-9 && (q.y + 0.5 * p.y).abs() < 1e-9));
+        }
+        let area = crate::bool2d::compute_signed_area(&derived.outer);
+        assert!((area - parent_area).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,293 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Progressive, chunked mesh streams
+//!
+//! [`encode_chunks`] splits processed geometry into ordered chunks plus a
+//! [`ChunkManifest`], so a remotely hosted model can start rendering after
+//! the first chunk arrives. Chunks are ordered coarse to fine (largest
+//! elements first) or by caller-supplied groups such as storeys, and each
+//! one is a self-contained [`cache_format`](crate::cache_format) blob that
+//! [`decode_meshes`](crate::cache_format::decode_meshes) reads.
+
+use crate::cache_format::{encode_meshes, CACHE_FORMAT_VERSION};
+use crate::mesh::Mesh;
+use rustc_hash::FxHashMap;
+use std::fmt::Write;
+
+/// Order in which elements are streamed
+#[derive(Debug, Clone, Default)]
+pub enum ChunkOrder {
+    /// Largest elements (by bounding box diagonal) first, so the overall
+    /// shape of the model appears with the first chunk
+    #[default]
+    LargestFirst,
+    /// Element groups (e.g. one per storey, bottom to top) in the given
+    /// order; groups never share a chunk. Elements in no group follow,
+    /// largest first.
+    Groups(Vec<Vec<u32>>),
+}
+
+/// Chunking settings
+#[derive(Debug, Clone)]
+pub struct ChunkOptions {
+    /// Triangle budget per chunk; a single larger element gets its own chunk
+    pub max_triangles: usize,
+    pub order: ChunkOrder,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            max_triangles: 100_000,
+            order: ChunkOrder::default(),
+        }
+    }
+}
+
+impl ChunkOptions {
+    /// Set the triangle budget per chunk
+    pub fn with_max_triangles(mut self, max_triangles: usize) -> Self {
+        self.max_triangles = max_triangles.max(1);
+        self
+    }
+
+    /// Set the element order
+    pub fn with_order(mut self, order: ChunkOrder) -> Self {
+        self.order = order;
+        self
+    }
+}
+
+/// Manifest entry describing one chunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkInfo {
+    /// Index into [`ChunkOrder::Groups`], `None` for ungrouped elements
+    pub group: Option<usize>,
+    /// Elements in the chunk, in stream order
+    pub element_ids: Vec<u32>,
+    pub triangle_count: usize,
+    /// Encoded size in bytes
+    pub byte_len: usize,
+    /// Bounds of the chunk's vertices as (min, max)
+    pub bounds: Option<([f32; 3], [f32; 3])>,
+}
+
+/// Chunk list sent ahead of the chunks themselves
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkManifest {
+    /// [`CACHE_FORMAT_VERSION`] of the chunk blobs
+    pub version: u16,
+    pub chunks: Vec<ChunkInfo>,
+}
+
+impl ChunkManifest {
+    /// Total triangles over all chunks
+    pub fn triangle_count(&self) -> usize {
+        self.chunks.iter().map(|c| c.triangle_count).sum()
+    }
+
+    /// Serialize as JSON for viewers and HTTP endpoints (`camelCase` keys,
+    /// `bounds` as `[minX, minY, minZ, maxX, maxY, maxZ]` or `null`)
+    pub fn to_json(&self) -> String {
+        let mut json = format!("{{\"version\":{},\"chunks\":[", self.version);
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let group = chunk
+                .group
+                .map_or_else(|| "null".to_string(), |g| g.to_string());
+            let ids: Vec<String> = chunk.element_ids.iter().map(u32::to_string).collect();
+            let bounds = chunk.bounds.map_or_else(
+                || "null".to_string(),
+                |(min, max)| {
+                    let values: Vec<String> =
+                        min.iter().chain(&max).map(|v| v.to_string()).collect();
+                    format!("[{}]", values.join(","))
+                },
+            );
+            let _ = write!(
+                json,
+                "{{\"group\":{},\"elementIds\":[{}],\"triangleCount\":{},",
+                group,
+                ids.join(","),
+                chunk.triangle_count
+            );
+            let _ = write!(
+                json,
+                "\"byteLength\":{},\"bounds\":{}}}",
+                chunk.byte_len, bounds
+            );
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// Encoded chunks with their manifest
+#[derive(Debug, Clone)]
+pub struct ChunkedMeshes {
+    pub manifest: ChunkManifest,
+    /// One [`encode_meshes`] blob per manifest entry
+    pub chunks: Vec<Vec<u8>>,
+}
+
+/// Split element meshes into ordered chunks. Empty meshes are skipped.
+pub fn encode_chunks<'a>(
+    meshes: impl IntoIterator<Item = (u32, &'a Mesh)>,
+    options: &ChunkOptions,
+) -> ChunkedMeshes {
+    let meshes: FxHashMap<u32, &Mesh> = meshes
+        .into_iter()
+        .filter(|(_, mesh)| !mesh.is_empty())
+        .collect();
+
+    // (group, elements) runs in stream order
+    let mut runs: Vec<(Option<usize>, Vec<u32>)> = Vec::new();
+    let mut remaining = meshes.clone();
+    if let ChunkOrder::Groups(groups) = &options.order {
+        for (index, group) in groups.iter().enumerate() {
+            let ids: Vec<u32> = group
+                .iter()
+                .filter(|id| remaining.remove(id).is_some())
+                .copied()
+                .collect();
+            runs.push((Some(index), ids));
+        }
+    }
+    let mut rest: Vec<u32> = remaining.into_keys().collect();
+    let sizes: FxHashMap<u32, f32> = rest.iter().map(|&id| (id, diagonal(meshes[&id]))).collect();
+    rest.sort_by(|a, b| sizes[b].total_cmp(&sizes[a]).then(a.cmp(b)));
+    runs.push((None, rest));
+
+    let mut result = ChunkedMeshes {
+        manifest: ChunkManifest {
+            version: CACHE_FORMAT_VERSION,
+            chunks: Vec::new(),
+        },
+        chunks: Vec::new(),
+    };
+    for (group, ids) in runs {
+        let mut start = 0;
+        while start < ids.len() {
+            let mut end = start;
+            let mut triangles = 0;
+            while end < ids.len() {
+                let count = meshes[&ids[end]].triangle_count();
+                if end > start && triangles + count > options.max_triangles {
+                    break;
+                }
+                triangles += count;
+                end += 1;
+            }
+            let chunk = &ids[start..end];
+            let data = encode_meshes(chunk.iter().map(|id| (*id, meshes[id])));
+            result.manifest.chunks.push(ChunkInfo {
+                group,
+                element_ids: chunk.to_vec(),
+                triangle_count: triangles,
+                byte_len: data.len(),
+                bounds: bounds(chunk.iter().map(|id| meshes[id])),
+            });
+            result.chunks.push(data);
+            start = end;
+        }
+    }
+    result
+}
+
+fn bounds<'a>(meshes: impl Iterator<Item = &'a Mesh>) -> Option<([f32; 3], [f32; 3])> {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    let mut any = false;
+    for p in meshes.flat_map(|mesh| mesh.positions.chunks_exact(3)) {
+        if p.iter().all(|v| v.is_finite()) {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
+            any = true;
+        }
+    }
+    any.then_some((min, max))
+}
+
+fn diagonal(mesh: &Mesh) -> f32 {
+    bounds(std::iter::once(mesh)).map_or(0.0, |(min, max)| {
+        (0..3)
+            .map(|axis| (max[axis] - min[axis]).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache_format::decode_meshes;
+
+    /// `n` triangles spread along x up to `size`
+    fn strip(n: usize, size: f32) -> Mesh {
+        let mut mesh = Mesh::new();
+        for i in 0..n {
+            let x = size * i as f32 / n as f32;
+            let base = mesh.positions.len() as u32 / 3;
+            mesh.positions
+                .extend_from_slice(&[x, 0.0, 0.0, size, 0.0, 0.0, x, 1.0, 0.0]);
+            mesh.indices.extend_from_slice(&[base, base + 1, base + 2]);
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_largest_first_with_budget() {
+        let meshes = [(1, strip(4, 1.0)), (2, strip(4, 50.0)), (3, strip(4, 10.0))];
+        let options = ChunkOptions::default().with_max_triangles(8);
+        let chunked = encode_chunks(meshes.iter().map(|(id, m)| (*id, m)), &options);
+
+        let ids: Vec<Vec<u32>> = chunked
+            .manifest
+            .chunks
+            .iter()
+            .map(|c| c.element_ids.clone())
+            .collect();
+        assert_eq!(ids, [vec![2, 3], vec![1]]);
+        assert_eq!(chunked.manifest.triangle_count(), 12);
+
+        let first = decode_meshes(&chunked.chunks[0]).unwrap();
+        assert_eq!(first.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(chunked.manifest.chunks[0].byte_len, chunked.chunks[0].len());
+        assert_eq!(chunked.manifest.chunks[0].bounds.unwrap().1[0], 50.0);
+    }
+
+    #[test]
+    fn test_groups_stream_in_order() {
+        let meshes = [
+            (1, strip(2, 1.0)),
+            (2, strip(2, 1.0)),
+            (3, strip(2, 1.0)),
+            (4, Mesh::new()),
+            (5, strip(2, 9.0)),
+        ];
+        let options =
+            ChunkOptions::default().with_order(ChunkOrder::Groups(vec![vec![3], vec![1, 2, 4]]));
+        let chunked = encode_chunks(meshes.iter().map(|(id, m)| (*id, m)), &options);
+
+        let summary: Vec<(Option<usize>, Vec<u32>)> = chunked
+            .manifest
+            .chunks
+            .iter()
+            .map(|c| (c.group, c.element_ids.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [(Some(0), vec![3]), (Some(1), vec![1, 2]), (None, vec![5])]
+        );
+
+        let json = chunked.manifest.to_json();
+        assert!(json.starts_with("{\"version\":1,\"chunks\":[{\"group\":0,\"elementIds\":[3]"));
+        assert!(json.contains("\"group\":null,\"elementIds\":[5],\"triangleCount\":2"));
+    }
+}
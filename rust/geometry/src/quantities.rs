@@ -0,0 +1,259 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Quantity takeoff from element geometry
+//!
+//! IfcElementQuantity sets are often missing or stale, so [`compute`]
+//! measures the tessellated mesh directly: enclosed volume (divergence
+//! theorem), surface area, plan footprint and bounding box dimensions.
+//!
+//! The volume is only meaningful for a closed, manifold mesh. Open meshes
+//! still get a signed volume (measured from the bounding box centre, so it
+//! doesn't depend on where the element sits in the model) together with
+//! the number of open and non-manifold edges, letting callers decide
+//! whether to trust it.
+//!
+//! [`mass_properties`] integrates the same tetrahedra further to get the
+//! centroid and inertia tensor for a given density.
+
+use crate::bool2d::{compute_signed_area, union_contours};
+use crate::mesh::Mesh;
+use nalgebra::{Matrix3, Point2, Point3, Vector3};
+use rustc_hash::FxHashMap;
+
+/// Vertices closer than this share a position when checking closure
+const WELD_TOLERANCE: f64 = 1e-6;
+
+/// Triangles whose plan projection is smaller than this don't contribute
+/// to the footprint (walls and other vertical faces)
+const MIN_FOOTPRINT_AREA: f64 = 1e-12;
+
+/// Geometric quantities of one mesh
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Quantities {
+    /// Enclosed volume, `|signed_volume|`
+    pub volume: f64,
+    /// Volume with orientation: negative for inward-facing meshes
+    pub signed_volume: f64,
+    /// Total triangle area
+    pub surface_area: f64,
+    /// Area of the mesh projected onto the XY plane
+    pub footprint_area: f64,
+    /// Bounding box size along x, y and z
+    pub dimensions: Vector3<f64>,
+    /// Edges used by only one triangle (holes in the surface)
+    pub open_edges: usize,
+    /// Edges shared by more than two triangles
+    pub non_manifold_edges: usize,
+}
+
+impl Quantities {
+    /// Whether the mesh is closed and manifold, i.e. the volume is reliable
+    pub fn is_watertight(&self) -> bool {
+        self.open_edges == 0 && self.non_manifold_edges == 0
+    }
+
+    /// Whether the mesh faces inward
+    pub fn is_inverted(&self) -> bool {
+        self.signed_volume < 0.0
+    }
+}
+
+/// Measure a mesh (in its own units).
+pub fn compute(mesh: &Mesh) -> Quantities {
+    let (points, ids) = mesh.position_ids(WELD_TOLERANCE);
+    let Some(first) = points.first() else {
+        return Quantities::default();
+    };
+    let (min, max) = points
+        .iter()
+        .fold((*first, *first), |(min, max), p| (min.inf(p), max.sup(p)));
+
+    let mut quantities = Quantities {
+        dimensions: max - min,
+        ..Quantities::default()
+    };
+    let mut edge_use: FxHashMap<(u32, u32), u32> = FxHashMap::default();
+    let mut footprint: Vec<Vec<Point2<f64>>> = Vec::new();
+
+    for tri in mesh.indices.chunks_exact(3) {
+        let Some(tri) = tri
+            .iter()
+            .map(|&i| ids.get(i as usize).copied())
+            .collect::<Option<Vec<u32>>>()
+        else {
+            continue;
+        };
+        let [a, b, c] = [0, 1, 2].map(|k| points[tri[k] as usize]);
+        if ![a, b, c].iter().all(|p| p.iter().all(|v| v.is_finite())) {
+            continue;
+        }
+
+        let cross = (b - a).cross(&(c - a));
+        quantities.surface_area += 0.5 * cross.norm();
+        if 0.5 * cross.z.abs() > MIN_FOOTPRINT_AREA {
+            footprint.push(vec![plan(&a), plan(&b), plan(&c)]);
+        }
+
+        for k in 0..3 {
+            let (u, v) = (tri[k], tri[(k + 1) % 3]);
+            if u != v {
+                *edge_use.entry((u.min(v), u.max(v))).or_default() += 1;
+            }
+        }
+    }
+
+    quantities.signed_volume = mesh.signed_volume();
+    quantities.volume = quantities.signed_volume.abs();
+    quantities.open_edges = edge_use.values().filter(|&&n| n == 1).count();
+    quantities.non_manifold_edges = edge_use.values().filter(|&&n| n > 2).count();
+    quantities.footprint_area = match union_contours(&footprint) {
+        // Outer boundaries and holes come back with opposite windings
+        Ok(contours) => contours
+            .iter()
+            .map(|c| compute_signed_area(c))
+            .sum::<f64>()
+            .abs(),
+        Err(_) => 0.0,
+    };
+    quantities
+}
+
+/// Mass, centroid and inertia of a solid mesh
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    /// Enclosed volume
+    pub volume: f64,
+    /// `volume * density`
+    pub mass: f64,
+    /// Centre of mass (uniform density)
+    pub centroid: Point3<f64>,
+    /// Inertia tensor about the centroid, in the mesh axes
+    pub inertia: Matrix3<f64>,
+}
+
+impl MassProperties {
+    /// Principal moments of inertia, ascending
+    pub fn principal_moments(&self) -> Vector3<f64> {
+        let mut moments = self.inertia.symmetric_eigenvalues();
+        moments
+            .as_mut_slice()
+            .sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        moments
+    }
+}
+
+/// Integrate mass properties of a closed mesh with uniform `density`
+/// (mass per cubic mesh unit, e.g. kg/m³ from the element's material).
+///
+/// Inward-facing meshes are measured as if they faced outward. Returns
+/// `None` when the mesh encloses no volume.
+pub fn mass_properties(mesh: &Mesh, density: f64) -> Option<MassProperties> {
+    // Second moment of a unit tetrahedron spanned by the axes
+    let canonical = Matrix3::new(2.0, 1.0, 1.0, 1.0, 2.0, 1.0, 1.0, 1.0, 2.0) / 120.0;
+    let vertex = |i: u32| -> Option<Point3<f64>> {
+        let i = i as usize * 3;
+        let p = mesh.positions.get(i..i + 3)?;
+        let p = Point3::new(p[0] as f64, p[1] as f64, p[2] as f64);
+        p.iter().all(|v| v.is_finite()).then_some(p)
+    };
+    // Integrate from a vertex rather than the origin to stay precise far
+    // from it
+    let origin = vertex(*mesh.indices.first()?)?;
+
+    let mut volume = 0.0;
+    let mut first_moment = Vector3::zeros();
+    let mut second_moment = Matrix3::zeros();
+    for tri in mesh.indices.chunks_exact(3) {
+        let (Some(a), Some(b), Some(c)) = (vertex(tri[0]), vertex(tri[1]), vertex(tri[2])) else {
+            continue;
+        };
+        let edges = Matrix3::from_columns(&[a - origin, b - origin, c - origin]);
+        let det = edges.determinant();
+        volume += det / 6.0;
+        first_moment += det / 24.0 * (edges.column(0) + edges.column(1) + edges.column(2));
+        second_moment += det * edges * canonical * edges.transpose();
+    }
+
+    if volume.abs() < f64::EPSILON {
+        return None;
+    }
+    if volume < 0.0 {
+        volume = -volume;
+        first_moment = -first_moment;
+        second_moment = -second_moment;
+    }
+
+    let offset = first_moment / volume;
+    let central = second_moment - volume * offset * offset.transpose();
+    Some(MassProperties {
+        volume,
+        mass: volume * density,
+        centroid: origin + offset,
+        inertia: density * (Matrix3::identity() * central.trace() - central),
+    })
+}
+
+fn plan(point: &Point3<f64>) -> Point2<f64> {
+    Point2::new(point.x, point.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::box_mesh;
+
+    #[test]
+    fn test_closed_box() {
+        let mesh = box_mesh([10.0, 20.0, 0.0], [12.0, 21.0, 3.0]);
+        let q = compute(&mesh);
+        assert!((q.volume - 6.0).abs() < 1e-6, "{q:?}");
+        assert!((q.signed_volume - 6.0).abs() < 1e-6);
+        assert!((q.surface_area - 22.0).abs() < 1e-6);
+        assert!((q.footprint_area - 2.0).abs() < 1e-6);
+        assert!((q.dimensions - Vector3::new(2.0, 1.0, 3.0)).norm() < 1e-6);
+        assert!(q.is_watertight() && !q.is_inverted());
+    }
+
+    #[test]
+    fn test_open_and_inverted_meshes() {
+        let mut open = box_mesh([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+        open.indices.drain(6..12);
+        let q = compute(&open);
+        assert_eq!(q.open_edges, 4);
+        assert!(!q.is_watertight());
+        assert!((q.surface_area - 5.0).abs() < 1e-6);
+        assert!((q.footprint_area - 1.0).abs() < 1e-6);
+
+        let mut inverted = box_mesh([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+        for tri in inverted.indices.chunks_exact_mut(3) {
+            tri.swap(1, 2);
+        }
+        let q = compute(&inverted);
+        assert!(q.is_inverted() && q.is_watertight());
+        assert!((q.volume - 1.0).abs() < 1e-6);
+
+        assert_eq!(compute(&Mesh::new()), Quantities::default());
+    }
+
+    #[test]
+    fn test_box_mass_properties() {
+        let mut mesh = box_mesh([10.0, 20.0, 0.0], [12.0, 21.0, 3.0]);
+        let props = mass_properties(&mesh, 10.0).unwrap();
+        assert!((props.volume - 6.0).abs() < 1e-6);
+        assert!((props.mass - 60.0).abs() < 1e-6);
+        assert!((props.centroid - Point3::new(11.0, 20.5, 1.5)).norm() < 1e-6);
+        // Solid cuboid: I_xx = m (b² + c²) / 12 etc.
+        let expected = Matrix3::from_diagonal(&Vector3::new(50.0, 65.0, 25.0));
+        assert!((props.inertia - expected).norm() < 1e-6, "{props:?}");
+        assert!((props.principal_moments() - Vector3::new(25.0, 50.0, 65.0)).norm() < 1e-6);
+
+        for tri in mesh.indices.chunks_exact_mut(3) {
+            tri.swap(1, 2);
+        }
+        let inverted = mass_properties(&mesh, 10.0).unwrap();
+        assert!((inverted.inertia - expected).norm() < 1e-6);
+        assert!(mass_properties(&Mesh::new(), 1.0).is_none());
+    }
+}
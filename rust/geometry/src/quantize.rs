@@ -0,0 +1,236 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Quantized vertex format
+//!
+//! Stores positions as normalized `u16 × 4` and normals as normalized
+//! `i8 × 4` (w = 0, padded for 4-byte vertex attribute alignment): 12 bytes
+//! per vertex instead of 24. Positions are quantized against the mesh's own
+//! bounding box, so each mesh carries its dequantization:
+//!
+//! ```text
+//! position = offset + q / 65535 * scale
+//! ```
+//!
+//! With RTC-shifted coordinates the error is at most `scale / 131070` — about
+//! 0.15 mm for a 20 m element. Uses the same convention as the meshopt
+//! compression pass (see [`crate::meshopt`]).
+
+use crate::mesh::Mesh;
+
+/// Per-mesh position dequantization: `offset + q / 65535 * scale`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionQuantization {
+    /// Minimum corner of the quantized box
+    pub offset: [f32; 3],
+    /// Largest extent of the box; all axes share it so the mesh keeps its
+    /// proportions under a single uniform scale
+    pub scale: f32,
+}
+
+impl Default for PositionQuantization {
+    fn default() -> Self {
+        Self {
+            offset: [0.0; 3],
+            scale: 1.0,
+        }
+    }
+}
+
+impl PositionQuantization {
+    /// Quantization covering the box `min..max`
+    pub fn from_bounds(min: [f32; 3], max: [f32; 3]) -> Self {
+        let extent = (0..3).map(|axis| max[axis] - min[axis]).fold(0.0, f32::max);
+        Self {
+            offset: min,
+            scale: if extent > 0.0 { extent } else { 1.0 },
+        }
+    }
+
+    /// Quantization covering every vertex of a flat `[x, y, z, …]` buffer
+    pub fn fit(positions: &[f32]) -> Self {
+        if positions.len() < 3 {
+            return Self::default();
+        }
+        let (min, max) = positions.chunks_exact(3).fold(
+            ([f32::MAX; 3], [f32::MIN; 3]),
+            |(mut min, mut max), p| {
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(p[axis]);
+                    max[axis] = max[axis].max(p[axis]);
+                }
+                (min, max)
+            },
+        );
+        Self::from_bounds(min, max)
+    }
+
+    /// Quantize one position to `[x, y, z, 0]`
+    pub fn quantize(&self, p: &[f32]) -> [u16; 4] {
+        let q = |axis: usize| {
+            let t = (p[axis] - self.offset[axis]) / self.scale;
+            (t.clamp(0.0, 1.0) * 65535.0).round() as u16
+        };
+        [q(0), q(1), q(2), 0]
+    }
+
+    /// Restore one quantized position
+    pub fn dequantize(&self, q: &[u16]) -> [f32; 3] {
+        let p = |axis: usize| self.offset[axis] + q[axis] as f32 / 65535.0 * self.scale;
+        [p(0), p(1), p(2)]
+    }
+
+    /// Largest per-axis rounding error
+    pub fn max_error(&self) -> f32 {
+        self.scale / 131070.0
+    }
+}
+
+/// Quantize one unit normal to `[x, y, z, 0]`
+pub fn quantize_normal(n: &[f32]) -> [i8; 4] {
+    let q = |c: f32| (c.clamp(-1.0, 1.0) * 127.0).round() as i8;
+    [q(n[0]), q(n[1]), q(n[2]), 0]
+}
+
+/// Restore one quantized normal (not renormalized)
+pub fn dequantize_normal(q: &[i8]) -> [f32; 3] {
+    [
+        q[0] as f32 / 127.0,
+        q[1] as f32 / 127.0,
+        q[2] as f32 / 127.0,
+    ]
+}
+
+/// A mesh in the quantized vertex format
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuantizedMesh {
+    /// Normalized positions, 4 components per vertex (w = 0)
+    pub positions: Vec<u16>,
+    /// Normalized normals, 4 components per vertex (w = 0); empty when the
+    /// source had no normals
+    pub normals: Vec<i8>,
+    /// Triangle indices (i0, i1, i2)
+    pub indices: Vec<u32>,
+    /// Dequantization for `positions`
+    pub quantization: PositionQuantization,
+}
+
+impl QuantizedMesh {
+    /// Quantize flat `f32 × 3` buffers. Normals are dropped unless there is
+    /// one per vertex.
+    pub fn from_buffers(positions: &[f32], normals: &[f32], indices: Vec<u32>) -> Self {
+        let quantization = PositionQuantization::fit(positions);
+        let quantized_positions = positions
+            .chunks_exact(3)
+            .flat_map(|p| quantization.quantize(p))
+            .collect();
+        let quantized_normals = if normals.len() == positions.len() {
+            normals.chunks_exact(3).flat_map(quantize_normal).collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            positions: quantized_positions,
+            normals: quantized_normals,
+            indices,
+            quantization,
+        }
+    }
+
+    /// Number of vertices
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len() / 4
+    }
+
+    /// Number of triangles
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Whether the mesh has no triangles
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Size of the vertex and index buffers in bytes
+    pub fn byte_len(&self) -> usize {
+        self.positions.len() * 2 + self.normals.len() + self.indices.len() * 4
+    }
+
+    /// Restore an `f32` mesh
+    pub fn dequantize(&self) -> Mesh {
+        let mut mesh = Mesh::with_capacity(self.vertex_count(), self.indices.len());
+        for q in self.positions.chunks_exact(4) {
+            mesh.positions
+                .extend_from_slice(&self.quantization.dequantize(q));
+        }
+        for q in self.normals.chunks_exact(4) {
+            mesh.normals.extend_from_slice(&dequantize_normal(q));
+        }
+        mesh.indices.extend_from_slice(&self.indices);
+        mesh
+    }
+}
+
+impl Mesh {
+    /// Convert to the quantized vertex format
+    pub fn quantize(&self) -> QuantizedMesh {
+        QuantizedMesh::from_buffers(&self.positions, &self.normals, self.indices.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Point3, Vector3};
+
+    #[test]
+    fn test_quantize_round_trip() {
+        // 12 m x 0.3 m x 3 m wall slab
+        let mut mesh = Mesh::new();
+        for &(x, y, z) in &[
+            (0.0, 0.0, 0.0),
+            (12.0, 0.0, 0.0),
+            (12.0, 0.3, 3.0),
+            (0.0, 0.3, 3.0),
+            (5.123_456, 0.15, 1.777_7),
+        ] {
+            mesh.add_vertex(Point3::new(x, y, z), Vector3::new(0.0, -0.6, 0.8));
+        }
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(0, 2, 3);
+
+        let quantized = mesh.quantize();
+        assert_eq!(quantized.vertex_count(), 5);
+        assert_eq!(quantized.quantization.offset, [0.0, 0.0, 0.0]);
+        assert_eq!(quantized.quantization.scale, 12.0);
+        assert_eq!(quantized.positions[4..8], [65535, 0, 0, 0]);
+        // Half the bytes of the f32 vertex buffers
+        let f32_vertex_bytes = (mesh.positions.len() + mesh.normals.len()) * 4;
+        let vertex_bytes = quantized.byte_len() - quantized.indices.len() * 4;
+        assert_eq!(vertex_bytes * 2, f32_vertex_bytes);
+
+        let restored = quantized.dequantize();
+        assert_eq!(restored.indices, mesh.indices);
+        let max_error = quantized.quantization.max_error();
+        for (a, b) in restored.positions.iter().zip(&mesh.positions) {
+            assert!((a - b).abs() <= max_error + 1e-6);
+        }
+        for (a, b) in restored.normals.iter().zip(&mesh.normals) {
+            assert!((a - b).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_bounds() {
+        let single = QuantizedMesh::from_buffers(&[4.0, 5.0, 6.0], &[], Vec::new());
+        assert_eq!(single.quantization.scale, 1.0);
+        assert!(single.normals.is_empty());
+        assert_eq!(single.dequantize().positions, vec![4.0, 5.0, 6.0]);
+
+        let empty = QuantizedMesh::from_buffers(&[], &[], Vec::new());
+        assert_eq!(empty.quantization, PositionQuantization::default());
+        assert_eq!(empty.vertex_count(), 0);
+    }
+}
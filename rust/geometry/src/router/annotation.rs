@@ -0,0 +1,182 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Line geometry of annotations: curves, survey points and text placeholders.
+
+use super::GeometryRouter;
+use crate::{LineMesh, ProfileProcessor, Result};
+use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcType};
+use nalgebra::{Matrix4, Point3};
+
+/// Half size of the cross drawn for points and text placements, in meters
+const MARKER_SIZE: f64 = 0.1;
+
+/// Maximum nesting of curve sets and mapped items
+const MAX_ITEM_DEPTH: usize = 8;
+
+impl GeometryRouter {
+    /// Line geometry of an element's Body, Curve3D and Point representations,
+    /// in meters with the RTC offset applied.
+    ///
+    /// Curves become polylines; IfcCartesianPoint items (survey points) and
+    /// IfcTextLiteral placements become small three-axis crosses. Solid items
+    /// are ignored here — [`Self::process_element`] meshes those — so calling
+    /// both covers IfcAnnotation products with mixed content.
+    pub fn process_element_lines(
+        &self,
+        element: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<LineMesh> {
+        let mut lines = LineMesh::new();
+        // IfcProduct attr 6: Representation → IfcProductDefinitionShape
+        let Some(representation_attr) = element.get(6).filter(|attr| !attr.is_null()) else {
+            return Ok(lines);
+        };
+        let Some(representation) = decoder.resolve_ref(representation_attr)? else {
+            return Ok(lines);
+        };
+        let Some(representations_attr) = representation.get(2) else {
+            return Ok(lines);
+        };
+
+        let profiles = ProfileProcessor::with_tessellation(
+            self.schema.clone(),
+            self.tessellation.in_model_units(self.unit_scale),
+        );
+        let placement = self.get_placement_transform_from_element(element, decoder)?;
+
+        for shape_rep in decoder.resolve_ref_list(representations_attr)? {
+            if shape_rep.ifc_type != IfcType::IfcShapeRepresentation {
+                continue;
+            }
+            // IfcShapeRepresentation: ContextOfItems, RepresentationIdentifier,
+            // RepresentationType, Items
+            let identifier = shape_rep.get(1).and_then(|attr| attr.as_string());
+            let rep_type = shape_rep.get(2).and_then(|attr| attr.as_string());
+            if identifier != Some("Body") && !matches!(rep_type, Some("Curve3D" | "Point")) {
+                continue;
+            }
+            let Some(items_attr) = shape_rep.get(3) else {
+                continue;
+            };
+            for item in decoder.resolve_ref_list(items_attr)? {
+                self.collect_item_lines(&item, decoder, &profiles, &placement, &mut lines, 0)?;
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Append the lines of one representation item. `transform` maps item
+    /// coordinates to world, in file units.
+    fn collect_item_lines(
+        &self,
+        item: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        profiles: &ProfileProcessor,
+        transform: &Matrix4<f64>,
+        lines: &mut LineMesh,
+        depth: usize,
+    ) -> Result<()> {
+        match item.ifc_type {
+            IfcType::IfcGeometricCurveSet | IfcType::IfcGeometricSet => {
+                // IfcGeometricSet: Elements
+                if depth >= MAX_ITEM_DEPTH {
+                    return Ok(());
+                }
+                if let Some(elements_attr) = item.get(0) {
+                    for element in decoder.resolve_ref_list(elements_attr)? {
+                        self.collect_item_lines(
+                            &element,
+                            decoder,
+                            profiles,
+                            transform,
+                            lines,
+                            depth + 1,
+                        )?;
+                    }
+                }
+            }
+            IfcType::IfcMappedItem => {
+                // IfcMappedItem: MappingSource (IfcRepresentationMap), MappingTarget
+                if depth >= MAX_ITEM_DEPTH {
+                    return Ok(());
+                }
+                let Some(source_id) = item.get_ref(0) else {
+                    return Ok(());
+                };
+                let mut transform = *transform;
+                if let Some(mapping) = self.mapping_target_transform(item, decoder)? {
+                    transform *= mapping;
+                }
+                // IfcRepresentationMap attr 1: MappedRepresentation, its attr 3: Items
+                let source = decoder.decode_by_id(source_id)?;
+                let Some(rep_id) = source.get_ref(1) else {
+                    return Ok(());
+                };
+                let mapped_rep = decoder.decode_by_id(rep_id)?;
+                if let Some(items_attr) = mapped_rep.get(3) {
+                    for sub_item in decoder.resolve_ref_list(items_attr)? {
+                        self.collect_item_lines(
+                            &sub_item,
+                            decoder,
+                            profiles,
+                            &transform,
+                            lines,
+                            depth + 1,
+                        )?;
+                    }
+                }
+            }
+            IfcType::IfcCartesianPoint => {
+                let coords = item.get(0).and_then(|attr| attr.as_list()).unwrap_or(&[]);
+                let coord = |i: usize| coords.get(i).and_then(|v| v.as_float()).unwrap_or(0.0);
+                let point = Point3::new(coord(0), coord(1), coord(2));
+                lines.add_marker(&self.line_point(transform, &point), MARKER_SIZE);
+            }
+            IfcType::IfcTextLiteral | IfcType::IfcTextLiteralWithExtent => {
+                // IfcTextLiteral: Literal, Placement (IfcAxis2Placement), Path
+                if let Some(placement_id) = item.get_ref(1) {
+                    let placement = decoder.decode_by_id(placement_id)?;
+                    let location = self.parse_cartesian_point(&placement, decoder, 0)?;
+                    lines.add_marker(&self.line_point(transform, &location), MARKER_SIZE);
+                }
+            }
+            IfcType::IfcPolyline
+            | IfcType::IfcIndexedPolyCurve
+            | IfcType::IfcCompositeCurve
+            | IfcType::IfcTrimmedCurve
+            | IfcType::IfcOffsetCurve2D
+            | IfcType::IfcOffsetCurve3D
+            | IfcType::IfcCircle
+            | IfcType::IfcEllipse
+            | IfcType::IfcBSplineCurveWithKnots
+            | IfcType::IfcRationalBSplineCurveWithKnots
+            | IfcType::IfcGradientCurve
+            | IfcType::IfcSegmentedReferenceCurve => {
+                let points: Vec<Point3<f64>> = profiles
+                    .get_curve_points(item, decoder)?
+                    .iter()
+                    .map(|p| self.line_point(transform, p))
+                    .collect();
+                lines.add_polyline(&points);
+            }
+            // Solids and surfaces are meshed by process_element
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// World position in meters with the RTC offset applied, computed in
+    /// f64 so large site coordinates keep their precision
+    pub(super) fn line_point(&self, transform: &Matrix4<f64>, point: &Point3<f64>) -> Point3<f64> {
+        let world = transform.transform_point(point) * self.unit_scale;
+        if self.has_rtc_offset() {
+            let (x, y, z) = self.rtc_offset;
+            Point3::new(world.x - x, world.y - y, world.z - z)
+        } else {
+            world
+        }
+    }
+}
@@ -6,8 +6,50 @@
 
 use super::GeometryRouter;
 use crate::Mesh;
+use rustc_hash::FxHashMap;
 use std::hash::{Hash, Hasher};
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Router cache shared between threads.
+///
+/// Locks are held only for the map operation itself, never while meshing,
+/// so contention stays low when many workers share one router. A poisoned
+/// lock (a panicking worker) still holds a consistent map and is reused.
+pub(super) struct SharedCache<K, V>(RwLock<FxHashMap<K, V>>);
+
+impl<K: std::hash::Hash + Eq, V> Default for SharedCache<K, V> {
+    fn default() -> Self {
+        Self(RwLock::new(FxHashMap::default()))
+    }
+}
+
+impl<K: std::hash::Hash + Eq, V: Clone> SharedCache<K, V> {
+    pub(super) fn get(&self, key: &K) -> Option<V> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .cloned()
+    }
+}
+
+impl<K: std::hash::Hash + Eq, V> SharedCache<K, V> {
+    pub(super) fn insert(&self, key: K, value: V) {
+        self.write().insert(key, value);
+    }
+
+    pub(super) fn remove(&self, key: &K) -> Option<V> {
+        self.write().remove(key)
+    }
+
+    pub(super) fn clear(&self) {
+        self.write().clear();
+    }
+
+    pub(super) fn write(&self) -> std::sync::RwLockWriteGuard<'_, FxHashMap<K, V>> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
 
 impl GeometryRouter {
     /// Compute hash of mesh geometry for deduplication.
@@ -78,19 +120,17 @@ impl GeometryRouter {
         let hash = Self::compute_mesh_hash(&mesh);
 
         // Check cache first
-        {
-            let cache = self.geometry_hash_cache.borrow();
-            if let Some(cached) = cache.get(&hash) {
-                return Arc::clone(cached);
-            }
+        if let Some(cached) = self.geometry_hash_cache.get(&hash) {
+            return cached;
         }
 
-        // Cache miss - store and return
-        let arc_mesh = Arc::new(mesh);
-        {
-            let mut cache = self.geometry_hash_cache.borrow_mut();
-            cache.insert(hash, Arc::clone(&arc_mesh));
-        }
-        arc_mesh
+        // Cache miss - store and return (a concurrent miss on the same hash
+        // keeps whichever mesh was stored first)
+        Arc::clone(
+            self.geometry_hash_cache
+                .write()
+                .entry(hash)
+                .or_insert_with(|| Arc::new(mesh)),
+        )
     }
 }
@@ -10,6 +10,9 @@ use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcType};
 use nalgebra::Matrix4;
 use rustc_hash::FxHashSet;
 
+/// Half-space clipping plane: point on the plane, normal, agreement flag
+type ClippingPlane = (Point3<f64>, Vector3<f64>, bool);
+
 /// Maximum IfcBooleanClippingResult chain depth we will follow when extracting a base profile.
 const MAX_CLIPPING_DEPTH: usize = 32;
 
@@ -153,6 +156,53 @@ impl GeometryRouter {
         ))
     }
 
+    /// Find the IfcExtrudedAreaSolid under a representation item and the
+    /// half-space planes clipping it (in the item's coordinate system).
+    ///
+    /// Returns `None` unless the item is an extrusion or a chain of
+    /// IfcBooleanClippingResult whose second operands are all unbounded
+    /// IfcHalfSpaceSolid planes.
+    pub(super) fn find_clipped_extrusion(
+        &self,
+        item: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Option<(DecodedEntity, Vec<ClippingPlane>)>> {
+        let mut clipping_planes = Vec::new();
+        let mut current = item.clone();
+
+        for _depth in 0..MAX_CLIPPING_DEPTH {
+            match current.ifc_type {
+                IfcType::IfcExtrudedAreaSolid => return Ok(Some((current, clipping_planes))),
+                IfcType::IfcBooleanClippingResult => {}
+                _ => return Ok(None),
+            }
+
+            let second_operand = match current.get(2) {
+                Some(attr) => decoder.resolve_ref(attr)?,
+                None => None,
+            };
+            match second_operand {
+                Some(half_space) if half_space.ifc_type == IfcType::IfcHalfSpaceSolid => {
+                    match self.extract_half_space_plane(&half_space, decoder) {
+                        Some(plane) => clipping_planes.push(plane),
+                        None => return Ok(None),
+                    }
+                }
+                _ => return Ok(None),
+            }
+
+            current = match current.get(1) {
+                Some(attr) => match decoder.resolve_ref(attr)? {
+                    Some(first_operand) => first_operand,
+                    None => return Ok(None),
+                },
+                None => return Ok(None),
+            };
+        }
+
+        Ok(None)
+    }
+
     /// Extract profile from IfcBooleanClippingResult recursively
     fn extract_profile_from_boolean_result(
         &self,
@@ -358,7 +408,11 @@ impl GeometryRouter {
 
         // FirstOperand is the base solid (IfcExtrudedAreaSolid, etc.)
         if let Some(processor) = self.processors.get(&first_operand.ifc_type) {
-            let mut mesh = processor.process(&first_operand, decoder, &self.schema)?;
+            let mut mesh = processor.process_with_context(
+                &first_operand,
+                decoder,
+                &self.processor_context(),
+            )?;
             self.scale_mesh(&mut mesh);
             // Note: placement is applied in the main function
             return Ok((mesh, clipping_planes));
@@ -0,0 +1,134 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Router services for geometry processors
+//!
+//! Downstream crates extend the router with processors for entity types it
+//! does not know, including proprietary ones, without forking: implement
+//! [`GeometryProcessor`](super::GeometryProcessor) and
+//! [`GeometryRouter::register`] it. Types outside the IFC schema are keyed
+//! by `IfcType::from_str`, which maps any entity name to a stable
+//! `IfcType::Unknown` hash.
+//!
+//! Processors that need more than the schema override
+//! [`GeometryProcessor::process_with_context`](super::GeometryProcessor::process_with_context)
+//! and get a
+//! [`ProcessorContext`]: unit scale, RTC offset, tessellation quality,
+//! style lookup, the mesh deduplication cache, and dispatch of nested items
+//! back to the router.
+//!
+//! ```rust,ignore
+//! struct AcmePanelProcessor;
+//!
+//! impl GeometryProcessor for AcmePanelProcessor {
+//!     fn process(&self, _: &DecodedEntity, _: &mut EntityDecoder, _: &IfcSchema) -> Result<Mesh> {
+//!         Err(Error::geometry("AcmePanel needs a router context".to_string()))
+//!     }
+//!
+//!     fn process_with_context(
+//!         &self,
+//!         entity: &DecodedEntity,
+//!         decoder: &mut EntityDecoder,
+//!         context: &ProcessorContext,
+//!     ) -> Result<Mesh> {
+//!         // ACMEPANEL(Body, Thickness): mesh the wrapped standard solid
+//!         let body = decoder.decode_by_id(entity.get_ref(0).unwrap())?;
+//!         context.process_item(&body, decoder)
+//!     }
+//!
+//!     fn supported_types(&self) -> Vec<IfcType> {
+//!         vec![IfcType::from_str("ACMEPANEL")]
+//!     }
+//! }
+//!
+//! router.register(Box::new(AcmePanelProcessor));
+//! ```
+//!
+//! Processors return meshes in file units, before RTC; the router scales,
+//! shifts and places them like any built-in result.
+
+use super::GeometryRouter;
+use crate::{Error, MaterialId, Mesh, Result, StyleIndex, TessellationConfig};
+use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema};
+use std::sync::Arc;
+
+/// Maximum nesting of [`ProcessorContext::process_item`] calls
+const MAX_NESTING: usize = 16;
+
+/// Router services available to a processor while it handles one item
+pub struct ProcessorContext<'a> {
+    router: &'a GeometryRouter,
+    depth: usize,
+}
+
+impl<'a> ProcessorContext<'a> {
+    pub(super) fn new(router: &'a GeometryRouter) -> Self {
+        Self { router, depth: 0 }
+    }
+
+    /// IFC schema of the router
+    pub fn schema(&self) -> &IfcSchema {
+        &self.router.schema
+    }
+
+    /// Scale from file units to meters
+    pub fn unit_scale(&self) -> f64 {
+        self.router.unit_scale
+    }
+
+    /// RTC offset in meters, subtracted by the router after processing
+    pub fn rtc_offset(&self) -> (f64, f64, f64) {
+        self.router.rtc_offset
+    }
+
+    /// Curve and surface tessellation quality, converted to file units
+    pub fn tessellation(&self) -> TessellationConfig {
+        self.router
+            .tessellation
+            .in_model_units(self.router.unit_scale)
+    }
+
+    /// Styled geometry items indexed by [`GeometryRouter::index_styles`]
+    pub fn style_index(&self) -> &StyleIndex {
+        &self.router.styles
+    }
+
+    /// Material of a styled geometry item
+    pub fn material_for_item(&self, item_id: u32) -> Option<MaterialId> {
+        self.router.styles.material_for_item(item_id)
+    }
+
+    /// Share one allocation between meshes with identical content (e.g.
+    /// repeated floors), through the router's deduplication cache
+    pub fn deduplicate(&self, mesh: Mesh) -> Arc<Mesh> {
+        self.router.get_or_cache_by_hash(mesh)
+    }
+
+    /// Mesh a nested representation item with the processor registered for
+    /// its type. The result is in file units, like the caller's own output.
+    pub fn process_item(&self, item: &DecodedEntity, decoder: &mut EntityDecoder) -> Result<Mesh> {
+        if self.depth >= MAX_NESTING {
+            return Err(Error::geometry(format!(
+                "Item #{} nested more than {} levels deep",
+                item.id, MAX_NESTING
+            )));
+        }
+        let processor =
+            self.router.processors.get(&item.ifc_type).ok_or_else(|| {
+                Error::geometry(format!("No processor for {}", item.ifc_type.name()))
+            })?;
+        let nested = Self {
+            router: self.router,
+            depth: self.depth + 1,
+        };
+        processor.process_with_context(item, decoder, &nested)
+    }
+}
+
+impl GeometryRouter {
+    /// Services handed to processors called by this router
+    pub fn processor_context(&self) -> ProcessorContext<'_> {
+        ProcessorContext::new(self)
+    }
+}
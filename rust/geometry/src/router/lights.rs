@@ -0,0 +1,171 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Light sources placed in world space, alongside the element meshes.
+
+use super::GeometryRouter;
+use crate::{Light, LightKind, Lights, Result};
+use ifc_lite_core::{has_geometry_by_name, DecodedEntity, EntityDecoder, EntityScanner, IfcType};
+use nalgebra::Matrix4;
+use rustc_hash::FxHashSet;
+
+/// Maximum nesting of mapped items
+const MAX_ITEM_DEPTH: usize = 8;
+
+impl GeometryRouter {
+    /// Light sources in an element's representations, in meters with the
+    /// RTC offset applied, so they line up with the element's mesh.
+    pub fn process_element_lights(
+        &self,
+        element: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Vec<Light>> {
+        let mut lights = Vec::new();
+        // IfcProduct attr 6: Representation → IfcProductDefinitionShape
+        let Some(representation_attr) = element.get(6).filter(|attr| !attr.is_null()) else {
+            return Ok(lights);
+        };
+        let Some(representation) = decoder.resolve_ref(representation_attr)? else {
+            return Ok(lights);
+        };
+        let Some(representations_attr) = representation.get(2) else {
+            return Ok(lights);
+        };
+
+        let placement = self.get_placement_transform_from_element(element, decoder)?;
+        for shape_rep in decoder.resolve_ref_list(representations_attr)? {
+            if shape_rep.ifc_type != IfcType::IfcShapeRepresentation {
+                continue;
+            }
+            let Some(items_attr) = shape_rep.get(3) else {
+                continue;
+            };
+            for item in decoder.resolve_ref_list(items_attr)? {
+                self.collect_item_lights(&item, decoder, &placement, &mut lights, 0)?;
+            }
+        }
+
+        for light in &mut lights {
+            light.element_id = Some(element.id);
+        }
+        Ok(lights)
+    }
+
+    /// All light sources of a model: those of elements placed with their
+    /// element, then any not used by an element in their own coordinates.
+    pub fn extract_lights(&self, content: &str, decoder: &mut EntityDecoder) -> Lights {
+        let mut lights = Lights::new();
+        let mut sources = Vec::new();
+        let mut elements = Vec::new();
+        let mut scanner = EntityScanner::new(content);
+        while let Some((id, type_name, _, _)) = scanner.next_entity() {
+            if type_name.starts_with("IFCLIGHTSOURCE") {
+                sources.push(id);
+            } else if has_geometry_by_name(type_name) {
+                elements.push(id);
+            }
+        }
+        if sources.is_empty() {
+            return lights;
+        }
+
+        let mut placed = FxHashSet::default();
+        for id in elements {
+            let Ok(element) = decoder.decode_by_id(id) else {
+                continue;
+            };
+            let Ok(element_lights) = self.process_element_lights(&element, decoder) else {
+                continue;
+            };
+            for light in element_lights {
+                placed.insert(light.express_id);
+                lights.push(light);
+            }
+        }
+
+        for id in sources {
+            if placed.contains(&id) {
+                continue;
+            }
+            let Ok(source) = decoder.decode_by_id(id) else {
+                continue;
+            };
+            if let Ok(Some(mut light)) = Light::from_entity(&source, decoder) {
+                self.place_light(&mut light, &Matrix4::identity());
+                lights.push(light);
+            }
+        }
+        lights
+    }
+
+    /// Append the lights of one representation item. `transform` maps item
+    /// coordinates to world, in file units.
+    fn collect_item_lights(
+        &self,
+        item: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        transform: &Matrix4<f64>,
+        lights: &mut Vec<Light>,
+        depth: usize,
+    ) -> Result<()> {
+        if item.ifc_type == IfcType::IfcMappedItem {
+            // IfcMappedItem: MappingSource (IfcRepresentationMap), MappingTarget
+            if depth >= MAX_ITEM_DEPTH {
+                return Ok(());
+            }
+            let Some(source_id) = item.get_ref(0) else {
+                return Ok(());
+            };
+            let mut transform = *transform;
+            if let Some(mapping) = self.mapping_target_transform(item, decoder)? {
+                transform *= mapping;
+            }
+            // IfcRepresentationMap attr 1: MappedRepresentation, its attr 3: Items
+            let source = decoder.decode_by_id(source_id)?;
+            let Some(rep_id) = source.get_ref(1) else {
+                return Ok(());
+            };
+            let mapped_rep = decoder.decode_by_id(rep_id)?;
+            if let Some(items_attr) = mapped_rep.get(3) {
+                for sub_item in decoder.resolve_ref_list(items_attr)? {
+                    self.collect_item_lights(&sub_item, decoder, &transform, lights, depth + 1)?;
+                }
+            }
+        } else if let Some(mut light) = Light::from_entity(item, decoder)? {
+            self.place_light(&mut light, transform);
+            lights.push(light);
+        }
+        Ok(())
+    }
+
+    /// Move a light from item coordinates (file units) to world meters
+    fn place_light(&self, light: &mut Light, transform: &Matrix4<f64>) {
+        let unit_scale = self.unit_scale;
+        let world_direction = |direction: &mut nalgebra::Vector3<f64>| {
+            if let Some(moved) = transform.transform_vector(direction).try_normalize(1e-12) {
+                *direction = moved;
+            }
+        };
+        match &mut light.kind {
+            LightKind::Ambient => {}
+            LightKind::Directional { direction } => world_direction(direction),
+            LightKind::Positional {
+                position, radius, ..
+            } => {
+                *position = self.line_point(transform, position);
+                *radius *= unit_scale;
+            }
+            LightKind::Spot {
+                position,
+                direction,
+                radius,
+                ..
+            } => {
+                *position = self.line_point(transform, position);
+                world_direction(direction);
+                *radius *= unit_scale;
+            }
+        }
+    }
+}
@@ -6,8 +6,12 @@
 //!
 //! Routes IFC representation entities to appropriate processors based on type.
 
+mod annotation;
 mod caching;
 mod clipping;
+mod context;
+mod lights;
+mod parallel;
 mod processing;
 mod transforms;
 mod voids;
@@ -16,23 +20,35 @@ mod voids_2d;
 #[cfg(test)]
 mod tests;
 
+use caching::SharedCache;
+use crate::lod::{generate_lods, ElementLods, LodOptions};
 use crate::processors::{
     AdvancedBrepProcessor, BooleanClippingProcessor, ExtrudedAreaSolidProcessor,
-    FaceBasedSurfaceModelProcessor, FacetedBrepProcessor, MappedItemProcessor,
-    PolygonalFaceSetProcessor, RevolvedAreaSolidProcessor, ShellBasedSurfaceModelProcessor,
-    SweptDiskSolidProcessor, TriangulatedFaceSetProcessor,
+    ExtrudedAreaSolidTaperedProcessor, FaceBasedSurfaceModelProcessor, FacetedBrepProcessor,
+    MappedItemProcessor, PolygonalFaceSetProcessor, RevolvedAreaSolidProcessor,
+    RevolvedAreaSolidTaperedProcessor, ShellBasedSurfaceModelProcessor, SweptDiskSolidProcessor,
+    TriangulatedFaceSetProcessor, TriangulatedIrregularNetworkProcessor,
 };
-use crate::{Mesh, Result};
+use crate::visual_merge::{merge_adjacent_walls, MergeCandidate, MergedMesh, VisualMergeOptions};
+use crate::{
+    FilterPolicy, FilterReport, MaterialPalette, Mesh, NormalSmoothing, Result, StyleIndex,
+    TerrainDecimation, TessellationConfig, ThinExtrusionConfig,
+};
+pub use context::ProcessorContext;
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcSchema, IfcType};
 use nalgebra::Matrix4;
 use rustc_hash::FxHashMap;
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Geometry processor trait
-/// Each processor handles one type of IFC representation
-pub trait GeometryProcessor {
+/// Each processor handles one type of IFC representation. Processors are
+/// shared by all threads using a router, hence `Send + Sync`.
+///
+/// Downstream crates register their own processors with
+/// [`GeometryRouter::register`]; see [`ProcessorContext`] for the router
+/// services they can use.
+pub trait GeometryProcessor: Send + Sync {
     /// Process entity into mesh
     fn process(
         &self,
@@ -41,25 +57,49 @@ pub trait GeometryProcessor {
         schema: &IfcSchema,
     ) -> Result<Mesh>;
 
+    /// Process entity into mesh with access to router services. The router
+    /// always calls this; the default forwards to [`Self::process`].
+    fn process_with_context(
+        &self,
+        entity: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        context: &ProcessorContext,
+    ) -> Result<Mesh> {
+        self.process(entity, decoder, context.schema())
+    }
+
     /// Get supported IFC types
     fn supported_types(&self) -> Vec<IfcType>;
 }
 
+/// An element instancing a shared IfcRepresentationMap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MappedInstance {
+    /// Express ID of the IfcRepresentationMap
+    pub source_id: u32,
+    /// Map coordinates to world (placement × MappingTarget), in meters
+    /// with the router's RTC offset applied
+    pub transform: Matrix4<f64>,
+}
+
 /// Geometry router - routes entities to processors
 pub struct GeometryRouter {
     schema: IfcSchema,
     processors: HashMap<IfcType, Arc<dyn GeometryProcessor>>,
+    /// Types served by processors from [`Self::register`], which rebuilding
+    /// the default processors leaves in place
+    custom_types: HashSet<IfcType>,
     /// Cache for IfcRepresentationMap source geometry (MappedItem instancing)
     /// Key: RepresentationMap entity ID, Value: Processed mesh
-    mapped_item_cache: RefCell<FxHashMap<u32, Arc<Mesh>>>,
+    mapped_item_cache: SharedCache<u32, Arc<Mesh>>,
     /// Cache for FacetedBrep geometry (batch processed)
     /// Key: FacetedBrep entity ID, Value: Processed mesh
     /// Uses Box to avoid copying large meshes, entries are taken (removed) when used
-    faceted_brep_cache: RefCell<FxHashMap<u32, Mesh>>,
+    faceted_brep_cache: SharedCache<u32, Mesh>,
     /// Cache for geometry deduplication by content hash
     /// Buildings with repeated floors have 99% identical geometry
     /// Key: Hash of mesh content, Value: Processed mesh
-    geometry_hash_cache: RefCell<FxHashMap<u64, Arc<Mesh>>>,
+    geometry_hash_cache: SharedCache<u64, Arc<Mesh>>,
     /// Unit scale factor (e.g., 0.001 for millimeters -> meters)
     /// Applied to all mesh positions after processing
     unit_scale: f64,
@@ -67,48 +107,179 @@ pub struct GeometryRouter {
     /// Subtracted from all world positions in f64 before converting to f32
     /// This preserves precision for georeferenced models (e.g., Swiss UTM)
     rtc_offset: (f64, f64, f64),
+    /// Plane angle unit in radians (π/180 for degrees), `None` if the model
+    /// declares none
+    plane_angle_unit: Option<f64>,
+    /// Curve and surface tessellation quality (chord tolerance in meters)
+    tessellation: TessellationConfig,
+    /// Minimum-thickness policy for extrusions (min depth in meters)
+    thin_extrusion: ThinExtrusionConfig,
+    /// Levels of detail emitted by `process_element_lods`
+    lod_options: LodOptions,
+    /// Crease-angle normal smoothing of item meshes, off when `None`
+    normal_smoothing: Option<NormalSmoothing>,
+    /// Styled geometry items and their material palette, used to tag sub-meshes
+    styles: StyleIndex,
+    /// Thresholds for excluding misplaced meshes
+    filter_policy: FilterPolicy,
+    /// Triangle budget for terrain surfaces, kept as surveyed when `None`
+    terrain_decimation: Option<TerrainDecimation>,
+    /// Display-only merging of wall runs, off when `None`
+    visual_merge: Option<VisualMergeOptions>,
 }
 
 impl GeometryRouter {
     /// Create new router with default processors
     pub fn new() -> Self {
-        let schema = IfcSchema::new();
-        let schema_clone = schema.clone();
         let mut router = Self {
-            schema,
+            schema: IfcSchema::new(),
             processors: HashMap::new(),
-            mapped_item_cache: RefCell::new(FxHashMap::default()),
-            faceted_brep_cache: RefCell::new(FxHashMap::default()),
-            geometry_hash_cache: RefCell::new(FxHashMap::default()),
+            custom_types: HashSet::new(),
+            mapped_item_cache: SharedCache::default(),
+            faceted_brep_cache: SharedCache::default(),
+            geometry_hash_cache: SharedCache::default(),
             unit_scale: 1.0,             // Default to base meters
             rtc_offset: (0.0, 0.0, 0.0), // Default to no offset
+            plane_angle_unit: None,
+            tessellation: TessellationConfig::default(),
+            thin_extrusion: ThinExtrusionConfig::default(),
+            lod_options: LodOptions::default(),
+            normal_smoothing: None,
+            styles: StyleIndex::default(),
+            filter_policy: FilterPolicy::default(),
+            terrain_decimation: None,
+            visual_merge: None,
         };
+        router.register_default_processors();
+        router
+    }
 
-        // Register default P0 processors
-        router.register(Box::new(ExtrudedAreaSolidProcessor::new(
-            schema_clone.clone(),
+    /// Register default P0 processors, configured with the router's
+    /// tessellation quality converted to file units
+    fn register_default_processors(&mut self) {
+        let schema = self.schema.clone();
+        let tessellation = self.tessellation.in_model_units(self.unit_scale);
+        let thin_extrusion = self.thin_extrusion.in_model_units(self.unit_scale);
+
+        self.register_builtin(Box::new(
+            ExtrudedAreaSolidProcessor::with_tessellation(schema.clone(), tessellation)
+                .with_thin_extrusion(thin_extrusion),
+        ));
+        self.register_builtin(Box::new(TriangulatedFaceSetProcessor::new()));
+        self.register_builtin(Box::new(TriangulatedIrregularNetworkProcessor::new()));
+        self.register_builtin(Box::new(PolygonalFaceSetProcessor::new()));
+        self.register_builtin(Box::new(
+            MappedItemProcessor::with_tessellation(tessellation)
+                .with_thin_extrusion(thin_extrusion),
+        ));
+        self.register_builtin(Box::new(FacetedBrepProcessor::new()));
+        self.register_builtin(Box::new(
+            BooleanClippingProcessor::with_tessellation(tessellation)
+                .with_thin_extrusion(thin_extrusion),
+        ));
+        self.register_builtin(Box::new(SweptDiskSolidProcessor::with_tessellation(
+            schema.clone(),
+            tessellation,
         )));
-        router.register(Box::new(TriangulatedFaceSetProcessor::new()));
-        router.register(Box::new(PolygonalFaceSetProcessor::new()));
-        router.register(Box::new(MappedItemProcessor::new()));
-        router.register(Box::new(FacetedBrepProcessor::new()));
-        router.register(Box::new(BooleanClippingProcessor::new()));
-        router.register(Box::new(SweptDiskSolidProcessor::new(schema_clone.clone())));
-        router.register(Box::new(RevolvedAreaSolidProcessor::new(
-            schema_clone.clone(),
+        self.register_builtin(Box::new(RevolvedAreaSolidProcessor::with_tessellation(
+            schema.clone(),
+            tessellation,
         )));
-        router.register(Box::new(AdvancedBrepProcessor::new()));
-        router.register(Box::new(ShellBasedSurfaceModelProcessor::new()));
-        router.register(Box::new(FaceBasedSurfaceModelProcessor::new()));
+        self.register_builtin(Box::new(
+            ExtrudedAreaSolidTaperedProcessor::with_tessellation(schema.clone(), tessellation),
+        ));
+        self.register_builtin(Box::new(
+            RevolvedAreaSolidTaperedProcessor::with_tessellation(schema, tessellation),
+        ));
+        self.register_builtin(Box::new(
+            AdvancedBrepProcessor::with_tessellation(tessellation)
+                .with_plane_angle_unit(self.plane_angle_unit),
+        ));
+        self.register_builtin(Box::new(
+            ShellBasedSurfaceModelProcessor::with_tessellation(tessellation)
+                .with_plane_angle_unit(self.plane_angle_unit),
+        ));
+        self.register_builtin(Box::new(
+            FaceBasedSurfaceModelProcessor::with_tessellation(tessellation)
+                .with_plane_angle_unit(self.plane_angle_unit),
+        ));
+    }
 
+    /// Create router with custom tessellation quality
+    pub fn with_tessellation(tessellation: TessellationConfig) -> Self {
+        let mut router = Self::new();
+        router.set_tessellation(tessellation);
         router
     }
 
+    /// Set curve and surface tessellation quality.
+    ///
+    /// Re-creates the default processors and clears cached meshes, which were
+    /// tessellated with the previous settings. Processors added with
+    /// [`Self::register`] are kept as they are.
+    pub fn set_tessellation(&mut self, tessellation: TessellationConfig) {
+        self.tessellation = tessellation;
+        self.mapped_item_cache.clear();
+        self.geometry_hash_cache.clear();
+        self.register_default_processors();
+    }
+
+    /// Get the current tessellation quality
+    pub fn tessellation(&self) -> &TessellationConfig {
+        &self.tessellation
+    }
+
+    /// Set how extrusions thinner than the minimum depth are meshed.
+    ///
+    /// Like [`Self::set_tessellation`], this re-creates the default processors
+    /// (keeping registered ones) and clears cached meshes.
+    pub fn set_thin_extrusion(&mut self, thin_extrusion: ThinExtrusionConfig) {
+        self.thin_extrusion = thin_extrusion;
+        self.mapped_item_cache.clear();
+        self.geometry_hash_cache.clear();
+        self.register_default_processors();
+    }
+
+    /// Get the current minimum-thickness policy
+    pub fn thin_extrusion(&self) -> &ThinExtrusionConfig {
+        &self.thin_extrusion
+    }
+
+    /// Set the plane angle unit in radians (π/180 for degrees), used for
+    /// surface angles such as a cone's SemiAngle. With `None`, angles are
+    /// read as radians unless they are only plausible as degrees.
+    ///
+    /// Like [`Self::set_tessellation`], this re-creates the default processors
+    /// (keeping registered ones) and clears cached meshes.
+    pub fn set_plane_angle_unit(&mut self, plane_angle_unit: Option<f64>) {
+        if self.plane_angle_unit == plane_angle_unit {
+            return;
+        }
+        self.plane_angle_unit = plane_angle_unit;
+        self.mapped_item_cache.clear();
+        self.geometry_hash_cache.clear();
+        self.register_default_processors();
+    }
+
+    /// Get the plane angle unit in radians, `None` if not declared
+    pub fn plane_angle_unit(&self) -> Option<f64> {
+        self.plane_angle_unit
+    }
+
     /// Create router and extract unit scale from IFC file
-    /// Automatically finds IFCPROJECT and extracts length unit conversion
+    /// Automatically finds IFCPROJECT and extracts length and plane angle units
     pub fn with_units(content: &str, decoder: &mut EntityDecoder) -> Self {
+        let (scale, plane_angle_unit) = Self::project_units(content, decoder);
+        let mut router = Self::with_scale(scale);
+        router.set_plane_angle_unit(plane_angle_unit);
+        router
+    }
+
+    /// Length unit scale and plane angle unit of the file's IFCPROJECT
+    fn project_units(content: &str, decoder: &mut EntityDecoder) -> (f64, Option<f64>) {
         let mut scanner = ifc_lite_core::EntityScanner::new(content);
         let mut scale = 1.0;
+        let mut plane_angle_unit = None;
 
         // Scan through file to find IFCPROJECT
         while let Some((id, type_name, _, _)) = scanner.next_entity() {
@@ -116,11 +287,14 @@ impl GeometryRouter {
                 if let Ok(s) = ifc_lite_core::extract_length_unit_scale(decoder, id) {
                     scale = s;
                 }
+                plane_angle_unit = ifc_lite_core::extract_plane_angle_unit_scale(decoder, id)
+                    .ok()
+                    .flatten();
                 break;
             }
         }
 
-        Self::with_scale(scale)
+        (scale, plane_angle_unit)
     }
 
     /// Create router with unit scale extracted from IFC file AND RTC offset for large coordinates
@@ -135,26 +309,17 @@ impl GeometryRouter {
         decoder: &mut ifc_lite_core::EntityDecoder,
         rtc_offset: (f64, f64, f64),
     ) -> Self {
-        let mut scanner = ifc_lite_core::EntityScanner::new(content);
-        let mut scale = 1.0;
-
-        // Scan through file to find IFCPROJECT
-        while let Some((id, type_name, _, _)) = scanner.next_entity() {
-            if type_name == "IFCPROJECT" {
-                if let Ok(s) = ifc_lite_core::extract_length_unit_scale(decoder, id) {
-                    scale = s;
-                }
-                break;
-            }
-        }
-
-        Self::with_scale_and_rtc(scale, rtc_offset)
+        let (scale, plane_angle_unit) = Self::project_units(content, decoder);
+        let mut router = Self::with_scale_and_rtc(scale, rtc_offset);
+        router.set_plane_angle_unit(plane_angle_unit);
+        router
     }
 
     /// Create router with pre-calculated unit scale
     pub fn with_scale(unit_scale: f64) -> Self {
         let mut router = Self::new();
         router.unit_scale = unit_scale;
+        router.register_default_processors();
         router
     }
 
@@ -171,6 +336,7 @@ impl GeometryRouter {
         let mut router = Self::new();
         router.unit_scale = unit_scale;
         router.rtc_offset = rtc_offset;
+        router.register_default_processors();
         router
     }
 
@@ -218,14 +384,27 @@ impl GeometryRouter {
         }
     }
 
-    /// Register a geometry processor
+    /// Register a geometry processor for its supported types, replacing any
+    /// processor previously registered for them
     pub fn register(&mut self, processor: Box<dyn GeometryProcessor>) {
         let processor_arc: Arc<dyn GeometryProcessor> = Arc::from(processor);
         for ifc_type in processor_arc.supported_types() {
+            self.custom_types.insert(ifc_type);
             self.processors.insert(ifc_type, Arc::clone(&processor_arc));
         }
     }
 
+    /// Register a default processor for the supported types that have no
+    /// processor from [`Self::register`]
+    fn register_builtin(&mut self, processor: Box<dyn GeometryProcessor>) {
+        let processor_arc: Arc<dyn GeometryProcessor> = Arc::from(processor);
+        for ifc_type in processor_arc.supported_types() {
+            if !self.custom_types.contains(&ifc_type) {
+                self.processors.insert(ifc_type, Arc::clone(&processor_arc));
+            }
+        }
+    }
+
     /// Batch preprocess FacetedBrep entities for maximum parallelism
     /// Call this before processing elements to enable batch triangulation
     /// across all FacetedBrep entities instead of per-entity parallelism
@@ -246,7 +425,7 @@ impl GeometryRouter {
         let results = processor.process_batch(brep_ids, decoder, rtc_file_units);
 
         // Store results in cache (preallocate to avoid rehashing)
-        let mut cache = self.faceted_brep_cache.borrow_mut();
+        let mut cache = self.faceted_brep_cache.write();
         cache.reserve(results.len());
         for (brep_idx, mesh) in results {
             let brep_id = brep_ids[brep_idx];
@@ -258,7 +437,7 @@ impl GeometryRouter {
     /// Returns owned Mesh directly - no cloning needed
     #[inline]
     pub fn take_cached_faceted_brep(&self, brep_id: u32) -> Option<Mesh> {
-        self.faceted_brep_cache.borrow_mut().remove(&brep_id)
+        self.faceted_brep_cache.remove(&brep_id)
     }
 
     /// Resolve an element's ObjectPlacement to a scaled world-space transform matrix.
@@ -282,6 +461,116 @@ impl GeometryRouter {
     pub fn schema(&self) -> &IfcSchema {
         &self.schema
     }
+
+    /// Set which levels of detail `process_element_lods` emits
+    pub fn set_lod_options(&mut self, options: LodOptions) {
+        self.lod_options = options;
+    }
+
+    /// Get the current level-of-detail options
+    pub fn lod_options(&self) -> &LodOptions {
+        &self.lod_options
+    }
+
+    /// Smooth item normals across edges flatter than the crease angle, or
+    /// keep the processors' per-face normals with `None` (the default).
+    ///
+    /// Clears cached meshes, which carry the previous normals.
+    pub fn set_normal_smoothing(&mut self, smoothing: Option<NormalSmoothing>) {
+        self.normal_smoothing = smoothing;
+        self.mapped_item_cache.clear();
+        self.geometry_hash_cache.clear();
+    }
+
+    /// Get the current normal smoothing
+    pub fn normal_smoothing(&self) -> Option<&NormalSmoothing> {
+        self.normal_smoothing.as_ref()
+    }
+
+    /// Set the thresholds used by [`Self::filter_mesh`]
+    pub fn set_filter_policy(&mut self, policy: FilterPolicy) {
+        self.filter_policy = policy;
+    }
+
+    /// Get the current outlier filter policy
+    pub fn filter_policy(&self) -> &FilterPolicy {
+        &self.filter_policy
+    }
+
+    /// Repair a processed mesh and check it against the filter policy, so
+    /// every caller excludes (or reports) the same misplaced meshes.
+    pub fn filter_mesh(&self, mesh: &mut Mesh) -> FilterReport {
+        self.filter_policy.apply(mesh)
+    }
+
+    /// Decimate terrain surfaces (IfcTriangulatedIrregularNetwork items and
+    /// IfcGeographicElement meshes) over a triangle budget, or keep every
+    /// surveyed triangle with `None` (the default).
+    ///
+    /// Clears cached meshes, which may hold undecimated terrain.
+    pub fn set_terrain_decimation(&mut self, decimation: Option<TerrainDecimation>) {
+        self.terrain_decimation = decimation;
+        self.mapped_item_cache.clear();
+        self.geometry_hash_cache.clear();
+    }
+
+    /// Get the current terrain decimation
+    pub fn terrain_decimation(&self) -> Option<&TerrainDecimation> {
+        self.terrain_decimation.as_ref()
+    }
+
+    /// Merge end-to-end wall segments in [`Self::merge_wall_meshes`], or keep
+    /// one mesh per element with `None` (the default). Merged meshes are for
+    /// display only.
+    pub fn set_visual_merge(&mut self, options: Option<VisualMergeOptions>) {
+        self.visual_merge = options;
+    }
+
+    /// Get the current visual merge options
+    pub fn visual_merge(&self) -> Option<&VisualMergeOptions> {
+        self.visual_merge.as_ref()
+    }
+
+    /// Merge processed wall meshes for display when visual merging is
+    /// enabled; otherwise every candidate comes back as its own mesh
+    pub fn merge_wall_meshes(&self, candidates: &[MergeCandidate]) -> Vec<MergedMesh> {
+        match &self.visual_merge {
+            Some(options) => merge_adjacent_walls(candidates, options),
+            None => candidates.iter().map(MergedMesh::from_candidate).collect(),
+        }
+    }
+
+    /// Resolve the file's surface styles so sub-meshes carry material IDs
+    pub fn index_styles(&mut self, content: &str, decoder: &mut EntityDecoder) {
+        self.styles = StyleIndex::build(content, decoder);
+    }
+
+    /// Use a prebuilt style index (e.g. shared between routers)
+    pub fn set_style_index(&mut self, styles: StyleIndex) {
+        self.styles = styles;
+    }
+
+    /// Styled geometry items indexed by [`Self::index_styles`]
+    pub fn style_index(&self) -> &StyleIndex {
+        &self.styles
+    }
+
+    /// Distinct materials referenced by sub-mesh material IDs
+    pub fn material_palette(&self) -> &MaterialPalette {
+        self.styles.palette()
+    }
+
+    /// Process element (with opening cutouts) once and derive all configured
+    /// levels of detail from the resulting mesh
+    pub fn process_element_lods(
+        &self,
+        element: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        void_index: &FxHashMap<u32, Vec<u32>>,
+    ) -> Result<ElementLods> {
+        let full = self.process_element_with_voids(element, decoder, void_index)?;
+        Ok(generate_lods(full, &self.lod_options))
+    }
 }
 
 impl Default for GeometryRouter {
@@ -0,0 +1,67 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Element-level parallelism on the rayon pool.
+//!
+//! The router is `Sync`: processors are shared and the caches are behind
+//! short-lived locks, so one configured router (units, RTC offset, styles,
+//! preprocessed FacetedBreps) serves every worker. Decoders are not shared;
+//! each rayon job gets its own over the common entity index.
+
+use super::GeometryRouter;
+use crate::{Mesh, Result};
+use ifc_lite_core::{DecodedEntity, EntityDecoder, EntityIndex};
+use rayon::prelude::*;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+impl GeometryRouter {
+    /// Run `f` for every element on the rayon pool, in input order.
+    ///
+    /// Each worker decodes through its own [`EntityDecoder`] sharing `index`.
+    /// Elements that fail to decode are reported as errors without calling `f`.
+    pub fn map_elements_parallel<T, F>(
+        &self,
+        content: &str,
+        index: &Arc<EntityIndex>,
+        element_ids: &[u32],
+        f: F,
+    ) -> Vec<(u32, Result<T>)>
+    where
+        T: Send,
+        F: Fn(&Self, &DecodedEntity, &mut EntityDecoder) -> Result<T> + Sync,
+    {
+        element_ids
+            .par_iter()
+            .map_init(
+                || EntityDecoder::with_arc_index(content, Arc::clone(index)),
+                |decoder, &id| {
+                    let result = decoder
+                        .decode_by_id(id)
+                        .map_err(Into::into)
+                        .and_then(|entity| f(self, &entity, decoder));
+                    (id, result)
+                },
+            )
+            .collect()
+    }
+
+    /// Mesh elements in parallel, cutting openings from `void_index` and
+    /// falling back to the plain mesh when void cutting fails or yields
+    /// nothing (the same policy as the serial pipeline)
+    pub fn process_elements_parallel(
+        &self,
+        content: &str,
+        index: &Arc<EntityIndex>,
+        element_ids: &[u32],
+        void_index: &FxHashMap<u32, Vec<u32>>,
+    ) -> Vec<(u32, Result<Mesh>)> {
+        self.map_elements_parallel(content, index, element_ids, |router, entity, decoder| {
+            match router.process_element_with_voids(entity, decoder, void_index) {
+                Ok(mesh) if !mesh.is_empty() => Ok(mesh),
+                _ => router.process_element(entity, decoder),
+            }
+        })
+    }
+}
@@ -4,8 +4,11 @@
 
 //! Core element processing: resolving representations, processing items, and caching.
 
-use super::GeometryRouter;
-use crate::{Error, Mesh, Result, SubMeshCollection};
+use super::{GeometryRouter, MappedInstance};
+use crate::terrain::{decimate_terrain, is_terrain};
+use crate::{
+    repair_orientation, smooth_normals, Error, MaterialId, Mesh, Result, SubMeshCollection,
+};
 use ifc_lite_core::{
     has_geometry_by_name, DecodedEntity, EntityDecoder, GeometryCategory, IfcType,
 };
@@ -167,7 +170,9 @@ impl GeometryRouter {
 
                     // ── Tessellated path ──
                     // attr 0 = Coordinates (IfcCartesianPointList3D)
-                    IfcType::IfcTriangulatedFaceSet | IfcType::IfcPolygonalFaceSet => {
+                    IfcType::IfcTriangulatedFaceSet
+                    | IfcType::IfcTriangulatedIrregularNetwork
+                    | IfcType::IfcPolygonalFaceSet => {
                         if let Some(pt) = self.tessellated_first_vertex(&item, decoder) {
                             return Some(pt);
                         }
@@ -438,6 +443,10 @@ impl GeometryRouter {
             }
         }
 
+        if is_terrain(&element.ifc_type) {
+            self.decimate_terrain(&mut combined_mesh);
+        }
+
         // Apply placement transformation
         self.apply_placement(element, decoder, &mut combined_mesh)?;
 
@@ -559,6 +568,12 @@ impl GeometryRouter {
             }
         }
 
+        if is_terrain(&element.ifc_type) {
+            for sub in &mut sub_meshes.sub_meshes {
+                self.decimate_terrain(&mut sub.mesh);
+            }
+        }
+
         // Apply placement transformation to all sub-meshes
         // ObjectPlacement translation is in file units (e.g., mm) but geometry is scaled to meters,
         // so we MUST scale the transform to match. Same as apply_placement does.
@@ -585,9 +600,11 @@ impl GeometryRouter {
         sub_meshes: &mut SubMeshCollection,
     ) -> Result<()> {
         let mut visited = FxHashSet::default();
-        self.collect_submeshes_from_item_inner(item, decoder, sub_meshes, 0, &mut visited)
+        self.collect_submeshes_from_item_inner(item, decoder, sub_meshes, 0, &mut visited, None)
     }
 
+    /// `inherited` is the material of the enclosing styled MappedItem, used by
+    /// mapped geometry that carries no style of its own.
     fn collect_submeshes_from_item_inner(
         &self,
         item: &DecodedEntity,
@@ -595,7 +612,10 @@ impl GeometryRouter {
         sub_meshes: &mut SubMeshCollection,
         depth: usize,
         visited: &mut FxHashSet<u32>,
+        inherited: Option<MaterialId>,
     ) -> Result<()> {
+        let material = self.styles.material_for_item(item.id).or(inherited);
+
         if depth >= MAX_MAPPED_ITEM_DEPTH {
             return Err(Error::geometry(format!(
                 "MappedItem nesting exceeded maximum depth of {} at #{}",
@@ -657,6 +677,7 @@ impl GeometryRouter {
                         sub_meshes,
                         depth + 1,
                         visited,
+                        material,
                     ) {
                         #[cfg(debug_assertions)]
                         eprintln!(
@@ -683,7 +704,7 @@ impl GeometryRouter {
             match self.process_representation_item(item, decoder) {
                 Ok(mesh) => {
                     if !mesh.is_empty() {
-                        sub_meshes.add(item.id, mesh);
+                        sub_meshes.add_with_material(item.id, mesh, material);
                     }
                 }
                 Err(_e) => {
@@ -707,6 +728,87 @@ impl GeometryRouter {
         element: &DecodedEntity,
         decoder: &mut EntityDecoder,
     ) -> Result<(Mesh, Matrix4<f64>)> {
+        // Process all body items and merge meshes
+        let mut combined_mesh = Mesh::new();
+        for item in self.body_items(element, decoder)? {
+            let mesh = self.process_representation_item(&item, decoder)?;
+            combined_mesh.merge(&mesh);
+        }
+        if is_terrain(&element.ifc_type) {
+            self.decimate_terrain(&mut combined_mesh);
+        }
+
+        // Get placement transform WITHOUT applying it
+        let transform = self.get_placement_transform_from_element(element, decoder)?;
+
+        Ok((combined_mesh, transform))
+    }
+
+    /// Detect an element whose body is a single IfcMappedItem, without
+    /// tessellating anything.
+    ///
+    /// Elements sharing an IfcRepresentationMap can then be emitted as one
+    /// mesh ([`Self::process_representation_map`]) plus a transform each,
+    /// instead of tessellating every element and comparing mesh hashes.
+    /// Returns `None` for elements with any other body geometry.
+    pub fn mapped_instance(
+        &self,
+        element: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Option<MappedInstance>> {
+        let items = self.body_items(element, decoder)?;
+        let [item] = items.as_slice() else {
+            return Ok(None);
+        };
+        if item.ifc_type != IfcType::IfcMappedItem {
+            return Ok(None);
+        }
+        // IfcMappedItem: MappingSource, MappingTarget
+        let Some(source_id) = item.get_ref(0) else {
+            return Ok(None);
+        };
+
+        let mut transform = self.get_placement_transform_from_element(element, decoder)?;
+        self.scale_transform(&mut transform);
+        if let Some(mut mapping) = self.mapping_target_transform(item, decoder)? {
+            self.scale_transform(&mut mapping);
+            transform *= mapping;
+        }
+        if self.has_rtc_offset() {
+            let (x, y, z) = self.rtc_offset;
+            transform[(0, 3)] -= x;
+            transform[(1, 3)] -= y;
+            transform[(2, 3)] -= z;
+        }
+
+        Ok(Some(MappedInstance {
+            source_id,
+            transform,
+        }))
+    }
+
+    /// Mesh of an IfcRepresentationMap in its own coordinates, scaled to
+    /// meters. Tessellated once and cached for all its instances.
+    pub fn process_representation_map(
+        &self,
+        source_id: u32,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Mesh> {
+        let source = decoder.decode_by_id(source_id)?;
+        let mut mesh = (*self.mapped_source_mesh(&source, decoder)?).clone();
+        if let Some(smoothing) = &self.normal_smoothing {
+            smooth_normals(&mut mesh, smoothing);
+        }
+        Ok(mesh)
+    }
+
+    /// Items of an element's body representations. MappedRepresentation
+    /// bodies are skipped when direct geometry is present.
+    fn body_items(
+        &self,
+        element: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Vec<DecodedEntity>> {
         // Get representation (attribute 6 for most building elements)
         let representation_attr = element.get(6).ok_or_else(|| {
             Error::geometry(format!(
@@ -716,7 +818,7 @@ impl GeometryRouter {
         })?;
 
         if representation_attr.is_null() {
-            return Ok((Mesh::new(), Matrix4::identity())); // No geometry
+            return Ok(Vec::new()); // No geometry
         }
 
         let representation = decoder
@@ -737,9 +839,6 @@ impl GeometryRouter {
 
         let representations = decoder.resolve_ref_list(representations_attr)?;
 
-        // Process all representations and merge meshes
-        let mut combined_mesh = Mesh::new();
-
         // Check for direct geometry
         let has_direct_geometry = representations.iter().any(|rep| {
             if rep.ifc_type != IfcType::IfcShapeRepresentation {
@@ -768,6 +867,7 @@ impl GeometryRouter {
             }
         });
 
+        let mut body_items = Vec::new();
         for shape_rep in representations {
             if shape_rep.ifc_type != IfcType::IfcShapeRepresentation {
                 continue;
@@ -802,27 +902,48 @@ impl GeometryRouter {
                 Error::geometry("IfcShapeRepresentation missing Items".to_string())
             })?;
 
-            let items = decoder.resolve_ref_list(items_attr)?;
-
-            for item in items {
-                let mesh = self.process_representation_item(&item, decoder)?;
-                combined_mesh.merge(&mesh);
-            }
+            body_items.extend(decoder.resolve_ref_list(items_attr)?);
         }
 
-        // Get placement transform WITHOUT applying it
-        let transform = self.get_placement_transform_from_element(element, decoder)?;
-
-        Ok((combined_mesh, transform))
+        Ok(body_items)
     }
 
     /// Process a single representation item (IfcExtrudedAreaSolid, etc.)
-    /// Uses hash-based caching for geometry deduplication across repeated floors
-    #[inline]
+    /// Uses hash-based caching for geometry deduplication across repeated floors.
+    /// Normals are smoothed when the router has a crease angle configured.
     pub fn process_representation_item(
         &self,
         item: &DecodedEntity,
         decoder: &mut EntityDecoder,
+    ) -> Result<Mesh> {
+        let mut mesh = self.process_representation_item_faceted(item, decoder)?;
+        if has_unreliable_winding(item.ifc_type) {
+            repair_orientation(&mut mesh);
+        }
+        if is_terrain(&item.ifc_type) {
+            self.decimate_terrain(&mut mesh);
+        }
+        if let Some(smoothing) = &self.normal_smoothing {
+            smooth_normals(&mut mesh, smoothing);
+        }
+        Ok(mesh)
+    }
+
+    /// Reduce a terrain mesh to the configured triangle budget, if any
+    fn decimate_terrain(&self, mesh: &mut Mesh) {
+        let Some(options) = &self.terrain_decimation else {
+            return;
+        };
+        if let Some(decimated) = decimate_terrain(mesh, options) {
+            *mesh = decimated;
+        }
+    }
+
+    #[inline]
+    fn process_representation_item_faceted(
+        &self,
+        item: &DecodedEntity,
+        decoder: &mut EntityDecoder,
     ) -> Result<Mesh> {
         // Special handling for MappedItem with caching
         if item.ifc_type == IfcType::IfcMappedItem {
@@ -863,7 +984,8 @@ impl GeometryRouter {
 
         // Check if we have a processor for this type
         if let Some(processor) = self.processors.get(&item.ifc_type) {
-            let mut mesh = processor.process(item, decoder, &self.schema)?;
+            let mut mesh =
+                processor.process_with_context(item, decoder, &self.processor_context())?;
             // Safety net: strip any out-of-bounds indices before downstream use
             mesh.validate_indices();
 
@@ -946,34 +1068,48 @@ impl GeometryRouter {
             .resolve_ref(source_attr)?
             .ok_or_else(|| Error::geometry("Failed to resolve MappingSource".to_string()))?;
 
-        let source_id = source_entity.id;
+        let mapping_transform = self.mapping_target_transform(item, decoder)?;
+        let mut mesh = (*self.mapped_source_mesh(&source_entity, decoder)?).clone();
 
-        // Get MappingTarget transformation (attribute 1: CartesianTransformationOperator)
-        let mapping_transform = if let Some(target_attr) = item.get(1) {
-            if !target_attr.is_null() {
-                if let Some(target_entity) = decoder.resolve_ref(target_attr)? {
-                    Some(self.parse_cartesian_transformation_operator(&target_entity, decoder)?)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        } else {
-            None
+        // Apply MappingTarget transformation to this instance
+        if let Some(mut transform) = mapping_transform {
+            self.scale_transform(&mut transform);
+            self.transform_mesh(&mut mesh, &transform);
+        }
+
+        Ok(mesh)
+    }
+
+    /// MappingTarget (attribute 1: CartesianTransformationOperator) of an
+    /// IfcMappedItem in file units, if set
+    pub(super) fn mapping_target_transform(
+        &self,
+        item: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Option<Matrix4<f64>>> {
+        let Some(target_attr) = item.get(1).filter(|attr| !attr.is_null()) else {
+            return Ok(None);
         };
+        match decoder.resolve_ref(target_attr)? {
+            Some(target) => self
+                .parse_cartesian_transformation_operator(&target, decoder)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Scaled mesh of an IfcRepresentationMap in source coordinates, cached
+    /// by its ID
+    fn mapped_source_mesh(
+        &self,
+        source_entity: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Arc<Mesh>> {
+        let source_id = source_entity.id;
 
         // Check cache first
-        {
-            let cache = self.mapped_item_cache.borrow();
-            if let Some(cached_mesh) = cache.get(&source_id) {
-                let mut mesh = cached_mesh.as_ref().clone();
-                if let Some(mut transform) = mapping_transform {
-                    self.scale_transform(&mut transform);
-                    self.transform_mesh(&mut mesh, &transform);
-                }
-                return Ok(mesh);
-            }
+        if let Some(cached_mesh) = self.mapped_item_cache.get(&source_id) {
+            return Ok(cached_mesh);
         }
 
         // Cache miss - process the geometry
@@ -1005,8 +1141,13 @@ impl GeometryRouter {
                 continue;
             }
             if let Some(processor) = self.processors.get(&sub_item.ifc_type) {
-                if let Ok(mut sub_mesh) = processor.process(&sub_item, decoder, &self.schema) {
+                if let Ok(mut sub_mesh) =
+                    processor.process_with_context(&sub_item, decoder, &self.processor_context())
+                {
                     sub_mesh.validate_indices();
+                    if has_unreliable_winding(sub_item.ifc_type) {
+                        repair_orientation(&mut sub_mesh);
+                    }
                     self.scale_mesh(&mut sub_mesh);
                     mesh.merge(&sub_mesh);
                 }
@@ -1014,17 +1155,19 @@ impl GeometryRouter {
         }
 
         // Store in cache (before transformation, so cached mesh is in source coordinates)
-        {
-            let mut cache = self.mapped_item_cache.borrow_mut();
-            cache.insert(source_id, Arc::new(mesh.clone()));
-        }
-
-        // Apply MappingTarget transformation to this instance
-        if let Some(mut transform) = mapping_transform {
-            self.scale_transform(&mut transform);
-            self.transform_mesh(&mut mesh, &transform);
-        }
-
+        let mesh = Arc::new(mesh);
+        self.mapped_item_cache.insert(source_id, Arc::clone(&mesh));
         Ok(mesh)
     }
 }
+
+/// Boundary representations whose face winding comes straight from the
+/// exporter and needs [`repair_orientation`]
+fn has_unreliable_winding(ifc_type: IfcType) -> bool {
+    matches!(
+        ifc_type,
+        IfcType::IfcFacetedBrep
+            | IfcType::IfcShellBasedSurfaceModel
+            | IfcType::IfcFaceBasedSurfaceModel
+    )
+}
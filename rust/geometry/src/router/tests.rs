@@ -49,6 +49,464 @@ fn test_parse_direction() {
     assert_eq!(vec.z, 0.0);
 }
 
+#[test]
+fn test_submeshes_carry_palette_material_ids() {
+    // Two styled extrusions share one surface style; the mapped one inherits
+    // the style of its IfcMappedItem
+    let content = r#"
+#1=IFCCOLOURRGB($,1.,0.,0.);
+#2=IFCSURFACESTYLESHADING(#1,0.);
+#3=IFCSURFACESTYLE('Red',.BOTH.,(#2));
+#10=IFCRECTANGLEPROFILEDEF(.AREA.,$,$,1.,1.);
+#11=IFCDIRECTION((0.,0.,1.));
+#12=IFCEXTRUDEDAREASOLID(#10,$,#11,1.);
+#13=IFCEXTRUDEDAREASOLID(#10,$,#11,2.);
+#14=IFCSTYLEDITEM(#12,(#3),$);
+#20=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#13));
+#21=IFCREPRESENTATIONMAP(#30,#20);
+#22=IFCMAPPEDITEM(#21,$);
+#23=IFCSTYLEDITEM(#22,(#3),$);
+#30=IFCAXIS2PLACEMENT3D(#31,$,$);
+#31=IFCCARTESIANPOINT((0.,0.,0.));
+#40=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#12,#22));
+#41=IFCPRODUCTDEFINITIONSHAPE($,$,(#40));
+#42=IFCWALL('guid',$,$,$,$,$,#41,$);
+"#;
+
+    let mut decoder = EntityDecoder::new(content);
+    let mut router = GeometryRouter::new();
+    router.index_styles(content, &mut decoder);
+    assert_eq!(router.material_palette().len(), 1);
+
+    let wall = decoder.decode_by_id(42).unwrap();
+    let sub_meshes = router
+        .process_element_with_submeshes(&wall, &mut decoder)
+        .unwrap();
+    assert_eq!(sub_meshes.len(), 2);
+    for sub in sub_meshes.iter() {
+        let material = sub.material.and_then(|id| router.material_palette().get(id));
+        assert_eq!(material.and_then(|m| m.name.as_deref()), Some("Red"));
+    }
+}
+
+#[test]
+fn test_parallel_elements_share_one_router() {
+    // Many walls instancing one representation map; every worker hits the
+    // shared mapped-item cache, results come back in input order
+    let mut content = String::from(
+        r#"
+#10=IFCRECTANGLEPROFILEDEF(.AREA.,$,$,1.,1.);
+#11=IFCDIRECTION((0.,0.,1.));
+#12=IFCEXTRUDEDAREASOLID(#10,$,#11,3.);
+#20=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#12));
+#21=IFCREPRESENTATIONMAP(#30,#20);
+#22=IFCMAPPEDITEM(#21,$);
+#30=IFCAXIS2PLACEMENT3D(#31,$,$);
+#31=IFCCARTESIANPOINT((0.,0.,0.));
+#40=IFCSHAPEREPRESENTATION($,'Body','MappedRepresentation',(#22));
+#41=IFCPRODUCTDEFINITIONSHAPE($,$,(#40));
+"#,
+    );
+    let ids: Vec<u32> = (100..164).collect();
+    for id in &ids {
+        content.push_str(&format!("#{id}=IFCWALL('g{id}',$,$,$,$,$,#41,$);\n"));
+    }
+    content.push_str("#999=IFCWALL('bad',$,$,$,$,$,$,$);\n");
+
+    let index = std::sync::Arc::new(ifc_lite_core::build_entity_index(&content));
+    let router = GeometryRouter::new();
+    let mut requested = ids.clone();
+    // No representation, and not in the file
+    requested.extend([999, 5000]);
+    let results = router.process_elements_parallel(
+        &content,
+        &index,
+        &requested,
+        &Default::default(),
+    );
+
+    assert_eq!(results.len(), requested.len());
+    for ((id, result), expected) in results.iter().zip(&requested) {
+        assert_eq!(id, expected);
+        match id {
+            999 => assert!(result.as_ref().is_ok_and(|mesh| mesh.is_empty())),
+            5000 => assert!(result.is_err()),
+            _ => assert_eq!(result.as_ref().unwrap().triangle_count(), 12),
+        }
+    }
+    assert!(router.mapped_item_cache.get(&21).is_some());
+}
+
+#[test]
+fn test_mapped_instances_share_representation_map() {
+    let content = r#"
+#10=IFCRECTANGLEPROFILEDEF(.AREA.,$,$,1000.,1000.);
+#11=IFCDIRECTION((0.,0.,1.));
+#12=IFCEXTRUDEDAREASOLID(#10,$,#11,3000.);
+#20=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#12));
+#21=IFCREPRESENTATIONMAP(#30,#20);
+#22=IFCMAPPEDITEM(#21,#25);
+#25=IFCCARTESIANTRANSFORMATIONOPERATOR3D($,$,#26,$,$);
+#26=IFCCARTESIANPOINT((0.,0.,500.));
+#30=IFCAXIS2PLACEMENT3D(#31,$,$);
+#31=IFCCARTESIANPOINT((0.,0.,0.));
+#40=IFCSHAPEREPRESENTATION($,'Body','MappedRepresentation',(#22));
+#41=IFCPRODUCTDEFINITIONSHAPE($,$,(#40));
+#50=IFCLOCALPLACEMENT($,#51);
+#51=IFCAXIS2PLACEMENT3D(#52,$,$);
+#52=IFCCARTESIANPOINT((2000.,0.,0.));
+#60=IFCWALL('a',$,$,$,$,#50,#41,$);
+#61=IFCWALL('b',$,$,$,$,$,#41,$);
+#70=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#12));
+#71=IFCPRODUCTDEFINITIONSHAPE($,$,(#70));
+#72=IFCWALL('c',$,$,$,$,#50,#71,$);
+"#;
+
+    let mut decoder = EntityDecoder::new(content);
+    let router = GeometryRouter::with_scale(0.001);
+
+    let placed = decoder.decode_by_id(60).unwrap();
+    let instance = router
+        .mapped_instance(&placed, &mut decoder)
+        .unwrap()
+        .unwrap();
+    assert_eq!(instance.source_id, 21);
+    let translation = instance.transform.column(3);
+    assert!((translation.x - 2.0).abs() < 1e-9 && (translation.z - 0.5).abs() < 1e-9);
+
+    let unplaced = decoder.decode_by_id(61).unwrap();
+    let other = router
+        .mapped_instance(&unplaced, &mut decoder)
+        .unwrap()
+        .unwrap();
+    assert_eq!(other.source_id, 21);
+    assert!((other.transform.column(3).x).abs() < 1e-9);
+
+    let direct = decoder.decode_by_id(72).unwrap();
+    assert!(router
+        .mapped_instance(&direct, &mut decoder)
+        .unwrap()
+        .is_none());
+
+    // The shared mesh stays in map coordinates, scaled to meters
+    let mesh = router.process_representation_map(21, &mut decoder).unwrap();
+    assert_eq!(mesh.triangle_count(), 12);
+    let top = mesh
+        .positions
+        .chunks_exact(3)
+        .map(|p| p[2])
+        .fold(f32::MIN, f32::max);
+    assert!((top - 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_terrain_tin_with_voids_and_decimation() {
+    // 20 x 20 grid of 1 m cells, second triangle flagged as a void
+    let n = 20;
+    let points: Vec<String> = (0..=n)
+        .flat_map(|j| (0..=n).map(move |i| format!("({i}.,{j}.,0.)")))
+        .collect();
+    let mut triangles = Vec::new();
+    for j in 0..n {
+        for i in 0..n {
+            let a = j * (n + 1) + i + 1;
+            triangles.push(format!("({},{},{})", a, a + 1, a + n + 2));
+            triangles.push(format!("({},{},{})", a, a + n + 2, a + n + 1));
+        }
+    }
+    let mut flags = vec!["0"; triangles.len()];
+    flags[1] = "-1";
+    let content = format!(
+        "#1=IFCCARTESIANPOINTLIST3D(({}),$);\n\
+         #2=IFCTRIANGULATEDIRREGULARNETWORK(#1,$,.F.,({}),$,({}));\n\
+         #3=IFCSHAPEREPRESENTATION($,'Body','Tessellation',(#2));\n\
+         #4=IFCPRODUCTDEFINITIONSHAPE($,$,(#3));\n\
+         #5=IFCGEOGRAPHICELEMENT('terrain',$,$,$,$,$,#4,$,.TERRAIN.);\n",
+        points.join(","),
+        triangles.join(","),
+        flags.join(",")
+    );
+
+    let mut decoder = EntityDecoder::new(&content);
+    let mut router = GeometryRouter::new();
+    let terrain = decoder.decode_by_id(5).unwrap();
+    let full = router.process_element(&terrain, &mut decoder).unwrap();
+    assert_eq!(full.triangle_count(), 2 * n * n - 1);
+
+    router.set_terrain_decimation(Some(
+        crate::TerrainDecimation::default().with_max_triangles(100),
+    ));
+    let decimated = router.process_element(&terrain, &mut decoder).unwrap();
+    assert!(decimated.triangle_count() < full.triangle_count() / 2);
+    let (min, max) = decimated.bounds();
+    assert_eq!((min.x, min.y, max.x, max.y), (0.0, 0.0, 20.0, 20.0));
+}
+
+#[test]
+fn test_annotation_lines() {
+    // Survey point, 3D polyline and a text placeholder, placed 1 m up
+    let content = r#"
+#1=IFCCARTESIANPOINT((0.,0.,0.));
+#2=IFCCARTESIANPOINT((1000.,0.,500.));
+#3=IFCCARTESIANPOINT((1000.,2000.,500.));
+#4=IFCPOLYLINE((#1,#2,#3));
+#5=IFCCARTESIANPOINT((3000.,3000.,0.));
+#6=IFCGEOMETRICCURVESET((#5));
+#7=IFCAXIS2PLACEMENT3D(#8,$,$);
+#8=IFCCARTESIANPOINT((-1000.,0.,0.));
+#9=IFCTEXTLITERAL('A1',#7,.LEFT.);
+#10=IFCSHAPEREPRESENTATION($,'Annotation','Curve3D',(#4,#6,#9));
+#11=IFCPRODUCTDEFINITIONSHAPE($,$,(#10));
+#12=IFCLOCALPLACEMENT($,#13);
+#13=IFCAXIS2PLACEMENT3D(#14,$,$);
+#14=IFCCARTESIANPOINT((0.,0.,1000.));
+#15=IFCANNOTATION('a',$,$,$,$,#12,#11);
+#20=IFCSHAPEREPRESENTATION($,'Annotation','Annotation2D',(#4));
+#21=IFCPRODUCTDEFINITIONSHAPE($,$,(#20));
+#22=IFCANNOTATION('b',$,$,$,$,$,#21);
+"#;
+
+    let mut decoder = EntityDecoder::new(content);
+    let router = GeometryRouter::with_scale(0.001);
+    let annotation = decoder.decode_by_id(15).unwrap();
+    let lines = router
+        .process_element_lines(&annotation, &mut decoder)
+        .unwrap();
+    // Two polyline segments plus two three-axis markers
+    assert_eq!(lines.segment_count(), 2 + 3 + 3);
+    assert_eq!(lines.positions[..6], [0.0, 0.0, 1.0, 1.0, 0.0, 1.5]);
+    let marker_x: Vec<f32> = lines.positions[9..15].to_vec();
+    assert_eq!(marker_x, [2.9, 3.0, 1.0, 3.1, 3.0, 1.0]);
+
+    // 2D annotation representations are left to the symbolic path
+    let plan = decoder.decode_by_id(22).unwrap();
+    assert!(router
+        .process_element_lines(&plan, &mut decoder)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_external_processor_with_context() {
+    use super::{GeometryProcessor, ProcessorContext};
+    use crate::{Error, Mesh, Result};
+    use ifc_lite_core::{DecodedEntity, IfcSchema, IfcType};
+
+    /// Proprietary ACMEPANEL(Body, Copies): the wrapped solid, repeated
+    /// along X at 2 m spacing
+    struct AcmePanelProcessor;
+
+    impl GeometryProcessor for AcmePanelProcessor {
+        fn process(&self, _: &DecodedEntity, _: &mut EntityDecoder, _: &IfcSchema) -> Result<Mesh> {
+            Err(Error::geometry("needs a router context".to_string()))
+        }
+
+        fn process_with_context(
+            &self,
+            entity: &DecodedEntity,
+            decoder: &mut EntityDecoder,
+            context: &ProcessorContext,
+        ) -> Result<Mesh> {
+            let body = decoder.decode_by_id(entity.get_ref(0).unwrap())?;
+            let copies = entity.get_float(1).unwrap_or(1.0) as usize;
+            let single = context.process_item(&body, decoder)?;
+            // Output stays in file units: 2 m spacing in millimetres
+            let spacing = (2.0 / context.unit_scale()) as f32;
+            let mut mesh = Mesh::new();
+            for i in 0..copies {
+                let mut copy = single.clone();
+                for p in copy.positions.chunks_exact_mut(3) {
+                    p[0] += spacing * i as f32;
+                }
+                mesh.merge(&copy);
+            }
+            Ok(mesh)
+        }
+
+        fn supported_types(&self) -> Vec<IfcType> {
+            vec![IfcType::from_str("IFCACMEPANEL")]
+        }
+    }
+
+    let content = r#"
+#10=IFCRECTANGLEPROFILEDEF(.AREA.,$,$,1000.,1000.);
+#11=IFCDIRECTION((0.,0.,1.));
+#12=IFCEXTRUDEDAREASOLID(#10,$,#11,100.);
+#13=IFCACMEPANEL(#12,3.);
+#20=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#13));
+#21=IFCPRODUCTDEFINITIONSHAPE($,$,(#20));
+#22=IFCBUILDINGELEMENTPROXY('p',$,$,$,$,$,#21,$,$);
+"#;
+    let mut decoder = EntityDecoder::new(content);
+    let proxy = decoder.decode_by_id(22).unwrap();
+    let mut router = GeometryRouter::with_scale(0.001);
+    assert!(router.process_element(&proxy, &mut decoder).is_err());
+
+    router.register(Box::new(AcmePanelProcessor));
+    let mesh = router.process_element(&proxy, &mut decoder).unwrap();
+    assert_eq!(mesh.triangle_count(), 36);
+    let (min, max) = mesh.bounds();
+    assert!((min.x + 0.5).abs() < 1e-5 && (max.x - 4.5).abs() < 1e-5);
+    assert!((max.z - 0.1).abs() < 1e-5);
+}
+
+#[test]
+fn test_custom_processor_survives_set_tessellation() {
+    use super::GeometryProcessor;
+    use crate::{Mesh, Point3, Result, TessellationConfig, Vector3};
+    use ifc_lite_core::{DecodedEntity, IfcSchema, IfcType};
+
+    /// Replaces extrusions with a single triangle
+    struct MarkerProcessor;
+
+    impl GeometryProcessor for MarkerProcessor {
+        fn process(&self, _: &DecodedEntity, _: &mut EntityDecoder, _: &IfcSchema) -> Result<Mesh> {
+            let mut mesh = Mesh::new();
+            for p in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)] {
+                mesh.add_vertex(Point3::new(p.0, p.1, 0.0), Vector3::z());
+            }
+            mesh.add_triangle(0, 1, 2);
+            Ok(mesh)
+        }
+
+        fn supported_types(&self) -> Vec<IfcType> {
+            vec![IfcType::IfcExtrudedAreaSolid]
+        }
+    }
+
+    let content = r#"
+#10=IFCRECTANGLEPROFILEDEF(.AREA.,$,$,1.,1.);
+#11=IFCDIRECTION((0.,0.,1.));
+#12=IFCEXTRUDEDAREASOLID(#10,$,#11,1.);
+#20=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#12));
+#21=IFCPRODUCTDEFINITIONSHAPE($,$,(#20));
+#22=IFCBUILDINGELEMENTPROXY('p',$,$,$,$,$,#21,$,$);
+"#;
+    let mut decoder = EntityDecoder::new(content);
+    let proxy = decoder.decode_by_id(22).unwrap();
+    let mut router = GeometryRouter::new();
+    router.register(Box::new(MarkerProcessor));
+    router.set_tessellation(TessellationConfig::high_quality());
+
+    let mesh = router.process_element(&proxy, &mut decoder).unwrap();
+    assert_eq!(mesh.triangle_count(), 1);
+    assert_eq!(router.tessellation(), &TessellationConfig::high_quality());
+}
+
+#[test]
+fn test_extract_lights() {
+    // Fixture type with a downlight spot 100 mm below its origin, placed
+    // twice at 3 m height and turned 90° about Z; plus a loose ambient light
+    let content = r#"
+#1=IFCCOLOURRGB($,1.,0.9,0.8);
+#2=IFCCARTESIANPOINT((0.,0.,-100.));
+#3=IFCDIRECTION((1.,0.,-1.));
+#4=IFCLIGHTSOURCESPOT('down',#1,0.,0.8,#2,5000.,1.,0.,0.,#3,1.,0.5,0.4);
+#5=IFCSHAPEREPRESENTATION($,'Light','LightSource',(#4));
+#6=IFCAXIS2PLACEMENT3D(#7,$,$);
+#7=IFCCARTESIANPOINT((0.,0.,0.));
+#8=IFCREPRESENTATIONMAP(#6,#5);
+#9=IFCMAPPEDITEM(#8,#10);
+#10=IFCCARTESIANTRANSFORMATIONOPERATOR3D($,$,#7,$,$);
+#11=IFCSHAPEREPRESENTATION($,'Light','MappedRepresentation',(#9));
+#12=IFCPRODUCTDEFINITIONSHAPE($,$,(#11));
+#13=IFCCARTESIANPOINT((2000.,0.,3000.));
+#14=IFCDIRECTION((0.,0.,1.));
+#15=IFCDIRECTION((0.,1.,0.));
+#16=IFCAXIS2PLACEMENT3D(#13,#14,#15);
+#17=IFCLOCALPLACEMENT($,#16);
+#18=IFCLIGHTFIXTURE('a',$,$,$,$,#17,#12,$,$);
+#19=IFCLIGHTFIXTURE('b',$,$,$,$,#17,#12,$,$);
+#20=IFCLIGHTSOURCEAMBIENT('fill',$,0.3,$);
+"#;
+    let mut decoder = EntityDecoder::new(content);
+    let router = GeometryRouter::with_scale(0.001);
+    let lights = router.extract_lights(content, &mut decoder);
+    assert_eq!(lights.len(), 3);
+
+    let spot = &lights.lights[0];
+    assert_eq!(spot.element_id, Some(18));
+    assert_eq!(lights.lights[1].element_id, Some(19));
+    let position = spot.position().unwrap();
+    assert!((position - crate::Point3::new(2.0, 0.0, 2.9)).norm() < 1e-9);
+    // Local +X is world +Y after the fixture's rotation
+    let direction = spot.direction().unwrap();
+    let expected = crate::Vector3::new(0.0, 1.0, -1.0).normalize();
+    assert!((direction - expected).norm() < 1e-9);
+    let crate::LightKind::Spot { radius, .. } = spot.kind else {
+        panic!("expected a spot light");
+    };
+    assert!((radius - 5.0).abs() < 1e-9);
+
+    let ambient = &lights.lights[2];
+    assert_eq!((ambient.express_id, ambient.element_id), (20, None));
+    assert_eq!(ambient.ambient_intensity, 0.3);
+}
+
+#[test]
+fn test_2d_voids_on_clipped_wall() {
+    // Wall footprint 4 x 0.2 extruded 3 high and clipped at 2.5, with a
+    // 1 x 1 window extruded horizontally through its thickness
+    let content = r#"
+#1=IFCCARTESIANPOINT((0.,0.,0.));
+#2=IFCDIRECTION((0.,0.,1.));
+#3=IFCDIRECTION((1.,0.,0.));
+#4=IFCAXIS2PLACEMENT3D(#1,#2,#3);
+#5=IFCLOCALPLACEMENT($,#4);
+#6=IFCCARTESIANPOINT((2.,0.));
+#7=IFCAXIS2PLACEMENT2D(#6,$);
+#8=IFCRECTANGLEPROFILEDEF(.AREA.,$,#7,4.,0.2);
+#9=IFCEXTRUDEDAREASOLID(#8,#4,#2,3.);
+#10=IFCCARTESIANPOINT((0.,0.,2.5));
+#11=IFCAXIS2PLACEMENT3D(#10,#2,#3);
+#12=IFCPLANE(#11);
+#13=IFCHALFSPACESOLID(#12,.F.);
+#14=IFCBOOLEANCLIPPINGRESULT(.DIFFERENCE.,#9,#13);
+#15=IFCSHAPEREPRESENTATION($,'Body','Clipping',(#14));
+#16=IFCPRODUCTDEFINITIONSHAPE($,$,(#15));
+#17=IFCWALL('w',$,$,$,$,#5,#16,$,$);
+#20=IFCCARTESIANPOINT((2.,-0.5,1.5));
+#21=IFCDIRECTION((0.,1.,0.));
+#22=IFCAXIS2PLACEMENT3D(#20,#21,#3);
+#23=IFCLOCALPLACEMENT(#5,#22);
+#24=IFCRECTANGLEPROFILEDEF(.AREA.,$,$,1.,1.);
+#25=IFCEXTRUDEDAREASOLID(#24,$,#2,1.);
+#26=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#25));
+#27=IFCPRODUCTDEFINITIONSHAPE($,$,(#26));
+#28=IFCOPENINGELEMENT('o',$,$,$,$,#23,#27,$,$);
+"#;
+    let mut decoder = EntityDecoder::new(content);
+    let router = GeometryRouter::new();
+    let wall = decoder.decode_by_id(17).unwrap();
+    let mut void_index = crate::void_index::VoidIndex::new();
+    void_index.add_relationship(17, 28);
+
+    let volume = |mesh: &crate::Mesh| -> f64 {
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            nalgebra::Vector3::new(
+                mesh.positions[i] as f64,
+                mesh.positions[i + 1] as f64,
+                mesh.positions[i + 2] as f64,
+            )
+        };
+        mesh.indices
+            .chunks_exact(3)
+            .map(|t| p(t[0]).dot(&p(t[1]).cross(&p(t[2]))) / 6.0)
+            .sum()
+    };
+
+    let solid = router.process_element(&wall, &mut decoder).unwrap();
+    let cut = router
+        .process_element_with_voids_2d(&wall, &mut decoder, &void_index)
+        .unwrap();
+    // Both meshes share the same open clip face, so their difference is the
+    // window cut out of the 0.2 thick wall
+    let removed = volume(&solid) - volume(&cut);
+    assert!((removed - 0.2).abs() < 1e-4, "removed {removed}");
+    assert_eq!(cut.bounds(), solid.bounds());
+}
+
 /// Wall Profile Research Tests
 ///
 /// These tests research and analyze how to correctly extrude wall footprints
@@ -5,7 +5,8 @@
 //! Placement and transformation: axis placement parsing, coordinate transforms, RTC offset.
 
 use super::GeometryRouter;
-use crate::{Error, Mesh, Point3, Result, Vector3};
+use crate::linear_placement::{linear_placement_transform, DistanceExpression};
+use crate::{Error, Mesh, Point3, ProfileProcessor, Result, Vector3};
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcType};
 use nalgebra::Matrix4;
 
@@ -81,6 +82,10 @@ impl GeometryRouter {
             return Ok(Matrix4::identity());
         }
 
+        if placement.ifc_type == IfcType::IfcLinearPlacement {
+            return self.get_linear_placement_transform(placement, decoder, depth);
+        }
+
         if placement.ifc_type != IfcType::IfcLocalPlacement {
             return Ok(Matrix4::identity());
         }
@@ -123,6 +128,114 @@ impl GeometryRouter {
         Ok(parent_transform * local_transform)
     }
 
+    /// Resolve IfcLinearPlacement: PlacementRelTo, RelativePlacement
+    /// (IfcAxis2PlacementLinear), CartesianPosition
+    ///
+    /// The station is evaluated on the alignment curve; the precomputed
+    /// CartesianPosition is only used when the curve cannot be evaluated.
+    fn get_linear_placement_transform(
+        &self,
+        placement: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        depth: usize,
+    ) -> Result<Matrix4<f64>> {
+        let parent_transform = match placement.get(0) {
+            Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                Some(parent) => {
+                    self.get_placement_transform_with_depth(&parent, decoder, depth + 1)?
+                }
+                None => Matrix4::identity(),
+            },
+            _ => Matrix4::identity(),
+        };
+
+        let evaluated = match placement.get(1) {
+            Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                Some(rel) if rel.ifc_type == IfcType::IfcAxis2PlacementLinear => {
+                    self.evaluate_axis2_placement_linear(&rel, decoder).ok().flatten()
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let local_transform = match evaluated {
+            Some(transform) => transform,
+            None => match placement.get(2) {
+                Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                    Some(pos) if pos.ifc_type == IfcType::IfcAxis2Placement3D => {
+                        self.parse_axis2_placement_3d(&pos, decoder)?
+                    }
+                    _ => Matrix4::identity(),
+                },
+                _ => Matrix4::identity(),
+            },
+        };
+
+        Ok(parent_transform * local_transform)
+    }
+
+    /// Evaluate IfcAxis2PlacementLinear: Location (IfcPointByDistanceExpression),
+    /// Axis, RefDirection
+    fn evaluate_axis2_placement_linear(
+        &self,
+        placement: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Option<Matrix4<f64>>> {
+        let location = match placement.get(0) {
+            Some(attr) => decoder.resolve_ref(attr)?,
+            None => None,
+        };
+        let location = match location {
+            Some(loc) if loc.ifc_type == IfcType::IfcPointByDistanceExpression => loc,
+            _ => return Ok(None),
+        };
+
+        // IfcPointByDistanceExpression: DistanceAlong, OffsetLateral,
+        // OffsetVertical, OffsetLongitudinal, BasisCurve
+        let expression = DistanceExpression {
+            distance_along: location.get_float(0).unwrap_or(0.0),
+            offset_lateral: location.get_float(1).unwrap_or(0.0),
+            offset_vertical: location.get_float(2).unwrap_or(0.0),
+            offset_longitudinal: location.get_float(3).unwrap_or(0.0),
+        };
+
+        let curve = match location.get(4) {
+            Some(attr) => decoder.resolve_ref(attr)?,
+            None => None,
+        };
+        let Some(curve) = curve else {
+            return Ok(None);
+        };
+
+        // Gradient and segmented reference curves are stationed along their
+        // horizontal base curve and carry elevation and cant
+        let profiles = ProfileProcessor::with_tessellation(
+            self.schema.clone(),
+            self.tessellation.in_model_units(self.unit_scale),
+        );
+        let alignment = profiles.get_alignment_curve(&curve, decoder)?;
+
+        let mut direction = |index: usize| -> Result<Option<Vector3<f64>>> {
+            match placement.get(index) {
+                Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                    Some(dir) => Ok(Some(self.parse_direction(&dir)?)),
+                    None => Ok(None),
+                },
+                _ => Ok(None),
+            }
+        };
+        let axis = direction(1)?;
+        let ref_direction = direction(2)?;
+
+        Ok(linear_placement_transform(
+            &alignment,
+            &expression,
+            axis,
+            ref_direction,
+        ))
+    }
+
     /// Parse IfcAxis2Placement3D into transformation matrix
     pub(super) fn parse_axis2_placement_3d(
         &self,
@@ -45,7 +45,7 @@ fn rotate_and_normalize(
 }
 
 /// Whether the representation type is geometry we can process.
-fn is_body_representation(rep_type: &str) -> bool {
+pub(super) fn is_body_representation(rep_type: &str) -> bool {
     matches!(
         rep_type,
         "Body"
@@ -527,6 +527,19 @@ impl GeometryRouter {
             return self.process_element(element, decoder);
         }
 
+        // Openings that would need 3D CSG usually project onto the host's
+        // extrusion basis; try the 2D profile path for those first
+        if openings
+            .iter()
+            .any(|o| matches!(o, OpeningType::NonRectangular(_)))
+        {
+            if let Ok(Some(mesh)) =
+                self.try_process_extrusion_with_voids_2d(element, decoder, opening_ids)
+            {
+                return Ok(mesh);
+            }
+        }
+
         use crate::csg::ClippingProcessor;
         let clipper = ClippingProcessor::new();
         let mut result = wall_mesh;
@@ -623,7 +636,7 @@ impl GeometryRouter {
                     // Some IfcOpeningElements have vertical (0,0,1) extrusion even in walls
                     // (e.g. 17 mm connection points). The `is_floor_opening` heuristic
                     // misclassifies these, forcing them into the CSG path.
-                    // The csgrs BSP tree then destroys the wall mesh because tiny operands
+                    // The BSP boolean then destroys the wall mesh because tiny operands
                     // trigger numerical instability in the BSP split/merge.
                     //
                     // Three guards:
@@ -651,7 +664,7 @@ impl GeometryRouter {
                     }
 
                     // Use full CSG subtraction for non-rectangular shapes
-                    // Note: mesh_to_csgrs validates and filters invalid triangles internally
+                    // Note: subtract_mesh validates and filters invalid triangles internally
                     let tri_before = result.triangle_count();
                     match clipper.subtract_mesh(&result, opening_mesh) {
                         Ok(csg_result) => {
@@ -4,24 +4,40 @@
 
 //! 2D void subtraction: profile-level opening processing for extrusions.
 
+use super::voids::is_body_representation;
 use super::GeometryRouter;
-use crate::bool2d::subtract_multiple_2d;
-use crate::csg::ClippingProcessor;
-use crate::profile::{Profile2D, Profile2DWithVoids, VoidInfo};
-use crate::void_analysis::{extract_coplanar_voids, extract_nonplanar_voids, VoidAnalyzer};
+use crate::bool2d::compute_signed_area;
+use crate::csg::{ClippingProcessor, Plane};
+use crate::extrusion::{apply_transform, extrude_profile_layered};
+use crate::profile::{Profile2D, VoidInfo};
+use crate::profiles::ProfileProcessor;
+use crate::void_analysis::{VoidAnalyzer, VoidClassification};
 use crate::void_index::VoidIndex;
-use crate::{Error, Mesh, Result, Vector3};
+use crate::{Error, Mesh, Point3, Result, Vector3};
 use ifc_lite_core::{DecodedEntity, EntityDecoder, IfcType};
 use nalgebra::{Matrix4, Point2};
 use rustc_hash::FxHashMap;
 
+/// Hosts extruded closer than this to their profile plane (z component of the
+/// unit extrusion direction) are left to 3D CSG
+const MIN_DIRECTION_Z: f64 = 0.05;
+
+/// Faces of a prism opening must be within this of horizontal or vertical
+/// (as the z component of the unit normal)
+const PRISM_NORMAL_TOLERANCE: f64 = 1e-3;
+
+/// Allowed relative mismatch between an opening's cap area and its footprint
+const FOOTPRINT_AREA_TOLERANCE: f64 = 0.01;
+
 impl GeometryRouter {
     /// Process element with voids using 2D profile-level operations
     ///
     /// This is a smarter and more efficient approach that:
-    /// 1. Classifies voids as coplanar (can subtract in 2D) or non-planar (need 3D CSG)
-    /// 2. Subtracts coplanar voids at the 2D profile level before extrusion
-    /// 3. Falls back to 3D CSG only for non-planar voids
+    /// 1. Projects openings onto the host extrusion's basis and classifies them
+    ///    as prisms along the extrusion (can subtract in 2D) or not (need 3D CSG)
+    /// 2. Subtracts prism voids at the 2D profile level, layer by layer in depth
+    /// 3. Re-applies half-space clipping (walls trimmed under a roof)
+    /// 4. Falls back to 3D CSG only for openings that do not project
     ///
     /// Benefits:
     /// - 10-25x faster than full 3D CSG for most openings
@@ -57,10 +73,13 @@ impl GeometryRouter {
 
     /// Try to process an extrusion with 2D void subtraction
     ///
+    /// The body must be a single IfcExtrudedAreaSolid, optionally clipped by
+    /// half-space planes (walls trimmed under a roof).
+    ///
     /// Returns Ok(Some(mesh)) if 2D processing was successful,
     /// Ok(None) if the element is not suitable for 2D processing,
     /// Err if an error occurred.
-    fn try_process_extrusion_with_voids_2d(
+    pub(super) fn try_process_extrusion_with_voids_2d(
         &self,
         element: &DecodedEntity,
         decoder: &mut EntityDecoder,
@@ -81,7 +100,6 @@ impl GeometryRouter {
             return Ok(None);
         }
 
-        // Find an IfcExtrudedAreaSolid in the representations
         let representations_attr = match representation.get(2) {
             Some(attr) => attr,
             None => return Ok(None),
@@ -89,229 +107,178 @@ impl GeometryRouter {
 
         let representations = decoder.resolve_ref_list(representations_attr)?;
 
-        // Look for extruded area solid
+        // Collect the body items; anything but a single item needs the 3D path
+        let mut body_items = Vec::new();
         for shape_rep in &representations {
             if shape_rep.ifc_type != IfcType::IfcShapeRepresentation {
                 continue;
             }
+            if let Some(rep_type) = shape_rep.get(2).and_then(|attr| attr.as_string()) {
+                if !is_body_representation(rep_type) {
+                    continue;
+                }
+            }
 
             let items_attr = match shape_rep.get(3) {
                 Some(attr) => attr,
                 None => continue,
             };
-
-            let items = decoder.resolve_ref_list(items_attr)?;
-
-            for item in &items {
-                if item.ifc_type == IfcType::IfcExtrudedAreaSolid {
-                    // Found an extrusion - try 2D void processing
-                    return self.process_extrusion_with_voids_2d_impl(
-                        element,
-                        item,
-                        decoder,
-                        opening_ids,
-                    );
-                }
-            }
+            body_items.extend(decoder.resolve_ref_list(items_attr)?);
         }
 
-        Ok(None)
+        let [item] = body_items.as_slice() else {
+            return Ok(None);
+        };
+        match self.find_clipped_extrusion(item, decoder)? {
+            Some((extrusion, clipping_planes)) => self.process_extrusion_with_voids_2d_impl(
+                element,
+                &extrusion,
+                &clipping_planes,
+                decoder,
+                opening_ids,
+            ),
+            None => Ok(None),
+        }
     }
 
     /// Implementation of 2D void processing for extrusions
+    ///
+    /// Openings are moved into the host's prism space: profile space sheared
+    /// so the extrusion runs straight along +Z from 0 to `height`. Openings
+    /// that are prisms along Z there (walls cut by horizontal window
+    /// extrusions, slabs by vertical shafts, including on sloped or sheared
+    /// hosts) become 2D footprints with a depth range. The rest are
+    /// subtracted with 3D CSG after placement.
     fn process_extrusion_with_voids_2d_impl(
         &self,
         element: &DecodedEntity,
         extrusion: &DecodedEntity,
+        clipping_planes: &[(Point3<f64>, Vector3<f64>, bool)],
         decoder: &mut EntityDecoder,
         opening_ids: &[u32],
     ) -> Result<Option<Mesh>> {
-        // Extract extrusion parameters
         // IfcExtrudedAreaSolid: SweptArea, Position, ExtrudedDirection, Depth
-
-        // Get depth (attribute 3)
         let depth = match extrusion.get_float(3) {
             Some(d) if d > 0.0 => d,
             _ => return Ok(None),
         };
 
-        // Get extrusion direction (attribute 2)
-        let direction_attr = match extrusion.get(2) {
-            Some(attr) if !attr.is_null() => attr,
+        let direction_entity = match extrusion.get(2) {
+            Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                Some(e) => e,
+                None => return Ok(None),
+            },
             _ => return Ok(None),
         };
-
-        let direction_entity = match decoder.resolve_ref(direction_attr)? {
-            Some(e) => e,
-            None => return Ok(None),
+        let direction = match self
+            .parse_direction(&direction_entity)?
+            .try_normalize(1e-12)
+        {
+            Some(d) if d.z.abs() >= MIN_DIRECTION_Z => d,
+            _ => return Ok(None),
         };
 
-        let local_extrusion_direction = self.parse_direction(&direction_entity)?;
-
-        // Get position transform (attribute 1)
-        let position_transform = if let Some(pos_attr) = extrusion.get(1) {
-            if !pos_attr.is_null() {
-                if let Some(pos_entity) = decoder.resolve_ref(pos_attr)? {
-                    self.parse_axis2_placement_3d(&pos_entity, decoder)?
-                } else {
-                    Matrix4::identity()
-                }
-            } else {
-                Matrix4::identity()
-            }
-        } else {
-            Matrix4::identity()
+        let position_transform = match extrusion.get(1) {
+            Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                Some(pos_entity) => self.parse_axis2_placement_3d(&pos_entity, decoder)?,
+                None => Matrix4::identity(),
+            },
+            _ => Matrix4::identity(),
         };
 
-        // Transform extrusion direction from local to world coordinates
-        // ExtrudedDirection is specified in Position's local coordinate system
-        let extrusion_direction = {
-            let rot_x = Vector3::new(
-                position_transform[(0, 0)],
-                position_transform[(1, 0)],
-                position_transform[(2, 0)],
-            );
-            let rot_y = Vector3::new(
-                position_transform[(0, 1)],
-                position_transform[(1, 1)],
-                position_transform[(2, 1)],
-            );
-            let rot_z = Vector3::new(
-                position_transform[(0, 2)],
-                position_transform[(1, 2)],
-                position_transform[(2, 2)],
-            );
-            (rot_x * local_extrusion_direction.x
-                + rot_y * local_extrusion_direction.y
-                + rot_z * local_extrusion_direction.z)
-                .normalize()
+        // Prism space -> profile space. A downward extrusion starts at its far
+        // end and runs back up, so the prism always extends along +Z.
+        let (axis, base) = if direction.z < 0.0 {
+            (-direction, direction * depth)
+        } else {
+            (direction, Vector3::zeros())
         };
+        let height = depth * axis.z;
+        #[rustfmt::skip]
+        let shear = Matrix4::new(
+            1.0, 0.0, axis.x / axis.z, base.x,
+            0.0, 1.0, axis.y / axis.z, base.y,
+            0.0, 0.0, 1.0, base.z,
+            0.0, 0.0, 0.0, 1.0,
+        );
+        let prism_to_object = position_transform * shear;
 
-        // Get element placement transform
         let element_transform = self.get_placement_transform_from_element(element, decoder)?;
-        let combined_transform = element_transform * position_transform;
-
-        // Get swept area (profile) - attribute 0
-        let profile_attr = match extrusion.get(0) {
-            Some(attr) if !attr.is_null() => attr,
-            _ => return Ok(None),
-        };
-
-        let profile_entity = match decoder.resolve_ref(profile_attr)? {
-            Some(e) => e,
+        let world_to_prism = match (element_transform * prism_to_object).try_inverse() {
+            Some(m) => m,
             None => return Ok(None),
         };
 
-        // Extract base 2D profile
-        let base_profile = match self.extract_profile_2d(&profile_entity, decoder) {
-            Ok(p) => p,
-            Err(_) => return Ok(None),
+        let profile_entity = match extrusion.get(0) {
+            Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                Some(e) => e,
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let profile_processor = ProfileProcessor::with_tessellation(
+            self.schema.clone(),
+            self.tessellation.in_model_units(self.unit_scale),
+        );
+        let base_profile = match profile_processor.process(&profile_entity, decoder) {
+            Ok(p) if p.outer.len() >= 3 => p,
+            _ => return Ok(None),
         };
 
-        // Process opening meshes and classify them
-        let mut void_meshes: Vec<Mesh> = Vec::new();
-
+        // Project openings; any item that is not a prism sends the whole
+        // opening to 3D CSG
+        let mut voids: Vec<VoidInfo> = Vec::new();
+        let mut nonplanar_voids: Vec<Mesh> = Vec::new();
         for &opening_id in opening_ids {
             let opening_entity = match decoder.decode_by_id(opening_id) {
                 Ok(e) => e,
                 Err(_) => continue,
             };
 
-            let opening_mesh = match self.process_element(&opening_entity, decoder) {
-                Ok(m) if !m.is_empty() => m,
-                _ => continue,
-            };
-
-            void_meshes.push(opening_mesh);
+            let projected = self
+                .opening_items_in_frame(&opening_entity, decoder, &world_to_prism)?
+                .and_then(|items| {
+                    items
+                        .iter()
+                        .map(|item| prism_void(item, height))
+                        .collect::<Option<Vec<_>>>()
+                });
+            match projected {
+                Some(item_voids) => voids.extend(item_voids),
+                None => match self.process_element(&opening_entity, decoder) {
+                    Ok(m) if !m.is_empty() => nonplanar_voids.push(m),
+                    _ => continue,
+                },
+            }
         }
 
-        if void_meshes.is_empty() {
-            // No valid openings - just process the extrusion normally
-            let processor = self.processors.get(&IfcType::IfcExtrudedAreaSolid);
-            if let Some(proc) = processor {
-                let mut mesh = proc.process(extrusion, decoder, &self.schema)?;
-                self.scale_mesh(&mut mesh);
-                self.apply_placement(element, decoder, &mut mesh)?;
-                return Ok(Some(mesh));
-            }
-            return Ok(None);
+        if voids.is_empty() && nonplanar_voids.is_empty() {
+            // No valid openings - just process the element normally
+            return self.process_element(element, decoder).map(Some);
         }
 
-        // Classify voids
-        // Use unscaled depth since void_meshes are in file units (not yet scaled)
-        let analyzer = VoidAnalyzer::new();
-
-        let classifications: Vec<crate::void_analysis::VoidClassification> = void_meshes
-            .iter()
-            .map(|mesh| {
-                analyzer.classify_void(
-                    mesh,
-                    &combined_transform,
-                    &extrusion_direction.normalize(),
-                    depth,
-                )
-            })
-            .collect();
-
-        // Extract coplanar and non-planar voids
-        let coplanar_voids = extract_coplanar_voids(&classifications);
-        let nonplanar_voids = extract_nonplanar_voids(classifications);
-
-        // Process coplanar voids at 2D level
-        let profile_with_voids = if !coplanar_voids.is_empty() {
-            // Collect through-void contours for 2D subtraction
-            let through_contours: Vec<Vec<Point2<f64>>> = coplanar_voids
-                .iter()
-                .filter(|v| v.is_through)
-                .map(|v| v.contour.clone())
-                .collect();
-
-            // Subtract voids from profile
-            let modified_profile = if !through_contours.is_empty() {
-                match subtract_multiple_2d(&base_profile, &through_contours) {
-                    Ok(p) => p,
-                    Err(_) => base_profile.clone(),
-                }
-            } else {
-                base_profile.clone()
+        let mut mesh =
+            match extrude_profile_layered(&base_profile, &voids, height, Some(prism_to_object)) {
+                Ok(m) => m,
+                Err(_) => return Ok(None),
             };
 
-            // Create profile with partial-depth voids
-            let partial_voids: Vec<VoidInfo> = coplanar_voids
-                .into_iter()
-                .filter(|v| !v.is_through)
-                .map(|v| VoidInfo {
-                    contour: v.contour,
-                    depth_start: v.depth_start,
-                    depth_end: v.depth_end,
-                    is_through: false,
-                })
-                .collect();
-
-            Profile2DWithVoids::new(modified_profile, partial_voids)
-        } else {
-            Profile2DWithVoids::from_profile(base_profile)
-        };
-
-        // Extrude with voids
-        use crate::extrusion::extrude_profile_with_voids;
-
-        let mut mesh = match extrude_profile_with_voids(&profile_with_voids, depth, None) {
-            Ok(m) => m,
-            Err(_) => {
-                // Fall back to normal extrusion
-                let processor = self.processors.get(&IfcType::IfcExtrudedAreaSolid);
-                if let Some(proc) = processor {
-                    proc.process(extrusion, decoder, &self.schema)?
+        // Half-space clips are in the extrusion's parent coordinate system
+        if !clipping_planes.is_empty() {
+            let clipper = ClippingProcessor::new();
+            for (plane_point, plane_normal, agreement) in clipping_planes {
+                let clip_normal = if *agreement {
+                    *plane_normal
                 } else {
-                    return Ok(None);
+                    -*plane_normal
+                };
+                let plane = Plane::new(*plane_point, clip_normal);
+                if let Ok(clipped) = clipper.clip_mesh(&mesh, &plane) {
+                    if !clipped.is_empty() {
+                        mesh = clipped;
+                    }
                 }
             }
-        };
-
-        // Apply extrusion position transform (with RTC offset)
-        if position_transform != Matrix4::identity() {
-            self.transform_mesh(&mut mesh, &position_transform);
         }
 
         // Scale mesh
@@ -329,6 +296,63 @@ impl GeometryRouter {
         Ok(Some(mesh))
     }
 
+    /// Body items of an opening transformed by `world_to_frame`, in file
+    /// units. Returns `None` when an item is not an IfcExtrudedAreaSolid.
+    fn opening_items_in_frame(
+        &self,
+        opening: &DecodedEntity,
+        decoder: &mut EntityDecoder,
+        world_to_frame: &Matrix4<f64>,
+    ) -> Result<Option<Vec<Mesh>>> {
+        let processor = match self.processors.get(&IfcType::IfcExtrudedAreaSolid) {
+            Some(p) => p,
+            None => return Ok(None),
+        };
+
+        let representation = match opening.get(6) {
+            Some(attr) if !attr.is_null() => match decoder.resolve_ref(attr)? {
+                Some(r) => r,
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let representations = match representation.get(2) {
+            Some(attr) => decoder.resolve_ref_list(attr)?,
+            None => return Ok(None),
+        };
+
+        let transform =
+            world_to_frame * self.get_placement_transform_from_element(opening, decoder)?;
+        let mut meshes = Vec::new();
+        for shape_rep in &representations {
+            if shape_rep.ifc_type != IfcType::IfcShapeRepresentation {
+                continue;
+            }
+            if let Some(rep_type) = shape_rep.get(2).and_then(|attr| attr.as_string()) {
+                if !is_body_representation(rep_type) {
+                    continue;
+                }
+            }
+            let items_attr = match shape_rep.get(3) {
+                Some(attr) => attr,
+                None => continue,
+            };
+            for item in decoder.resolve_ref_list(items_attr)? {
+                if item.ifc_type != IfcType::IfcExtrudedAreaSolid {
+                    return Ok(None);
+                }
+                let mut mesh = match processor.process(&item, decoder, &self.schema) {
+                    Ok(m) if !m.is_empty() => m,
+                    _ => return Ok(None),
+                };
+                apply_transform(&mut mesh, &transform);
+                meshes.push(mesh);
+            }
+        }
+
+        Ok((!meshes.is_empty()).then_some(meshes))
+    }
+
     /// Extract a 2D profile from an IFC profile entity
     pub(super) fn extract_profile_2d(
         &self,
@@ -491,3 +515,58 @@ impl GeometryRouter {
         }
     }
 }
+
+/// Footprint and depth range of an opening item that is a prism along Z in
+/// the host's prism space, or `None` if it has to be cut in 3D
+fn prism_void(mesh: &Mesh, height: f64) -> Option<VoidInfo> {
+    let analyzer = VoidAnalyzer::new();
+    match analyzer.classify_void(mesh, &Matrix4::identity(), &Vector3::z(), height) {
+        VoidClassification::Coplanar {
+            profile_hole,
+            depth_start,
+            depth_end,
+            is_through,
+        } if is_z_prism(mesh, &profile_hole) => Some(VoidInfo {
+            contour: profile_hole,
+            depth_start,
+            depth_end,
+            is_through,
+        }),
+        _ => None,
+    }
+}
+
+/// Whether every face of `mesh` is horizontal or vertical and its horizontal
+/// faces cover `footprint` exactly twice (top and bottom), which rules out
+/// concave openings whose convex-hull footprint would cut too much.
+fn is_z_prism(mesh: &Mesh, footprint: &[Point2<f64>]) -> bool {
+    let position = |i: u32| {
+        let i = i as usize * 3;
+        Vector3::new(
+            mesh.positions[i] as f64,
+            mesh.positions[i + 1] as f64,
+            mesh.positions[i + 2] as f64,
+        )
+    };
+
+    let mut horizontal_area = 0.0;
+    for tri in mesh.indices.chunks_exact(3) {
+        let (a, b, c) = (position(tri[0]), position(tri[1]), position(tri[2]));
+        let normal = (b - a).cross(&(c - a));
+        let doubled_area = normal.norm();
+        if doubled_area < 1e-12 {
+            continue;
+        }
+        let nz = (normal.z / doubled_area).abs();
+        if nz > 1.0 - PRISM_NORMAL_TOLERANCE {
+            horizontal_area += doubled_area / 2.0;
+        } else if nz > PRISM_NORMAL_TOLERANCE {
+            return false;
+        }
+    }
+
+    let footprint_area = compute_signed_area(footprint).abs();
+    footprint_area > 0.0
+        && (horizontal_area - 2.0 * footprint_area).abs()
+            <= 2.0 * footprint_area * FOOTPRINT_AREA_TOLERANCE
+}
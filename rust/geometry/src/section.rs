@@ -0,0 +1,387 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Section plane cuts with cap polygons
+//!
+//! Each element mesh is intersected with a plane; the crossing segments are
+//! chained into closed 2D loops in the plane's (u, v) frame and optionally
+//! filled with cap triangles facing along the plane normal, i.e. towards a
+//! viewer looking at the kept half (behind the plane).
+//!
+//! Vertices are welded by position first and segments are joined through the
+//! mesh edge they cross, so loops close exactly even on meshes with split
+//! vertices at hard edges. A vertex lying on the plane counts as in front of
+//! it, which keeps every triangle to either zero or two crossing edges.
+//! Loops are oriented by nesting depth (outer boundaries counter-clockwise,
+//! holes clockwise) rather than by triangle winding, which IFC doesn't
+//! guarantee.
+
+use crate::bool2d::{compute_signed_area, ensure_ccw, ensure_cw, point_in_contour};
+use crate::mesh::Mesh;
+use crate::triangulation::{project_to_2d, triangulate_polygon_with_holes};
+use nalgebra::{Point2, Point3, Vector3};
+use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
+
+/// A cutting plane with a 2D frame for the section drawing
+#[derive(Debug, Clone, Copy)]
+pub struct SectionPlane {
+    pub origin: Point3<f64>,
+    /// Unit normal, pointing at the removed half
+    pub normal: Vector3<f64>,
+    pub u_axis: Vector3<f64>,
+    pub v_axis: Vector3<f64>,
+}
+
+impl SectionPlane {
+    /// Plane through `origin` with an arbitrary in-plane frame
+    pub fn new(origin: Point3<f64>, normal: Vector3<f64>) -> Self {
+        let normal = normal.normalize();
+        let (_, u_axis, v_axis, _) = project_to_2d(&[origin], &normal);
+        Self {
+            origin,
+            normal,
+            u_axis,
+            v_axis,
+        }
+    }
+
+    /// Floor-plan cut at an elevation, looking down: (u, v) are model (x, y)
+    pub fn horizontal(elevation: f64) -> Self {
+        Self {
+            origin: Point3::new(0.0, 0.0, elevation),
+            normal: Vector3::z(),
+            u_axis: Vector3::x(),
+            v_axis: Vector3::y(),
+        }
+    }
+
+    pub fn signed_distance(&self, point: &Point3<f64>) -> f64 {
+        (point - self.origin).dot(&self.normal)
+    }
+
+    pub fn to_2d(&self, point: &Point3<f64>) -> Point2<f64> {
+        let d = point - self.origin;
+        Point2::new(d.dot(&self.u_axis), d.dot(&self.v_axis))
+    }
+
+    pub fn to_3d(&self, point: &Point2<f64>) -> Point3<f64> {
+        self.origin + self.u_axis * point.x + self.v_axis * point.y
+    }
+}
+
+/// Section settings
+#[derive(Debug, Clone, Copy)]
+pub struct SectionOptions {
+    /// Triangulate closed loops into cap meshes
+    pub caps: bool,
+    /// Vertices closer than this are treated as one (mesh units)
+    pub weld_tolerance: f64,
+}
+
+impl Default for SectionOptions {
+    fn default() -> Self {
+        Self {
+            caps: true,
+            weld_tolerance: 1e-4,
+        }
+    }
+}
+
+impl SectionOptions {
+    /// Enable or disable cap triangulation
+    pub fn with_caps(mut self, caps: bool) -> Self {
+        self.caps = caps;
+        self
+    }
+
+    /// Set the vertex weld tolerance
+    pub fn with_weld_tolerance(mut self, tolerance: f64) -> Self {
+        self.weld_tolerance = tolerance;
+        self
+    }
+}
+
+/// Section result of one element
+#[derive(Debug, Clone)]
+pub struct SectionCut {
+    pub express_id: u32,
+    /// Closed polylines in the plane frame (last point not repeated)
+    pub loops: Vec<Vec<Point2<f64>>>,
+    /// Chains that could not be closed (open or non-manifold meshes)
+    pub open_chains: Vec<Vec<Point2<f64>>>,
+    /// Cap triangles in mesh coordinates, when requested and loops closed
+    pub cap: Option<Mesh>,
+}
+
+/// Cut every element mesh; elements the plane misses are omitted
+pub fn section_model<'a>(
+    meshes: impl IntoIterator<Item = (u32, &'a Mesh)>,
+    plane: &SectionPlane,
+    options: &SectionOptions,
+) -> Vec<SectionCut> {
+    meshes
+        .into_iter()
+        .filter_map(|(express_id, mesh)| section_mesh(express_id, mesh, plane, options))
+        .collect()
+}
+
+/// Cut one element mesh
+pub fn section_mesh(
+    express_id: u32,
+    mesh: &Mesh,
+    plane: &SectionPlane,
+    options: &SectionOptions,
+) -> Option<SectionCut> {
+    let (points, canonical) = mesh.position_ids(options.weld_tolerance);
+    let distances: Vec<f64> = points.iter().map(|p| plane.signed_distance(p)).collect();
+    if distances.iter().all(|&d| d >= 0.0) || distances.iter().all(|&d| d < 0.0) {
+        return None;
+    }
+
+    // Crossed edges are the nodes, triangles link their two crossed edges
+    let mut links: Vec<[(u32, u32); 2]> = Vec::new();
+    for tri in mesh.indices.chunks_exact(3) {
+        let Some(ids) = tri
+            .iter()
+            .map(|&i| canonical.get(i as usize).copied())
+            .collect::<Option<SmallVec<[u32; 3]>>>()
+        else {
+            continue;
+        };
+        let crossed: SmallVec<[(u32, u32); 2]> =
+            [(ids[0], ids[1]), (ids[1], ids[2]), (ids[2], ids[0])]
+                .into_iter()
+                .filter(|&(a, b)| {
+                    a != b && (distances[a as usize] >= 0.0) != (distances[b as usize] >= 0.0)
+                })
+                .map(|(a, b)| (a.min(b), a.max(b)))
+                .collect();
+        if let [e0, e1] = crossed[..] {
+            links.push([e0, e1]);
+        }
+    }
+    if links.is_empty() {
+        return None;
+    }
+
+    let crossing = |(a, b): (u32, u32)| {
+        let (pa, pb) = (points[a as usize], points[b as usize]);
+        let (da, db) = (distances[a as usize], distances[b as usize]);
+        plane.to_2d(&(pa + (pb - pa) * (da / (da - db))))
+    };
+
+    let mut loops = Vec::new();
+    let mut open_chains = Vec::new();
+    for (chain, closed) in chain_links(&links) {
+        let mut polyline: Vec<Point2<f64>> = Vec::with_capacity(chain.len());
+        for node in chain {
+            let p = crossing(node);
+            if polyline
+                .last()
+                .is_none_or(|q| (p - q).norm() > options.weld_tolerance)
+            {
+                polyline.push(p);
+            }
+        }
+        if closed {
+            while polyline.len() > 1
+                && (polyline[0] - polyline[polyline.len() - 1]).norm() <= options.weld_tolerance
+            {
+                polyline.pop();
+            }
+            let min_area = options.weld_tolerance * options.weld_tolerance;
+            if polyline.len() >= 3 && compute_signed_area(&polyline).abs() > min_area {
+                loops.push(polyline);
+            }
+        } else if polyline.len() >= 2 {
+            open_chains.push(polyline);
+        }
+    }
+    if loops.is_empty() && open_chains.is_empty() {
+        return None;
+    }
+
+    // Nesting depth decides orientation and which loops are holes of which
+    let depths: Vec<usize> = loops
+        .iter()
+        .enumerate()
+        .map(|(i, l)| {
+            loops
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| *j != i && point_in_contour(&l[0], other))
+                .count()
+        })
+        .collect();
+    let loops: Vec<Vec<Point2<f64>>> = loops
+        .iter()
+        .zip(&depths)
+        .map(|(l, depth)| {
+            if depth.is_multiple_of(2) {
+                ensure_ccw(l)
+            } else {
+                ensure_cw(l)
+            }
+        })
+        .collect();
+
+    let cap = (options.caps && !loops.is_empty()).then(|| build_cap(&loops, &depths, plane));
+    Some(SectionCut {
+        express_id,
+        loops,
+        open_chains,
+        cap,
+    })
+}
+
+/// Walk links into chains of nodes; `true` marks a closed chain
+fn chain_links(links: &[[(u32, u32); 2]]) -> Vec<(Vec<(u32, u32)>, bool)> {
+    let mut by_node: FxHashMap<(u32, u32), SmallVec<[usize; 2]>> = FxHashMap::default();
+    for (i, link) in links.iter().enumerate() {
+        for node in link {
+            by_node.entry(*node).or_default().push(i);
+        }
+    }
+
+    let mut used = vec![false; links.len()];
+    let walk = |from: (u32, u32), used: &mut Vec<bool>| {
+        let mut path = Vec::new();
+        let mut node = from;
+        while let Some(&link) = by_node
+            .get(&node)
+            .and_then(|candidates| candidates.iter().find(|&&l| !used[l]))
+        {
+            used[link] = true;
+            let [a, b] = links[link];
+            node = if a == node { b } else { a };
+            path.push(node);
+        }
+        path
+    };
+
+    let mut chains = Vec::new();
+    for start in 0..links.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let [first, second] = links[start];
+        let mut chain = vec![first, second];
+        chain.extend(walk(second, &mut used));
+        if chain.len() > 2 && chain[chain.len() - 1] == first {
+            chain.pop();
+            chains.push((chain, true));
+            continue;
+        }
+        // Open so far: extend backwards from the first node as well
+        let mut backwards = walk(first, &mut used);
+        backwards.reverse();
+        backwards.extend(chain);
+        chains.push((backwards, false));
+    }
+    chains
+}
+
+/// Triangulate even-depth loops with their directly nested holes
+fn build_cap(loops: &[Vec<Point2<f64>>], depths: &[usize], plane: &SectionPlane) -> Mesh {
+    let mut cap = Mesh::new();
+    for (i, outer) in loops.iter().enumerate() {
+        if !depths[i].is_multiple_of(2) {
+            continue;
+        }
+        let holes: Vec<Vec<Point2<f64>>> = loops
+            .iter()
+            .enumerate()
+            .filter(|(j, hole)| depths[*j] == depths[i] + 1 && point_in_contour(&hole[0], outer))
+            .map(|(_, hole)| hole.clone())
+            .collect();
+        let Ok(indices) = triangulate_polygon_with_holes(outer, &holes) else {
+            continue;
+        };
+
+        let base = cap.vertex_count() as u32;
+        for p in outer.iter().chain(holes.iter().flatten()) {
+            cap.add_vertex(plane.to_3d(p), plane.normal);
+        }
+        for tri in indices.chunks_exact(3) {
+            cap.add_triangle(
+                base + tri[0] as u32,
+                base + tri[1] as u32,
+                base + tri[2] as u32,
+            );
+        }
+    }
+    cap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extrude_profile, Profile2D};
+
+    #[test]
+    fn test_section_hollow_column_with_cap() {
+        // 2 × 2 column with a 1 × 1 shaft, 3 high, split vertices at hard edges
+        let square = |c: f64, r: f64| {
+            vec![
+                Point2::new(c - r, c - r),
+                Point2::new(c + r, c - r),
+                Point2::new(c + r, c + r),
+                Point2::new(c - r, c + r),
+            ]
+        };
+        let mut profile = Profile2D::new(square(1.0, 1.0));
+        profile.add_hole(square(1.0, 0.5).into_iter().rev().collect());
+        let column = extrude_profile(&profile, 3.0, None).unwrap();
+
+        let plane = SectionPlane::horizontal(1.2);
+        let cut = section_mesh(7, &column, &plane, &SectionOptions::default()).unwrap();
+        assert_eq!(cut.loops.len(), 2);
+        assert!(cut.open_chains.is_empty());
+        let mut areas: Vec<f64> = cut.loops.iter().map(|l| compute_signed_area(l)).collect();
+        areas.sort_by(f64::total_cmp);
+        assert!((areas[0] + 1.0).abs() < 1e-6 && (areas[1] - 4.0).abs() < 1e-6);
+
+        let cap = cut.cap.unwrap();
+        let cap_area: f64 = cap
+            .indices
+            .chunks_exact(3)
+            .map(|t| {
+                let p = |i: u32| {
+                    plane.to_2d(&Point3::new(
+                        cap.positions[i as usize * 3] as f64,
+                        cap.positions[i as usize * 3 + 1] as f64,
+                        0.0,
+                    ))
+                };
+                let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+                0.5 * ((b - a).perp(&(c - a)))
+            })
+            .sum();
+        assert!((cap_area - 3.0).abs() < 1e-6);
+        assert!(cap
+            .positions
+            .chunks_exact(3)
+            .all(|p| (p[2] - 1.2).abs() < 1e-6));
+
+        // Planes missing the element, and an oblique cut
+        assert!(section_mesh(
+            7,
+            &column,
+            &SectionPlane::horizontal(5.0),
+            &SectionOptions::default()
+        )
+        .is_none());
+        let oblique = SectionPlane::new(Point3::new(1.0, 1.0, 1.5), Vector3::new(1.0, 0.0, 1.0));
+        let cuts = section_model(
+            [(7, &column), (8, &Mesh::new())],
+            &oblique,
+            &SectionOptions::default().with_caps(false),
+        );
+        assert_eq!(cuts.len(), 1);
+        assert_eq!(cuts[0].loops.len(), 2);
+        assert!(cuts[0].cap.is_none());
+    }
+}
@@ -0,0 +1,568 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Quadric error mesh decimation
+//!
+//! Reduces a mesh to a triangle budget by repeatedly collapsing the edge whose
+//! removal changes the surface least, measured with Garland–Heckbert error
+//! quadrics. Collapses move one vertex onto its neighbour (no new positions),
+//! so the output only contains original vertices and their normals.
+//!
+//! Vertices are welded by position before decimation. Normal discontinuities
+//! sharper than [`SimplifyOptions::crease_angle`] are treated as hard edges and
+//! their vertices are never moved, so flat-shaded edges stay sharp. Open
+//! boundaries are kept in place unless [`SimplifyOptions::preserve_boundaries`]
+//! is disabled.
+
+use crate::mesh::Mesh;
+use nalgebra::{Point3, Vector3};
+use rustc_hash::FxHashMap;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Decimation options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimplifyOptions {
+    /// Stop once the mesh has at most this many triangles
+    pub target_triangles: usize,
+    /// Stop before any collapse whose error exceeds this distance (model units)
+    pub max_error: f64,
+    /// Keep vertices on open boundaries fixed
+    pub preserve_boundaries: bool,
+    /// Normals diverging by more than this angle (radians) mark a hard edge
+    pub crease_angle: f64,
+}
+
+impl Default for SimplifyOptions {
+    fn default() -> Self {
+        Self {
+            target_triangles: 0,
+            max_error: f64::INFINITY,
+            preserve_boundaries: true,
+            crease_angle: 30f64.to_radians(),
+        }
+    }
+}
+
+impl SimplifyOptions {
+    /// Options targeting `ratio` (0–1) of the input's triangle count
+    pub fn with_ratio(mesh: &Mesh, ratio: f64) -> Self {
+        Self {
+            target_triangles: (mesh.triangle_count() as f64 * ratio.clamp(0.0, 1.0)) as usize,
+            ..Default::default()
+        }
+    }
+}
+
+/// Symmetric 4x4 error quadric plus accumulated area weight
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric {
+    m: [f64; 10],
+    weight: f64,
+}
+
+impl Quadric {
+    /// Squared distance to the plane `n·p + d = 0`, scaled by `weight`
+    fn from_plane(n: Vector3<f64>, d: f64, weight: f64) -> Self {
+        let (a, b, c) = (n.x, n.y, n.z);
+        let m = [
+            a * a,
+            a * b,
+            a * c,
+            a * d,
+            b * b,
+            b * c,
+            b * d,
+            c * c,
+            c * d,
+            d * d,
+        ];
+        Self {
+            m: m.map(|v| v * weight),
+            weight,
+        }
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (a, b) in self.m.iter_mut().zip(other.m.iter()) {
+            *a += b;
+        }
+        self.weight += other.weight;
+    }
+
+    /// Weighted mean squared distance of `p` to the accumulated planes
+    fn error(&self, p: &Point3<f64>) -> f64 {
+        let [a2, ab, ac, ad, b2, bc, bd, c2, cd, d2] = self.m;
+        let (x, y, z) = (p.x, p.y, p.z);
+        let e = a2 * x * x
+            + 2.0 * ab * x * y
+            + 2.0 * ac * x * z
+            + 2.0 * ad * x
+            + b2 * y * y
+            + 2.0 * bc * y * z
+            + 2.0 * bd * y
+            + c2 * z * z
+            + 2.0 * cd * z
+            + d2;
+        if self.weight > 0.0 {
+            (e / self.weight).max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Candidate collapse of `from` onto `to`
+#[derive(Debug, Clone, Copy)]
+struct Collapse {
+    cost: f64,
+    from: u32,
+    to: u32,
+    versions: (u32, u32),
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    // Reversed so BinaryHeap pops the cheapest collapse first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// Decimation state over position-welded vertices
+struct Decimator<'a> {
+    mesh: &'a Mesh,
+    /// Original vertex -> welded position
+    remap: Vec<u32>,
+    points: Vec<Point3<f64>>,
+    /// Original vertex indices per triangle (attributes are kept per corner)
+    triangles: Vec<[u32; 3]>,
+    alive: Vec<bool>,
+    /// Triangles touching each welded position (may include dead ones)
+    adjacency: Vec<Vec<u32>>,
+    quadrics: Vec<Quadric>,
+    locked: Vec<bool>,
+    removed: Vec<bool>,
+    versions: Vec<u32>,
+}
+
+impl<'a> Decimator<'a> {
+    fn new(mesh: &'a Mesh, options: &SimplifyOptions) -> Self {
+        let vertex_count = mesh.vertex_count();
+        let mut welded: FxHashMap<[u32; 3], u32> = FxHashMap::default();
+        let mut points = Vec::new();
+        let mut remap = Vec::with_capacity(vertex_count);
+        for i in 0..vertex_count {
+            let p = &mesh.positions[i * 3..i * 3 + 3];
+            let id = *welded
+                .entry([p[0].to_bits(), p[1].to_bits(), p[2].to_bits()])
+                .or_insert_with(|| {
+                    points.push(Point3::new(p[0] as f64, p[1] as f64, p[2] as f64));
+                    (points.len() - 1) as u32
+                });
+            remap.push(id);
+        }
+
+        let mut triangles = Vec::with_capacity(mesh.indices.len() / 3);
+        for tri in mesh.indices.chunks_exact(3) {
+            if tri.iter().any(|&i| i as usize >= vertex_count) {
+                continue;
+            }
+            let (a, b, c) = (
+                remap[tri[0] as usize],
+                remap[tri[1] as usize],
+                remap[tri[2] as usize],
+            );
+            if a != b && b != c && a != c {
+                triangles.push([tri[0], tri[1], tri[2]]);
+            }
+        }
+
+        let mut decimator = Self {
+            mesh,
+            remap,
+            adjacency: vec![Vec::new(); points.len()],
+            quadrics: vec![Quadric::default(); points.len()],
+            locked: vec![false; points.len()],
+            removed: vec![false; points.len()],
+            versions: vec![0; points.len()],
+            alive: vec![true; triangles.len()],
+            points,
+            triangles,
+        };
+        decimator.build(options);
+        decimator
+    }
+
+    fn corners(&self, t: usize) -> [u32; 3] {
+        self.triangles[t].map(|v| self.remap[v as usize])
+    }
+
+    fn normal(&self, v: u32) -> Option<Vector3<f64>> {
+        let n = self.mesh.normals.get(v as usize * 3..v as usize * 3 + 3)?;
+        Some(Vector3::new(n[0] as f64, n[1] as f64, n[2] as f64))
+    }
+
+    /// Fill adjacency and quadrics, and lock boundary, non-manifold and crease vertices
+    fn build(&mut self, options: &SimplifyOptions) {
+        let mut edges: FxHashMap<(u32, u32), (u32, usize)> = FxHashMap::default();
+        for t in 0..self.triangles.len() {
+            let [a, b, c] = self.corners(t);
+            for p in [a, b, c] {
+                self.adjacency[p as usize].push(t as u32);
+            }
+            let (pa, pb, pc) = (
+                self.points[a as usize],
+                self.points[b as usize],
+                self.points[c as usize],
+            );
+            let cross = (pb - pa).cross(&(pc - pa));
+            let area = cross.norm() * 0.5;
+            if let Some(n) = cross.try_normalize(1e-20) {
+                let q = Quadric::from_plane(n, -n.dot(&pa.coords), area);
+                for p in [a, b, c] {
+                    self.quadrics[p as usize].add(&q);
+                }
+            }
+            for (u, v) in [(a, b), (b, c), (c, a)] {
+                edges.entry((u.min(v), u.max(v))).or_insert((0, t)).0 += 1;
+            }
+        }
+
+        for (&(u, v), &(count, t)) in &edges {
+            if count > 2 {
+                self.locked[u as usize] = true;
+                self.locked[v as usize] = true;
+            } else if count == 1 {
+                if options.preserve_boundaries {
+                    self.locked[u as usize] = true;
+                    self.locked[v as usize] = true;
+                } else {
+                    // Plane through the edge, perpendicular to its face, keeps the border in place
+                    let [a, b, c] = self.corners(t).map(|p| self.points[p as usize]);
+                    let face = (b - a).cross(&(c - a));
+                    let (pu, pv) = (self.points[u as usize], self.points[v as usize]);
+                    let edge = pv - pu;
+                    if let Some(n) = edge.cross(&face).try_normalize(1e-20) {
+                        let q = Quadric::from_plane(n, -n.dot(&pu.coords), edge.norm_squared());
+                        self.quadrics[u as usize].add(&q);
+                        self.quadrics[v as usize].add(&q);
+                    }
+                }
+            }
+        }
+
+        // Hard edges: corners at one position whose normals diverge
+        let min_dot = options.crease_angle.cos();
+        let mut first_normal: Vec<Option<Vector3<f64>>> = vec![None; self.points.len()];
+        for (v, &p) in self.remap.iter().enumerate() {
+            let Some(n) = self.normal(v as u32).and_then(|n| n.try_normalize(1e-12)) else {
+                continue;
+            };
+            match first_normal[p as usize] {
+                Some(first) if first.dot(&n) < min_dot => self.locked[p as usize] = true,
+                Some(_) => {}
+                None => first_normal[p as usize] = Some(n),
+            }
+        }
+    }
+
+    fn live_triangles(&self, p: u32) -> impl Iterator<Item = usize> + '_ {
+        self.adjacency[p as usize]
+            .iter()
+            .map(|&t| t as usize)
+            .filter(|&t| self.alive[t])
+    }
+
+    fn neighbours(&self, p: u32) -> Vec<u32> {
+        let mut result: Vec<u32> = self
+            .live_triangles(p)
+            .flat_map(|t| self.corners(t))
+            .filter(|&q| q != p)
+            .collect();
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
+    fn candidate(&self, from: u32, to: u32) -> Option<Collapse> {
+        if self.locked[from as usize] {
+            return None;
+        }
+        let mut q = self.quadrics[from as usize];
+        q.add(&self.quadrics[to as usize]);
+        Some(Collapse {
+            cost: q.error(&self.points[to as usize]),
+            from,
+            to,
+            versions: (self.versions[from as usize], self.versions[to as usize]),
+        })
+    }
+
+    /// Link condition and normal-flip check for collapsing `from` onto `to`
+    fn can_collapse(&self, from: u32, to: u32) -> bool {
+        let shared = self
+            .live_triangles(from)
+            .filter(|&t| self.corners(t).contains(&to))
+            .count();
+        if shared == 0 {
+            return false;
+        }
+        let from_ring = self.neighbours(from);
+        let to_ring = self.neighbours(to);
+        let common = from_ring
+            .iter()
+            .filter(|p| to_ring.binary_search(p).is_ok())
+            .count();
+        if common != shared {
+            return false;
+        }
+
+        let target = self.points[to as usize];
+        self.live_triangles(from).all(|t| {
+            let corners = self.corners(t);
+            if corners.contains(&to) {
+                return true;
+            }
+            let before = corners.map(|p| self.points[p as usize]);
+            let after = corners.map(|p| {
+                if p == from {
+                    target
+                } else {
+                    self.points[p as usize]
+                }
+            });
+            let n0 = (before[1] - before[0]).cross(&(before[2] - before[0]));
+            let n1 = (after[1] - after[0]).cross(&(after[2] - after[0]));
+            n1.norm_squared() > 1e-24 && n0.dot(&n1) > 0.0
+        })
+    }
+
+    /// Collapse and return the number of triangles removed
+    fn collapse(&mut self, from: u32, to: u32) -> usize {
+        let mut removed = 0;
+        let triangles: Vec<usize> = self.live_triangles(from).collect();
+
+        // Attribute vertex already used at `to`, per candidate normal
+        let to_corners: Vec<u32> = self
+            .live_triangles(to)
+            .flat_map(|t| self.triangles[t])
+            .filter(|&v| self.remap[v as usize] == to)
+            .collect();
+
+        for t in triangles {
+            if self.corners(t).contains(&to) {
+                self.alive[t] = false;
+                removed += 1;
+                continue;
+            }
+            for slot in 0..3 {
+                let v = self.triangles[t][slot];
+                if self.remap[v as usize] == from {
+                    self.triangles[t][slot] = self.closest_corner(v, &to_corners);
+                }
+            }
+            self.adjacency[to as usize].push(t as u32);
+        }
+
+        let q = self.quadrics[from as usize];
+        self.quadrics[to as usize].add(&q);
+        self.removed[from as usize] = true;
+        // Only `to` changed its quadric; topology is rechecked when popping
+        self.versions[to as usize] += 1;
+        removed
+    }
+
+    /// Corner from `candidates` whose normal best matches vertex `v`
+    fn closest_corner(&self, v: u32, candidates: &[u32]) -> u32 {
+        let Some(n) = self.normal(v) else {
+            return candidates[0];
+        };
+        *candidates
+            .iter()
+            .max_by(|&&a, &&b| {
+                let da = self.normal(a).map_or(f64::MIN, |m| m.dot(&n));
+                let db = self.normal(b).map_or(f64::MIN, |m| m.dot(&n));
+                da.total_cmp(&db)
+            })
+            .unwrap_or(&candidates[0])
+    }
+
+    fn push_candidates(&self, heap: &mut BinaryHeap<Collapse>, p: u32) {
+        for q in self.neighbours(p) {
+            heap.extend(self.candidate(p, q));
+            heap.extend(self.candidate(q, p));
+        }
+    }
+
+    fn run(&mut self, options: &SimplifyOptions) {
+        let mut triangle_count = self.triangles.len();
+        let max_error_sq = options.max_error * options.max_error;
+
+        let mut heap = BinaryHeap::new();
+        for t in 0..self.triangles.len() {
+            let [a, b, c] = self.corners(t);
+            for (u, v) in [(a, b), (b, c), (c, a)] {
+                heap.extend(self.candidate(u, v));
+                heap.extend(self.candidate(v, u));
+            }
+        }
+
+        while triangle_count > options.target_triangles {
+            let Some(c) = heap.pop() else { break };
+            if c.cost > max_error_sq {
+                break;
+            }
+            let (from, to) = (c.from as usize, c.to as usize);
+            if self.removed[from]
+                || self.removed[to]
+                || c.versions != (self.versions[from], self.versions[to])
+                || !self.can_collapse(c.from, c.to)
+            {
+                continue;
+            }
+            triangle_count -= self.collapse(c.from, c.to);
+            self.push_candidates(&mut heap, c.to);
+        }
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let mut out = Mesh::with_capacity(0, 0);
+        let mut new_index: FxHashMap<u32, u32> = FxHashMap::default();
+        let has_normals = self.mesh.normals.len() == self.mesh.positions.len();
+        for (t, tri) in self.triangles.iter().enumerate() {
+            if !self.alive[t] {
+                continue;
+            }
+            for &v in tri {
+                let index = *new_index.entry(v).or_insert_with(|| {
+                    let i = v as usize * 3;
+                    out.positions
+                        .extend_from_slice(&self.mesh.positions[i..i + 3]);
+                    if has_normals {
+                        out.normals.extend_from_slice(&self.mesh.normals[i..i + 3]);
+                    }
+                    (out.positions.len() / 3 - 1) as u32
+                });
+                out.indices.push(index);
+            }
+        }
+        out.rtc_applied = self.mesh.rtc_applied;
+        out
+    }
+}
+
+/// Decimate `mesh` towards `options.target_triangles` by quadric edge collapse.
+///
+/// The result may keep more triangles than requested when further collapses
+/// would exceed `max_error`, flip faces or touch locked (boundary or crease)
+/// vertices.
+pub fn simplify(mesh: &Mesh, options: &SimplifyOptions) -> Mesh {
+    if mesh.triangle_count() <= options.target_triangles {
+        return mesh.clone();
+    }
+    let mut decimator = Decimator::new(mesh, options);
+    decimator.run(options);
+    decimator.into_mesh()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Smooth-shaded n x n grid on a gently curved surface
+    fn terrain(n: u32) -> Mesh {
+        let mut mesh = Mesh::new();
+        for j in 0..=n {
+            for i in 0..=n {
+                let (x, y) = (i as f64 / n as f64, j as f64 / n as f64);
+                mesh.add_vertex(Point3::new(x, y, 0.05 * (x * 3.0).sin()), Vector3::z());
+            }
+        }
+        let cols = n + 1;
+        for j in 0..n {
+            for i in 0..n {
+                let a = j * cols + i;
+                mesh.add_triangle(a, a + 1, a + cols + 1);
+                mesh.add_triangle(a, a + cols + 1, a + cols);
+            }
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_simplify_reaches_budget_and_keeps_bounds() {
+        let mesh = terrain(40);
+        let options = SimplifyOptions::with_ratio(&mesh, 0.1);
+        let simplified = simplify(&mesh, &options);
+
+        assert!(simplified.triangle_count() <= options.target_triangles);
+        assert!(simplified.triangle_count() > 0);
+        assert_eq!(simplified.normals.len(), simplified.positions.len());
+        assert!(simplified
+            .indices
+            .iter()
+            .all(|&i| (i as usize) < simplified.vertex_count()));
+
+        // Boundary vertices are locked, so the footprint is unchanged
+        let (min, max) = simplified.bounds();
+        let (orig_min, orig_max) = mesh.bounds();
+        assert_eq!(
+            (min.x, min.y, max.x, max.y),
+            (orig_min.x, orig_min.y, orig_max.x, orig_max.y)
+        );
+    }
+
+    #[test]
+    fn test_simplify_respects_error_and_creases() {
+        // Flat grid: every interior vertex can go at zero error
+        let mut flat = terrain(10);
+        for z in flat.positions.iter_mut().skip(2).step_by(3) {
+            *z = 0.0;
+        }
+        let simplified = simplify(
+            &flat,
+            &SimplifyOptions {
+                max_error: 1e-6,
+                ..Default::default()
+            },
+        );
+        assert!(simplified.triangle_count() < flat.triangle_count());
+
+        // A closed flat-shaded box has only crease vertices and is left alone
+        let mut cube = Mesh::new();
+        let corners = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        for axis in 0..3 {
+            for sign in [0.0, 1.0] {
+                let base = cube.vertex_count() as u32;
+                let mut n = Vector3::zeros();
+                n[axis] = if sign > 0.0 { 1.0 } else { -1.0 };
+                for [u, v] in corners {
+                    let mut p = Point3::origin();
+                    p[axis] = sign;
+                    p[(axis + 1) % 3] = u;
+                    p[(axis + 2) % 3] = v;
+                    cube.add_vertex(p, n);
+                }
+                cube.add_triangle(base, base + 1, base + 2);
+                cube.add_triangle(base, base + 2, base + 3);
+            }
+        }
+        let simplified = simplify(&cube, &SimplifyOptions::default());
+        assert_eq!(simplified.triangle_count(), 12);
+    }
+}
@@ -0,0 +1,188 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Crease-angle normal smoothing
+//!
+//! Processors emit one normal per face, which shades tessellated pipes and
+//! revolved solids as visible facets. [`smooth_normals`] rebuilds the vertex
+//! normals so that faces meeting at a dihedral angle below the crease angle
+//! share an averaged normal, while sharper edges (box corners, the cap rim of
+//! a pipe) keep split vertices and crisp shading.
+//!
+//! The smoothing group of a corner is the set of faces around its position
+//! whose normal lies within the crease angle of the corner's own face, so
+//! the result does not depend on face order.
+
+use crate::mesh::Mesh;
+use nalgebra::Vector3;
+use rustc_hash::FxHashMap;
+
+/// Normal smoothing settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalSmoothing {
+    /// Largest dihedral angle still shaded smooth, in radians
+    pub crease_angle: f64,
+    /// Vertices closer than this share a position (mesh units)
+    pub weld_tolerance: f64,
+}
+
+impl Default for NormalSmoothing {
+    /// 30°: smooth for tessellated curves at any of the tessellation presets,
+    /// crisp for right angles and typical chamfers
+    fn default() -> Self {
+        Self {
+            crease_angle: 30f64.to_radians(),
+            weld_tolerance: 1e-5,
+        }
+    }
+}
+
+impl NormalSmoothing {
+    /// Set the crease angle in degrees
+    pub fn with_crease_angle_degrees(mut self, degrees: f64) -> Self {
+        self.crease_angle = degrees.to_radians();
+        self
+    }
+
+    /// Set the position weld tolerance
+    pub fn with_weld_tolerance(mut self, tolerance: f64) -> Self {
+        self.weld_tolerance = tolerance;
+        self
+    }
+}
+
+/// Recompute normals with smoothing groups split at creases.
+///
+/// Vertices are re-indexed: corners in one smoothing group share a vertex,
+/// so a smooth cylinder ends up with fewer vertices than it started with.
+/// Triangle order and winding are unchanged; triangles with out-of-range
+/// indices are dropped.
+pub fn smooth_normals(mesh: &mut Mesh, smoothing: &NormalSmoothing) {
+    mesh.validate_indices();
+    if mesh.indices.is_empty() {
+        return;
+    }
+    let (points, position_ids) = mesh.position_ids(smoothing.weld_tolerance);
+
+    // Unit normal per face (None when degenerate), over welded positions
+    let faces: Vec<[u32; 3]> = mesh
+        .indices
+        .chunks_exact(3)
+        .map(|t| [0, 1, 2].map(|k| position_ids[t[k] as usize]))
+        .collect();
+    let unit: Vec<Option<Vector3<f64>>> = faces
+        .iter()
+        .map(|f| {
+            let [a, b, c] = f.map(|p| points[p as usize]);
+            (b - a).cross(&(c - a)).try_normalize(1e-20)
+        })
+        .collect();
+
+    // Faces around each position with their corner angle there; weighting
+    // by angle keeps the result independent of how quads were split
+    let mut incident: Vec<Vec<(u32, f64)>> = vec![Vec::new(); points.len()];
+    for (f, face) in faces.iter().enumerate() {
+        if unit[f].is_none() {
+            continue;
+        }
+        for k in 0..3 {
+            let corner = points[face[k] as usize];
+            let to_next = points[face[(k + 1) % 3] as usize] - corner;
+            let to_prev = points[face[(k + 2) % 3] as usize] - corner;
+            incident[face[k] as usize].push((f as u32, to_next.angle(&to_prev)));
+        }
+    }
+
+    let cos_crease = smoothing
+        .crease_angle
+        .clamp(0.0, std::f64::consts::PI)
+        .cos();
+    let mut vertex_of: FxHashMap<(u32, [u32; 3]), u32> = FxHashMap::default();
+    let mut positions = Vec::with_capacity(mesh.positions.len());
+    let mut normals = Vec::with_capacity(mesh.positions.len());
+    let mut indices = Vec::with_capacity(mesh.indices.len());
+
+    for (f, face) in faces.iter().enumerate() {
+        for &p in face {
+            // A degenerate face takes whatever its neighbours agree on
+            let sum: Vector3<f64> = incident[p as usize]
+                .iter()
+                .filter_map(|&(g, angle)| Some((unit[g as usize]?, angle)))
+                .filter(|(n, _)| unit[f].is_none_or(|own| n.dot(&own) >= cos_crease))
+                .map(|(n, angle)| n * angle)
+                .sum();
+            let normal = sum
+                .try_normalize(1e-20)
+                .map_or([0.0, 0.0, 1.0], |n| [n.x as f32, n.y as f32, n.z as f32]);
+
+            let key = (p, normal.map(f32::to_bits));
+            let vertex = *vertex_of.entry(key).or_insert_with(|| {
+                let point = points[p as usize];
+                positions.extend([point.x as f32, point.y as f32, point.z as f32]);
+                normals.extend(normal);
+                (positions.len() / 3 - 1) as u32
+            });
+            indices.push(vertex);
+        }
+    }
+
+    mesh.positions = positions;
+    mesh.normals = normals;
+    mesh.indices = indices;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extrude_profile, Profile2D};
+    use nalgebra::Point2;
+    use std::f64::consts::TAU;
+
+    fn normal_at(mesh: &Mesh, vertex: usize) -> Vector3<f32> {
+        Vector3::from_column_slice(&mesh.normals[vertex * 3..vertex * 3 + 3])
+    }
+
+    #[test]
+    fn test_cylinder_sides_smooth_caps_crisp() {
+        let circle: Vec<Point2<f64>> = (0..24)
+            .map(|i| {
+                let a = TAU * i as f64 / 24.0;
+                Point2::new(a.cos(), a.sin())
+            })
+            .collect();
+        let mut cylinder = extrude_profile(&Profile2D::new(circle), 2.0, None).unwrap();
+        let triangles = cylinder.triangle_count();
+        smooth_normals(&mut cylinder, &NormalSmoothing::default());
+        assert_eq!(cylinder.triangle_count(), triangles);
+
+        // Every rim position has one radial (side) vertex and one cap vertex
+        assert_eq!(cylinder.vertex_count(), 24 * 2 * 2);
+        for v in 0..cylinder.vertex_count() {
+            let p = &cylinder.positions[v * 3..v * 3 + 3];
+            let n = normal_at(&cylinder, v);
+            let radial = Vector3::new(p[0], p[1], 0.0).normalize();
+            let is_cap = n.z.abs() > 0.999;
+            assert!(is_cap || (n - radial).norm() < 1e-4, "vertex {v}: {n:?}");
+        }
+
+        // With a zero crease angle every face keeps its own normal
+        let square = Profile2D::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(0.0, 1.0),
+        ]);
+        let mut cube = extrude_profile(&square, 1.0, None).unwrap();
+        smooth_normals(
+            &mut cube,
+            &NormalSmoothing::default().with_crease_angle_degrees(0.0),
+        );
+        assert_eq!(cube.vertex_count(), 24);
+        assert!((0..24).all(|v| normal_at(&cube, v)
+            .iter()
+            .filter(|c| c.abs() > 0.999)
+            .count()
+            == 1));
+    }
+}
@@ -0,0 +1,285 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Uniform spatial hash grid over element AABBs
+//!
+//! Elements are registered in every cell their box touches; only occupied
+//! cells are stored. The grid is the broad phase for clash detection
+//! ([`SpatialGrid::overlapping_pairs`]) and answers point, box and frustum
+//! queries without walking every element.
+//!
+//! Elements spanning more than [`MAX_CELLS_PER_ELEMENT`] cells (site slabs,
+//! long curtain walls) are kept in a separate list and tested directly, so
+//! one huge element can't flood the grid.
+
+use crate::bounds::{Aabb, ModelBounds};
+use crate::csg::Plane;
+use nalgebra::Point3;
+use rustc_hash::FxHashMap;
+
+/// Cell count above which an element is tested directly instead of binned
+pub const MAX_CELLS_PER_ELEMENT: usize = 512;
+
+type Cell = [i32; 3];
+
+/// Spatial hash grid keyed by element AABBs
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f64,
+    elements: Vec<(u32, Aabb)>,
+    cells: FxHashMap<Cell, Vec<u32>>,
+    /// Indices into `elements` that cover too many cells
+    oversized: Vec<u32>,
+}
+
+impl SpatialGrid {
+    /// Empty grid with the given cell edge length
+    pub fn new(cell_size: f64) -> Self {
+        Self {
+            cell_size: if cell_size.is_finite() && cell_size > 0.0 {
+                cell_size
+            } else {
+                1.0
+            },
+            elements: Vec::new(),
+            cells: FxHashMap::default(),
+            oversized: Vec::new(),
+        }
+    }
+
+    /// Grid over model bounds, with cells about twice the median element size
+    pub fn from_bounds(bounds: &ModelBounds) -> Self {
+        let mut sizes: Vec<f64> = bounds.iter().map(|(_, aabb)| aabb.size().max()).collect();
+        sizes.sort_by(f64::total_cmp);
+        let median = sizes.get(sizes.len() / 2).copied().unwrap_or(1.0);
+        let mut grid = Self::new(2.0 * median);
+        for (express_id, aabb) in bounds.iter() {
+            grid.insert(*express_id, *aabb);
+        }
+        grid
+    }
+
+    /// Register an element's box
+    pub fn insert(&mut self, express_id: u32, aabb: Aabb) {
+        let index = self.elements.len() as u32;
+        self.elements.push((express_id, aabb));
+        let (lo, hi) = self.cell_range(&aabb);
+        let cell_count = (0..3)
+            .map(|axis| (hi[axis] as i64 - lo[axis] as i64 + 1) as usize)
+            .product::<usize>();
+        if cell_count > MAX_CELLS_PER_ELEMENT {
+            self.oversized.push(index);
+            return;
+        }
+        for cell in cells_in(lo, hi) {
+            self.cells.entry(cell).or_default().push(index);
+        }
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    /// Number of registered elements
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Elements whose box contains the point
+    pub fn query_point(&self, point: &Point3<f64>) -> Vec<u32> {
+        let cell = self.cell_of(point);
+        let candidates = self.cells.get(&cell).into_iter().flatten();
+        let mut ids: Vec<u32> = candidates
+            .chain(&self.oversized)
+            .map(|&i| &self.elements[i as usize])
+            .filter(|(_, aabb)| aabb.contains(point))
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Elements whose box intersects `query`
+    pub fn query_box(&self, query: &Aabb) -> Vec<u32> {
+        self.collect_ids(self.candidates(query), |aabb| aabb.intersects(query))
+    }
+
+    /// Elements whose box is not entirely behind any of the planes. Plane
+    /// normals point into the frustum; the test is conservative near corners.
+    /// Occupied cells are culled first, so off-screen regions cost one test.
+    pub fn query_frustum(&self, planes: &[Plane]) -> Vec<u32> {
+        let visible = |aabb: &Aabb| {
+            planes.iter().all(|plane| {
+                // Corner farthest along the normal
+                let corner = Point3::from(std::array::from_fn(|axis| {
+                    if plane.normal[axis] >= 0.0 {
+                        aabb.max[axis]
+                    } else {
+                        aabb.min[axis]
+                    }
+                }));
+                plane.signed_distance(&corner) >= 0.0
+            })
+        };
+        let mut candidates = self.oversized.clone();
+        for (cell, members) in &self.cells {
+            if visible(&self.cell_bounds(*cell)) {
+                candidates.extend(members);
+            }
+        }
+        self.collect_ids(candidates, visible)
+    }
+
+    /// Broad phase: pairs of distinct elements whose boxes, grown by
+    /// `tolerance`, intersect. Each pair is reported once as (lower, higher)
+    /// express ID, sorted.
+    pub fn overlapping_pairs(&self, tolerance: f64) -> Vec<(u32, u32)> {
+        let grow = |aabb: &Aabb| Aabb {
+            min: aabb.min.map(|c| c - tolerance),
+            max: aabb.max.map(|c| c + tolerance),
+        };
+        let mut pairs = Vec::new();
+        let mut report = |a: u32, b: u32| {
+            let (id_a, box_a) = &self.elements[a as usize];
+            let (id_b, box_b) = &self.elements[b as usize];
+            if id_a != id_b && grow(box_a).intersects(box_b) {
+                pairs.push((*id_a.min(id_b), *id_a.max(id_b)));
+            }
+        };
+
+        for (&cell, members) in &self.cells {
+            for (k, &a) in members.iter().enumerate() {
+                for &b in &members[k + 1..] {
+                    // Report a pair only in the first cell both boxes share,
+                    // so pairs spanning several cells aren't repeated
+                    let lo_a = self.cell_range(&self.elements[a as usize].1).0;
+                    let lo_b = self.cell_range(&self.elements[b as usize].1).0;
+                    if std::array::from_fn(|axis| lo_a[axis].max(lo_b[axis])) == cell {
+                        report(a, b);
+                    }
+                }
+            }
+        }
+        for (k, &a) in self.oversized.iter().enumerate() {
+            for b in 0..self.elements.len() as u32 {
+                // Skip itself and earlier oversized elements, already paired
+                let already_paired = b == a || self.oversized[..k].contains(&b);
+                if !already_paired {
+                    report(a, b);
+                }
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    fn candidates(&self, query: &Aabb) -> Vec<u32> {
+        let (lo, hi) = self.cell_range(query);
+        let mut candidates = self.oversized.clone();
+        let cell_count = (0..3)
+            .map(|axis| (hi[axis] as i64 - lo[axis] as i64 + 1) as usize)
+            .product::<usize>();
+        if cell_count > self.cells.len() {
+            // Cheaper to walk the occupied cells than the query range
+            for (cell, members) in &self.cells {
+                if (0..3).all(|axis| lo[axis] <= cell[axis] && cell[axis] <= hi[axis]) {
+                    candidates.extend(members);
+                }
+            }
+        } else {
+            for cell in cells_in(lo, hi) {
+                candidates.extend(self.cells.get(&cell).into_iter().flatten());
+            }
+        }
+        candidates
+    }
+
+    fn collect_ids(&self, candidates: Vec<u32>, keep: impl Fn(&Aabb) -> bool) -> Vec<u32> {
+        let mut ids: Vec<u32> = candidates
+            .into_iter()
+            .map(|i| &self.elements[i as usize])
+            .filter(|(_, aabb)| keep(aabb))
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    fn cell_of(&self, point: &Point3<f64>) -> Cell {
+        let c = |v: f64| {
+            (v / self.cell_size)
+                .floor()
+                .clamp(i32::MIN as f64, i32::MAX as f64) as i32
+        };
+        [c(point.x), c(point.y), c(point.z)]
+    }
+
+    fn cell_range(&self, aabb: &Aabb) -> (Cell, Cell) {
+        (self.cell_of(&aabb.min), self.cell_of(&aabb.max))
+    }
+
+    fn cell_bounds(&self, cell: Cell) -> Aabb {
+        let min = Point3::from(cell.map(|c| c as f64 * self.cell_size));
+        Aabb {
+            min,
+            max: min.map(|c| c + self.cell_size),
+        }
+    }
+}
+
+fn cells_in(lo: Cell, hi: Cell) -> impl Iterator<Item = Cell> {
+    (lo[0]..=hi[0]).flat_map(move |x| {
+        (lo[1]..=hi[1]).flat_map(move |y| (lo[2]..=hi[2]).map(move |z| [x, y, z]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    fn aabb(min: [f64; 3], max: [f64; 3]) -> Aabb {
+        Aabb {
+            min: Point3::from(min),
+            max: Point3::from(max),
+        }
+    }
+
+    #[test]
+    fn test_grid_queries_and_broad_phase() {
+        let mut grid = SpatialGrid::new(1.0);
+        // Two touching walls, a column clear of both, and a huge slab under all
+        grid.insert(1, aabb([0.0, 0.0, 0.0], [3.0, 0.2, 3.0]));
+        grid.insert(2, aabb([2.9, 0.0, 0.0], [3.1, 3.0, 3.0]));
+        grid.insert(3, aabb([6.0, 6.0, 0.0], [6.3, 6.3, 3.0]));
+        grid.insert(4, aabb([-50.0, -50.0, -0.3], [50.0, 50.0, 0.0]));
+        assert_eq!(grid.oversized.len(), 1);
+
+        assert_eq!(
+            grid.overlapping_pairs(0.0),
+            [(1, 2), (1, 4), (2, 4), (3, 4)]
+        );
+        let empty = SpatialGrid::from_bounds(&ModelBounds::default());
+        assert!(empty.is_empty() && empty.overlapping_pairs(0.0).is_empty());
+
+        assert_eq!(grid.query_point(&Point3::new(3.0, 0.1, 1.0)), [1, 2]);
+        assert_eq!(grid.query_point(&Point3::new(20.0, 20.0, -0.1)), [4]);
+        assert_eq!(grid.query_box(&aabb([5.0, 5.0, 1.0], [7.0, 7.0, 2.0])), [3]);
+
+        // Half-space x >= 4, above the slab
+        let planes = [
+            Plane::new(Point3::new(4.0, 0.0, 0.0), Vector3::x()),
+            Plane::new(Point3::new(0.0, 0.0, 0.5), Vector3::z()),
+        ];
+        assert_eq!(grid.query_frustum(&planes), [3]);
+    }
+}
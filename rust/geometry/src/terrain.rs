@@ -0,0 +1,116 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Terrain surface decimation
+//!
+//! Site surfaces (IfcTriangulatedIrregularNetwork items, IfcGeographicElement
+//! terrain) often carry hundreds of thousands of survey triangles, far more
+//! than a viewer needs. [`decimate_terrain`] reduces them to a triangle
+//! budget with quadric edge collapse (see [`crate::simplify`]), keeping the
+//! site boundary fixed and never moving the surface further than a height
+//! tolerance.
+
+use crate::mesh::Mesh;
+use crate::simplify::{simplify, SimplifyOptions};
+use ifc_lite_core::IfcType;
+
+/// Terrain decimation settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainDecimation {
+    /// Surfaces with more triangles than this are decimated towards it
+    pub max_triangles: usize,
+    /// Largest allowed deviation from the surveyed surface, in meters
+    pub max_error: f64,
+}
+
+impl Default for TerrainDecimation {
+    fn default() -> Self {
+        Self {
+            max_triangles: 100_000,
+            max_error: 0.05,
+        }
+    }
+}
+
+impl TerrainDecimation {
+    /// Set the triangle budget
+    pub fn with_max_triangles(mut self, max_triangles: usize) -> Self {
+        self.max_triangles = max_triangles.max(1);
+        self
+    }
+
+    /// Set the height tolerance in meters
+    pub fn with_max_error(mut self, max_error: f64) -> Self {
+        self.max_error = max_error;
+        self
+    }
+}
+
+/// Whether meshes of this item or element type are terrain surfaces
+pub fn is_terrain(ifc_type: &IfcType) -> bool {
+    matches!(
+        ifc_type,
+        IfcType::IfcTriangulatedIrregularNetwork | IfcType::IfcGeographicElement
+    )
+}
+
+/// Decimate a terrain mesh (in meters) over the triangle budget. Returns
+/// `None` when the mesh is already within budget.
+pub fn decimate_terrain(mesh: &Mesh, options: &TerrainDecimation) -> Option<Mesh> {
+    if mesh.triangle_count() <= options.max_triangles {
+        return None;
+    }
+    Some(simplify(
+        mesh,
+        &SimplifyOptions {
+            target_triangles: options.max_triangles,
+            max_error: options.max_error,
+            preserve_boundaries: true,
+            ..SimplifyOptions::default()
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Point3, Vector3};
+
+    /// Gently rolling n x n grid over a 100 m square
+    fn terrain(n: u32) -> Mesh {
+        let mut mesh = Mesh::new();
+        for j in 0..=n {
+            for i in 0..=n {
+                let (x, y) = (100.0 * i as f64 / n as f64, 100.0 * j as f64 / n as f64);
+                let z = 0.5 * (x / 40.0).sin() + 0.3 * (y / 25.0).cos();
+                mesh.add_vertex(Point3::new(x, y, z), Vector3::z());
+            }
+        }
+        let cols = n + 1;
+        for j in 0..n {
+            for i in 0..n {
+                let a = j * cols + i;
+                mesh.add_triangle(a, a + 1, a + cols + 1);
+                mesh.add_triangle(a, a + cols + 1, a + cols);
+            }
+        }
+        mesh
+    }
+
+    #[test]
+    fn test_decimate_over_budget() {
+        let mesh = terrain(60);
+        let options = TerrainDecimation::default()
+            .with_max_triangles(1_000)
+            .with_max_error(0.1);
+        assert!(decimate_terrain(&mesh, &options.with_max_triangles(10_000)).is_none());
+
+        let decimated = decimate_terrain(&mesh, &options).unwrap();
+        assert!(decimated.triangle_count() < mesh.triangle_count() / 2);
+        // Boundary corners stay in place
+        let (min, max) = decimated.bounds();
+        assert!((min.x - 0.0).abs() < 1e-4 && (max.x - 100.0).abs() < 1e-4);
+        assert!((min.y - 0.0).abs() < 1e-4 && (max.y - 100.0).abs() < 1e-4);
+    }
+}
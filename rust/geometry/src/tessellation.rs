@@ -0,0 +1,195 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tessellation quality settings
+//!
+//! [`TessellationConfig`] controls how finely curved geometry is approximated:
+//! circle and ellipse profiles, arcs in composite curves, swept disk and
+//! revolved solids, B-spline edges and surfaces, and analytic advanced faces.
+//! A segment count is derived from two limits, whichever is finer: the chord
+//! tolerance (maximum gap between the true curve and a straight segment) and
+//! the maximum angle a single segment may span. The result is clamped to the
+//! configured range, scaled by the fraction of a full turn being sampled.
+
+use nalgebra::Point3;
+use std::f64::consts::{PI, TAU};
+
+/// Curve and surface tessellation quality.
+///
+/// Set once on the [`GeometryRouter`](crate::GeometryRouter) and used by every
+/// processor it creates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TessellationConfig {
+    /// Maximum distance between a curve and its approximating chords, in metres.
+    pub chord_tolerance: f64,
+    /// Maximum angle spanned by one segment, in radians.
+    pub max_angle: f64,
+    /// Minimum number of segments for a full circle.
+    pub min_segments: usize,
+    /// Maximum number of segments for a full circle.
+    pub max_segments: usize,
+}
+
+impl Default for TessellationConfig {
+    /// Balanced quality for interactive viewing (24–36 segments per circle).
+    fn default() -> Self {
+        Self {
+            chord_tolerance: 0.002,
+            max_angle: PI / 12.0,
+            min_segments: 8,
+            max_segments: 36,
+        }
+    }
+}
+
+impl TessellationConfig {
+    /// Coarse settings for fast loading of large models.
+    pub fn fast() -> Self {
+        Self {
+            chord_tolerance: 0.01,
+            max_angle: PI / 4.0,
+            min_segments: 6,
+            max_segments: 16,
+        }
+    }
+
+    /// Fine settings for export and close-up rendering.
+    pub fn high_quality() -> Self {
+        Self {
+            chord_tolerance: 0.0002,
+            max_angle: PI / 36.0,
+            min_segments: 16,
+            max_segments: 256,
+        }
+    }
+
+    /// Express the chord tolerance in model units, given the model's
+    /// length unit scale (e.g., `0.001` for millimetres).
+    ///
+    /// Processors work on raw file coordinates, so the router converts
+    /// the config once before handing it to them.
+    pub fn in_model_units(mut self, unit_scale: f64) -> Self {
+        if unit_scale > 0.0 && unit_scale.is_finite() {
+            self.chord_tolerance /= unit_scale;
+        }
+        self
+    }
+
+    /// Number of segments for a circular arc of `radius` spanning `sweep` radians.
+    pub fn arc_segments(&self, radius: f64, sweep: f64) -> usize {
+        let sweep = sweep.abs().min(TAU);
+        if !sweep.is_finite() || sweep <= 0.0 {
+            return 1;
+        }
+
+        let mut step = self.max_angle.max(1e-3);
+        let radius = radius.abs();
+        if self.chord_tolerance > 0.0 && radius > self.chord_tolerance {
+            // Sagitta of a chord spanning angle a: r * (1 - cos(a / 2))
+            let chord_step = 2.0 * (1.0 - self.chord_tolerance / radius).acos();
+            step = step.min(chord_step);
+        }
+
+        let fraction = sweep / TAU;
+        let min = ((self.min_segments as f64 * fraction).ceil() as usize).max(1);
+        let max = ((self.max_segments as f64 * fraction).ceil() as usize).max(min);
+        ((sweep / step).ceil() as usize).clamp(min, max)
+    }
+
+    /// Number of segments for a full circle of `radius`.
+    pub fn circle_segments(&self, radius: f64) -> usize {
+        self.arc_segments(radius, TAU)
+    }
+
+    /// Number of segments for a curve described by a control polygon
+    /// (B-spline control points).
+    ///
+    /// The polygon's total turning angle and length are treated as an arc of
+    /// equivalent radius; a straight polygon yields a single segment.
+    pub fn polygon_segments(&self, points: &[Point3<f64>]) -> usize {
+        if points.len() < 3 {
+            return 1;
+        }
+
+        let mut length = 0.0;
+        let mut turning = 0.0;
+        let mut prev_dir = None;
+        for pair in points.windows(2) {
+            let edge = pair[1] - pair[0];
+            let edge_len = edge.norm();
+            if edge_len < 1e-12 {
+                continue;
+            }
+            length += edge_len;
+            let dir = edge / edge_len;
+            if let Some(prev) = prev_dir {
+                turning += dir.dot(&prev).clamp(-1.0, 1.0).acos();
+            }
+            prev_dir = Some(dir);
+        }
+
+        if turning < 1e-6 {
+            return 1;
+        }
+        self.arc_segments(length / turning, turning)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circle_segments_follow_quality() {
+        let fast = TessellationConfig::fast();
+        let default = TessellationConfig::default();
+        let high = TessellationConfig::high_quality();
+
+        for radius in [0.01, 0.1, 1.0, 10.0] {
+            let counts = [
+                fast.circle_segments(radius),
+                default.circle_segments(radius),
+                high.circle_segments(radius),
+            ];
+            assert!(
+                counts[0] <= counts[1] && counts[1] <= counts[2],
+                "{counts:?}"
+            );
+            assert!(counts[0] >= fast.min_segments && counts[2] <= high.max_segments);
+        }
+
+        // Large circles are bounded by chord tolerance, small ones by max_angle
+        assert_eq!(default.circle_segments(0.01), 24);
+        assert_eq!(default.circle_segments(10.0), 36);
+    }
+
+    #[test]
+    fn test_arc_and_unit_scaling() {
+        let config = TessellationConfig::default();
+        assert_eq!(config.arc_segments(0.01, PI / 2.0), 6);
+        assert_eq!(config.arc_segments(1.0, 0.0), 1);
+
+        // 1 m radius in millimetre coordinates tessellates like 1 m in metres
+        let mm = config.in_model_units(0.001);
+        assert_eq!(mm.circle_segments(1000.0), config.circle_segments(1.0));
+    }
+
+    #[test]
+    fn test_polygon_segments() {
+        let config = TessellationConfig::default();
+        let straight = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+        ];
+        assert_eq!(config.polygon_segments(&straight), 1);
+
+        let bent = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+        ];
+        assert!(config.polygon_segments(&bent) >= 6);
+    }
+}
@@ -0,0 +1,32 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Meshes shared by unit tests, and by tests of dependent crates through
+//! the `test-fixtures` feature.
+
+use crate::Mesh;
+
+/// Closed outward-facing box, 8 shared vertices and 12 triangles
+pub fn box_mesh(min: [f32; 3], max: [f32; 3]) -> Mesh {
+    let mut mesh = Mesh::new();
+    for i in 0..8 {
+        let corner = [0, 1, 2].map(|axis| {
+            if i >> axis & 1 == 1 {
+                max[axis]
+            } else {
+                min[axis]
+            }
+        });
+        mesh.positions.extend_from_slice(&corner);
+    }
+    mesh.indices = vec![
+        0, 2, 1, 1, 2, 3, // bottom
+        4, 5, 6, 5, 7, 6, // top
+        0, 1, 4, 1, 5, 4, // front
+        2, 6, 3, 3, 6, 7, // back
+        0, 4, 2, 2, 4, 6, // left
+        1, 3, 5, 3, 7, 5, // right
+    ];
+    mesh
+}
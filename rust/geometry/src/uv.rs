@@ -0,0 +1,180 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Texture coordinate generation
+//!
+//! IFC rarely carries explicit texture coordinates, so textured materials
+//! (`IfcSurfaceTexture`) need UVs projected onto the mesh. Projections run in
+//! the element's local frame, so a texture follows the element when it is
+//! rotated, and use metric units: one texture tile covers `tile_size` metres.
+
+use crate::mesh::Mesh;
+use nalgebra::{Matrix4, Point3, Vector3};
+
+/// How texture coordinates are projected onto a mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UvMapping {
+    /// Project onto the local plane across the element's thinnest extent
+    /// (the face of a wall, the top of a slab)
+    Planar,
+    /// Project each vertex along the local axis its normal is closest to
+    Box,
+    /// Wrap around the local Z axis through the element's centre
+    Cylindrical,
+}
+
+/// UV projection settings
+#[derive(Debug, Clone, Copy)]
+pub struct UvOptions {
+    pub mapping: UvMapping,
+    /// Element placement (local -> mesh coordinates); identity when the mesh
+    /// is already in element-local coordinates
+    pub frame: Matrix4<f64>,
+    /// Size of one texture repeat, in mesh units
+    pub tile_size: f64,
+}
+
+impl Default for UvOptions {
+    fn default() -> Self {
+        Self {
+            mapping: UvMapping::Box,
+            frame: Matrix4::identity(),
+            tile_size: 1.0,
+        }
+    }
+}
+
+impl UvOptions {
+    /// Options for a mapping with default frame and tile size
+    pub fn new(mapping: UvMapping) -> Self {
+        Self {
+            mapping,
+            ..Self::default()
+        }
+    }
+
+    /// Project in the frame of an element placement
+    pub fn with_frame(mut self, frame: Matrix4<f64>) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Set the size of one texture repeat
+    pub fn with_tile_size(mut self, tile_size: f64) -> Self {
+        self.tile_size = tile_size;
+        self
+    }
+}
+
+impl Mesh {
+    /// Generate texture coordinates (u, v per vertex).
+    ///
+    /// Box mapping uses the vertex normals; vertices without a normal fall
+    /// back to the planar projection.
+    pub fn generate_uvs(&self, options: &UvOptions) -> Vec<f32> {
+        let vertex_count = self.vertex_count();
+        if vertex_count == 0 {
+            return Vec::new();
+        }
+
+        let to_local = options
+            .frame
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+        let scale = if options.tile_size > f64::EPSILON {
+            1.0 / options.tile_size
+        } else {
+            1.0
+        };
+        let local: Vec<Point3<f64>> = self
+            .positions
+            .chunks_exact(3)
+            .map(|p| to_local.transform_point(&Point3::new(p[0] as f64, p[1] as f64, p[2] as f64)))
+            .collect();
+
+        let (min, max) = local.iter().fold(
+            (Point3::from([f64::MAX; 3]), Point3::from([f64::MIN; 3])),
+            |(min, max), p| (min.inf(p), max.sup(p)),
+        );
+        let extent = max - min;
+        let thinnest = extent.imin();
+        let centre = nalgebra::center(&min, &max);
+        let radius = 0.5 * extent.x.max(extent.y);
+
+        let mut uvs = Vec::with_capacity(vertex_count * 2);
+        for (i, p) in local.iter().enumerate() {
+            let (u, v) = match options.mapping {
+                UvMapping::Planar => project_along(p, thinnest),
+                UvMapping::Box => {
+                    let normal = self.normals.get(i * 3..i * 3 + 3).map(|n| {
+                        to_local.transform_vector(&Vector3::new(
+                            n[0] as f64,
+                            n[1] as f64,
+                            n[2] as f64,
+                        ))
+                    });
+                    match normal {
+                        Some(n) if n.norm_squared() > 1e-12 => project_along(p, n.abs().imax()),
+                        _ => project_along(p, thinnest),
+                    }
+                }
+                UvMapping::Cylindrical => {
+                    let angle = (p.y - centre.y).atan2(p.x - centre.x);
+                    // Arc length keeps texels square on the mantle
+                    (angle * radius, p.z)
+                }
+            };
+            uvs.push((u * scale) as f32);
+            uvs.push((v * scale) as f32);
+        }
+        uvs
+    }
+}
+
+/// Planar coordinates of `p` viewed along local `axis` (0 = X, 1 = Y, 2 = Z)
+#[inline]
+fn project_along(p: &Point3<f64>, axis: usize) -> (f64, f64) {
+    match axis {
+        0 => (p.y, p.z),
+        1 => (p.x, p.z),
+        _ => (p.x, p.y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uv_mappings_follow_element_frame() {
+        // Wall face: 4 m long along X, 3 m high, 0.2 m thick along Y
+        let mut wall = Mesh::new();
+        let normal = Vector3::new(0.0, -1.0, 0.0);
+        for (x, z) in [(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)] {
+            wall.add_vertex(Point3::new(x, 0.0, z), normal);
+        }
+        wall.add_vertex(Point3::new(0.0, 0.2, 0.0), -normal);
+
+        let planar = wall.generate_uvs(&UvOptions::new(UvMapping::Planar).with_tile_size(2.0));
+        assert_eq!(planar.len(), 10);
+        assert_eq!(&planar[4..6], &[2.0, 1.5]);
+
+        let boxed = wall.generate_uvs(&UvOptions::new(UvMapping::Box));
+        assert_eq!(&boxed[2..4], &[4.0, 0.0]);
+
+        // Rotating the wall and its frame together leaves the UVs unchanged
+        let frame = Matrix4::new_rotation(Vector3::new(0.0, 0.0, 0.7))
+            .append_translation(&Vector3::new(10.0, 5.0, 0.0));
+        let mut rotated = wall.clone();
+        crate::extrusion::apply_transform(&mut rotated, &frame);
+        let uvs = rotated.generate_uvs(&UvOptions::new(UvMapping::Box).with_frame(frame));
+        for (a, b) in uvs.iter().zip(&boxed) {
+            assert!((a - b).abs() < 1e-4);
+        }
+
+        let cylinder = wall.generate_uvs(&UvOptions::new(UvMapping::Cylindrical));
+        assert_eq!(cylinder[5], 3.0);
+        assert!(Mesh::new().generate_uvs(&UvOptions::default()).is_empty());
+    }
+}
@@ -0,0 +1,426 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Mesh validation and repair
+//!
+//! Malformed IFC data and numerically unstable processing leave meshes with
+//! NaN coordinates, indices past the end of the vertex buffer, zero-area
+//! triangles and edges shared by more than two faces. [`Mesh::validate`]
+//! counts these defects without touching the mesh; [`Mesh::repair`] strips
+//! the triangles and vertices responsible so downstream consumers (GPU
+//! upload, export, CSG) can rely on a clean buffer.
+
+use crate::mesh::Mesh;
+use nalgebra::Vector3;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Vertices closer than this share a position when counting edge usage
+const WELD_TOLERANCE: f64 = 1e-6;
+
+/// Triangles whose doubled area is below this fraction of their longest
+/// squared edge are treated as degenerate (collinear or collapsed)
+const DEGENERATE_AREA_RATIO: f32 = 1e-7;
+
+/// Defects found by [`Mesh::validate`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MeshReport {
+    /// Vertices with a NaN or infinite coordinate
+    pub non_finite_vertices: usize,
+    /// Triangles referencing a vertex past the end of the positions array
+    /// (including a trailing partial triangle)
+    pub out_of_range_triangles: usize,
+    /// Triangles with repeated corners or (near) zero area
+    pub degenerate_triangles: usize,
+    /// Triangles with the same corners as an earlier triangle
+    pub duplicate_triangles: usize,
+    /// Edges shared by more than two triangles
+    pub non_manifold_edges: usize,
+    /// Largest finite absolute coordinate
+    pub max_coordinate: f32,
+}
+
+impl MeshReport {
+    /// Whether the mesh has no defects
+    pub fn is_valid(&self) -> bool {
+        self.non_finite_vertices == 0
+            && self.out_of_range_triangles == 0
+            && self.degenerate_triangles == 0
+            && self.duplicate_triangles == 0
+            && self.non_manifold_edges == 0
+    }
+}
+
+/// What [`FilterPolicy::apply`] does with a mesh that fails the checks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Exclude the mesh
+    #[default]
+    Drop,
+    /// Keep the mesh and only flag it in the [`FilterReport`]
+    Report,
+}
+
+/// Thresholds for excluding meshes placed far from the model, typically
+/// the result of a broken placement chain or a missing RTC shift
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FilterPolicy {
+    /// Vertices further than this from the origin along any axis (after
+    /// RTC shifting) are outliers
+    pub max_offset: f32,
+    /// Meshes with a larger fraction of outlier vertices are rejected
+    pub max_outlier_ratio: f32,
+    /// Meshes with any coordinate beyond `max_offset * hard_limit_factor`
+    /// are rejected regardless of the outlier ratio
+    pub hard_limit_factor: f32,
+    pub mode: FilterMode,
+}
+
+impl Default for FilterPolicy {
+    fn default() -> Self {
+        Self {
+            max_offset: 50_000.0, // 50km from RTC center
+            max_outlier_ratio: 0.9,
+            hard_limit_factor: 4.0,
+            mode: FilterMode::Drop,
+        }
+    }
+}
+
+impl FilterPolicy {
+    /// Set the outlier distance
+    pub fn with_max_offset(mut self, max_offset: f32) -> Self {
+        self.max_offset = max_offset;
+        self
+    }
+
+    /// Set the tolerated fraction of outlier vertices
+    pub fn with_max_outlier_ratio(mut self, ratio: f32) -> Self {
+        self.max_outlier_ratio = ratio;
+        self
+    }
+
+    /// Set the hard coordinate limit as a multiple of `max_offset`
+    pub fn with_hard_limit_factor(mut self, factor: f32) -> Self {
+        self.hard_limit_factor = factor;
+        self
+    }
+
+    /// Set whether rejected meshes are dropped or only reported
+    pub fn with_mode(mut self, mode: FilterMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Repair `mesh` (see [`Mesh::repair`]) and check it against the
+    /// thresholds. The caller excludes the mesh unless
+    /// [`FilterReport::keep`] is set.
+    pub fn apply(&self, mesh: &mut Mesh) -> FilterReport {
+        let outlier_ratio = mesh.outlier_ratio(self.max_offset);
+        let repair = mesh.repair();
+        let rejected = outlier_ratio > self.max_outlier_ratio
+            || repair.max_coordinate > self.max_offset * self.hard_limit_factor;
+        FilterReport {
+            repair,
+            outlier_ratio,
+            rejected,
+            keep: !rejected || self.mode == FilterMode::Report,
+        }
+    }
+}
+
+/// Outcome of [`FilterPolicy::apply`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FilterReport {
+    /// Defects of the mesh before repair
+    pub repair: MeshReport,
+    /// Fraction of vertices beyond [`FilterPolicy::max_offset`]
+    pub outlier_ratio: f32,
+    /// Whether the mesh failed the thresholds
+    pub rejected: bool,
+    /// Whether the mesh should be kept (not rejected, or report-only mode)
+    pub keep: bool,
+}
+
+/// Why a triangle is removed by [`Mesh::repair`]
+#[derive(Clone, Copy, PartialEq)]
+enum TriangleDefect {
+    OutOfRange,
+    NonFinite,
+    Degenerate,
+    Duplicate,
+}
+
+impl Mesh {
+    /// Report defects without modifying the mesh.
+    pub fn validate(&self) -> MeshReport {
+        let mut report = MeshReport::default();
+        for p in self.positions.chunks_exact(3) {
+            if p.iter().all(|c| c.is_finite()) {
+                let magnitude = p[0].abs().max(p[1].abs()).max(p[2].abs());
+                report.max_coordinate = report.max_coordinate.max(magnitude);
+            } else {
+                report.non_finite_vertices += 1;
+            }
+        }
+
+        let defects = self.triangle_defects();
+        for defect in defects.iter().flatten() {
+            match defect {
+                TriangleDefect::OutOfRange => report.out_of_range_triangles += 1,
+                TriangleDefect::Degenerate => report.degenerate_triangles += 1,
+                TriangleDefect::Duplicate => report.duplicate_triangles += 1,
+                TriangleDefect::NonFinite => {}
+            }
+        }
+        if !self.indices.len().is_multiple_of(3) {
+            report.out_of_range_triangles += 1;
+        }
+        report.non_manifold_edges = self.non_manifold_edge_count(&defects);
+        report
+    }
+
+    /// Fraction of vertices that are non-finite or further than `max_offset`
+    /// from the origin along any axis.
+    ///
+    /// Used after RTC shifting to catch meshes placed far from the model,
+    /// which usually indicates a broken placement chain.
+    pub fn outlier_ratio(&self, max_offset: f32) -> f32 {
+        let vertex_count = self.vertex_count();
+        if vertex_count == 0 {
+            return 0.0;
+        }
+        let outliers = self
+            .positions
+            .chunks_exact(3)
+            .filter(|p| !p.iter().all(|c| c.is_finite() && c.abs() <= max_offset))
+            .count();
+        outliers as f32 / vertex_count as f32
+    }
+
+    /// Remove out-of-range, non-finite, degenerate and duplicate triangles,
+    /// then drop vertices no longer referenced (which removes every NaN
+    /// vertex).
+    ///
+    /// Non-manifold edges that remain after duplicates are gone are a
+    /// property of the shape (e.g. two boxes touching along an edge) and are
+    /// kept. Returns the report of the mesh before repair.
+    pub fn repair(&mut self) -> MeshReport {
+        let report = self.validate();
+        if report.is_valid() {
+            return report;
+        }
+
+        let defects = self.triangle_defects();
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for (tri, defect) in self.indices.chunks_exact(3).zip(&defects) {
+            if defect.is_none() {
+                indices.extend_from_slice(tri);
+            }
+        }
+        self.indices = indices;
+        self.compact_vertices();
+        report
+    }
+
+    /// Per complete triangle, the first defect found (if any)
+    fn triangle_defects(&self) -> Vec<Option<TriangleDefect>> {
+        let vertex_count = self.vertex_count();
+        let position = |i: u32| {
+            let i = i as usize * 3;
+            Vector3::new(
+                self.positions[i],
+                self.positions[i + 1],
+                self.positions[i + 2],
+            )
+        };
+
+        let mut seen: FxHashSet<[u32; 3]> = FxHashSet::default();
+        self.indices
+            .chunks_exact(3)
+            .map(|tri| {
+                if tri.iter().any(|&i| i as usize >= vertex_count) {
+                    return Some(TriangleDefect::OutOfRange);
+                }
+                let (a, b, c) = (position(tri[0]), position(tri[1]), position(tri[2]));
+                if ![a, b, c].iter().all(|p| p.iter().all(|v| v.is_finite())) {
+                    return Some(TriangleDefect::NonFinite);
+                }
+                if tri[0] == tri[1] || tri[1] == tri[2] || tri[0] == tri[2] {
+                    return Some(TriangleDefect::Degenerate);
+                }
+                let longest_sq = (b - a)
+                    .norm_squared()
+                    .max((c - b).norm_squared())
+                    .max((a - c).norm_squared());
+                if (b - a).cross(&(c - a)).norm() <= DEGENERATE_AREA_RATIO * longest_sq {
+                    return Some(TriangleDefect::Degenerate);
+                }
+                let mut key = [tri[0], tri[1], tri[2]];
+                key.sort_unstable();
+                if !seen.insert(key) {
+                    return Some(TriangleDefect::Duplicate);
+                }
+                None
+            })
+            .collect()
+    }
+
+    /// Edges (by welded position) used by more than two defect-free triangles
+    fn non_manifold_edge_count(&self, defects: &[Option<TriangleDefect>]) -> usize {
+        let (_, ids) = self.position_ids(WELD_TOLERANCE);
+        let mut edge_use: FxHashMap<(u32, u32), u32> = FxHashMap::default();
+        for (tri, _) in self
+            .indices
+            .chunks_exact(3)
+            .zip(defects)
+            .filter(|(_, defect)| defect.is_none())
+        {
+            for k in 0..3 {
+                let (a, b) = (ids[tri[k] as usize], ids[tri[(k + 1) % 3] as usize]);
+                if a != b {
+                    *edge_use.entry((a.min(b), a.max(b))).or_default() += 1;
+                }
+            }
+        }
+        edge_use.values().filter(|&&n| n > 2).count()
+    }
+
+    /// Drop unreferenced vertices and renumber indices
+    fn compact_vertices(&mut self) {
+        let has_normals = self.normals.len() == self.positions.len();
+        let mut remap = vec![u32::MAX; self.vertex_count()];
+        let mut positions = Vec::with_capacity(self.positions.len());
+        let mut normals = Vec::with_capacity(self.normals.len());
+        for i in self.indices.iter_mut() {
+            let old = *i as usize;
+            if remap[old] == u32::MAX {
+                remap[old] = (positions.len() / 3) as u32;
+                positions.extend_from_slice(&self.positions[old * 3..old * 3 + 3]);
+                if has_normals {
+                    normals.extend_from_slice(&self.normals[old * 3..old * 3 + 3]);
+                }
+            }
+            *i = remap[old];
+        }
+        self.positions = positions;
+        self.normals = if has_normals { normals } else { Vec::new() };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> Mesh {
+        let mut mesh = Mesh::new();
+        for p in [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ] {
+            mesh.positions.extend_from_slice(&p);
+            mesh.normals.extend_from_slice(&[0.0, 0.0, 1.0]);
+        }
+        mesh.indices = vec![0, 1, 2, 0, 2, 3];
+        mesh
+    }
+
+    #[test]
+    fn test_clean_mesh_is_valid() {
+        let mut mesh = quad();
+        let report = mesh.validate();
+        assert!(report.is_valid(), "{report:?}");
+        assert_eq!(report.max_coordinate, 1.0);
+        assert_eq!(mesh.repair(), report);
+        assert_eq!(mesh.triangle_count(), 2);
+        assert_eq!(mesh.vertex_count(), 4);
+    }
+
+    #[test]
+    fn test_repair_strips_defects() {
+        let mut mesh = quad();
+        // NaN vertex 4, collinear vertex 5
+        mesh.positions
+            .extend_from_slice(&[f32::NAN, 0.0, 0.0, 2.0, 0.0, 0.0]);
+        mesh.normals
+            .extend_from_slice(&[0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+        mesh.indices.extend_from_slice(&[
+            0, 1, 9, // out of range
+            0, 1, 4, // NaN corner
+            0, 1, 5, // collinear
+            2, 2, 3, // repeated corner
+            2, 0, 1, // duplicate of the first triangle
+        ]);
+
+        let report = mesh.validate();
+        assert_eq!(report.non_finite_vertices, 1);
+        assert_eq!(report.out_of_range_triangles, 1);
+        assert_eq!(report.degenerate_triangles, 2);
+        assert_eq!(report.duplicate_triangles, 1);
+        assert_eq!(report.non_manifold_edges, 0);
+        assert_eq!(report.max_coordinate, 2.0);
+
+        assert_eq!(mesh.repair(), report);
+        assert!(mesh.validate().is_valid());
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.normals.len(), mesh.positions.len());
+    }
+
+    #[test]
+    fn test_non_manifold_edge() {
+        // Three triangles fanned around the edge (0,0,0)-(1,0,0)
+        let mut mesh = Mesh::new();
+        mesh.positions = vec![
+            0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.5, 1.0, 0.0, 0.5, -1.0, 0.0, 0.5, 0.0, 1.0,
+        ];
+        mesh.indices = vec![0, 1, 2, 1, 0, 3, 0, 1, 4];
+        let report = mesh.validate();
+        assert_eq!(report.non_manifold_edges, 1);
+        assert!(!report.is_valid());
+
+        // Shape-level non-manifold edges survive repair
+        mesh.repair();
+        assert_eq!(mesh.triangle_count(), 3);
+    }
+
+    #[test]
+    fn test_outlier_ratio() {
+        let mut mesh = quad();
+        assert_eq!(mesh.outlier_ratio(10.0), 0.0);
+        mesh.positions[0] = 100.0;
+        mesh.positions[4] = f32::INFINITY;
+        assert_eq!(mesh.outlier_ratio(10.0), 0.5);
+    }
+
+    #[test]
+    fn test_filter_policy() {
+        let policy = FilterPolicy::default().with_max_offset(10.0);
+        let mut mesh = quad();
+        let report = policy.apply(&mut mesh);
+        assert!(!report.rejected && report.keep);
+
+        // One far vertex: under the outlier ratio but past the hard limit
+        let mut far = quad();
+        far.positions[0] = 100.0;
+        let report = policy.apply(&mut far);
+        assert_eq!(report.outlier_ratio, 0.25);
+        assert!(report.rejected && !report.keep);
+
+        let report = policy.with_mode(FilterMode::Report).apply(&mut far);
+        assert!(report.rejected && report.keep);
+
+        // Every vertex shifted just past the offset
+        let mut shifted = quad();
+        for x in shifted.positions.iter_mut().step_by(3) {
+            *x += 15.0;
+        }
+        let report = policy.apply(&mut shifted);
+        assert_eq!(report.outlier_ratio, 1.0);
+        assert!(report.rejected);
+        let lenient = policy.with_max_outlier_ratio(1.0);
+        assert!(!lenient.apply(&mut shifted).rejected);
+    }
+}
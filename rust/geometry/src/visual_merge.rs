@@ -0,0 +1,551 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Visual merging of adjacent wall segments.
+//!
+//! Large residential models often split one straight wall run into many
+//! `IfcWall` segments. Rendering them separately costs one draw call each and
+//! leaves coincident end caps that z-fight at every joint. This optional pass
+//! groups coplanar, equally thick segments whose axes are collinear and meet
+//! end to end and that share a type/material key, concatenates them into one
+//! mesh, and drops the end caps buried inside a neighbour. An id-range table
+//! keeps per-element picking. Enable it on a router with
+//! [`GeometryRouter::set_visual_merge`](crate::GeometryRouter::set_visual_merge).
+//!
+//! The pass is purely visual: merged meshes must not be used for quantity
+//! take-off or export.
+
+use crate::Mesh;
+use nalgebra::Vector3;
+use rustc_hash::FxHashMap;
+
+/// Element mesh offered to [`merge_adjacent_walls`].
+#[derive(Debug, Clone, Copy)]
+pub struct MergeCandidate<'a> {
+    /// Express ID of the source element.
+    pub express_id: u32,
+    /// Only candidates with equal keys are merged (e.g. a hash of IFC type and material).
+    pub group_key: u64,
+    /// Element mesh in world coordinates.
+    pub mesh: &'a Mesh,
+}
+
+/// Triangle range of one source element inside a [`MergedMesh`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdRange {
+    pub express_id: u32,
+    /// First index (into `MergedMesh::mesh.indices`) belonging to the element.
+    pub index_offset: u32,
+    /// Number of indices belonging to the element.
+    pub index_count: u32,
+}
+
+/// Output of [`merge_adjacent_walls`]: one mesh per connected wall run.
+#[derive(Debug, Clone)]
+pub struct MergedMesh {
+    pub mesh: Mesh,
+    /// One entry per source element, in index-buffer order.
+    pub id_ranges: Vec<IdRange>,
+}
+
+impl MergedMesh {
+    /// Single-range copy of one candidate, as returned when nothing merges.
+    pub fn from_candidate(candidate: &MergeCandidate) -> Self {
+        Self {
+            mesh: candidate.mesh.clone(),
+            id_ranges: vec![IdRange {
+                express_id: candidate.express_id,
+                index_offset: 0,
+                index_count: candidate.mesh.indices.len() as u32,
+            }],
+        }
+    }
+
+    /// Express ID owning triangle `triangle_index`, for picking.
+    pub fn express_id_at(&self, triangle_index: u32) -> Option<u32> {
+        let index = triangle_index * 3;
+        self.id_ranges
+            .iter()
+            .find(|r| index >= r.index_offset && index < r.index_offset + r.index_count)
+            .map(|r| r.express_id)
+    }
+}
+
+/// Tolerances for [`merge_adjacent_walls`].
+#[derive(Debug, Clone, Copy)]
+pub struct VisualMergeOptions {
+    /// Maximum gap / plane offset / thickness difference, in model units.
+    pub distance_tolerance: f32,
+    /// Maximum angle between wall faces, in degrees.
+    pub angle_tolerance_deg: f32,
+}
+
+impl Default for VisualMergeOptions {
+    fn default() -> Self {
+        Self {
+            distance_tolerance: 0.005,
+            angle_tolerance_deg: 1.0,
+        }
+    }
+}
+
+/// Merge coplanar wall segments with the same group key that continue one
+/// another: same base elevation, and one segment starts where the other ends
+/// along the wall axis.
+///
+/// Every candidate appears in exactly one output, so callers can replace their
+/// input meshes with the result. Candidates that merge with nothing come back
+/// unchanged as single-range meshes.
+pub fn merge_adjacent_walls(
+    candidates: &[MergeCandidate],
+    options: &VisualMergeOptions,
+) -> Vec<MergedMesh> {
+    let frames: Vec<Option<WallFrame>> = candidates.iter().map(|c| WallFrame::of(c.mesh)).collect();
+    let cos_tol = options.angle_tolerance_deg.to_radians().cos();
+    let tol = options.distance_tolerance;
+
+    // Sweep along X inside each group, joining compatible neighbours
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by(|&a, &b| {
+        candidates[a]
+            .group_key
+            .cmp(&candidates[b].group_key)
+            .then_with(|| {
+                let ax = frames[a].as_ref().map_or(f32::MAX, |f| f.min[0]);
+                let bx = frames[b].as_ref().map_or(f32::MAX, |f| f.min[0]);
+                ax.total_cmp(&bx)
+            })
+    });
+
+    let mut sets = DisjointSet::new(candidates.len());
+    for (pos, &a) in order.iter().enumerate() {
+        let Some(fa) = &frames[a] else { continue };
+        for &b in &order[pos + 1..] {
+            if candidates[b].group_key != candidates[a].group_key {
+                break;
+            }
+            let Some(fb) = &frames[b] else { continue };
+            if fb.min[0] > fa.max[0] + tol {
+                break;
+            }
+            if fa.touches(fb, tol) && fa.coplanar_with(fb, cos_tol, tol) && fa.continues(fb, tol) {
+                sets.union(a, b);
+            }
+        }
+    }
+
+    let mut clusters: FxHashMap<usize, Vec<usize>> = FxHashMap::default();
+    for i in 0..candidates.len() {
+        clusters.entry(sets.find(i)).or_default().push(i);
+    }
+    let mut clusters: Vec<Vec<usize>> = clusters.into_values().collect();
+    for members in &mut clusters {
+        members.sort_by_key(|&i| candidates[i].express_id);
+    }
+    clusters.sort_by_key(|members| candidates[members[0]].express_id);
+
+    clusters
+        .iter()
+        .map(|members| build_merged(candidates, &frames, members, tol))
+        .collect()
+}
+
+/// Concatenate cluster members, skipping end caps buried in a neighbour.
+fn build_merged(
+    candidates: &[MergeCandidate],
+    frames: &[Option<WallFrame>],
+    members: &[usize],
+    tol: f32,
+) -> MergedMesh {
+    let mut mesh = Mesh::with_capacity(
+        members
+            .iter()
+            .map(|&i| candidates[i].mesh.vertex_count())
+            .sum(),
+        members
+            .iter()
+            .map(|&i| candidates[i].mesh.indices.len())
+            .sum(),
+    );
+    let mut id_ranges = Vec::with_capacity(members.len());
+
+    for &m in members {
+        let source = candidates[m].mesh;
+        let index_offset = mesh.indices.len() as u32;
+        let mut remap: FxHashMap<u32, u32> = FxHashMap::default();
+
+        for tri in source.indices.chunks_exact(3) {
+            if members.len() > 1 {
+                if let Some(frame) = &frames[m] {
+                    let buried = members.iter().filter(|&&o| o != m).any(|&o| {
+                        frames[o]
+                            .as_ref()
+                            .is_some_and(|other| frame.is_cap_inside(source, tri, other, tol))
+                    });
+                    if buried {
+                        continue;
+                    }
+                }
+            }
+            for &i in tri {
+                let next = (mesh.positions.len() / 3) as u32;
+                let mapped = *remap.entry(i).or_insert_with(|| {
+                    let i = i as usize * 3;
+                    mesh.positions
+                        .extend_from_slice(&source.positions[i..i + 3]);
+                    match source.normals.get(i..i + 3) {
+                        Some(n) => mesh.normals.extend_from_slice(n),
+                        None => mesh.normals.extend_from_slice(&[0.0, 0.0, 0.0]),
+                    }
+                    next
+                });
+                mesh.indices.push(mapped);
+            }
+        }
+        mesh.rtc_applied |= source.rtc_applied;
+
+        id_ranges.push(IdRange {
+            express_id: candidates[m].express_id,
+            index_offset,
+            index_count: mesh.indices.len() as u32 - index_offset,
+        });
+    }
+
+    MergedMesh { mesh, id_ranges }
+}
+
+/// Oriented extents of a wall-like mesh.
+struct WallFrame {
+    /// Dominant face normal (the wall's side faces), sign-normalised.
+    normal: Vector3<f32>,
+    /// Second axis, perpendicular to `normal` (along the wall or vertical).
+    tangent: Vector3<f32>,
+    /// `[min, max]` along `normal`, `tangent` and `normal x tangent`.
+    extents: [[f32; 2]; 3],
+    /// Horizontal direction along the wall (`normal x Z`).
+    axis: Vector3<f32>,
+    /// `[min, max]` along `axis`.
+    run: [f32; 2],
+    /// World axis-aligned bounds.
+    min: [f32; 3],
+    max: [f32; 3],
+}
+
+impl WallFrame {
+    fn of(mesh: &Mesh) -> Option<Self> {
+        if mesh.is_empty() || mesh.positions.len() < 9 {
+            return None;
+        }
+
+        // Area-weighted normal buckets; opposite faces share a bucket
+        let mut buckets: FxHashMap<[i32; 3], (Vector3<f32>, f32)> = FxHashMap::default();
+        for tri in mesh.indices.chunks_exact(3) {
+            let Some(n) = triangle_cross(mesh, tri) else {
+                continue;
+            };
+            let area = n.norm();
+            if area < 1e-12 {
+                continue;
+            }
+            let n = canonical(n / area);
+            let key = [
+                (n.x * 20.0).round() as i32,
+                (n.y * 20.0).round() as i32,
+                (n.z * 20.0).round() as i32,
+            ];
+            let entry = buckets.entry(key).or_insert((Vector3::zeros(), 0.0));
+            entry.0 += n * area;
+            entry.1 += area;
+        }
+        let mut ranked: Vec<(Vector3<f32>, f32)> = buckets.into_values().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let normal = ranked.first()?.0.try_normalize(1e-9)?;
+        let tangent = ranked
+            .iter()
+            .skip(1)
+            .filter_map(|(v, _)| {
+                let t = v - normal * v.dot(&normal);
+                t.try_normalize(1e-3)
+            })
+            .next()
+            .or_else(|| normal.cross(&Vector3::z()).try_normalize(1e-6))
+            .or_else(|| normal.cross(&Vector3::x()).try_normalize(1e-6))?;
+        let third = normal.cross(&tangent);
+        // Floors and roofs have no horizontal axis and never merge
+        let axis = normal.cross(&Vector3::z()).try_normalize(1e-3)?;
+
+        let axes = [normal, tangent, third];
+        let mut extents = [[f32::MAX, f32::MIN]; 3];
+        let mut run = [f32::MAX, f32::MIN];
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for p in mesh.positions.chunks_exact(3) {
+            let v = Vector3::new(p[0], p[1], p[2]);
+            for (e, axis) in extents.iter_mut().zip(axes.iter()) {
+                let d = v.dot(axis);
+                e[0] = e[0].min(d);
+                e[1] = e[1].max(d);
+            }
+            let d = v.dot(&axis);
+            run[0] = run[0].min(d);
+            run[1] = run[1].max(d);
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+
+        Some(Self {
+            normal,
+            tangent,
+            extents,
+            axis,
+            run,
+            min,
+            max,
+        })
+    }
+
+    fn touches(&self, other: &WallFrame, tol: f32) -> bool {
+        (0..3).all(|k| self.min[k] <= other.max[k] + tol && other.min[k] <= self.max[k] + tol)
+    }
+
+    /// Same wall plane and thickness.
+    fn coplanar_with(&self, other: &WallFrame, cos_tol: f32, tol: f32) -> bool {
+        if self.normal.dot(&other.normal) < cos_tol {
+            return false;
+        }
+        let [a_lo, a_hi] = self.extents[0];
+        let [b_lo, b_hi] = other.extents[0];
+        (a_lo - b_lo).abs() <= tol && (a_hi - b_hi).abs() <= tol
+    }
+
+    /// Collinear axes meeting end to end: equal base elevation, and one run
+    /// starts where the other ends (overlapping or gapped runs don't count).
+    fn continues(&self, other: &WallFrame, tol: f32) -> bool {
+        if (self.min[2] - other.min[2]).abs() > tol {
+            return false;
+        }
+        let [a_lo, a_hi] = self.run;
+        let [b_lo, b_hi] = if self.axis.dot(&other.axis) < 0.0 {
+            [-other.run[1], -other.run[0]]
+        } else {
+            other.run
+        };
+        (a_hi - b_lo).abs() <= tol || (b_hi - a_lo).abs() <= tol
+    }
+
+    /// Whether triangle `tri` of `mesh` (owned by `self`) is a face
+    /// perpendicular to the wall plane lying within `other`'s extents.
+    fn is_cap_inside(&self, mesh: &Mesh, tri: &[u32], other: &WallFrame, tol: f32) -> bool {
+        let Some(n) = triangle_cross(mesh, tri).and_then(|n| n.try_normalize(1e-12)) else {
+            return false;
+        };
+        if n.dot(&self.normal).abs() > 0.1 {
+            return false;
+        }
+        let centroid = tri
+            .iter()
+            .map(|&i| {
+                let i = i as usize * 3;
+                Vector3::new(
+                    mesh.positions[i],
+                    mesh.positions[i + 1],
+                    mesh.positions[i + 2],
+                )
+            })
+            .sum::<Vector3<f32>>()
+            / 3.0;
+        let axes = [
+            other.normal,
+            other.tangent,
+            other.normal.cross(&other.tangent),
+        ];
+        axes.iter()
+            .zip(other.extents.iter())
+            .all(|(axis, [lo, hi])| {
+                let d = centroid.dot(axis);
+                d >= lo - tol && d <= hi + tol
+            })
+    }
+}
+
+fn triangle_cross(mesh: &Mesh, tri: &[u32]) -> Option<Vector3<f32>> {
+    let p = |i: u32| {
+        let i = i as usize * 3;
+        mesh.positions
+            .get(i..i + 3)
+            .map(|s| Vector3::new(s[0], s[1], s[2]))
+    };
+    let (a, b, c) = (p(tri[0])?, p(tri[1])?, p(tri[2])?);
+    Some((b - a).cross(&(c - a)))
+}
+
+/// Flip `n` so its largest component is positive.
+fn canonical(n: Vector3<f32>) -> Vector3<f32> {
+    let k = n.iamax();
+    if n[k] < 0.0 {
+        -n
+    } else {
+        n
+    }
+}
+
+/// Union-find over candidate indices.
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra.max(rb)] = ra.min(rb);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::box_mesh;
+
+    #[test]
+    fn test_collinear_walls_merge_without_joint_caps() {
+        let a = box_mesh([0.0, 0.0, 0.0], [4.0, 0.2, 3.0]);
+        let b = box_mesh([4.0, 0.0, 0.0], [7.0, 0.2, 3.0]);
+        let candidates = [
+            MergeCandidate {
+                express_id: 10,
+                group_key: 1,
+                mesh: &a,
+            },
+            MergeCandidate {
+                express_id: 11,
+                group_key: 1,
+                mesh: &b,
+            },
+        ];
+
+        let merged = merge_adjacent_walls(&candidates, &VisualMergeOptions::default());
+        assert_eq!(merged.len(), 1);
+        let m = &merged[0];
+        // Two end caps (2 triangles each) at x = 4 are dropped
+        assert_eq!(m.mesh.triangle_count(), 24 - 4);
+        assert_eq!(m.id_ranges.len(), 2);
+        assert_eq!(m.id_ranges[0].index_count, 30);
+        assert_eq!(m.express_id_at(0), Some(10));
+        assert_eq!(m.express_id_at(19), Some(11));
+    }
+
+    #[test]
+    fn test_walls_not_merged_across_keys_or_corners() {
+        let a = box_mesh([0.0, 0.0, 0.0], [4.0, 0.2, 3.0]);
+        let b = box_mesh([4.0, 0.0, 0.0], [7.0, 0.2, 3.0]);
+        let corner = box_mesh([3.8, 0.0, 0.0], [4.0, 5.0, 3.0]);
+
+        let different_keys = [
+            MergeCandidate {
+                express_id: 1,
+                group_key: 1,
+                mesh: &a,
+            },
+            MergeCandidate {
+                express_id: 2,
+                group_key: 2,
+                mesh: &b,
+            },
+        ];
+        assert_eq!(
+            merge_adjacent_walls(&different_keys, &VisualMergeOptions::default()).len(),
+            2
+        );
+
+        let perpendicular = [
+            MergeCandidate {
+                express_id: 1,
+                group_key: 1,
+                mesh: &a,
+            },
+            MergeCandidate {
+                express_id: 3,
+                group_key: 1,
+                mesh: &corner,
+            },
+        ];
+        let merged = merge_adjacent_walls(&perpendicular, &VisualMergeOptions::default());
+        assert_eq!(merged.len(), 2);
+        assert!(merged.iter().all(|m| m.mesh.triangle_count() == 12));
+    }
+
+    #[test]
+    fn test_walls_not_merged_unless_end_to_end() {
+        let a = box_mesh([0.0, 0.0, 0.0], [4.0, 0.2, 3.0]);
+        // Same plane and thickness, but overlapping, stacked or offset along the axis
+        let overlapping = box_mesh([2.0, 0.0, 0.0], [6.0, 0.2, 3.0]);
+        let stacked = box_mesh([0.0, 0.0, 3.0], [4.0, 0.2, 6.0]);
+        let raised = box_mesh([4.0, 0.0, 1.0], [7.0, 0.2, 4.0]);
+
+        for other in [&overlapping, &stacked, &raised] {
+            let candidates = [
+                MergeCandidate {
+                    express_id: 1,
+                    group_key: 1,
+                    mesh: &a,
+                },
+                MergeCandidate {
+                    express_id: 2,
+                    group_key: 1,
+                    mesh: other,
+                },
+            ];
+            let merged = merge_adjacent_walls(&candidates, &VisualMergeOptions::default());
+            assert_eq!(merged.len(), 2);
+            assert!(merged.iter().all(|m| m.mesh.triangle_count() == 12));
+        }
+    }
+
+    #[test]
+    fn test_router_merges_walls_only_when_enabled() {
+        let a = box_mesh([0.0, 0.0, 0.0], [4.0, 0.2, 3.0]);
+        let b = box_mesh([4.0, 0.0, 0.0], [7.0, 0.2, 3.0]);
+        let candidates = [
+            MergeCandidate {
+                express_id: 10,
+                group_key: 1,
+                mesh: &a,
+            },
+            MergeCandidate {
+                express_id: 11,
+                group_key: 1,
+                mesh: &b,
+            },
+        ];
+
+        let mut router = crate::GeometryRouter::new();
+        let separate = router.merge_wall_meshes(&candidates);
+        assert_eq!(separate.len(), 2);
+        assert_eq!(separate[1].express_id_at(11), Some(11));
+
+        router.set_visual_merge(Some(VisualMergeOptions::default()));
+        let merged = router.merge_wall_meshes(&candidates);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].mesh.triangle_count(), 20);
+    }
+}
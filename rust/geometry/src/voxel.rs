@@ -0,0 +1,529 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Voxelization of element meshes into an occupancy grid
+//!
+//! Every triangle marks the voxels its surface passes through (exact
+//! triangle/box overlap). With interior filling, each element is then
+//! filled column by column: a vertical ray through the column centre
+//! crosses the element's surface an even number of times on a closed mesh,
+//! and voxels between entry and exit crossings are inside. Columns with an
+//! odd crossing count (open meshes) keep only their surface voxels.
+//!
+//! The grid optionally records which elements occupy each voxel, which
+//! makes it usable as an approximate clash pre-filter
+//! ([`VoxelGrid::candidate_pairs`]) next to volume checks and buffer zones
+//! ([`VoxelGrid::dilate`]).
+
+use crate::bounds::Aabb;
+use crate::error::{Error, Result};
+use crate::mesh::Mesh;
+use nalgebra::{Point3, Vector3};
+use rustc_hash::FxHashMap;
+
+/// Grids with more voxels than this are rejected (16 MiB of occupancy bits)
+pub const MAX_VOXELS: usize = 1 << 27;
+
+/// Column rays are nudged off voxel centres by this fraction of the voxel
+/// size so they don't pass exactly through mesh edges and vertices
+const RAY_JITTER: [f64; 2] = [1.234_567e-6, 2.345_678e-6];
+
+/// Voxelization settings
+#[derive(Debug, Clone, Copy)]
+pub struct VoxelOptions {
+    /// Edge length of a voxel (mesh units)
+    pub voxel_size: f64,
+    /// Fill the inside of closed meshes, not just their surface
+    pub fill_interior: bool,
+    /// Record the express IDs occupying each voxel
+    pub track_elements: bool,
+}
+
+impl Default for VoxelOptions {
+    fn default() -> Self {
+        Self {
+            voxel_size: 0.5,
+            fill_interior: true,
+            track_elements: false,
+        }
+    }
+}
+
+impl VoxelOptions {
+    /// Solid voxelization at the given voxel size
+    pub fn new(voxel_size: f64) -> Self {
+        Self {
+            voxel_size,
+            ..Self::default()
+        }
+    }
+
+    /// Enable or disable interior filling
+    pub fn with_interior(mut self, fill_interior: bool) -> Self {
+        self.fill_interior = fill_interior;
+        self
+    }
+
+    /// Enable or disable per-voxel express ID sets
+    pub fn with_element_ids(mut self, track_elements: bool) -> Self {
+        self.track_elements = track_elements;
+        self
+    }
+}
+
+/// Occupancy grid over a model, indexed by `[x, y, z]` voxel coordinates
+#[derive(Debug, Clone)]
+pub struct VoxelGrid {
+    origin: Point3<f64>,
+    voxel_size: f64,
+    dims: [usize; 3],
+    occupied: Vec<u64>,
+    /// Sorted express IDs per occupied voxel, when tracked
+    elements: Option<FxHashMap<usize, Vec<u32>>>,
+}
+
+/// Voxelize element meshes into a solid occupancy grid
+pub fn voxelize<'a>(
+    meshes: impl IntoIterator<Item = (u32, &'a Mesh)>,
+    voxel_size: f64,
+) -> Result<VoxelGrid> {
+    voxelize_with_options(meshes, &VoxelOptions::new(voxel_size))
+}
+
+/// Voxelize element meshes with explicit options.
+///
+/// The grid covers the union of the meshes' bounding boxes. Fails for a
+/// non-positive voxel size or a grid larger than [`MAX_VOXELS`].
+pub fn voxelize_with_options<'a>(
+    meshes: impl IntoIterator<Item = (u32, &'a Mesh)>,
+    options: &VoxelOptions,
+) -> Result<VoxelGrid> {
+    let size = options.voxel_size;
+    if !(size.is_finite() && size > 0.0) {
+        return Err(Error::geometry(format!("Invalid voxel size {}", size)));
+    }
+
+    let meshes: Vec<(u32, &Mesh)> = meshes.into_iter().collect();
+    let bounds = meshes
+        .iter()
+        .filter_map(|(_, mesh)| Aabb::from_mesh(mesh))
+        .reduce(|a, b| a.union(&b));
+    let Some(bounds) = bounds else {
+        return Ok(VoxelGrid::empty(size, options));
+    };
+
+    let extent = bounds.size();
+    let dims = [0, 1, 2].map(|axis| ((extent[axis] / size).ceil() as usize).max(1));
+    let count = dims
+        .iter()
+        .try_fold(1usize, |acc, &d| acc.checked_mul(d))
+        .filter(|&count| count <= MAX_VOXELS)
+        .ok_or_else(|| {
+            Error::geometry(format!(
+                "Voxel grid {}x{}x{} exceeds {} voxels",
+                dims[0], dims[1], dims[2], MAX_VOXELS
+            ))
+        })?;
+
+    let mut grid = VoxelGrid {
+        origin: bounds.min,
+        voxel_size: size,
+        dims,
+        occupied: vec![0; count.div_ceil(64)],
+        elements: options.track_elements.then(FxHashMap::default),
+    };
+
+    for (express_id, mesh) in meshes {
+        let mut voxels = grid.surface_voxels(mesh, options.fill_interior);
+        if options.fill_interior {
+            voxels.extend(grid.interior_voxels(mesh));
+        }
+        voxels.sort_unstable();
+        voxels.dedup();
+        for index in voxels {
+            grid.occupied[index / 64] |= 1 << (index % 64);
+            if let Some(elements) = grid.elements.as_mut() {
+                elements.entry(index).or_default().push(express_id);
+            }
+        }
+    }
+
+    if let Some(elements) = grid.elements.as_mut() {
+        for ids in elements.values_mut() {
+            ids.sort_unstable();
+            ids.dedup();
+        }
+    }
+    Ok(grid)
+}
+
+impl VoxelGrid {
+    fn empty(voxel_size: f64, options: &VoxelOptions) -> Self {
+        Self {
+            origin: Point3::origin(),
+            voxel_size,
+            dims: [0; 3],
+            occupied: Vec::new(),
+            elements: options.track_elements.then(FxHashMap::default),
+        }
+    }
+
+    /// Minimum corner of voxel `[0, 0, 0]`
+    pub fn origin(&self) -> Point3<f64> {
+        self.origin
+    }
+
+    pub fn voxel_size(&self) -> f64 {
+        self.voxel_size
+    }
+
+    /// Voxel counts along x, y and z
+    pub fn dims(&self) -> [usize; 3] {
+        self.dims
+    }
+
+    /// Voxel containing `point`, `None` outside the grid
+    pub fn voxel_at(&self, point: &Point3<f64>) -> Option<[usize; 3]> {
+        let mut voxel = [0; 3];
+        for axis in 0..3 {
+            let t = ((point[axis] - self.origin[axis]) / self.voxel_size).floor();
+            if !(t >= 0.0 && t < self.dims[axis] as f64) {
+                return None;
+            }
+            voxel[axis] = t as usize;
+        }
+        Some(voxel)
+    }
+
+    /// Centre of a voxel
+    pub fn center(&self, voxel: [usize; 3]) -> Point3<f64> {
+        self.origin + Vector3::from(voxel.map(|i| (i as f64 + 0.5) * self.voxel_size))
+    }
+
+    /// Whether a voxel is occupied (false outside the grid)
+    pub fn is_occupied(&self, voxel: [usize; 3]) -> bool {
+        self.index(voxel)
+            .is_some_and(|index| self.occupied[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    /// Number of occupied voxels
+    pub fn occupied_count(&self) -> usize {
+        self.occupied
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Total volume of occupied voxels
+    pub fn occupied_volume(&self) -> f64 {
+        self.occupied_count() as f64 * self.voxel_size.powi(3)
+    }
+
+    /// Coordinates of every occupied voxel, in x-fastest order
+    pub fn occupied_voxels(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.occupied
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, &word)| {
+                (0..64)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .map(move |bit| word_index * 64 + bit)
+            })
+            .map(|index| self.coords(index))
+    }
+
+    /// Express IDs occupying a voxel (empty unless element tracking is on)
+    pub fn elements_at(&self, voxel: [usize; 3]) -> &[u32] {
+        self.index(voxel)
+            .and_then(|index| self.elements.as_ref()?.get(&index))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Voxel volume per element (empty unless element tracking is on)
+    pub fn element_volumes(&self) -> FxHashMap<u32, f64> {
+        let voxel_volume = self.voxel_size.powi(3);
+        let mut volumes: FxHashMap<u32, f64> = FxHashMap::default();
+        for ids in self.elements.iter().flat_map(|e| e.values()) {
+            for &id in ids {
+                *volumes.entry(id).or_default() += voxel_volume;
+            }
+        }
+        volumes
+    }
+
+    /// Element pairs sharing at least one voxel, sorted with `a < b`.
+    ///
+    /// A conservative clash pre-filter at voxel resolution: touching
+    /// elements also share voxels, so pairs still need an exact test.
+    pub fn candidate_pairs(&self) -> Vec<(u32, u32)> {
+        let mut pairs: Vec<(u32, u32)> = self
+            .elements
+            .iter()
+            .flat_map(|e| e.values())
+            .filter(|ids| ids.len() > 1)
+            .flat_map(|ids| {
+                ids.iter()
+                    .enumerate()
+                    .flat_map(move |(i, &a)| ids[i + 1..].iter().map(move |&b| (a, b)))
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    /// Grid grown by `distance` around every occupied voxel (e.g. clearance
+    /// or accessibility zones). The result covers the enlarged extent and
+    /// does not track elements.
+    pub fn dilate(&self, distance: f64) -> Result<VoxelGrid> {
+        let radius = (distance.max(0.0) / self.voxel_size).ceil() as usize;
+        let dims = self.dims.map(|d| if d == 0 { 0 } else { d + 2 * radius });
+        let count = dims
+            .iter()
+            .try_fold(1usize, |acc, &d| acc.checked_mul(d))
+            .filter(|&count| count <= MAX_VOXELS)
+            .ok_or_else(|| Error::geometry("Dilated voxel grid is too large".to_string()))?;
+
+        let mut grid = VoxelGrid {
+            origin: self.origin - Vector3::repeat(radius as f64 * self.voxel_size),
+            voxel_size: self.voxel_size,
+            dims,
+            occupied: vec![0; count.div_ceil(64)],
+            elements: None,
+        };
+
+        // Spherical structuring element, measured between voxel centres
+        let r = radius as i64;
+        let offsets: Vec<[i64; 3]> = (-r..=r)
+            .flat_map(|x| (-r..=r).flat_map(move |y| (-r..=r).map(move |z| [x, y, z])))
+            .filter(|o| o.iter().map(|v| v * v).sum::<i64>() <= r * r)
+            .collect();
+        for voxel in self.occupied_voxels() {
+            for offset in &offsets {
+                let shifted =
+                    [0, 1, 2].map(|axis| (voxel[axis] as i64 + r + offset[axis]) as usize);
+                let index = grid.index(shifted).expect("dilated voxel inside grid");
+                grid.occupied[index / 64] |= 1 << (index % 64);
+            }
+        }
+        Ok(grid)
+    }
+
+    fn index(&self, voxel: [usize; 3]) -> Option<usize> {
+        (0..3)
+            .all(|axis| voxel[axis] < self.dims[axis])
+            .then(|| voxel[0] + self.dims[0] * (voxel[1] + self.dims[1] * voxel[2]))
+    }
+
+    fn coords(&self, index: usize) -> [usize; 3] {
+        let plane = self.dims[0] * self.dims[1];
+        [
+            index % self.dims[0],
+            (index % plane) / self.dims[0],
+            index / plane,
+        ]
+    }
+
+    /// Voxel range `[lo, hi]` (inclusive) covering a box, clamped to the grid
+    fn voxel_range(&self, min: &Point3<f64>, max: &Point3<f64>) -> ([usize; 3], [usize; 3]) {
+        let clamp = |axis: usize, value: f64| {
+            let t = ((value - self.origin[axis]) / self.voxel_size).floor();
+            (t.max(0.0) as usize).min(self.dims[axis] - 1)
+        };
+        (
+            [0, 1, 2].map(|axis| clamp(axis, min[axis])),
+            [0, 1, 2].map(|axis| clamp(axis, max[axis])),
+        )
+    }
+
+    /// Voxels crossed by the mesh surface.
+    ///
+    /// A face lying exactly on a voxel boundary touches the voxels on both
+    /// sides. When interiors are filled, voxels are shrunk slightly so such
+    /// faces mark neither side and the inner one comes from the fill,
+    /// keeping volumes of grid-aligned solids exact.
+    fn surface_voxels(&self, mesh: &Mesh, shrink: bool) -> Vec<usize> {
+        let scale = if shrink { 1.0 - 1e-9 } else { 1.0 };
+        let half = Vector3::repeat(0.5 * self.voxel_size * scale);
+        let mut voxels = Vec::new();
+        for tri in triangles(mesh) {
+            let min = tri[0].inf(&tri[1]).inf(&tri[2]);
+            let max = tri[0].sup(&tri[1]).sup(&tri[2]);
+            let (lo, hi) = self.voxel_range(&min, &max);
+            for z in lo[2]..=hi[2] {
+                for y in lo[1]..=hi[1] {
+                    for x in lo[0]..=hi[0] {
+                        let voxel = [x, y, z];
+                        if triangle_box_overlap(&tri, &self.center(voxel), &half) {
+                            voxels.extend(self.index(voxel));
+                        }
+                    }
+                }
+            }
+        }
+        voxels
+    }
+
+    /// Voxels whose centre lies inside the mesh, by vertical ray parity
+    fn interior_voxels(&self, mesh: &Mesh) -> Vec<usize> {
+        let jitter = RAY_JITTER.map(|j| j * self.voxel_size);
+        let mut columns: FxHashMap<(usize, usize), Vec<f64>> = FxHashMap::default();
+        for tri in triangles(mesh) {
+            let min = tri[0].inf(&tri[1]).inf(&tri[2]);
+            let max = tri[0].sup(&tri[1]).sup(&tri[2]);
+            let (lo, hi) = self.voxel_range(&min, &max);
+            for y in lo[1]..=hi[1] {
+                for x in lo[0]..=hi[0] {
+                    let center = self.center([x, y, 0]);
+                    let (px, py) = (center.x + jitter[0], center.y + jitter[1]);
+                    if let Some(z) = vertical_crossing(&tri, px, py) {
+                        columns.entry((x, y)).or_default().push(z);
+                    }
+                }
+            }
+        }
+
+        let mut voxels = Vec::new();
+        for ((x, y), mut crossings) in columns {
+            if crossings.len() % 2 != 0 {
+                continue;
+            }
+            crossings.sort_by(f64::total_cmp);
+            for pair in crossings.chunks_exact(2) {
+                let first = ((pair[0] - self.origin.z) / self.voxel_size - 0.5).ceil();
+                let last = ((pair[1] - self.origin.z) / self.voxel_size - 0.5).floor();
+                let first = first.max(0.0) as usize;
+                let last = last.min(self.dims[2] as f64 - 1.0);
+                if last < first as f64 {
+                    continue;
+                }
+                for z in first..=last as usize {
+                    voxels.extend(self.index([x, y, z]));
+                }
+            }
+        }
+        voxels
+    }
+}
+
+/// Triangles of a mesh as f64 points, skipping out-of-range indices
+fn triangles(mesh: &Mesh) -> impl Iterator<Item = [Point3<f64>; 3]> + '_ {
+    let vertex_count = mesh.vertex_count();
+    mesh.indices.chunks_exact(3).filter_map(move |tri| {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            return None;
+        }
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            Point3::new(
+                mesh.positions[i] as f64,
+                mesh.positions[i + 1] as f64,
+                mesh.positions[i + 2] as f64,
+            )
+        };
+        let tri = [p(tri[0]), p(tri[1]), p(tri[2])];
+        tri.iter()
+            .all(|p| p.iter().all(|c| c.is_finite()))
+            .then_some(tri)
+    })
+}
+
+/// Height at which the vertical line through `(x, y)` crosses a triangle
+fn vertical_crossing(tri: &[Point3<f64>; 3], x: f64, y: f64) -> Option<f64> {
+    let [a, b, c] = tri;
+    let det = (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y);
+    if det.abs() < 1e-18 {
+        return None;
+    }
+    let u = ((x - a.x) * (c.y - a.y) - (c.x - a.x) * (y - a.y)) / det;
+    let v = ((b.x - a.x) * (y - a.y) - (x - a.x) * (b.y - a.y)) / det;
+    (u >= 0.0 && v >= 0.0 && u + v <= 1.0).then(|| a.z + u * (b.z - a.z) + v * (c.z - a.z))
+}
+
+/// Separating axis test between a triangle and an axis-aligned box
+fn triangle_box_overlap(tri: &[Point3<f64>; 3], center: &Point3<f64>, half: &Vector3<f64>) -> bool {
+    let v = tri.map(|p| p - center);
+    let edges = [v[1] - v[0], v[2] - v[1], v[0] - v[2]];
+    let separated = |axis: Vector3<f64>| {
+        let projections = v.map(|p| p.dot(&axis));
+        let min = projections.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = projections
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let r = half.x * axis.x.abs() + half.y * axis.y.abs() + half.z * axis.z.abs();
+        min > r || max < -r
+    };
+
+    let box_axes = [Vector3::x(), Vector3::y(), Vector3::z()];
+    if box_axes.iter().any(|&axis| separated(axis)) {
+        return false;
+    }
+    let normal = edges[0].cross(&edges[1]);
+    if normal.norm_squared() > 0.0 && separated(normal) {
+        return false;
+    }
+    !edges.iter().any(|edge| {
+        box_axes.iter().any(|box_axis| {
+            let axis = edge.cross(box_axis);
+            axis.norm_squared() > 1e-24 && separated(axis)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::box_mesh;
+
+    #[test]
+    fn test_solid_box_volume() {
+        let mesh = box_mesh([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+        let grid = voxelize([(1, &mesh)], 0.25).unwrap();
+        assert_eq!(grid.dims(), [4, 4, 4]);
+        assert_eq!(grid.occupied_count(), 64);
+        assert!((grid.occupied_volume() - 1.0).abs() < 1e-12);
+
+        // Surface only: the 2x2x2 core stays empty
+        let options = VoxelOptions::new(0.25).with_interior(false);
+        let shell = voxelize_with_options([(1, &mesh)], &options).unwrap();
+        assert_eq!(shell.occupied_count(), 64 - 8);
+        assert!(!shell.is_occupied([1, 1, 1]));
+        assert_eq!(shell.voxel_at(&Point3::new(0.6, 0.1, 0.9)), Some([2, 0, 3]));
+    }
+
+    #[test]
+    fn test_element_ids_and_candidate_pairs() {
+        let a = box_mesh([0.0, 0.0, 0.0], [2.0, 1.0, 1.0]);
+        let b = box_mesh([1.5, 0.0, 0.0], [3.0, 1.0, 1.0]);
+        let c = box_mesh([0.0, 3.0, 0.0], [1.0, 4.0, 1.0]);
+        let options = VoxelOptions::new(0.5).with_element_ids(true);
+        let grid = voxelize_with_options([(10, &a), (20, &b), (30, &c)], &options).unwrap();
+
+        assert_eq!(grid.candidate_pairs(), vec![(10, 20)]);
+        let shared = grid.voxel_at(&Point3::new(1.75, 0.25, 0.25)).unwrap();
+        assert_eq!(grid.elements_at(shared), &[10, 20]);
+        let volumes = grid.element_volumes();
+        assert!((volumes[&10] - 2.0).abs() < 1e-12);
+        assert!((volumes[&30] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dilate_and_limits() {
+        let mesh = box_mesh([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+        let grid = voxelize([(1, &mesh)], 1.0).unwrap();
+        assert_eq!(grid.occupied_count(), 1);
+
+        // One voxel grown by one voxel: the centre plus its 6 face neighbours
+        let zone = grid.dilate(1.0).unwrap();
+        assert_eq!(zone.dims(), [3, 3, 3]);
+        assert_eq!(zone.occupied_count(), 7);
+        assert!(zone.is_occupied([1, 1, 1]) && zone.is_occupied([0, 1, 1]));
+        assert!(!zone.is_occupied([0, 0, 0]));
+
+        assert!(voxelize([(1, &mesh)], 0.0).is_err());
+        assert!(voxelize([(1, &mesh)], 1e-4).is_err());
+        assert_eq!(voxelize([], 1.0).unwrap().occupied_count(), 0);
+    }
+}
@@ -139,7 +139,7 @@ fn test_ifc_with_materials_does_not_crash() {
     assert!(!entity_index.is_empty(), "Entity index should not be empty");
 
     // Create decoder
-    let mut decoder = EntityDecoder::with_index(content, entity_index.into());
+    let mut decoder = EntityDecoder::with_index(content, entity_index);
 
     // Create geometry router
     let mut router = GeometryRouter::with_units(content, &mut decoder);
@@ -206,7 +206,7 @@ fn test_styled_item_parsing() {
 
     // Build entity index
     let entity_index = build_entity_index(content);
-    let mut decoder = EntityDecoder::with_index(content, entity_index.into());
+    let mut decoder = EntityDecoder::with_index(content, entity_index);
 
     // Decode IFCSURFACESTYLERENDERING with typed values
     let rendering = decoder
@@ -11,11 +11,12 @@ mod processor;
 mod types;
 
 pub use processor::{
-    process_geometry, process_geometry_filtered, process_geometry_streaming,
+    process_geometry, process_geometry_filtered, process_geometry_filtered_with_tessellation,
+    process_geometry_streaming,
     process_geometry_streaming_filtered, process_geometry_streaming_filtered_with_options,
     process_geometry_streaming_with_options,
     process_geometry_streaming_with_options_and_bootstrap,
-    OpeningFilterMode, ProcessingResult, StreamingOptions,
+    OpeningFilterMode, ProcessingResult, StreamingOptions, TessellationQuality,
 };
 pub use types::mesh::MeshData;
 pub use types::response::{
@@ -15,14 +15,14 @@ use ifc_lite_core::{
     build_entity_index, AttributeValue, DecodedEntity, EntityDecoder, EntityIndex,
     EntityScanner, IfcType,
 };
-use ifc_lite_geometry::{calculate_normals, GeometryRouter};
+use ifc_lite_geometry::{calculate_normals, GeometryRouter, Mesh, TessellationConfig};
 use rayon::prelude::*;
 use rustc_hash::FxHashMap;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 /// Controls how IfcWindow / IfcDoor openings are exported.
-#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum OpeningFilterMode {
     /// Export all openings and cut their voids in host walls (default behaviour).
@@ -46,6 +46,39 @@ impl OpeningFilterMode {
     }
 }
 
+/// Curve and surface tessellation preset (see [`TessellationConfig`]).
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TessellationQuality {
+    /// Coarse circles and curves for fast loading of large models.
+    Fast,
+    /// Balanced quality for interactive viewing.
+    #[default]
+    Default,
+    /// Fine tessellation for export and close-up rendering.
+    HighQuality,
+}
+
+impl TessellationQuality {
+    /// Tessellation settings for this preset.
+    pub fn config(&self) -> TessellationConfig {
+        match self {
+            Self::Fast => TessellationConfig::fast(),
+            Self::Default => TessellationConfig::default(),
+            Self::HighQuality => TessellationConfig::high_quality(),
+        }
+    }
+
+    /// Stable string suffix for disk-cache keys.
+    pub fn cache_key_suffix(&self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Default => "default",
+            Self::HighQuality => "high_quality",
+        }
+    }
+}
+
 /// Result of processing an IFC file.
 pub struct ProcessingResult {
     pub meshes: Vec<MeshData>,
@@ -76,6 +109,8 @@ pub struct StreamingOptions {
     pub emit_quick_metadata_bootstrap: bool,
     /// Retain emitted meshes in the returned ProcessingResult.
     pub retain_emitted_meshes: bool,
+    /// Curve and surface tessellation quality.
+    pub tessellation: TessellationConfig,
 }
 
 impl Default for StreamingOptions {
@@ -88,6 +123,7 @@ impl Default for StreamingOptions {
             include_presentation_layers: true,
             emit_quick_metadata_bootstrap: false,
             retain_emitted_meshes: true,
+            tessellation: TessellationConfig::default(),
         }
     }
 }
@@ -722,12 +758,26 @@ pub fn process_geometry_streaming_with_options_and_bootstrap(
 
 /// Process IFC content with parallel geometry extraction and a configurable opening filter.
 pub fn process_geometry_filtered(content: &str, opening_filter: OpeningFilterMode) -> ProcessingResult {
+    process_geometry_filtered_with_tessellation(
+        content,
+        opening_filter,
+        TessellationConfig::default(),
+    )
+}
+
+/// Process IFC content with a configurable opening filter and tessellation quality.
+pub fn process_geometry_filtered_with_tessellation(
+    content: &str,
+    opening_filter: OpeningFilterMode,
+    tessellation: TessellationConfig,
+) -> ProcessingResult {
     process_geometry_streaming_filtered_with_options(
         content,
         opening_filter,
         StreamingOptions {
             initial_batch_size: usize::MAX,
             throughput_batch_size: usize::MAX,
+            tessellation,
             ..StreamingOptions::default()
         },
         |_, _, _| {},
@@ -1062,6 +1112,7 @@ pub fn process_geometry_streaming_filtered_with_options(
     // Preprocess complex geometry
     let preprocess_start = std::time::Instant::now();
     let mut router = GeometryRouter::with_units(content, &mut decoder);
+    router.set_tessellation(options.tessellation);
 
     // Resolve IfcSite and IfcBuilding placement transforms.
     // The Site placement translation is used as the RTC offset so that mesh
@@ -1111,8 +1162,9 @@ pub fn process_geometry_streaming_filtered_with_options(
     // PARALLEL GEOMETRY PROCESSING
     let geometry_start = std::time::Instant::now();
     let entity_index_arc = entity_index; // Already Arc from above
-    let unit_scale = router.unit_scale();
-    let rtc_offset = router.rtc_offset();
+    // One router serves every worker, so its caches (mapped items, content
+    // hashes, preprocessed FacetedBreps) are shared across the pool
+    let router = &router;
     let void_index_arc = Arc::new(filtered_void_index);
     let skipped_entity_ids = Arc::new(skipped_entity_ids);
     let mut geometry_style_index = Arc::new(geometry_style_index);
@@ -1219,8 +1271,7 @@ pub fn process_geometry_streaming_filtered_with_options(
                     job,
                     content,
                     &entity_index_arc,
-                    unit_scale,
-                    rtc_offset,
+                    router,
                     void_index_arc.as_ref(),
                     skipped_entity_ids.as_ref(),
                     geometry_style_index.as_ref(),
@@ -1319,8 +1370,7 @@ fn process_entity_job(
     job: &EntityJob,
     content: &str,
     entity_index_arc: &Arc<EntityIndex>,
-    unit_scale: f64,
-    rtc_offset: (f64, f64, f64),
+    router: &GeometryRouter,
     void_index: &FxHashMap<u32, Vec<u32>>,
     skipped_entity_ids: &HashSet<u32>,
     geometry_style_index: &FxHashMap<u32, GeometryStyleInfo>,
@@ -1342,7 +1392,6 @@ fn process_entity_job(
         return Vec::new();
     }
 
-    let local_router = GeometryRouter::with_scale_and_rtc(unit_scale, rtc_offset);
     let global_id = job.global_id.clone();
     let name = job.name.clone();
     let presentation_layer = job.presentation_layer.clone();
@@ -1350,7 +1399,7 @@ fn process_entity_job(
     let element_color = job.element_color;
 
     if is_opening_with_subparts(&job.ifc_type) {
-        if let Ok(sub_meshes) = local_router.process_element_with_submeshes(&entity, &mut local_decoder) {
+        if let Ok(sub_meshes) = router.process_element_with_submeshes(&entity, &mut local_decoder) {
             if !sub_meshes.is_empty() {
                 let mut out: Vec<MeshData> = Vec::with_capacity(sub_meshes.len());
 
@@ -1360,6 +1409,9 @@ fn process_entity_job(
                         continue;
                     }
 
+                    if !filter_mesh(router, job, &mut sub_mesh) {
+                        continue;
+                    }
                     if sub_mesh.normals.is_empty() {
                         calculate_normals(&mut sub_mesh);
                     }
@@ -1395,7 +1447,7 @@ fn process_entity_job(
         }
     }
 
-    let mut mesh_candidate = local_router
+    let mut mesh_candidate = router
         .process_element_with_voids(&entity, &mut local_decoder, void_index)
         .ok();
     let needs_fallback = match mesh_candidate.as_ref() {
@@ -1403,11 +1455,11 @@ fn process_entity_job(
         None => true,
     };
     if needs_fallback {
-        mesh_candidate = local_router.process_element(&entity, &mut local_decoder).ok();
+        mesh_candidate = router.process_element(&entity, &mut local_decoder).ok();
     }
 
     if let Some(mut mesh) = mesh_candidate {
-        if !mesh.is_empty() {
+        if !mesh.is_empty() && filter_mesh(router, job, &mut mesh) {
             if mesh.normals.is_empty() {
                 calculate_normals(&mut mesh);
             }
@@ -1430,6 +1482,23 @@ fn process_entity_job(
     Vec::new()
 }
 
+/// Apply the router's filter policy, logging rejected meshes. Returns
+/// whether the mesh should be kept.
+fn filter_mesh(router: &GeometryRouter, job: &EntityJob, mesh: &mut Mesh) -> bool {
+    let report = router.filter_mesh(mesh);
+    if report.rejected {
+        tracing::warn!(
+            id = job.id,
+            ifc_type = job.ifc_type.name(),
+            outlier_ratio = report.outlier_ratio,
+            max_coordinate = report.repair.max_coordinate,
+            kept = report.keep,
+            "Mesh outside the filter policy"
+        );
+    }
+    report.keep
+}
+
 fn collect_geometry_style_info(
     geometry_styles: &mut FxHashMap<u32, GeometryStyleInfo>,
     styled_item: &DecodedEntity,
@@ -0,0 +1,133 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Cancellation of the async parse methods
+//!
+//! `parseMeshesAsync` and `parseToGpuGeometryAsync` accept an AbortSignal
+//! as `signal` option. Batches are normally produced without yielding, so
+//! with a signal the parser yields to the event loop after each batch; that
+//! lets the page react (e.g. to the user dropping another file) and abort
+//! before the next batch starts.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+/// The `signal` option of an async parse
+pub(crate) struct ParseAbort {
+    signal: Option<JsValue>,
+}
+
+impl ParseAbort {
+    /// Read `options.signal`; absent or null means the parse can't be aborted
+    pub(crate) fn from_options(options: &JsValue) -> Self {
+        let signal = js_sys::Reflect::get(options, &"signal".into())
+            .ok()
+            .filter(|signal| signal.is_object());
+        Self { signal }
+    }
+
+    /// Whether the signal has been aborted, without yielding
+    pub(crate) fn is_aborted(&self) -> bool {
+        self.signal.as_ref().is_some_and(|signal| {
+            js_sys::Reflect::get(signal, &"aborted".into())
+                .ok()
+                .and_then(|aborted| aborted.as_bool())
+                .unwrap_or(false)
+        })
+    }
+
+    /// Yield to the event loop so the signal can fire, then check it. A
+    /// no-op returning `false` without a signal.
+    pub(crate) async fn checkpoint(&self) -> bool {
+        if self.signal.is_none() {
+            return false;
+        }
+        yield_to_event_loop().await;
+        self.is_aborted()
+    }
+
+    /// Value to reject the parse promise with: the signal's `reason`, or an
+    /// `AbortError` for signals without one
+    pub(crate) fn reason(&self) -> JsValue {
+        if let Some(reason) = self
+            .signal
+            .as_ref()
+            .and_then(|signal| js_sys::Reflect::get(signal, &"reason".into()).ok())
+            .filter(|reason| !reason.is_undefined())
+        {
+            return reason;
+        }
+        let error = js_sys::Error::new("The parse was aborted");
+        error.set_name("AbortError");
+        error.into()
+    }
+}
+
+/// Resolve on the next macrotask via the global `setTimeout`, which exists
+/// both on pages and in workers
+async fn yield_to_event_loop() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let set_timeout = js_sys::Reflect::get(&js_sys::global(), &"setTimeout".into())
+            .ok()
+            .and_then(|set_timeout| set_timeout.dyn_into::<js_sys::Function>().ok());
+        match set_timeout {
+            Some(set_timeout) => {
+                let _ = set_timeout.call2(&JsValue::NULL, &resolve, &0.into());
+            }
+            None => {
+                let _ = resolve.call0(&JsValue::NULL);
+            }
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::task::{Context, Poll, Waker};
+
+    #[test]
+    fn test_without_signal() {
+        let abort = ParseAbort { signal: None };
+        assert!(!abort.is_aborted());
+
+        // Resolves at once instead of yielding to the event loop
+        let mut checkpoint = std::pin::pin!(abort.checkpoint());
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(checkpoint.as_mut().poll(&mut cx), Poll::Ready(false));
+    }
+
+    /// Options whose `signal` is a plain object standing in for an AbortSignal
+    #[cfg(target_arch = "wasm32")]
+    fn options(aborted: bool, reason: JsValue) -> JsValue {
+        let signal = js_sys::Object::new();
+        js_sys::Reflect::set(&signal, &"aborted".into(), &aborted.into()).unwrap();
+        js_sys::Reflect::set(&signal, &"reason".into(), &reason).unwrap();
+        let options = js_sys::Object::new();
+        js_sys::Reflect::set(&options, &"signal".into(), &signal).unwrap();
+        options.into()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn test_signal() {
+        let abort = ParseAbort::from_options(&options(false, JsValue::UNDEFINED));
+        assert!(!abort.is_aborted());
+        assert!(!abort.checkpoint().await);
+
+        let abort = ParseAbort::from_options(&options(true, "dropped".into()));
+        assert!(abort.checkpoint().await);
+        assert_eq!(abort.reason().as_string().as_deref(), Some("dropped"));
+
+        // Signals without a reason reject with an AbortError
+        let abort = ParseAbort::from_options(&options(true, JsValue::UNDEFINED));
+        let error: js_sys::Error = abort.reason().dyn_into().unwrap();
+        assert_eq!(String::from(error.name()), "AbortError");
+
+        let abort = ParseAbort::from_options(&JsValue::NULL);
+        assert!(!abort.is_aborted());
+    }
+}
@@ -0,0 +1,119 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! WASM API: parseAnnotationLines — 3D line geometry of IfcAnnotation elements.
+
+use super::IfcAPI;
+use wasm_bindgen::prelude::*;
+
+/// Line geometry of all annotations, packed into flat arrays.
+///
+/// Every two entries of `indices` form one segment; segment `i` belongs to
+/// `segmentExpressIds[i]`. Positions are in WebGL Y-up world space (metres),
+/// with the same RTC offset as `parseMeshes`.
+#[wasm_bindgen]
+pub struct AnnotationLineCollection {
+    positions: Vec<f32>,
+    indices: Vec<u32>,
+    segment_express_ids: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl AnnotationLineCollection {
+    /// Number of segments.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.segment_express_ids.len()
+    }
+
+    /// Vertex positions: `[x0, y0, z0, x1, …]`.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(&self.positions[..])
+    }
+
+    /// Segment indices for `LineSegments`: `[a0, b0, a1, b1, …]`.
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.indices[..])
+    }
+
+    /// Express ID of the annotation each segment belongs to.
+    #[wasm_bindgen(getter, js_name = segmentExpressIds)]
+    pub fn segment_express_ids(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.segment_express_ids[..])
+    }
+}
+
+impl AnnotationLineCollection {
+    /// Append one element's lines, converting IFC Z-up to WebGL Y-up.
+    fn add(&mut self, express_id: u32, lines: ifc_lite_geometry::LineMesh) {
+        let offset = (self.positions.len() / 3) as u32;
+        for p in lines.positions.chunks_exact(3) {
+            self.positions.extend_from_slice(&[p[0], p[2], -p[1]]);
+        }
+        self.indices
+            .extend(lines.indices.iter().map(|&i| i + offset));
+        self.segment_express_ids
+            .extend(std::iter::repeat_n(express_id, lines.segment_count()));
+    }
+}
+
+#[wasm_bindgen]
+impl IfcAPI {
+    /// Extract 3D line geometry of IfcAnnotation elements: annotation
+    /// curves, survey points and text placements (drawn as small crosses).
+    ///
+    /// Annotations with solid Body geometry are still meshed by
+    /// `parseMeshes`; this covers the curve and point content those calls
+    /// skip, so viewers can toggle annotations as a layer.
+    ///
+    /// ```javascript
+    /// const api = new IfcAPI();
+    /// const lines = api.parseAnnotationLines(ifcContent);
+    /// const geometry = new THREE.BufferGeometry();
+    /// geometry.setAttribute('position', new THREE.BufferAttribute(lines.positions, 3));
+    /// geometry.setIndex(new THREE.BufferAttribute(lines.indices, 1));
+    /// scene.add(new THREE.LineSegments(geometry));
+    /// ```
+    #[wasm_bindgen(js_name = parseAnnotationLines)]
+    pub fn parse_annotation_lines(&self, content: String) -> AnnotationLineCollection {
+        use ifc_lite_core::{build_entity_index, EntityDecoder, EntityScanner};
+
+        let entity_index = build_entity_index(&content);
+        let mut decoder = EntityDecoder::with_index(&content, entity_index);
+
+        // Same unit scale and RTC offset as mesh parsing so lines line up
+        let mut router = ifc_lite_geometry::GeometryRouter::with_units(&content, &mut decoder);
+        let rtc_offset = router.detect_rtc_offset_from_first_element(&content, &mut decoder);
+        if rtc_offset.0.abs() > 10000.0
+            || rtc_offset.1.abs() > 10000.0
+            || rtc_offset.2.abs() > 10000.0
+        {
+            router.set_rtc_offset(rtc_offset);
+        }
+
+        let mut collection = AnnotationLineCollection {
+            positions: Vec::new(),
+            indices: Vec::new(),
+            segment_express_ids: Vec::new(),
+        };
+        let mut scanner = EntityScanner::new(&content);
+        while let Some((id, type_name, start, end)) = scanner.next_entity() {
+            if type_name != "IFCANNOTATION" {
+                continue;
+            }
+            let Ok(entity) = decoder.decode_at_with_id(id, start, end) else {
+                continue;
+            };
+            match router.process_element_lines(&entity, &mut decoder) {
+                Ok(lines) => collection.add(id, lines),
+                Err(e) => web_sys::console::warn_1(
+                    &format!("[IFC-LITE] Failed to process annotation #{}: {}", id, e).into(),
+                ),
+            }
+        }
+        collection
+    }
+}
@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! WASM API: parseBoundsOnly — oriented boxes per element without triangulation.
+
+use super::IfcAPI;
+use wasm_bindgen::prelude::*;
+
+/// Oriented bounding boxes for all elements, packed into flat arrays.
+///
+/// Entry `i` uses `expressIds[i]`, `centers[3i..3i+3]`,
+/// `halfExtents[3i..3i+3]` and `axes[9i..9i+9]`. All values are in WebGL
+/// Y-up world space (metres).
+#[wasm_bindgen]
+pub struct BoundsCollection {
+    express_ids: Vec<u32>,
+    ifc_types: Vec<String>,
+    centers: Vec<f32>,
+    half_extents: Vec<f32>,
+    axes: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl BoundsCollection {
+    /// Number of boxes.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.express_ids.len()
+    }
+
+    /// Express ID per box.
+    #[wasm_bindgen(getter, js_name = expressIds)]
+    pub fn express_ids(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.express_ids[..])
+    }
+
+    /// Box centres: `[x0, y0, z0, x1, …]`.
+    #[wasm_bindgen(getter)]
+    pub fn centers(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(&self.centers[..])
+    }
+
+    /// Half sizes along each box axis: `[hx0, hy0, hz0, hx1, …]`.
+    #[wasm_bindgen(getter, js_name = halfExtents)]
+    pub fn half_extents(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(&self.half_extents[..])
+    }
+
+    /// Box axes, three unit vectors (9 floats) per box.
+    #[wasm_bindgen(getter)]
+    pub fn axes(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(&self.axes[..])
+    }
+
+    /// IFC type name of the box at `index` (e.g., `"IfcWall"`).
+    #[wasm_bindgen(js_name = ifcType)]
+    pub fn ifc_type(&self, index: usize) -> Option<String> {
+        self.ifc_types.get(index).cloned()
+    }
+}
+
+impl From<Vec<ifc_lite_geometry::ElementBounds>> for BoundsCollection {
+    fn from(bounds: Vec<ifc_lite_geometry::ElementBounds>) -> Self {
+        let n = bounds.len();
+        let mut out = Self {
+            express_ids: Vec::with_capacity(n),
+            ifc_types: Vec::with_capacity(n),
+            centers: Vec::with_capacity(n * 3),
+            half_extents: Vec::with_capacity(n * 3),
+            axes: Vec::with_capacity(n * 9),
+        };
+        for b in bounds {
+            out.express_ids.push(b.express_id);
+            out.ifc_types.push(b.ifc_type);
+            out.centers.extend_from_slice(&b.center);
+            out.half_extents.extend_from_slice(&b.half_extents);
+            out.axes.extend_from_slice(&b.axes);
+        }
+        out
+    }
+}
+
+#[wasm_bindgen]
+impl IfcAPI {
+    /// Compute one oriented bounding box per element without triangulating.
+    ///
+    /// Resolves placements and profile/coordinate extents only, so it finishes
+    /// far faster than a full parse. Use it for placeholder rendering and
+    /// spatial queries while `parseMeshes` runs.
+    ///
+    /// ```javascript
+    /// const api = new IfcAPI();
+    /// const bounds = api.parseBoundsOnly(ifcContent);
+    /// const centers = bounds.centers;
+    /// for (let i = 0; i < bounds.length; i++) {
+    ///   console.log(bounds.ifcType(i), centers.subarray(i * 3, i * 3 + 3));
+    /// }
+    /// ```
+    #[wasm_bindgen(js_name = parseBoundsOnly)]
+    pub fn parse_bounds_only(&self, content: String) -> BoundsCollection {
+        ifc_lite_geometry::extract_bounds(&content).into()
+    }
+}
@@ -3,12 +3,250 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 //! Debug methods for IFC-Lite API
+//!
+//! Also collects the debug overlay: entities that `parseMeshes` filtered,
+//! failed to process or processed unusually slowly, each with a box that
+//! `getDebugMeshes` turns into a wireframe the viewer can draw.
 
 use super::IfcAPI;
+use ifc_lite_core::{DecodedEntity, EntityDecoder};
+use ifc_lite_geometry::{GeometryRouter, Mesh};
 use wasm_bindgen::prelude::*;
 
+/// Elements taking longer than this (ms) to process are reported as timed out
+const ELEMENT_TIME_BUDGET_MS: f64 = 2000.0;
+
+/// Half size (m) of the placeholder box drawn at the placement of entities
+/// without usable geometry
+const PLACEHOLDER_HALF_SIZE: f32 = 0.5;
+
+/// Box corner pairs forming the 12 wireframe edges
+const BOX_EDGES: [(usize, usize); 12] = [
+    (0, 1),
+    (1, 3),
+    (3, 2),
+    (2, 0),
+    (4, 5),
+    (5, 7),
+    (7, 6),
+    (6, 4),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
+/// Why an entity is missing from (or suspicious in) the parsed geometry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DebugReason {
+    /// Mesh dropped by the coordinate outlier filter
+    Filtered,
+    /// Decoding or geometry processing returned an error
+    Failed,
+    /// Processing succeeded but produced no triangles
+    Empty,
+    /// Processing exceeded `ELEMENT_TIME_BUDGET_MS` (the mesh is still emitted)
+    TimedOut,
+}
+
+impl DebugReason {
+    /// Reason to report a processed element for, if any. Failures come
+    /// first; an element without meshes is empty unless it was already
+    /// reported as filtered; otherwise it is only reported when slow.
+    pub(crate) fn classify(
+        failed: bool,
+        added_mesh: bool,
+        filtered: bool,
+        elapsed_ms: f64,
+    ) -> Option<Self> {
+        if failed {
+            Some(Self::Failed)
+        } else if !added_mesh && !filtered {
+            Some(Self::Empty)
+        } else if elapsed_ms > ELEMENT_TIME_BUDGET_MS {
+            Some(Self::TimedOut)
+        } else {
+            None
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Filtered => "filtered",
+            Self::Failed => "failed",
+            Self::Empty => "empty",
+            Self::TimedOut => "timedOut",
+        }
+    }
+}
+
+/// One overlay entry: axis-aligned box in WebGL Y-up space (metres, RTC-shifted)
+#[derive(Debug, Clone)]
+pub(crate) struct DebugEntity {
+    express_id: u32,
+    ifc_type: String,
+    reason: DebugReason,
+    min: [f32; 3],
+    max: [f32; 3],
+}
+
+impl DebugEntity {
+    /// Box around the finite vertices of an IFC Z-up mesh
+    pub(crate) fn from_mesh(
+        express_id: u32,
+        ifc_type: &str,
+        reason: DebugReason,
+        mesh: &Mesh,
+    ) -> Option<Self> {
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for p in mesh.positions.chunks_exact(3) {
+            if p.iter().all(|v| v.is_finite()) {
+                let p = to_y_up([p[0], p[1], p[2]]);
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(p[axis]);
+                    max[axis] = max[axis].max(p[axis]);
+                }
+            }
+        }
+        (min[0] <= max[0]).then(|| Self {
+            express_id,
+            ifc_type: ifc_type.to_string(),
+            reason,
+            min,
+            max,
+        })
+    }
+
+    /// Placeholder box at an IFC Z-up placement origin
+    pub(crate) fn at_origin(
+        express_id: u32,
+        ifc_type: &str,
+        reason: DebugReason,
+        origin: [f32; 3],
+    ) -> Self {
+        let c = to_y_up(origin);
+        Self {
+            express_id,
+            ifc_type: ifc_type.to_string(),
+            reason,
+            min: c.map(|v| v - PLACEHOLDER_HALF_SIZE),
+            max: c.map(|v| v + PLACEHOLDER_HALF_SIZE),
+        }
+    }
+}
+
+/// Element placement origin in IFC Z-up space (metres, RTC-shifted)
+pub(crate) fn placement_origin(
+    router: &GeometryRouter,
+    entity: &DecodedEntity,
+    decoder: &mut EntityDecoder,
+) -> Option<[f32; 3]> {
+    let m = router.resolve_scaled_placement(entity, decoder).ok()?;
+    let rtc = router.rtc_offset();
+    Some([
+        (m[12] - rtc.0) as f32,
+        (m[13] - rtc.1) as f32,
+        (m[14] - rtc.2) as f32,
+    ])
+}
+
+/// IFC Z-up → WebGL Y-up, as done by `MeshDataJs::new`
+fn to_y_up(p: [f32; 3]) -> [f32; 3] {
+    [p[0], p[2], -p[1]]
+}
+
+/// Wireframe boxes and labels for entities reported by the last `parseMeshes`.
+///
+/// Entry `i` owns `expressIds[i]` and the 24 line-list vertices
+/// `positions[72i..72i+72]` (12 edges, WebGL Y-up, same space as the meshes).
+#[wasm_bindgen]
+pub struct DebugMeshes {
+    entities: Vec<DebugEntity>,
+}
+
+#[wasm_bindgen]
+impl DebugMeshes {
+    /// Number of reported entities.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Express ID per entity.
+    #[wasm_bindgen(getter, js_name = expressIds)]
+    pub fn express_ids(&self) -> js_sys::Uint32Array {
+        let ids: Vec<u32> = self.entities.iter().map(|e| e.express_id).collect();
+        js_sys::Uint32Array::from(&ids[..])
+    }
+
+    /// Line-list vertices, 24 per entity: `[x0, y0, z0, x1, …]`.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> js_sys::Float32Array {
+        let mut positions = Vec::with_capacity(self.entities.len() * 72);
+        for e in &self.entities {
+            let corner = |i: usize| {
+                [
+                    if i & 1 == 0 { e.min[0] } else { e.max[0] },
+                    if i & 2 == 0 { e.min[1] } else { e.max[1] },
+                    if i & 4 == 0 { e.min[2] } else { e.max[2] },
+                ]
+            };
+            for (a, b) in BOX_EDGES {
+                positions.extend_from_slice(&corner(a));
+                positions.extend_from_slice(&corner(b));
+            }
+        }
+        js_sys::Float32Array::from(&positions[..])
+    }
+
+    /// Reason of the entity at `index`: `"filtered"`, `"failed"`, `"empty"` or `"timedOut"`.
+    pub fn reason(&self, index: usize) -> Option<String> {
+        self.entities
+            .get(index)
+            .map(|e| e.reason.as_str().to_string())
+    }
+
+    /// IFC type name of the entity at `index` (e.g., `"IfcWall"`).
+    #[wasm_bindgen(js_name = ifcType)]
+    pub fn ifc_type(&self, index: usize) -> Option<String> {
+        self.entities.get(index).map(|e| e.ifc_type.clone())
+    }
+
+    /// Display label of the entity at `index`, e.g. `"#42 IfcWall (failed)"`.
+    pub fn label(&self, index: usize) -> Option<String> {
+        self.entities
+            .get(index)
+            .map(|e| format!("#{} {} ({})", e.express_id, e.ifc_type, e.reason.as_str()))
+    }
+
+    /// Label anchor (box centre) of the entity at `index`.
+    pub fn anchor(&self, index: usize) -> Option<Vec<f32>> {
+        self.entities
+            .get(index)
+            .map(|e| (0..3).map(|i| (e.min[i] + e.max[i]) * 0.5).collect())
+    }
+}
+
 #[wasm_bindgen]
 impl IfcAPI {
+    /// Wireframe boxes for entities the last `parseMeshes` call filtered as
+    /// outliers, failed to process, produced no geometry for, or spent longer
+    /// than the time budget on.
+    ///
+    /// ```javascript
+    /// const meshes = api.parseMeshes(ifcContent);
+    /// const debug = api.getDebugMeshes();
+    /// const lines = new THREE.LineSegments(lineGeometry(debug.positions), red);
+    /// for (let i = 0; i < debug.length; i++) console.log(debug.label(i));
+    /// ```
+    #[wasm_bindgen(js_name = getDebugMeshes)]
+    pub fn get_debug_meshes(&self) -> DebugMeshes {
+        DebugMeshes {
+            entities: self.debug_entities.borrow().clone(),
+        }
+    }
+
     /// Debug: Test processing entity #953 (FacetedBrep wall)
     #[wasm_bindgen(js_name = debugProcessEntity953)]
     pub fn debug_process_entity_953(&self, content: String) -> String {
@@ -89,3 +327,40 @@ impl IfcAPI {
         "No walls found".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        use DebugReason::*;
+        let classify = DebugReason::classify;
+
+        assert_eq!(classify(true, false, false, 0.0), Some(Failed));
+        assert_eq!(classify(true, true, true, 5000.0), Some(Failed));
+        assert_eq!(classify(false, false, false, 0.0), Some(Empty));
+        // Filtered meshes already have their own entry
+        assert_eq!(classify(false, false, true, 5000.0), Some(TimedOut));
+        assert_eq!(classify(false, false, true, 0.0), None);
+        assert_eq!(classify(false, true, false, 5000.0), Some(TimedOut));
+        assert_eq!(classify(false, true, false, ELEMENT_TIME_BUDGET_MS), None);
+    }
+
+    #[test]
+    fn test_boxes() {
+        let mut mesh = Mesh::new();
+        mesh.positions = vec![1.0, 2.0, 3.0, f32::NAN, 0.0, 0.0, 4.0, -1.0, 5.0];
+        let entity = DebugEntity::from_mesh(7, "IfcWall", DebugReason::Filtered, &mesh).unwrap();
+        // Z-up (x, y, z) becomes Y-up (x, z, -y); the NaN vertex is ignored
+        assert_eq!(entity.min, [1.0, 3.0, -2.0]);
+        assert_eq!(entity.max, [4.0, 5.0, 1.0]);
+
+        mesh.positions = vec![f32::INFINITY, 0.0, 0.0];
+        assert!(DebugEntity::from_mesh(7, "IfcWall", DebugReason::Filtered, &mesh).is_none());
+
+        let entity = DebugEntity::at_origin(8, "IfcSlab", DebugReason::Empty, [1.0, 2.0, 3.0]);
+        assert_eq!(entity.min, [0.5, 2.5, -2.5]);
+        assert_eq!(entity.max, [1.5, 3.5, -1.5]);
+    }
+}
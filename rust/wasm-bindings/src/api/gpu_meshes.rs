@@ -12,6 +12,9 @@ use super::styling::{
     build_geometry_style_index, extract_building_rotation, get_default_color_for_type,
     resolve_element_color, resolve_submesh_color,
 };
+use super::abort::ParseAbort;
+use super::debug::{placement_origin, DebugEntity, DebugReason};
+use super::parse_filter::ParseFilter;
 use super::GeometryStats;
 use super::IfcAPI;
 use crate::gpu_geometry::{GpuGeometry, GpuInstancedGeometry, GpuInstancedGeometryCollection};
@@ -29,6 +32,94 @@ fn decode_ifc_bytes<'a>(data: &'a [u8]) -> &'a str {
     }
 }
 
+/// Flag marking instanced group keys that are IfcRepresentationMap IDs, so
+/// they never collide with mesh hashes
+const REPRESENTATION_MAP_KEY: u64 = 1 << 63;
+
+/// Group key, column-major transform and (if it had to be tessellated) mesh
+/// of an element for the instanced parse paths.
+///
+/// Elements whose body is a single IfcMappedItem are keyed by their
+/// IfcRepresentationMap without being tessellated; [`instanced_group_mesh`]
+/// builds the shared mesh once per group. Other elements are tessellated and
+/// keyed by a hash of their mesh.
+fn instanced_element_geometry(
+    router: &ifc_lite_geometry::GeometryRouter,
+    entity: &ifc_lite_core::DecodedEntity,
+    decoder: &mut ifc_lite_core::EntityDecoder,
+) -> Option<(u64, [f64; 16], Option<ifc_lite_geometry::Mesh>)> {
+    use rustc_hash::FxHasher;
+    use std::hash::{Hash, Hasher};
+
+    if let Ok(Some(instance)) = router.mapped_instance(entity, decoder) {
+        let key = REPRESENTATION_MAP_KEY | instance.source_id as u64;
+        // nalgebra stores matrices column-major, as WebGPU expects
+        let transform = instance.transform.as_slice().try_into().ok()?;
+        return Some((key, transform, None));
+    }
+
+    let (mesh, mut transform) = router
+        .process_element_with_transform(entity, decoder)
+        .ok()?;
+    if mesh.is_empty() {
+        return None;
+    }
+    // Placements are resolved in file units, meshes are already in meters
+    for row in 0..3 {
+        transform[(row, 3)] *= router.unit_scale();
+    }
+
+    let mut hasher = FxHasher::default();
+    mesh.positions.len().hash(&mut hasher);
+    mesh.indices.len().hash(&mut hasher);
+    for pos in &mesh.positions {
+        pos.to_bits().hash(&mut hasher);
+    }
+    for idx in &mesh.indices {
+        idx.hash(&mut hasher);
+    }
+    let key = hasher.finish() & !REPRESENTATION_MAP_KEY;
+    Some((key, transform.as_slice().try_into().ok()?, Some(mesh)))
+}
+
+/// Shared mesh of a new instanced group, with complete normals
+fn instanced_group_mesh(
+    router: &ifc_lite_geometry::GeometryRouter,
+    key: u64,
+    mesh: Option<ifc_lite_geometry::Mesh>,
+    decoder: &mut ifc_lite_core::EntityDecoder,
+) -> Option<ifc_lite_geometry::Mesh> {
+    let mut mesh = match mesh {
+        Some(mesh) => mesh,
+        None => router
+            .process_representation_map((key & !REPRESENTATION_MAP_KEY) as u32, decoder)
+            .ok()?,
+    };
+    if mesh.is_empty() {
+        return None;
+    }
+    // CSG operations may produce partial normals, so check for matching count
+    if mesh.normals.len() != mesh.positions.len() {
+        ifc_lite_geometry::calculate_normals(&mut mesh);
+    }
+    Some(mesh)
+}
+
+/// `onBatch` payload of parseMeshesAsync and its mesh count: `MeshDataJs`
+/// wrappers, or with the `transferable` option standalone buffers (see
+/// [`crate::zero_copy::pack_transferable`])
+fn mesh_batch_to_js(meshes: &mut Vec<MeshDataJs>, transferable: bool) -> (JsValue, usize) {
+    let count = meshes.len();
+    let batch = if transferable {
+        let batch = std::mem::replace(meshes, Vec::with_capacity(meshes.capacity()));
+        crate::zero_copy::pack_transferable(batch)
+    } else {
+        let js_meshes: js_sys::Array = meshes.drain(..).map(JsValue::from).collect();
+        js_meshes.into()
+    };
+    (batch, count)
+}
+
 #[wasm_bindgen]
 impl IfcAPI {
     /// Parse IFC file and return individual meshes with express IDs and colors
@@ -128,6 +219,8 @@ impl IfcAPI {
 
         // Track geometry parsing statistics
         let mut stats = GeometryStats::default();
+        // Boxes for entities missing from the output, served by getDebugMeshes
+        let mut debug_entities: Vec<DebugEntity> = Vec::new();
 
         // Process all building elements
         while let Some((id, type_name, start, end)) = scanner.next_entity() {
@@ -139,6 +232,7 @@ impl IfcAPI {
             stats.total += 1;
 
             // Decode and process the entity
+            let started = js_sys::Date::now();
             if let Ok(entity) = decoder.decode_at_with_id(id, start, end) {
                 // Check if entity actually has representation (attribute index 6 for IfcProduct)
                 let has_representation = entity.get(6).map(|a| !a.is_null()).unwrap_or(false);
@@ -161,6 +255,8 @@ impl IfcAPI {
                 let default_color = get_default_color_for_type(&entity.ifc_type);
                 let ifc_type_name = entity.ifc_type.name().to_string();
                 let mut added_any_mesh = false;
+                let mut failed = false;
+                let debug_len = debug_entities.len();
 
                 let mut push_mesh_if_valid =
                     |mesh: &mut ifc_lite_geometry::Mesh, color: [f32; 4]| {
@@ -174,30 +270,10 @@ impl IfcAPI {
                         }
 
                         // Safety filter: exclude meshes with unreasonable coordinates after RTC
-                        const MAX_REASONABLE_OFFSET: f32 = 50_000.0; // 50km from RTC center
-                        let mut max_coord = 0.0f32;
-                        let mut outlier_vertex_count = 0;
-                        let mut has_non_finite = false;
-
-                        for chunk in mesh.positions.chunks_exact(3) {
-                            let x = chunk[0];
-                            let y = chunk[1];
-                            let z = chunk[2];
-
-                            if !x.is_finite() || !y.is_finite() || !z.is_finite() {
-                                outlier_vertex_count += 1;
-                                has_non_finite = true;
-                                continue;
-                            }
+                        // and strip NaN vertices and broken triangles before upload
+                        let report = router.filter_mesh(mesh);
 
-                            let coord_mag = x.abs().max(y.abs()).max(z.abs());
-                            max_coord = max_coord.max(coord_mag);
-                            if coord_mag > MAX_REASONABLE_OFFSET {
-                                outlier_vertex_count += 1;
-                            }
-                        }
-
-                        if has_non_finite {
+                        if report.repair.non_finite_vertices > 0 {
                             web_sys::console::warn_1(
                                 &format!(
                                     "[WASM FILTER] Mesh #{} ({}) contains NaN/Inf coordinates",
@@ -208,25 +284,27 @@ impl IfcAPI {
                             );
                         }
 
-                        let total_vertices = mesh.positions.len() / 3;
-                        let outlier_ratio = if total_vertices > 0 {
-                            outlier_vertex_count as f32 / total_vertices as f32
-                        } else {
-                            0.0
-                        };
-
-                        if outlier_ratio > 0.9 || max_coord > MAX_REASONABLE_OFFSET * 4.0 {
+                        if report.rejected {
                             web_sys::console::warn_1(
                             &format!(
-                                "[WASM FILTER] Excluding mesh #{} ({}) - {:.1}% outliers, max coord: {:.2}m",
+                                "[WASM FILTER] {} mesh #{} ({}) - {:.1}% outliers, max coord: {:.2}m",
+                                if report.keep { "Keeping" } else { "Excluding" },
                                 id,
                                 entity.ifc_type.name(),
-                                outlier_ratio * 100.0,
-                                max_coord
+                                report.outlier_ratio * 100.0,
+                                report.repair.max_coordinate
                             )
                             .into(),
                         );
+                        }
+                        if !report.keep {
                             stats.outlier_filtered += 1;
+                            debug_entities.extend(DebugEntity::from_mesh(
+                                id,
+                                &ifc_type_name,
+                                DebugReason::Filtered,
+                                mesh,
+                            ));
                             return;
                         }
 
@@ -249,6 +327,7 @@ impl IfcAPI {
                                 .into(),
                             );
                             stats.process_failed += 1;
+                            failed = true;
                         }
                         Ok(mut mesh) => {
                             let color = style_index.get(&id).copied().unwrap_or(default_color);
@@ -301,6 +380,7 @@ impl IfcAPI {
                                     .into(),
                                 );
                                 stats.process_failed += 1;
+                                failed = true;
                             }
                             Ok(mut mesh) => {
                                 let color = style_index.get(&id).copied().unwrap_or(default_color);
@@ -315,6 +395,22 @@ impl IfcAPI {
                 } else {
                     stats.empty_mesh += 1;
                 }
+
+                if let Some(reason) = DebugReason::classify(
+                    failed,
+                    added_any_mesh,
+                    debug_entities.len() != debug_len,
+                    js_sys::Date::now() - started,
+                ) {
+                    if let Some(origin) = placement_origin(&router, &entity, &mut decoder) {
+                        debug_entities.push(DebugEntity::at_origin(
+                            id,
+                            &ifc_type_name,
+                            reason,
+                            origin,
+                        ));
+                    }
+                }
             } else {
                 stats.decode_failed += 1;
             }
@@ -334,6 +430,12 @@ impl IfcAPI {
                 stats.success, stats.total, actual_candidates, stats.no_representation
             ).into());
 
+            let decode = decoder.cache_stats();
+            web_sys::console::debug_1(&format!(
+                "[IFC-LITE] Decode cache: {} hits, {} misses ({:.1}% hit rate), {} evictions",
+                decode.hits, decode.misses, decode.hit_rate() * 100.0, decode.evictions
+            ).into());
+
             // Warn only on actual processing failures (not missing representations — those are expected)
             let actual_failures = stats.decode_failed + stats.process_failed;
             if actual_failures > 0 || candidate_success_rate < 0.5 {
@@ -345,6 +447,8 @@ impl IfcAPI {
             }
         }
 
+        *self.debug_entities.borrow_mut() = debug_entities;
+
         mesh_collection
     }
 
@@ -574,10 +678,8 @@ impl IfcAPI {
     #[wasm_bindgen(js_name = parseMeshesInstanced)]
     pub fn parse_meshes_instanced(&self, content: String) -> InstancedMeshCollection {
         use ifc_lite_core::{build_entity_index, EntityDecoder, EntityScanner};
-        use ifc_lite_geometry::{calculate_normals, GeometryRouter, Mesh};
+        use ifc_lite_geometry::{GeometryRouter, Mesh};
         use rustc_hash::FxHashMap;
-        use rustc_hash::FxHasher;
-        use std::hash::{Hash, Hasher};
 
         // Build entity index once upfront for O(1) lookups
         let entity_index = build_entity_index(&content);
@@ -625,51 +727,23 @@ impl IfcAPI {
 
             // Decode and process the entity
             if let Ok(entity) = decoder.decode_at_with_id(id, start, end) {
-                if let Ok((mut mesh, transform)) =
-                    router.process_element_with_transform(&entity, &mut decoder)
+                if let Some((geometry_id, transform_array, mesh)) =
+                    instanced_element_geometry(&router, &entity, &mut decoder)
                 {
-                    if !mesh.is_empty() {
-                        // Calculate normals if not present or incomplete
-                        // CSG operations may produce partial normals, so check for matching count
-                        if mesh.normals.len() != mesh.positions.len() {
-                            calculate_normals(&mut mesh);
-                        }
-
-                        // Compute geometry hash (same as router does)
-                        let mut hasher = FxHasher::default();
-                        mesh.positions.len().hash(&mut hasher);
-                        mesh.indices.len().hash(&mut hasher);
-                        for pos in &mesh.positions {
-                            pos.to_bits().hash(&mut hasher);
-                        }
-                        for idx in &mesh.indices {
-                            idx.hash(&mut hasher);
-                        }
-                        let geometry_hash = hasher.finish();
-
-                        // Try to get color from style index, otherwise use default
-                        let color = style_index
-                            .get(&id)
-                            .copied()
-                            .unwrap_or_else(|| get_default_color_for_type(&entity.ifc_type));
+                    let color = style_index
+                        .get(&id)
+                        .copied()
+                        .unwrap_or_else(|| get_default_color_for_type(&entity.ifc_type));
 
-                        // Convert Matrix4<f64> to [f64; 16] array (column-major for WebGPU)
-                        let mut transform_array = [0.0; 16];
-                        for col in 0..4 {
-                            for row in 0..4 {
-                                transform_array[col * 4 + row] = transform[(row, col)];
-                            }
+                    // Only the first instance of a geometry brings its mesh
+                    match geometry_groups.entry(geometry_id) {
+                        std::collections::hash_map::Entry::Occupied(mut o) => {
+                            o.get_mut().1.push((id, transform_array, color));
                         }
-
-                        // Add to group - only store mesh once per hash
-                        let entry = geometry_groups.entry(geometry_hash);
-                        match entry {
-                            std::collections::hash_map::Entry::Occupied(mut o) => {
-                                // Geometry already exists, just add instance
-                                o.get_mut().1.push((id, transform_array, color));
-                            }
-                            std::collections::hash_map::Entry::Vacant(v) => {
-                                // First instance of this geometry
+                        std::collections::hash_map::Entry::Vacant(v) => {
+                            if let Some(mesh) =
+                                instanced_group_mesh(&router, geometry_id, mesh, &mut decoder)
+                            {
                                 v.insert((mesh, vec![(id, transform_array, color)]));
                             }
                         }
@@ -703,6 +777,8 @@ impl IfcAPI {
     /// Groups identical geometries and yields batches of InstancedGeometry
     /// Uses fast-first-frame streaming: simple geometry (walls, slabs) first
     ///
+    /// Accepts the same `filter` option as `parseMeshesAsync`.
+    ///
     /// Example:
     /// ```javascript
     /// const api = new IfcAPI();
@@ -725,15 +801,14 @@ impl IfcAPI {
         options: JsValue,
     ) -> js_sys::Promise {
         use ifc_lite_core::{build_entity_index, EntityDecoder, EntityScanner};
-        use ifc_lite_geometry::{calculate_normals, GeometryRouter, Mesh};
-        use rustc_hash::{FxHashMap, FxHasher};
-        use std::hash::{Hash, Hasher};
+        use ifc_lite_geometry::{GeometryRouter, Mesh};
+        use rustc_hash::FxHashMap;
 
         // Use Option::take() to move ownership into the closure without cloning.
         // This avoids doubling WASM memory usage for large files (700MB+ saves ~700MB).
         let mut content = Some(content);
         let mut options = Some(options);
-        let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let promise = js_sys::Promise::new(&mut |resolve, reject| {
             let content = content.take().expect("content already taken");
             let options = options.take().expect("options already taken");
 
@@ -779,6 +854,14 @@ impl IfcAPI {
                     router.preprocess_faceted_breps(&faceted_brep_ids, &mut decoder);
                 }
 
+                let filter = match ParseFilter::from_options(&options, &content, &mut decoder) {
+                    Ok(filter) => filter,
+                    Err(error) => {
+                        let _ = reject.call1(&JsValue::NULL, &error);
+                        return;
+                    }
+                };
+
                 // Reset scanner for main processing
                 scanner = EntityScanner::new(&content);
 
@@ -802,6 +885,12 @@ impl IfcAPI {
                     }
 
                     let ifc_type = ifc_lite_core::IfcType::from_str(type_name);
+                    if filter
+                        .as_ref()
+                        .is_some_and(|filter| !filter.matches(id, ifc_type))
+                    {
+                        continue;
+                    }
 
                     // Simple geometry: process immediately
                     if matches!(
@@ -822,54 +911,34 @@ impl IfcAPI {
                             | "IFCRAMPFLIGHT"
                     ) {
                         if let Ok(entity) = decoder.decode_at_with_id(id, start, end) {
-                            if let Ok((mut mesh, transform)) =
-                                router.process_element_with_transform(&entity, &mut decoder)
+                            if let Some((geometry_id, transform_array, mesh)) =
+                                instanced_element_geometry(&router, &entity, &mut decoder)
                             {
-                                if !mesh.is_empty() {
-                                    if mesh.normals.len() != mesh.positions.len() {
-                                        calculate_normals(&mut mesh);
-                                    }
-
-                                    // Compute geometry hash (before transformation)
-                                    let mut hasher = FxHasher::default();
-                                    mesh.positions.len().hash(&mut hasher);
-                                    mesh.indices.len().hash(&mut hasher);
-                                    for pos in &mesh.positions {
-                                        pos.to_bits().hash(&mut hasher);
-                                    }
-                                    for idx in &mesh.indices {
-                                        idx.hash(&mut hasher);
-                                    }
-                                    let geometry_hash = hasher.finish();
-
-                                    // Get color
-                                    let color = style_index
-                                        .get(&id)
-                                        .copied()
-                                        .unwrap_or_else(|| get_default_color_for_type(&ifc_type));
+                                let color = style_index
+                                    .get(&id)
+                                    .copied()
+                                    .unwrap_or_else(|| get_default_color_for_type(&ifc_type));
 
-                                    // Convert Matrix4<f64> to [f64; 16] array (column-major for WebGPU)
-                                    let mut transform_array = [0.0; 16];
-                                    for col in 0..4 {
-                                        for row in 0..4 {
-                                            transform_array[col * 4 + row] = transform[(row, col)];
-                                        }
+                                // Only the first instance of a geometry brings its mesh
+                                match geometry_groups.entry(geometry_id) {
+                                    std::collections::hash_map::Entry::Occupied(mut o) => {
+                                        o.get_mut().1.push((id, transform_array, color));
+                                        total_instances += 1;
+                                        processed += 1;
                                     }
-
-                                    // Add to group
-                                    let entry = geometry_groups.entry(geometry_hash);
-                                    match entry {
-                                        std::collections::hash_map::Entry::Occupied(mut o) => {
-                                            o.get_mut().1.push((id, transform_array, color));
-                                            total_instances += 1;
-                                        }
-                                        std::collections::hash_map::Entry::Vacant(v) => {
+                                    std::collections::hash_map::Entry::Vacant(v) => {
+                                        if let Some(mesh) = instanced_group_mesh(
+                                            &router,
+                                            geometry_id,
+                                            mesh,
+                                            &mut decoder,
+                                        ) {
                                             v.insert((mesh, vec![(id, transform_array, color)]));
                                             total_geometries += 1;
                                             total_instances += 1;
+                                            processed += 1;
                                         }
                                     }
-                                    processed += 1;
                                 }
                             }
                         }
@@ -982,54 +1051,34 @@ impl IfcAPI {
                 let total_elements = processed + deferred_complex.len();
                 for (id, start, end, ifc_type) in deferred_complex {
                     if let Ok(entity) = decoder.decode_at_with_id(id, start, end) {
-                        if let Ok((mut mesh, transform)) =
-                            router.process_element_with_transform(&entity, &mut decoder)
+                        if let Some((geometry_id, transform_array, mesh)) =
+                            instanced_element_geometry(&router, &entity, &mut decoder)
                         {
-                            if !mesh.is_empty() {
-                                if mesh.normals.len() != mesh.positions.len() {
-                                    calculate_normals(&mut mesh);
-                                }
-
-                                // Compute geometry hash
-                                let mut hasher = FxHasher::default();
-                                mesh.positions.len().hash(&mut hasher);
-                                mesh.indices.len().hash(&mut hasher);
-                                for pos in &mesh.positions {
-                                    pos.to_bits().hash(&mut hasher);
-                                }
-                                for idx in &mesh.indices {
-                                    idx.hash(&mut hasher);
-                                }
-                                let geometry_hash = hasher.finish();
-
-                                // Get color
-                                let color = style_index
-                                    .get(&id)
-                                    .copied()
-                                    .unwrap_or_else(|| get_default_color_for_type(&ifc_type));
+                            let color = style_index
+                                .get(&id)
+                                .copied()
+                                .unwrap_or_else(|| get_default_color_for_type(&ifc_type));
 
-                                // Convert transform (column-major for WebGPU)
-                                let mut transform_array = [0.0; 16];
-                                for col in 0..4 {
-                                    for row in 0..4 {
-                                        transform_array[col * 4 + row] = transform[(row, col)];
-                                    }
+                            // Only the first instance of a geometry brings its mesh
+                            match geometry_groups.entry(geometry_id) {
+                                std::collections::hash_map::Entry::Occupied(mut o) => {
+                                    o.get_mut().1.push((id, transform_array, color));
+                                    total_instances += 1;
+                                    processed += 1;
                                 }
-
-                                // Add to group
-                                let entry = geometry_groups.entry(geometry_hash);
-                                match entry {
-                                    std::collections::hash_map::Entry::Occupied(mut o) => {
-                                        o.get_mut().1.push((id, transform_array, color));
-                                        total_instances += 1;
-                                    }
-                                    std::collections::hash_map::Entry::Vacant(v) => {
+                                std::collections::hash_map::Entry::Vacant(v) => {
+                                    if let Some(mesh) = instanced_group_mesh(
+                                        &router,
+                                        geometry_id,
+                                        mesh,
+                                        &mut decoder,
+                                    ) {
                                         v.insert((mesh, vec![(id, transform_array, color)]));
                                         total_geometries += 1;
                                         total_instances += 1;
+                                        processed += 1;
                                     }
                                 }
-                                processed += 1;
                             }
                         }
                     }
@@ -1155,12 +1204,22 @@ impl IfcAPI {
     /// - `onRtcOffset({x, y, z, hasRtc})`: Called early with RTC offset for camera/world setup
     /// - `onColorUpdate(Map<id, color>)`: Called with style updates after initial render
     /// - `onComplete(stats)`: Called when parsing completes with stats including rtcOffset
+    /// - `signal`: AbortSignal; when aborted, parsing stops before the next batch,
+    ///   frees its buffers and the promise rejects with `signal.reason`
+    /// - `filter`: `{ includeTypes, excludeTypes, storeys, expressIds, skipSpaces,
+    ///   skipOpenings }`; only matching elements are processed (see `parse_filter`),
+    ///   and the promise rejects on an unknown type name
+    /// - `transferable`: pass each batch to `onBatch` as one object of standalone
+    ///   typed arrays (`positions`, `normals`, `indices`, `expressIds`, ...) plus a
+    ///   `transfer` list of their ArrayBuffers, ready for `postMessage` from a worker
     ///
     /// Example:
     /// ```javascript
     /// const api = new IfcAPI();
+    /// const controller = new AbortController();
     /// await api.parseMeshesAsync(ifcData, {
     ///   batchSize: 100,
+    ///   signal: controller.signal, // controller.abort() when another file is dropped
     ///   onRtcOffset: (rtc) => {
     ///     if (rtc.hasRtc) {
     ///       // Model uses large coordinates - adjust camera/world origin
@@ -1179,6 +1238,14 @@ impl IfcAPI {
     ///   }
     /// });
     /// ```
+    ///
+    /// In a Web Worker, hand batches to the render thread without copying:
+    /// ```javascript
+    /// await api.parseMeshesAsync(ifcData, {
+    ///   transferable: true,
+    ///   onBatch: (batch, progress) => postMessage({ batch, progress }, batch.transfer),
+    /// });
+    /// ```
     #[wasm_bindgen(js_name = parseMeshesAsync)]
     pub fn parse_meshes_async(&self, content: String, options: JsValue) -> js_sys::Promise {
         use super::styling::{
@@ -1191,7 +1258,7 @@ impl IfcAPI {
         // This avoids doubling WASM memory usage for large files (700MB+ saves ~700MB).
         let mut content = Some(content);
         let mut options = Some(options);
-        let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let promise = js_sys::Promise::new(&mut |resolve, reject| {
             let content = content.take().expect("content already taken");
             let options = options.take().expect("options already taken");
 
@@ -1220,6 +1287,18 @@ impl IfcAPI {
                     .ok()
                     .and_then(|v| v.dyn_into::<Function>().ok());
 
+                let transferable = js_sys::Reflect::get(&options, &"transferable".into())
+                    .ok()
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                // Returning on abort drops the decoder, content and pending batch
+                let abort = ParseAbort::from_options(&options);
+                if abort.is_aborted() {
+                    let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                    return;
+                }
+
                 // ── Phase 1: Build entity index (fast memchr scan, ~200 ms) ──
                 let entity_index = ifc_lite_core::build_entity_index(&content);
                 let mut decoder = EntityDecoder::with_index(&content, entity_index);
@@ -1229,7 +1308,11 @@ impl IfcAPI {
                 // and classifies all geometry entities into simple/complex job lists.
                 // Replaces: build_geometry_style_index + build_element_style_index +
                 //           void pre-pass + processing scan.
-                let pre_pass = combined_pre_pass(&content, &mut decoder);
+                let mut pre_pass = combined_pre_pass(&content, &mut decoder);
+                if abort.checkpoint().await {
+                    let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                    return;
+                }
 
                 // Pre-allocate decoder cache to avoid HashMap resize-and-rehash
                 // during Phase 3b/4. Each building element + shared placement/repr
@@ -1283,6 +1366,21 @@ impl IfcAPI {
                     .site_position
                     .and_then(|pos| extract_building_rotation_from_site(pos, &mut decoder));
 
+                // Drop filtered-out elements before styling them (after RTC
+                // detection, so filtered loads share the full model's offset)
+                match ParseFilter::from_options(&options, &content, &mut decoder) {
+                    Ok(Some(filter)) => {
+                        for jobs in [&mut pre_pass.simple_jobs, &mut pre_pass.complex_jobs] {
+                            jobs.retain(|&(id, _, _, ifc_type)| filter.matches(id, ifc_type));
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        let _ = reject.call1(&JsValue::NULL, &error);
+                        return;
+                    }
+                }
+
                 // ── Phase 3b: Build element style map + pre-warm decoder cache (~1.2 s) ──
                 // Iterates collected jobs (no re-scan!) to:
                 //   1. Build element_id → color map for O(1) color lookup during processing
@@ -1373,10 +1471,8 @@ impl IfcAPI {
                     // Yield batch when full
                     if batch_meshes.len() >= current_batch_size {
                         if let Some(ref callback) = on_batch {
-                            let js_meshes = js_sys::Array::new();
-                            for mesh in batch_meshes.drain(..) {
-                                js_meshes.push(&mesh.into());
-                            }
+                            let (js_meshes, count) =
+                                mesh_batch_to_js(&mut batch_meshes, transferable);
 
                             let progress = js_sys::Object::new();
                             super::set_js_prop(&progress, "percent", &0u32.into());
@@ -1384,33 +1480,35 @@ impl IfcAPI {
                             super::set_js_prop(&progress, "phase", &"simple".into());
 
                             let _ = callback.call2(&JsValue::NULL, &js_meshes, &progress);
-                            total_meshes += js_meshes.length() as usize;
+                            total_meshes += count;
                         }
 
                         // After first batch, ramp up batch size for throughput
                         current_batch_size = throughput_batch_size;
 
-                        // Yield to browser
-                        // yield removed — sync for speed
+                        if abort.checkpoint().await {
+                            let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                            return;
+                        }
                     }
                 }
 
                 // Flush remaining simple elements
                 if !batch_meshes.is_empty() {
                     if let Some(ref callback) = on_batch {
-                        let js_meshes = js_sys::Array::new();
-                        for mesh in batch_meshes.drain(..) {
-                            js_meshes.push(&mesh.into());
-                        }
+                        let (js_meshes, count) = mesh_batch_to_js(&mut batch_meshes, transferable);
 
                         let progress = js_sys::Object::new();
                         super::set_js_prop(&progress, "phase", &"simple_complete".into());
 
                         let _ = callback.call2(&JsValue::NULL, &js_meshes, &progress);
-                        total_meshes += js_meshes.length() as usize;
+                        total_meshes += count;
                     }
 
-                    // yield removed — sync for speed
+                    if abort.checkpoint().await {
+                        let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                        return;
+                    }
                 }
 
                 let total_elements = processed + pre_pass.complex_jobs.len();
@@ -1539,10 +1637,8 @@ impl IfcAPI {
                     // Yield batch (uses adaptive batch size)
                     if batch_meshes.len() >= current_batch_size {
                         if let Some(ref callback) = on_batch {
-                            let js_meshes = js_sys::Array::new();
-                            for mesh in batch_meshes.drain(..) {
-                                js_meshes.push(&mesh.into());
-                            }
+                            let (js_meshes, count) =
+                                mesh_batch_to_js(&mut batch_meshes, transferable);
 
                             let progress = js_sys::Object::new();
                             let percent = (processed as f64 / total_elements as f64 * 100.0) as u32;
@@ -1552,27 +1648,27 @@ impl IfcAPI {
                             super::set_js_prop(&progress, "phase", &"complex".into());
 
                             let _ = callback.call2(&JsValue::NULL, &js_meshes, &progress);
-                            total_meshes += js_meshes.length() as usize;
+                            total_meshes += count;
                         }
 
-                        // yield removed — sync for speed
+                        if abort.checkpoint().await {
+                            let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                            return;
+                        }
                     }
                 }
 
                 // Final flush
                 if !batch_meshes.is_empty() {
                     if let Some(ref callback) = on_batch {
-                        let js_meshes = js_sys::Array::new();
-                        for mesh in batch_meshes.drain(..) {
-                            js_meshes.push(&mesh.into());
-                        }
+                        let (js_meshes, count) = mesh_batch_to_js(&mut batch_meshes, transferable);
 
                         let progress = js_sys::Object::new();
                         super::set_js_prop(&progress, "percent", &100u32.into());
                         super::set_js_prop(&progress, "phase", &"complete".into());
 
                         let _ = callback.call2(&JsValue::NULL, &js_meshes, &progress);
-                        total_meshes += js_meshes.length() as usize;
+                        total_meshes += count;
                     }
                 }
 
@@ -1762,6 +1858,10 @@ impl IfcAPI {
     /// Yields batches of GPU-ready geometry for progressive rendering with zero-copy upload.
     /// Uses fast-first-frame streaming: simple geometry (walls, slabs) first.
     ///
+    /// Like `parseMeshesAsync`, accepts an AbortSignal as `signal` option to stop
+    /// between batches; the promise then rejects with `signal.reason`.
+    /// The `filter` option restricts which elements are processed, as there.
+    ///
     /// Example:
     /// ```javascript
     /// const api = new IfcAPI();
@@ -1801,7 +1901,7 @@ impl IfcAPI {
         // This avoids doubling WASM memory usage for large files (700MB+ saves ~700MB).
         let mut content = Some(content);
         let mut options = Some(options);
-        let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let promise = js_sys::Promise::new(&mut |resolve, reject| {
             let content = content.take().expect("content already taken");
             let options = options.take().expect("options already taken");
 
@@ -1821,6 +1921,13 @@ impl IfcAPI {
                     .ok()
                     .and_then(|v| v.dyn_into::<Function>().ok());
 
+                // Returning on abort drops the decoder, content and pending batch
+                let abort = ParseAbort::from_options(&options);
+                if abort.is_aborted() {
+                    let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                    return;
+                }
+
                 // Build entity index
                 let entity_index = build_entity_index(&content);
                 let mut decoder = EntityDecoder::with_index(&content, entity_index);
@@ -1869,6 +1976,19 @@ impl IfcAPI {
                     router.preprocess_faceted_breps(&faceted_brep_ids, &mut decoder);
                 }
 
+                if abort.checkpoint().await {
+                    let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                    return;
+                }
+
+                let filter = match ParseFilter::from_options(&options, &content, &mut decoder) {
+                    Ok(filter) => filter,
+                    Err(error) => {
+                        let _ = reject.call1(&JsValue::NULL, &error);
+                        return;
+                    }
+                };
+
                 // Reset scanner
                 scanner = EntityScanner::new(&content);
 
@@ -1909,6 +2029,12 @@ impl IfcAPI {
                     }
 
                     let ifc_type = ifc_lite_core::IfcType::from_str(type_name);
+                    if filter
+                        .as_ref()
+                        .is_some_and(|filter| !filter.matches(id, ifc_type))
+                    {
+                        continue;
+                    }
 
                     // Simple geometry: process immediately
                     if matches!(
@@ -1974,8 +2100,10 @@ impl IfcAPI {
 
                             flush_batch(&mut current_batch, &on_batch, &progress.into());
 
-                            // Yield to browser
-                            // yield removed — sync for speed
+                            if abort.checkpoint().await {
+                                let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                                return;
+                            }
                         }
                     } else {
                         // Defer complex geometry
@@ -1988,7 +2116,10 @@ impl IfcAPI {
                     let progress = js_sys::Object::new();
                     super::set_js_prop(&progress, "phase", &"simple_complete".into());
                     flush_batch(&mut current_batch, &on_batch, &progress.into());
-                    // yield removed — sync for speed
+                    if abort.checkpoint().await {
+                        let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                        return;
+                    }
                 }
 
                 // Process deferred complex geometry
@@ -2036,7 +2167,10 @@ impl IfcAPI {
                         super::set_js_prop(&progress, "phase", &"complex".into());
 
                         flush_batch(&mut current_batch, &on_batch, &progress.into());
-                        // yield removed — sync for speed
+                        if abort.checkpoint().await {
+                            let _ = reject.call1(&JsValue::NULL, &abort.reason());
+                            return;
+                        }
                     }
                 }
 
@@ -2084,10 +2218,8 @@ impl IfcAPI {
         content: String,
     ) -> GpuInstancedGeometryCollection {
         use ifc_lite_core::{build_entity_index, EntityDecoder, EntityScanner};
-        use ifc_lite_geometry::{calculate_normals, GeometryRouter, Mesh};
+        use ifc_lite_geometry::{GeometryRouter, Mesh};
         use rustc_hash::FxHashMap;
-        use rustc_hash::FxHasher;
-        use std::hash::{Hash, Hasher};
 
         // Build entity index
         let entity_index = build_entity_index(&content);
@@ -2130,47 +2262,23 @@ impl IfcAPI {
             }
 
             if let Ok(entity) = decoder.decode_at_with_id(id, start, end) {
-                if let Ok((mut mesh, transform)) =
-                    router.process_element_with_transform(&entity, &mut decoder)
+                if let Some((geometry_id, transform_array, mesh)) =
+                    instanced_element_geometry(&router, &entity, &mut decoder)
                 {
-                    if !mesh.is_empty() {
-                        if mesh.normals.len() != mesh.positions.len() {
-                            calculate_normals(&mut mesh);
-                        }
-
-                        // Compute geometry hash
-                        let mut hasher = FxHasher::default();
-                        mesh.positions.len().hash(&mut hasher);
-                        mesh.indices.len().hash(&mut hasher);
-                        for pos in &mesh.positions {
-                            pos.to_bits().hash(&mut hasher);
-                        }
-                        for idx in &mesh.indices {
-                            idx.hash(&mut hasher);
-                        }
-                        let geometry_hash = hasher.finish();
-
-                        // Get color
-                        let color = style_index
-                            .get(&id)
-                            .copied()
-                            .unwrap_or_else(|| get_default_color_for_type(&entity.ifc_type));
+                    let color = style_index
+                        .get(&id)
+                        .copied()
+                        .unwrap_or_else(|| get_default_color_for_type(&entity.ifc_type));
 
-                        // Convert transform to column-major array
-                        let mut transform_array = [0.0f64; 16];
-                        for col in 0..4 {
-                            for row in 0..4 {
-                                transform_array[col * 4 + row] = transform[(row, col)];
-                            }
+                    // Only the first instance of a geometry brings its mesh
+                    match geometry_groups.entry(geometry_id) {
+                        std::collections::hash_map::Entry::Occupied(mut o) => {
+                            o.get_mut().1.push((id, transform_array, color));
                         }
-
-                        // Add to group
-                        let entry = geometry_groups.entry(geometry_hash);
-                        match entry {
-                            std::collections::hash_map::Entry::Occupied(mut o) => {
-                                o.get_mut().1.push((id, transform_array, color));
-                            }
-                            std::collections::hash_map::Entry::Vacant(v) => {
+                        std::collections::hash_map::Entry::Vacant(v) => {
+                            if let Some(mesh) =
+                                instanced_group_mesh(&router, geometry_id, mesh, &mut decoder)
+                            {
                                 v.insert((mesh, vec![(id, transform_array, color)]));
                             }
                         }
@@ -2735,9 +2843,8 @@ impl IfcAPI {
         style_colors: &[u8],
     ) -> InstancedMeshCollection {
         use ifc_lite_core::EntityDecoder;
-        use ifc_lite_geometry::{calculate_normals, GeometryRouter, Mesh};
-        use rustc_hash::{FxHashMap, FxHasher};
-        use std::hash::{Hash, Hasher};
+        use ifc_lite_geometry::{GeometryRouter, Mesh};
+        use rustc_hash::FxHashMap;
 
         let content = decode_ifc_bytes(data);
 
@@ -2810,46 +2917,25 @@ impl IfcAPI {
                     continue;
                 }
 
-                if let Ok((mut mesh, transform)) =
-                    router.process_element_with_transform(&entity, &mut decoder)
+                if let Some((geometry_id, transform_array, mesh)) =
+                    instanced_element_geometry(&router, &entity, &mut decoder)
                 {
-                    if mesh.is_empty() {
-                        continue;
-                    }
-
-                    if mesh.normals.len() != mesh.positions.len() {
-                        calculate_normals(&mut mesh);
-                    }
-
-                    let mut hasher = FxHasher::default();
-                    mesh.positions.len().hash(&mut hasher);
-                    mesh.indices.len().hash(&mut hasher);
-                    for pos in &mesh.positions {
-                        pos.to_bits().hash(&mut hasher);
-                    }
-                    for idx in &mesh.indices {
-                        idx.hash(&mut hasher);
-                    }
-                    let geometry_hash = hasher.finish();
-
                     let color = element_styles
                         .get(&id)
                         .copied()
                         .unwrap_or_else(|| get_default_color_for_type(&entity.ifc_type));
 
-                    let mut transform_array = [0.0; 16];
-                    for col in 0..4 {
-                        for row in 0..4 {
-                            transform_array[col * 4 + row] = transform[(row, col)];
+                    // Only the first instance of a geometry brings its mesh
+                    match geometry_groups.entry(geometry_id) {
+                        std::collections::hash_map::Entry::Occupied(mut o) => {
+                            o.get_mut().1.push((id, transform_array, color));
                         }
-                    }
-
-                    match geometry_groups.entry(geometry_hash) {
-                        std::collections::hash_map::Entry::Occupied(mut entry) => {
-                            entry.get_mut().1.push((id, transform_array, color));
-                        }
-                        std::collections::hash_map::Entry::Vacant(entry) => {
-                            entry.insert((mesh, vec![(id, transform_array, color)]));
+                        std::collections::hash_map::Entry::Vacant(v) => {
+                            if let Some(mesh) =
+                                instanced_group_mesh(&router, geometry_id, mesh, &mut decoder)
+                            {
+                                v.insert((mesh, vec![(id, transform_array, color)]));
+                            }
                         }
                     }
                 }
@@ -0,0 +1,60 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! WASM API: getElementByGuid — resolve a GlobalId to an element.
+
+use super::{set_js_prop, IfcAPI};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl IfcAPI {
+    /// Look up an element by its 22-character GlobalId, e.g. to navigate to
+    /// the components of a BCF topic.
+    ///
+    /// Returns `null` if no entity has the GlobalId, otherwise
+    /// `{ expressId, type, name, container }` where `container` is
+    /// `{ expressId, type, name }` of the nearest spatial element (storey,
+    /// space, ...) holding it, or `null` if it is not placed in the spatial
+    /// structure.
+    ///
+    /// ```javascript
+    /// const element = api.getElementByGuid(ifcContent, '2O2Fr$t4X7Zf8NOew3FLOH');
+    /// if (element) viewer.select(element.expressId);
+    /// ```
+    #[wasm_bindgen(js_name = getElementByGuid)]
+    pub fn get_element_by_guid(&self, content: String, guid: String) -> JsValue {
+        self.with_model_cache(&content, |cache, decoder| {
+            let Some(express_id) = cache.guids(&content).get(guid.trim()) else {
+                return JsValue::NULL;
+            };
+            let Ok(entity) = decoder.decode_by_id(express_id) else {
+                return JsValue::NULL;
+            };
+            // IfcRoot attr 2: Name
+            let element = entity_summary(express_id, entity.ifc_type.name(), entity.get_string(2));
+
+            let container = cache
+                .spatial_tree(&content, decoder)
+                .and_then(|tree| tree.spatial_container(express_id))
+                .map(|node| {
+                    entity_summary(node.express_id, node.ifc_type.name(), node.name.as_deref())
+                })
+                .unwrap_or(JsValue::NULL);
+            set_js_prop(&element, "container", &container);
+            element
+        })
+    }
+}
+
+fn entity_summary(express_id: u32, ifc_type: &str, name: Option<&str>) -> JsValue {
+    let obj = js_sys::Object::new();
+    set_js_prop(&obj, "expressId", &express_id.into());
+    set_js_prop(&obj, "type", &ifc_type.into());
+    set_js_prop(
+        &obj,
+        "name",
+        &name.map(JsValue::from).unwrap_or(JsValue::NULL),
+    );
+    obj.into()
+}
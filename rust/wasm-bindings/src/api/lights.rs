@@ -0,0 +1,198 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! WASM API: parseLights — authored IfcLightSource lighting.
+
+use super::IfcAPI;
+use ifc_lite_geometry::{Light, LightKind};
+use wasm_bindgen::prelude::*;
+
+/// One light source. Positions and directions are in WebGL Y-up world
+/// space (metres), with the same RTC offset as `parseMeshes`.
+#[wasm_bindgen]
+pub struct LightJs {
+    light: Light,
+}
+
+#[wasm_bindgen]
+impl LightJs {
+    /// Express ID of the IfcLightSource
+    #[wasm_bindgen(getter, js_name = expressId)]
+    pub fn express_id(&self) -> u32 {
+        self.light.express_id
+    }
+
+    /// Express ID of the element (e.g. IfcLightFixture) carrying the light
+    #[wasm_bindgen(getter, js_name = elementId)]
+    pub fn element_id(&self) -> Option<u32> {
+        self.light.element_id
+    }
+
+    /// "ambient", "directional", "positional" or "spot"
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        match self.light.kind {
+            LightKind::Ambient => "ambient",
+            LightKind::Directional { .. } => "directional",
+            LightKind::Positional { .. } => "positional",
+            LightKind::Spot { .. } => "spot",
+        }
+        .to_string()
+    }
+
+    /// Light name
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> Option<String> {
+        self.light.name.clone()
+    }
+
+    /// Colour as [r, g, b], 0..1
+    #[wasm_bindgen(getter)]
+    pub fn color(&self) -> Vec<f32> {
+        self.light.color.to_vec()
+    }
+
+    /// Direct intensity, 0..1
+    #[wasm_bindgen(getter)]
+    pub fn intensity(&self) -> f64 {
+        self.light.intensity
+    }
+
+    /// Contribution to the ambient light, 0..1
+    #[wasm_bindgen(getter, js_name = ambientIntensity)]
+    pub fn ambient_intensity(&self) -> f64 {
+        self.light.ambient_intensity
+    }
+
+    /// Position [x, y, z] of positional and spot lights
+    #[wasm_bindgen(getter)]
+    pub fn position(&self) -> Option<Vec<f64>> {
+        self.light.position().map(|p| vec![p.x, p.z, -p.y])
+    }
+
+    /// Unit direction [x, y, z] of directional and spot lights
+    #[wasm_bindgen(getter)]
+    pub fn direction(&self) -> Option<Vec<f64>> {
+        self.light.direction().map(|d| vec![d.x, d.z, -d.y])
+    }
+
+    /// Range in metres of positional and spot lights (0 when unbounded)
+    #[wasm_bindgen(getter)]
+    pub fn radius(&self) -> f64 {
+        match self.light.kind {
+            LightKind::Positional { radius, .. } | LightKind::Spot { radius, .. } => radius,
+            _ => 0.0,
+        }
+    }
+
+    /// Attenuation [constant, distance, quadric] of positional and spot lights
+    #[wasm_bindgen(getter)]
+    pub fn attenuation(&self) -> Option<Vec<f64>> {
+        match self.light.kind {
+            LightKind::Positional { attenuation, .. } | LightKind::Spot { attenuation, .. } => {
+                Some(vec![
+                    attenuation.constant,
+                    attenuation.distance,
+                    attenuation.quadric,
+                ])
+            }
+            _ => None,
+        }
+    }
+
+    /// Half angle of a spot light's cone, in radians
+    #[wasm_bindgen(getter, js_name = spreadAngle)]
+    pub fn spread_angle(&self) -> Option<f64> {
+        match self.light.kind {
+            LightKind::Spot { spread_angle, .. } => Some(spread_angle),
+            _ => None,
+        }
+    }
+
+    /// Half angle of a spot light's full-intensity cone, in radians
+    #[wasm_bindgen(getter, js_name = beamWidthAngle)]
+    pub fn beam_width_angle(&self) -> Option<f64> {
+        match self.light.kind {
+            LightKind::Spot {
+                beam_width_angle, ..
+            } => Some(beam_width_angle),
+            _ => None,
+        }
+    }
+
+    /// Falloff of a spot light's intensity away from its axis
+    #[wasm_bindgen(getter, js_name = concentrationExponent)]
+    pub fn concentration_exponent(&self) -> Option<f64> {
+        match self.light.kind {
+            LightKind::Spot {
+                concentration_exponent,
+                ..
+            } => Some(concentration_exponent),
+            _ => None,
+        }
+    }
+}
+
+/// Light sources of a model
+#[wasm_bindgen]
+pub struct LightCollection {
+    lights: Vec<Light>,
+}
+
+#[wasm_bindgen]
+impl LightCollection {
+    /// Number of lights
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.lights.len()
+    }
+
+    /// Light at `index`
+    pub fn get(&self, index: usize) -> Option<LightJs> {
+        self.lights.get(index).map(|light| LightJs {
+            light: light.clone(),
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl IfcAPI {
+    /// Extract the model's authored lights (IfcLightSourceAmbient,
+    /// Directional, Positional and Spot), placed with their light fixtures.
+    ///
+    /// ```javascript
+    /// const api = new IfcAPI();
+    /// const lights = api.parseLights(ifcContent);
+    /// for (let i = 0; i < lights.length; i++) {
+    ///   const light = lights.get(i);
+    ///   if (light.kind === 'spot') {
+    ///     const spot = new THREE.SpotLight(new THREE.Color(...light.color), light.intensity);
+    ///     spot.position.fromArray(light.position);
+    ///     spot.angle = light.spreadAngle;
+    ///     scene.add(spot);
+    ///   }
+    /// }
+    /// ```
+    #[wasm_bindgen(js_name = parseLights)]
+    pub fn parse_lights(&self, content: String) -> LightCollection {
+        use ifc_lite_core::{build_entity_index, EntityDecoder};
+
+        let entity_index = build_entity_index(&content);
+        let mut decoder = EntityDecoder::with_index(&content, entity_index);
+
+        // Same unit scale and RTC offset as mesh parsing so lights line up
+        let mut router = ifc_lite_geometry::GeometryRouter::with_units(&content, &mut decoder);
+        let rtc_offset = router.detect_rtc_offset_from_first_element(&content, &mut decoder);
+        if rtc_offset.0.abs() > 10000.0
+            || rtc_offset.1.abs() > 10000.0
+            || rtc_offset.2.abs() > 10000.0
+        {
+            router.set_rtc_offset(rtc_offset);
+        }
+
+        LightCollection {
+            lights: router.extract_lights(&content, &mut decoder).lights,
+        }
+    }
+}
@@ -6,13 +6,24 @@
 //!
 //! Modern async/await API for parsing IFC files.
 
+mod abort;
+mod annotations;
+mod bounds;
 mod debug;
 mod extract_profiles;
 mod georef;
 mod gpu_meshes;
+mod guid;
+mod lights;
+mod model_cache;
+mod parse_filter;
 mod parsing;
+mod properties;
+mod relationships;
+mod spatial_tree;
 pub(crate) mod styling;
 mod symbolic;
+mod types;
 mod zero_copy_api;
 
 use std::cell::RefCell;
@@ -222,6 +233,10 @@ pub struct IfcAPI {
     initialized: bool,
     /// Cached entity index from buildPrePassOnce, reused by processGeometryBatch
     cached_entity_index: RefCell<Option<EntityIndex>>,
+    /// Entities reported by the last parseMeshes, served by getDebugMeshes
+    debug_entities: RefCell<Vec<debug::DebugEntity>>,
+    /// Indexes for the element query methods (getPropertySets, getTypeCensus, ...)
+    model_cache: RefCell<Option<model_cache::ModelCache>>,
 }
 
 #[wasm_bindgen]
@@ -232,7 +247,12 @@ impl IfcAPI {
         #[cfg(feature = "console_error_panic_hook")]
         console_error_panic_hook::set_once();
 
-        Self { initialized: true, cached_entity_index: RefCell::new(None) }
+        Self {
+            initialized: true,
+            cached_entity_index: RefCell::new(None),
+            debug_entities: RefCell::new(Vec::new()),
+            model_cache: RefCell::new(None),
+        }
     }
 
     /// Check if API is initialized
@@ -247,6 +267,62 @@ impl IfcAPI {
         self.cached_entity_index.borrow_mut().take();
     }
 
+    /// Release the caches the API keeps between calls (pre-pass entity
+    /// index, debug overlay, element query indexes). Call `free()`
+    /// afterwards if the API itself is no longer needed; mesh results are
+    /// freed separately with their own `free()`.
+    #[wasm_bindgen]
+    pub fn dispose(&self) {
+        self.cached_entity_index.replace(None);
+        self.debug_entities.replace(Vec::new());
+        self.model_cache.replace(None);
+    }
+
+    /// Report WASM memory usage, to spot leaks in long-running sessions.
+    ///
+    /// Returns `{ linearMemoryBytes, liveMeshes, liveMeshCollections,
+    /// liveGpuGeometries }`: the size of the linear memory (which never
+    /// shrinks) and how many mesh wrappers JavaScript still holds. Counts
+    /// that keep growing across model loads mean results are not `free()`d.
+    ///
+    /// ```javascript
+    /// const stats = api.memoryStats();
+    /// console.log(`${stats.linearMemoryBytes / 1e6} MB, ${stats.liveMeshes} meshes alive`);
+    /// ```
+    #[wasm_bindgen(js_name = memoryStats)]
+    pub fn memory_stats(&self) -> JsValue {
+        use crate::memory::{live, LIVE_GPU_GEOMETRIES, LIVE_MESHES, LIVE_MESH_COLLECTIONS};
+
+        let linear_memory_bytes = crate::zero_copy::get_memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .map(|memory| {
+                memory
+                    .buffer()
+                    .unchecked_into::<js_sys::ArrayBuffer>()
+                    .byte_length()
+            })
+            .unwrap_or(0);
+
+        let stats = js_sys::Object::new();
+        set_js_prop(
+            &stats,
+            "linearMemoryBytes",
+            &(linear_memory_bytes as f64).into(),
+        );
+        set_js_prop(&stats, "liveMeshes", &(live(&LIVE_MESHES) as f64).into());
+        set_js_prop(
+            &stats,
+            "liveMeshCollections",
+            &(live(&LIVE_MESH_COLLECTIONS) as f64).into(),
+        );
+        set_js_prop(
+            &stats,
+            "liveGpuGeometries",
+            &(live(&LIVE_GPU_GEOMETRIES) as f64).into(),
+        );
+        stats.into()
+    }
+
     /// Get WASM memory for zero-copy access
     #[wasm_bindgen(js_name = getMemory)]
     pub fn get_memory(&self) -> JsValue {
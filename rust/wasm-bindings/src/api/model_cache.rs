@@ -0,0 +1,106 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Lookup indexes for the element query methods
+//!
+//! Methods like `getPropertySets` are called once per selected element, so
+//! the indexes they need are built on the first call and kept until a call
+//! passes different content (or `dispose()` is called).
+
+use super::IfcAPI;
+use ifc_lite_core::{
+    build_entity_index, build_spatial_tree, content_hash, EntityDecoder, EntityIndex, GuidIndex,
+    PropertySetIndex, RelationshipIndex, SpatialNode, TypeIndex,
+};
+use std::cell::OnceCell;
+use std::sync::Arc;
+
+/// Indexes of the model last passed to an element query method. Indexes
+/// beyond the entity index are built when a method first needs them.
+pub(crate) struct ModelCache {
+    /// Content hash of the model
+    key: u128,
+    entities: Arc<EntityIndex>,
+    guids: OnceCell<GuidIndex>,
+    property_sets: OnceCell<PropertySetIndex>,
+    relationships: OnceCell<RelationshipIndex>,
+    spatial_tree: OnceCell<Option<SpatialNode>>,
+    types: OnceCell<TypeIndex>,
+}
+
+impl ModelCache {
+    fn new(content: &str, key: u128) -> Self {
+        Self {
+            key,
+            entities: Arc::new(build_entity_index(content)),
+            guids: OnceCell::new(),
+            property_sets: OnceCell::new(),
+            relationships: OnceCell::new(),
+            spatial_tree: OnceCell::new(),
+            types: OnceCell::new(),
+        }
+    }
+
+    /// Express IDs by GlobalId
+    pub(crate) fn guids(&self, content: &str) -> &GuidIndex {
+        self.guids.get_or_init(|| GuidIndex::build(content))
+    }
+
+    /// Property set assignments
+    pub(crate) fn property_sets(
+        &self,
+        content: &str,
+        decoder: &mut EntityDecoder,
+    ) -> &PropertySetIndex {
+        self.property_sets
+            .get_or_init(|| PropertySetIndex::build(content, decoder))
+    }
+
+    /// Relationships by kind and entity
+    pub(crate) fn relationships(
+        &self,
+        content: &str,
+        decoder: &mut EntityDecoder,
+    ) -> &RelationshipIndex {
+        self.relationships
+            .get_or_init(|| RelationshipIndex::build(content, decoder))
+    }
+
+    /// Spatial structure, `None` for models without an IfcProject
+    pub(crate) fn spatial_tree(
+        &self,
+        content: &str,
+        decoder: &mut EntityDecoder,
+    ) -> Option<&SpatialNode> {
+        self.spatial_tree
+            .get_or_init(|| build_spatial_tree(content, decoder).ok().flatten())
+            .as_ref()
+    }
+
+    /// Express IDs by entity type
+    pub(crate) fn types(&self, content: &str) -> &TypeIndex {
+        self.types.get_or_init(|| TypeIndex::build(content))
+    }
+}
+
+impl IfcAPI {
+    /// Run `query` against the cached indexes of `content`, building them
+    /// first if `content` is not the cached model
+    pub(crate) fn with_model_cache<R>(
+        &self,
+        content: &str,
+        query: impl FnOnce(&ModelCache, &mut EntityDecoder) -> R,
+    ) -> R {
+        let key = content_hash(content.as_bytes());
+        let mut cache = self.model_cache.borrow_mut();
+        if cache.as_ref().is_none_or(|cache| cache.key != key) {
+            // Drop the old indexes before building the new ones
+            *cache = None;
+            *cache = Some(ModelCache::new(content, key));
+        }
+        let cache = cache.as_ref().expect("model cache was just built");
+        let mut decoder = EntityDecoder::with_arc_index(content, cache.entities.clone());
+        query(cache, &mut decoder)
+    }
+}
@@ -0,0 +1,306 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Selective loading for the async mesh methods
+//!
+//! The `filter` option restricts which elements are tessellated:
+//!
+//! ```javascript
+//! await api.parseMeshesAsync(content, {
+//!   filter: {
+//!     includeTypes: ['IfcWall', 'IfcSlab', 'IfcColumn'], // subtypes included
+//!     excludeTypes: ['IfcFurniture'],
+//!     storeys: [storeyId],       // express IDs, e.g. from getSpatialTree
+//!     expressIds: [101, 102],
+//!     skipSpaces: true,
+//!     skipOpenings: true,
+//!   },
+//!   onBatch,
+//! });
+//! ```
+//!
+//! All given criteria must match. Openings still cut their host elements when
+//! they are skipped, and the RTC offset is detected from the whole model so
+//! filtered loads line up with full ones. Unknown type names reject the parse;
+//! IFC4 types removed in IFC4X3 (e.g. `IfcBeamStandardCase`) count as their
+//! base type.
+
+use ifc_lite_core::{
+    build_spatial_tree, map_legacy_to_base_type, EntityDecoder, IfcType, SpatialNode,
+};
+use rustc_hash::FxHashSet;
+use wasm_bindgen::prelude::*;
+
+/// IFC4 element types without a variant in the IFC4X3 schema; they scan as
+/// `IfcType::Unknown` and are matched as their legacy base type
+const LEGACY_ELEMENT_TYPES: &[&str] = &[
+    "IFCBEAMSTANDARDCASE",
+    "IFCCOLUMNSTANDARDCASE",
+    "IFCDOORSTANDARDCASE",
+    "IFCMEMBERSTANDARDCASE",
+    "IFCOPENINGSTANDARDCASE",
+    "IFCPLATESTANDARDCASE",
+    "IFCSLABELEMENTEDCASE",
+    "IFCSLABSTANDARDCASE",
+    "IFCWALLELEMENTEDCASE",
+    "IFCWINDOWSTANDARDCASE",
+];
+
+/// `options.filter` as read from JavaScript
+#[derive(Debug, Default)]
+struct FilterOptions {
+    include_types: Vec<String>,
+    exclude_types: Vec<String>,
+    storeys: Option<Vec<u32>>,
+    express_ids: Option<Vec<u32>>,
+    skip_spaces: bool,
+    skip_openings: bool,
+}
+
+/// Elements to process, read from `options.filter`
+pub(crate) struct ParseFilter {
+    include_types: Vec<IfcType>,
+    exclude_types: Vec<IfcType>,
+    /// Everything inside the requested storeys, if restricted
+    storey_members: Option<FxHashSet<u32>>,
+    express_ids: Option<FxHashSet<u32>>,
+    /// Scanned type of each legacy element type, with its base type
+    legacy_types: Vec<(IfcType, IfcType)>,
+}
+
+impl ParseFilter {
+    /// Read `options.filter`; `Ok(None)` without one, so unfiltered parses
+    /// skip the per-element checks. Errors on unknown type names.
+    pub(crate) fn from_options(
+        options: &JsValue,
+        content: &str,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Option<Self>, JsValue> {
+        let Some(filter) = js_sys::Reflect::get(options, &"filter".into())
+            .ok()
+            .filter(JsValue::is_object)
+        else {
+            return Ok(None);
+        };
+
+        let options = FilterOptions {
+            include_types: string_list(&filter, "includeTypes"),
+            exclude_types: string_list(&filter, "excludeTypes"),
+            storeys: id_list(&filter, "storeys"),
+            express_ids: id_list(&filter, "expressIds"),
+            skip_spaces: flag(&filter, "skipSpaces"),
+            skip_openings: flag(&filter, "skipOpenings"),
+        };
+        Self::new(options, content, decoder)
+            .map(Some)
+            .map_err(|message| js_sys::Error::new(&message).into())
+    }
+
+    fn new(
+        options: FilterOptions,
+        content: &str,
+        decoder: &mut EntityDecoder,
+    ) -> Result<Self, String> {
+        let types = |names: &[String], key: &str| -> Result<Vec<IfcType>, String> {
+            names
+                .iter()
+                .map(|name| {
+                    filter_type(name)
+                        .ok_or_else(|| format!("Unknown IFC type '{name}' in filter.{key}"))
+                })
+                .collect()
+        };
+        let include_types = types(&options.include_types, "includeTypes")?;
+        let mut exclude_types = types(&options.exclude_types, "excludeTypes")?;
+        if options.skip_spaces {
+            exclude_types.push(IfcType::IfcSpace);
+        }
+        if options.skip_openings {
+            exclude_types.push(IfcType::IfcFeatureElementSubtraction);
+        }
+
+        let storey_members = options.storeys.map(|storeys| {
+            let tree = build_spatial_tree(content, decoder).ok().flatten();
+            let mut members = FxHashSet::default();
+            for storey in storeys {
+                if let Some(node) = tree.as_ref().and_then(|tree| tree.find(storey)) {
+                    collect_ids(node, &mut members);
+                }
+            }
+            members
+        });
+
+        Ok(Self {
+            include_types,
+            exclude_types,
+            storey_members,
+            express_ids: options.express_ids.map(|ids| ids.into_iter().collect()),
+            legacy_types: LEGACY_ELEMENT_TYPES
+                .iter()
+                .filter_map(|&name| Some((IfcType::from_str(name), map_legacy_to_base_type(name)?)))
+                .collect(),
+        })
+    }
+
+    /// Whether the element passes every criterion
+    pub(crate) fn matches(&self, express_id: u32, ifc_type: IfcType) -> bool {
+        let ifc_type = match ifc_type {
+            IfcType::Unknown(_) => self
+                .legacy_types
+                .iter()
+                .find(|&&(legacy, _)| legacy == ifc_type)
+                .map_or(ifc_type, |&(_, base)| base),
+            _ => ifc_type,
+        };
+        let is_a = |types: &[IfcType]| types.iter().any(|&t| ifc_type.is_subtype_of(t));
+        (self.include_types.is_empty() || is_a(&self.include_types))
+            && !is_a(&self.exclude_types)
+            && self
+                .storey_members
+                .as_ref()
+                .is_none_or(|members| members.contains(&express_id))
+            && self
+                .express_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&express_id))
+    }
+}
+
+/// Schema type for a filter name (any case), resolving legacy IFC4 types;
+/// `None` for names the schema doesn't know
+fn filter_type(name: &str) -> Option<IfcType> {
+    let upper = name.to_ascii_uppercase();
+    map_legacy_to_base_type(&upper)
+        .or_else(|| Some(IfcType::from_str(&upper)))
+        .filter(|ifc_type| !matches!(ifc_type, IfcType::Unknown(_)))
+}
+
+/// Express IDs of a node and everything below it
+fn collect_ids(node: &SpatialNode, ids: &mut FxHashSet<u32>) {
+    ids.insert(node.express_id);
+    for child in node.children.iter().chain(&node.elements) {
+        collect_ids(child, ids);
+    }
+}
+
+fn flag(filter: &JsValue, key: &str) -> bool {
+    js_sys::Reflect::get(filter, &key.into())
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+fn string_list(filter: &JsValue, key: &str) -> Vec<String> {
+    array(filter, key)
+        .map(|items| items.iter().filter_map(|v| v.as_string()).collect())
+        .unwrap_or_default()
+}
+
+/// `None` if the key is absent, so an empty list filters out everything
+fn id_list(filter: &JsValue, key: &str) -> Option<Vec<u32>> {
+    array(filter, key).map(|items| {
+        items
+            .iter()
+            .filter_map(|v| v.as_f64())
+            .map(|id| id as u32)
+            .collect()
+    })
+}
+
+/// Arrays and typed arrays (`Uint32Array` of express IDs)
+fn array(filter: &JsValue, key: &str) -> Option<js_sys::Array> {
+    let value = js_sys::Reflect::get(filter, &key.into()).ok()?;
+    if value.is_undefined() || value.is_null() {
+        return None;
+    }
+    Some(js_sys::Array::from(&value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = r#"
+#1=IFCPROJECT('p',$,'Project',$,$,$,$,$,$);
+#10=IFCBUILDINGSTOREY('g',$,'Ground',$,$,$,$,$,.ELEMENT.,0.);
+#11=IFCBUILDINGSTOREY('u',$,'Upper',$,$,$,$,$,.ELEMENT.,3000.);
+#20=IFCWALL('w',$,'Wall',$,$,$,$,$,$);
+#21=IFCSLAB('s',$,'Slab',$,$,$,$,$,$);
+#30=IFCRELAGGREGATES('r1',$,$,$,#1,(#10,#11));
+#31=IFCRELCONTAINEDINSPATIALSTRUCTURE('r2',$,$,$,(#20),#10);
+#32=IFCRELCONTAINEDINSPATIALSTRUCTURE('r3',$,$,$,(#21),#11);
+"#;
+
+    fn build(options: FilterOptions) -> Result<ParseFilter, String> {
+        ParseFilter::new(options, CONTENT, &mut EntityDecoder::new(CONTENT))
+    }
+
+    #[test]
+    fn test_types() {
+        let filter = build(FilterOptions {
+            include_types: vec!["IfcBuildingElement".into(), "IfcBeamStandardCase".into()],
+            exclude_types: vec!["IFCSLAB".into()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(filter.matches(1, IfcType::IfcWall));
+        assert!(filter.matches(1, IfcType::IfcWallStandardCase));
+        assert!(filter.matches(1, IfcType::from_str("IFCCOLUMNSTANDARDCASE")));
+        assert!(!filter.matches(1, IfcType::IfcSlab));
+        assert!(!filter.matches(1, IfcType::IfcSpace));
+
+        let error = build(FilterOptions {
+            include_types: vec!["IfcWal".into()],
+            ..Default::default()
+        })
+        .err();
+        assert_eq!(
+            error.as_deref(),
+            Some("Unknown IFC type 'IfcWal' in filter.includeTypes")
+        );
+    }
+
+    #[test]
+    fn test_skip_spaces_and_openings() {
+        let filter = build(FilterOptions {
+            skip_spaces: true,
+            skip_openings: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!filter.matches(1, IfcType::IfcSpace));
+        assert!(!filter.matches(1, IfcType::IfcOpeningElement));
+        assert!(!filter.matches(1, IfcType::from_str("IFCOPENINGSTANDARDCASE")));
+        assert!(filter.matches(1, IfcType::IfcWall));
+        assert!(filter.matches(1, IfcType::from_str("IFCUNKNOWNTHING")));
+    }
+
+    #[test]
+    fn test_storeys_and_express_ids() {
+        let filter = build(FilterOptions {
+            storeys: Some(vec![10]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(filter.matches(20, IfcType::IfcWall));
+        assert!(!filter.matches(21, IfcType::IfcSlab));
+
+        let filter = build(FilterOptions {
+            storeys: Some(vec![10, 11]),
+            express_ids: Some(vec![21]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!filter.matches(20, IfcType::IfcWall));
+        assert!(filter.matches(21, IfcType::IfcSlab));
+
+        // An empty list filters out everything
+        let filter = build(FilterOptions {
+            express_ids: Some(Vec::new()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!filter.matches(20, IfcType::IfcWall));
+    }
+}
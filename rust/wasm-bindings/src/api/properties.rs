@@ -0,0 +1,100 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! WASM API: getPropertySets — property and quantity sets of an element.
+
+use super::{set_js_prop, IfcAPI};
+use ifc_lite_core::{PropertySet, PropertySetKind, PropertyValue};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl IfcAPI {
+    /// Get the property and quantity sets of an element, including those
+    /// inherited from its type.
+    ///
+    /// Returns an array of
+    /// `{ id, name, kind: 'properties' | 'quantities', fromType, properties }`
+    /// where each property is `{ name, value, type }`. Values are numbers,
+    /// strings, booleans, `null`, arrays (list and enumerated values),
+    /// `{ lower, upper }` (bounded values) or `{ ref }` (entity references);
+    /// `type` is the IFC value type, e.g. `IFCLABEL` or `IfcQuantityArea`.
+    ///
+    /// Indexes are built on the first call and reused while `content` stays
+    /// the same.
+    ///
+    /// ```javascript
+    /// for (const pset of api.getPropertySets(ifcContent, expressId)) {
+    ///   for (const { name, value } of pset.properties) {
+    ///     console.log(`${pset.name}.${name} = ${value}`);
+    ///   }
+    /// }
+    /// ```
+    #[wasm_bindgen(js_name = getPropertySets)]
+    pub fn get_property_sets(&self, content: String, express_id: u32) -> js_sys::Array {
+        let sets = self.with_model_cache(&content, |cache, decoder| {
+            cache
+                .property_sets(&content, decoder)
+                .property_sets(express_id, decoder)
+                .unwrap_or_default()
+        });
+        sets.iter().map(property_set_to_js).collect()
+    }
+}
+
+fn property_set_to_js(set: &PropertySet) -> JsValue {
+    let obj = js_sys::Object::new();
+    set_js_prop(&obj, "id", &set.id.into());
+    set_js_prop(&obj, "name", &set.name.as_str().into());
+    let kind = match set.kind {
+        PropertySetKind::Properties => "properties",
+        PropertySetKind::Quantities => "quantities",
+    };
+    set_js_prop(&obj, "kind", &kind.into());
+    set_js_prop(&obj, "fromType", &set.from_type.into());
+
+    let properties: js_sys::Array = set
+        .properties
+        .iter()
+        .map(|property| {
+            let obj = js_sys::Object::new();
+            set_js_prop(&obj, "name", &property.name.as_str().into());
+            set_js_prop(&obj, "value", &property_value_to_js(&property.value));
+            let value_type = property
+                .value_type
+                .as_deref()
+                .map(JsValue::from)
+                .unwrap_or(JsValue::NULL);
+            set_js_prop(&obj, "type", &value_type);
+            JsValue::from(obj)
+        })
+        .collect();
+    set_js_prop(&obj, "properties", &properties);
+    obj.into()
+}
+
+fn property_value_to_js(value: &PropertyValue) -> JsValue {
+    match value {
+        PropertyValue::Null => JsValue::NULL,
+        PropertyValue::Boolean(b) => (*b).into(),
+        PropertyValue::Integer(i) => (*i as f64).into(),
+        PropertyValue::Real(f) => (*f).into(),
+        PropertyValue::Text(s) => s.as_str().into(),
+        PropertyValue::Reference(id) => {
+            let obj = js_sys::Object::new();
+            set_js_prop(&obj, "ref", &(*id).into());
+            obj.into()
+        }
+        PropertyValue::List(values) => values
+            .iter()
+            .map(property_value_to_js)
+            .collect::<js_sys::Array>()
+            .into(),
+        PropertyValue::Range { lower, upper } => {
+            let obj = js_sys::Object::new();
+            set_js_prop(&obj, "lower", &property_value_to_js(lower));
+            set_js_prop(&obj, "upper", &property_value_to_js(upper));
+            obj.into()
+        }
+    }
+}
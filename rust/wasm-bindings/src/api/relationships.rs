@@ -0,0 +1,57 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! WASM API: getRelated — follow relationships between entities.
+
+use super::{set_js_prop, IfcAPI};
+use ifc_lite_core::RelationKind;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl IfcAPI {
+    /// Get the entities linked to an entity by one kind of relationship.
+    ///
+    /// `relationKind` is one of `contained-in`, `decomposes`, `fills`,
+    /// `voids`, `connects`, `defines-by-type`, `defines-by-properties` or
+    /// `associates`. Returns
+    /// `{ relating, related }` (both `Uint32Array`): `relating` holds the
+    /// other side of relationships the entity is the related side of, and
+    /// vice versa. For example a window's `fills` relating side is its
+    /// opening, an opening's `voids` relating side is its host wall, and a
+    /// storey's `contained-in` related side is its elements.
+    ///
+    /// Throws for an unknown `relationKind`.
+    ///
+    /// ```javascript
+    /// // Host wall of a window
+    /// const [opening] = api.getRelated(ifcContent, windowId, 'fills').relating;
+    /// const [wall] = api.getRelated(ifcContent, opening, 'voids').relating;
+    /// ```
+    #[wasm_bindgen(js_name = getRelated)]
+    pub fn get_related(
+        &self,
+        content: String,
+        express_id: u32,
+        relation_kind: String,
+    ) -> Result<JsValue, JsValue> {
+        let kind = RelationKind::from_name(&relation_kind).ok_or_else(|| {
+            js_sys::Error::new(&format!("Unknown relation kind '{relation_kind}'"))
+        })?;
+        Ok(self.with_model_cache(&content, |cache, decoder| {
+            let relationships = cache.relationships(&content, decoder);
+            let result = js_sys::Object::new();
+            set_js_prop(
+                &result,
+                "relating",
+                &js_sys::Uint32Array::from(relationships.relating(express_id, kind)),
+            );
+            set_js_prop(
+                &result,
+                "related",
+                &js_sys::Uint32Array::from(relationships.related(express_id, kind)),
+            );
+            result.into()
+        }))
+    }
+}
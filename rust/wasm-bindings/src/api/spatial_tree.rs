@@ -0,0 +1,61 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! WASM API: getSpatialTree — the model's spatial structure hierarchy.
+
+use super::{set_js_prop, IfcAPI};
+use ifc_lite_core::SpatialNode;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl IfcAPI {
+    /// Get the Project → Site → Building → Storey → Element hierarchy.
+    ///
+    /// Returns `null` for models without an IfcProject, otherwise the root
+    /// node. Each node is
+    /// `{ expressId, type, name, globalId, elevation, children, elements }`:
+    /// `children` are the parts it is decomposed into (storeys of a
+    /// building, flights of a stair), `elements` the elements contained in
+    /// a spatial element. Storeys are ordered bottom to top, with
+    /// `elevation` in meters.
+    ///
+    /// ```javascript
+    /// const tree = api.getSpatialTree(ifcContent);
+    /// const walk = (node, depth) => {
+    ///   console.log(`${'  '.repeat(depth)}${node.type} ${node.name ?? ''}`);
+    ///   node.children.forEach((child) => walk(child, depth + 1));
+    /// };
+    /// if (tree) walk(tree, 0);
+    /// ```
+    #[wasm_bindgen(js_name = getSpatialTree)]
+    pub fn get_spatial_tree(&self, content: String) -> JsValue {
+        self.with_model_cache(&content, |cache, decoder| {
+            cache
+                .spatial_tree(&content, decoder)
+                .map(node_to_js)
+                .unwrap_or(JsValue::NULL)
+        })
+    }
+}
+
+fn node_to_js(node: &SpatialNode) -> JsValue {
+    let optional =
+        |value: &Option<String>| value.as_deref().map(JsValue::from).unwrap_or(JsValue::NULL);
+
+    let obj = js_sys::Object::new();
+    set_js_prop(&obj, "expressId", &node.express_id.into());
+    set_js_prop(&obj, "type", &node.ifc_type.name().into());
+    set_js_prop(&obj, "name", &optional(&node.name));
+    set_js_prop(&obj, "globalId", &optional(&node.global_id));
+    set_js_prop(
+        &obj,
+        "elevation",
+        &node.elevation.map(JsValue::from).unwrap_or(JsValue::NULL),
+    );
+    let children: js_sys::Array = node.children.iter().map(node_to_js).collect();
+    set_js_prop(&obj, "children", &children);
+    let elements: js_sys::Array = node.elements.iter().map(node_to_js).collect();
+    set_js_prop(&obj, "elements", &elements);
+    obj.into()
+}
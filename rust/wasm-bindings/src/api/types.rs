@@ -0,0 +1,57 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! WASM API: getExpressIdsOfType / getTypeCensus — entities by type.
+
+use super::{set_js_prop, IfcAPI};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl IfcAPI {
+    /// Get the express IDs of all entities of a type, sorted ascending.
+    ///
+    /// `ifcType` is case-insensitive (`IfcDoor` or `IFCDOOR`). With
+    /// `includeSubtypes`, entities of subtypes match too, e.g. `IfcWall`
+    /// also returns `IfcWallStandardCase` entities.
+    ///
+    /// ```javascript
+    /// const doors = api.getExpressIdsOfType(ifcContent, 'IfcDoor', true);
+    /// console.log(`${doors.length} doors`);
+    /// ```
+    #[wasm_bindgen(js_name = getExpressIdsOfType)]
+    pub fn get_express_ids_of_type(
+        &self,
+        content: String,
+        ifc_type: String,
+        include_subtypes: bool,
+    ) -> js_sys::Uint32Array {
+        let ids = self.with_model_cache(&content, |cache, _| {
+            cache
+                .types(&content)
+                .ids_of_type(&ifc_type, include_subtypes)
+        });
+        js_sys::Uint32Array::from(&ids[..])
+    }
+
+    /// Count entities per type.
+    ///
+    /// Returns an object mapping type names (`IfcWall`, ...) to counts, with
+    /// the most common types first.
+    ///
+    /// ```javascript
+    /// for (const [type, count] of Object.entries(api.getTypeCensus(ifcContent))) {
+    ///   console.log(`${type}: ${count}`);
+    /// }
+    /// ```
+    #[wasm_bindgen(js_name = getTypeCensus)]
+    pub fn get_type_census(&self, content: String) -> JsValue {
+        let census = js_sys::Object::new();
+        self.with_model_cache(&content, |cache, _| {
+            for (type_name, count) in cache.types(&content).census() {
+                set_js_prop(&census, type_name, &(count as f64).into());
+            }
+        });
+        census.into()
+    }
+}
@@ -0,0 +1,315 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Several models in one scene
+//!
+//! Every model added to a [`FederatedModelSet`] gets a stable model ID and a
+//! block of *federated IDs* (`idOffset + expressId`), so pick buffers and
+//! selection sets can mix elements of all models without collisions. Model
+//! IDs and ID blocks are never reused after a model is removed.
+//!
+//! Bounds are kept in world coordinates (WebGL Y-up, metres) and reported
+//! relative to one shared origin, fixed by the first model with geometry: its
+//! centre if it has large (>10 km) coordinates, the world origin otherwise.
+
+use wasm_bindgen::prelude::*;
+
+/// Coordinates beyond this distance (metres) need a shared origin
+const LARGE_COORDINATE: f64 = 10_000.0;
+
+/// Axis-aligned box, Y-up world metres
+type Aabb = [[f64; 3]; 2];
+
+struct FederatedModel {
+    model_id: u32,
+    name: String,
+    id_offset: u32,
+    /// Largest express ID + 1
+    id_range: u32,
+    bounds: Option<Aabb>,
+    /// RTC offset the model's meshes were parsed with (IFC Z-up)
+    mesh_rtc: [f64; 3],
+}
+
+/// Registry of the models shown together in a viewer
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct FederatedModelSet {
+    models: Vec<FederatedModel>,
+    next_model_id: u32,
+    next_id_offset: u32,
+    /// Shared origin, Y-up world metres
+    origin: Option<[f64; 3]>,
+}
+
+#[wasm_bindgen]
+impl FederatedModelSet {
+    /// Create an empty set
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a model and return its model ID.
+    ///
+    /// Scans `content` for its express ID range and element bounds; the
+    /// content itself is not kept. Throws when the federated ID space
+    /// (`u32`) is exhausted.
+    ///
+    /// ```javascript
+    /// const models = new FederatedModelSet();
+    /// const arch = models.addModel(archContent, 'ARC');
+    /// const struct = models.addModel(structContent, 'STR');
+    /// ```
+    #[wasm_bindgen(js_name = addModel)]
+    pub fn add_model(&mut self, content: String, name: String) -> Result<u32, JsValue> {
+        let mut max_express_id = 0;
+        let mut scanner = ifc_lite_core::EntityScanner::new(&content);
+        while let Some((id, ..)) = scanner.next_entity() {
+            max_express_id = max_express_id.max(id);
+        }
+        let bounds = element_bounds(&ifc_lite_geometry::extract_bounds(&content));
+        self.register(name, max_express_id, bounds)
+            .map_err(|message| js_sys::Error::new(&message).into())
+    }
+
+    /// Remove a model. Its model ID and federated IDs are not reused.
+    #[wasm_bindgen(js_name = removeModel)]
+    pub fn remove_model(&mut self, model_id: u32) -> bool {
+        let count = self.models.len();
+        self.models.retain(|model| model.model_id != model_id);
+        self.models.len() != count
+    }
+
+    /// IDs of the models in the set, in the order they were added
+    #[wasm_bindgen(getter, js_name = modelIds)]
+    pub fn model_ids(&self) -> js_sys::Uint32Array {
+        let ids: Vec<u32> = self.models.iter().map(|model| model.model_id).collect();
+        js_sys::Uint32Array::from(&ids[..])
+    }
+
+    /// Name a model was added with
+    #[wasm_bindgen(js_name = modelName)]
+    pub fn model_name(&self, model_id: u32) -> Option<String> {
+        self.model(model_id).map(|model| model.name.clone())
+    }
+
+    /// First federated ID of a model (federated ID = offset + express ID)
+    #[wasm_bindgen(js_name = idOffset)]
+    pub fn id_offset(&self, model_id: u32) -> Option<u32> {
+        self.model(model_id).map(|model| model.id_offset)
+    }
+
+    /// Federated ID of an element, e.g. to write into a pick buffer
+    #[wasm_bindgen(js_name = toFederatedId)]
+    pub fn to_federated_id(&self, model_id: u32, express_id: u32) -> Option<u32> {
+        self.model(model_id)
+            .filter(|model| express_id < model.id_range)
+            .map(|model| model.id_offset + express_id)
+    }
+
+    /// Resolve a federated ID (e.g. read back from a pick buffer) to
+    /// `{ modelId, expressId }`, or `null` if no model in the set owns it
+    #[wasm_bindgen(js_name = resolveFederatedId)]
+    pub fn resolve_federated_id(&self, federated_id: u32) -> JsValue {
+        let Some((model_id, express_id)) = self.resolve(federated_id) else {
+            return JsValue::NULL;
+        };
+        let obj = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&obj, &"modelId".into(), &model_id.into());
+        let _ = js_sys::Reflect::set(&obj, &"expressId".into(), &express_id.into());
+        obj.into()
+    }
+
+    /// Record the RTC offset a model's meshes were parsed with, as reported by
+    /// `onRtcOffset` / `onComplete` (`{ x, y, z }`, IFC coordinates)
+    #[wasm_bindgen(js_name = setMeshRtcOffset)]
+    pub fn set_mesh_rtc_offset(&mut self, model_id: u32, x: f64, y: f64, z: f64) -> bool {
+        let Some(model) = self.models.iter_mut().find(|m| m.model_id == model_id) else {
+            return false;
+        };
+        model.mesh_rtc = [x, y, z];
+        true
+    }
+
+    /// Translation `[x, y, z]` (Y-up) to apply to a model's meshes so all
+    /// models line up around the shared origin
+    #[wasm_bindgen(js_name = modelTranslation)]
+    pub fn model_translation(&self, model_id: u32) -> Option<Vec<f64>> {
+        let model = self.model(model_id)?;
+        let origin = self.origin.unwrap_or_default();
+        let rtc = to_y_up(model.mesh_rtc);
+        Some((0..3).map(|axis| rtc[axis] - origin[axis]).collect())
+    }
+
+    /// Shared origin `[x, y, z]` in Y-up world metres, for converting
+    /// scene positions back to world coordinates
+    #[wasm_bindgen(getter)]
+    pub fn origin(&self) -> Vec<f64> {
+        self.origin.unwrap_or_default().to_vec()
+    }
+
+    /// Bounds of all models, `{ min: [x, y, z], max: [x, y, z] }` relative to
+    /// the shared origin, or `null` if no model has geometry
+    #[wasm_bindgen]
+    pub fn bounds(&self) -> JsValue {
+        let union = self
+            .models
+            .iter()
+            .filter_map(|model| model.bounds)
+            .reduce(|a, b| union(&a, &b));
+        self.bounds_to_js(union)
+    }
+
+    /// Bounds of one model, as `bounds()`
+    #[wasm_bindgen(js_name = modelBounds)]
+    pub fn model_bounds(&self, model_id: u32) -> JsValue {
+        self.bounds_to_js(self.model(model_id).and_then(|model| model.bounds))
+    }
+}
+
+impl FederatedModelSet {
+    fn model(&self, model_id: u32) -> Option<&FederatedModel> {
+        self.models.iter().find(|model| model.model_id == model_id)
+    }
+
+    /// Assign a model ID and federated ID block
+    fn register(
+        &mut self,
+        name: String,
+        max_express_id: u32,
+        bounds: Option<Aabb>,
+    ) -> Result<u32, String> {
+        let id_range = max_express_id
+            .checked_add(1)
+            .filter(|range| self.next_id_offset.checked_add(*range).is_some())
+            .ok_or_else(|| format!("No federated IDs left for model '{name}'"))?;
+
+        if self.origin.is_none() {
+            self.origin = bounds.map(|bounds| {
+                let large = bounds.iter().flatten().any(|c| c.abs() > LARGE_COORDINATE);
+                if large {
+                    [0, 1, 2].map(|axis| (bounds[0][axis] + bounds[1][axis]) / 2.0)
+                } else {
+                    [0.0; 3]
+                }
+            });
+        }
+
+        let model_id = self.next_model_id;
+        self.next_model_id += 1;
+        self.models.push(FederatedModel {
+            model_id,
+            name,
+            id_offset: self.next_id_offset,
+            id_range,
+            bounds,
+            mesh_rtc: [0.0; 3],
+        });
+        self.next_id_offset += id_range;
+        Ok(model_id)
+    }
+
+    /// Model ID and express ID of a federated ID
+    fn resolve(&self, federated_id: u32) -> Option<(u32, u32)> {
+        // Models are ordered by offset: they are only appended
+        let index = self
+            .models
+            .partition_point(|model| model.id_offset <= federated_id)
+            .checked_sub(1)?;
+        let model = &self.models[index];
+        let express_id = federated_id - model.id_offset;
+        (express_id < model.id_range).then_some((model.model_id, express_id))
+    }
+
+    fn bounds_to_js(&self, bounds: Option<Aabb>) -> JsValue {
+        let Some([min, max]) = bounds else {
+            return JsValue::NULL;
+        };
+        let origin = self.origin.unwrap_or_default();
+        let relative = |corner: [f64; 3]| -> js_sys::Array {
+            (0..3)
+                .map(|axis| JsValue::from(corner[axis] - origin[axis]))
+                .collect()
+        };
+        let obj = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&obj, &"min".into(), &relative(min));
+        let _ = js_sys::Reflect::set(&obj, &"max".into(), &relative(max));
+        obj.into()
+    }
+}
+
+/// Axis-aligned box around element boxes
+fn element_bounds(elements: &[ifc_lite_geometry::ElementBounds]) -> Option<Aabb> {
+    elements
+        .iter()
+        .map(|element| {
+            let mut extent = [0.0f64; 3];
+            for (axis, half) in element.axes.chunks_exact(3).zip(element.half_extents) {
+                for (e, a) in extent.iter_mut().zip(axis) {
+                    *e += (a * half).abs() as f64;
+                }
+            }
+            let center = element.center.map(f64::from);
+            [
+                [0, 1, 2].map(|i| center[i] - extent[i]),
+                [0, 1, 2].map(|i| center[i] + extent[i]),
+            ]
+        })
+        .reduce(|a, b| union(&a, &b))
+}
+
+fn union(a: &Aabb, b: &Aabb) -> Aabb {
+    [
+        [0, 1, 2].map(|i| a[0][i].min(b[0][i])),
+        [0, 1, 2].map(|i| a[1][i].max(b[1][i])),
+    ]
+}
+
+/// IFC Z-up to WebGL Y-up, as the mesh output
+fn to_y_up([x, y, z]: [f64; 3]) -> [f64; 3] {
+    [x, z, -y]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_federated_ids() {
+        let mut set = FederatedModelSet::new();
+        let arch = set.register("ARC".into(), 99, None).unwrap();
+        let structure = set.register("STR".into(), 49, None).unwrap();
+
+        assert_eq!(set.to_federated_id(arch, 42), Some(42));
+        assert_eq!(set.to_federated_id(structure, 42), Some(142));
+        assert_eq!(set.to_federated_id(structure, 50), None);
+        assert_eq!(set.resolve(142), Some((structure, 42)));
+        assert_eq!(set.resolve(150), None);
+
+        // Removed models keep their IDs and ID block
+        assert!(set.remove_model(arch));
+        assert_eq!(set.resolve(42), None);
+        let mep = set.register("MEP".into(), 9, None).unwrap();
+        assert_eq!(mep, 2);
+        assert_eq!(set.resolve(150), Some((mep, 0)));
+
+        set.next_id_offset = u32::MAX - 5;
+        assert!(set.register("Huge".into(), 10, None).is_err());
+    }
+
+    #[test]
+    fn test_shared_origin() {
+        let mut set = FederatedModelSet::new();
+        let far = [
+            [2_600_000.0, 0.0, -1_200_010.0],
+            [2_600_100.0, 20.0, -1_200_000.0],
+        ];
+        let model = set.register("Site".into(), 10, Some(far)).unwrap();
+        assert_eq!(set.origin, Some([2_600_050.0, 10.0, -1_200_005.0]));
+
+        set.set_mesh_rtc_offset(model, 2_600_000.0, 1_200_000.0, 0.0);
+        assert_eq!(set.model_translation(model), Some(vec![-50.0, -10.0, 5.0]));
+    }
+}
@@ -33,6 +33,7 @@
 //! gpuGeom.free();
 //! ```
 
+use crate::memory::{LiveCount, LIVE_GPU_GEOMETRIES};
 use wasm_bindgen::prelude::*;
 
 /// Metadata for a single mesh within the GPU geometry buffer
@@ -121,6 +122,8 @@ pub struct GpuGeometry {
     rtc_offset_x: f64,
     rtc_offset_y: f64,
     rtc_offset_z: f64,
+
+    _live: LiveCount,
 }
 
 #[wasm_bindgen]
@@ -136,6 +139,7 @@ impl GpuGeometry {
             rtc_offset_x: 0.0,
             rtc_offset_y: 0.0,
             rtc_offset_z: 0.0,
+            _live: LiveCount::new(&LIVE_GPU_GEOMETRIES),
         }
     }
 
@@ -257,6 +261,7 @@ impl GpuGeometry {
             rtc_offset_x: 0.0,
             rtc_offset_y: 0.0,
             rtc_offset_z: 0.0,
+            _live: LiveCount::new(&LIVE_GPU_GEOMETRIES),
         }
     }
 
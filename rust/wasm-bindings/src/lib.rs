@@ -66,19 +66,25 @@ use wasm_bindgen::prelude::*;
 pub use console_error_panic_hook::set_once as set_panic_hook;
 
 mod api;
+mod federation;
 mod gpu_geometry;
+mod memory;
+mod model;
 mod utils;
 mod zero_copy;
 
 pub use api::IfcAPI;
+pub use federation::FederatedModelSet;
 pub use gpu_geometry::{
     GpuGeometry, GpuInstancedGeometry, GpuInstancedGeometryCollection, GpuInstancedGeometryRef,
     GpuMeshMetadata,
 };
+pub use model::IfcModel;
 pub use utils::set_panic_hook as init_panic_hook;
 pub use zero_copy::{
     get_memory, InstanceData, InstancedGeometry, InstancedMeshCollection, MeshCollection,
-    MeshDataJs, SymbolicCircle, SymbolicPolyline, SymbolicRepresentationCollection, ZeroCopyMesh,
+    MeshDataJs, QuantizedMeshCollection, QuantizedMeshDataJs, SymbolicCircle, SymbolicPolyline,
+    SymbolicRepresentationCollection, ZeroCopyMesh,
 };
 
 /// Initialize the WASM module.
@@ -106,3 +112,28 @@ pub fn init() {
 pub fn version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
+
+/// Compute the canonical cache key for an IFC file.
+///
+/// Produces the same key as the server and desktop app for the same file and
+/// options, so results cached by one can be looked up from another.
+///
+/// # Example
+///
+/// ```javascript
+/// const key = computeCacheKey(bytes, { opening_filter: 'default' });
+/// ```
+#[wasm_bindgen(js_name = computeCacheKey)]
+pub fn compute_cache_key(data: &[u8], options: JsValue) -> Result<String, JsValue> {
+    let options: std::collections::BTreeMap<String, String> =
+        if options.is_null() || options.is_undefined() {
+            Default::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)?
+        };
+    let pairs: Vec<(&str, &str)> = options
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    Ok(ifc_lite_core::cache_key(data, &pairs))
+}
@@ -0,0 +1,57 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Live buffer accounting for `memoryStats`
+//!
+//! Mesh buffers handed to JavaScript stay in WASM memory until their
+//! wrapper is freed (`free()`, or the FinalizationRegistry eventually).
+//! Each wrapper type embeds a [`LiveCount`], so a count that keeps growing
+//! across model loads points at wrappers the viewer never frees.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Live `MeshDataJs` objects
+pub(crate) static LIVE_MESHES: AtomicUsize = AtomicUsize::new(0);
+/// Live `MeshCollection` objects
+pub(crate) static LIVE_MESH_COLLECTIONS: AtomicUsize = AtomicUsize::new(0);
+/// Live `GpuGeometry` objects
+pub(crate) static LIVE_GPU_GEOMETRIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts its owner in a live counter until dropped
+pub(crate) struct LiveCount(&'static AtomicUsize);
+
+impl LiveCount {
+    pub(crate) fn new(counter: &'static AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Drop for LiveCount {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Current value of a live counter
+pub(crate) fn live(counter: &AtomicUsize) -> usize {
+    counter.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_count() {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let first = LiveCount::new(&COUNTER);
+        let second = LiveCount::new(&COUNTER);
+        assert_eq!(live(&COUNTER), 2);
+        drop(first);
+        assert_eq!(live(&COUNTER), 1);
+        drop(second);
+        assert_eq!(live(&COUNTER), 0);
+    }
+}
@@ -0,0 +1,198 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Editable model handle
+//!
+//! Unlike [`IfcAPI`](crate::IfcAPI) methods, which take the file content on
+//! every call, an [`IfcModel`] keeps the content together with the edits made
+//! to it, and writes both back out as STEP for round-trip workflows.
+//!
+//! ```javascript
+//! const model = new IfcModel(content);
+//! model.setAttribute(wallId, 'Name', 'Wall 1');
+//! model.addPropertySet(wallId, 'ACME_Fire', { FireRating: 'EI30', Tested: true });
+//! model.deleteElement(furnitureId);
+//! model.dirtyIds; // Uint32Array of changed, added and deleted entities
+//! const edited = model.exportIfc();
+//! ```
+
+use ifc_lite_core::{encode_string, is_defined_type_name, AttributeValue, ModelEditor};
+use wasm_bindgen::prelude::*;
+
+/// Value types written as INTEGER
+const INTEGER_TYPES: [&str; 3] = ["IFCINTEGER", "IFCCOUNTMEASURE", "IFCTIMESTAMP"];
+
+/// A loaded model and its pending edits
+#[wasm_bindgen]
+pub struct IfcModel {
+    content: String,
+    editor: ModelEditor,
+}
+
+#[wasm_bindgen]
+impl IfcModel {
+    /// Load a model from IFC (STEP) content
+    ///
+    /// ```javascript
+    /// const model = new IfcModel(content);
+    /// // ... edit ...
+    /// const blob = new Blob([model.exportIfcBytes()], { type: 'application/x-step' });
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(content: String) -> Self {
+        let editor = ModelEditor::new(&content);
+        Self { content, editor }
+    }
+
+    /// Whether the model has edits that are not in the loaded content
+    #[wasm_bindgen(getter, js_name = isDirty)]
+    pub fn is_dirty(&self) -> bool {
+        !self.editor.edits().is_empty()
+    }
+
+    /// Express IDs of changed, added and deleted entities, ascending
+    #[wasm_bindgen(getter, js_name = dirtyIds)]
+    pub fn dirty_ids(&self) -> js_sys::Uint32Array {
+        let mut ids: Vec<u32> = self.editor.edits().ids().collect();
+        ids.sort_unstable();
+        js_sys::Uint32Array::from(&ids[..])
+    }
+
+    /// Set an attribute by name (`Name`, `Description`, `ObjectType`, `Tag`,
+    /// `LongName`, ...) or 0-based index.
+    ///
+    /// `value` is a string, number, boolean or `null`; typed attributes keep
+    /// their IFC type. Throws for unknown entities and attributes.
+    #[wasm_bindgen(js_name = setAttribute)]
+    pub fn set_attribute(
+        &mut self,
+        express_id: u32,
+        name: &str,
+        value: JsValue,
+    ) -> Result<(), JsValue> {
+        let value = attribute_value(&value)?;
+        self.editor
+            .set_attribute(&self.content, express_id, name, value)
+            .map_err(to_js_error)
+    }
+
+    /// Attach a new property set to an object and return its express ID.
+    ///
+    /// `properties` maps property names to values: strings become `IfcLabel`,
+    /// booleans `IfcBoolean`, integers `IfcInteger`, other numbers `IfcReal`.
+    /// Use `{ type: 'IfcLengthMeasure', value: 2.5 }` for another type; throws
+    /// if `type` is not an IFC value type name.
+    #[wasm_bindgen(js_name = addPropertySet)]
+    pub fn add_property_set(
+        &mut self,
+        express_id: u32,
+        name: &str,
+        properties: JsValue,
+    ) -> Result<u32, JsValue> {
+        if !properties.is_object() {
+            return Err(js_sys::Error::new("properties must be an object").into());
+        }
+        let mut values = Vec::new();
+        for entry in js_sys::Object::entries(&properties.into()).iter() {
+            let entry = js_sys::Array::from(&entry);
+            let property = entry.get(0).as_string().unwrap_or_default();
+            values.push((property, nominal_value(&entry.get(1))?));
+        }
+        if values.is_empty() {
+            return Err(js_sys::Error::new("A property set needs at least one property").into());
+        }
+        self.editor
+            .add_property_set(&self.content, express_id, name, values)
+            .map_err(to_js_error)
+    }
+
+    /// Delete an element (any `IfcProduct`). Relationships referencing it are
+    /// updated, and deleted when nothing is left on one side. Throws for
+    /// unknown entities and entities that are not products.
+    #[wasm_bindgen(js_name = deleteElement)]
+    pub fn delete_element(&mut self, express_id: u32) -> Result<(), JsValue> {
+        self.editor
+            .delete_element(&self.content, express_id)
+            .map_err(to_js_error)
+    }
+
+    /// The model as STEP text, with all edits applied. Unedited entities are
+    /// written exactly as loaded.
+    #[wasm_bindgen(js_name = exportIfc)]
+    pub fn export_ifc(&self) -> String {
+        self.editor.write(&self.content)
+    }
+
+    /// The model as UTF-8 STEP bytes, ready for a `Blob` or download
+    #[wasm_bindgen(js_name = exportIfcBytes)]
+    pub fn export_ifc_bytes(&self) -> Vec<u8> {
+        self.export_ifc().into_bytes()
+    }
+}
+
+fn to_js_error(error: ifc_lite_core::Error) -> JsValue {
+    js_sys::Error::new(&error.to_string()).into()
+}
+
+/// Attribute value of a JS string, number, boolean or `null`
+fn attribute_value(value: &JsValue) -> Result<AttributeValue, JsValue> {
+    if value.is_null() || value.is_undefined() {
+        return Ok(AttributeValue::Null);
+    }
+    if let Some(text) = value.as_string() {
+        return Ok(AttributeValue::String(encode_string(&text)));
+    }
+    if let Some(flag) = value.as_bool() {
+        return Ok(AttributeValue::Enum(if flag { "T" } else { "F" }.into()));
+    }
+    match value.as_f64() {
+        Some(number) if number.fract() == 0.0 && number.abs() < i64::MAX as f64 => {
+            Ok(AttributeValue::Integer(number as i64))
+        }
+        Some(number) if number.is_finite() => Ok(AttributeValue::Float(number)),
+        _ => Err(js_sys::Error::new("Unsupported attribute value").into()),
+    }
+}
+
+/// Typed nominal value of a property: a plain value or `{ type, value }`
+fn nominal_value(value: &JsValue) -> Result<AttributeValue, JsValue> {
+    let explicit_type = js_sys::Reflect::get(value, &"type".into())
+        .ok()
+        .and_then(|t| t.as_string())
+        .filter(|_| value.is_object());
+    let (type_name, inner) = match explicit_type {
+        Some(type_name) => {
+            let type_name = type_name.to_uppercase();
+            if !is_defined_type_name(&type_name) {
+                let message = format!("'{type_name}' is not an IFC value type");
+                return Err(js_sys::Error::new(&message).into());
+            }
+            let inner = match attribute_value(&js_sys::Reflect::get(value, &"value".into())?)? {
+                // Measures are REALs, whole numbers or not
+                AttributeValue::Integer(i) if !INTEGER_TYPES.contains(&type_name.as_str()) => {
+                    AttributeValue::Float(i as f64)
+                }
+                inner => inner,
+            };
+            (type_name, inner)
+        }
+        None => {
+            let inner = attribute_value(value)?;
+            let type_name = match inner {
+                AttributeValue::String(_) => "IFCLABEL",
+                AttributeValue::Enum(_) => "IFCBOOLEAN",
+                AttributeValue::Integer(_) => "IFCINTEGER",
+                _ => "IFCREAL",
+            };
+            (type_name.to_string(), inner)
+        }
+    };
+    if inner.is_null() {
+        return Ok(AttributeValue::Null);
+    }
+    Ok(AttributeValue::List(vec![
+        AttributeValue::String(type_name),
+        inner,
+    ]))
+}
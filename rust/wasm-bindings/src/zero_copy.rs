@@ -6,7 +6,8 @@
 //!
 //! Enables direct access to WASM memory from JavaScript without copying.
 
-use ifc_lite_geometry::Mesh;
+use crate::memory::{LiveCount, LIVE_MESHES, LIVE_MESH_COLLECTIONS};
+use ifc_lite_geometry::{Mesh, QuantizedMesh};
 use wasm_bindgen::prelude::*;
 
 /// Individual mesh data with express ID and color (matches MeshData interface)
@@ -18,6 +19,7 @@ pub struct MeshDataJs {
     normals: Vec<f32>,
     indices: Vec<u32>,
     color: [f32; 4], // RGBA
+    _live: LiveCount,
 }
 
 #[wasm_bindgen]
@@ -109,11 +111,98 @@ impl MeshDataJs {
             normals: mesh.normals,
             indices: mesh.indices,
             color,
+            _live: LiveCount::new(&LIVE_MESHES),
         }
     }
 }
 
+/// Pack meshes into standalone JS buffers that can be transferred with
+/// `postMessage`, instead of `MeshDataJs` wrappers that point into WASM memory.
+///
+/// Returns `{ expressIds, ifcTypes, colors, vertexCounts, indexCounts,
+/// positions, normals, indices, transfer }`. Mesh `i` owns `vertexCounts[i]`
+/// vertices (3 floats each in `positions`/`normals`) and `indexCounts[i]`
+/// indices, laid out after those of the previous meshes; indices are relative
+/// to the mesh's first vertex. `colors` holds 4 floats per mesh. `transfer`
+/// lists the underlying ArrayBuffers for the `postMessage` transfer list.
+pub(crate) fn pack_transferable(meshes: Vec<MeshDataJs>) -> JsValue {
+    let vertex_floats: usize = meshes.iter().map(|m| m.positions.len()).sum();
+    let index_count: usize = meshes.iter().map(|m| m.indices.len()).sum();
+
+    let positions = js_sys::Float32Array::new_with_length(vertex_floats as u32);
+    let normals = js_sys::Float32Array::new_with_length(vertex_floats as u32);
+    let indices = js_sys::Uint32Array::new_with_length(index_count as u32);
+    let mut express_ids = Vec::with_capacity(meshes.len());
+    let mut colors = Vec::with_capacity(meshes.len() * 4);
+    let mut vertex_counts = Vec::with_capacity(meshes.len());
+    let mut index_counts = Vec::with_capacity(meshes.len());
+    let ifc_types = js_sys::Array::new_with_length(meshes.len() as u32);
+
+    // One copy per buffer, straight from WASM memory into the JS-owned arrays
+    let (mut vertex_offset, mut index_offset) = (0u32, 0u32);
+    for (i, mesh) in meshes.iter().enumerate() {
+        let floats = mesh.positions.len() as u32;
+        positions
+            .subarray(vertex_offset, vertex_offset + floats)
+            .copy_from(&mesh.positions);
+        // Normals may be missing on degenerate meshes; leave them zeroed
+        if mesh.normals.len() == mesh.positions.len() {
+            normals
+                .subarray(vertex_offset, vertex_offset + floats)
+                .copy_from(&mesh.normals);
+        }
+        let count = mesh.indices.len() as u32;
+        indices
+            .subarray(index_offset, index_offset + count)
+            .copy_from(&mesh.indices);
+        vertex_offset += floats;
+        index_offset += count;
+
+        express_ids.push(mesh.express_id);
+        colors.extend_from_slice(&mesh.color);
+        vertex_counts.push(floats / 3);
+        index_counts.push(count);
+        ifc_types.set(i as u32, JsValue::from_str(&mesh.ifc_type));
+    }
+    drop(meshes);
+
+    let express_ids = js_sys::Uint32Array::from(&express_ids[..]);
+    let colors = js_sys::Float32Array::from(&colors[..]);
+    let vertex_counts = js_sys::Uint32Array::from(&vertex_counts[..]);
+    let index_counts = js_sys::Uint32Array::from(&index_counts[..]);
+    let transfer: js_sys::Array = [
+        positions.buffer(),
+        normals.buffer(),
+        indices.buffer(),
+        express_ids.buffer(),
+        colors.buffer(),
+        vertex_counts.buffer(),
+        index_counts.buffer(),
+    ]
+    .iter()
+    .collect();
+
+    let batch = js_sys::Object::new();
+    let entries: [(&str, &JsValue); 9] = [
+        ("expressIds", &express_ids),
+        ("ifcTypes", &ifc_types),
+        ("colors", &colors),
+        ("vertexCounts", &vertex_counts),
+        ("indexCounts", &index_counts),
+        ("positions", &positions),
+        ("normals", &normals),
+        ("indices", &indices),
+        ("transfer", &transfer),
+    ];
+    for (key, value) in entries {
+        let _ = js_sys::Reflect::set(&batch, &key.into(), value);
+    }
+    batch.into()
+}
+
 /// Collection of mesh data for returning multiple meshes
+///
+/// Holds every mesh buffer in WASM memory until `free()` is called.
 #[wasm_bindgen]
 pub struct MeshCollection {
     meshes: Vec<MeshDataJs>,
@@ -125,6 +214,7 @@ pub struct MeshCollection {
     /// Building rotation angle in radians (from IfcSite's top-level placement)
     /// This is the rotation of the building's principal axes relative to world X/Y/Z
     building_rotation: Option<f64>,
+    _live: LiveCount,
 }
 
 #[wasm_bindgen]
@@ -145,6 +235,7 @@ impl MeshCollection {
             normals: m.normals.clone(),
             indices: m.indices.clone(),
             color: m.color,
+            _live: LiveCount::new(&LIVE_MESHES),
         })
     }
 
@@ -195,6 +286,30 @@ impl MeshCollection {
         self.building_rotation
     }
 
+    /// Convert to the quantized vertex format, halving vertex memory.
+    ///
+    /// Consumes the collection so its `f32` buffers are freed as each mesh
+    /// is converted.
+    ///
+    /// ```javascript
+    /// const meshes = api.parseMeshes(ifcContent).quantize();
+    /// const m = meshes.get(0);
+    /// geometry.setAttribute('position', new THREE.BufferAttribute(m.positions, 4, true));
+    /// geometry.setAttribute('normal', new THREE.BufferAttribute(m.normals, 4, true));
+    /// object.position.fromArray(m.positionOffset);
+    /// object.scale.setScalar(m.positionScale);
+    /// ```
+    #[wasm_bindgen]
+    pub fn quantize(self) -> QuantizedMeshCollection {
+        QuantizedMeshCollection {
+            meshes: self.meshes.into_iter().map(Into::into).collect(),
+            rtc_offset_x: self.rtc_offset_x,
+            rtc_offset_y: self.rtc_offset_y,
+            rtc_offset_z: self.rtc_offset_z,
+            building_rotation: self.building_rotation,
+        }
+    }
+
     /// Convert local coordinates to world coordinates
     /// Use this to convert mesh positions back to original IFC coordinates
     #[wasm_bindgen(js_name = localToWorld)]
@@ -216,6 +331,7 @@ impl MeshCollection {
             rtc_offset_y: 0.0,
             rtc_offset_z: 0.0,
             building_rotation: None,
+            _live: LiveCount::new(&LIVE_MESH_COLLECTIONS),
         }
     }
 
@@ -227,6 +343,7 @@ impl MeshCollection {
             rtc_offset_y: 0.0,
             rtc_offset_z: 0.0,
             building_rotation: None,
+            _live: LiveCount::new(&LIVE_MESH_COLLECTIONS),
         }
     }
 
@@ -244,6 +361,7 @@ impl MeshCollection {
             rtc_offset_y: 0.0,
             rtc_offset_z: 0.0,
             building_rotation: None,
+            _live: LiveCount::new(&LIVE_MESH_COLLECTIONS),
         }
     }
 
@@ -298,12 +416,14 @@ impl Clone for MeshCollection {
                     normals: m.normals.clone(),
                     indices: m.indices.clone(),
                     color: m.color,
+                    _live: LiveCount::new(&LIVE_MESHES),
                 })
                 .collect(),
             rtc_offset_x: self.rtc_offset_x,
             rtc_offset_y: self.rtc_offset_y,
             rtc_offset_z: self.rtc_offset_z,
             building_rotation: self.building_rotation,
+            _live: LiveCount::new(&LIVE_MESH_COLLECTIONS),
         }
     }
 }
@@ -314,6 +434,166 @@ impl Default for MeshCollection {
     }
 }
 
+/// Mesh in the quantized vertex format: 12 bytes per vertex instead of 24.
+///
+/// Positions are normalized `u16 × 4` and normals normalized `i8 × 4` (w = 0),
+/// ready for `unorm16x4` / `snorm8x4` vertex attributes. Dequantize with
+/// `position = positionOffset + q / 65535 * positionScale`, e.g. as the mesh
+/// transform in three.js.
+#[wasm_bindgen]
+pub struct QuantizedMeshDataJs {
+    express_id: u32,
+    ifc_type: String,
+    mesh: QuantizedMesh,
+    color: [f32; 4],
+}
+
+#[wasm_bindgen]
+impl QuantizedMeshDataJs {
+    /// Get express ID
+    #[wasm_bindgen(getter, js_name = expressId)]
+    pub fn express_id(&self) -> u32 {
+        self.express_id
+    }
+
+    /// Get IFC type name (e.g., "IfcWall", "IfcSpace")
+    #[wasm_bindgen(getter, js_name = ifcType)]
+    pub fn ifc_type(&self) -> String {
+        self.ifc_type.clone()
+    }
+
+    /// Get quantized positions as Uint16Array `[x, y, z, 0, …]` (copy to JS)
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> js_sys::Uint16Array {
+        js_sys::Uint16Array::from(&self.mesh.positions[..])
+    }
+
+    /// Get quantized normals as Int8Array `[x, y, z, 0, …]` (copy to JS)
+    #[wasm_bindgen(getter)]
+    pub fn normals(&self) -> js_sys::Int8Array {
+        js_sys::Int8Array::from(&self.mesh.normals[..])
+    }
+
+    /// Get indices as Uint32Array (copy to JS)
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.mesh.indices[..])
+    }
+
+    /// Get color as [r, g, b, a] array
+    #[wasm_bindgen(getter)]
+    pub fn color(&self) -> Vec<f32> {
+        self.color.to_vec()
+    }
+
+    /// Get dequantization offset [x, y, z] (minimum corner, Y-up)
+    #[wasm_bindgen(getter, js_name = positionOffset)]
+    pub fn position_offset(&self) -> Vec<f32> {
+        self.mesh.quantization.offset.to_vec()
+    }
+
+    /// Get dequantization scale (shared by all axes)
+    #[wasm_bindgen(getter, js_name = positionScale)]
+    pub fn position_scale(&self) -> f32 {
+        self.mesh.quantization.scale
+    }
+
+    /// Get vertex count
+    #[wasm_bindgen(getter, js_name = vertexCount)]
+    pub fn vertex_count(&self) -> usize {
+        self.mesh.vertex_count()
+    }
+
+    /// Get triangle count
+    #[wasm_bindgen(getter, js_name = triangleCount)]
+    pub fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
+}
+
+impl From<MeshDataJs> for QuantizedMeshDataJs {
+    fn from(mesh: MeshDataJs) -> Self {
+        Self {
+            mesh: QuantizedMesh::from_buffers(&mesh.positions, &mesh.normals, mesh.indices),
+            express_id: mesh.express_id,
+            ifc_type: mesh.ifc_type,
+            color: mesh.color,
+        }
+    }
+}
+
+/// Collection of quantized meshes, see [`MeshCollection::quantize`]
+#[wasm_bindgen]
+pub struct QuantizedMeshCollection {
+    meshes: Vec<QuantizedMeshDataJs>,
+    rtc_offset_x: f64,
+    rtc_offset_y: f64,
+    rtc_offset_z: f64,
+    building_rotation: Option<f64>,
+}
+
+#[wasm_bindgen]
+impl QuantizedMeshCollection {
+    /// Get number of meshes
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.meshes.len()
+    }
+
+    /// Get mesh at index
+    #[wasm_bindgen]
+    pub fn get(&self, index: usize) -> Option<QuantizedMeshDataJs> {
+        self.meshes.get(index).map(|m| QuantizedMeshDataJs {
+            express_id: m.express_id,
+            ifc_type: m.ifc_type.clone(),
+            mesh: m.mesh.clone(),
+            color: m.color,
+        })
+    }
+
+    /// Get total vertex count across all meshes
+    #[wasm_bindgen(getter, js_name = totalVertices)]
+    pub fn total_vertices(&self) -> usize {
+        self.meshes.iter().map(|m| m.mesh.vertex_count()).sum()
+    }
+
+    /// Get total triangle count across all meshes
+    #[wasm_bindgen(getter, js_name = totalTriangles)]
+    pub fn total_triangles(&self) -> usize {
+        self.meshes.iter().map(|m| m.mesh.triangle_count()).sum()
+    }
+
+    /// Get total size of all vertex and index buffers in bytes
+    #[wasm_bindgen(getter, js_name = byteLength)]
+    pub fn byte_length(&self) -> usize {
+        self.meshes.iter().map(|m| m.mesh.byte_len()).sum()
+    }
+
+    /// Get RTC offset X (add to dequantized X for world X)
+    #[wasm_bindgen(getter, js_name = rtcOffsetX)]
+    pub fn rtc_offset_x(&self) -> f64 {
+        self.rtc_offset_x
+    }
+
+    /// Get RTC offset Y
+    #[wasm_bindgen(getter, js_name = rtcOffsetY)]
+    pub fn rtc_offset_y(&self) -> f64 {
+        self.rtc_offset_y
+    }
+
+    /// Get RTC offset Z
+    #[wasm_bindgen(getter, js_name = rtcOffsetZ)]
+    pub fn rtc_offset_z(&self) -> f64 {
+        self.rtc_offset_z
+    }
+
+    /// Get building rotation angle in radians (from IfcSite placement)
+    #[wasm_bindgen(getter, js_name = buildingRotation)]
+    pub fn building_rotation(&self) -> Option<f64> {
+        self.building_rotation
+    }
+}
+
 /// Zero-copy mesh that exposes pointers to WASM memory
 #[wasm_bindgen]
 pub struct ZeroCopyMesh {
@@ -895,4 +1175,65 @@ mod tests {
         assert!(!mesh.normals_ptr().is_null());
         assert!(!mesh.indices_ptr().is_null());
     }
+
+    fn triangle(x: f32) -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.positions = vec![x, 0.0, 0.0, x, 1.0, 0.0, x, 0.0, 2.0];
+        mesh.normals = vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        mesh.indices = vec![0, 1, 2];
+        mesh
+    }
+
+    #[test]
+    fn test_mesh_data_y_up() {
+        let mesh = MeshDataJs::new(1, "IfcWall".into(), triangle(3.0), [1.0; 4]);
+        // (x, y, z) becomes (x, z, -y), with the winding reversed
+        assert_eq!(
+            mesh.positions,
+            [3.0, 0.0, -0.0, 3.0, 0.0, -1.0, 3.0, 2.0, -0.0]
+        );
+        assert_eq!(
+            mesh.normals,
+            [1.0, 0.0, -0.0, 0.0, 0.0, -1.0, 0.0, 1.0, -0.0]
+        );
+        assert_eq!(mesh.indices, [0, 2, 1]);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn test_pack_transferable() {
+        let get = |batch: &JsValue, key: &str| js_sys::Reflect::get(batch, &key.into()).unwrap();
+        let u32s = |value: JsValue| js_sys::Uint32Array::from(value).to_vec();
+        let f32s = |value: JsValue| js_sys::Float32Array::from(value).to_vec();
+
+        let first = MeshDataJs::new(7, "IfcWall".into(), triangle(0.0), [0.5; 4]);
+        // No normals, e.g. a degenerate mesh
+        let mut second = triangle(1.0);
+        second.normals.clear();
+        let second = MeshDataJs::new(9, "IfcSlab".into(), second, [1.0; 4]);
+        let second_positions = second.positions.clone();
+
+        let batch = pack_transferable(vec![first, second]);
+        assert_eq!(u32s(get(&batch, "expressIds")), [7, 9]);
+        let ifc_types: Vec<_> = js_sys::Array::from(&get(&batch, "ifcTypes"))
+            .iter()
+            .filter_map(|t| t.as_string())
+            .collect();
+        assert_eq!(ifc_types, ["IfcWall", "IfcSlab"]);
+        assert_eq!(
+            f32s(get(&batch, "colors")),
+            [0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 1.0]
+        );
+        assert_eq!(u32s(get(&batch, "vertexCounts")), [3, 3]);
+        assert_eq!(u32s(get(&batch, "indexCounts")), [3, 3]);
+
+        // Indices stay relative to each mesh's first vertex
+        assert_eq!(u32s(get(&batch, "indices")), [0, 2, 1, 0, 2, 1]);
+        assert_eq!(f32s(get(&batch, "positions"))[9..], second_positions[..]);
+        let normals = f32s(get(&batch, "normals"));
+        assert_eq!(normals.len(), 18);
+        assert!(normals[9..].iter().all(|&n| n == 0.0));
+
+        assert_eq!(js_sys::Array::from(&get(&batch, "transfer")).length(), 7);
+    }
 }